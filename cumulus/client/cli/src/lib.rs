@@ -441,6 +441,10 @@ impl sc_cli::CliConfiguration for NormalizedRunCmd {
 		self.base.max_runtime_instances()
 	}
 
+	fn offchain_execution_limits(&self) -> sc_cli::Result<sc_service::config::ExecutionLimits> {
+		self.base.offchain_execution_limits()
+	}
+
 	fn runtime_cache_size(&self) -> sc_cli::Result<u8> {
 		self.base.runtime_cache_size()
 	}
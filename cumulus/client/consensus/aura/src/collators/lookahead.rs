@@ -45,8 +45,9 @@ use cumulus_primitives_core::{
 use cumulus_relay_chain_interface::RelayChainInterface;
 
 use polkadot_node_primitives::SubmitCollationParams;
-use polkadot_node_subsystem::messages::{
-	CollationGenerationMessage, RuntimeApiMessage, RuntimeApiRequest,
+use polkadot_node_subsystem::{
+	errors::RuntimeApiError,
+	messages::{CollationGenerationMessage, RuntimeApiMessage, RuntimeApiRequest},
 };
 use polkadot_overseer::Handle as OverseerHandle;
 use polkadot_primitives::{CollatorPair, Id as ParaId, OccupiedCoreAssumption};
@@ -182,13 +183,23 @@ where
 
 		while let Some(relay_parent_header) = import_notifications.next().await {
 			let relay_parent = relay_parent_header.hash();
+			let ancestry_lookback = max_ancestry_lookback(relay_parent, &params.relay_client).await;
 
-			if !is_para_scheduled(relay_parent, params.para_id, &mut params.overseer_handle).await {
+			// Collations built on this relay parent can be backed within the allowed ancestry.
+			let n_claims = claims_within_depth(
+				relay_parent,
+				params.para_id,
+				ancestry_lookback + 1,
+				&mut params.overseer_handle,
+			)
+			.await;
+
+			if n_claims == 0 {
 				tracing::trace!(
 					target: crate::LOG_TARGET,
 					?relay_parent,
 					?params.para_id,
-					"Para is not scheduled on any core, skipping import notification",
+					"Para has no claim on any core, skipping import notification",
 				);
 
 				continue
@@ -234,7 +245,7 @@ where
 			let parent_search_params = ParentSearchParams {
 				relay_parent,
 				para_id: params.para_id,
-				ancestry_lookback: max_ancestry_lookback(relay_parent, &params.relay_client).await,
+				ancestry_lookback,
 				max_depth: PARENT_SEARCH_DEPTH,
 				ignore_alternative_branches: true,
 			};
@@ -295,9 +306,8 @@ where
 			let mut parent_header = initial_parent.header;
 			let overseer_handle = &mut params.overseer_handle;
 
-			// This needs to change to support elastic scaling, but for continuously
-			// scheduled chains this ensures that the backlog will grow steadily.
-			for n_built in 0..2 {
+			// Build one collation for each claim of the para.
+			for n_built in 0..n_claims {
 				let slot_claim = match can_build_upon(parent_hash).await {
 					None => break,
 					Some(c) => c,
@@ -458,14 +468,56 @@ async fn max_ancestry_lookback(
 	}
 }
 
-// Checks if there exists a scheduled core for the para at the provided relay parent.
+// The number of collations to build at the provided relay parent: one for each claim the para
+// has on any core within `depth` blocks.
+//
+// A collation built on the relay parent can be backed up to `depth` blocks later, so claims
+// further ahead in the claim queue are of no use yet. If the relay chain runtime does not expose
+// the claim queue, or querying it fails, only the availability cores are checked and up to
+// `LEGACY_COLLATIONS_PER_RELAY_PARENT` collations are built if the para is scheduled on any.
 //
-// Falls back to `false` in case of an error.
-async fn is_para_scheduled(
+// Falls back to `0` in case of an error.
+async fn claims_within_depth(
 	relay_parent: PHash,
 	para_id: ParaId,
+	depth: usize,
 	overseer_handle: &mut OverseerHandle,
-) -> bool {
+) -> usize {
+	// This needs to change to support elastic scaling, but for continuously scheduled chains
+	// this ensures that the backlog will grow steadily.
+	const LEGACY_COLLATIONS_PER_RELAY_PARENT: usize = 2;
+
+	let (tx, rx) = oneshot::channel();
+	let request = RuntimeApiRequest::ClaimQueue(tx);
+	overseer_handle
+		.send_msg(RuntimeApiMessage::Request(relay_parent, request), "LookaheadCollator")
+		.await;
+
+	match rx.await {
+		Ok(Ok(claim_queue)) =>
+			return claim_queue
+				.values()
+				.flat_map(|claims| claims.iter().take(depth))
+				.filter(|claim| **claim == para_id)
+				.count(),
+		Ok(Err(RuntimeApiError::NotSupported { .. })) => {},
+		Ok(Err(error)) => {
+			tracing::warn!(
+				target: crate::LOG_TARGET,
+				?error,
+				?relay_parent,
+				"Failed to query claim queue runtime API, falling back to availability cores",
+			);
+		},
+		Err(oneshot::Canceled) => {
+			tracing::warn!(
+				target: crate::LOG_TARGET,
+				?relay_parent,
+				"Sender for claim queue runtime request dropped, falling back to availability cores",
+			);
+		},
+	}
+
 	let (tx, rx) = oneshot::channel();
 	let request = RuntimeApiRequest::AvailabilityCores(tx);
 	overseer_handle
@@ -481,7 +533,7 @@ async fn is_para_scheduled(
 				?relay_parent,
 				"Failed to query availability cores runtime API",
 			);
-			return false
+			return 0
 		},
 		Err(oneshot::Canceled) => {
 			tracing::error!(
@@ -489,9 +541,13 @@ async fn is_para_scheduled(
 				?relay_parent,
 				"Sender for availability cores runtime request dropped",
 			);
-			return false
+			return 0
 		},
 	};
 
-	cores.iter().any(|core| core.para_id() == Some(para_id))
+	if cores.iter().any(|core| core.para_id() == Some(para_id)) {
+		LEGACY_COLLATIONS_PER_RELAY_PARENT
+	} else {
+		0
+	}
 }
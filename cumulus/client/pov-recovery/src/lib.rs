@@ -55,7 +55,7 @@ use polkadot_node_primitives::{PoV, POV_BOMB_LIMIT};
 use polkadot_node_subsystem::messages::AvailabilityRecoveryMessage;
 use polkadot_overseer::Handle as OverseerHandle;
 use polkadot_primitives::{
-	CandidateReceipt, CommittedCandidateReceipt, Id as ParaId, SessionIndex,
+	CandidateReceipt, CommittedCandidateReceipt, CoreIndex, CoreState, Id as ParaId, SessionIndex,
 };
 
 use cumulus_primitives_core::ParachainBlockData;
@@ -63,7 +63,10 @@ use cumulus_relay_chain_interface::{RelayChainInterface, RelayChainResult};
 
 use codec::Decode;
 use futures::{
-	channel::mpsc::Receiver, select, stream::FuturesUnordered, Future, FutureExt, Stream, StreamExt,
+	channel::mpsc::Receiver,
+	select,
+	stream::{self, FuturesUnordered},
+	Future, FutureExt, Stream, StreamExt,
 };
 use futures_timer::Delay;
 use rand::{distributions::Uniform, prelude::Distribution, thread_rng};
@@ -143,6 +146,8 @@ impl RecoveryDelayRange {
 struct Candidate<Block: BlockT> {
 	receipt: CandidateReceipt,
 	session_index: SessionIndex,
+	// The core occupied by the candidate, if known.
+	core_index: Option<CoreIndex>,
 	block_number: NumberFor<Block>,
 	parent_hash: Block::Hash,
 	// Lazy recovery has been submitted.
@@ -268,6 +273,7 @@ where
 		&mut self,
 		receipt: CommittedCandidateReceipt,
 		session_index: SessionIndex,
+		core_index: Option<CoreIndex>,
 	) {
 		let header = match Block::Header::decode(&mut &receipt.commitments.head_data.0[..]) {
 			Ok(header) => header,
@@ -298,6 +304,7 @@ where
 				block_number: *header.number(),
 				receipt: receipt.to_plain(),
 				session_index,
+				core_index,
 				parent_hash: *header.parent_hash(),
 				waiting_recovery: false,
 			},
@@ -555,8 +562,8 @@ where
 		loop {
 			select! {
 				pending_candidate = pending_candidates.next() => {
-					if let Some((receipt, session_index)) = pending_candidate {
-						self.handle_pending_candidate(receipt, session_index);
+					if let Some((receipt, session_index, core_index)) = pending_candidate {
+						self.handle_pending_candidate(receipt, session_index, core_index);
 					} else {
 						tracing::debug!(target: LOG_TARGET, "Pending candidates stream ended");
 						return;
@@ -610,12 +617,15 @@ where
 	}
 }
 
-/// Returns a stream over pending candidates for the parachain corresponding to `para_id`.
+/// Returns a stream over pending candidates for the parachain corresponding to `para_id`,
+/// together with the session and the core they occupy.
 async fn pending_candidates(
 	relay_chain_client: impl RelayChainInterface + Clone,
 	para_id: ParaId,
 	sync_service: Arc<dyn SyncOracle + Sync + Send>,
-) -> RelayChainResult<impl Stream<Item = (CommittedCandidateReceipt, SessionIndex)>> {
+) -> RelayChainResult<
+	impl Stream<Item = (CommittedCandidateReceipt, SessionIndex, Option<CoreIndex>)>,
+> {
 	let import_notification_stream = relay_chain_client.import_notification_stream().await?;
 
 	let filtered_stream = import_notification_stream.filter_map(move |n| {
@@ -633,7 +643,7 @@ async fn pending_candidates(
 			}
 
 			let pending_availability_result = client_for_closure
-				.candidate_pending_availability(hash, para_id)
+				.candidates_pending_availability(hash, para_id)
 				.await
 				.map_err(|e| {
 					tracing::error!(
@@ -651,12 +661,44 @@ async fn pending_candidates(
 					)
 				});
 
-			if let Ok(Some(candidate)) = pending_availability_result {
-				session_index_result.map(|session_index| (candidate, session_index)).ok()
+			// Not knowing the cores only prevents fetching systematic chunks.
+			let occupied_cores = client_for_closure
+				.availability_cores(hash)
+				.await
+				.map_err(|e| {
+					tracing::debug!(
+						target: LOG_TARGET,
+						error = ?e,
+						"Failed to fetch availability cores.",
+					)
+				})
+				.unwrap_or_default()
+				.into_iter()
+				.enumerate()
+				.filter_map(|(index, core)| match core {
+					CoreState::Occupied(occupied) =>
+						Some((occupied.candidate_hash, CoreIndex(index as u32))),
+					_ => None,
+				})
+				.collect::<Vec<_>>();
+
+			if let Ok(candidates) = pending_availability_result {
+				session_index_result
+					.map(|session_index| {
+						candidates.into_iter().map(move |candidate| {
+							let candidate_hash = candidate.hash();
+							let core_index = occupied_cores
+								.iter()
+								.find(|(hash, _)| *hash == candidate_hash)
+								.map(|(_, core_index)| *core_index);
+							(candidate, session_index, core_index)
+						})
+					})
+					.ok()
 			} else {
 				None
 			}
 		}
 	});
-	Ok(filtered_stream)
+	Ok(filtered_stream.flat_map(stream::iter))
 }
@@ -19,9 +19,9 @@ use std::{pin::Pin, sync::Arc, time::Duration};
 use async_trait::async_trait;
 use cumulus_primitives_core::{
 	relay_chain::{
-		runtime_api::ParachainHost, Block as PBlock, BlockId, CommittedCandidateReceipt,
-		Hash as PHash, Header as PHeader, InboundHrmpMessage, OccupiedCoreAssumption, SessionIndex,
-		ValidatorId,
+		runtime_api::ParachainHost, Block as PBlock, BlockId, BlockNumber,
+		CommittedCandidateReceipt, CoreState, Hash as PHash, Header as PHeader, InboundHrmpMessage,
+		OccupiedCoreAssumption, SessionIndex, ValidatorId,
 	},
 	InboundDownwardMessage, ParaId, PersistedValidationData,
 };
@@ -36,7 +36,7 @@ use sc_client_api::{
 	StorageProof,
 };
 use sc_telemetry::TelemetryWorkerHandle;
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_consensus::SyncOracle;
 use sp_core::{sp_std::collections::btree_map::BTreeMap, Pair};
 use sp_state_machine::{Backend as StateBackend, StorageValue};
@@ -123,6 +123,28 @@ impl RelayChainInterface for RelayChainInProcessInterface {
 		Ok(self.full_client.runtime_api().candidate_pending_availability(hash, para_id)?)
 	}
 
+	async fn candidates_pending_availability(
+		&self,
+		hash: PHash,
+		para_id: ParaId,
+	) -> RelayChainResult<Vec<CommittedCandidateReceipt>> {
+		let runtime_api = self.full_client.runtime_api();
+		let api_version = runtime_api.api_version::<dyn ParachainHost<PBlock>>(hash)?;
+
+		if api_version.map_or(false, |version| version >= 11) {
+			Ok(runtime_api.candidates_pending_availability(hash, para_id)?)
+		} else {
+			Ok(runtime_api.candidate_pending_availability(hash, para_id)?.into_iter().collect())
+		}
+	}
+
+	async fn availability_cores(
+		&self,
+		hash: PHash,
+	) -> RelayChainResult<Vec<CoreState<PHash, BlockNumber>>> {
+		Ok(self.full_client.runtime_api().availability_cores(hash)?)
+	}
+
 	async fn session_index_for_child(&self, hash: PHash) -> RelayChainResult<SessionIndex> {
 		Ok(self.full_client.runtime_api().session_index_for_child(hash)?)
 	}
@@ -285,6 +307,7 @@ fn build_polkadot_full_node(
 			is_parachain_node,
 			// Disable BEEFY. It should not be required by the internal relay chain node.
 			enable_beefy: false,
+			beefy_warp_sync: false,
 			force_authoring_backoff: false,
 			jaeger_agent: None,
 			telemetry_worker_handle,
@@ -29,8 +29,8 @@ use sp_api::ApiError;
 use cumulus_primitives_core::relay_chain::BlockId;
 pub use cumulus_primitives_core::{
 	relay_chain::{
-		CommittedCandidateReceipt, Hash as PHash, Header as PHeader, InboundHrmpMessage,
-		OccupiedCoreAssumption, SessionIndex, ValidatorId,
+		BlockNumber, CommittedCandidateReceipt, CoreState, Hash as PHash, Header as PHeader,
+		InboundHrmpMessage, OccupiedCoreAssumption, SessionIndex, ValidatorId,
 	},
 	InboundDownwardMessage, ParaId, PersistedValidationData,
 };
@@ -157,6 +157,34 @@ pub trait RelayChainInterface: Send + Sync {
 		para_id: ParaId,
 	) -> RelayChainResult<Option<CommittedCandidateReceipt>>;
 
+	/// Get the receipts of all candidates pending availability for a para, in the order they
+	/// build on top of each other.
+	///
+	/// Falls back to [`Self::candidate_pending_availability`] by default, which only yields the
+	/// candidate occupying the first core of the para.
+	async fn candidates_pending_availability(
+		&self,
+		block_id: PHash,
+		para_id: ParaId,
+	) -> RelayChainResult<Vec<CommittedCandidateReceipt>> {
+		Ok(self
+			.candidate_pending_availability(block_id, para_id)
+			.await?
+			.into_iter()
+			.collect())
+	}
+
+	/// Yields information on all availability cores as relevant to the child block.
+	///
+	/// Returns no cores by default, in which case callers can't map candidates to the cores
+	/// they occupy.
+	async fn availability_cores(
+		&self,
+		_block_id: PHash,
+	) -> RelayChainResult<Vec<CoreState<PHash, BlockNumber>>> {
+		Ok(Vec::new())
+	}
+
 	/// Returns the session index expected at a child of the block.
 	async fn session_index_for_child(&self, block_id: PHash) -> RelayChainResult<SessionIndex>;
 
@@ -236,6 +264,21 @@ where
 		(**self).candidate_pending_availability(block_id, para_id).await
 	}
 
+	async fn candidates_pending_availability(
+		&self,
+		block_id: PHash,
+		para_id: ParaId,
+	) -> RelayChainResult<Vec<CommittedCandidateReceipt>> {
+		(**self).candidates_pending_availability(block_id, para_id).await
+	}
+
+	async fn availability_cores(
+		&self,
+		block_id: PHash,
+	) -> RelayChainResult<Vec<CoreState<PHash, BlockNumber>>> {
+		(**self).availability_cores(block_id).await
+	}
+
 	async fn session_index_for_child(&self, block_id: PHash) -> RelayChainResult<SessionIndex> {
 		(**self).session_index_for_child(block_id).await
 	}
@@ -442,6 +442,30 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 	async fn node_features(&self, at: Hash) -> Result<NodeFeatures, ApiError> {
 		Ok(self.rpc_client.parachain_host_node_features(at).await?)
 	}
+
+	async fn candidates_pending_availability(
+		&self,
+		at: Hash,
+		para_id: cumulus_primitives_core::ParaId,
+	) -> Result<Vec<polkadot_primitives::CommittedCandidateReceipt<Hash>>, sp_api::ApiError> {
+		Ok(self
+			.rpc_client
+			.parachain_host_candidates_pending_availability(at, para_id)
+			.await?)
+	}
+
+	async fn claim_queue(
+		&self,
+		at: Hash,
+	) -> Result<
+		std::collections::BTreeMap<
+			polkadot_primitives::CoreIndex,
+			std::collections::VecDeque<cumulus_primitives_core::ParaId>,
+		>,
+		sp_api::ApiError,
+	> {
+		Ok(self.rpc_client.parachain_host_claim_queue(at).await?)
+	}
 }
 
 #[async_trait::async_trait]
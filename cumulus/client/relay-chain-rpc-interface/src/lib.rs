@@ -18,8 +18,9 @@ use async_trait::async_trait;
 use core::time::Duration;
 use cumulus_primitives_core::{
 	relay_chain::{
-		CommittedCandidateReceipt, Hash as RelayHash, Header as RelayHeader, InboundHrmpMessage,
-		OccupiedCoreAssumption, SessionIndex, ValidatorId,
+		runtime_api::ParachainHost, Block, BlockNumber, CommittedCandidateReceipt, CoreState,
+		Hash as RelayHash, Header as RelayHeader, InboundHrmpMessage, OccupiedCoreAssumption,
+		SessionIndex, ValidatorId,
 	},
 	InboundDownwardMessage, ParaId, PersistedValidationData,
 };
@@ -30,6 +31,7 @@ use futures::{FutureExt, Stream, StreamExt};
 use polkadot_overseer::Handle;
 
 use sc_client_api::StorageProof;
+use sp_api::RuntimeApiInfo;
 use sp_core::sp_std::collections::btree_map::BTreeMap;
 use sp_state_machine::StorageValue;
 use sp_storage::StorageKey;
@@ -120,6 +122,35 @@ impl RelayChainInterface for RelayChainRpcInterface {
 			.await
 	}
 
+	async fn candidates_pending_availability(
+		&self,
+		hash: RelayHash,
+		para_id: ParaId,
+	) -> RelayChainResult<Vec<CommittedCandidateReceipt>> {
+		let api_id = <dyn ParachainHost<Block>>::ID;
+		let api_version = self.rpc_client.runtime_version(hash).await?.api_version(&api_id);
+
+		if api_version.map_or(false, |version| version >= 11) {
+			self.rpc_client
+				.parachain_host_candidates_pending_availability(hash, para_id)
+				.await
+		} else {
+			Ok(self
+				.rpc_client
+				.parachain_host_candidate_pending_availability(hash, para_id)
+				.await?
+				.into_iter()
+				.collect())
+		}
+	}
+
+	async fn availability_cores(
+		&self,
+		hash: RelayHash,
+	) -> RelayChainResult<Vec<CoreState<RelayHash, BlockNumber>>> {
+		self.rpc_client.parachain_host_availability_cores(hash).await
+	}
+
 	async fn session_index_for_child(&self, hash: RelayHash) -> RelayChainResult<SessionIndex> {
 		self.rpc_client.parachain_host_session_index_for_child(hash).await
 	}
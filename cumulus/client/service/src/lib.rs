@@ -405,7 +405,7 @@ pub struct BuildNetworkParams<
 	pub parachain_config: &'a Configuration,
 	pub net_config: sc_network::config::FullNetworkConfiguration,
 	pub client: Arc<Client>,
-	pub transaction_pool: Arc<sc_transaction_pool::FullPool<Block, Client>>,
+	pub transaction_pool: Arc<sc_transaction_pool::FullTransactionPool<Block, Client>>,
 	pub para_id: ParaId,
 	pub relay_chain_interface: RCInterface,
 	pub spawn_handle: SpawnTaskHandle,
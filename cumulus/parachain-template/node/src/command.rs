@@ -377,6 +377,10 @@ impl CliConfiguration<Self> for RelayChainCli {
 		self.base.base.max_runtime_instances()
 	}
 
+	fn offchain_execution_limits(&self) -> Result<sc_service::config::ExecutionLimits> {
+		self.base.base.offchain_execution_limits()
+	}
+
 	fn announce_block(&self) -> Result<bool> {
 		self.base.base.announce_block()
 	}
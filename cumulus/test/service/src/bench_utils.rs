@@ -191,6 +191,7 @@ pub fn get_wasm_module() -> Box<dyn sc_executor_common::wasm_runtime::WasmModule
 			heap_alloc_strategy: DEFAULT_HEAP_ALLOC_STRATEGY,
 			instantiation_strategy: sc_executor::WasmtimeInstantiationStrategy::PoolingCopyOnWrite,
 			deterministic_stack_limit: None,
+			fuel_limit: None,
 			canonicalize_nans: false,
 			parallel_compilation: true,
 			wasm_multi_value: false,
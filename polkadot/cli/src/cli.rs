@@ -88,6 +88,13 @@ pub struct RunCmd {
 	#[arg(long)]
 	pub no_beefy: bool,
 
+	/// Warp sync with BEEFY justifications instead of GRANDPA ones.
+	///
+	/// Proofs start from the BEEFY validators at genesis, and are only served by nodes indexing
+	/// the MMR offchain.
+	#[arg(long)]
+	pub beefy_warp_sync: bool,
+
 	/// Allows a validator to run insecurely outside of Secure Validator Mode. Security features
 	/// are still enabled on a best-effort basis, but missing features are no longer required. For
 	/// more information see <https://github.com/w3f/polkadot-wiki/issues/4881>.
@@ -213,6 +213,10 @@ where
 		}
 	}
 
+	if cli.run.beefy_warp_sync && !runner.config().network.sync_mode.is_warp() {
+		return Err(Error::Other("BEEFY warp sync requires `--sync warp`.".into()))
+	}
+
 	set_default_ss58_version(chain_spec);
 
 	if chain_spec.is_kusama() {
@@ -254,6 +258,7 @@ where
 			service::NewFullParams {
 				is_parachain_node: service::IsParachainNode::No,
 				enable_beefy,
+				beefy_warp_sync: cli.run.beefy_warp_sync,
 				force_authoring_backoff: cli.run.force_authoring_backoff,
 				jaeger_agent,
 				telemetry_worker_handle: None,
@@ -96,6 +96,19 @@ pub const fn recovery_threshold(n_validators: usize) -> Result<usize, Error> {
 	Ok(needed + 1)
 }
 
+/// Obtain the number of systematic chunks that should be enough to recover the data by simply
+/// concatenating them.
+///
+/// The Reed-Solomon code works over a power-of-two number of payload shards, so this is the
+/// largest power of two that does not exceed [`recovery_threshold`]. The first
+/// `systematic_recovery_threshold` chunks contain the original payload verbatim.
+pub const fn systematic_recovery_threshold(n_validators: usize) -> Result<usize, Error> {
+	match recovery_threshold(n_validators) {
+		Ok(threshold) => Ok(1 << (usize::BITS - 1 - threshold.leading_zeros())),
+		Err(e) => Err(e),
+	}
+}
+
 fn code_params(n_validators: usize) -> Result<CodeParams, Error> {
 	// we need to be able to reconstruct from 1/3 - eps
 
@@ -204,6 +217,56 @@ where
 	Decode::decode(&mut &payload_bytes[..]).or_else(|_e| Err(Error::BadPayload))
 }
 
+/// Reconstruct the v1 available data from the set of systematic chunks.
+///
+/// Provide a vector containing the chunk data of the first
+/// [`systematic_recovery_threshold`] chunks, in chunk index order.
+///
+/// Works only up to 65536 validators, and `n_validators` must be non-zero.
+pub fn reconstruct_from_systematic_v1(
+	n_validators: usize,
+	chunks: Vec<Vec<u8>>,
+) -> Result<AvailableData, Error> {
+	reconstruct_from_systematic(n_validators, chunks)
+}
+
+/// Reconstruct decodable data from the set of systematic chunks.
+///
+/// Provide a vector containing the chunk data of the first
+/// [`systematic_recovery_threshold`] chunks, in chunk index order. No decoding is performed,
+/// the payload is obtained by interleaving the field elements of the systematic chunks.
+///
+/// Works only up to 65536 validators, and `n_validators` must be non-zero.
+pub fn reconstruct_from_systematic<T: Decode>(
+	n_validators: usize,
+	chunks: Vec<Vec<u8>>,
+) -> Result<T, Error> {
+	let k = systematic_recovery_threshold(n_validators)?;
+
+	if chunks.len() < k {
+		return Err(Error::NotEnoughChunks)
+	}
+
+	let shard_len = chunks[0].len();
+	if shard_len % 2 != 0 {
+		return Err(Error::UnevenLength)
+	}
+	if shard_len == 0 || chunks.iter().take(k).any(|chunk| chunk.len() != shard_len) {
+		return Err(Error::NonUniformChunks)
+	}
+
+	// Each chunk holds one 2-byte field element of every encoding run, the first `k` elements
+	// of a run being the payload itself.
+	let mut payload_bytes = Vec::with_capacity(shard_len * k);
+	for element in (0..shard_len).step_by(2) {
+		for chunk in chunks.iter().take(k) {
+			payload_bytes.extend_from_slice(&chunk[element..element + 2]);
+		}
+	}
+
+	Decode::decode(&mut &payload_bytes[..]).or_else(|_e| Err(Error::BadPayload))
+}
+
 /// An iterator that yields merkle branches and chunk data for all chunks to
 /// be sent to other validators.
 pub struct Branches<'a, I> {
@@ -379,6 +442,36 @@ mod tests {
 		assert_eq!(reconstructed, available_data);
 	}
 
+	#[test]
+	fn systematic_threshold_is_power_of_two_below_threshold() {
+		assert_eq!(systematic_recovery_threshold(2), Ok(1));
+		assert_eq!(systematic_recovery_threshold(10), Ok(4));
+		assert_eq!(systematic_recovery_threshold(12), Ok(4));
+		assert_eq!(systematic_recovery_threshold(300), Ok(64));
+		assert_eq!(systematic_recovery_threshold(1000), Ok(256));
+		assert_eq!(systematic_recovery_threshold(1), Err(Error::NotEnoughValidators));
+	}
+
+	#[test]
+	fn systematic_round_trip_works() {
+		let pov = PoV { block_data: BlockData((0..255).collect()) };
+		let available_data = AvailableData { pov: pov.into(), validation_data: Default::default() };
+
+		for n_validators in [2, 3, 5, 10, 100, 300] {
+			let chunks = obtain_chunks(n_validators, &available_data).unwrap();
+			let k = systematic_recovery_threshold(n_validators).unwrap();
+
+			let reconstructed: AvailableData =
+				reconstruct_from_systematic(n_validators, chunks[..k].to_vec()).unwrap();
+			assert_eq!(reconstructed, available_data);
+
+			assert_eq!(
+				reconstruct_from_systematic_v1(n_validators, chunks[..k - 1].to_vec()),
+				Err(Error::NotEnoughChunks),
+			);
+		}
+	}
+
 	#[test]
 	fn reconstruct_does_not_panic_on_low_validator_count() {
 		let reconstructed = reconstruct_v1(1, [].iter().cloned());
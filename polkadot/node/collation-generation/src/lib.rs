@@ -23,7 +23,8 @@
 //! * If there is no collation generation config, ignore.
 //! * Otherwise, for each `activated` head in the update:
 //!   * Determine if the para is scheduled on any core by fetching the `availability_cores` Runtime
-//!     API.
+//!     API. If the runtime supports the `claim_queue` Runtime API, a claim anywhere in the queue of
+//!     a core counts as well.
 //!   * Use the Runtime API subsystem to fetch the full validation data.
 //!   * Invoke the `collator`, and use its outputs to produce a [`CandidateReceipt`], signed with
 //!     the configuration's `key`.
@@ -43,13 +44,14 @@ use polkadot_node_subsystem::{
 	SubsystemContext, SubsystemError, SubsystemResult,
 };
 use polkadot_node_subsystem_util::{
-	request_async_backing_params, request_availability_cores, request_persisted_validation_data,
-	request_validation_code, request_validation_code_hash, request_validators,
+	request_async_backing_params, request_availability_cores, request_claim_queue,
+	request_persisted_validation_data, request_validation_code, request_validation_code_hash,
+	request_validators,
 };
 use polkadot_primitives::{
 	collator_signature_payload, CandidateCommitments, CandidateDescriptor, CandidateReceipt,
-	CollatorPair, CoreState, Hash, Id as ParaId, OccupiedCoreAssumption, PersistedValidationData,
-	ValidationCodeHash,
+	CollatorPair, CoreIndex, CoreState, Hash, Id as ParaId, OccupiedCoreAssumption,
+	PersistedValidationData, ScheduledCore, ValidationCodeHash,
 };
 use sp_core::crypto::Pair;
 use std::sync::Arc;
@@ -204,22 +206,54 @@ async fn handle_new_activations<Context>(
 	for relay_parent in activated {
 		let _relay_parent_timer = metrics.time_new_activations_relay_parent();
 
-		let (availability_cores, validators, async_backing_params) = join!(
+		let (availability_cores, validators, async_backing_params, claim_queue) = join!(
 			request_availability_cores(relay_parent, ctx.sender()).await,
 			request_validators(relay_parent, ctx.sender()).await,
 			request_async_backing_params(relay_parent, ctx.sender()).await,
+			request_claim_queue(relay_parent, ctx.sender()).await,
 		);
 
 		let availability_cores = availability_cores??;
 		let n_validators = validators??.len();
 		let async_backing_params = async_backing_params?.ok();
+		let claim_queue = match claim_queue? {
+			Ok(claim_queue) => Some(claim_queue),
+			Err(RuntimeApiError::NotSupported { .. }) => None,
+			Err(err) => {
+				gum::debug!(
+					target: LOG_TARGET,
+					relay_parent = ?relay_parent,
+					?err,
+					"failed to fetch the claim queue, falling back to availability cores",
+				);
+				None
+			},
+		};
+
+		// Our para may be scheduled on a core at any point of the claim queue, not only next.
+		// Otherwise, the para scheduled next is reported.
+		let claim_on_core = |core_idx: usize| {
+			claim_queue.as_ref().map(|claim_queue| {
+				let claims = claim_queue.get(&CoreIndex(core_idx as u32))?;
+				claims
+					.iter()
+					.find(|para_id| **para_id == config.para_id)
+					.or_else(|| claims.front())
+					.map(|para_id| ScheduledCore { para_id: *para_id, collator: None })
+			})
+		};
 
 		for (core_idx, core) in availability_cores.into_iter().enumerate() {
 			let _availability_core_timer = metrics.time_new_activations_availability_core();
 
 			let (scheduled_core, assumption) = match core {
 				CoreState::Scheduled(scheduled_core) =>
-					(scheduled_core, OccupiedCoreAssumption::Free),
+					match (claim_on_core(core_idx), async_backing_params) {
+						// With async backing, collations for later claims on the core can be built
+						// ahead of time.
+						(Some(Some(claim)), Some(_)) => (claim, OccupiedCoreAssumption::Free),
+						_ => (scheduled_core, OccupiedCoreAssumption::Free),
+					},
 				CoreState::Occupied(occupied_core) => match async_backing_params {
 					Some(params) if params.max_candidate_depth >= 1 => {
 						// maximum candidate depth when building on top of a block
@@ -229,7 +263,12 @@ async fn handle_new_activations<Context>(
 						// TODO [now]: this assumes that next up == current.
 						// in practice we should only set `OccupiedCoreAssumption::Included`
 						// when the candidate occupying the core is also of the same para.
-						if let Some(scheduled) = occupied_core.next_up_on_available {
+						let next_up = match claim_on_core(core_idx) {
+							Some(claim) => claim,
+							None => occupied_core.next_up_on_available,
+						};
+
+						if let Some(scheduled) = next_up {
 							(scheduled, OccupiedCoreAssumption::Included)
 						} else {
 							continue
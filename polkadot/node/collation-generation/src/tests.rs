@@ -29,11 +29,15 @@ use polkadot_node_subsystem::{
 use polkadot_node_subsystem_test_helpers::{subsystem_test_harness, TestSubsystemContextHandle};
 use polkadot_node_subsystem_util::TimeoutExt;
 use polkadot_primitives::{
-	CollatorPair, HeadData, Id as ParaId, PersistedValidationData, ScheduledCore, ValidationCode,
+	AsyncBackingParams, CollatorPair, GroupIndex, HeadData, Id as ParaId, OccupiedCore,
+	PersistedValidationData, ScheduledCore, ValidationCode,
 };
 use sp_keyring::sr25519::Keyring as Sr25519Keyring;
-use std::pin::Pin;
-use test_helpers::{dummy_hash, dummy_head_data, dummy_validator};
+use std::{
+	collections::{BTreeMap, VecDeque},
+	pin::Pin,
+};
+use test_helpers::{dummy_candidate_descriptor, dummy_hash, dummy_head_data, dummy_validator};
 
 type VirtualOverseer = TestSubsystemContextHandle<CollationGenerationMessage>;
 
@@ -159,6 +163,14 @@ fn requests_availability_per_relay_parent() {
 				))) => {
 					tx.send(Err(RuntimeApiError::NotSupported { runtime_api_name: "doesnt_matter" })).unwrap();
 				},
+				Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_hash,
+					RuntimeApiRequest::ClaimQueue(
+						tx,
+					),
+				))) => {
+					tx.send(Err(RuntimeApiError::NotSupported { runtime_api_name: "doesnt_matter" })).unwrap();
+				},
 				Some(msg) => panic!("didn't expect any other overseer requests given no availability cores; got {:?}", msg),
 			}
 		}
@@ -242,6 +254,15 @@ fn requests_validation_data_for_scheduled_matches() {
 					}))
 					.unwrap();
 				},
+				Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_hash,
+					RuntimeApiRequest::ClaimQueue(tx),
+				))) => {
+					tx.send(Err(RuntimeApiError::NotSupported {
+						runtime_api_name: "doesnt_matter",
+					}))
+					.unwrap();
+				},
 				Some(msg) => {
 					panic!("didn't expect any other overseer requests; got {:?}", msg)
 				},
@@ -339,6 +360,15 @@ fn sends_distribute_collation_message() {
 					}))
 					.unwrap();
 				},
+				Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_hash,
+					RuntimeApiRequest::ClaimQueue(tx),
+				))) => {
+					tx.send(Err(RuntimeApiError::NotSupported {
+						runtime_api_name: "doesnt_matter",
+					}))
+					.unwrap();
+				},
 				Some(msg @ AllMessages::CollatorProtocol(_)) => {
 					inner_to_collator_protocol.lock().await.push(msg);
 				},
@@ -501,6 +531,15 @@ fn fallback_when_no_validation_code_hash_api() {
 					}))
 					.unwrap();
 				},
+				Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_hash,
+					RuntimeApiRequest::ClaimQueue(tx),
+				))) => {
+					tx.send(Err(RuntimeApiError::NotSupported {
+						runtime_api_name: "doesnt_matter",
+					}))
+					.unwrap();
+				},
 				Some(msg @ AllMessages::CollatorProtocol(_)) => {
 					inner_to_collator_protocol.lock().await.push(msg);
 				},
@@ -540,6 +579,200 @@ fn fallback_when_no_validation_code_hash_api() {
 	}
 }
 
+#[test]
+fn occupied_cores_use_claim_queue_when_supported() {
+	let relay_parent = Hash::repeat_byte(1);
+
+	let occupied_core = |next_up_on_available: Option<ScheduledCore>| {
+		CoreState::Occupied(OccupiedCore {
+			next_up_on_available,
+			occupied_since: 0,
+			time_out_at: 10,
+			next_up_on_time_out: None,
+			availability: Default::default(),
+			group_responsible: GroupIndex(0),
+			candidate_hash: Default::default(),
+			candidate_descriptor: dummy_candidate_descriptor(dummy_hash()),
+		})
+	};
+
+	let requested_validation_data = Arc::new(Mutex::new(Vec::new()));
+
+	let overseer_requested_validation_data = requested_validation_data.clone();
+	let overseer = |mut handle: TestSubsystemContextHandle<CollationGenerationMessage>| async move {
+		loop {
+			match handle.try_recv().await {
+				None => break,
+				Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_hash,
+					RuntimeApiRequest::AvailabilityCores(tx),
+				))) => {
+					// `next_up_on_available` disagrees with the claim queue on purpose.
+					tx.send(Ok(vec![
+						occupied_core(Some(scheduled_core_for(16))),
+						occupied_core(None),
+					]))
+					.unwrap();
+				},
+				Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_hash,
+					RuntimeApiRequest::ClaimQueue(tx),
+				))) => {
+					let mut claim_queue = BTreeMap::new();
+					claim_queue.insert(CoreIndex(0), VecDeque::from([ParaId::from(17)]));
+					// Our para is only scheduled after another one.
+					claim_queue
+						.insert(CoreIndex(1), VecDeque::from([ParaId::from(17), ParaId::from(16)]));
+					tx.send(Ok(claim_queue)).unwrap();
+				},
+				Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_hash,
+					RuntimeApiRequest::PersistedValidationData(para_id, assumption, tx),
+				))) => {
+					overseer_requested_validation_data.lock().await.push((para_id, assumption));
+					tx.send(Ok(None)).unwrap();
+				},
+				Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_hash,
+					RuntimeApiRequest::Validators(tx),
+				))) => {
+					tx.send(Ok(vec![dummy_validator(); 3])).unwrap();
+				},
+				Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_hash,
+					RuntimeApiRequest::AsyncBackingParams(tx),
+				))) => {
+					tx.send(Ok(AsyncBackingParams {
+						max_candidate_depth: 1,
+						allowed_ancestry_len: 1,
+					}))
+					.unwrap();
+				},
+				Some(msg) => {
+					panic!("didn't expect any other overseer requests; got {:?}", msg)
+				},
+			}
+		}
+	};
+
+	subsystem_test_harness(overseer, |mut ctx| async move {
+		handle_new_activations(
+			Arc::new(test_config(16)),
+			vec![relay_parent],
+			&mut ctx,
+			Metrics(None),
+		)
+		.await
+		.unwrap();
+	});
+
+	let requested_validation_data = Arc::try_unwrap(requested_validation_data)
+		.expect("overseer should have shut down by now")
+		.into_inner();
+
+	// Only the second core has a claim for our para, so validation data is requested exactly once.
+	assert_eq!(
+		requested_validation_data,
+		vec![(ParaId::from(16), OccupiedCoreAssumption::Included)]
+	);
+}
+
+#[test]
+fn claim_queue_errors_fall_back_to_availability_cores() {
+	let relay_parent = Hash::repeat_byte(1);
+
+	let occupied_core = |next_up_on_available: Option<ScheduledCore>| {
+		CoreState::Occupied(OccupiedCore {
+			next_up_on_available,
+			occupied_since: 0,
+			time_out_at: 10,
+			next_up_on_time_out: None,
+			availability: Default::default(),
+			group_responsible: GroupIndex(0),
+			candidate_hash: Default::default(),
+			candidate_descriptor: dummy_candidate_descriptor(dummy_hash()),
+		})
+	};
+
+	let requested_validation_data = Arc::new(Mutex::new(Vec::new()));
+
+	let overseer_requested_validation_data = requested_validation_data.clone();
+	let overseer = |mut handle: TestSubsystemContextHandle<CollationGenerationMessage>| async move {
+		loop {
+			match handle.try_recv().await {
+				None => break,
+				Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_hash,
+					RuntimeApiRequest::AvailabilityCores(tx),
+				))) => {
+					tx.send(Ok(vec![
+						occupied_core(Some(scheduled_core_for(16))),
+						occupied_core(None),
+					]))
+					.unwrap();
+				},
+				Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_hash,
+					RuntimeApiRequest::ClaimQueue(tx),
+				))) => {
+					tx.send(Err(RuntimeApiError::Execution {
+						runtime_api_name: "claim_queue",
+						source: Arc::new(std::io::Error::new(std::io::ErrorKind::Other, "oops")),
+					}))
+					.unwrap();
+				},
+				Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_hash,
+					RuntimeApiRequest::PersistedValidationData(para_id, assumption, tx),
+				))) => {
+					overseer_requested_validation_data.lock().await.push((para_id, assumption));
+					tx.send(Ok(None)).unwrap();
+				},
+				Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_hash,
+					RuntimeApiRequest::Validators(tx),
+				))) => {
+					tx.send(Ok(vec![dummy_validator(); 3])).unwrap();
+				},
+				Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_hash,
+					RuntimeApiRequest::AsyncBackingParams(tx),
+				))) => {
+					tx.send(Ok(AsyncBackingParams {
+						max_candidate_depth: 1,
+						allowed_ancestry_len: 1,
+					}))
+					.unwrap();
+				},
+				Some(msg) => {
+					panic!("didn't expect any other overseer requests; got {:?}", msg)
+				},
+			}
+		}
+	};
+
+	subsystem_test_harness(overseer, |mut ctx| async move {
+		handle_new_activations(
+			Arc::new(test_config(16)),
+			vec![relay_parent],
+			&mut ctx,
+			Metrics(None),
+		)
+		.await
+		.unwrap();
+	});
+
+	let requested_validation_data = Arc::try_unwrap(requested_validation_data)
+		.expect("overseer should have shut down by now")
+		.into_inner();
+
+	// Only the first core is up next for our para, according to the availability cores.
+	assert_eq!(
+		requested_validation_data,
+		vec![(ParaId::from(16), OccupiedCoreAssumption::Included)]
+	);
+}
+
 #[test]
 fn submit_collation_is_no_op_before_initialization() {
 	test_harness(|mut virtual_overseer| async move {
@@ -55,9 +55,9 @@ use polkadot_node_subsystem_util::{
 };
 use polkadot_primitives::{
 	vstaging::{ApprovalVoteMultipleCandidates, ApprovalVotingParams},
-	BlockNumber, CandidateHash, CandidateIndex, CandidateReceipt, DisputeStatement, ExecutorParams,
-	GroupIndex, Hash, PvfExecKind, SessionIndex, SessionInfo, ValidDisputeStatementKind,
-	ValidatorId, ValidatorIndex, ValidatorPair, ValidatorSignature,
+	BlockNumber, CandidateHash, CandidateIndex, CandidateReceipt, CoreIndex, DisputeStatement,
+	ExecutorParams, GroupIndex, Hash, PvfExecKind, SessionIndex, SessionInfo,
+	ValidDisputeStatementKind, ValidatorId, ValidatorIndex, ValidatorPair, ValidatorSignature,
 };
 use sc_keystore::LocalKeystore;
 use sp_application_crypto::Pair;
@@ -897,6 +897,7 @@ enum Action {
 		executor_params: ExecutorParams,
 		candidate: CandidateReceipt,
 		backing_group: GroupIndex,
+		core_index: Option<CoreIndex>,
 		distribute_assignment: bool,
 	},
 	NoteApprovedInChainSelection(Hash),
@@ -1156,6 +1157,7 @@ async fn handle_actions<Context>(
 				executor_params,
 				candidate,
 				backing_group,
+				core_index,
 				distribute_assignment,
 			} => {
 				// Don't launch approval work if the node is syncing.
@@ -1212,6 +1214,7 @@ async fn handle_actions<Context>(
 										validator_index,
 										block_hash,
 										backing_group,
+										core_index,
 										executor_params,
 										&launch_approval_span,
 									)
@@ -2961,6 +2964,12 @@ async fn process_wakeup<Context>(
 					};
 					db.write_block_entry(block_entry.clone());
 
+					let core_index = block_entry
+						.candidates()
+						.iter()
+						.find(|(_, hash)| hash == &candidate_hash)
+						.map(|(core_index, _)| *core_index);
+
 					actions.push(Action::LaunchApproval {
 						claimed_candidate_indices,
 						candidate_hash,
@@ -2971,6 +2980,7 @@ async fn process_wakeup<Context>(
 						executor_params: executor_params.clone(),
 						candidate: candidate_receipt,
 						backing_group,
+						core_index,
 						distribute_assignment,
 					});
 				},
@@ -3029,6 +3039,7 @@ async fn launch_approval<Context>(
 	validator_index: ValidatorIndex,
 	block_hash: Hash,
 	backing_group: GroupIndex,
+	core_index: Option<CoreIndex>,
 	executor_params: ExecutorParams,
 	span: &jaeger::Span,
 ) -> SubsystemResult<RemoteHandle<ApprovalState>> {
@@ -3076,6 +3087,7 @@ async fn launch_approval<Context>(
 		candidate.clone(),
 		session_index,
 		Some(backing_group),
+		core_index,
 		a_tx,
 	))
 	.await;
@@ -104,14 +104,17 @@ use polkadot_node_subsystem_util::{
 	Validator,
 };
 use polkadot_primitives::{
-	BackedCandidate, CandidateCommitments, CandidateHash, CandidateReceipt,
-	CommittedCandidateReceipt, CoreIndex, CoreState, ExecutorParams, Hash, Id as ParaId,
-	PersistedValidationData, PvfExecKind, SigningContext, ValidationCode, ValidatorId,
-	ValidatorIndex, ValidatorSignature, ValidityAttestation,
+	effective_minimum_backing_votes, BackedCandidate, CandidateCommitments, CandidateHash,
+	CandidateReceipt, CommittedCandidateReceipt, CoreIndex, CoreState, ExecutorParams, Hash,
+	Id as ParaId, PersistedValidationData, PvfExecKind, SigningContext, ValidationCode,
+	ValidatorId, ValidatorIndex, ValidatorSignature, ValidityAttestation,
 };
 use sp_keystore::KeystorePtr;
 use statement_table::{
-	generic::AttestedCandidate as TableAttestedCandidate,
+	generic::{
+		AttestedCandidate as TableAttestedCandidate,
+		ValidityAttestation as TableValidityAttestation,
+	},
 	v2::{
 		SignedStatement as TableSignedStatement, Statement as TableStatement,
 		Summary as TableSummary,
@@ -381,10 +384,24 @@ struct AttestingData {
 #[derive(Default)]
 struct TableContext {
 	validator: Option<Validator>,
-	groups: HashMap<ParaId, Vec<ValidatorIndex>>,
+	/// The backing groups of the cores assigned to each para. A para scheduled on multiple
+	/// cores has one group per core.
+	groups: HashMap<ParaId, Vec<Vec<ValidatorIndex>>>,
 	validators: Vec<ValidatorId>,
 }
 
+impl TableContext {
+	/// Whether the given validator is in the same backing group of the para as the local
+	/// validator.
+	fn is_in_local_group(&self, para_id: &ParaId, validator: &ValidatorIndex) -> bool {
+		let Some(local) = self.validator.as_ref().map(|v| v.index()) else { return false };
+
+		self.groups.get(para_id).map_or(false, |groups| {
+			groups.iter().any(|g| g.contains(&local) && g.contains(validator))
+		})
+	}
+}
+
 impl TableContextTrait for TableContext {
 	type AuthorityId = ValidatorIndex;
 	type Digest = CandidateHash;
@@ -401,11 +418,13 @@ impl TableContextTrait for TableContext {
 	}
 
 	fn is_member_of(&self, authority: &ValidatorIndex, group: &ParaId) -> bool {
-		self.groups.get(group).map_or(false, |g| g.iter().any(|a| a == authority))
+		self.groups
+			.get(group)
+			.map_or(false, |groups| groups.iter().any(|g| g.contains(authority)))
 	}
 
 	fn get_group_size(&self, group: &ParaId) -> Option<usize> {
-		self.groups.get(group).map(|g| g.len())
+		self.groups.get(group).and_then(|groups| groups.iter().map(|g| g.len()).max())
 	}
 }
 
@@ -432,13 +451,32 @@ fn table_attested_to_backed(
 		ValidatorSignature,
 	>,
 	table_context: &TableContext,
+	minimum_backing_votes: u32,
 ) -> Option<BackedCandidate> {
 	let TableAttestedCandidate { candidate, validity_votes, group_id: para_id } = attested;
 
-	let (ids, validity_votes): (Vec<_>, Vec<ValidityAttestation>) =
-		validity_votes.into_iter().map(|(id, vote)| (id, vote.into())).unzip();
+	// With the para scheduled on multiple cores, the table accepts votes from the backing group
+	// of any of these cores. Only the votes of the group the candidate was seconded in count.
+	let seconder = validity_votes.iter().find_map(|(id, vote)| {
+		matches!(vote, TableValidityAttestation::Implicit(_)).then_some(id)
+	})?;
+	let group = table_context.groups.get(&para_id)?.iter().find(|g| g.contains(seconder))?;
 
-	let group = table_context.groups.get(&para_id)?;
+	let (ids, validity_votes): (Vec<_>, Vec<ValidityAttestation>) = validity_votes
+		.into_iter()
+		.filter(|(id, _)| group.contains(id))
+		.map(|(id, vote)| (id, vote.into()))
+		.unzip();
+
+	if ids.len() < effective_minimum_backing_votes(group.len(), minimum_backing_votes) {
+		gum::debug!(
+			target: LOG_TARGET,
+			?para_id,
+			"Not enough validity votes from the backing group of the seconder",
+		);
+
+		return None
+	}
 
 	let mut validator_indices = BitVec::with_capacity(group.len());
 
@@ -1050,7 +1088,7 @@ async fn construct_per_relay_parent_state<Context>(
 			if validator.as_ref().map_or(false, |v| g.contains(&v.index())) {
 				assignment = Some(core_para_id);
 			}
-			groups.insert(core_para_id, g.clone());
+			groups.entry(core_para_id).or_insert_with(Vec::new).push(g.clone());
 		}
 	}
 
@@ -1584,9 +1622,13 @@ async fn post_import_statement_actions<Context>(
 	}) {
 		let candidate_hash = attested.candidate.hash();
 
-		// `HashSet::insert` returns true if the thing wasn't in there already.
-		if rp_state.backed.insert(candidate_hash) {
-			if let Some(backed) = table_attested_to_backed(attested, &rp_state.table_context) {
+		if !rp_state.backed.contains(&candidate_hash) {
+			if let Some(backed) = table_attested_to_backed(
+				attested,
+				&rp_state.table_context,
+				rp_state.minimum_backing_votes,
+			) {
+				rp_state.backed.insert(candidate_hash);
 				let para_id = backed.candidate.descriptor.para_id;
 				gum::debug!(
 					target: LOG_TARGET,
@@ -1783,6 +1825,17 @@ async fn maybe_validate_and_import<Context>(
 		},
 	};
 
+	// With our para scheduled on multiple cores, only import and validate the candidates
+	// seconded by our own backing group.
+	if let StatementWithPVD::Seconded(receipt, _) = statement.payload() {
+		let para_id = receipt.descriptor.para_id;
+		if Some(para_id) == rp_state.assignment &&
+			!rp_state.table_context.is_in_local_group(&para_id, &statement.validator_index())
+		{
+			return Ok(())
+		}
+	}
+
 	let res = import_statement(ctx, rp_state, &mut state.per_candidate, &statement).await;
 
 	// if we get an Error::RejectedByProspectiveParachains,
@@ -2027,7 +2080,13 @@ fn handle_get_backed_candidates_message(
 					&rp_state.table_context,
 					rp_state.minimum_backing_votes,
 				)
-				.and_then(|attested| table_attested_to_backed(attested, &rp_state.table_context))
+				.and_then(|attested| {
+					table_attested_to_backed(
+						attested,
+						&rp_state.table_context,
+						rp_state.minimum_backing_votes,
+					)
+				})
 		})
 		.collect();
 
@@ -1413,8 +1413,10 @@ fn candidate_backing_reorders_votes() {
 	let validator_public = validator_pubkeys(&validators);
 	let validator_groups = {
 		let mut validator_groups = HashMap::new();
-		validator_groups
-			.insert(para_id, vec![0, 1, 2, 3, 4, 5].into_iter().map(ValidatorIndex).collect());
+		validator_groups.insert(
+			para_id,
+			vec![vec![0, 1, 2, 3, 4, 5].into_iter().map(ValidatorIndex).collect()],
+		);
 		validator_groups
 	};
 
@@ -1446,7 +1448,7 @@ fn candidate_backing_reorders_votes() {
 		group_id: para_id,
 	};
 
-	let backed = table_attested_to_backed(attested, &table_context).unwrap();
+	let backed = table_attested_to_backed(attested, &table_context, 2).unwrap();
 
 	let expected_bitvec = {
 		let mut validator_indices = BitVec::<u8, bitvec::order::Lsb0>::with_capacity(6);
@@ -1467,6 +1469,99 @@ fn candidate_backing_reorders_votes() {
 	assert_eq!(backed.validity_votes, expected_attestations);
 }
 
+#[test]
+fn candidate_backing_drops_votes_of_other_core_groups() {
+	use sp_core::Encode;
+
+	let para_id = ParaId::from(10);
+	let validators = vec![
+		Sr25519Keyring::Alice,
+		Sr25519Keyring::Bob,
+		Sr25519Keyring::Charlie,
+		Sr25519Keyring::Dave,
+		Sr25519Keyring::Ferdie,
+		Sr25519Keyring::One,
+	];
+
+	// The para is scheduled on two cores.
+	let validator_public = validator_pubkeys(&validators);
+	let validator_groups = {
+		let mut validator_groups = HashMap::new();
+		validator_groups.insert(
+			para_id,
+			vec![
+				vec![0, 1, 2].into_iter().map(ValidatorIndex).collect(),
+				vec![3, 4, 5].into_iter().map(ValidatorIndex).collect(),
+			],
+		);
+		validator_groups
+	};
+
+	let table_context = TableContext {
+		validator: None,
+		groups: validator_groups,
+		validators: validator_public.clone(),
+	};
+
+	let fake_signature = |idx: u32| {
+		let candidate =
+			dummy_candidate_receipt_bad_sig(Default::default(), Some(Default::default()));
+		let hash = candidate.hash();
+		let mut data = vec![0; 64];
+		data[0..32].copy_from_slice(hash.0.as_bytes());
+		data[32..36].copy_from_slice(idx.encode().as_slice());
+
+		ValidatorSignature::try_from(data).unwrap()
+	};
+	let attested = |votes: &[(u32, bool)]| TableAttestedCandidate {
+		candidate: dummy_committed_candidate_receipt(dummy_hash()),
+		validity_votes: votes
+			.iter()
+			.map(|&(idx, seconded)| {
+				let attestation = if seconded {
+					statement_table::generic::ValidityAttestation::Implicit(fake_signature(idx))
+				} else {
+					statement_table::generic::ValidityAttestation::Explicit(fake_signature(idx))
+				};
+				(ValidatorIndex(idx), attestation)
+			})
+			.collect(),
+		group_id: para_id,
+	};
+
+	// The vote of the other core's group doesn't prevent backing.
+	let backed =
+		table_attested_to_backed(attested(&[(1, true), (4, false), (2, false)]), &table_context, 2)
+			.unwrap();
+
+	let expected_bitvec = {
+		let mut validator_indices = BitVec::<u8, bitvec::order::Lsb0>::with_capacity(3);
+		validator_indices.resize(3, false);
+
+		validator_indices.set(1, true);
+		validator_indices.set(2, true);
+
+		validator_indices
+	};
+
+	assert_eq!(backed.validator_indices, expected_bitvec);
+	assert_eq!(
+		backed.validity_votes,
+		vec![
+			ValidityAttestation::Implicit(fake_signature(1)),
+			ValidityAttestation::Explicit(fake_signature(2)),
+		],
+	);
+
+	// Votes of the other core's group don't count towards the threshold.
+	assert!(table_attested_to_backed(
+		attested(&[(1, true), (4, false), (5, false)]),
+		&table_context,
+		2,
+	)
+	.is_none());
+}
+
 // Test whether we retry on failed PoV fetching.
 #[test]
 fn retry_works() {
@@ -35,10 +35,16 @@ use polkadot_node_subsystem::{
 	overseer, ActivatedLeaf, FromOrchestra, OverseerSignal, PerLeafSpan, SpawnedSubsystem,
 	SubsystemError, SubsystemResult, SubsystemSender,
 };
-use polkadot_node_subsystem_util::{self as util, Validator};
-use polkadot_primitives::{AvailabilityBitfield, CoreState, Hash, ValidatorIndex};
+use polkadot_node_subsystem_util::{
+	self as util, availability_chunks::availability_chunk_index, Validator,
+};
+use polkadot_primitives::{
+	vstaging::NodeFeatures, AvailabilityBitfield, CoreIndex, CoreState, Hash, SessionIndex,
+	ValidatorIndex,
+};
+use schnellru::{ByLength, LruMap};
 use sp_keystore::{Error as KeystoreError, KeystorePtr};
-use std::{collections::HashMap, iter::FromIterator, time::Duration};
+use std::{collections::HashMap, iter::FromIterator, sync::Arc, time::Duration};
 use wasm_timer::{Delay, Instant};
 
 mod metrics;
@@ -50,6 +56,8 @@ mod tests;
 /// Delay between starting a bitfield signing job and its attempting to create a bitfield.
 const SPAWNED_TASK_DELAY: Duration = Duration::from_millis(1500);
 const LOG_TARGET: &str = "parachain::bitfield-signing";
+/// Number of sessions to cache the session data of, enough to cover a session change.
+const SESSION_CACHE_SIZE: u32 = 2;
 
 // TODO: use `fatality` (https://github.com/paritytech/polkadot/issues/5540).
 /// Errors we may encounter in the course of executing the `BitfieldSigningSubsystem`.
@@ -71,15 +79,53 @@ pub enum Error {
 	#[error(transparent)]
 	Runtime(#[from] RuntimeApiError),
 
+	#[error(transparent)]
+	RuntimeInfo(#[from] util::runtime::Error),
+
+	#[error(transparent)]
+	Erasure(#[from] polkadot_erasure_coding::Error),
+
 	#[error("Keystore failed: {0:?}")]
 	Keystore(KeystoreError),
 }
 
+/// Data needed to construct bitfields, which only changes at session boundaries.
+#[derive(Clone)]
+struct SessionData {
+	n_validators: usize,
+	node_features: Option<NodeFeatures>,
+}
+
+/// Session data, shared between the jobs of all active leaves.
+type SessionCache = Arc<Mutex<LruMap<SessionIndex, SessionData>>>;
+
+/// Get the data of `session_index` from the cache, requesting it from the runtime at
+/// `relay_parent` on a miss.
+async fn get_session_data(
+	cache: &SessionCache,
+	relay_parent: Hash,
+	session_index: SessionIndex,
+	sender: &mut impl SubsystemSender<overseer::BitfieldSigningOutgoingMessages>,
+) -> Result<SessionData, Error> {
+	if let Some(session_data) = cache.lock().await.get(&session_index) {
+		return Ok(session_data.clone())
+	}
+
+	let n_validators = util::request_validators(relay_parent, sender).await.await??.len();
+	let node_features =
+		util::runtime::request_node_features(relay_parent, session_index, sender).await?;
+	let session_data = SessionData { n_validators, node_features };
+
+	cache.lock().await.insert(session_index, session_data.clone());
+
+	Ok(session_data)
+}
+
 /// If there is a candidate pending availability, query the Availability Store
-/// for whether we have the availability chunk for our validator index.
+/// for whether we have the availability chunk we are responsible for on that core.
 async fn get_core_availability(
 	core: &CoreState,
-	validator_idx: ValidatorIndex,
+	chunk_index: ValidatorIndex,
 	sender: &Mutex<&mut impl SubsystemSender<overseer::BitfieldSigningOutgoingMessages>>,
 	span: &jaeger::Span,
 ) -> Result<bool, Error> {
@@ -93,7 +139,7 @@ async fn get_core_availability(
 			.send_message(
 				AvailabilityStoreMessage::QueryChunkAvailability(
 					core.candidate_hash,
-					validator_idx,
+					chunk_index,
 					tx,
 				)
 				.into(),
@@ -143,6 +189,8 @@ async fn construct_availability_bitfield(
 	relay_parent: Hash,
 	span: &jaeger::Span,
 	validator_idx: ValidatorIndex,
+	n_validators: usize,
+	node_features: Option<&NodeFeatures>,
 	sender: &mut impl SubsystemSender<overseer::BitfieldSigningOutgoingMessages>,
 ) -> Result<AvailabilityBitfield, Error> {
 	// get the set of availability cores from the runtime
@@ -151,6 +199,18 @@ async fn construct_availability_bitfield(
 		get_availability_cores(relay_parent, sender).await?
 	};
 
+	// The chunk we hold for a candidate depends on the core it occupies.
+	let chunk_indices = (0..availability_cores.len())
+		.map(|core_index| {
+			availability_chunk_index(
+				node_features,
+				n_validators,
+				CoreIndex(core_index as u32),
+				validator_idx,
+			)
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+
 	// Wrap the sender in a Mutex to share it between the futures.
 	//
 	// We use a `Mutex` here to not `clone` the sender inside the future, because
@@ -163,7 +223,8 @@ async fn construct_availability_bitfield(
 	let results = future::try_join_all(
 		availability_cores
 			.iter()
-			.map(|core| get_core_availability(core, validator_idx, &sender, span)),
+			.zip(chunk_indices)
+			.map(|(core, chunk_index)| get_core_availability(core, chunk_index, &sender, span)),
 	)
 	.await?;
 
@@ -214,6 +275,8 @@ async fn run<Context>(
 ) -> SubsystemResult<()> {
 	// Track spawned jobs per active leaf.
 	let mut running = HashMap::<Hash, future::AbortHandle>::new();
+	let session_cache: SessionCache =
+		Arc::new(Mutex::new(LruMap::new(ByLength::new(SESSION_CACHE_SIZE))));
 
 	loop {
 		match ctx.recv().await? {
@@ -233,6 +296,7 @@ async fn run<Context>(
 						sender,
 						leaf,
 						keystore.clone(),
+						session_cache.clone(),
 						metrics.clone(),
 					));
 
@@ -252,6 +316,7 @@ async fn handle_active_leaves_update<Sender>(
 	mut sender: Sender,
 	leaf: ActivatedLeaf,
 	keystore: KeystorePtr,
+	session_cache: SessionCache,
 	metrics: Metrics,
 ) -> Result<(), Error>
 where
@@ -279,10 +344,20 @@ where
 	drop(span_delay);
 	let span_availability = span.child("availability");
 
+	let session_data = get_session_data(
+		&session_cache,
+		leaf.hash,
+		validator.signing_context().session_index,
+		&mut sender,
+	)
+	.await?;
+
 	let bitfield = match construct_availability_bitfield(
 		leaf.hash,
 		&span_availability,
 		validator.index(),
+		session_data.n_validators,
+		session_data.node_features.as_ref(),
 		&mut sender,
 	)
 	.await
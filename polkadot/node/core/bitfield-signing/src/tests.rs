@@ -18,7 +18,7 @@ use super::*;
 use futures::{executor::block_on, pin_mut, StreamExt};
 use polkadot_node_subsystem::messages::AllMessages;
 use polkadot_primitives::{CandidateHash, OccupiedCore};
-use test_helpers::dummy_candidate_descriptor;
+use test_helpers::{dummy_candidate_descriptor, dummy_validator};
 
 fn occupied_core(para_id: u32, candidate_hash: CandidateHash) -> CoreState {
 	CoreState::Occupied(OccupiedCore {
@@ -44,6 +44,8 @@ fn construct_availability_bitfield_works() {
 			relay_parent,
 			&jaeger::Span::Disabled,
 			validator_index,
+			5,
+			None,
 			&mut sender,
 		)
 		.fuse();
@@ -83,3 +85,124 @@ fn construct_availability_bitfield_works() {
 		}
 	});
 }
+
+#[test]
+fn construct_availability_bitfield_queries_mapped_chunk_index() {
+	block_on(async move {
+		let relay_parent = Hash::default();
+		let validator_index = ValidatorIndex(1u32);
+		let n_validators = 5;
+		let node_features = NodeFeatures::repeat(
+			true,
+			polkadot_primitives::vstaging::node_features::FeatureIndex::AvailabilityChunkMapping
+				as usize + 1,
+		);
+
+		let (mut sender, mut receiver) = polkadot_node_subsystem_test_helpers::sender_receiver();
+		let future = construct_availability_bitfield(
+			relay_parent,
+			&jaeger::Span::Disabled,
+			validator_index,
+			n_validators,
+			Some(&node_features),
+			&mut sender,
+		)
+		.fuse();
+		pin_mut!(future);
+
+		let hash_a = CandidateHash(Hash::repeat_byte(1));
+		let hash_b = CandidateHash(Hash::repeat_byte(2));
+
+		let chunk_index = |core_index| {
+			availability_chunk_index(
+				Some(&node_features),
+				n_validators,
+				CoreIndex(core_index),
+				validator_index,
+			)
+			.unwrap()
+		};
+
+		loop {
+			futures::select! {
+				m = receiver.next() => match m.unwrap() {
+					AllMessages::RuntimeApi(
+						RuntimeApiMessage::Request(_, RuntimeApiRequest::AvailabilityCores(tx)),
+					) => {
+						tx.send(Ok(vec![CoreState::Free, occupied_core(1, hash_a), occupied_core(2, hash_b)])).unwrap();
+					}
+					AllMessages::AvailabilityStore(
+						AvailabilityStoreMessage::QueryChunkAvailability(c_hash, chunk_idx, tx),
+					) => {
+						if c_hash == hash_a {
+							assert_eq!(chunk_idx, chunk_index(1));
+						} else {
+							assert_eq!(chunk_idx, chunk_index(2));
+						}
+
+						tx.send(true).unwrap();
+					},
+					o => panic!("Unknown message: {:?}", o),
+				},
+				r = future => match r {
+					Ok(r) => {
+						assert!(!r.0.get(0).unwrap());
+						assert!(r.0.get(1).unwrap());
+						assert!(r.0.get(2).unwrap());
+						break
+					},
+					Err(e) => panic!("Failed: {:?}", e),
+				},
+			}
+		}
+	});
+}
+
+#[test]
+fn session_data_is_requested_once_per_session() {
+	block_on(async move {
+		let relay_parent = Hash::default();
+		let session_index = 1;
+		let cache: SessionCache = Arc::new(Mutex::new(LruMap::new(ByLength::new(1))));
+
+		let (mut sender, mut receiver) = polkadot_node_subsystem_test_helpers::sender_receiver();
+
+		{
+			let future = get_session_data(&cache, relay_parent, session_index, &mut sender).fuse();
+			pin_mut!(future);
+
+			loop {
+				futures::select! {
+					m = receiver.next() => match m.unwrap() {
+						AllMessages::RuntimeApi(
+							RuntimeApiMessage::Request(rp, RuntimeApiRequest::Validators(tx)),
+						) => {
+							assert_eq!(relay_parent, rp);
+							tx.send(Ok(vec![dummy_validator(); 5])).unwrap();
+						},
+						AllMessages::RuntimeApi(
+							RuntimeApiMessage::Request(rp, RuntimeApiRequest::NodeFeatures(s, tx)),
+						) => {
+							assert_eq!(relay_parent, rp);
+							assert_eq!(session_index, s);
+							tx.send(Ok(NodeFeatures::EMPTY)).unwrap();
+						},
+						o => panic!("Unknown message: {:?}", o),
+					},
+					r = future => {
+						assert_eq!(r.unwrap().n_validators, 5);
+						break
+					},
+				}
+			}
+		}
+
+		// Cached for the rest of the session.
+		let session_data = get_session_data(&cache, relay_parent, session_index, &mut sender)
+			.await
+			.unwrap();
+		assert_eq!(session_data.n_validators, 5);
+		assert_eq!(session_data.node_features, Some(NodeFeatures::EMPTY));
+		assert!(receiver.next().now_or_never().is_none());
+	});
+}
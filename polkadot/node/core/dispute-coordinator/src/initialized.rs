@@ -1170,6 +1170,7 @@ impl Initialized {
 						new_state.candidate_receipt().clone(),
 						session,
 						env.executor_params().clone(),
+						self.scraper.get_included_candidate_core(&candidate_hash),
 						request_timer,
 					),
 				)
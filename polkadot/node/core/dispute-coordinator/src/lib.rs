@@ -400,6 +400,7 @@ impl DisputeCoordinatorSubsystem {
 							vote_state.votes().candidate_receipt.clone(),
 							session,
 							env.executor_params().clone(),
+							scraper.get_included_candidate_core(candidate_hash),
 							request_timer,
 						),
 					));
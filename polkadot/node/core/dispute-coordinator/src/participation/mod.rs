@@ -305,6 +305,7 @@ async fn participate(
 			req.candidate_receipt().clone(),
 			req.session(),
 			None,
+			req.core_index(),
 			recover_available_data_tx,
 		))
 		.await;
@@ -22,7 +22,7 @@ use std::{
 use futures::channel::oneshot;
 use polkadot_node_subsystem::{messages::ChainApiMessage, overseer};
 use polkadot_primitives::{
-	BlockNumber, CandidateHash, CandidateReceipt, ExecutorParams, Hash, SessionIndex,
+	BlockNumber, CandidateHash, CandidateReceipt, CoreIndex, ExecutorParams, Hash, SessionIndex,
 };
 
 use crate::{
@@ -76,6 +76,8 @@ pub struct ParticipationRequest {
 	candidate_receipt: CandidateReceipt,
 	session: SessionIndex,
 	executor_params: ExecutorParams,
+	/// The core the candidate was occupying, if it was seen included.
+	core_index: Option<CoreIndex>,
 	request_timer: Option<prometheus::HistogramTimer>, // Sends metric data when request is dropped
 }
 
@@ -124,6 +126,7 @@ impl ParticipationRequest {
 		candidate_receipt: CandidateReceipt,
 		session: SessionIndex,
 		executor_params: ExecutorParams,
+		core_index: Option<CoreIndex>,
 		request_timer: Option<prometheus::HistogramTimer>,
 	) -> Self {
 		Self {
@@ -131,6 +134,7 @@ impl ParticipationRequest {
 			candidate_receipt,
 			session,
 			executor_params,
+			core_index,
 			request_timer,
 		}
 	}
@@ -147,6 +151,9 @@ impl ParticipationRequest {
 	pub fn executor_params(&self) -> ExecutorParams {
 		self.executor_params.clone()
 	}
+	pub fn core_index(&self) -> Option<CoreIndex> {
+		self.core_index
+	}
 	pub fn discard_timer(&mut self) {
 		if let Some(timer) = self.request_timer.take() {
 			timer.stop_and_discard();
@@ -168,12 +175,14 @@ impl PartialEq for ParticipationRequest {
 			candidate_hash,
 			session,
 			executor_params,
+			core_index,
 			request_timer: _,
 		} = self;
 		candidate_receipt == other.candidate_receipt() &&
 			candidate_hash == other.candidate_hash() &&
 			*session == other.session() &&
-			executor_params.hash() == other.executor_params.hash()
+			executor_params.hash() == other.executor_params.hash() &&
+			*core_index == other.core_index()
 	}
 }
 #[cfg(test)]
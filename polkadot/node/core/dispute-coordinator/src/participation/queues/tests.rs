@@ -27,7 +27,7 @@ fn make_participation_request(hash: Hash) -> ParticipationRequest {
 	// make it differ:
 	receipt.commitments_hash = hash;
 	let request_timer = Metrics::default().time_participation_pipeline();
-	ParticipationRequest::new(receipt, 1, Default::default(), request_timer)
+	ParticipationRequest::new(receipt, 1, Default::default(), None, request_timer)
 }
 
 /// Make dummy comparator for request, based on the given block number.
@@ -38,7 +38,8 @@ use polkadot_node_subsystem_test_helpers::{
 	make_subsystem_context, mock::new_leaf, TestSubsystemContext, TestSubsystemContextHandle,
 };
 use polkadot_primitives::{
-	BlakeTwo256, CandidateCommitments, HashT, Header, PersistedValidationData, ValidationCode,
+	BlakeTwo256, CandidateCommitments, CoreIndex, HashT, Header, PersistedValidationData,
+	ValidationCode,
 };
 
 type VirtualOverseer = TestSubsystemContextHandle<DisputeCoordinatorMessage>;
@@ -72,8 +73,13 @@ async fn participate_with_commitments_hash<Context>(
 	let session = 1;
 
 	let request_timer = participation.metrics.time_participation_pipeline();
-	let req =
-		ParticipationRequest::new(candidate_receipt, session, Default::default(), request_timer);
+	let req = ParticipationRequest::new(
+		candidate_receipt,
+		session,
+		Default::default(),
+		None,
+		request_timer,
+	);
 
 	participation
 		.queue_participation(ctx, ParticipationPriority::BestEffort, req)
@@ -132,7 +138,7 @@ pub async fn participation_missing_availability(ctx_handle: &mut VirtualOverseer
 	assert_matches!(
 		ctx_handle.recv().await,
 		AllMessages::AvailabilityRecovery(
-			AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, tx)
+			AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, _, tx)
 		) => {
 			tx.send(Err(RecoveryError::Unavailable)).unwrap();
 		},
@@ -151,7 +157,7 @@ async fn recover_available_data(virtual_overseer: &mut VirtualOverseer) {
 	assert_matches!(
 		virtual_overseer.recv().await,
 		AllMessages::AvailabilityRecovery(
-			AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, tx)
+			AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, _, tx)
 		) => {
 			tx.send(Ok(available_data)).unwrap();
 		},
@@ -195,7 +201,7 @@ fn same_req_wont_get_queued_if_participation_is_already_running() {
 		assert_matches!(
 			ctx_handle.recv().await,
 			AllMessages::AvailabilityRecovery(
-				AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, tx)
+				AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, _, tx)
 			) => {
 				tx.send(Err(RecoveryError::Unavailable)).unwrap();
 			},
@@ -218,6 +224,41 @@ fn same_req_wont_get_queued_if_participation_is_already_running() {
 	})
 }
 
+#[test]
+fn recovery_is_requested_with_the_core_of_the_included_candidate() {
+	futures::executor::block_on(async {
+		let (mut ctx, mut ctx_handle) = make_our_subsystem_context(TaskExecutor::new());
+
+		let (sender, _worker_receiver) = mpsc::channel(1);
+		let mut participation = Participation::new(sender, Metrics::default());
+		activate_leaf(&mut ctx, &mut participation, 10).await.unwrap();
+
+		let candidate_receipt = dummy_candidate_receipt_bad_sig(dummy_hash(), dummy_hash());
+		let req = ParticipationRequest::new(
+			candidate_receipt,
+			1,
+			Default::default(),
+			Some(CoreIndex(3)),
+			None,
+		);
+		participation
+			.queue_participation(&mut ctx, ParticipationPriority::BestEffort, req)
+			.await
+			.unwrap();
+
+		assert_matches!(
+			ctx_handle.recv().await,
+			AllMessages::AvailabilityRecovery(
+				AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, core_index, tx)
+			) => {
+				assert_eq!(core_index, Some(CoreIndex(3)));
+				tx.send(Err(RecoveryError::Unavailable)).unwrap();
+			},
+			"overseer did not receive recover available data message",
+		);
+	})
+}
+
 #[test]
 fn reqs_get_queued_when_out_of_capacity() {
 	let (mut ctx, mut ctx_handle) = make_our_subsystem_context(TaskExecutor::new());
@@ -260,7 +301,7 @@ fn reqs_get_queued_when_out_of_capacity() {
 		{
 			match ctx_handle.recv().await {
 				AllMessages::AvailabilityRecovery(
-					AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, tx),
+					AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, _, tx),
 				) => {
 					tx.send(Err(RecoveryError::Unavailable)).unwrap();
 					recover_available_data_msg_count += 1;
@@ -346,7 +387,7 @@ fn cannot_participate_if_cannot_recover_available_data() {
 		assert_matches!(
 			ctx_handle.recv().await,
 			AllMessages::AvailabilityRecovery(
-				AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, tx)
+				AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, _, tx)
 			) => {
 				tx.send(Err(RecoveryError::Unavailable)).unwrap();
 			},
@@ -413,7 +454,7 @@ fn cast_invalid_vote_if_available_data_is_invalid() {
 		assert_matches!(
 			ctx_handle.recv().await,
 			AllMessages::AvailabilityRecovery(
-				AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, tx)
+				AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, _, tx)
 			) => {
 				tx.send(Err(RecoveryError::Invalid)).unwrap();
 			},
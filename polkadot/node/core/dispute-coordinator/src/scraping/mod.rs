@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::{btree_map::Entry, BTreeMap, HashSet};
+use std::collections::{btree_map::Entry, BTreeMap, HashMap, HashSet};
 
 use futures::channel::oneshot;
 use schnellru::{ByLength, LruMap};
@@ -28,8 +28,8 @@ use polkadot_node_subsystem_util::runtime::{
 	self, get_candidate_events, get_on_chain_votes, get_unapplied_slashes,
 };
 use polkadot_primitives::{
-	slashing::PendingSlashes, BlockNumber, CandidateEvent, CandidateHash, CandidateReceipt, Hash,
-	ScrapedOnChainVotes, SessionIndex,
+	slashing::PendingSlashes, BlockNumber, CandidateEvent, CandidateHash, CandidateReceipt,
+	CoreIndex, Hash, ScrapedOnChainVotes, SessionIndex,
 };
 
 use crate::{
@@ -187,6 +187,11 @@ pub struct ChainScraper {
 	/// candidate.
 	inclusions: Inclusions,
 
+	/// The cores included candidates were occupying, as reported by their inclusion events.
+	///
+	/// Pruned along with `inclusions`.
+	included_cores: HashMap<CandidateHash, CoreIndex>,
+
 	/// Latest relay blocks observed by the provider.
 	///
 	/// This is used to avoid redundant scraping of ancestry. We assume that ancestors of cached
@@ -215,6 +220,7 @@ impl ChainScraper {
 		let mut s = Self {
 			backed_candidates: candidates::ScrapedCandidates::new(),
 			inclusions: Inclusions::new(),
+			included_cores: HashMap::new(),
 			last_observed_blocks: LruMap::new(ByLength::new(LRU_OBSERVED_BLOCKS_CAPACITY)),
 		};
 		let update =
@@ -228,6 +234,11 @@ impl ChainScraper {
 		self.inclusions.contains(candidate_hash)
 	}
 
+	/// Get the core an included candidate was occupying, if we have seen it included.
+	pub fn get_included_candidate_core(&self, candidate_hash: &CandidateHash) -> Option<CoreIndex> {
+		self.included_cores.get(candidate_hash).copied()
+	}
+
 	/// Check whether the candidate is backed
 	pub fn is_candidate_backed(&self, candidate_hash: &CandidateHash) -> bool {
 		self.backed_candidates.contains(candidate_hash)
@@ -317,6 +328,9 @@ impl ChainScraper {
 			Some(key_to_prune) => {
 				self.backed_candidates.remove_up_to_height(&key_to_prune);
 				self.inclusions.remove_up_to_height(&key_to_prune);
+				let inclusions = &self.inclusions;
+				self.included_cores
+					.retain(|candidate_hash, _| inclusions.contains(candidate_hash));
 			},
 			None => {
 				// Nothing to prune. We are still in the beginning of the chain and there are not
@@ -345,7 +359,7 @@ impl ChainScraper {
 		// Get included and backed events:
 		for ev in events {
 			match ev {
-				CandidateEvent::CandidateIncluded(receipt, _, _, _) => {
+				CandidateEvent::CandidateIncluded(receipt, _, core_index, _) => {
 					let candidate_hash = receipt.hash();
 					gum::trace!(
 						target: LOG_TARGET,
@@ -354,6 +368,7 @@ impl ChainScraper {
 						"Processing included event"
 					);
 					self.inclusions.insert(candidate_hash, block_number, block_hash);
+					self.included_cores.insert(candidate_hash, core_index);
 					included_receipts.push(receipt);
 				},
 				CandidateEvent::CandidateBacked(receipt, _, _, _) => {
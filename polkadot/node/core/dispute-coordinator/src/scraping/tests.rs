@@ -314,6 +314,7 @@ fn scraper_provides_included_state_when_initialized() {
 
 		assert!(!scraper.is_candidate_included(&candidate_2.hash()));
 		assert!(!scraper.is_candidate_backed(&candidate_2.hash()));
+		assert_eq!(scraper.get_included_candidate_core(&candidate_2.hash()), None);
 		assert!(scraper.is_candidate_included(&candidate_1.hash()));
 		assert!(scraper.is_candidate_backed(&candidate_1.hash()));
 
@@ -334,6 +335,7 @@ fn scraper_provides_included_state_when_initialized() {
 
 		assert!(scraper.is_candidate_included(&candidate_2.hash()));
 		assert!(scraper.is_candidate_backed(&candidate_2.hash()));
+		assert_eq!(scraper.get_included_candidate_core(&candidate_2.hash()), Some(CoreIndex(0)));
 	});
 }
 
@@ -467,6 +469,7 @@ fn scraper_prunes_finalized_candidates() {
 			.await;
 
 		let candidate = make_candidate_receipt(get_block_number_hash(TEST_TARGET_BLOCK_NUMBER));
+		assert_eq!(scraper.get_included_candidate_core(&candidate.hash()), Some(CoreIndex(0)));
 
 		// After `DISPUTE_CANDIDATE_LIFETIME_AFTER_FINALIZATION` blocks the candidate should be
 		// removed
@@ -476,6 +479,7 @@ fn scraper_prunes_finalized_candidates() {
 
 		assert!(!scraper.is_candidate_backed(&candidate.hash()));
 		assert!(!scraper.is_candidate_included(&candidate.hash()));
+		assert_eq!(scraper.get_included_candidate_core(&candidate.hash()), None);
 	});
 }
 
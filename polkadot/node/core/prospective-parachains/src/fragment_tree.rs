@@ -753,53 +753,75 @@ impl FragmentTree {
 		depths.iter_ones().collect()
 	}
 
-	/// Select a candidate after the given `required_path` which passes
-	/// the predicate.
+	/// Select `count` candidates after the given `ancestors` which pass
+	/// the predicate, each building on the previous one.
 	///
-	/// If there are multiple possibilities, this will select the first one.
+	/// The `ancestors` are unordered: they must form a path starting at the root of the tree,
+	/// which is reconstructed here. If they don't, no candidates are returned.
 	///
-	/// This returns `None` if there is no candidate meeting those criteria.
+	/// If there are multiple possibilities at any step, this will select the first one.
 	///
-	/// The intention of the `required_path` is to allow queries on the basis of
-	/// one or more candidates which were previously pending availability becoming
-	/// available and opening up more room on the core.
-	pub(crate) fn select_child(
+	/// This returns fewer than `count` candidates, possibly none, if the chain of
+	/// candidates meeting those criteria is shorter than requested.
+	///
+	/// The intention of the `ancestors` is to allow queries on the basis of
+	/// one or more candidates which are pending availability, or were previously
+	/// pending availability and became available, opening up more room on the cores.
+	pub(crate) fn select_children(
 		&self,
-		required_path: &[CandidateHash],
+		ancestors: &HashSet<CandidateHash>,
+		count: u32,
 		pred: impl Fn(&CandidateHash) -> bool,
-	) -> Option<CandidateHash> {
+	) -> Vec<CandidateHash> {
 		let base_node = {
-			// traverse the required path.
+			// traverse the ancestors, in the order in which they build on each other.
 			let mut node = NodePointer::Root;
-			for required_step in required_path {
-				node = self.node_candidate_child(node, &required_step)?;
+			for _ in 0..ancestors.len() {
+				let next =
+					ancestors.iter().find_map(|ancestor| self.node_candidate_child(node, ancestor));
+				match next {
+					Some(next_node) => node = next_node,
+					None => return Vec::new(),
+				}
 			}
 
 			node
 		};
 
-		// TODO [now]: taking the first selection might introduce bias
-		// or become gameable.
-		//
-		// For plausibly unique parachains, this shouldn't matter much.
-		// figure out alternative selection criteria?
-		match base_node {
-			NodePointer::Root => self
-				.nodes
-				.iter()
-				.take_while(|n| n.parent == NodePointer::Root)
-				.filter(|n| self.scope.get_pending_availability(&n.candidate_hash).is_none())
-				.filter(|n| pred(&n.candidate_hash))
-				.map(|n| n.candidate_hash)
-				.next(),
-			NodePointer::Storage(ptr) => self.nodes[ptr]
-				.children
-				.iter()
-				.filter(|n| self.scope.get_pending_availability(&n.1).is_none())
-				.filter(|n| pred(&n.1))
-				.map(|n| n.1)
-				.next(),
+		let mut selected = Vec::with_capacity(count as usize);
+		let mut node = base_node;
+		while selected.len() < count as usize {
+			// TODO [now]: taking the first selection might introduce bias
+			// or become gameable.
+			//
+			// For plausibly unique parachains, this shouldn't matter much.
+			// figure out alternative selection criteria?
+			let next = match node {
+				NodePointer::Root => self
+					.nodes
+					.iter()
+					.take_while(|n| n.parent == NodePointer::Root)
+					.filter(|n| self.scope.get_pending_availability(&n.candidate_hash).is_none())
+					.filter(|n| pred(&n.candidate_hash))
+					.map(|n| n.candidate_hash)
+					.next(),
+				NodePointer::Storage(ptr) => self.nodes[ptr]
+					.children
+					.iter()
+					.filter(|n| self.scope.get_pending_availability(&n.1).is_none())
+					.filter(|n| pred(&n.1))
+					.map(|n| n.1)
+					.next(),
+			};
+
+			let Some(candidate_hash) = next else { break };
+			let Some(next_node) = self.node_candidate_child(node, &candidate_hash) else { break };
+
+			selected.push(candidate_hash);
+			node = next_node;
 		}
+
+		selected
 	}
 
 	fn populate_from_bases(&mut self, storage: &CandidateStorage, initial_bases: Vec<NodePointer>) {
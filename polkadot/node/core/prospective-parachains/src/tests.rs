@@ -404,20 +404,22 @@ async fn get_membership(
 	assert_eq!(resp, expected_membership_response);
 }
 
-async fn get_backable_candidate(
+async fn get_backable_candidates(
 	virtual_overseer: &mut VirtualOverseer,
 	leaf: &TestLeaf,
 	para_id: ParaId,
-	required_path: Vec<CandidateHash>,
-	expected_result: Option<(CandidateHash, Hash)>,
+	count: u32,
+	ancestors: Vec<CandidateHash>,
+	expected_result: Vec<(CandidateHash, Hash)>,
 ) {
 	let (tx, rx) = oneshot::channel();
 	virtual_overseer
 		.send(overseer::FromOrchestra::Communication {
-			msg: ProspectiveParachainsMessage::GetBackableCandidate(
+			msg: ProspectiveParachainsMessage::GetBackableCandidates(
 				leaf.hash,
 				para_id,
-				required_path,
+				count,
+				ancestors.into_iter().collect(),
 				tx,
 			),
 		})
@@ -850,7 +852,7 @@ fn check_candidate_on_multiple_forks() {
 	assert_eq!(view.candidate_storage.get(&2.into()).unwrap().len(), (0, 0));
 }
 
-// Backs some candidates and tests `GetBackableCandidate`.
+// Backs some candidates and tests `GetBackableCandidates`.
 #[test]
 fn check_backable_query() {
 	let test_state = TestState::default();
@@ -897,12 +899,13 @@ fn check_backable_query() {
 		introduce_candidate(&mut virtual_overseer, candidate_b.clone(), pvd_b).await;
 
 		// Should not get any backable candidates.
-		get_backable_candidate(
+		get_backable_candidates(
 			&mut virtual_overseer,
 			&leaf_a,
 			1.into(),
+			1,
 			vec![candidate_hash_a],
-			None,
+			vec![],
 		)
 		.await;
 
@@ -911,12 +914,13 @@ fn check_backable_query() {
 		second_candidate(&mut virtual_overseer, candidate_b.clone()).await;
 
 		// Should not get any backable candidates.
-		get_backable_candidate(
+		get_backable_candidates(
 			&mut virtual_overseer,
 			&leaf_a,
 			1.into(),
+			1,
 			vec![candidate_hash_a],
-			None,
+			vec![],
 		)
 		.await;
 
@@ -925,30 +929,55 @@ fn check_backable_query() {
 		back_candidate(&mut virtual_overseer, &candidate_b, candidate_hash_b).await;
 
 		// Get backable candidate.
-		get_backable_candidate(
+		get_backable_candidates(
 			&mut virtual_overseer,
 			&leaf_a,
 			1.into(),
+			1,
 			vec![],
-			Some((candidate_hash_a, leaf_a.hash)),
+			vec![(candidate_hash_a, leaf_a.hash)],
 		)
 		.await;
-		get_backable_candidate(
+		get_backable_candidates(
 			&mut virtual_overseer,
 			&leaf_a,
 			1.into(),
+			1,
 			vec![candidate_hash_a],
-			Some((candidate_hash_b, leaf_a.hash)),
+			vec![(candidate_hash_b, leaf_a.hash)],
 		)
 		.await;
 
 		// Should not get anything at the wrong path.
-		get_backable_candidate(
+		get_backable_candidates(
 			&mut virtual_overseer,
 			&leaf_a,
 			1.into(),
+			1,
 			vec![candidate_hash_b],
-			None,
+			vec![],
+		)
+		.await;
+
+		// Get a chain of backable candidates.
+		get_backable_candidates(
+			&mut virtual_overseer,
+			&leaf_a,
+			1.into(),
+			2,
+			vec![],
+			vec![(candidate_hash_a, leaf_a.hash), (candidate_hash_b, leaf_a.hash)],
+		)
+		.await;
+
+		// Asking for more candidates than available returns the whole chain.
+		get_backable_candidates(
+			&mut virtual_overseer,
+			&leaf_a,
+			1.into(),
+			5,
+			vec![],
+			vec![(candidate_hash_a, leaf_a.hash), (candidate_hash_b, leaf_a.hash)],
 		)
 		.await;
 
@@ -1449,12 +1478,13 @@ fn persists_pending_availability_candidate() {
 		second_candidate(&mut virtual_overseer, candidate_b.clone()).await;
 		back_candidate(&mut virtual_overseer, &candidate_b, candidate_hash_b).await;
 
-		get_backable_candidate(
+		get_backable_candidates(
 			&mut virtual_overseer,
 			&leaf_b,
 			para_id,
+			1,
 			vec![candidate_hash_a],
-			Some((candidate_hash_b, leaf_b_hash)),
+			vec![(candidate_hash_b, leaf_b_hash)],
 		)
 		.await;
 
@@ -1513,12 +1543,13 @@ fn backwards_compatible() {
 		second_candidate(&mut virtual_overseer, candidate_a.clone()).await;
 		back_candidate(&mut virtual_overseer, &candidate_a, candidate_hash_a).await;
 
-		get_backable_candidate(
+		get_backable_candidates(
 			&mut virtual_overseer,
 			&leaf_a,
 			para_id,
+			1,
 			vec![],
-			Some((candidate_hash_a, candidate_relay_parent)),
+			vec![(candidate_hash_a, candidate_relay_parent)],
 		)
 		.await;
 
@@ -1538,7 +1569,7 @@ fn backwards_compatible() {
 		)
 		.await;
 
-		get_backable_candidate(&mut virtual_overseer, &leaf_b, para_id, vec![], None).await;
+		get_backable_candidates(&mut virtual_overseer, &leaf_b, para_id, 1, vec![], vec![]).await;
 
 		virtual_overseer
 	});
@@ -43,7 +43,7 @@ use polkadot_primitives::{
 	BackedCandidate, BlockNumber, CandidateHash, CandidateReceipt, CoreState, Hash, Id as ParaId,
 	OccupiedCoreAssumption, SignedAvailabilityBitfield, ValidatorIndex,
 };
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 mod disputes;
 mod error;
@@ -643,14 +643,16 @@ async fn request_backable_candidates(
 ) -> Result<Vec<(CandidateHash, Hash)>, Error> {
 	let block_number = get_block_number_under_construction(relay_parent, sender).await?;
 
-	let mut selected_candidates = Vec::with_capacity(availability_cores.len());
+	// Per para, the number of cores which can be backed on and the candidates pending
+	// availability that new candidates need to build on.
+	let mut requests: BTreeMap<ParaId, (u32, HashSet<CandidateHash>)> = BTreeMap::new();
 
 	for (core_idx, core) in availability_cores.iter().enumerate() {
-		let (para_id, required_path) = match core {
+		let para_id = match core {
 			CoreState::Scheduled(scheduled_core) => {
 				// The core is free, pick the first eligible candidate from
 				// the fragment tree.
-				(scheduled_core.para_id, Vec::new())
+				scheduled_core.para_id
 			},
 			CoreState::Occupied(occupied_core) => {
 				if bitfields_indicate_availability(core_idx, bitfields, &occupied_core.availability)
@@ -662,17 +664,23 @@ async fn request_backable_candidates(
 						// TODO: doesn't work for on-demand parachains. We lean hard on the
 						// assumption that cores are fixed to specific parachains within a session.
 						// https://github.com/paritytech/polkadot/issues/5492
-						(scheduled_core.para_id, vec![occupied_core.candidate_hash])
+						let (_, ancestors) = requests.entry(scheduled_core.para_id).or_default();
+						ancestors.insert(occupied_core.candidate_hash);
+						scheduled_core.para_id
 					} else {
 						continue
 					}
 				} else {
 					if occupied_core.time_out_at != block_number {
+						// The candidate stays pending availability, so anything backed
+						// for the same para on another core has to build on top of it.
+						let (_, ancestors) = requests.entry(occupied_core.para_id()).or_default();
+						ancestors.insert(occupied_core.candidate_hash);
 						continue
 					}
 					if let Some(ref scheduled_core) = occupied_core.next_up_on_time_out {
 						// Candidate's availability timed out, practically same as scheduled.
-						(scheduled_core.para_id, Vec::new())
+						scheduled_core.para_id
 					} else {
 						continue
 					}
@@ -681,19 +689,30 @@ async fn request_backable_candidates(
 			CoreState::Free => continue,
 		};
 
-		let response = get_backable_candidate(relay_parent, para_id, required_path, sender).await?;
+		let (count, _) = requests.entry(para_id).or_default();
+		*count += 1;
+	}
 
-		match response {
-			Some((hash, relay_parent)) => selected_candidates.push((hash, relay_parent)),
-			None => {
-				gum::debug!(
-					target: LOG_TARGET,
-					leaf_hash = ?relay_parent,
-					core = core_idx,
-					"No backable candidate returned by prospective parachains",
-				);
-			},
+	let mut selected_candidates = Vec::with_capacity(availability_cores.len());
+
+	for (para_id, (count, ancestors)) in requests {
+		if count == 0 {
+			continue
+		}
+
+		let response =
+			get_backable_candidates(relay_parent, para_id, count, ancestors, sender).await?;
+
+		if response.is_empty() {
+			gum::debug!(
+				target: LOG_TARGET,
+				leaf_hash = ?relay_parent,
+				?para_id,
+				"No backable candidate returned by prospective parachains",
+			);
 		}
+
+		selected_candidates.extend(response);
 	}
 
 	Ok(selected_candidates)
@@ -737,8 +756,9 @@ async fn select_candidates(
 	gum::trace!(target: LOG_TARGET, leaf_hash=?relay_parent,
 				"Got {} backed candidates", candidates.len());
 
-	// `selected_candidates` is generated in ascending order by core index, and
-	// `GetBackedCandidates` _should_ preserve that property, but let's just make sure.
+	// `selected_candidates` is generated in a deterministic order (by core index, or by para
+	// and then chain position), and `GetBackedCandidates` _should_ preserve that order, but
+	// let's just make sure.
 	//
 	// We can't easily map from `BackedCandidate` to `core_idx`, but we know that every selected
 	// candidate maps to either 0 or 1 backed candidate, and the hashes correspond. Therefore, by
@@ -796,20 +816,22 @@ async fn get_block_number_under_construction(
 	}
 }
 
-/// Requests backable candidate from Prospective Parachains based on
-/// the given path in the fragment tree.
-async fn get_backable_candidate(
+/// Requests up to `count` backable candidates from Prospective Parachains, building
+/// on the given ancestors in the fragment tree.
+async fn get_backable_candidates(
 	relay_parent: Hash,
 	para_id: ParaId,
-	required_path: Vec<CandidateHash>,
+	count: u32,
+	ancestors: HashSet<CandidateHash>,
 	sender: &mut impl overseer::ProvisionerSenderTrait,
-) -> Result<Option<(CandidateHash, Hash)>, Error> {
+) -> Result<Vec<(CandidateHash, Hash)>, Error> {
 	let (tx, rx) = oneshot::channel();
 	sender
-		.send_message(ProspectiveParachainsMessage::GetBackableCandidate(
+		.send_message(ProspectiveParachainsMessage::GetBackableCandidates(
 			relay_parent,
 			para_id,
-			required_path,
+			count,
+			ancestors,
 			tx,
 		))
 		.await;
@@ -373,10 +373,10 @@ mod select_candidates {
 					let _ = sender.send(response);
 				},
 				AllMessages::ProspectiveParachains(
-					ProspectiveParachainsMessage::GetBackableCandidate(.., tx),
+					ProspectiveParachainsMessage::GetBackableCandidates(_, _, count, _, tx),
 				) => match prospective_parachains_mode {
 					ProspectiveParachainsMode::Enabled { .. } => {
-						let _ = tx.send(candidates_iter.next());
+						let _ = tx.send(candidates_iter.by_ref().take(count as usize).collect());
 					},
 					ProspectiveParachainsMode::Disabled =>
 						panic!("unexpected prospective parachains request"),
@@ -700,4 +700,80 @@ mod select_candidates {
 			},
 		)
 	}
+
+	#[test]
+	fn request_multiple_candidates_for_para_on_multiple_cores() {
+		let para_id: ParaId = 1.into();
+		let pending_hash = CandidateHash(Hash::repeat_byte(1));
+		let timed_out_hash = CandidateHash(Hash::repeat_byte(2));
+
+		let mut pending_descriptor = dummy_candidate_descriptor(dummy_hash());
+		pending_descriptor.para_id = para_id;
+
+		let mock_cores = vec![
+			CoreState::Scheduled(scheduled_core(1)),
+			CoreState::Scheduled(scheduled_core(1)),
+			// Still pending availability, new candidates must build on it.
+			build_occupied_core(1, |core| {
+				core.candidate_descriptor = pending_descriptor.clone();
+				core.candidate_hash = pending_hash;
+			}),
+			// Timed out, so the core is free and the candidate is not an ancestor.
+			build_occupied_core(1, |core| {
+				core.candidate_descriptor = pending_descriptor.clone();
+				core.candidate_hash = timed_out_hash;
+				core.next_up_on_time_out = Some(scheduled_core(1));
+				core.time_out_at = BLOCK_UNDER_PRODUCTION;
+			}),
+		];
+
+		let prospective_parachains_mode =
+			ProspectiveParachainsMode::Enabled { max_candidate_depth: 0, allowed_ancestry_len: 0 };
+
+		test_harness(
+			|mut receiver: mpsc::UnboundedReceiver<AllMessages>| async move {
+				while let Some(from_job) = receiver.next().await {
+					match from_job {
+						AllMessages::ChainApi(ChainApiMessage::BlockNumber(_, tx)) =>
+							tx.send(Ok(Some(BLOCK_UNDER_PRODUCTION - 1))).unwrap(),
+						AllMessages::ProspectiveParachains(
+							ProspectiveParachainsMessage::GetBackableCandidates(
+								_,
+								requested_para,
+								count,
+								ancestors,
+								tx,
+							),
+						) => {
+							assert_eq!(requested_para, para_id);
+							assert_eq!(count, 3);
+							assert_eq!(ancestors, [pending_hash].into_iter().collect());
+							let _ = tx.send(Vec::new());
+						},
+						AllMessages::CandidateBacking(
+							CandidateBackingMessage::GetBackedCandidates(hashes, sender),
+						) => {
+							assert!(hashes.is_empty());
+							let _ = sender.send(Vec::new());
+						},
+						_ => panic!("Unexpected message: {:?}", from_job),
+					}
+				}
+			},
+			|mut tx: TestSubsystemSender| async move {
+				let result = select_candidates(
+					&mock_cores,
+					&[],
+					&[],
+					prospective_parachains_mode,
+					Default::default(),
+					&mut tx,
+				)
+				.await
+				.unwrap();
+
+				assert!(result.is_empty());
+			},
+		)
+	}
 }
@@ -33,9 +33,10 @@ use polkadot_node_subsystem::{
 	messages::{AvailabilityStoreMessage, IfDisconnected, NetworkBridgeTxMessage},
 	overseer,
 };
+use polkadot_node_subsystem_util::availability_chunks::availability_chunk_index;
 use polkadot_primitives::{
-	AuthorityDiscoveryId, BlakeTwo256, CandidateHash, GroupIndex, Hash, HashT, OccupiedCore,
-	SessionIndex,
+	AuthorityDiscoveryId, BlakeTwo256, CandidateHash, CoreIndex, GroupIndex, Hash, HashT,
+	OccupiedCore, SessionIndex,
 };
 
 use crate::{
@@ -136,6 +137,7 @@ impl FetchTaskConfig {
 	/// The result of this function can be passed into [`FetchTask::start`].
 	pub fn new(
 		leaf: Hash,
+		core_index: CoreIndex,
 		core: &OccupiedCore,
 		sender: mpsc::Sender<FromFetchTask>,
 		metrics: Metrics,
@@ -159,6 +161,26 @@ impl FetchTaskConfig {
 			return FetchTaskConfig { live_in, prepared_running: None }
 		}
 
+		// The chunk we are responsible for depends on the core the candidate occupies.
+		let chunk_index = match availability_chunk_index(
+			Some(&session_info.node_features),
+			session_info.n_validators,
+			core_index,
+			session_info.our_index,
+		) {
+			Ok(chunk_index) => chunk_index,
+			Err(err) => {
+				gum::warn!(
+					target: LOG_TARGET,
+					candidate_hash = ?core.candidate_hash,
+					?core_index,
+					?err,
+					"Failed to compute our availability chunk index",
+				);
+				return FetchTaskConfig { live_in, prepared_running: None }
+			},
+		};
+
 		let prepared_running = RunningTask {
 			session_index: session_info.session_index,
 			group_index: core.group_responsible,
@@ -167,7 +189,7 @@ impl FetchTaskConfig {
 				.clone(),
 			request: ChunkFetchingRequest {
 				candidate_hash: core.candidate_hash,
-				index: session_info.our_index,
+				index: chunk_index,
 			},
 			erasure_root: core.candidate_descriptor.erasure_root,
 			relay_parent: core.candidate_descriptor.relay_parent,
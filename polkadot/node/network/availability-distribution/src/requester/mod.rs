@@ -38,7 +38,7 @@ use polkadot_node_subsystem::{
 	overseer, ActivatedLeaf, ActiveLeavesUpdate,
 };
 use polkadot_node_subsystem_util::runtime::{get_occupied_cores, RuntimeInfo};
-use polkadot_primitives::{CandidateHash, Hash, OccupiedCore, SessionIndex};
+use polkadot_primitives::{CandidateHash, CoreIndex, Hash, OccupiedCore, SessionIndex};
 
 use super::{FatalError, Metrics, Result, LOG_TARGET};
 
@@ -197,10 +197,10 @@ impl Requester {
 		runtime: &mut RuntimeInfo,
 		leaf: Hash,
 		leaf_session_index: SessionIndex,
-		cores: impl IntoIterator<Item = OccupiedCore>,
+		cores: impl IntoIterator<Item = (CoreIndex, OccupiedCore)>,
 		span: jaeger::Span,
 	) -> Result<()> {
-		for core in cores {
+		for (core_index, core) in cores {
 			let mut span = span
 				.child("check-fetch-candidate")
 				.with_trace_id(core.candidate_hash)
@@ -230,7 +230,11 @@ impl Requester {
 							// guaranteed to be fetchable by the state trie.
 							leaf,
 							leaf_session_index,
-							|info| FetchTaskConfig::new(leaf, &core, tx, metrics, info, span),
+							|info| {
+								FetchTaskConfig::new(
+									leaf, core_index, &core, tx, metrics, info, span,
+								)
+							},
 						)
 						.await
 						.map_err(|err| {
@@ -21,7 +21,9 @@ use schnellru::{ByLength, LruMap};
 
 use polkadot_node_subsystem::overseer;
 use polkadot_node_subsystem_util::runtime::RuntimeInfo;
-use polkadot_primitives::{AuthorityDiscoveryId, GroupIndex, Hash, SessionIndex, ValidatorIndex};
+use polkadot_primitives::{
+	vstaging::NodeFeatures, AuthorityDiscoveryId, GroupIndex, Hash, SessionIndex, ValidatorIndex,
+};
 
 use crate::{
 	error::{Error, Result},
@@ -54,6 +56,12 @@ pub struct SessionInfo {
 	/// validators.
 	pub validator_groups: Vec<Vec<AuthorityDiscoveryId>>,
 
+	/// Number of validators in the session.
+	pub n_validators: usize,
+
+	/// Node features enabled in the session, determining which chunk we are responsible for.
+	pub node_features: NodeFeatures,
+
 	/// Information about ourselves:
 	pub our_index: ValidatorIndex,
 
@@ -208,7 +216,14 @@ impl SessionCache {
 				})
 				.collect();
 
-			let info = SessionInfo { validator_groups, our_index, session_index, our_group };
+			let info = SessionInfo {
+				validator_groups,
+				n_validators: discovery_keys.len(),
+				node_features: info.node_features.clone(),
+				our_index,
+				session_index,
+				our_group,
+			};
 			return Ok(Some(info))
 		}
 		return Ok(None)
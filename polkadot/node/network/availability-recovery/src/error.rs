@@ -42,6 +42,9 @@ pub enum Error {
 
 	#[error(transparent)]
 	Util(#[from] polkadot_node_subsystem_util::Error),
+
+	#[error(transparent)]
+	RuntimeInfo(#[from] polkadot_node_subsystem_util::runtime::Error),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
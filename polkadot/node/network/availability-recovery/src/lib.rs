@@ -35,11 +35,15 @@ use futures::{
 	task::{Context, Poll},
 };
 use schnellru::{ByLength, LruMap};
-use task::{FetchChunks, FetchChunksParams, FetchFull, FetchFullParams};
+use task::{
+	FetchChunks, FetchChunksParams, FetchFull, FetchFullParams, FetchSystematicChunks,
+	FetchSystematicChunksParams,
+};
 
 use fatality::Nested;
 use polkadot_erasure_coding::{
-	branch_hash, branches, obtain_chunks_v1, recovery_threshold, Error as ErasureEncodingError,
+	branch_hash, branches, obtain_chunks_v1, recovery_threshold, systematic_recovery_threshold,
+	Error as ErasureEncodingError,
 };
 use task::{RecoveryParams, RecoveryStrategy, RecoveryTask};
 
@@ -55,9 +59,13 @@ use polkadot_node_subsystem::{
 	overseer, ActiveLeavesUpdate, FromOrchestra, OverseerSignal, SpawnedSubsystem,
 	SubsystemContext, SubsystemError, SubsystemResult,
 };
-use polkadot_node_subsystem_util::request_session_info;
+use polkadot_node_subsystem_util::{
+	availability_chunks::{availability_chunk_indices, availability_chunk_mapping_is_enabled},
+	request_session_info,
+	runtime::request_node_features,
+};
 use polkadot_primitives::{
-	BlakeTwo256, BlockNumber, CandidateHash, CandidateReceipt, GroupIndex, Hash, HashT,
+	BlakeTwo256, BlockNumber, CandidateHash, CandidateReceipt, CoreIndex, GroupIndex, Hash, HashT,
 	SessionIndex, SessionInfo, ValidatorIndex,
 };
 
@@ -126,6 +134,13 @@ pub enum ErasureTask {
 		HashMap<ValidatorIndex, ErasureChunk>,
 		oneshot::Sender<Result<AvailableData, ErasureEncodingError>>,
 	),
+	/// Reconstructs `AvailableData` given `n_validators` from the systematic chunks, ordered by
+	/// chunk index.
+	ReconstructSystematic(
+		usize,
+		Vec<Vec<u8>>,
+		oneshot::Sender<Result<AvailableData, ErasureEncodingError>>,
+	),
 	/// Re-encode `AvailableData` into erasure chunks in order to verify the provided root hash of
 	/// the Merkle tree.
 	Reencode(usize, Hash, AvailableData, oneshot::Sender<Option<AvailableData>>),
@@ -350,6 +365,7 @@ async fn launch_recovery_task<Context>(
 	state: &mut State,
 	ctx: &mut Context,
 	session_info: SessionInfo,
+	chunk_indices: Vec<ValidatorIndex>,
 	receipt: CandidateReceipt,
 	response_sender: oneshot::Sender<Result<AvailableData, RecoveryError>>,
 	metrics: &Metrics,
@@ -362,6 +378,7 @@ async fn launch_recovery_task<Context>(
 		validator_authority_keys: session_info.discovery_keys.clone(),
 		n_validators: session_info.validators.len(),
 		threshold: recovery_threshold(session_info.validators.len())?,
+		chunk_indices,
 		candidate_hash,
 		erasure_root: receipt.descriptor.erasure_root,
 		metrics: metrics.clone(),
@@ -399,6 +416,7 @@ async fn handle_recover<Context>(
 	receipt: CandidateReceipt,
 	session_index: SessionIndex,
 	backing_group: Option<GroupIndex>,
+	core_index: Option<CoreIndex>,
 	response_sender: oneshot::Sender<Result<AvailableData, RecoveryError>>,
 	metrics: &Metrics,
 	erasure_task_tx: futures::channel::mpsc::Sender<ErasureTask>,
@@ -440,9 +458,15 @@ async fn handle_recover<Context>(
 	let _span = span.child("session-info-ctx-received");
 	match session_info {
 		Some(session_info) => {
+			let node_features =
+				request_node_features(state.live_block.1, session_index, ctx.sender()).await?;
+			let n_validators = session_info.validators.len();
+			let chunk_mapping_enabled =
+				availability_chunk_mapping_is_enabled(node_features.as_ref());
+
 			let mut recovery_strategies: VecDeque<
 				Box<dyn RecoveryStrategy<<Context as SubsystemContext>::Sender>>,
-			> = VecDeque::with_capacity(2);
+			> = VecDeque::with_capacity(3);
 
 			if let Some(backing_group) = backing_group {
 				if let Some(backing_validators) = session_info.validator_groups.get(backing_group) {
@@ -487,8 +511,48 @@ async fn handle_recover<Context>(
 				}
 			}
 
+			// The chunk held by each validator depends on the core the candidate occupied. Without
+			// the mapping, any core yields the identity.
+			let chunk_indices = match core_index {
+				Some(core_index) =>
+					availability_chunk_indices(node_features.as_ref(), n_validators, core_index)?,
+				None => {
+					if chunk_mapping_enabled {
+						gum::debug!(
+							target: LOG_TARGET,
+							?candidate_hash,
+							"Core index unknown while the availability chunk mapping is enabled, \
+							requesting chunks by validator index",
+						);
+					}
+
+					// Received chunks are still checked against the erasure root by their own
+					// index.
+					availability_chunk_indices(None, n_validators, CoreIndex(0))?
+				},
+			};
+
+			// Systematic chunks can only be located with the core index.
+			if chunk_mapping_enabled && core_index.is_some() {
+				let systematic_threshold = systematic_recovery_threshold(n_validators)?;
+				let validators = chunk_indices
+					.iter()
+					.enumerate()
+					.filter(|(_, chunk_index)| (chunk_index.0 as usize) < systematic_threshold)
+					.map(|(validator_index, _)| ValidatorIndex(validator_index as u32))
+					.collect();
+
+				recovery_strategies.push_back(Box::new(FetchSystematicChunks::new(
+					FetchSystematicChunksParams {
+						validators,
+						threshold: systematic_threshold,
+						erasure_task_tx: erasure_task_tx.clone(),
+					},
+				)));
+			}
+
 			recovery_strategies.push_back(Box::new(FetchChunks::new(FetchChunksParams {
-				n_validators: session_info.validators.len(),
+				n_validators,
 				erasure_task_tx,
 			})));
 
@@ -496,6 +560,7 @@ async fn handle_recover<Context>(
 				state,
 				ctx,
 				session_info,
+				chunk_indices,
 				receipt,
 				response_sender,
 				metrics,
@@ -689,6 +754,7 @@ impl AvailabilityRecoverySubsystem {
 									receipt,
 									session_index,
 									maybe_backing_group,
+									maybe_core_index,
 									response_sender,
 								) => {
 									if let Err(e) = handle_recover(
@@ -697,6 +763,7 @@ impl AvailabilityRecoverySubsystem {
 										receipt,
 										session_index,
 										maybe_backing_group,
+										maybe_core_index,
 										response_sender,
 										&metrics,
 										erasure_task_tx.clone(),
@@ -828,6 +895,12 @@ async fn erasure_task_thread(
 					chunks.values().map(|c| (&c.chunk[..], c.index.0 as usize)),
 				));
 			},
+			Some(ErasureTask::ReconstructSystematic(n_validators, chunks, sender)) => {
+				let _ = sender.send(polkadot_erasure_coding::reconstruct_from_systematic_v1(
+					n_validators,
+					chunks,
+				));
+			},
 			Some(ErasureTask::Reencode(n_validators, root, available_data, sender)) => {
 				let metrics = metrics.clone();
 
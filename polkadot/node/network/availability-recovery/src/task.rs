@@ -84,6 +84,9 @@ pub struct RecoveryParams {
 	/// The number of chunks needed.
 	pub threshold: usize,
 
+	/// The index of the chunk held by each validator, indexed by validator index.
+	pub chunk_indices: Vec<ValidatorIndex>,
+
 	/// A hash of the relevant candidate.
 	pub candidate_hash: CandidateHash,
 
@@ -106,7 +109,7 @@ pub struct RecoveryParams {
 /// Intermediate/common data that must be passed between `RecoveryStrategy`s belonging to the
 /// same `RecoveryTask`.
 pub struct State {
-	/// Chunks received so far.
+	/// Chunks received so far, by chunk index.
 	received_chunks: HashMap<ValidatorIndex, ErasureChunk>,
 }
 
@@ -115,14 +118,22 @@ impl State {
 		Self { received_chunks: HashMap::new() }
 	}
 
-	fn insert_chunk(&mut self, validator: ValidatorIndex, chunk: ErasureChunk) {
-		self.received_chunks.insert(validator, chunk);
+	fn insert_chunk(&mut self, chunk_index: ValidatorIndex, chunk: ErasureChunk) {
+		self.received_chunks.insert(chunk_index, chunk);
 	}
 
 	fn chunk_count(&self) -> usize {
 		self.received_chunks.len()
 	}
 
+	/// Number of received chunks among the first `systematic_threshold` ones.
+	fn systematic_chunk_count(&self, systematic_threshold: usize) -> usize {
+		self.received_chunks
+			.keys()
+			.filter(|chunk_index| (chunk_index.0 as usize) < systematic_threshold)
+			.count()
+	}
+
 	/// Retrieve the local chunks held in the av-store (either 0 or 1).
 	async fn populate_from_av_store<Sender: overseer::AvailabilityRecoverySenderTrait>(
 		&mut self,
@@ -145,7 +156,7 @@ impl State {
 						gum::trace!(
 							target: LOG_TARGET,
 							candidate_hash = ?params.candidate_hash,
-							validator_index = ?chunk.index,
+							chunk_index = ?chunk.index,
 							"Found valid chunk on disk"
 						);
 						self.insert_chunk(chunk.index, chunk);
@@ -192,10 +203,12 @@ impl State {
 		while requesting_chunks.len() < desired_requests_count {
 			if let Some(validator_index) = validators.pop_back() {
 				let validator = params.validator_authority_keys[validator_index.0 as usize].clone();
+				let chunk_index = params.chunk_indices[validator_index.0 as usize];
 				gum::trace!(
 					target: LOG_TARGET,
 					?validator,
 					?validator_index,
+					?chunk_index,
 					?candidate_hash,
 					"Requesting chunk",
 				);
@@ -203,7 +216,7 @@ impl State {
 				// Request data.
 				let raw_request = req_res::v1::ChunkFetchingRequest {
 					candidate_hash: params.candidate_hash,
-					index: validator_index,
+					index: chunk_index,
 				};
 
 				let (req, res) = OutgoingRequest::new(Recipient::Authority(validator), raw_request);
@@ -242,7 +255,7 @@ impl State {
 		requesting_chunks: &mut FuturesUndead<
 			Result<Option<ErasureChunk>, (ValidatorIndex, RequestError)>,
 		>,
-		can_conclude: impl Fn(usize, usize, usize, &RecoveryParams, usize) -> bool,
+		can_conclude: impl Fn(usize, usize, &State, &RecoveryParams, usize) -> bool,
 	) -> (usize, usize) {
 		let metrics = &params.metrics;
 
@@ -264,7 +277,7 @@ impl State {
 						gum::trace!(
 							target: LOG_TARGET,
 							candidate_hash = ?params.candidate_hash,
-							validator_index = ?chunk.index,
+							chunk_index = ?chunk.index,
 							"Received valid chunk",
 						);
 						self.insert_chunk(chunk.index, chunk);
@@ -324,7 +337,7 @@ impl State {
 			if can_conclude(
 				validators.len(),
 				requesting_chunks.total_len(),
-				self.chunk_count(),
+				self,
 				params,
 				error_count,
 			) {
@@ -450,6 +463,52 @@ where
 	}
 }
 
+/// Perform the configured post-recovery check on data reconstructed from chunks. Returns `None` if
+/// the check failed.
+async fn check_reconstructed_data(
+	erasure_task_tx: &mut futures::channel::mpsc::Sender<ErasureTask>,
+	common_params: &RecoveryParams,
+	data: AvailableData,
+) -> Result<Option<AvailableData>, RecoveryError> {
+	let maybe_data = match common_params.post_recovery_check {
+		PostRecoveryCheck::Reencode => {
+			// Send request to re-encode the chunks and check merkle root.
+			let (reencode_tx, reencode_rx) = oneshot::channel();
+			erasure_task_tx
+				.send(ErasureTask::Reencode(
+					common_params.n_validators,
+					common_params.erasure_root,
+					data,
+					reencode_tx,
+				))
+				.await
+				.map_err(|_| RecoveryError::ChannelClosed)?;
+
+			reencode_rx.await.map_err(|_| RecoveryError::ChannelClosed)?.or_else(|| {
+				gum::trace!(
+					target: LOG_TARGET,
+					candidate_hash = ?common_params.candidate_hash,
+					erasure_root = ?common_params.erasure_root,
+					"Data recovery error - root mismatch",
+				);
+				None
+			})
+		},
+		PostRecoveryCheck::PovHash =>
+			(data.pov.hash() == common_params.pov_hash).then_some(data).or_else(|| {
+				gum::trace!(
+					target: LOG_TARGET,
+					candidate_hash = ?common_params.candidate_hash,
+					pov_hash = ?common_params.pov_hash,
+					"Data recovery error - PoV hash mismatch",
+				);
+				None
+			}),
+	};
+
+	Ok(maybe_data)
+}
+
 /// `RecoveryStrategy` that sequentially tries to fetch the full `AvailableData` from
 /// already-connected validators in the configured validator set.
 pub struct FetchFull {
@@ -563,6 +622,232 @@ impl<Sender: overseer::AvailabilityRecoverySenderTrait> RecoveryStrategy<Sender>
 	}
 }
 
+/// `RecoveryStrategy` that requests the systematic chunks from the validators holding them, in
+/// parallel, and recovers the data by concatenating them, without decoding.
+///
+/// Concludes as unavailable as soon as one of the systematic chunks can't be retrieved, leaving
+/// the chunks received so far to the following strategies.
+pub struct FetchSystematicChunks {
+	/// Number of systematic chunks needed for the recovery.
+	threshold: usize,
+	/// Collection of in-flight requests.
+	requesting_chunks: FuturesUndead<Result<Option<ErasureChunk>, (ValidatorIndex, RequestError)>>,
+	/// A random shuffling of the validators holding the systematic chunks, which indicates the
+	/// order in which we connect to them and request the chunk.
+	validators: VecDeque<ValidatorIndex>,
+	/// Channel to the erasure task handler.
+	erasure_task_tx: futures::channel::mpsc::Sender<ErasureTask>,
+}
+
+/// Parameters specific to the `FetchSystematicChunks` strategy.
+pub struct FetchSystematicChunksParams {
+	/// Validators holding the systematic chunks.
+	pub validators: Vec<ValidatorIndex>,
+	/// Number of systematic chunks.
+	pub threshold: usize,
+	/// Channel to the erasure task handler.
+	pub erasure_task_tx: futures::channel::mpsc::Sender<ErasureTask>,
+}
+
+impl FetchSystematicChunks {
+	/// Instantiate a new strategy.
+	pub fn new(mut params: FetchSystematicChunksParams) -> Self {
+		params.validators.shuffle(&mut rand::thread_rng());
+
+		Self {
+			threshold: params.threshold,
+			requesting_chunks: FuturesUndead::new(),
+			validators: params.validators.into(),
+			erasure_task_tx: params.erasure_task_tx,
+		}
+	}
+
+	fn is_unavailable(
+		unrequested_validators: usize,
+		in_flight_requests: usize,
+		systematic_chunk_count: usize,
+		threshold: usize,
+	) -> bool {
+		is_unavailable(
+			systematic_chunk_count,
+			in_flight_requests,
+			unrequested_validators,
+			threshold,
+		)
+	}
+
+	async fn attempt_systematic_recovery(
+		&mut self,
+		state: &mut State,
+		common_params: &RecoveryParams,
+	) -> Result<AvailableData, RecoveryError> {
+		let recovery_duration = common_params.metrics.time_erasure_recovery();
+
+		// The chunks are kept in `state`, so that they can still be used by a regular chunk
+		// recovery.
+		let chunks = (0..self.threshold)
+			.map(|chunk_index| {
+				state
+					.received_chunks
+					.get(&ValidatorIndex(chunk_index as u32))
+					.map(|chunk| chunk.chunk.clone())
+					.ok_or(RecoveryError::Unavailable)
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+
+		// Send request to reconstruct available data from the systematic chunks.
+		let (available_data_tx, available_data_rx) = oneshot::channel();
+		self.erasure_task_tx
+			.send(ErasureTask::ReconstructSystematic(
+				common_params.n_validators,
+				chunks,
+				available_data_tx,
+			))
+			.await
+			.map_err(|_| RecoveryError::ChannelClosed)?;
+
+		let available_data_response =
+			available_data_rx.await.map_err(|_| RecoveryError::ChannelClosed)?;
+
+		match available_data_response {
+			Ok(data) => {
+				let maybe_data =
+					check_reconstructed_data(&mut self.erasure_task_tx, common_params, data)
+						.await?;
+
+				if let Some(data) = maybe_data {
+					gum::trace!(
+						target: LOG_TARGET,
+						candidate_hash = ?common_params.candidate_hash,
+						erasure_root = ?common_params.erasure_root,
+						"Data recovery from systematic chunks complete",
+					);
+
+					Ok(data)
+				} else {
+					recovery_duration.map(|rd| rd.stop_and_discard());
+
+					Err(RecoveryError::Invalid)
+				}
+			},
+			Err(err) => {
+				recovery_duration.map(|rd| rd.stop_and_discard());
+				gum::trace!(
+					target: LOG_TARGET,
+					candidate_hash = ?common_params.candidate_hash,
+					erasure_root = ?common_params.erasure_root,
+					?err,
+					"Systematic data recovery error",
+				);
+
+				// All systematic chunks passed the merkle proof check, so the backers committed to
+				// data which doesn't decode.
+				Err(RecoveryError::Invalid)
+			},
+		}
+	}
+}
+
+#[async_trait::async_trait]
+impl<Sender: overseer::AvailabilityRecoverySenderTrait> RecoveryStrategy<Sender>
+	for FetchSystematicChunks
+{
+	fn display_name(&self) -> &'static str {
+		"Fetch systematic chunks"
+	}
+
+	async fn run(
+		&mut self,
+		state: &mut State,
+		sender: &mut Sender,
+		common_params: &RecoveryParams,
+	) -> Result<AvailableData, RecoveryError> {
+		// First query the store for any chunks we've got.
+		if !common_params.bypass_availability_store {
+			let local_chunk_indices = state.populate_from_av_store(common_params, sender).await;
+			self.validators.retain(|validator_index| {
+				!local_chunk_indices
+					.contains(&common_params.chunk_indices[validator_index.0 as usize])
+			});
+		}
+
+		// No need to query the validators that have the chunks we already received.
+		self.validators.retain(|validator_index| {
+			!state
+				.received_chunks
+				.contains_key(&common_params.chunk_indices[validator_index.0 as usize])
+		});
+
+		loop {
+			let systematic_chunk_count = state.systematic_chunk_count(self.threshold);
+			if systematic_chunk_count >= self.threshold {
+				return self.attempt_systematic_recovery(state, common_params).await
+			}
+
+			if Self::is_unavailable(
+				self.validators.len(),
+				self.requesting_chunks.total_len(),
+				systematic_chunk_count,
+				self.threshold,
+			) {
+				gum::debug!(
+					target: LOG_TARGET,
+					candidate_hash = ?common_params.candidate_hash,
+					erasure_root = ?common_params.erasure_root,
+					received = %systematic_chunk_count,
+					requesting = %self.requesting_chunks.len(),
+					total_requesting = %self.requesting_chunks.total_len(),
+					n_validators = %common_params.n_validators,
+					systematic_threshold = %self.threshold,
+					"Data recovery from systematic chunks is not possible",
+				);
+
+				return Err(RecoveryError::Unavailable)
+			}
+
+			// Every missing systematic chunk is needed, so request all of them at once.
+			let desired_requests_count =
+				std::cmp::min(N_PARALLEL, self.threshold - systematic_chunk_count);
+			gum::debug!(
+				target: LOG_TARGET,
+				?common_params.candidate_hash,
+				?desired_requests_count,
+				systematic_threshold = ?self.threshold,
+				already_requesting_count = ?self.requesting_chunks.len(),
+				"Requesting systematic availability chunks for a candidate",
+			);
+			state
+				.launch_parallel_chunk_requests(
+					common_params,
+					sender,
+					desired_requests_count,
+					&mut self.validators,
+					&mut self.requesting_chunks,
+				)
+				.await;
+
+			let threshold = self.threshold;
+			state
+				.wait_for_chunks(
+					common_params,
+					&mut self.validators,
+					&mut self.requesting_chunks,
+					|unrequested_validators, reqs, state, _params, _error_count| {
+						let systematic_chunk_count = state.systematic_chunk_count(threshold);
+						systematic_chunk_count >= threshold ||
+							Self::is_unavailable(
+								unrequested_validators,
+								reqs,
+								systematic_chunk_count,
+								threshold,
+							)
+					},
+				)
+				.await;
+		}
+	}
+}
+
 /// `RecoveryStrategy` that requests chunks from validators, in parallel.
 pub struct FetchChunks {
 	/// How many requests have been unsuccessful so far.
@@ -662,41 +947,9 @@ impl FetchChunks {
 
 		match available_data_response {
 			Ok(data) => {
-				let maybe_data = match common_params.post_recovery_check {
-					PostRecoveryCheck::Reencode => {
-						// Send request to re-encode the chunks and check merkle root.
-						let (reencode_tx, reencode_rx) = oneshot::channel();
-						self.erasure_task_tx
-							.send(ErasureTask::Reencode(
-								common_params.n_validators,
-								common_params.erasure_root,
-								data,
-								reencode_tx,
-							))
-							.await
-							.map_err(|_| RecoveryError::ChannelClosed)?;
-
-						reencode_rx.await.map_err(|_| RecoveryError::ChannelClosed)?.or_else(|| {
-							gum::trace!(
-								target: LOG_TARGET,
-								candidate_hash = ?common_params.candidate_hash,
-								erasure_root = ?common_params.erasure_root,
-								"Data recovery error - root mismatch",
-							);
-							None
-						})
-					},
-					PostRecoveryCheck::PovHash =>
-						(data.pov.hash() == common_params.pov_hash).then_some(data).or_else(|| {
-							gum::trace!(
-								target: LOG_TARGET,
-								candidate_hash = ?common_params.candidate_hash,
-								pov_hash = ?common_params.pov_hash,
-								"Data recovery error - PoV hash mismatch",
-							);
-							None
-						}),
-				};
+				let maybe_data =
+					check_reconstructed_data(&mut self.erasure_task_tx, common_params, data)
+						.await?;
 
 				if let Some(data) = maybe_data {
 					gum::trace!(
@@ -744,11 +997,18 @@ impl<Sender: overseer::AvailabilityRecoverySenderTrait> RecoveryStrategy<Sender>
 		// First query the store for any chunks we've got.
 		if !common_params.bypass_availability_store {
 			let local_chunk_indices = state.populate_from_av_store(common_params, sender).await;
-			self.validators.retain(|i| !local_chunk_indices.contains(i));
+			self.validators.retain(|validator_index| {
+				!local_chunk_indices
+					.contains(&common_params.chunk_indices[validator_index.0 as usize])
+			});
 		}
 
 		// No need to query the validators that have the chunks we already received.
-		self.validators.retain(|i| !state.received_chunks.contains_key(i));
+		self.validators.retain(|validator_index| {
+			!state
+				.received_chunks
+				.contains_key(&common_params.chunk_indices[validator_index.0 as usize])
+		});
 
 		loop {
 			// If received_chunks has more than threshold entries, attempt to recover the data.
@@ -808,7 +1068,8 @@ impl<Sender: overseer::AvailabilityRecoverySenderTrait> RecoveryStrategy<Sender>
 					common_params,
 					&mut self.validators,
 					&mut self.requesting_chunks,
-					|unrequested_validators, reqs, chunk_count, params, _error_count| {
+					|unrequested_validators, reqs, state, params, _error_count| {
+						let chunk_count = state.chunk_count();
 						chunk_count >= params.threshold ||
 							Self::is_unavailable(
 								unrequested_validators,
@@ -37,8 +37,9 @@ use polkadot_node_subsystem::messages::{
 use polkadot_node_subsystem_test_helpers::{
 	make_subsystem_context, mock::new_leaf, TestSubsystemContextHandle,
 };
-use polkadot_node_subsystem_util::TimeoutExt;
+use polkadot_node_subsystem_util::{availability_chunks::availability_chunk_indices, TimeoutExt};
 use polkadot_primitives::{
+	vstaging::{node_features::FeatureIndex, NodeFeatures},
 	AuthorityDiscoveryId, Hash, HeadData, IndexedVec, PersistedValidationData, ValidatorId,
 };
 use polkadot_primitives_test_helpers::{dummy_candidate_receipt, dummy_hash};
@@ -230,6 +231,8 @@ struct TestState {
 	current: Hash,
 	candidate: CandidateReceipt,
 	session_index: SessionIndex,
+	core_index: CoreIndex,
+	node_features: NodeFeatures,
 
 	persisted_validation_data: PersistedValidationData,
 
@@ -247,6 +250,26 @@ impl TestState {
 		self.validators.len() - self.threshold() + 1
 	}
 
+	fn systematic_threshold(&self) -> usize {
+		systematic_recovery_threshold(self.validators.len()).unwrap()
+	}
+
+	fn chunk_indices(&self) -> Vec<ValidatorIndex> {
+		availability_chunk_indices(
+			Some(&self.node_features),
+			self.validators.len(),
+			self.core_index,
+		)
+		.unwrap()
+	}
+
+	fn with_chunk_mapping(mut self, core_index: CoreIndex) -> Self {
+		self.core_index = core_index;
+		self.node_features =
+			NodeFeatures::repeat(true, FeatureIndex::AvailabilityChunkMapping as usize + 1);
+		self
+	}
+
 	async fn test_runtime_api(&self, virtual_overseer: &mut VirtualOverseer) {
 		assert_matches!(
 			overseer_recv(virtual_overseer).await,
@@ -278,6 +301,22 @@ impl TestState {
 				}))).unwrap();
 			}
 		);
+
+		assert_matches!(
+			overseer_recv(virtual_overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				relay_parent,
+				RuntimeApiRequest::NodeFeatures(
+					session_index,
+					tx,
+				)
+			)) => {
+				assert_eq!(relay_parent, self.current);
+				assert_eq!(session_index, self.session_index);
+
+				tx.send(Ok(self.node_features.clone())).unwrap();
+			}
+		);
 	}
 
 	async fn respond_to_available_data_query(
@@ -350,6 +389,7 @@ impl TestState {
 		// arbitrary order.
 		let mut i = 0;
 		let mut senders = Vec::new();
+		let chunk_indices = self.chunk_indices();
 		while i < n {
 			// Receive a request for a chunk.
 			assert_matches!(
@@ -367,10 +407,16 @@ impl TestState {
 							Requests::ChunkFetchingV1(req) => {
 								assert_eq!(req.payload.candidate_hash, candidate_hash);
 
-								let validator_index = req.payload.index.0 as usize;
+								let validator_index = self.validator_authority_id
+									.iter()
+									.position(|a| Recipient::Authority(a.clone()) == req.peer)
+									.unwrap();
+								let chunk_index = req.payload.index.0 as usize;
+								assert_eq!(chunk_indices[validator_index].0 as usize, chunk_index);
+
 								let available_data = match who_has(validator_index) {
 									Has::No => Ok(None),
-									Has::Yes => Ok(Some(self.chunks[validator_index].clone().into())),
+									Has::Yes => Ok(Some(self.chunks[chunk_index].clone().into())),
 									Has::NetworkError(e) => Err(e),
 									Has::DoesNotReturn => {
 										senders.push(req.pending_response);
@@ -548,6 +594,8 @@ impl Default for TestState {
 			current,
 			candidate,
 			session_index,
+			core_index: CoreIndex(0),
+			node_features: NodeFeatures::EMPTY,
 			persisted_validation_data,
 			available_data,
 			chunks,
@@ -578,6 +626,7 @@ fn availability_is_recovered_from_chunks_if_no_group_provided() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				None,
+				None,
 				tx,
 			),
 		)
@@ -615,6 +664,7 @@ fn availability_is_recovered_from_chunks_if_no_group_provided() {
 				new_candidate.clone(),
 				test_state.session_index,
 				None,
+				None,
 				tx,
 			),
 		)
@@ -662,6 +712,7 @@ fn availability_is_recovered_from_chunks_even_if_backing_group_supplied_if_chunk
 				test_state.candidate.clone(),
 				test_state.session_index,
 				Some(GroupIndex(0)),
+				None,
 				tx,
 			),
 		)
@@ -699,6 +750,7 @@ fn availability_is_recovered_from_chunks_even_if_backing_group_supplied_if_chunk
 				new_candidate.clone(),
 				test_state.session_index,
 				None,
+				None,
 				tx,
 			),
 		)
@@ -746,6 +798,7 @@ fn bad_merkle_path_leads_to_recovery_error() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				None,
+				None,
 				tx,
 			),
 		)
@@ -802,6 +855,7 @@ fn wrong_chunk_index_leads_to_recovery_error() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				None,
+				None,
 				tx,
 			),
 		)
@@ -874,6 +928,7 @@ fn invalid_erasure_coding_leads_to_invalid_error() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				None,
+				None,
 				tx,
 			),
 		)
@@ -921,6 +976,7 @@ fn fast_path_backing_group_recovers() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				Some(GroupIndex(0)),
+				None,
 				tx,
 			),
 		)
@@ -969,6 +1025,7 @@ fn recovers_from_only_chunks_if_pov_large() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				Some(GroupIndex(0)),
+				None,
 				tx,
 			),
 		)
@@ -1015,6 +1072,7 @@ fn recovers_from_only_chunks_if_pov_large() {
 				new_candidate.clone(),
 				test_state.session_index,
 				Some(GroupIndex(0)),
+				None,
 				tx,
 			),
 		)
@@ -1071,6 +1129,7 @@ fn fast_path_backing_group_recovers_if_pov_small() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				Some(GroupIndex(0)),
+				None,
 				tx,
 			),
 		)
@@ -1128,6 +1187,7 @@ fn no_answers_in_fast_path_causes_chunk_requests() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				Some(GroupIndex(0)),
+				None,
 				tx,
 			),
 		)
@@ -1188,6 +1248,7 @@ fn task_canceled_when_receivers_dropped() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				None,
+				None,
 				tx,
 			),
 		)
@@ -1228,6 +1289,7 @@ fn chunks_retry_until_all_nodes_respond() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				Some(GroupIndex(0)),
+				None,
 				tx,
 			),
 		)
@@ -1287,6 +1349,7 @@ fn not_returning_requests_wont_stall_retrieval() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				Some(GroupIndex(0)),
+				None,
 				tx,
 			),
 		)
@@ -1357,6 +1420,7 @@ fn all_not_returning_requests_still_recovers_on_return() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				Some(GroupIndex(0)),
+				None,
 				tx,
 			),
 		)
@@ -1432,6 +1496,7 @@ fn returns_early_if_we_have_the_data() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				None,
+				None,
 				tx,
 			),
 		)
@@ -1467,6 +1532,7 @@ fn does_not_query_local_validator() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				None,
+				None,
 				tx,
 			),
 		)
@@ -1524,6 +1590,7 @@ fn invalid_local_chunk_is_ignored() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				None,
+				None,
 				tx,
 			),
 		)
@@ -1550,3 +1617,166 @@ fn invalid_local_chunk_is_ignored() {
 		(virtual_overseer, req_cfg)
 	});
 }
+
+#[test]
+fn systematic_chunks_are_recovered_with_chunk_mapping() {
+	let test_state = TestState::default().with_chunk_mapping(CoreIndex(1));
+	let chunk_indices = test_state.chunk_indices();
+	let systematic_threshold = test_state.systematic_threshold();
+
+	test_harness_chunks_only(|mut virtual_overseer, req_cfg| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(new_leaf(
+				test_state.current,
+				1,
+			))),
+		)
+		.await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				None,
+				Some(test_state.core_index),
+				tx,
+			),
+		)
+		.await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+
+		// Only the validators holding the systematic chunks are queried.
+		test_state
+			.test_chunk_requests(
+				test_state.candidate.hash(),
+				&mut virtual_overseer,
+				systematic_threshold,
+				|i| {
+					assert!((chunk_indices[i].0 as usize) < systematic_threshold);
+					Has::Yes
+				},
+			)
+			.await;
+
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+		(virtual_overseer, req_cfg)
+	});
+}
+
+#[test]
+fn systematic_recovery_falls_back_to_regular_chunks() {
+	let test_state = TestState::default().with_chunk_mapping(CoreIndex(1));
+	let chunk_indices = test_state.chunk_indices();
+	let systematic_threshold = test_state.systematic_threshold();
+
+	test_harness_chunks_only(|mut virtual_overseer, req_cfg| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(new_leaf(
+				test_state.current,
+				1,
+			))),
+		)
+		.await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				None,
+				Some(test_state.core_index),
+				tx,
+			),
+		)
+		.await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+
+		let candidate_hash = test_state.candidate.hash();
+
+		// The holder of the first systematic chunk doesn't have it.
+		test_state
+			.test_chunk_requests(candidate_hash, &mut virtual_overseer, systematic_threshold, |i| {
+				if chunk_indices[i].0 == 0 {
+					Has::No
+				} else {
+					Has::Yes
+				}
+			})
+			.await;
+
+		// Regular chunk recovery reuses the systematic chunks received so far.
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+		test_state
+			.test_chunk_requests(
+				candidate_hash,
+				&mut virtual_overseer,
+				test_state.threshold() - (systematic_threshold - 1),
+				|_| Has::Yes,
+			)
+			.await;
+
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+		(virtual_overseer, req_cfg)
+	});
+}
+
+#[test]
+fn chunks_are_fetched_by_validator_index_without_core_index() {
+	let test_state = TestState::default().with_chunk_mapping(CoreIndex(1));
+	// Without the core index, the chunk requested from each validator is its own index.
+	let by_validator_index = TestState { node_features: NodeFeatures::EMPTY, ..test_state.clone() };
+
+	test_harness_chunks_only(|mut virtual_overseer, req_cfg| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(new_leaf(
+				test_state.current,
+				1,
+			))),
+		)
+		.await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				None,
+				None,
+				tx,
+			),
+		)
+		.await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+
+		by_validator_index
+			.test_chunk_requests(
+				test_state.candidate.hash(),
+				&mut virtual_overseer,
+				test_state.threshold(),
+				|_| Has::Yes,
+			)
+			.await;
+
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+		(virtual_overseer, req_cfg)
+	});
+}
@@ -48,8 +48,8 @@ use polkadot_node_subsystem_util::{
 	backing_implicit_view::View as ImplicitView,
 	reputation::{ReputationAggregator, REPUTATION_CHANGE_INTERVAL},
 	runtime::{
-		get_availability_cores, get_group_rotation_info, prospective_parachains_mode,
-		ProspectiveParachainsMode, RuntimeInfo,
+		fetch_claim_queue, get_availability_cores, get_group_rotation_info,
+		prospective_parachains_mode, ProspectiveParachainsMode, RuntimeInfo,
 	},
 	TimeoutExt,
 };
@@ -516,6 +516,20 @@ async fn determine_core(
 ) -> Result<Option<(CoreIndex, usize)>> {
 	let cores = get_availability_cores(sender, relay_parent).await?;
 
+	if let Some(claim_queue) = fetch_claim_queue(sender, relay_parent).await? {
+		// Pick the core on which the para has its earliest claim. A collation built ahead of
+		// time for a claim further down the queue still goes to the group of that core.
+		let our_core = claim_queue
+			.iter()
+			.filter_map(|(core, claims)| {
+				claims.iter().position(|p| *p == para_id).map(|depth| (depth, *core))
+			})
+			.min()
+			.map(|(_, core)| core);
+
+		return Ok(our_core.map(|core| (core, cores.len())))
+	}
+
 	for (idx, core) in cores.iter().enumerate() {
 		let core_para_id = match core {
 			CoreState::Scheduled(scheduled) => Some(scheduled.para_id),
@@ -377,6 +377,18 @@ async fn distribute_collation_with_receipt(
 		}
 	);
 
+	// The runtime doesn't expose the claim queue, so the availability cores are used.
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			_relay_parent,
+			RuntimeApiRequest::ClaimQueue(tx)
+		)) => {
+			assert_eq!(relay_parent, _relay_parent);
+			tx.send(Err(RuntimeApiError::NotSupported { runtime_api_name: "claim_queue" })).unwrap();
+		}
+	);
+
 	// We don't know precisely what is going to come as session info might be cached:
 	loop {
 		match overseer_recv(virtual_overseer).await {
@@ -52,7 +52,7 @@ use polkadot_node_subsystem::{
 use polkadot_node_subsystem_util::{
 	backing_implicit_view::View as ImplicitView,
 	reputation::{ReputationAggregator, REPUTATION_CHANGE_INTERVAL},
-	runtime::{prospective_parachains_mode, ProspectiveParachainsMode},
+	runtime::{fetch_claim_queue, prospective_parachains_mode, ProspectiveParachainsMode},
 };
 use polkadot_primitives::{
 	CandidateHash, CollatorId, CoreState, Hash, Id as ParaId, OccupiedCoreAssumption,
@@ -362,8 +362,9 @@ impl PeerData {
 
 #[derive(Debug)]
 struct GroupAssignments {
-	/// Current assignment.
-	current: Option<ParaId>,
+	/// Paras with a claim on our core, starting with the one scheduled next. Without async backing
+	/// or the claim queue runtime API this holds at most the para assigned to the core right now.
+	current: Vec<ParaId>,
 }
 
 struct PerRelayParent {
@@ -376,7 +377,7 @@ impl PerRelayParent {
 	fn new(mode: ProspectiveParachainsMode) -> Self {
 		Self {
 			prospective_parachains_mode: mode,
-			assignment: GroupAssignments { current: None },
+			assignment: GroupAssignments { current: Vec::new() },
 			collations: Collations::default(),
 		}
 	}
@@ -493,17 +494,40 @@ where
 		.await
 		.map_err(Error::CancelledAvailabilityCores)??;
 
-	let para_now = match polkadot_node_subsystem_util::signing_key_and_index(&validators, keystore)
+	// Collations for upcoming claims can only be backed with async backing, where they may use an
+	// older relay parent.
+	let claim_queue = if relay_parent_mode.is_enabled() {
+		fetch_claim_queue(sender, relay_parent).await?
+	} else {
+		None
+	};
+
+	let paras_now = match polkadot_node_subsystem_util::signing_key_and_index(&validators, keystore)
 		.and_then(|(_, index)| polkadot_node_subsystem_util::find_validator_group(&groups, index))
 	{
 		Some(group) => {
 			let core_now = rotation_info.core_for_group(group, cores.len());
 
-			cores.get(core_now.0 as usize).and_then(|c| match c {
-				CoreState::Occupied(core) if relay_parent_mode.is_enabled() => Some(core.para_id()),
-				CoreState::Scheduled(core) => Some(core.para_id),
-				CoreState::Occupied(_) | CoreState::Free => None,
-			})
+			match claim_queue {
+				// Accept collations for all upcoming claims on our core, so that collators can
+				// build them ahead of time.
+				Some(mut claim_queue) => {
+					let mut paras = Vec::from(claim_queue.remove(&core_now).unwrap_or_default());
+					let mut seen = HashSet::new();
+					paras.retain(|para_id| seen.insert(*para_id));
+					paras
+				},
+				None => cores
+					.get(core_now.0 as usize)
+					.and_then(|c| match c {
+						CoreState::Occupied(core) if relay_parent_mode.is_enabled() =>
+							Some(core.para_id()),
+						CoreState::Scheduled(core) => Some(core.para_id),
+						CoreState::Occupied(_) | CoreState::Free => None,
+					})
+					.into_iter()
+					.collect(),
+			}
 		},
 		None => {
 			gum::trace!(target: LOG_TARGET, ?relay_parent, "Not a validator");
@@ -512,15 +536,14 @@ where
 		},
 	};
 
-	// This code won't work well, if at all for on-demand parachains. For on-demand we'll
-	// have to be aware of which core the on-demand claim is going to be multiplexed
-	// onto. The on-demand claim will also have a known collator, and we should always
-	// allow an incoming connection from that collator. If not even connecting to them
-	// directly.
+	// Without the claim queue this code won't work well, if at all for on-demand parachains,
+	// as we only learn about an on-demand claim once it is scheduled on our core. The on-demand
+	// claim will also have a known collator, and we should always allow an incoming connection
+	// from that collator. If not even connecting to them directly.
 	//
 	// However, this'll work fine for parachains, as each parachain gets a dedicated
 	// core.
-	if let Some(para_id) = para_now.as_ref() {
+	for para_id in paras_now.iter() {
 		let entry = current_assignments.entry(*para_id).or_default();
 		*entry += 1;
 		if *entry == 1 {
@@ -533,7 +556,7 @@ where
 		}
 	}
 
-	*group_assignment = GroupAssignments { current: para_now };
+	*group_assignment = GroupAssignments { current: paras_now };
 
 	Ok(())
 }
@@ -544,7 +567,7 @@ fn remove_outgoing(
 ) {
 	let GroupAssignments { current, .. } = per_relay_parent.assignment;
 
-	if let Some(cur) = current {
+	for cur in current {
 		if let Entry::Occupied(mut occupied) = current_assignments.entry(cur) {
 			*occupied.get_mut() -= 1;
 			if *occupied.get() == 0 {
@@ -1089,7 +1112,7 @@ where
 		peer_data.collating_para().ok_or(AdvertisementError::UndeclaredCollator)?;
 
 	// Check if this is assigned to us.
-	if assignment.current.map_or(true, |id| id != collator_para_id) {
+	if !assignment.current.contains(&collator_para_id) {
 		return Err(AdvertisementError::InvalidAssignment)
 	}
 
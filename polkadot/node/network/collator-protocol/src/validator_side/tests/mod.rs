@@ -20,7 +20,12 @@ use futures::{executor, future, Future};
 use sp_core::{crypto::Pair, Encode};
 use sp_keyring::Sr25519Keyring;
 use sp_keystore::Keystore;
-use std::{iter, sync::Arc, time::Duration};
+use std::{
+	collections::{BTreeMap, VecDeque},
+	iter,
+	sync::Arc,
+	time::Duration,
+};
 
 use polkadot_node_network_protocol::{
 	our_view,
@@ -36,7 +41,7 @@ use polkadot_node_subsystem::{
 use polkadot_node_subsystem_test_helpers as test_helpers;
 use polkadot_node_subsystem_util::{reputation::add_reputation, TimeoutExt};
 use polkadot_primitives::{
-	CandidateReceipt, CollatorPair, CoreState, GroupIndex, GroupRotationInfo, HeadData,
+	CandidateReceipt, CollatorPair, CoreIndex, CoreState, GroupIndex, GroupRotationInfo, HeadData,
 	OccupiedCore, PersistedValidationData, ScheduledCore, ValidatorId, ValidatorIndex,
 };
 use polkadot_primitives_test_helpers::{
@@ -70,6 +75,8 @@ struct TestState {
 	validator_groups: Vec<Vec<ValidatorIndex>>,
 	group_rotation_info: GroupRotationInfo,
 	cores: Vec<CoreState>,
+	/// `None` if the runtime doesn't support the claim queue API.
+	claim_queue: Option<BTreeMap<CoreIndex, VecDeque<ParaId>>>,
 }
 
 impl Default for TestState {
@@ -127,6 +134,7 @@ impl Default for TestState {
 			validator_groups,
 			group_rotation_info,
 			cores,
+			claim_queue: None,
 		}
 	}
 }
@@ -263,6 +271,15 @@ async fn respond_to_core_info_queries(
 	);
 }
 
+fn claim_queue_response(
+	test_state: &TestState,
+) -> Result<BTreeMap<CoreIndex, VecDeque<ParaId>>, RuntimeApiError> {
+	test_state
+		.claim_queue
+		.clone()
+		.ok_or(RuntimeApiError::NotSupported { runtime_api_name: "claim_queue" })
+}
+
 /// Assert that the next message is a `CandidateBacking(Second())`.
 async fn assert_candidate_backing_second(
 	virtual_overseer: &mut VirtualOverseer,
@@ -71,6 +71,15 @@ async fn assert_assign_incoming(
 			tx.send(Ok(test_state.cores.clone())).unwrap();
 		}
 	);
+
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::RuntimeApi(
+			RuntimeApiMessage::Request(parent, RuntimeApiRequest::ClaimQueue(tx))
+		) if parent == hash => {
+			tx.send(claim_queue_response(test_state)).unwrap();
+		}
+	);
 }
 
 /// Handle a view update.
@@ -486,6 +495,67 @@ fn accept_advertisements_from_implicit_view() {
 	});
 }
 
+#[test]
+fn accept_advertisements_for_upcoming_claims() {
+	let mut test_state = TestState::default();
+	let (para_a, para_b) = (test_state.chain_ids[0], test_state.chain_ids[1]);
+	// Core 1 is free, but `para_b` has a claim on it after `para_a`.
+	test_state.claim_queue = Some(BTreeMap::from([
+		(CoreIndex(0), VecDeque::from([para_a])),
+		(CoreIndex(1), VecDeque::from([para_a, para_b])),
+		(CoreIndex(2), VecDeque::from([para_b])),
+	]));
+
+	test_harness(ReputationAggregator::new(|_| true), |test_harness| async move {
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
+
+		let pair_b = CollatorPair::generate().0;
+
+		// Group rotation frequency is 1 by default, at `b` we're assigned to the second core.
+		let head_b = Hash::from_low_u64_be(128);
+		let head_b_num: u32 = 2;
+
+		update_view(&mut virtual_overseer, &test_state, vec![(head_b, head_b_num)], 1).await;
+
+		let peer_b = PeerId::random();
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_b,
+			pair_b.clone(),
+			para_b,
+			CollationVersion::V2,
+		)
+		.await;
+
+		let candidate_hash = CandidateHash::default();
+		let parent_head_data_hash = Hash::zero();
+		advertise_collation(
+			&mut virtual_overseer,
+			peer_b,
+			head_b,
+			Some((candidate_hash, parent_head_data_hash)),
+		)
+		.await;
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::CandidateBacking(
+				CandidateBackingMessage::CanSecond(request, tx),
+			) => {
+				assert_eq!(request.candidate_hash, candidate_hash);
+				assert_eq!(request.candidate_para_id, para_b);
+				assert_eq!(request.parent_head_data_hash, parent_head_data_hash);
+				tx.send(true).expect("receiving side should be alive");
+			}
+		);
+
+		assert_fetch_collation_request(&mut virtual_overseer, head_b, para_b, Some(candidate_hash))
+			.await;
+
+		virtual_overseer
+	});
+}
+
 #[test]
 fn second_multiple_candidates_per_relay_parent() {
 	let test_state = TestState::default();
@@ -230,6 +230,10 @@ pub enum Error {
 	#[error("Creating a custom database is required for validators")]
 	DatabasePathRequired,
 
+	#[cfg(feature = "full-node")]
+	#[error("BEEFY warp sync requires BEEFY validators at genesis")]
+	NoBeefyGenesisValidators,
+
 	#[cfg(feature = "full-node")]
 	#[error("Expected at least one of polkadot, kusama, westend or rococo runtime feature")]
 	NoRuntime,
@@ -429,13 +433,17 @@ fn new_partial_basics(
 		.default_heap_pages
 		.map_or(DEFAULT_HEAP_ALLOC_STRATEGY, |h| HeapAllocStrategy::Static { extra_pages: h as _ });
 
-	let executor = WasmExecutor::builder()
+	let mut executor = WasmExecutor::builder()
 		.with_execution_method(config.wasm_method)
 		.with_onchain_heap_alloc_strategy(heap_pages)
 		.with_offchain_heap_alloc_strategy(heap_pages)
 		.with_max_runtime_instances(config.max_runtime_instances)
 		.with_runtime_cache_size(config.runtime_cache_size)
-		.build();
+		.with_offchain_execution_limits(config.offchain_execution_limits);
+	if let Some(cache_path) = config.executor_cache_path() {
+		executor = executor.with_cache_path(cache_path);
+	}
+	let executor = executor.build();
 
 	let (client, backend, keystore_container, task_manager) =
 		service::new_full_parts::<Block, RuntimeApi, _>(
@@ -472,7 +480,7 @@ fn new_partial<ChainSelection>(
 		FullBackend,
 		ChainSelection,
 		sc_consensus::DefaultImportQueue<Block>,
-		sc_transaction_pool::FullPool<Block, FullClient>,
+		sc_transaction_pool::FullTransactionPool<Block, FullClient>,
 		(
 			impl Fn(
 				polkadot_rpc::DenyUnsafe,
@@ -498,7 +506,7 @@ fn new_partial<ChainSelection>(
 where
 	ChainSelection: 'static + SelectChain<Block>,
 {
-	let transaction_pool = sc_transaction_pool::BasicPool::new_full(
+	let transaction_pool = sc_transaction_pool::FullTransactionPool::new_full(
 		config.transaction_pool.clone(),
 		config.role.is_authority().into(),
 		config.prometheus_registry(),
@@ -625,6 +633,10 @@ where
 pub struct NewFullParams<OverseerGenerator: OverseerGen> {
 	pub is_parachain_node: IsParachainNode,
 	pub enable_beefy: bool,
+	/// Whether to warp sync with BEEFY justifications instead of GRANDPA ones.
+	///
+	/// The peers serving the proofs need the MMR to be indexed offchain.
+	pub beefy_warp_sync: bool,
 	/// Whether to enable the block authoring backoff on production networks
 	/// where it isn't enabled by default.
 	pub force_authoring_backoff: bool,
@@ -720,6 +732,7 @@ pub fn new_full<OverseerGenerator: OverseerGen>(
 	NewFullParams {
 		is_parachain_node,
 		enable_beefy,
+		beefy_warp_sync,
 		force_authoring_backoff,
 		jaeger_agent,
 		telemetry_worker_handle,
@@ -733,8 +746,9 @@ pub fn new_full<OverseerGenerator: OverseerGen>(
 		hwbench,
 	}: NewFullParams<OverseerGenerator>,
 ) -> Result<NewFull, Error> {
+	use beefy_primitives::BeefyApi;
 	use polkadot_node_network_protocol::request_response::IncomingRequest;
-	use sc_network_sync::warp::WarpSyncParams;
+	use sc_network_sync::warp::{WarpSyncParams, WarpSyncProvider};
 
 	let is_offchain_indexing_enabled = config.offchain_worker.indexing_enabled;
 	let role = config.role.clone();
@@ -894,11 +908,26 @@ pub fn new_full<OverseerGenerator: OverseerGen>(
 		Vec::new()
 	};
 
-	let warp_sync = Arc::new(grandpa::warp_proof::NetworkProvider::new(
-		backend.clone(),
-		import_setup.1.shared_authority_set().clone(),
-		grandpa_hard_forks,
-	));
+	let warp_sync: Arc<dyn WarpSyncProvider<Block>> = if beefy_warp_sync {
+		let genesis_authorities = client
+			.runtime_api()
+			.validator_set(genesis_hash)
+			.map_err(sp_blockchain::Error::RuntimeApiError)?
+			.ok_or(Error::NoBeefyGenesisValidators)?
+			.validators()
+			.to_vec();
+		Arc::new(beefy::warp_proof::NetworkProvider::new(
+			backend.clone(),
+			client.clone(),
+			genesis_authorities,
+		))
+	} else {
+		Arc::new(grandpa::warp_proof::NetworkProvider::new(
+			backend.clone(),
+			import_setup.1.shared_authority_set().clone(),
+			grandpa_hard_forks,
+		))
+	};
 
 	let (network, system_rpc_tx, tx_handler_controller, network_starter, sync_service) =
 		service::build_network(service::BuildNetworkParams {
@@ -0,0 +1,34 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Command line options of the availability benchmarks.
+
+use serde::{Deserialize, Serialize};
+
+/// Options of the `data-availability-read` objective.
+#[derive(Clone, Debug, clap::Parser, Serialize, Deserialize)]
+#[clap(rename_all = "kebab-case")]
+pub struct DataAvailabilityReadOptions {
+	/// Try to fetch the full available data from backers before falling back to chunks.
+	#[clap(short, long, default_value_t = false)]
+	#[serde(default)]
+	pub fetch_from_backers: bool,
+	/// Enable the availability chunk mapping node feature, so that systematic recovery is
+	/// attempted before regular chunk recovery.
+	#[clap(short, long, default_value_t = false)]
+	#[serde(default)]
+	pub systematic_recovery: bool,
+}
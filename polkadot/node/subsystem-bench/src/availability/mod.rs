@@ -0,0 +1,323 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Benchmark of the availability recovery subsystem.
+//!
+//! For every block, one candidate per core is recovered from the emulated validators. PoV sizes
+//! are sampled once per core, and candidates of the same size share their available data, so
+//! that the erasure coding of the test data does not dominate the preparation of the test.
+
+use std::{collections::HashMap, sync::Arc, time::Instant};
+
+use futures::{channel::oneshot, stream::FuturesUnordered, StreamExt};
+use parity_scale_codec::Encode;
+use sc_network::config::RequestResponseConfig;
+
+use polkadot_availability_recovery::AvailabilityRecoverySubsystem;
+use polkadot_erasure_coding::{branches, obtain_chunks_v1 as obtain_chunks};
+use polkadot_node_network_protocol::request_response::{IncomingRequest, ReqProtocolNames};
+use polkadot_node_primitives::{AvailableData, BlockData, ErasureChunk, PoV, Proof};
+use polkadot_node_subsystem::messages::{AllMessages, AvailabilityRecoveryMessage};
+use polkadot_node_subsystem_test_helpers::mock::dummy_unpin_handle;
+use polkadot_node_subsystem_util::metrics::Metrics;
+use polkadot_overseer::{dummy::dummy_overseer_builder, BlockInfo, Handle as OverseerHandle};
+use polkadot_primitives::{
+	vstaging::{node_features::FeatureIndex, NodeFeatures},
+	BlockNumber, CandidateHash, CandidateReceipt, CoreIndex, GroupIndex, Hash, HeadData,
+	Id as ParaId, PersistedValidationData, ValidatorIndex,
+};
+use polkadot_primitives_test_helpers::dummy_candidate_receipt;
+
+use crate::core::{
+	configuration::TestConfiguration,
+	environment::{AlwaysSupportsParachains, TestEnvironment, TestEnvironmentDependencies},
+	keyring::Keyring,
+	mock::{
+		av_store::{AvailabilityStoreState, MockAvailabilityStore},
+		network_bridge::{MockNetworkBridgeTx, NetworkAvailabilityState},
+		runtime_api::{MockRuntimeApi, RuntimeApiState, BACKING_GROUP_SIZE, SESSION_INDEX},
+	},
+	network::NetworkEmulator,
+};
+
+mod cli;
+pub use cli::DataAvailabilityReadOptions;
+
+const LOG_TARGET: &str = "subsystem-bench::availability";
+
+/// Deterministic genesis hash for protocol names.
+const GENESIS_HASH: Hash = Hash::repeat_byte(0xff);
+
+/// The candidates of a test and the data needed to recover them.
+pub struct TestState {
+	options: DataAvailabilityReadOptions,
+	keyring: Keyring,
+	/// One candidate per core, for every block.
+	candidates: Vec<Vec<CandidateReceipt>>,
+	/// Maps a candidate to its index in `available_data` and `chunks`.
+	candidate_hashes: HashMap<CandidateHash, usize>,
+	available_data: Arc<Vec<AvailableData>>,
+	chunks: Arc<Vec<Vec<ErasureChunk>>>,
+}
+
+impl TestState {
+	/// Generate the candidates, available data and chunks of a test.
+	pub fn new(config: &TestConfiguration, options: DataAvailabilityReadOptions) -> Self {
+		let keyring = Keyring::new(config.n_validators);
+
+		let mut available_data = Vec::new();
+		let mut chunks = Vec::new();
+		let mut erasure_roots = Vec::new();
+		let mut pov_size_to_index = HashMap::new();
+		let pov_indices = config
+			.generate_pov_sizes()
+			.into_iter()
+			.map(|pov_size| {
+				*pov_size_to_index.entry(pov_size).or_insert_with(|| {
+					let (data, data_chunks, erasure_root) =
+						generate_available_data(config.n_validators, pov_size);
+					available_data.push(data);
+					chunks.push(data_chunks);
+					erasure_roots.push(erasure_root);
+					available_data.len() - 1
+				})
+			})
+			.collect::<Vec<_>>();
+
+		gum::info!(
+			target: LOG_TARGET,
+			n_pov_sizes = available_data.len(),
+			"Generated availability test data"
+		);
+
+		let mut candidate_hashes = HashMap::new();
+		let candidates = (1..=config.num_blocks)
+			.map(|block_number| {
+				let relay_parent = block_hash(block_number as BlockNumber);
+				pov_indices
+					.iter()
+					.enumerate()
+					.map(|(core_index, pov_index)| {
+						let mut candidate = dummy_candidate_receipt(relay_parent);
+						candidate.descriptor.para_id = ParaId::from(core_index as u32);
+						candidate.descriptor.erasure_root = erasure_roots[*pov_index];
+						candidate_hashes.insert(candidate.hash(), *pov_index);
+						candidate
+					})
+					.collect()
+			})
+			.collect();
+
+		Self {
+			options,
+			keyring,
+			candidates,
+			candidate_hashes,
+			available_data: Arc::new(available_data),
+			chunks: Arc::new(chunks),
+		}
+	}
+
+	fn node_features(&self) -> NodeFeatures {
+		let mut node_features = NodeFeatures::EMPTY;
+		if self.options.systematic_recovery {
+			let feature = FeatureIndex::AvailabilityChunkMapping as usize;
+			node_features.resize(feature + 1, false);
+			node_features.set(feature, true);
+		}
+		node_features
+	}
+}
+
+fn block_hash(block_number: BlockNumber) -> Hash {
+	Hash::from_low_u64_be(block_number as u64)
+}
+
+fn generate_available_data(
+	n_validators: usize,
+	pov_size: usize,
+) -> (AvailableData, Vec<ErasureChunk>, Hash) {
+	let available_data = AvailableData {
+		validation_data: PersistedValidationData {
+			parent_head: HeadData(vec![7, 8, 9]),
+			relay_parent_number: Default::default(),
+			max_pov_size: pov_size as u32,
+			relay_parent_storage_root: Default::default(),
+		},
+		pov: Arc::new(PoV { block_data: BlockData(vec![42; pov_size]) }),
+	};
+
+	let chunks = obtain_chunks(n_validators, &available_data)
+		.expect("The number of validators is valid; qed");
+	let branches = branches(chunks.as_ref());
+	let erasure_root = branches.root();
+	let chunks = branches
+		.enumerate()
+		.map(|(index, (proof, chunk))| ErasureChunk {
+			chunk: chunk.to_vec(),
+			index: ValidatorIndex(index as _),
+			proof: Proof::try_from(proof).expect("Proofs of generated chunks are valid; qed"),
+		})
+		.collect();
+
+	(available_data, chunks, erasure_root)
+}
+
+/// Build the test environment: the availability recovery subsystem, talking to mocks of the
+/// runtime API, the availability store and the network bridge.
+///
+/// The returned request/response config has to be kept alive for the duration of the test,
+/// since dropping it closes the subsystem's incoming request channel.
+pub fn prepare_test(
+	config: TestConfiguration,
+	state: &TestState,
+	dependencies: TestEnvironmentDependencies,
+) -> (TestEnvironment, RequestResponseConfig) {
+	let (req_receiver, req_cfg) =
+		IncomingRequest::get_config_receiver(&ReqProtocolNames::new(&GENESIS_HASH, None));
+	let metrics = Metrics::register(Some(&dependencies.registry))
+		.expect("Metrics are only registered once; qed");
+	let subsystem = if state.options.fetch_from_backers {
+		AvailabilityRecoverySubsystem::with_fast_path(req_receiver, metrics)
+	} else {
+		AvailabilityRecoverySubsystem::with_chunks_only(req_receiver, metrics)
+	};
+
+	let network = NetworkEmulator::new(
+		&config,
+		&dependencies.task_manager.spawn_handle(),
+		&state.keyring.validator_authority_id(),
+	);
+
+	let runtime_api = MockRuntimeApi::new(
+		config.clone(),
+		RuntimeApiState {
+			validator_public: state.keyring.validator_public(),
+			validator_authority_id: state.keyring.validator_authority_id(),
+			node_features: state.node_features(),
+		},
+	);
+	let av_store = MockAvailabilityStore::new(AvailabilityStoreState {
+		candidate_hashes: state.candidate_hashes.clone(),
+		chunks: state.chunks.clone(),
+	});
+	let network_bridge_tx = MockNetworkBridgeTx::new(
+		network.clone(),
+		NetworkAvailabilityState {
+			candidate_hashes: state.candidate_hashes.clone(),
+			available_data: state.available_data.clone(),
+			chunks: state.chunks.clone(),
+		},
+	);
+
+	let (overseer, overseer_handle) = dummy_overseer_builder(
+		dependencies.task_manager.spawn_handle(),
+		AlwaysSupportsParachains,
+		Some(&dependencies.registry),
+	)
+	.expect("The dummy overseer builder is complete; qed")
+	.replace_runtime_api(|_| runtime_api)
+	.replace_availability_store(|_| av_store)
+	.replace_network_bridge_tx(|_| network_bridge_tx)
+	.replace_availability_recovery(|_| subsystem)
+	.build()
+	.expect("All subsystems are provided; qed");
+
+	let env = TestEnvironment::new(
+		dependencies,
+		config,
+		network,
+		overseer,
+		OverseerHandle::new(overseer_handle),
+	);
+
+	(env, req_cfg)
+}
+
+/// Recover the candidates of every block and report timings, throughput and resource usage.
+pub async fn benchmark_availability_read(env: &mut TestEnvironment, state: TestState) {
+	let config = env.config().clone();
+	let n_backing_groups = (config.n_validators / BACKING_GROUP_SIZE).max(1);
+	let test_start = Instant::now();
+	let mut recovered_bytes = 0u128;
+	let mut failed_recoveries = 0usize;
+
+	for (block_index, candidates) in state.candidates.iter().enumerate() {
+		let block_number = (block_index + 1) as BlockNumber;
+		let hash = block_hash(block_number);
+		let block_start = Instant::now();
+
+		env.import_block(BlockInfo {
+			hash,
+			parent_hash: block_hash(block_number - 1),
+			number: block_number,
+			unpin_handle: dummy_unpin_handle(hash),
+		})
+		.await;
+
+		let mut pending_recoveries = FuturesUnordered::new();
+		for (core_index, candidate) in candidates.iter().enumerate() {
+			let (tx, rx) = oneshot::channel();
+			let backing_group = state
+				.options
+				.fetch_from_backers
+				.then(|| GroupIndex((core_index % n_backing_groups) as u32));
+
+			env.send_message(AllMessages::AvailabilityRecovery(
+				AvailabilityRecoveryMessage::RecoverAvailableData(
+					candidate.clone(),
+					SESSION_INDEX,
+					backing_group,
+					Some(CoreIndex(core_index as u32)),
+					tx,
+				),
+			))
+			.await;
+			pending_recoveries.push(rx);
+		}
+
+		gum::info!(target: LOG_TARGET, block_number, n_recoveries = pending_recoveries.len(), "Recoveries launched");
+
+		while let Some(result) = pending_recoveries.next().await {
+			match result {
+				Ok(Ok(available_data)) => recovered_bytes += available_data.encoded_size() as u128,
+				Ok(Err(err)) => {
+					gum::warn!(target: LOG_TARGET, ?err, "Recovery failed");
+					failed_recoveries += 1;
+				},
+				Err(_) => {
+					gum::warn!(target: LOG_TARGET, "Recovery was canceled");
+					failed_recoveries += 1;
+				},
+			}
+		}
+
+		println!("Block #{} recovered in {}ms", block_number, block_start.elapsed().as_millis());
+	}
+
+	let num_blocks = config.num_blocks.max(1) as u128;
+	let test_duration = test_start.elapsed().as_millis();
+	println!("All {} blocks processed in {}ms", config.num_blocks, test_duration);
+	println!("Average block time: {}ms", test_duration / num_blocks);
+	println!("Throughput: {} KiB/block", recovered_bytes / 1024 / num_blocks);
+	if failed_recoveries > 0 {
+		println!("Failed recoveries: {}", failed_recoveries);
+	}
+
+	env.display_network_usage();
+	env.display_cpu_usage(&["availability-recovery"]);
+	env.stop().await;
+}
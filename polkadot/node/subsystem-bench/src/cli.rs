@@ -0,0 +1,82 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Command line options shared by all test objectives.
+
+use serde::{Deserialize, Serialize};
+
+use crate::availability::DataAvailabilityReadOptions;
+
+/// The subsystem benchmarks that can be run.
+#[derive(Clone, Debug, clap::Parser, Serialize, Deserialize)]
+#[command(rename_all = "kebab-case")]
+pub enum TestObjective {
+	/// Benchmark availability recovery.
+	DataAvailabilityRead(DataAvailabilityReadOptions),
+	/// Run the sequence of tests described by a YAML file.
+	TestSequence(TestSequenceOptions),
+}
+
+/// Options of the `test-sequence` objective.
+#[derive(Clone, Debug, clap::Parser, Serialize, Deserialize)]
+#[clap(rename_all = "kebab-case")]
+pub struct TestSequenceOptions {
+	/// Path of the YAML test sequence.
+	#[clap(short, long)]
+	pub path: String,
+}
+
+/// Test parameters which apply to every objective.
+#[derive(Clone, Debug, clap::Parser)]
+#[clap(rename_all = "kebab-case")]
+pub struct StandardTestOptions {
+	/// Number of validators in the emulated network.
+	#[clap(long, default_value_t = 500)]
+	pub n_validators: usize,
+	/// Number of availability cores.
+	#[clap(long, default_value_t = 100)]
+	pub n_cores: usize,
+	/// Minimum PoV size in KiB.
+	#[clap(long, default_value_t = 5 * 1024)]
+	pub min_pov_size: usize,
+	/// Maximum PoV size in KiB.
+	#[clap(long, default_value_t = 5 * 1024)]
+	pub max_pov_size: usize,
+	/// Number of blocks to run the test for.
+	#[clap(long, default_value_t = 1)]
+	pub num_blocks: usize,
+}
+
+/// Network emulation parameters, overriding those of the test configuration.
+#[derive(Clone, Debug, clap::Parser)]
+#[clap(rename_all = "kebab-case")]
+pub struct NetworkOptions {
+	/// Upload bandwidth of each emulated peer, in bytes per second.
+	#[clap(long)]
+	pub peer_bandwidth: Option<usize>,
+	/// Download bandwidth of our node, in bytes per second.
+	#[clap(long)]
+	pub bandwidth: Option<usize>,
+	/// Percentage of requests the emulated peers fail.
+	#[clap(long, value_parser = clap::value_parser!(u8).range(0..=100))]
+	pub peer_error: Option<u8>,
+	/// Minimum latency of emulated peers, in milliseconds.
+	#[clap(long, value_parser = clap::value_parser!(u64).range(0..=5000))]
+	pub peer_min_latency: Option<u64>,
+	/// Maximum latency of emulated peers, in milliseconds.
+	#[clap(long, value_parser = clap::value_parser!(u64).range(0..=5000))]
+	pub peer_max_latency: Option<u64>,
+}
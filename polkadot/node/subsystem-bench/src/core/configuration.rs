@@ -0,0 +1,125 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test configuration, either built from the command line or read from a YAML test sequence.
+
+use std::{path::Path, time::Duration};
+
+use color_eyre::eyre;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::cli::{StandardTestOptions, TestObjective};
+
+/// Bandwidth of emulated peers and of our node when not configured, in bytes per second.
+const DEFAULT_BANDWIDTH: usize = 50 * 1024 * 1024;
+
+fn default_bandwidth() -> usize {
+	DEFAULT_BANDWIDTH
+}
+
+/// Latency of the responses sent by emulated peers.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PeerLatency {
+	/// Minimum latency in milliseconds.
+	pub min_latency: u64,
+	/// Maximum latency in milliseconds.
+	pub max_latency: u64,
+}
+
+impl PeerLatency {
+	/// Pick a latency uniformly in `[min_latency, max_latency]`.
+	pub fn sample(&self) -> Duration {
+		let max_latency = self.max_latency.max(self.min_latency);
+		Duration::from_millis(rand::thread_rng().gen_range(self.min_latency..=max_latency))
+	}
+}
+
+/// The parameters of a single test run.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TestConfiguration {
+	/// The subsystem and scenario under test.
+	pub objective: TestObjective,
+	/// Number of validators in the emulated network.
+	pub n_validators: usize,
+	/// Number of availability cores, i.e. candidates per block.
+	pub n_cores: usize,
+	/// Minimum PoV size in KiB.
+	pub min_pov_size: usize,
+	/// Maximum PoV size in KiB.
+	pub max_pov_size: usize,
+	/// Upload bandwidth of each emulated peer, in bytes per second.
+	#[serde(default = "default_bandwidth")]
+	pub peer_bandwidth: usize,
+	/// Download bandwidth of our node, in bytes per second.
+	#[serde(default = "default_bandwidth")]
+	pub bandwidth: usize,
+	/// Optional latency of emulated peers.
+	#[serde(default)]
+	pub latency: Option<PeerLatency>,
+	/// Percentage of requests the emulated peers fail.
+	#[serde(default)]
+	pub error: usize,
+	/// Number of blocks to run the test for.
+	pub num_blocks: usize,
+}
+
+impl TestConfiguration {
+	/// Create a configuration with an ideal network for `objective`.
+	pub fn new(objective: TestObjective, options: &StandardTestOptions) -> Self {
+		Self {
+			objective,
+			n_validators: options.n_validators,
+			n_cores: options.n_cores,
+			min_pov_size: options.min_pov_size,
+			max_pov_size: options.max_pov_size,
+			peer_bandwidth: DEFAULT_BANDWIDTH,
+			bandwidth: DEFAULT_BANDWIDTH,
+			latency: None,
+			error: 0,
+			num_blocks: options.num_blocks,
+		}
+	}
+
+	/// Sample one PoV size in bytes for each core.
+	pub fn generate_pov_sizes(&self) -> Vec<usize> {
+		let mut rng = rand::thread_rng();
+		let max_pov_size = self.max_pov_size.max(self.min_pov_size);
+		(0..self.n_cores)
+			.map(|_| rng.gen_range(self.min_pov_size..=max_pov_size) * 1024)
+			.collect()
+	}
+}
+
+/// A list of test configurations, run one after another.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TestSequence {
+	#[serde(rename = "TestConfiguration")]
+	test_configurations: Vec<TestConfiguration>,
+}
+
+impl TestSequence {
+	/// Read a test sequence from a YAML file.
+	pub fn new_from_file(path: &Path) -> eyre::Result<Self> {
+		let contents = std::fs::read_to_string(path)?;
+		Ok(serde_yaml::from_str(&contents)?)
+	}
+
+	/// The configurations of the sequence, in order.
+	pub fn into_vec(self) -> Vec<TestConfiguration> {
+		self.test_configurations
+	}
+}
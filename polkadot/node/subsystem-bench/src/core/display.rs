@@ -0,0 +1,170 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Reporting of test configurations and of the metrics collected during a test.
+
+use std::fmt::{Display, Formatter};
+
+use prometheus_endpoint::{
+	prometheus::proto::{Metric, MetricType},
+	Registry,
+};
+
+use super::configuration::TestConfiguration;
+
+const LOG_TARGET: &str = "subsystem-bench::display";
+
+/// A single sample of a prometheus metric.
+#[derive(Clone, Debug)]
+pub struct TestMetric {
+	name: String,
+	label_names: Vec<String>,
+	label_values: Vec<String>,
+	value: f64,
+}
+
+impl TestMetric {
+	fn new(name: String, metric: &Metric, value: f64) -> Self {
+		let (label_names, label_values) = metric
+			.get_label()
+			.iter()
+			.map(|label| (label.get_name().to_owned(), label.get_value().to_owned()))
+			.unzip();
+
+		Self { name, label_names, label_values, value }
+	}
+
+	fn label_value(&self, label_name: &str) -> Option<&str> {
+		self.label_names
+			.iter()
+			.position(|name| name == label_name)
+			.map(|index| self.label_values[index].as_str())
+	}
+}
+
+impl Display for TestMetric {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let labels = self
+			.label_names
+			.iter()
+			.zip(self.label_values.iter())
+			.map(|(name, value)| format!("{}={}", name, value))
+			.collect::<Vec<_>>()
+			.join(", ");
+		write!(f, "{}{{{}}} = {}", self.name, labels, self.value)
+	}
+}
+
+/// A set of metric samples, as gathered from a registry.
+#[derive(Clone, Debug, Default)]
+pub struct MetricCollection(Vec<TestMetric>);
+
+impl MetricCollection {
+	/// All samples of the collection.
+	pub fn all(&self) -> &[TestMetric] {
+		&self.0
+	}
+
+	/// Sum the values of all samples of the metric `name`.
+	pub fn sum_by(&self, name: &str) -> f64 {
+		self.0
+			.iter()
+			.filter(|metric| metric.name == name)
+			.map(|metric| metric.value)
+			.sum()
+	}
+
+	/// The samples whose label `label_name` has the value `label_value`.
+	pub fn subset_with_label_value(&self, label_name: &str, label_value: &str) -> Self {
+		Self(
+			self.0
+				.iter()
+				.filter(|metric| metric.label_value(label_name) == Some(label_value))
+				.cloned()
+				.collect(),
+		)
+	}
+}
+
+impl Display for MetricCollection {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		for metric in self.all() {
+			writeln!(f, "{}", metric)?;
+		}
+		Ok(())
+	}
+}
+
+/// Gather all counters, gauges and histograms of `registry`.
+///
+/// Histograms are flattened into their `_sum` and `_count` samples.
+pub fn parse_metrics(registry: &Registry) -> MetricCollection {
+	let mut metrics = Vec::new();
+	for family in registry.gather() {
+		let name = family.get_name();
+		for metric in family.get_metric() {
+			match family.get_field_type() {
+				MetricType::COUNTER => metrics.push(TestMetric::new(
+					name.into(),
+					metric,
+					metric.get_counter().get_value(),
+				)),
+				MetricType::GAUGE => metrics.push(TestMetric::new(
+					name.into(),
+					metric,
+					metric.get_gauge().get_value(),
+				)),
+				MetricType::HISTOGRAM => {
+					let histogram = metric.get_histogram();
+					metrics.push(TestMetric::new(
+						format!("{}_sum", name),
+						metric,
+						histogram.get_sample_sum(),
+					));
+					metrics.push(TestMetric::new(
+						format!("{}_count", name),
+						metric,
+						histogram.get_sample_count() as f64,
+					));
+				},
+				_ => {},
+			}
+		}
+	}
+
+	MetricCollection(metrics)
+}
+
+/// Log the parameters of a test run.
+pub fn display_configuration(config: &TestConfiguration) {
+	gum::info!(target: LOG_TARGET, "{:?}", config.objective);
+	gum::info!(
+		target: LOG_TARGET,
+		n_validators = config.n_validators,
+		n_cores = config.n_cores,
+		pov_size = ?(config.min_pov_size, config.max_pov_size),
+		num_blocks = config.num_blocks,
+		"Test parameters"
+	);
+	gum::info!(
+		target: LOG_TARGET,
+		peer_bandwidth = config.peer_bandwidth,
+		bandwidth = config.bandwidth,
+		latency = ?config.latency,
+		error = config.error,
+		"Network emulation"
+	);
+}
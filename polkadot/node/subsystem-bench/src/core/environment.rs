@@ -0,0 +1,185 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The environment a subsystem under test runs in.
+
+use std::time::Duration;
+
+use polkadot_node_subsystem::messages::AllMessages;
+use polkadot_node_subsystem_util::TimeoutExt;
+use polkadot_overseer::{
+	BlockInfo, Handle as OverseerHandle, HeadSupportsParachains, Overseer, SpawnGlue,
+};
+use polkadot_primitives::Hash;
+use prometheus_endpoint::Registry;
+use sc_service::{SpawnTaskHandle, TaskManager};
+
+use super::{configuration::TestConfiguration, display::parse_metrics, network::NetworkEmulator};
+
+const LOG_TARGET: &str = "subsystem-bench::environment";
+
+/// How long sending a message to the overseer may take before the test is considered stuck.
+const MAX_TIME_OF_FLIGHT: Duration = Duration::from_millis(5000);
+
+/// Claims that every block supports parachains.
+pub struct AlwaysSupportsParachains;
+
+#[async_trait::async_trait]
+impl HeadSupportsParachains for AlwaysSupportsParachains {
+	async fn head_supports_parachains(&self, _head: &Hash) -> bool {
+		true
+	}
+}
+
+/// The resources a test runs on.
+///
+/// Subsystems are spawned by the task manager, which reports the polling time of every task to
+/// the registry, labelled with the subsystem's name. This is what CPU usage is measured with.
+pub struct TestEnvironmentDependencies {
+	/// The registry every metric of the test is registered with.
+	pub registry: Registry,
+	/// Spawns the overseer, the subsystems and the emulated peers.
+	pub task_manager: TaskManager,
+	/// The runtime the test runs on.
+	pub runtime: tokio::runtime::Runtime,
+}
+
+impl Default for TestEnvironmentDependencies {
+	fn default() -> Self {
+		let runtime = tokio::runtime::Builder::new_multi_thread()
+			.thread_name("subsystem-bench")
+			.enable_all()
+			.thread_stack_size(3 * 1024 * 1024)
+			.build()
+			.expect("Failed to create the tokio runtime");
+		let registry = Registry::new();
+		let task_manager = TaskManager::new(runtime.handle().clone(), Some(&registry))
+			.expect("Failed to create the task manager");
+
+		Self { registry, task_manager, runtime }
+	}
+}
+
+/// A running overseer with the subsystem under test and the mocks it talks to.
+pub struct TestEnvironment {
+	dependencies: TestEnvironmentDependencies,
+	runtime_handle: tokio::runtime::Handle,
+	overseer_handle: OverseerHandle,
+	config: TestConfiguration,
+	network: NetworkEmulator,
+}
+
+impl TestEnvironment {
+	/// Spawn `overseer` and wrap it in a test environment.
+	pub fn new(
+		dependencies: TestEnvironmentDependencies,
+		config: TestConfiguration,
+		network: NetworkEmulator,
+		overseer: Overseer<SpawnGlue<SpawnTaskHandle>, AlwaysSupportsParachains>,
+		overseer_handle: OverseerHandle,
+	) -> Self {
+		let runtime_handle = dependencies.runtime.handle().clone();
+		dependencies.task_manager.spawn_essential_handle().spawn_blocking(
+			"overseer",
+			None,
+			overseer.run(),
+		);
+
+		Self { dependencies, runtime_handle, overseer_handle, config, network }
+	}
+
+	/// The configuration of the test.
+	pub fn config(&self) -> &TestConfiguration {
+		&self.config
+	}
+
+	/// The registry metrics are collected in.
+	pub fn registry(&self) -> &Registry {
+		&self.dependencies.registry
+	}
+
+	/// A handle to the runtime the test runs on.
+	pub fn runtime(&self) -> tokio::runtime::Handle {
+		self.runtime_handle.clone()
+	}
+
+	/// Notify the subsystems about a new block.
+	pub async fn import_block(&mut self, block: BlockInfo) {
+		self.overseer_handle
+			.block_imported(block)
+			.timeout(MAX_TIME_OF_FLIGHT)
+			.await
+			.unwrap_or_else(|| {
+				panic!("{}ms maximum time of flight breached", MAX_TIME_OF_FLIGHT.as_millis())
+			});
+	}
+
+	/// Send a message to the subsystems.
+	pub async fn send_message(&mut self, msg: AllMessages) {
+		self.overseer_handle
+			.send_msg(msg, LOG_TARGET)
+			.timeout(MAX_TIME_OF_FLIGHT)
+			.await
+			.unwrap_or_else(|| {
+				panic!("{}ms maximum time of flight breached", MAX_TIME_OF_FLIGHT.as_millis())
+			});
+	}
+
+	/// Stop the overseer and all subsystems.
+	pub async fn stop(&mut self) {
+		self.overseer_handle.stop().await;
+	}
+
+	/// Print the traffic between our node and the emulated peers.
+	pub fn display_network_usage(&self) {
+		let (sent, received) =
+			self.network.stats().iter().fold((0, 0), |(sent, received), stats| {
+				(sent + stats.sent(), received + stats.received())
+			});
+		let num_blocks = self.config.num_blocks.max(1) as u64;
+
+		println!(
+			"Network usage: sent {} KiB, received {} KiB ({} KiB / {} KiB per block)",
+			sent / 1024,
+			received / 1024,
+			sent / 1024 / num_blocks,
+			received / 1024 / num_blocks,
+		);
+	}
+
+	/// Print the CPU time spent by each of `subsystems_under_test`.
+	pub fn display_cpu_usage(&self, subsystems_under_test: &[&str]) {
+		let metrics = parse_metrics(self.registry());
+		let num_blocks = self.config.num_blocks.max(1) as f64;
+
+		for subsystem in subsystems_under_test {
+			let total_cpu = metrics
+				.subset_with_label_value("task_group", subsystem)
+				.sum_by("substrate_tasks_polling_duration_sum");
+			println!(
+				"{} CPU usage: {:.3}s ({:.3}s per block)",
+				subsystem,
+				total_cpu,
+				total_cpu / num_blocks,
+			);
+		}
+
+		let network_cpu = metrics
+			.subset_with_label_value("task_group", "test-environment")
+			.sum_by("substrate_tasks_polling_duration_sum");
+		println!("Network emulation CPU usage: {:.3}s", network_cpu);
+	}
+}
@@ -0,0 +1,50 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Deterministically generated validator keys for the emulated network.
+
+use polkadot_primitives::{AuthorityDiscoveryId, ValidatorId};
+use sp_core::{sr25519, Pair};
+
+/// The keys of every validator taking part in a test.
+#[derive(Clone)]
+pub struct Keyring {
+	pairs: Vec<sr25519::Pair>,
+}
+
+impl Keyring {
+	/// Derive `n_validators` key pairs from `//Node<index>` seeds.
+	pub fn new(n_validators: usize) -> Self {
+		let pairs = (0..n_validators)
+			.map(|index| {
+				sr25519::Pair::from_string(&format!("//Node{}", index), None)
+					.expect("Derivation paths are valid; qed")
+			})
+			.collect();
+
+		Self { pairs }
+	}
+
+	/// The validator ids, in validator index order.
+	pub fn validator_public(&self) -> Vec<ValidatorId> {
+		self.pairs.iter().map(|pair| pair.public().into()).collect()
+	}
+
+	/// The authority discovery ids, in validator index order.
+	pub fn validator_authority_id(&self) -> Vec<AuthorityDiscoveryId> {
+		self.pairs.iter().map(|pair| pair.public().into()).collect()
+	}
+}
@@ -0,0 +1,110 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A mocked availability store, serving the chunks generated for the test.
+
+use std::{collections::HashMap, sync::Arc};
+
+use futures::FutureExt;
+
+use polkadot_node_primitives::ErasureChunk;
+use polkadot_node_subsystem::{
+	messages::AvailabilityStoreMessage, overseer, FromOrchestra, OverseerSignal, SpawnedSubsystem,
+	SubsystemError,
+};
+use polkadot_primitives::CandidateHash;
+
+const LOG_TARGET: &str = "subsystem-bench::av-store-mock";
+
+/// The chunks of every candidate of the test.
+#[derive(Clone, Default)]
+pub struct AvailabilityStoreState {
+	/// Maps a candidate to its index in `chunks`.
+	pub candidate_hashes: HashMap<CandidateHash, usize>,
+	/// Erasure chunks, indexed by candidate and then by chunk index.
+	pub chunks: Arc<Vec<Vec<ErasureChunk>>>,
+}
+
+/// A mocked availability store.
+///
+/// It never holds the full available data of a candidate and holds none of its chunks locally,
+/// so everything has to be fetched from the network. It still knows the chunk size of every
+/// candidate, like a node that has received its own chunk via availability distribution.
+pub struct MockAvailabilityStore {
+	state: AvailabilityStoreState,
+}
+
+impl MockAvailabilityStore {
+	/// Create a new mock serving `state`.
+	pub fn new(state: AvailabilityStoreState) -> Self {
+		Self { state }
+	}
+
+	fn chunks(&self, candidate_hash: &CandidateHash) -> Option<&Vec<ErasureChunk>> {
+		self.state
+			.candidate_hashes
+			.get(candidate_hash)
+			.and_then(|index| self.state.chunks.get(*index))
+	}
+}
+
+#[overseer::subsystem(AvailabilityStore, error=SubsystemError, prefix=self::overseer)]
+impl<Context> MockAvailabilityStore {
+	fn start(self, ctx: Context) -> SpawnedSubsystem {
+		let future = self.run(ctx).map(|_| Ok(())).boxed();
+
+		SpawnedSubsystem { name: "test-environment", future }
+	}
+}
+
+#[overseer::contextbounds(AvailabilityStore, prefix = self::overseer)]
+impl MockAvailabilityStore {
+	async fn run<Context>(self, mut ctx: Context) {
+		loop {
+			let msg = match ctx.recv().await {
+				Ok(FromOrchestra::Signal(OverseerSignal::Conclude)) | Err(_) => return,
+				Ok(FromOrchestra::Signal(_)) => continue,
+				Ok(FromOrchestra::Communication { msg }) => msg,
+			};
+
+			match msg {
+				AvailabilityStoreMessage::QueryAvailableData(_, tx) => {
+					let _ = tx.send(None);
+				},
+				AvailabilityStoreMessage::QueryAllChunks(_, tx) => {
+					let _ = tx.send(Vec::new());
+				},
+				AvailabilityStoreMessage::QueryChunkSize(candidate_hash, tx) => {
+					let chunk_size = self
+						.chunks(&candidate_hash)
+						.and_then(|chunks| chunks.first())
+						.map(|chunk| chunk.chunk.len());
+					let _ = tx.send(chunk_size);
+				},
+				AvailabilityStoreMessage::QueryChunk(candidate_hash, index, tx) => {
+					let chunk = self
+						.chunks(&candidate_hash)
+						.and_then(|chunks| chunks.get(index.0 as usize))
+						.cloned();
+					let _ = tx.send(chunk);
+				},
+				msg => {
+					gum::debug!(target: LOG_TARGET, ?msg, "Ignoring message");
+				},
+			}
+		}
+	}
+}
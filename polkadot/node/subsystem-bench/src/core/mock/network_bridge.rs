@@ -0,0 +1,198 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A mocked network bridge, routing the requests of the subsystem under test to the emulated
+//! peers.
+
+use std::{collections::HashMap, sync::Arc};
+
+use futures::FutureExt;
+use parity_scale_codec::Encode;
+use sc_network::{OutboundFailure, RequestFailure};
+
+use polkadot_node_network_protocol::request_response::{
+	v1::{AvailableDataFetchingResponse, ChunkFetchingResponse, ChunkResponse},
+	Recipient, Requests,
+};
+use polkadot_node_primitives::{AvailableData, ErasureChunk};
+use polkadot_node_subsystem::{
+	messages::NetworkBridgeTxMessage, overseer, FromOrchestra, OverseerSignal, SpawnedSubsystem,
+	SubsystemError,
+};
+use polkadot_primitives::{AuthorityDiscoveryId, CandidateHash};
+
+use crate::core::network::{NetworkAction, NetworkEmulator};
+
+const LOG_TARGET: &str = "subsystem-bench::network-bridge-tx-mock";
+
+/// The data every emulated peer can serve.
+#[derive(Clone, Default)]
+pub struct NetworkAvailabilityState {
+	/// Maps a candidate to its index in `available_data` and `chunks`.
+	pub candidate_hashes: HashMap<CandidateHash, usize>,
+	/// The full available data of every candidate.
+	pub available_data: Arc<Vec<AvailableData>>,
+	/// Erasure chunks, indexed by candidate and then by chunk index.
+	pub chunks: Arc<Vec<Vec<ErasureChunk>>>,
+}
+
+impl NetworkAvailabilityState {
+	fn chunk_response(
+		&self,
+		candidate_hash: &CandidateHash,
+		index: usize,
+	) -> ChunkFetchingResponse {
+		self.candidate_hashes
+			.get(candidate_hash)
+			.and_then(|candidate| self.chunks.get(*candidate))
+			.and_then(|chunks| chunks.get(index))
+			.map(|chunk| ChunkResponse::from(chunk.clone()))
+			.into()
+	}
+
+	fn available_data_response(
+		&self,
+		candidate_hash: &CandidateHash,
+	) -> AvailableDataFetchingResponse {
+		self.candidate_hashes
+			.get(candidate_hash)
+			.and_then(|candidate| self.available_data.get(*candidate))
+			.cloned()
+			.into()
+	}
+}
+
+/// A mocked network bridge.
+///
+/// Every request is answered by the emulated peer it is addressed to, which fails it with the
+/// configured error rate.
+pub struct MockNetworkBridgeTx {
+	network: NetworkEmulator,
+	availability: NetworkAvailabilityState,
+}
+
+impl MockNetworkBridgeTx {
+	/// Create a new mock answering requests with `availability` via `network`.
+	pub fn new(network: NetworkEmulator, availability: NetworkAvailabilityState) -> Self {
+		Self { network, availability }
+	}
+
+	/// Turn `request` into the action of the peer it is addressed to.
+	fn respond_to_request(&self, request: Requests) -> Option<NetworkAction> {
+		let (peer, request_size, response, pending_response) = match request {
+			Requests::ChunkFetchingV1(request) => {
+				let response = self.availability.chunk_response(
+					&request.payload.candidate_hash,
+					request.payload.index.0 as usize,
+				);
+				(
+					request.peer,
+					request.payload.encoded_size(),
+					response.encode(),
+					request.pending_response,
+				)
+			},
+			Requests::AvailableDataFetchingV1(request) => {
+				let response =
+					self.availability.available_data_response(&request.payload.candidate_hash);
+				(
+					request.peer,
+					request.payload.encoded_size(),
+					response.encode(),
+					request.pending_response,
+				)
+			},
+			request => {
+				gum::warn!(
+					target: LOG_TARGET,
+					protocol = ?request.get_protocol(),
+					"Request not supported by the emulated network"
+				);
+				return None
+			},
+		};
+
+		let Recipient::Authority(peer) = peer else {
+			gum::warn!(target: LOG_TARGET, ?peer, "Requests can only be sent to authorities");
+			return None
+		};
+
+		let Some(stats) = self.network.peer_stats(&peer) else {
+			gum::warn!(target: LOG_TARGET, ?peer, "Request for unknown peer");
+			return None
+		};
+
+		// Traffic is accounted from the point of view of our node: the request is sent right
+		// away, the response is received once the peer delivers it.
+		stats.inc_sent(request_size);
+
+		if self.network.should_fail() {
+			return Some(Self::failed_request(peer, pending_response))
+		}
+
+		let size = response.len();
+		let run = async move {
+			stats.inc_received(size);
+			let _ = pending_response.send(Ok(response));
+		};
+		Some(NetworkAction::new(peer, size, run.boxed()))
+	}
+
+	fn failed_request(
+		peer: AuthorityDiscoveryId,
+		pending_response: futures::channel::oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+	) -> NetworkAction {
+		let run = async move {
+			let _ = pending_response
+				.send(Err(RequestFailure::Network(OutboundFailure::ConnectionClosed)));
+		};
+		NetworkAction::new(peer, 0, run.boxed())
+	}
+}
+
+#[overseer::subsystem(NetworkBridgeTx, error=SubsystemError, prefix=self::overseer)]
+impl<Context> MockNetworkBridgeTx {
+	fn start(self, ctx: Context) -> SpawnedSubsystem {
+		let future = self.run(ctx).map(|_| Ok(())).boxed();
+
+		SpawnedSubsystem { name: "test-environment", future }
+	}
+}
+
+#[overseer::contextbounds(NetworkBridgeTx, prefix = self::overseer)]
+impl MockNetworkBridgeTx {
+	async fn run<Context>(self, mut ctx: Context) {
+		loop {
+			let msg = match ctx.recv().await {
+				Ok(FromOrchestra::Signal(OverseerSignal::Conclude)) | Err(_) => return,
+				Ok(FromOrchestra::Signal(_)) => continue,
+				Ok(FromOrchestra::Communication { msg }) => msg,
+			};
+
+			match msg {
+				NetworkBridgeTxMessage::SendRequests(requests, _if_disconnected) =>
+					for request in requests {
+						if let Some(action) = self.respond_to_request(request) {
+							self.network.submit_peer_action(action);
+						}
+					},
+				msg => {
+					gum::debug!(target: LOG_TARGET, ?msg, "Ignoring message");
+				},
+			}
+		}
+	}
+}
@@ -0,0 +1,125 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A mocked runtime API, answering the session related requests of the subsystem under test.
+
+use futures::FutureExt;
+
+use polkadot_node_subsystem::{
+	messages::{RuntimeApiMessage, RuntimeApiRequest},
+	overseer, FromOrchestra, OverseerSignal, SpawnedSubsystem, SubsystemError,
+};
+use polkadot_primitives::{
+	vstaging::NodeFeatures, AuthorityDiscoveryId, GroupIndex, IndexedVec, SessionIndex,
+	SessionInfo, ValidatorId, ValidatorIndex,
+};
+
+use crate::core::configuration::TestConfiguration;
+
+const LOG_TARGET: &str = "subsystem-bench::runtime-api-mock";
+
+/// Validators are split into backing groups of this size.
+const BACKING_GROUP_SIZE: usize = 5;
+
+/// The session every block of the test belongs to.
+pub const SESSION_INDEX: SessionIndex = 1;
+
+/// The session state the mocked runtime API serves.
+#[derive(Clone)]
+pub struct RuntimeApiState {
+	/// The validator ids, in validator index order.
+	pub validator_public: Vec<ValidatorId>,
+	/// The authority discovery ids, in validator index order.
+	pub validator_authority_id: Vec<AuthorityDiscoveryId>,
+	/// Node features enabled in the session.
+	pub node_features: NodeFeatures,
+}
+
+/// A mocked runtime API.
+pub struct MockRuntimeApi {
+	state: RuntimeApiState,
+	config: TestConfiguration,
+}
+
+impl MockRuntimeApi {
+	/// Create a new mock serving `state`.
+	pub fn new(config: TestConfiguration, state: RuntimeApiState) -> Self {
+		Self { state, config }
+	}
+
+	fn session_info(&self) -> SessionInfo {
+		let validator_indices = (0..self.config.n_validators)
+			.map(|index| ValidatorIndex(index as _))
+			.collect::<Vec<_>>();
+		let validator_groups = validator_indices
+			.chunks(BACKING_GROUP_SIZE)
+			.map(|group| group.to_vec())
+			.collect::<Vec<_>>();
+
+		SessionInfo {
+			validators: self.state.validator_public.clone().into(),
+			discovery_keys: self.state.validator_authority_id.clone(),
+			validator_groups: IndexedVec::<GroupIndex, Vec<ValidatorIndex>>::from(validator_groups),
+			assignment_keys: vec![],
+			n_cores: self.config.n_cores as u32,
+			zeroth_delay_tranche_width: 0,
+			relay_vrf_modulo_samples: 0,
+			n_delay_tranches: 0,
+			no_show_slots: 0,
+			needed_approvals: 0,
+			active_validator_indices: validator_indices,
+			dispute_period: 6,
+			random_seed: [0u8; 32],
+		}
+	}
+}
+
+#[overseer::subsystem(RuntimeApi, error=SubsystemError, prefix=self::overseer)]
+impl<Context> MockRuntimeApi {
+	fn start(self, ctx: Context) -> SpawnedSubsystem {
+		let future = self.run(ctx).map(|_| Ok(())).boxed();
+
+		SpawnedSubsystem { name: "test-environment", future }
+	}
+}
+
+#[overseer::contextbounds(RuntimeApi, prefix = self::overseer)]
+impl MockRuntimeApi {
+	async fn run<Context>(self, mut ctx: Context) {
+		loop {
+			let msg = match ctx.recv().await {
+				Ok(FromOrchestra::Signal(OverseerSignal::Conclude)) | Err(_) => return,
+				Ok(FromOrchestra::Signal(_)) => continue,
+				Ok(FromOrchestra::Communication { msg }) => msg,
+			};
+
+			match msg {
+				RuntimeApiMessage::Request(_, RuntimeApiRequest::SessionIndexForChild(tx)) => {
+					let _ = tx.send(Ok(SESSION_INDEX));
+				},
+				RuntimeApiMessage::Request(_, RuntimeApiRequest::SessionInfo(_, tx)) => {
+					let _ = tx.send(Ok(Some(self.session_info())));
+				},
+				RuntimeApiMessage::Request(_, RuntimeApiRequest::NodeFeatures(_, tx)) => {
+					let _ = tx.send(Ok(self.state.node_features.clone()));
+				},
+				msg => {
+					gum::debug!(target: LOG_TARGET, ?msg, "Ignoring request");
+				},
+			}
+		}
+	}
+}
@@ -0,0 +1,256 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Emulation of the peers our node talks to.
+//!
+//! Every validator of the test is backed by an emulated peer task which executes the
+//! [`NetworkAction`]s submitted to it in order. Before running an action the peer spends the
+//! action's size from its own upload budget and from the download budget of our node, then waits
+//! for a randomly sampled latency, so that bandwidth, latency and error rate all show up in the
+//! subsystem's timings.
+
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::{Duration, Instant},
+};
+
+use futures::future::BoxFuture;
+use rand::Rng;
+use sc_service::SpawnTaskHandle;
+use tokio::sync::{mpsc, Mutex};
+
+use polkadot_primitives::AuthorityDiscoveryId;
+
+use super::configuration::{PeerLatency, TestConfiguration};
+
+const LOG_TARGET: &str = "subsystem-bench::network";
+
+/// How often rate limiters are refilled.
+const TICK_DURATION: Duration = Duration::from_millis(10);
+const TICKS_PER_SECOND: usize = 100;
+
+/// A token bucket limiting throughput to a given number of bytes per second.
+pub struct RateLimit {
+	credits_per_tick: usize,
+	credits: usize,
+	last_refill: Instant,
+}
+
+impl RateLimit {
+	/// Create a rate limiter for `bandwidth` bytes per second.
+	pub fn new(bandwidth: usize) -> Self {
+		let credits_per_tick = (bandwidth / TICKS_PER_SECOND).max(1);
+		Self { credits_per_tick, credits: credits_per_tick, last_refill: Instant::now() }
+	}
+
+	fn refill(&mut self) {
+		let ticks = (self.last_refill.elapsed().as_millis() / TICK_DURATION.as_millis()) as u32;
+		if ticks > 0 {
+			// Unused credits do not accumulate beyond a single tick.
+			self.credits = self.credits_per_tick;
+			self.last_refill += TICK_DURATION * ticks;
+		}
+	}
+
+	/// Wait until `amount` bytes fit in the bandwidth budget.
+	pub async fn reap(&mut self, amount: usize) {
+		let mut remaining = amount;
+		loop {
+			self.refill();
+			let taken = remaining.min(self.credits);
+			self.credits -= taken;
+			remaining -= taken;
+
+			if remaining == 0 {
+				return
+			}
+			tokio::time::sleep(TICK_DURATION).await;
+		}
+	}
+}
+
+/// Work an emulated peer does on behalf of our node: sending `size` bytes and then running
+/// `run`, which typically answers a pending request.
+pub struct NetworkAction {
+	peer: AuthorityDiscoveryId,
+	size: usize,
+	run: BoxFuture<'static, ()>,
+}
+
+impl NetworkAction {
+	/// Create a new action for `peer`.
+	pub fn new(peer: AuthorityDiscoveryId, size: usize, run: BoxFuture<'static, ()>) -> Self {
+		Self { peer, size, run }
+	}
+
+	/// The peer executing this action.
+	pub fn peer(&self) -> &AuthorityDiscoveryId {
+		&self.peer
+	}
+}
+
+/// Traffic between our node and an emulated peer.
+#[derive(Default)]
+pub struct PeerEmulatorStats {
+	/// Bytes our node sent to the peer.
+	tx_bytes_total: AtomicU64,
+	/// Bytes our node received from the peer.
+	rx_bytes_total: AtomicU64,
+}
+
+impl PeerEmulatorStats {
+	/// Account for `bytes` sent by our node to the peer.
+	pub fn inc_sent(&self, bytes: usize) {
+		self.tx_bytes_total.fetch_add(bytes as u64, Ordering::Relaxed);
+	}
+
+	/// Account for `bytes` received by our node from the peer.
+	pub fn inc_received(&self, bytes: usize) {
+		self.rx_bytes_total.fetch_add(bytes as u64, Ordering::Relaxed);
+	}
+
+	/// Total bytes sent to the peer.
+	pub fn sent(&self) -> u64 {
+		self.tx_bytes_total.load(Ordering::Relaxed)
+	}
+
+	/// Total bytes received from the peer.
+	pub fn received(&self) -> u64 {
+		self.rx_bytes_total.load(Ordering::Relaxed)
+	}
+}
+
+fn spawn_peer(
+	spawn_task_handle: &SpawnTaskHandle,
+	bandwidth: usize,
+	latency: Option<PeerLatency>,
+	ingress: Arc<Mutex<RateLimit>>,
+) -> mpsc::UnboundedSender<NetworkAction> {
+	let (actions_tx, mut actions_rx) = mpsc::unbounded_channel::<NetworkAction>();
+	let latency_spawn_handle = spawn_task_handle.clone();
+
+	spawn_task_handle.spawn("peer-emulator", Some("test-environment"), async move {
+		let mut egress = RateLimit::new(bandwidth);
+		while let Some(action) = actions_rx.recv().await {
+			let NetworkAction { size, run, .. } = action;
+			egress.reap(size).await;
+			ingress.lock().await.reap(size).await;
+
+			match latency.as_ref().map(PeerLatency::sample) {
+				Some(latency) => latency_spawn_handle.spawn(
+					"peer-emulator-latency",
+					Some("test-environment"),
+					async move {
+						tokio::time::sleep(latency).await;
+						run.await;
+					},
+				),
+				None => run.await,
+			}
+		}
+	});
+
+	actions_tx
+}
+
+/// The emulated network: one peer per validator of the test.
+#[derive(Clone)]
+pub struct NetworkEmulator {
+	peers: Vec<mpsc::UnboundedSender<NetworkAction>>,
+	stats: Vec<Arc<PeerEmulatorStats>>,
+	peer_indices: Arc<HashMap<AuthorityDiscoveryId, usize>>,
+	error: usize,
+}
+
+impl NetworkEmulator {
+	/// Spawn one emulated peer for each of `authorities`.
+	pub fn new(
+		config: &TestConfiguration,
+		spawn_task_handle: &SpawnTaskHandle,
+		authorities: &[AuthorityDiscoveryId],
+	) -> Self {
+		gum::info!(target: LOG_TARGET, n_peers = authorities.len(), "Initializing network emulation");
+
+		let ingress = Arc::new(Mutex::new(RateLimit::new(config.bandwidth)));
+		let stats: Vec<_> =
+			authorities.iter().map(|_| Arc::new(PeerEmulatorStats::default())).collect();
+		let peers = authorities
+			.iter()
+			.map(|_| {
+				spawn_peer(
+					spawn_task_handle,
+					config.peer_bandwidth,
+					config.latency.clone(),
+					ingress.clone(),
+				)
+			})
+			.collect();
+		let peer_indices = authorities
+			.iter()
+			.enumerate()
+			.map(|(index, authority)| (authority.clone(), index))
+			.collect();
+
+		Self { peers, stats, peer_indices: Arc::new(peer_indices), error: config.error }
+	}
+
+	/// Queue `action` on the peer it belongs to.
+	pub fn submit_peer_action(&self, action: NetworkAction) {
+		let Some(&index) = self.peer_indices.get(action.peer()) else {
+			gum::warn!(target: LOG_TARGET, peer = ?action.peer(), "Action for unknown peer");
+			return
+		};
+
+		if self.peers[index].send(action).is_err() {
+			gum::warn!(target: LOG_TARGET, index, "Emulated peer is gone");
+		}
+	}
+
+	/// The traffic statistics of `peer`, to account for the bytes exchanged with it.
+	pub fn peer_stats(&self, peer: &AuthorityDiscoveryId) -> Option<Arc<PeerEmulatorStats>> {
+		self.peer_indices.get(peer).map(|&index| self.stats[index].clone())
+	}
+
+	/// Whether the next request should fail, according to the configured error rate.
+	pub fn should_fail(&self) -> bool {
+		self.error > 0 && rand::thread_rng().gen_range(0..100) < self.error
+	}
+
+	/// Per peer traffic statistics, in validator index order.
+	pub fn stats(&self) -> &[Arc<PeerEmulatorStats>] {
+		&self.stats
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn rate_limit_throttles_to_bandwidth() {
+		// 100 KiB/s, so reaping 50 KiB has to take about half a second.
+		let mut rate_limit = RateLimit::new(100 * 1024);
+		let start = Instant::now();
+
+		rate_limit.reap(50 * 1024).await;
+
+		assert!(start.elapsed() >= Duration::from_millis(400));
+	}
+}
@@ -0,0 +1,159 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A tool for running subsystem benchmarks designed for development and CI regression testing.
+
+use std::path::Path;
+
+use clap::Parser;
+use color_eyre::eyre;
+
+pub(crate) mod availability;
+pub(crate) mod cli;
+pub(crate) mod core;
+
+use crate::core::{
+	configuration::{PeerLatency, TestConfiguration, TestSequence},
+	display::display_configuration,
+	environment::TestEnvironmentDependencies,
+};
+use cli::{NetworkOptions, StandardTestOptions, TestObjective};
+
+const LOG_TARGET: &str = "subsystem-bench";
+
+#[derive(Debug, Parser)]
+#[command(about = "Benchmark a polkadot node subsystem with emulated peers.", version)]
+#[allow(missing_docs)]
+struct BenchCli {
+	#[command(subcommand)]
+	pub objective: TestObjective,
+	#[clap(flatten)]
+	pub standard_configuration: StandardTestOptions,
+	#[clap(flatten)]
+	pub network: NetworkOptions,
+}
+
+impl BenchCli {
+	/// Run the selected objective, or every step of a test sequence.
+	fn launch(self) -> eyre::Result<()> {
+		let test_configs = match self.objective {
+			TestObjective::TestSequence(options) => {
+				let test_sequence = TestSequence::new_from_file(Path::new(&options.path))?;
+				test_sequence.into_vec()
+			},
+			objective => {
+				let mut config = TestConfiguration::new(objective, &self.standard_configuration);
+				apply_network_options(&mut config, &self.network);
+				vec![config]
+			},
+		};
+
+		let n_steps = test_configs.len();
+		for (index, config) in test_configs.into_iter().enumerate() {
+			gum::info!(target: LOG_TARGET, "Step {}/{}", index + 1, n_steps);
+			display_configuration(&config);
+			run_test(config)?;
+		}
+
+		Ok(())
+	}
+}
+
+fn apply_network_options(config: &mut TestConfiguration, options: &NetworkOptions) {
+	if let Some(peer_bandwidth) = options.peer_bandwidth {
+		config.peer_bandwidth = peer_bandwidth;
+	}
+	if let Some(bandwidth) = options.bandwidth {
+		config.bandwidth = bandwidth;
+	}
+	if let Some(error) = options.peer_error {
+		config.error = error as usize;
+	}
+	if options.peer_min_latency.is_some() || options.peer_max_latency.is_some() {
+		let min_latency = options.peer_min_latency.unwrap_or_default();
+		let max_latency = options.peer_max_latency.unwrap_or(min_latency);
+		config.latency = Some(PeerLatency { min_latency, max_latency });
+	}
+}
+
+fn run_test(config: TestConfiguration) -> eyre::Result<()> {
+	let dependencies = TestEnvironmentDependencies::default();
+
+	match config.objective.clone() {
+		TestObjective::DataAvailabilityRead(options) => {
+			let state = availability::TestState::new(&config, options);
+			let (mut env, _req_cfg) = availability::prepare_test(config, &state, dependencies);
+			env.runtime()
+				.block_on(availability::benchmark_availability_read(&mut env, state));
+		},
+		TestObjective::TestSequence(_) => eyre::bail!("Test sequences can not be nested"),
+	}
+
+	Ok(())
+}
+
+fn main() -> eyre::Result<()> {
+	color_eyre::install()?;
+	env_logger::builder().filter(None, log::LevelFilter::Info).try_init()?;
+
+	let cli = BenchCli::parse();
+	cli.launch()?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn network_options_override_configuration() {
+		let cli = BenchCli::try_parse_from([
+			"subsystem-bench",
+			"--n-validators",
+			"300",
+			"--peer-error",
+			"3",
+			"--peer-min-latency",
+			"10",
+			"data-availability-read",
+			"--fetch-from-backers",
+		])
+		.unwrap();
+
+		let mut config = TestConfiguration::new(cli.objective.clone(), &cli.standard_configuration);
+		apply_network_options(&mut config, &cli.network);
+
+		assert_eq!(config.n_validators, 300);
+		assert_eq!(config.error, 3);
+		let latency = config.latency.unwrap();
+		assert_eq!((latency.min_latency, latency.max_latency), (10, 10));
+		assert_matches::assert_matches!(
+			config.objective,
+			TestObjective::DataAvailabilityRead(options) if options.fetch_from_backers
+		);
+	}
+
+	#[test]
+	fn example_test_sequence_parses() {
+		let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/availability_read.yaml");
+		let test_configs = TestSequence::new_from_file(&path).unwrap().into_vec();
+
+		assert!(!test_configs.is_empty());
+		assert!(test_configs
+			.iter()
+			.all(|config| matches!(config.objective, TestObjective::DataAvailabilityRead(_))));
+	}
+}
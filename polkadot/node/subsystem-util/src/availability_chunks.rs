@@ -0,0 +1,164 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Mapping of availability chunks to validators.
+//!
+//! When [`FeatureIndex::AvailabilityChunkMapping`] is enabled, the chunk held by a validator is
+//! no longer the one with the validator's own index. Instead, the identity mapping is rotated by
+//! `core_index * systematic_threshold`, so that the systematic chunks of candidates occupying
+//! different cores are held by different validators, spreading the load of systematic recovery.
+
+use polkadot_erasure_coding::systematic_recovery_threshold;
+use polkadot_primitives::{
+	vstaging::{node_features::FeatureIndex, NodeFeatures},
+	CoreIndex, ValidatorIndex,
+};
+
+/// Whether the availability chunk mapping is enabled by the given node features.
+pub fn availability_chunk_mapping_is_enabled(node_features: Option<&NodeFeatures>) -> bool {
+	node_features
+		.and_then(|features| {
+			features
+				.get(FeatureIndex::AvailabilityChunkMapping as usize)
+				.as_deref()
+				.copied()
+		})
+		.unwrap_or(false)
+}
+
+/// Compute the index of the availability chunk held by `validator_index`, for a candidate that
+/// occupied `core_index`.
+///
+/// Without the mapping feature, this is the validator index itself.
+pub fn availability_chunk_index(
+	node_features: Option<&NodeFeatures>,
+	n_validators: usize,
+	core_index: CoreIndex,
+	validator_index: ValidatorIndex,
+) -> Result<ValidatorIndex, polkadot_erasure_coding::Error> {
+	if !availability_chunk_mapping_is_enabled(node_features) {
+		return Ok(validator_index)
+	}
+
+	let shift = core_shift(n_validators, core_index)?;
+	Ok(ValidatorIndex(((validator_index.0 as usize + shift) % n_validators) as u32))
+}
+
+/// Compute the availability chunk indices of all validators, for a candidate that occupied
+/// `core_index`.
+///
+/// The returned vector is indexed by validator index.
+pub fn availability_chunk_indices(
+	node_features: Option<&NodeFeatures>,
+	n_validators: usize,
+	core_index: CoreIndex,
+) -> Result<Vec<ValidatorIndex>, polkadot_erasure_coding::Error> {
+	// Validates `n_validators` regardless of the feature being enabled.
+	let shift = core_shift(n_validators, core_index)?;
+
+	if !availability_chunk_mapping_is_enabled(node_features) {
+		return Ok((0..n_validators).map(|i| ValidatorIndex(i as u32)).collect())
+	}
+
+	Ok((0..n_validators)
+		.map(|i| ValidatorIndex(((i + shift) % n_validators) as u32))
+		.collect())
+}
+
+fn core_shift(
+	n_validators: usize,
+	core_index: CoreIndex,
+) -> Result<usize, polkadot_erasure_coding::Error> {
+	let systematic_threshold = systematic_recovery_threshold(n_validators)?;
+	Ok((core_index.0 as usize % n_validators) * systematic_threshold % n_validators)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashSet;
+
+	fn node_features_with_mapping(enabled: bool) -> NodeFeatures {
+		NodeFeatures::repeat(enabled, FeatureIndex::AvailabilityChunkMapping as usize + 1)
+	}
+
+	#[test]
+	fn identity_without_feature() {
+		let n_validators = 10;
+
+		for node_features in [None, Some(node_features_with_mapping(false))] {
+			for core in 0..5 {
+				let indices = availability_chunk_indices(
+					node_features.as_ref(),
+					n_validators,
+					CoreIndex(core),
+				)
+				.unwrap();
+				assert_eq!(
+					indices,
+					(0..n_validators).map(|i| ValidatorIndex(i as u32)).collect::<Vec<_>>()
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn mapping_is_a_per_core_permutation() {
+		let node_features = node_features_with_mapping(true);
+
+		for n_validators in [2, 5, 10, 100, 301] {
+			let systematic_threshold = systematic_recovery_threshold(n_validators).unwrap();
+
+			for core in 0..10 {
+				let indices =
+					availability_chunk_indices(Some(&node_features), n_validators, CoreIndex(core))
+						.unwrap();
+
+				assert_eq!(indices.len(), n_validators);
+				assert_eq!(indices.iter().collect::<HashSet<_>>().len(), n_validators);
+				assert!(indices.iter().all(|i| (i.0 as usize) < n_validators));
+
+				for (validator_index, chunk_index) in indices.iter().enumerate() {
+					assert_eq!(
+						availability_chunk_index(
+							Some(&node_features),
+							n_validators,
+							CoreIndex(core),
+							ValidatorIndex(validator_index as u32),
+						)
+						.unwrap(),
+						*chunk_index,
+					);
+				}
+
+				// The validator holding the first systematic chunk is shifted by the core index.
+				let first = indices.iter().position(|i| i.0 == 0).unwrap();
+				assert_eq!(
+					first,
+					(n_validators - (core as usize * systematic_threshold) % n_validators) %
+						n_validators
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn invalid_validator_count_errors() {
+		let node_features = node_features_with_mapping(true);
+		assert!(availability_chunk_indices(Some(&node_features), 1, CoreIndex(0)).is_err());
+		assert!(availability_chunk_indices(None, 1, CoreIndex(0)).is_err());
+	}
+}
@@ -17,6 +17,7 @@
 //! Convenient interface to runtime information.
 
 use schnellru::{ByLength, LruMap};
+use std::collections::{BTreeMap, VecDeque};
 
 use parity_scale_codec::Encode;
 use sp_application_crypto::AppCrypto;
@@ -32,15 +33,15 @@ use polkadot_node_subsystem_types::UnpinHandle;
 use polkadot_primitives::{
 	slashing,
 	vstaging::{node_features::FeatureIndex, NodeFeatures},
-	AsyncBackingParams, CandidateEvent, CandidateHash, CoreState, EncodeAs, ExecutorParams,
-	GroupIndex, GroupRotationInfo, Hash, IndexedVec, OccupiedCore, ScrapedOnChainVotes,
-	SessionIndex, SessionInfo, Signed, SigningContext, UncheckedSigned, ValidationCode,
-	ValidationCodeHash, ValidatorId, ValidatorIndex, LEGACY_MIN_BACKING_VOTES,
+	AsyncBackingParams, CandidateEvent, CandidateHash, CoreIndex, CoreState, EncodeAs,
+	ExecutorParams, GroupIndex, GroupRotationInfo, Hash, Id as ParaId, IndexedVec, OccupiedCore,
+	ScrapedOnChainVotes, SessionIndex, SessionInfo, Signed, SigningContext, UncheckedSigned,
+	ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex, LEGACY_MIN_BACKING_VOTES,
 };
 
 use crate::{
 	request_async_backing_params, request_availability_cores, request_candidate_events,
-	request_from_runtime, request_key_ownership_proof, request_on_chain_votes,
+	request_claim_queue, request_from_runtime, request_key_ownership_proof, request_on_chain_votes,
 	request_session_executor_params, request_session_index_for_child, request_session_info,
 	request_submit_report_dispute_lost, request_unapplied_slashes, request_validation_code_by_hash,
 	request_validator_groups,
@@ -319,11 +320,12 @@ where
 	recv_runtime(request_availability_cores(relay_parent, sender).await).await
 }
 
-/// Variant of `request_availability_cores` that only returns occupied ones.
+/// Variant of `request_availability_cores` that only returns occupied ones, along with their
+/// core index.
 pub async fn get_occupied_cores<Sender>(
 	sender: &mut Sender,
 	relay_parent: Hash,
-) -> Result<Vec<OccupiedCore>>
+) -> Result<Vec<(CoreIndex, OccupiedCore)>>
 where
 	Sender: overseer::SubsystemSender<RuntimeApiMessage>,
 {
@@ -331,9 +333,10 @@ where
 
 	Ok(cores
 		.into_iter()
-		.filter_map(|core_state| {
+		.enumerate()
+		.filter_map(|(core_index, core_state)| {
 			if let CoreState::Occupied(occupied) = core_state {
-				Some(occupied)
+				Some((CoreIndex(core_index as u32), occupied))
 			} else {
 				None
 			}
@@ -553,3 +556,35 @@ pub async fn request_node_features(
 		res.map(Some)
 	}
 }
+
+/// Fetch the claim queue at the given relay parent.
+/// Prior to runtime API version 12, or if the runtime fails to provide it, just return `None`.
+pub async fn fetch_claim_queue(
+	sender: &mut impl overseer::SubsystemSender<RuntimeApiMessage>,
+	relay_parent: Hash,
+) -> Result<Option<BTreeMap<CoreIndex, VecDeque<ParaId>>>> {
+	let res = recv_runtime(request_claim_queue(relay_parent, sender).await).await;
+
+	match res {
+		Err(Error::RuntimeRequest(RuntimeApiError::NotSupported { .. })) => {
+			gum::trace!(
+				target: LOG_TARGET,
+				?relay_parent,
+				"Querying the claim queue from the runtime is not supported by the current Runtime API",
+			);
+
+			Ok(None)
+		},
+		Err(Error::RuntimeRequest(err)) => {
+			gum::debug!(
+				target: LOG_TARGET,
+				?relay_parent,
+				?err,
+				"Failed to fetch the claim queue, falling back to availability cores",
+			);
+
+			Ok(None)
+		},
+		res => res.map(Some),
+	}
+}
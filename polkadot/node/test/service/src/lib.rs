@@ -81,6 +81,7 @@ pub fn new_full(
 		polkadot_service::NewFullParams {
 			is_parachain_node,
 			enable_beefy: true,
+			beefy_warp_sync: false,
 			force_authoring_backoff: false,
 			jaeger_agent: None,
 			telemetry_worker_handle: None,
@@ -166,6 +167,8 @@ pub fn node_config(
 		keystore: KeystoreConfig::InMemory,
 		database: DatabaseSource::RocksDb { path: root.join("db"), cache_size: 128 },
 		trie_cache_maximum_size: Some(64 * 1024 * 1024),
+		trie_cache_snapshot: None,
+		trie_cache_warmup: false,
 		state_pruning: Default::default(),
 		blocks_pruning: BlocksPruning::KeepFinalized,
 		chain_spec: Box::new(spec),
@@ -173,6 +176,7 @@ pub fn node_config(
 			instantiation_strategy: WasmtimeInstantiationStrategy::PoolingCopyOnWrite,
 		},
 		wasm_runtime_overrides: Default::default(),
+		runtime_artifact_cache: false,
 		rpc_addr: Default::default(),
 		rpc_max_request_size: Default::default(),
 		rpc_max_response_size: Default::default(),
@@ -193,6 +197,7 @@ pub fn node_config(
 		tracing_receiver: Default::default(),
 		max_runtime_instances: 8,
 		runtime_cache_size: 2,
+		offchain_execution_limits: Default::default(),
 		announce_block: true,
 		data_path: root,
 		base_path,
@@ -83,6 +83,7 @@ fn main() -> Result<()> {
 							collator.collator_key(),
 						),
 						enable_beefy: false,
+						beefy_warp_sync: false,
 						force_authoring_backoff: false,
 						jaeger_agent: None,
 						telemetry_worker_handle: None,
@@ -117,14 +117,18 @@ use crate::{
 	async_backing, slashing,
 	vstaging::{self, ApprovalVotingParams},
 	AsyncBackingParams, BlockNumber, CandidateCommitments, CandidateEvent, CandidateHash,
-	CommittedCandidateReceipt, CoreState, DisputeState, ExecutorParams, GroupRotationInfo, Hash,
-	OccupiedCoreAssumption, PersistedValidationData, PvfCheckStatement, ScrapedOnChainVotes,
-	SessionIndex, SessionInfo, ValidatorId, ValidatorIndex, ValidatorSignature,
+	CommittedCandidateReceipt, CoreIndex, CoreState, DisputeState, ExecutorParams,
+	GroupRotationInfo, Hash, OccupiedCoreAssumption, PersistedValidationData, PvfCheckStatement,
+	ScrapedOnChainVotes, SessionIndex, SessionInfo, ValidatorId, ValidatorIndex,
+	ValidatorSignature,
 };
 
 use polkadot_core_primitives as pcp;
 use polkadot_parachain_primitives::primitives as ppp;
-use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+use sp_std::{
+	collections::{btree_map::BTreeMap, vec_deque::VecDeque},
+	prelude::*,
+};
 
 sp_api::decl_runtime_apis! {
 	/// The API for querying the state of parachains on-chain.
@@ -281,5 +285,22 @@ sp_api::decl_runtime_apis! {
 		/// Approval voting configuration parameters
 		#[api_version(10)]
 		fn approval_voting_params() -> ApprovalVotingParams;
+
+		/***** Added in v11 *****/
+
+		/// Elastic scaling: returns all the candidates pending availability for the given para,
+		/// in the order they build on top of each other.
+		/// This is a staging method! Do not use on production runtimes!
+		#[api_version(11)]
+		fn candidates_pending_availability(para_id: ppp::Id) -> Vec<CommittedCandidateReceipt<Hash>>;
+
+		/***** Added in v12 *****/
+
+		/// Claim queue: returns, for every core, the paras that are going to be scheduled on it
+		/// next, in order. The first entry is the para that can be backed on the core in the next
+		/// block.
+		/// This is a staging method! Do not use on production runtimes!
+		#[api_version(12)]
+		fn claim_queue() -> BTreeMap<CoreIndex, VecDeque<ppp::Id>>;
 	}
 }
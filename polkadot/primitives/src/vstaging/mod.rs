@@ -64,9 +64,12 @@ pub mod node_features {
 		/// Tells if tranch0 assignments could be sent in a single certificate.
 		/// Reserved for: `<https://github.com/paritytech/polkadot-sdk/issues/628>`
 		EnableAssignmentsV2 = 0,
+		/// Enables the shuffling of availability chunks across validators, per core, which is
+		/// needed for systematic chunk recovery.
+		AvailabilityChunkMapping = 1,
 		/// First unassigned feature bit.
 		/// Every time a new feature flag is assigned it should take this value.
 		/// and this should be incremented.
-		FirstUnassigned = 1,
+		FirstUnassigned = 2,
 	}
 }
@@ -0,0 +1,67 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Coretime assigner pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::{Pallet, *};
+
+use frame_benchmarking::v2::*;
+use frame_system::RawOrigin;
+use pallet_broker::CORE_MASK_BITS;
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn request_core_count() {
+		#[extrinsic_call]
+		_(RawOrigin::Root, 100)
+	}
+
+	#[benchmark]
+	fn assign_core(s: Linear<1, { CORE_MASK_BITS as u32 }>) {
+		// Setup
+		let core: u16 = 0;
+		let begin = BlockNumberFor::<T>::from(10u32);
+
+		// Worst case: a schedule is queued already and needs to be linked to the new one.
+		Pallet::<T>::do_assign_core(
+			CoreIndex(core.into()),
+			begin,
+			vec![(CoreAssignment::Pool, FULL_CORE)],
+			None,
+		)
+		.unwrap();
+
+		// Split the core evenly, the last assignment gets what is left.
+		let parts = FULL_CORE / s as PartsOf57600;
+		let mut assignment: Vec<_> =
+			(1..s).map(|task| (CoreAssignment::Task(task), parts)).collect();
+		assignment.push((CoreAssignment::Task(s), FULL_CORE - parts * (s - 1) as PartsOf57600));
+
+		#[extrinsic_call]
+		_(RawOrigin::Root, core, begin + 1u32.into(), assignment, Some(begin + 100u32.into()))
+	}
+
+	impl_benchmark_test_suite!(
+		Pallet,
+		crate::mock::new_test_ext(Default::default()),
+		crate::mock::Test
+	);
+}
@@ -0,0 +1,532 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The parachain coretime assignment module.
+//!
+//! Handles the `assign_core` messages of the coretime chain (`pallet-broker`), as described by
+//! RFC-5. Each message assigns a core, from a given block on, to a set of [`CoreAssignment`]s,
+//! each receiving a fraction of the core expressed in [`PartsOf57600`]. The assignments of a
+//! core are interlaced block by block according to their ratios.
+//!
+//! The messages arrive either as calls dispatched by the coretime chain, or through the
+//! [`CoretimeInterface`] implementation of the pallet when the broker runs on the relay chain
+//! itself. The number of cores handed out by the coretime chain is kept in the `coretime_cores`
+//! configuration and thus changes at session boundaries only.
+//!
+//! Parts of a core assigned to the instantaneous coretime pool or left idle, as well as cores
+//! without any assignment, are served from the on demand assigner.
+//!
+//! This module is not handled by the initializer but is instead instantiated in the
+//! `construct_runtime` macro.
+
+mod benchmarking;
+#[cfg(test)]
+mod tests;
+
+use crate::{
+	assigner_on_demand, configuration, ensure_parachain, paras,
+	scheduler::common::{Assignment, AssignmentProvider, AssignmentProviderConfig},
+};
+
+use frame_support::{defensive, pallet_prelude::*};
+use frame_system::pallet_prelude::*;
+pub use pallet_broker::{CoreAssignment, PartsOf57600};
+use pallet_broker::{CoreIndex as BrokerCoreIndex, CoretimeInterface};
+use primitives::{CoreIndex, Id as ParaId};
+use sp_runtime::traits::{Saturating, Zero};
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+
+pub use pallet::*;
+
+const LOG_TARGET: &str = "runtime::parachains::assigner-coretime";
+
+/// The whole core, expressed in [`PartsOf57600`].
+const FULL_CORE: PartsOf57600 = 57_600;
+
+pub trait WeightInfo {
+	fn request_core_count() -> Weight;
+	fn assign_core(s: u32) -> Weight;
+}
+
+/// A weight info that is only suitable for testing.
+pub struct TestWeightInfo;
+
+impl WeightInfo for TestWeightInfo {
+	fn request_core_count() -> Weight {
+		Weight::zero()
+	}
+
+	fn assign_core(_: u32) -> Weight {
+		Weight::zero()
+	}
+}
+
+/// Assignments as received from the coretime chain, going into effect at a given block.
+#[derive(Encode, Decode, TypeInfo, RuntimeDebug, Clone, PartialEq)]
+pub struct Schedule<N> {
+	/// Original assignments.
+	assignments: Vec<(CoreAssignment, PartsOf57600)>,
+	/// When do our assignments become invalid, if at all?
+	///
+	/// If this is `Some`, then the assignments stop being served at that block number. If this is
+	/// `None`, then we will keep serving the assignments in a circle until a new set of
+	/// assignments goes into effect.
+	end_hint: Option<N>,
+	/// The block number of the next schedule queued for the same core, if any.
+	next_schedule: Option<N>,
+}
+
+/// Descriptor of the queue of schedules for a core.
+#[derive(Encode, Decode, TypeInfo, RuntimeDebug, Clone, Copy, PartialEq)]
+pub struct QueueDescriptor<N> {
+	/// Block number of the first schedule in the queue.
+	first: N,
+	/// Block number of the last schedule in the queue.
+	last: N,
+}
+
+/// Progress of a single assignment of the schedule currently in effect on a core.
+#[derive(Encode, Decode, TypeInfo, RuntimeDebug, Clone, Copy, PartialEq)]
+pub struct AssignmentState {
+	/// The share of the core this assignment was given.
+	ratio: PartsOf57600,
+	/// How many parts are left in the current round before moving on to the next assignment.
+	remaining: PartsOf57600,
+}
+
+/// The schedule currently in effect on a core.
+#[derive(Encode, Decode, TypeInfo, RuntimeDebug, Clone, PartialEq)]
+pub struct WorkState<N> {
+	/// The assignments of the schedule, along with their progress.
+	assignments: Vec<(CoreAssignment, AssignmentState)>,
+	/// See [`Schedule::end_hint`].
+	end_hint: Option<N>,
+	/// Position of the assignment served on the next pop.
+	pos: u16,
+	/// The parts consumed by every pop: the smallest ratio of all assignments.
+	step: PartsOf57600,
+}
+
+impl<N> From<Schedule<N>> for WorkState<N> {
+	fn from(schedule: Schedule<N>) -> Self {
+		let Schedule { assignments, end_hint, next_schedule: _ } = schedule;
+		// Assignments without any share of the core would never be served anyway.
+		let assignments: Vec<_> = assignments
+			.into_iter()
+			.filter(|(_, ratio)| *ratio > 0)
+			.map(|(assignment, ratio)| (assignment, AssignmentState { ratio, remaining: ratio }))
+			.collect();
+		let step = assignments.iter().map(|(_, state)| state.ratio).min().unwrap_or(FULL_CORE);
+
+		Self { assignments, end_hint, pos: 0, step }
+	}
+}
+
+impl<N> WorkState<N> {
+	/// The assignment to serve next, advancing the interlacing by one block.
+	fn pop(&mut self) -> Option<CoreAssignment> {
+		if self.assignments.is_empty() {
+			return None
+		}
+
+		// Wrap around.
+		self.pos = self.pos % self.assignments.len() as u16;
+		let (assignment, assignment_state) = self
+			.assignments
+			.get_mut(self.pos as usize)
+			.expect("`pos` was limited to the length of the assignments right above; qed");
+
+		// Advance for the next pop: once this round of the assignment is used up, move on to the
+		// next one, carrying over whatever is left to its next round.
+		assignment_state.remaining = assignment_state.remaining.saturating_sub(self.step);
+		if assignment_state.remaining < self.step {
+			self.pos = self.pos.saturating_add(1);
+			assignment_state.remaining =
+				assignment_state.remaining.saturating_add(assignment_state.ratio);
+		}
+
+		Some(assignment.clone())
+	}
+}
+
+/// The schedules queued for a core and the one currently in effect.
+#[derive(Encode, Decode, TypeInfo, RuntimeDebug, Default, Clone, PartialEq)]
+pub struct CoreDescriptor<N> {
+	/// The queue of schedules which did not go into effect yet.
+	queue: Option<QueueDescriptor<N>>,
+	/// The schedule currently being served.
+	current_work: Option<WorkState<N>>,
+	/// The number of on demand orders popped for this core which did not conclude yet, per para.
+	pool_claims: BTreeMap<ParaId, u32>,
+}
+
+impl<N> CoreDescriptor<N> {
+	/// Note that an on demand order of the para was popped for this core.
+	fn note_pool_claim(&mut self, para_id: ParaId) {
+		self.pool_claims.entry(para_id).or_default().saturating_inc();
+	}
+
+	/// Release an on demand order of the para popped for this core, returning whether there was
+	/// one.
+	fn take_pool_claim(&mut self, para_id: ParaId) -> bool {
+		let Some(count) = self.pool_claims.get_mut(&para_id) else { return false };
+
+		count.saturating_dec();
+		if *count == 0 {
+			self.pool_claims.remove(&para_id);
+		}
+		true
+	}
+}
+
+type BalanceOf<T> = assigner_on_demand::BalanceOf<T>;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config:
+		frame_system::Config + configuration::Config + paras::Config + assigner_on_demand::Config
+	{
+		type RuntimeOrigin: From<<Self as frame_system::Config>::RuntimeOrigin>
+			+ Into<Result<crate::Origin, <Self as Config>::RuntimeOrigin>>;
+
+		/// The coretime chain, the only parachain allowed to instruct core assignments.
+		#[pallet::constant]
+		type BrokerId: Get<ParaId>;
+
+		/// Something that provides the weight of this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// Schedules queued for each core, keyed by the block number they go into effect at.
+	///
+	/// Once that block number is reached, the schedule replaces whatever was in effect on the
+	/// core before.
+	#[pallet::storage]
+	pub(super) type CoreSchedules<T: Config> = StorageMap<
+		_,
+		Twox256,
+		(BlockNumberFor<T>, CoreIndex),
+		Schedule<BlockNumberFor<T>>,
+		OptionQuery,
+	>;
+
+	/// The queue of schedules and the schedule currently in effect for each core.
+	#[pallet::storage]
+	pub(super) type CoreDescriptors<T: Config> = StorageMap<
+		_,
+		Twox256,
+		CoreIndex,
+		CoreDescriptor<BlockNumberFor<T>>,
+		ValueQuery,
+		GetDefault,
+	>;
+
+	/// The core count requested last, to be picked up by a broker running on this chain.
+	#[pallet::storage]
+	pub(super) type CoreCountNotification<T: Config> =
+		StorageValue<_, BrokerCoreIndex, OptionQuery>;
+
+	/// The revenue information requested last, to be picked up by a broker running on this chain.
+	#[pallet::storage]
+	pub(super) type RevenueNotification<T: Config> =
+		StorageValue<_, (BlockNumberFor<T>, BalanceOf<T>), OptionQuery>;
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The `assign_core` message carries no assignments.
+		AssignmentsEmpty,
+		/// The assignments of an `assign_core` message are not sorted or contain duplicates.
+		AssignmentsNotSorted,
+		/// The assignments of an `assign_core` message add up to more than a full core.
+		OverScheduled,
+		/// The assignments of an `assign_core` message add up to less than a full core.
+		UnderScheduled,
+		/// A schedule can only be queued after all schedules already queued for the core.
+		DisallowedInsert,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Request the number of cores handed out by the coretime chain to be set to `count`.
+		///
+		/// The new count goes into effect with the configuration at a later session.
+		#[pallet::call_index(0)]
+		#[pallet::weight(<T as Config>::WeightInfo::request_core_count())]
+		pub fn request_core_count(origin: OriginFor<T>, count: BrokerCoreIndex) -> DispatchResult {
+			Self::ensure_root_or_broker(origin)?;
+
+			Self::do_request_core_count(count)
+		}
+
+		/// Queue a new set of assignments for a core, going into effect at block `begin`.
+		///
+		/// See [`Pallet::do_assign_core`].
+		#[pallet::call_index(1)]
+		#[pallet::weight(<T as Config>::WeightInfo::assign_core(assignment.len() as u32))]
+		pub fn assign_core(
+			origin: OriginFor<T>,
+			core: BrokerCoreIndex,
+			begin: BlockNumberFor<T>,
+			assignment: Vec<(CoreAssignment, PartsOf57600)>,
+			end_hint: Option<BlockNumberFor<T>>,
+		) -> DispatchResult {
+			Self::ensure_root_or_broker(origin)?;
+
+			Self::do_assign_core(CoreIndex(core.into()), begin, assignment, end_hint)
+		}
+	}
+}
+
+impl<T: Config> AssignmentProvider<BlockNumberFor<T>> for Pallet<T> {
+	fn session_core_count() -> u32 {
+		let config = <configuration::Pallet<T>>::config();
+		config.coretime_cores
+	}
+
+	fn pop_assignment_for_core(
+		core_idx: CoreIndex,
+		concluded_para: Option<ParaId>,
+	) -> Option<Assignment> {
+		let now = <frame_system::Pallet<T>>::block_number();
+
+		CoreDescriptors::<T>::mutate(core_idx, |core_descriptor| {
+			// Only a concluding on demand order holds on to the core in the on demand assigner,
+			// whatever the core serves next.
+			if let Some(concluded_para) = concluded_para {
+				if core_descriptor.take_pool_claim(concluded_para) {
+					<assigner_on_demand::Pallet<T>>::report_processed(concluded_para, core_idx);
+				}
+			}
+
+			Self::ensure_workload(now, core_idx, core_descriptor);
+
+			match core_descriptor.current_work.as_mut().and_then(WorkState::pop) {
+				Some(CoreAssignment::Task(para_id)) => Some(Assignment::new(para_id.into())),
+				// Pool and idle parts, as well as cores without any work, fall back to on demand.
+				Some(CoreAssignment::Pool) | Some(CoreAssignment::Idle) | None => {
+					let popped = <assigner_on_demand::Pallet<T> as AssignmentProvider<
+						BlockNumberFor<T>,
+					>>::pop_assignment_for_core(core_idx, None);
+					if let Some(ref assignment) = popped {
+						core_descriptor.note_pool_claim(assignment.para_id);
+					}
+					popped
+				},
+			}
+		})
+	}
+
+	/// Only on demand orders are pushed back, bulk assignments are served again from the core's
+	/// schedule anyway.
+	fn push_assignment_for_core(core_idx: CoreIndex, assignment: Assignment) {
+		let from_pool = CoreDescriptors::<T>::mutate(core_idx, |core_descriptor| {
+			core_descriptor.take_pool_claim(assignment.para_id)
+		});
+
+		if from_pool {
+			<assigner_on_demand::Pallet<T> as AssignmentProvider<BlockNumberFor<T>>>::push_assignment_for_core(core_idx, assignment)
+		}
+	}
+
+	fn get_provider_config(_core_idx: CoreIndex) -> AssignmentProviderConfig<BlockNumberFor<T>> {
+		let config = <configuration::Pallet<T>>::config();
+		AssignmentProviderConfig {
+			max_availability_timeouts: config.on_demand_retries,
+			ttl: config.on_demand_ttl,
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Ensure the origin is either root or the coretime chain.
+	fn ensure_root_or_broker(origin: OriginFor<T>) -> DispatchResult {
+		if ensure_root(origin.clone()).is_ok() {
+			return Ok(())
+		}
+
+		let para_id = ensure_parachain(<T as Config>::RuntimeOrigin::from(origin))?;
+		ensure!(para_id == T::BrokerId::get(), DispatchError::BadOrigin);
+
+		Ok(())
+	}
+
+	/// Schedule the number of cores handed out by the coretime chain to be set to `count`, and
+	/// note it for a broker running on this chain.
+	fn do_request_core_count(count: BrokerCoreIndex) -> DispatchResult {
+		<configuration::Pallet<T>>::set_coretime_cores_unchecked(count.into())?;
+		CoreCountNotification::<T>::put(count);
+
+		Ok(())
+	}
+
+	/// Queue a new set of assignments for a core, going into effect at block `begin`.
+	///
+	/// This is the relay chain side of the `assign_core` message of the coretime chain. The
+	/// assignments must be sorted without duplicates and add up to exactly a full core, and
+	/// `begin` must lie after all schedules already queued for the core.
+	///
+	/// Errors:
+	/// - `AssignmentsEmpty`
+	/// - `AssignmentsNotSorted`
+	/// - `OverScheduled`
+	/// - `UnderScheduled`
+	/// - `DisallowedInsert`
+	pub fn do_assign_core(
+		core_idx: CoreIndex,
+		begin: BlockNumberFor<T>,
+		assignments: Vec<(CoreAssignment, PartsOf57600)>,
+		end_hint: Option<BlockNumberFor<T>>,
+	) -> DispatchResult {
+		ensure!(!assignments.is_empty(), Error::<T>::AssignmentsEmpty);
+
+		// Strictly ascending order also rules out duplicates.
+		ensure!(
+			assignments.windows(2).all(|pair| pair[0].0 < pair[1].0),
+			Error::<T>::AssignmentsNotSorted
+		);
+
+		let parts_sum = assignments
+			.iter()
+			.try_fold(0 as PartsOf57600, |sum, (_, parts)| sum.checked_add(*parts))
+			.filter(|sum| *sum <= FULL_CORE)
+			.ok_or(Error::<T>::OverScheduled)?;
+		ensure!(parts_sum == FULL_CORE, Error::<T>::UnderScheduled);
+
+		CoreDescriptors::<T>::try_mutate(core_idx, |core_descriptor| -> DispatchResult {
+			let new_queue = match core_descriptor.queue {
+				Some(queue) => {
+					ensure!(begin > queue.last, Error::<T>::DisallowedInsert);
+
+					let linked = CoreSchedules::<T>::mutate((queue.last, core_idx), |schedule| {
+						schedule.as_mut().map(|schedule| schedule.next_schedule = Some(begin))
+					});
+
+					if linked.is_some() {
+						QueueDescriptor { first: queue.first, last: begin }
+					} else {
+						defensive!("The last schedule of a core's queue must exist");
+						QueueDescriptor { first: begin, last: begin }
+					}
+				},
+				None => QueueDescriptor { first: begin, last: begin },
+			};
+
+			core_descriptor.queue = Some(new_queue);
+			CoreSchedules::<T>::insert(
+				(begin, core_idx),
+				Schedule { assignments, end_hint, next_schedule: None },
+			);
+
+			Ok(())
+		})
+	}
+
+	/// Make the schedules of the core which are due by `now` go into effect, and drop the
+	/// current one if it ended.
+	fn ensure_workload(
+		now: BlockNumberFor<T>,
+		core_idx: CoreIndex,
+		core_descriptor: &mut CoreDescriptor<BlockNumberFor<T>>,
+	) {
+		let ended = |end_hint: Option<BlockNumberFor<T>>| end_hint.map_or(false, |end| end <= now);
+
+		if core_descriptor.current_work.as_ref().map_or(false, |work| ended(work.end_hint)) {
+			core_descriptor.current_work = None;
+		}
+
+		let Some(queue) = core_descriptor.queue else { return };
+
+		let mut next = Some(queue.first);
+		while let Some(begin) = next.filter(|begin| *begin <= now) {
+			let Some(schedule) = CoreSchedules::<T>::take((begin, core_idx)) else {
+				defensive!("Schedules in a core's queue must exist");
+				next = None;
+				break
+			};
+
+			next = schedule.next_schedule;
+			// Each schedule replaces the previous one, even if it ended already.
+			core_descriptor.current_work =
+				if ended(schedule.end_hint) { None } else { Some(schedule.into()) };
+		}
+
+		core_descriptor.queue = next.map(|first| QueueDescriptor { first, last: queue.last });
+	}
+}
+
+/// Lets a broker running on this chain instruct the coretime assignments directly, instead of
+/// dispatching the calls of this pallet from the coretime chain.
+impl<T: Config> CoretimeInterface for Pallet<T> {
+	type AccountId = T::AccountId;
+	type Balance = BalanceOf<T>;
+	type BlockNumber = BlockNumberFor<T>;
+
+	fn latest() -> Self::BlockNumber {
+		<frame_system::Pallet<T>>::block_number()
+	}
+
+	fn request_core_count(count: BrokerCoreIndex) {
+		if let Err(err) = Self::do_request_core_count(count) {
+			log::warn!(target: LOG_TARGET, "Failed to request core count {}: {:?}", count, err);
+		}
+	}
+
+	/// On demand revenue is not accounted for the coretime chain, so no revenue is reported. The
+	/// notification is still sent, so that the broker does not wait for it.
+	fn request_revenue_info_at(when: Self::BlockNumber) {
+		RevenueNotification::<T>::put((when, BalanceOf::<T>::zero()));
+	}
+
+	/// Instantaneous coretime credit is not supported, on demand orders are paid directly.
+	fn credit_account(_who: Self::AccountId, _amount: Self::Balance) {}
+
+	fn assign_core(
+		core: BrokerCoreIndex,
+		begin: Self::BlockNumber,
+		assignment: Vec<(CoreAssignment, PartsOf57600)>,
+		end_hint: Option<Self::BlockNumber>,
+	) {
+		if let Err(err) = Self::do_assign_core(CoreIndex(core.into()), begin, assignment, end_hint)
+		{
+			log::warn!(target: LOG_TARGET, "Failed to assign core {}: {:?}", core, err);
+		}
+	}
+
+	fn check_notify_core_count() -> Option<u16> {
+		CoreCountNotification::<T>::take()
+	}
+
+	fn check_notify_revenue_info() -> Option<(Self::BlockNumber, Self::Balance)> {
+		RevenueNotification::<T>::take()
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn ensure_notify_core_count(count: u16) {
+		CoreCountNotification::<T>::put(count);
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn ensure_notify_revenue_info(when: Self::BlockNumber, revenue: Self::Balance) {
+		RevenueNotification::<T>::put((when, revenue));
+	}
+}
@@ -0,0 +1,431 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+
+use crate::{
+	assigner_on_demand::QueuePushDirection,
+	configuration::PendingConfigs,
+	mock::{
+		new_test_ext, Broker, BrokerId, CoretimeAssigner, MockGenesisConfig, OnDemandAssigner,
+		RuntimeOrigin, System, Test,
+	},
+	paras::{ParaGenesisArgs, ParaKind},
+};
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
+use pallet_broker::{ConfigRecord, CoreMask, ScheduleItem, TaskId};
+use primitives::{BlockNumber, HeadData, ValidationCode};
+use sp_runtime::{DispatchError, Perbill};
+
+const ON_DEMAND_PARA: u32 = 2000;
+
+fn genesis_config() -> MockGenesisConfig {
+	let mut genesis = MockGenesisConfig::default();
+	let config = &mut genesis.configuration.config;
+	config.on_demand_cores = 2;
+	config.coretime_cores = 2;
+	config.on_demand_queue_max_size = 100;
+
+	genesis.paras.paras.push((
+		ON_DEMAND_PARA.into(),
+		ParaGenesisArgs {
+			genesis_head: HeadData::from(vec![0u8]),
+			validation_code: ValidationCode::from(vec![0u8]),
+			para_kind: ParaKind::Parathread,
+		},
+	));
+
+	genesis
+}
+
+fn para(id: u32) -> Option<ParaId> {
+	Some(id.into())
+}
+
+fn task(id: TaskId) -> CoreAssignment {
+	CoreAssignment::Task(id)
+}
+
+/// Pop the assignments of a core for the blocks `from..to`, one per block.
+fn pop_range(core: CoreIndex, from: BlockNumber, to: BlockNumber) -> Vec<Option<ParaId>> {
+	(from..to)
+		.map(|block| {
+			System::set_block_number(block);
+			CoretimeAssigner::pop_assignment_for_core(core, None).map(|a| a.para_id)
+		})
+		.collect()
+}
+
+/// Run the broker up to the given block, as the relay chain would if it hosted it.
+fn advance_broker_to(to: BlockNumber) {
+	while System::block_number() < to {
+		System::set_block_number(System::block_number() + 1);
+		Broker::on_initialize(System::block_number());
+	}
+}
+
+#[test]
+fn assign_core_rejects_invalid_assignments() {
+	new_test_ext(genesis_config()).execute_with(|| {
+		let core = CoreIndex(0);
+
+		assert_noop!(
+			CoretimeAssigner::do_assign_core(core, 10, vec![], None),
+			Error::<Test>::AssignmentsEmpty
+		);
+
+		assert_noop!(
+			CoretimeAssigner::do_assign_core(
+				core,
+				10,
+				vec![(task(2), 28800), (task(1), 28800),],
+				None
+			),
+			Error::<Test>::AssignmentsNotSorted
+		);
+
+		assert_noop!(
+			CoretimeAssigner::do_assign_core(
+				core,
+				10,
+				vec![(task(1), 28800), (task(1), 28800),],
+				None
+			),
+			Error::<Test>::AssignmentsNotSorted
+		);
+
+		assert_noop!(
+			CoretimeAssigner::do_assign_core(
+				core,
+				10,
+				vec![(task(1), FULL_CORE), (task(2), 1)],
+				None
+			),
+			Error::<Test>::OverScheduled
+		);
+
+		assert_noop!(
+			CoretimeAssigner::do_assign_core(core, 10, vec![(task(1), 57599)], None),
+			Error::<Test>::UnderScheduled
+		);
+	});
+}
+
+#[test]
+fn schedules_are_queued_in_order() {
+	new_test_ext(genesis_config()).execute_with(|| {
+		let core = CoreIndex(0);
+
+		assert_ok!(CoretimeAssigner::do_assign_core(core, 10, vec![(task(1), FULL_CORE)], None));
+		assert_ok!(CoretimeAssigner::do_assign_core(core, 20, vec![(task(2), FULL_CORE)], None));
+
+		assert_eq!(
+			CoreDescriptors::<Test>::get(core).queue,
+			Some(QueueDescriptor { first: 10, last: 20 })
+		);
+		assert_eq!(CoreSchedules::<Test>::get((10, core)).unwrap().next_schedule, Some(20));
+		assert_eq!(CoreSchedules::<Test>::get((20, core)).unwrap().next_schedule, None);
+
+		// Inserting before or at the end of the queue is not allowed.
+		assert_noop!(
+			CoretimeAssigner::do_assign_core(core, 15, vec![(task(3), FULL_CORE)], None),
+			Error::<Test>::DisallowedInsert
+		);
+		assert_noop!(
+			CoretimeAssigner::do_assign_core(core, 20, vec![(task(3), FULL_CORE)], None),
+			Error::<Test>::DisallowedInsert
+		);
+	});
+}
+
+#[test]
+fn schedules_go_into_effect_at_their_block() {
+	new_test_ext(genesis_config()).execute_with(|| {
+		let core = CoreIndex(0);
+
+		assert_ok!(CoretimeAssigner::do_assign_core(core, 3, vec![(task(1), FULL_CORE)], None));
+		assert_ok!(CoretimeAssigner::do_assign_core(core, 5, vec![(task(2), FULL_CORE)], None));
+
+		assert_eq!(pop_range(core, 1, 7), vec![None, None, para(1), para(1), para(2), para(2)]);
+
+		// All schedules went into effect, the queue is gone.
+		assert_eq!(CoreDescriptors::<Test>::get(core).queue, None);
+		assert_eq!(CoreSchedules::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn schedule_due_while_core_was_not_popped_replaces_previous_ones() {
+	new_test_ext(genesis_config()).execute_with(|| {
+		let core = CoreIndex(0);
+
+		assert_ok!(CoretimeAssigner::do_assign_core(core, 2, vec![(task(1), FULL_CORE)], None));
+		assert_ok!(CoretimeAssigner::do_assign_core(core, 4, vec![(task(2), FULL_CORE)], None));
+
+		// Both schedules are due, only the latest one is served.
+		assert_eq!(pop_range(core, 6, 7), vec![para(2)]);
+		assert_eq!(CoreSchedules::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn assignments_end_at_end_hint() {
+	new_test_ext(genesis_config()).execute_with(|| {
+		let core = CoreIndex(0);
+
+		assert_ok!(CoretimeAssigner::do_assign_core(core, 1, vec![(task(1), FULL_CORE)], Some(3)));
+
+		assert_eq!(pop_range(core, 1, 5), vec![para(1), para(1), None, None]);
+		assert_eq!(CoreDescriptors::<Test>::get(core), CoreDescriptor::default());
+	});
+}
+
+#[test]
+fn interlaced_assignments_share_the_core_by_ratio() {
+	new_test_ext(genesis_config()).execute_with(|| {
+		let core = CoreIndex(0);
+
+		// Three quarters for task 1, one quarter for task 2.
+		assert_ok!(CoretimeAssigner::do_assign_core(
+			core,
+			1,
+			vec![(task(1), 43200), (task(2), 14400),],
+			None
+		));
+
+		let a = para(1);
+		let b = para(2);
+		assert_eq!(pop_range(core, 1, 9), vec![a, a, a, b, a, a, a, b]);
+	});
+}
+
+#[test]
+fn idle_parts_and_cores_without_work_fall_back_to_on_demand() {
+	new_test_ext(genesis_config()).execute_with(|| {
+		let core = CoreIndex(0);
+		let on_demand_para = ParaId::from(ON_DEMAND_PARA);
+
+		assert_ok!(CoretimeAssigner::do_assign_core(
+			core,
+			2,
+			vec![(CoreAssignment::Idle, 28800), (task(1), 28800)],
+			Some(6)
+		));
+
+		for _ in 0..4 {
+			assert_ok!(OnDemandAssigner::add_on_demand_assignment(
+				Assignment::new(on_demand_para),
+				QueuePushDirection::Back
+			));
+		}
+
+		// Before the schedule begins, after it ended and on its idle parts, the core serves on
+		// demand orders.
+		let o = Some(on_demand_para);
+		assert_eq!(pop_range(core, 1, 7), vec![o, o, para(1), o, para(1), o]);
+		assert!(OnDemandAssigner::get_queue().is_empty());
+		assert_eq!(CoreDescriptors::<Test>::get(core).pool_claims.get(&on_demand_para), Some(&4));
+	});
+}
+
+#[test]
+fn pool_parts_fall_back_to_on_demand() {
+	new_test_ext(genesis_config()).execute_with(|| {
+		let core = CoreIndex(0);
+		let on_demand_para = ParaId::from(ON_DEMAND_PARA);
+
+		assert_ok!(CoretimeAssigner::do_assign_core(
+			core,
+			1,
+			vec![(CoreAssignment::Pool, 28800), (task(1), 28800),],
+			None
+		));
+
+		assert_ok!(OnDemandAssigner::add_on_demand_assignment(
+			Assignment::new(on_demand_para),
+			QueuePushDirection::Back
+		));
+
+		// The first pool slot serves the order, the next one finds the queue empty.
+		assert_eq!(pop_range(core, 1, 5), vec![Some(on_demand_para), para(1), None, para(1)]);
+		assert_eq!(
+			OnDemandAssigner::get_affinity_map(on_demand_para).map(|a| a.core_idx),
+			Some(core)
+		);
+
+		// Concluding the order releases the affinity.
+		System::set_block_number(5);
+		assert_eq!(CoretimeAssigner::pop_assignment_for_core(core, Some(on_demand_para)), None);
+		assert_eq!(OnDemandAssigner::get_affinity_map(on_demand_para), None);
+		assert!(CoreDescriptors::<Test>::get(core).pool_claims.is_empty());
+	});
+}
+
+#[test]
+fn only_on_demand_claims_are_reported_processed() {
+	new_test_ext(genesis_config()).execute_with(|| {
+		let core = CoreIndex(0);
+		let on_demand_para = ParaId::from(ON_DEMAND_PARA);
+
+		// The para orders on demand coretime before its bulk coretime begins.
+		assert_ok!(OnDemandAssigner::add_on_demand_assignment(
+			Assignment::new(on_demand_para),
+			QueuePushDirection::Back
+		));
+		assert_eq!(pop_range(core, 1, 2), vec![Some(on_demand_para)]);
+
+		assert_ok!(CoretimeAssigner::do_assign_core(
+			core,
+			2,
+			vec![(task(ON_DEMAND_PARA), FULL_CORE)],
+			None
+		));
+		assert_eq!(pop_range(core, 2, 3), vec![Some(on_demand_para)]);
+
+		// The on demand order concludes first and releases the affinity.
+		System::set_block_number(3);
+		assert_eq!(
+			CoretimeAssigner::pop_assignment_for_core(core, Some(on_demand_para)),
+			Some(Assignment::new(on_demand_para))
+		);
+		assert_eq!(OnDemandAssigner::get_affinity_map(on_demand_para), None);
+
+		// Concluding the bulk assignment does not release any on demand claim.
+		System::set_block_number(4);
+		assert_eq!(
+			CoretimeAssigner::pop_assignment_for_core(core, Some(on_demand_para)),
+			Some(Assignment::new(on_demand_para))
+		);
+		assert!(CoreDescriptors::<Test>::get(core).pool_claims.is_empty());
+	});
+}
+
+#[test]
+fn only_on_demand_assignments_are_pushed_back() {
+	new_test_ext(genesis_config()).execute_with(|| {
+		let core = CoreIndex(0);
+		let on_demand_para = ParaId::from(ON_DEMAND_PARA);
+
+		assert_ok!(CoretimeAssigner::do_assign_core(
+			core,
+			1,
+			vec![(CoreAssignment::Pool, FULL_CORE)],
+			None
+		));
+		assert_ok!(OnDemandAssigner::add_on_demand_assignment(
+			Assignment::new(on_demand_para),
+			QueuePushDirection::Back
+		));
+
+		System::set_block_number(1);
+		let popped = CoretimeAssigner::pop_assignment_for_core(core, None).unwrap();
+		assert!(OnDemandAssigner::get_queue().is_empty());
+
+		// A bulk assignment is not turned into an on demand order.
+		CoretimeAssigner::push_assignment_for_core(core, Assignment::new(1.into()));
+		assert!(OnDemandAssigner::get_queue().is_empty());
+
+		CoretimeAssigner::push_assignment_for_core(core, popped);
+		assert_eq!(OnDemandAssigner::get_queue(), vec![Assignment::new(on_demand_para)]);
+	});
+}
+
+#[test]
+fn broker_workplan_is_served() {
+	new_test_ext(genesis_config()).execute_with(|| {
+		assert_ok!(Broker::configure(
+			RuntimeOrigin::root(),
+			ConfigRecord {
+				advance_notice: 2,
+				interlude_length: 1,
+				leadin_length: 1,
+				ideal_bulk_proportion: Default::default(),
+				limit_cores_offered: None,
+				region_length: 3,
+				renewal_bump: Perbill::from_percent(10),
+				contribution_timeout: 5,
+			}
+		));
+
+		// Core 0 is reserved for task 1001, core 1 is split between three tasks by core mask.
+		let item = ScheduleItem { assignment: task(1001), mask: CoreMask::complete() };
+		assert_ok!(Broker::reserve(RuntimeOrigin::root(), vec![item].try_into().unwrap()));
+		let items = vec![
+			ScheduleItem { assignment: task(1002), mask: 0xfffff_fffff_00000_00000.into() },
+			ScheduleItem { assignment: task(1003), mask: 0x00000_00000_fffff_00000.into() },
+			ScheduleItem { assignment: task(1004), mask: 0x00000_00000_00000_fffff.into() },
+		];
+		assert_ok!(Broker::reserve(RuntimeOrigin::root(), items.try_into().unwrap()));
+
+		assert_ok!(Broker::request_core_count(RuntimeOrigin::root(), 3));
+		assert_ok!(Broker::start_sales(RuntimeOrigin::root(), 100, 2));
+		advance_broker_to(10);
+
+		// The core count goes into effect with the configuration of a later session.
+		assert_eq!(PendingConfigs::<Test>::get().last().map(|(_, c)| c.coretime_cores), Some(3));
+		assert_eq!(pallet_broker::Status::<Test>::get().map(|s| s.core_count), Some(3));
+
+		// The first region begins at block 8.
+		assert_eq!(pop_range(CoreIndex(0), 7, 10), vec![None, para(1001), para(1001)]);
+		assert_eq!(
+			pop_range(CoreIndex(1), 8, 12),
+			vec![para(1002), para(1002), para(1003), para(1004)]
+		);
+	});
+}
+
+#[test]
+fn calls_require_root_or_broker_origin() {
+	new_test_ext(genesis_config()).execute_with(|| {
+		let assignment = || vec![(task(1), FULL_CORE)];
+
+		assert_noop!(
+			CoretimeAssigner::assign_core(RuntimeOrigin::signed(1), 0, 10, assignment(), None),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			CoretimeAssigner::assign_core(
+				crate::Origin::Parachain(2000.into()).into(),
+				0,
+				10,
+				assignment(),
+				None
+			),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			CoretimeAssigner::request_core_count(RuntimeOrigin::signed(1), 4),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(CoretimeAssigner::assign_core(
+			crate::Origin::Parachain(BrokerId::get()).into(),
+			0,
+			10,
+			assignment(),
+			None
+		));
+		assert_ok!(CoretimeAssigner::assign_core(RuntimeOrigin::root(), 1, 10, assignment(), None));
+		assert_eq!(CoreSchedules::<Test>::iter().count(), 2);
+
+		assert_ok!(CoretimeAssigner::request_core_count(
+			crate::Origin::Parachain(BrokerId::get()).into(),
+			4
+		));
+		assert_eq!(PendingConfigs::<Test>::get().last().map(|(_, c)| c.coretime_cores), Some(4));
+		assert_eq!(CoretimeAssigner::check_notify_core_count(), Some(4));
+	});
+}
@@ -92,7 +92,7 @@ pub enum QueuePushDirection {
 }
 
 /// Shorthand for the Balance type the runtime is using.
-type BalanceOf<T> =
+pub(crate) type BalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
 /// Errors that can happen during spot traffic calculation.
@@ -484,6 +484,15 @@ where
 		ParaIdAffinity::<T>::get(para_id)
 	}
 
+	/// Report that a `ParaId` was processed on a specified `CoreIndex`, releasing its affinity
+	/// to that core.
+	///
+	/// Used by assignment providers which only hand some of their slots to the on demand queue,
+	/// so that the affinity is released even when the core's next slot is not an on demand one.
+	pub(crate) fn report_processed(para_id: ParaId, core_idx: CoreIndex) {
+		Pallet::<T>::decrease_affinity(para_id, core_idx)
+	}
+
 	/// Decreases the affinity of a `ParaId` to a specified `CoreIndex`.
 	/// Subtracts from the count of the `CoreAffinityCount` if an entry is found and the core_idx
 	/// matches. When the count reaches 0, the entry is removed.
@@ -266,6 +266,8 @@ pub struct HostConfiguration<BlockNumber> {
 	pub node_features: NodeFeatures,
 	/// Params used by approval-voting
 	pub approval_voting_params: ApprovalVotingParams,
+	/// The amount of execution cores handed out by the coretime chain.
+	pub coretime_cores: u32,
 }
 
 impl<BlockNumber: Default + From<u32>> Default for HostConfiguration<BlockNumber> {
@@ -319,6 +321,7 @@ impl<BlockNumber: Default + From<u32>> Default for HostConfiguration<BlockNumber
 			on_demand_ttl: 5u32.into(),
 			minimum_backing_votes: LEGACY_MIN_BACKING_VOTES,
 			node_features: NodeFeatures::EMPTY,
+			coretime_cores: Default::default(),
 		}
 	}
 }
@@ -520,7 +523,7 @@ pub mod pallet {
 	/// v8-v9:  <https://github.com/paritytech/polkadot/pull/7577>
 	/// v9-v10: <https://github.com/paritytech/polkadot-sdk/pull/2177>
 	/// v10-11: <https://github.com/paritytech/polkadot-sdk/pull/1191>
-	const STORAGE_VERSION: StorageVersion = StorageVersion::new(11);
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(12);
 
 	#[pallet::pallet]
 	#[pallet::storage_version(STORAGE_VERSION)]
@@ -1243,6 +1246,17 @@ pub mod pallet {
 				config.approval_voting_params = new;
 			})
 		}
+
+		/// Set the number of coretime execution cores.
+		#[pallet::call_index(55)]
+		#[pallet::weight((
+			T::WeightInfo::set_config_with_u32(),
+			DispatchClass::Operational,
+		))]
+		pub fn set_coretime_cores(origin: OriginFor<T>, new: u32) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::set_coretime_cores_unchecked(new)
+		}
 	}
 
 	#[pallet::hooks]
@@ -1328,6 +1342,15 @@ impl<T: Config> Pallet<T> {
 		ActiveConfig::<T>::set(config);
 	}
 
+	/// Schedule an update of the number of coretime execution cores, without any origin check.
+	///
+	/// Used by the coretime assigner to follow the core count requested by the coretime chain.
+	pub(crate) fn set_coretime_cores_unchecked(new: u32) -> DispatchResult {
+		Self::schedule_config_update(|config| {
+			config.coretime_cores = new;
+		})
+	}
+
 	/// This function should be used to update members of the configuration.
 	///
 	/// This function is used to update the configuration in a way that is safe. It will check the
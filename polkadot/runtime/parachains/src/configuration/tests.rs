@@ -321,6 +321,7 @@ fn setting_pending_config_members() {
 			on_demand_ttl: 5u32,
 			minimum_backing_votes: 5,
 			node_features: bitvec![u8, Lsb0; 0, 1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1],
+			coretime_cores: 3,
 		};
 
 		Configuration::set_validation_upgrade_cooldown(
@@ -482,6 +483,8 @@ fn setting_pending_config_members() {
 		Configuration::set_node_feature(RuntimeOrigin::root(), 10, true).unwrap();
 		Configuration::set_node_feature(RuntimeOrigin::root(), 10, false).unwrap();
 		Configuration::set_node_feature(RuntimeOrigin::root(), 11, true).unwrap();
+		Configuration::set_coretime_cores(RuntimeOrigin::root(), new_config.coretime_cores)
+			.unwrap();
 
 		assert_eq!(PendingConfigs::<Test>::get(), vec![(shared::SESSION_DELAY, new_config)],);
 	})
@@ -252,12 +252,17 @@ impl<T: Config> Pallet<T> {
 	}
 
 	/// Checks if the number of processed downward messages is valid.
+	///
+	/// `pending_processed_downward_messages` are the messages processed by the candidates of the
+	/// para which are not enacted yet, and are not part of the queue anymore from the point of
+	/// view of the checked candidate.
 	pub(crate) fn check_processed_downward_messages(
 		para: ParaId,
 		relay_parent_number: BlockNumberFor<T>,
 		processed_downward_messages: u32,
+		pending_processed_downward_messages: u32,
 	) -> Result<(), ProcessedDownwardMessagesAcceptanceErr> {
-		let dmq_length = Self::dmq_length(para);
+		let dmq_length = Self::dmq_length(para).saturating_sub(pending_processed_downward_messages);
 
 		if dmq_length > 0 && processed_downward_messages == 0 {
 			// The advancement rule is for at least one downwards message to be processed
@@ -266,7 +271,10 @@ impl<T: Config> Pallet<T> {
 			let contents = Self::dmq_contents(para);
 
 			// sanity: if dmq_length is >0 this should always be 'Some'.
-			if contents.get(0).map_or(false, |msg| msg.sent_at <= relay_parent_number) {
+			if contents
+				.get(pending_processed_downward_messages as usize)
+				.map_or(false, |msg| msg.sent_at <= relay_parent_number)
+			{
 				return Err(ProcessedDownwardMessagesAcceptanceErr::AdvancementRule)
 			}
 		}
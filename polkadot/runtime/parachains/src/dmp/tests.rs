@@ -128,20 +128,27 @@ fn check_processed_downward_messages() {
 		let block_number = System::block_number();
 
 		// processed_downward_messages=0 is allowed when the DMQ is empty.
-		assert!(Dmp::check_processed_downward_messages(a, block_number, 0).is_ok());
+		assert!(Dmp::check_processed_downward_messages(a, block_number, 0, 0).is_ok());
 
 		queue_downward_message(a, vec![1, 2, 3]).unwrap();
 		queue_downward_message(a, vec![4, 5, 6]).unwrap();
 		queue_downward_message(a, vec![7, 8, 9]).unwrap();
 
 		// 0 doesn't pass if the DMQ has msgs.
-		assert!(Dmp::check_processed_downward_messages(a, block_number, 0).is_err());
+		assert!(Dmp::check_processed_downward_messages(a, block_number, 0, 0).is_err());
 		// a candidate can consume up to 3 messages
-		assert!(Dmp::check_processed_downward_messages(a, block_number, 1).is_ok());
-		assert!(Dmp::check_processed_downward_messages(a, block_number, 2).is_ok());
-		assert!(Dmp::check_processed_downward_messages(a, block_number, 3).is_ok());
+		assert!(Dmp::check_processed_downward_messages(a, block_number, 1, 0).is_ok());
+		assert!(Dmp::check_processed_downward_messages(a, block_number, 2, 0).is_ok());
+		assert!(Dmp::check_processed_downward_messages(a, block_number, 3, 0).is_ok());
 		// there is no 4 messages in the queue
-		assert!(Dmp::check_processed_downward_messages(a, block_number, 4).is_err());
+		assert!(Dmp::check_processed_downward_messages(a, block_number, 4, 0).is_err());
+
+		// 2 messages are processed by a candidate pending enactment, only 1 is left.
+		assert!(Dmp::check_processed_downward_messages(a, block_number, 0, 2).is_err());
+		assert!(Dmp::check_processed_downward_messages(a, block_number, 1, 2).is_ok());
+		assert!(Dmp::check_processed_downward_messages(a, block_number, 2, 2).is_err());
+		// 0 is allowed once all messages are processed by candidates pending enactment.
+		assert!(Dmp::check_processed_downward_messages(a, block_number, 0, 3).is_ok());
 	});
 }
 
@@ -159,9 +166,9 @@ fn check_processed_downward_messages_advancement_rule() {
 		queue_downward_message(a, vec![4, 5, 6]).unwrap();
 
 		// The queue was empty at genesis, 0 is OK despite it being non-empty in the further block.
-		assert!(Dmp::check_processed_downward_messages(a, block_number, 0).is_ok());
+		assert!(Dmp::check_processed_downward_messages(a, block_number, 0, 0).is_ok());
 		// For the advanced block number, however, the rule is broken in case of 0.
-		assert!(Dmp::check_processed_downward_messages(a, advanced_block_number, 0).is_err());
+		assert!(Dmp::check_processed_downward_messages(a, advanced_block_number, 0, 0).is_err());
 	});
 }
 
@@ -884,7 +884,7 @@ impl<T: Config> Pallet<T> {
 			outgoing_paras.len() as u32
 		))
 		.saturating_add(<T as Config>::WeightInfo::force_process_hrmp_close(
-			outgoing_paras.len() as u32
+			outgoing_paras.len() as u32,
 		))
 	}
 
@@ -1102,10 +1102,14 @@ impl<T: Config> Pallet<T> {
 	}
 
 	/// Check that the candidate of the given recipient controls the HRMP watermark properly.
+	///
+	/// `pending_hrmp_watermark` is the watermark of the latest candidate of the para which is not
+	/// enacted yet, if any.
 	pub(crate) fn check_hrmp_watermark(
 		recipient: ParaId,
 		relay_chain_parent_number: BlockNumberFor<T>,
 		new_hrmp_watermark: BlockNumberFor<T>,
+		pending_hrmp_watermark: Option<BlockNumberFor<T>>,
 	) -> Result<(), HrmpWatermarkAcceptanceErr<BlockNumberFor<T>>> {
 		// First, check where the watermark CANNOT legally land.
 		//
@@ -1126,7 +1130,9 @@ impl<T: Config> Pallet<T> {
 			})
 		}
 
-		if let Some(last_watermark) = HrmpWatermarks::<T>::get(&recipient) {
+		if let Some(last_watermark) =
+			HrmpWatermarks::<T>::get(&recipient).max(pending_hrmp_watermark)
+		{
 			if new_hrmp_watermark <= last_watermark {
 				return Err(HrmpWatermarkAcceptanceErr::AdvancementRule {
 					new_watermark: new_hrmp_watermark,
@@ -1159,10 +1165,14 @@ impl<T: Config> Pallet<T> {
 			.collect()
 	}
 
+	///
+	/// `pending_hrmp_msgs` are the number and total size of the messages sent to each recipient by
+	/// the candidates of the para which are not enacted yet.
 	pub(crate) fn check_outbound_hrmp(
 		config: &HostConfiguration<BlockNumberFor<T>>,
 		sender: ParaId,
 		out_hrmp_msgs: &[OutboundHrmpMessage<ParaId>],
+		pending_hrmp_msgs: &BTreeMap<ParaId, (u32, u32)>,
 	) -> Result<(), OutboundHrmpAcceptanceErr> {
 		if out_hrmp_msgs.len() as u32 > config.hrmp_max_message_num_per_candidate {
 			return Err(OutboundHrmpAcceptanceErr::MoreMessagesThanPermitted {
@@ -1201,7 +1211,10 @@ impl<T: Config> Pallet<T> {
 				})
 			}
 
-			let new_total_size = channel.total_size + out_msg.data.len() as u32;
+			let (pending_count, pending_size) =
+				pending_hrmp_msgs.get(&out_msg.recipient).copied().unwrap_or_default();
+
+			let new_total_size = channel.total_size + pending_size + out_msg.data.len() as u32;
 			if new_total_size > channel.max_total_size {
 				return Err(OutboundHrmpAcceptanceErr::TotalSizeExceeded {
 					idx,
@@ -1210,7 +1223,7 @@ impl<T: Config> Pallet<T> {
 				})
 			}
 
-			let new_msg_count = channel.msg_count + 1;
+			let new_msg_count = channel.msg_count + pending_count + 1;
 			if new_msg_count > channel.max_capacity {
 				return Err(OutboundHrmpAcceptanceErr::CapacityExceeded {
 					idx,
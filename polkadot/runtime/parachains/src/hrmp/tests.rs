@@ -452,14 +452,19 @@ fn send_recv_messages() {
 				.try_into()
 				.unwrap();
 		let config = Configuration::config();
-		assert!(Hrmp::check_outbound_hrmp(&config, para_a, &msgs).is_ok());
+		assert!(Hrmp::check_outbound_hrmp(&config, para_a, &msgs, &Default::default()).is_ok());
+		// The channel is filled up by candidates of A pending enactment.
+		let pending = BTreeMap::from([(para_b, (2, 0))]);
+		assert!(Hrmp::check_outbound_hrmp(&config, para_a, &msgs, &pending).is_err());
 		let _ = Hrmp::queue_outbound_hrmp(para_a, msgs);
 		Hrmp::assert_storage_consistency_exhaustive();
 
 		// On Block 7:
 		// B receives the message sent by A. B sets the watermark to 6.
 		run_to_block(7, None);
-		assert!(Hrmp::check_hrmp_watermark(para_b, 7, 6).is_ok());
+		assert!(Hrmp::check_hrmp_watermark(para_b, 7, 6, None).is_ok());
+		// A candidate of B pending enactment already set the watermark to 6.
+		assert!(Hrmp::check_hrmp_watermark(para_b, 7, 6, Some(6)).is_err());
 		let _ = Hrmp::prune_hrmp(para_b, 6);
 		Hrmp::assert_storage_consistency_exhaustive();
 	});
@@ -561,7 +566,7 @@ fn check_sent_messages() {
 				.try_into()
 				.unwrap();
 		let config = Configuration::config();
-		assert!(Hrmp::check_outbound_hrmp(&config, para_a, &msgs).is_ok());
+		assert!(Hrmp::check_outbound_hrmp(&config, para_a, &msgs, &Default::default()).is_ok());
 		let _ = Hrmp::queue_outbound_hrmp(para_a, msgs.clone());
 
 		// Verify that the sent messages are there and that also the empty channels are present.
@@ -852,21 +857,21 @@ fn watermark_maxed_out_at_relay_parent() {
 				.try_into()
 				.unwrap();
 		let config = Configuration::config();
-		assert!(Hrmp::check_outbound_hrmp(&config, para_a, &msgs).is_ok());
+		assert!(Hrmp::check_outbound_hrmp(&config, para_a, &msgs, &Default::default()).is_ok());
 		let _ = Hrmp::queue_outbound_hrmp(para_a, msgs);
 		Hrmp::assert_storage_consistency_exhaustive();
 
 		// On block 8:
 		// B receives the message sent by A. B sets the watermark to 7.
 		run_to_block(8, None);
-		assert!(Hrmp::check_hrmp_watermark(para_b, 7, 7).is_ok());
+		assert!(Hrmp::check_hrmp_watermark(para_b, 7, 7, None).is_ok());
 		let _ = Hrmp::prune_hrmp(para_b, 7);
 		Hrmp::assert_storage_consistency_exhaustive();
 
 		// On block 9:
 		// B includes a candidate with the same relay parent as before.
 		run_to_block(9, None);
-		assert!(Hrmp::check_hrmp_watermark(para_b, 7, 7).is_ok());
+		assert!(Hrmp::check_hrmp_watermark(para_b, 7, 7, None).is_ok());
 		let _ = Hrmp::prune_hrmp(para_b, 7);
 		Hrmp::assert_storage_consistency_exhaustive();
 	});
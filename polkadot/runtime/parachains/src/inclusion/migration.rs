@@ -0,0 +1,229 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A module that is responsible for migration of storage.
+
+use super::*;
+use frame_support::{
+	migrations::VersionedMigration, storage_alias, traits::OnRuntimeUpgrade, weights::Weight,
+};
+
+mod v0 {
+	use super::*;
+
+	/// A candidate pending availability, before the commitments were stored along with it.
+	#[derive(Encode, Decode, PartialEq, TypeInfo, Clone)]
+	#[cfg_attr(test, derive(Debug))]
+	pub struct CandidatePendingAvailability<H, N> {
+		pub core: CoreIndex,
+		pub hash: CandidateHash,
+		pub descriptor: CandidateDescriptor<H>,
+		pub availability_votes: BitVec<u8, BitOrderLsb0>,
+		pub backers: BitVec<u8, BitOrderLsb0>,
+		pub relay_parent_number: N,
+		pub backed_in_number: N,
+		pub backing_group: GroupIndex,
+	}
+
+	#[storage_alias]
+	pub(super) type PendingAvailability<T: Config> = StorageMap<
+		Pallet<T>,
+		Twox64Concat,
+		ParaId,
+		CandidatePendingAvailability<<T as frame_system::Config>::Hash, BlockNumberFor<T>>,
+	>;
+
+	#[storage_alias]
+	pub(super) type PendingAvailabilityCommitments<T: Config> =
+		StorageMap<Pallet<T>, Twox64Concat, ParaId, CandidateCommitments>;
+}
+
+pub mod v1 {
+	use super::*;
+
+	#[allow(deprecated)]
+	pub type MigrateToV1<T> = VersionedMigration<
+		0,
+		1,
+		UncheckedMigrateToV1<T>,
+		Pallet<T>,
+		<T as frame_system::Config>::DbWeight,
+	>;
+
+	#[deprecated(note = "Use MigrateToV1 instead")]
+	pub struct UncheckedMigrateToV1<T>(sp_std::marker::PhantomData<T>);
+	#[allow(deprecated)]
+	impl<T: Config> OnRuntimeUpgrade for UncheckedMigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let weight_consumed = migrate_to_v1::<T>();
+
+			log::info!(target: LOG_TARGET, "Migrating para inclusion storage to v1");
+
+			weight_consumed
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::DispatchError> {
+			// Candidates without commitments are dropped.
+			let n = v0::PendingAvailability::<T>::iter_keys()
+				.filter(|para_id| v0::PendingAvailabilityCommitments::<T>::contains_key(para_id))
+				.count() as u32;
+
+			log::info!(target: LOG_TARGET, "Number of candidates pending availability before: {n}");
+
+			Ok(n.encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::DispatchError> {
+			log::info!(target: LOG_TARGET, "Running post_upgrade()");
+
+			ensure!(
+				v0::PendingAvailabilityCommitments::<T>::iter().next().is_none(),
+				"PendingAvailabilityCommitments should be empty after the migration"
+			);
+
+			let expected = u32::decode(&mut &state[..]).unwrap();
+			let migrated =
+				super::PendingAvailability::<T>::iter_values().map(|c| c.len()).sum::<usize>();
+
+			ensure!(
+				migrated as u32 == expected,
+				"PendingAvailability should hold one candidate per para pending before"
+			);
+
+			Ok(())
+		}
+	}
+}
+
+pub fn migrate_to_v1<T: Config>() -> Weight {
+	let mut weight: Weight = Weight::zero();
+
+	let pending = v0::PendingAvailability::<T>::drain().collect::<Vec<_>>();
+	let pending_len = pending.len() as u64;
+	let mut dropped_cores = Vec::new();
+
+	for (para_id, candidate) in pending {
+		// Candidates without commitments can't be enacted, so they are dropped.
+		let Some(commitments) = v0::PendingAvailabilityCommitments::<T>::take(&para_id) else {
+			dropped_cores.push((candidate.core, scheduler::FreedReason::TimedOut));
+			continue
+		};
+
+		let candidate = CandidatePendingAvailability {
+			core: candidate.core,
+			hash: candidate.hash,
+			descriptor: candidate.descriptor,
+			commitments,
+			availability_votes: candidate.availability_votes,
+			backers: candidate.backers,
+			relay_parent_number: candidate.relay_parent_number,
+			backed_in_number: candidate.backed_in_number,
+			backing_group: candidate.backing_group,
+		};
+
+		super::PendingAvailability::<T>::insert(para_id, VecDeque::from([candidate]));
+	}
+
+	// Commitments without a candidate are stale.
+	let stale = v0::PendingAvailabilityCommitments::<T>::drain().count() as u64;
+
+	// reading and killing both maps, writing the merged one
+	weight = weight.saturating_add(
+		T::DbWeight::get().reads_writes(2 * pending_len + stale, 3 * pending_len + stale),
+	);
+
+	// The cores of the dropped candidates would otherwise stay occupied forever, as only the
+	// candidates pending availability are ever timed out. Their assignments are handed back.
+	if !dropped_cores.is_empty() {
+		let now = frame_system::Pallet::<T>::block_number();
+		<scheduler::Pallet<T>>::free_cores_and_fill_claimqueue(dropped_cores, now);
+
+		// the availability cores, the claim queue, the validator groups and the configuration,
+		// plus the assignment provider
+		weight = weight.saturating_add(T::DbWeight::get().reads_writes(6, 4));
+	}
+
+	weight
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		mock::{new_test_ext, MockGenesisConfig, Test},
+		scheduler::{common::Assignment, CoreOccupied, ParasEntry},
+	};
+	use primitives::Id as ParaId;
+	use test_helpers::{dummy_candidate_descriptor, dummy_hash};
+
+	#[test]
+	fn migrate_to_v1() {
+		new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+			let para_id = ParaId::from(1);
+			let commitments =
+				CandidateCommitments { head_data: vec![1, 2, 3].into(), ..Default::default() };
+			let candidate = v0::CandidatePendingAvailability {
+				core: CoreIndex(2),
+				hash: CandidateHash(Hash::repeat_byte(1)),
+				descriptor: dummy_candidate_descriptor(dummy_hash()),
+				availability_votes: Default::default(),
+				backers: Default::default(),
+				relay_parent_number: 4,
+				backed_in_number: 5,
+				backing_group: GroupIndex(3),
+			};
+			v0::PendingAvailability::<Test>::insert(para_id, candidate.clone());
+			v0::PendingAvailabilityCommitments::<Test>::insert(para_id, commitments.clone());
+			// Stale commitments of a para without a candidate pending availability.
+			v0::PendingAvailabilityCommitments::<Test>::insert(
+				ParaId::from(2),
+				commitments.clone(),
+			);
+			// A candidate without commitments, occupying core 1.
+			let dropped_para_id = ParaId::from(3);
+			v0::PendingAvailability::<Test>::insert(
+				dropped_para_id,
+				v0::CandidatePendingAvailability { core: CoreIndex(1), ..candidate.clone() },
+			);
+			scheduler::AvailabilityCores::<Test>::put(vec![
+				CoreOccupied::Free,
+				CoreOccupied::Paras(ParasEntry::new(Assignment::new(dropped_para_id), 10)),
+				CoreOccupied::Paras(ParasEntry::new(Assignment::new(para_id), 10)),
+			]);
+
+			super::migrate_to_v1::<Test>();
+
+			assert!(v0::PendingAvailabilityCommitments::<Test>::iter().next().is_none());
+			assert_eq!(super::PendingAvailability::<Test>::iter().count(), 1);
+
+			// The core of the dropped candidate is freed.
+			let cores = scheduler::AvailabilityCores::<Test>::get();
+			assert!(cores[1].is_free());
+			assert!(!cores[2].is_free());
+
+			let migrated = super::PendingAvailability::<Test>::get(para_id).unwrap();
+			assert_eq!(migrated.len(), 1);
+			assert_eq!(migrated[0].core, candidate.core);
+			assert_eq!(migrated[0].hash, candidate.hash);
+			assert_eq!(migrated[0].commitments, commitments);
+			assert_eq!(migrated[0].relay_parent_number, candidate.relay_parent_number);
+			assert_eq!(migrated[0].backed_in_number, candidate.backed_in_number);
+			assert_eq!(migrated[0].backing_group, candidate.backing_group);
+		});
+	}
+}
@@ -30,7 +30,7 @@ use bitvec::{order::Lsb0 as BitOrderLsb0, vec::BitVec};
 use frame_support::{
 	defensive,
 	pallet_prelude::*,
-	traits::{Defensive, EnqueueMessage, Footprint, QueueFootprint},
+	traits::{EnqueueMessage, Footprint, QueueFootprint},
 	BoundedSlice,
 };
 use frame_system::pallet_prelude::*;
@@ -48,7 +48,7 @@ use sp_runtime::{traits::One, DispatchError, SaturatedConversion, Saturating};
 #[cfg(feature = "std")]
 use sp_std::fmt;
 use sp_std::{
-	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet, vec_deque::VecDeque},
 	prelude::*,
 };
 
@@ -57,6 +57,8 @@ pub use pallet::*;
 #[cfg(test)]
 pub(crate) mod tests;
 
+pub mod migration;
+
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
@@ -105,6 +107,8 @@ pub struct CandidatePendingAvailability<H, N> {
 	hash: CandidateHash,
 	/// The candidate descriptor.
 	descriptor: CandidateDescriptor<H>,
+	/// The candidate commitments.
+	commitments: CandidateCommitments,
 	/// The received availability votes. One bit per validator.
 	availability_votes: BitVec<u8, BitOrderLsb0>,
 	/// The backers of the candidate pending availability.
@@ -143,6 +147,11 @@ impl<H, N> CandidatePendingAvailability<H, N> {
 		&self.descriptor
 	}
 
+	/// Get the candidate commitments.
+	pub(crate) fn candidate_commitments(&self) -> &CandidateCommitments {
+		&self.commitments
+	}
+
 	/// Get the candidate's relay parent's number.
 	pub(crate) fn relay_parent_number(&self) -> N
 	where
@@ -156,6 +165,7 @@ impl<H, N> CandidatePendingAvailability<H, N> {
 		core: CoreIndex,
 		hash: CandidateHash,
 		descriptor: CandidateDescriptor<H>,
+		commitments: CandidateCommitments,
 		availability_votes: BitVec<u8, BitOrderLsb0>,
 		backers: BitVec<u8, BitOrderLsb0>,
 		relay_parent_number: N,
@@ -166,6 +176,7 @@ impl<H, N> CandidatePendingAvailability<H, N> {
 			core,
 			hash,
 			descriptor,
+			commitments,
 			availability_votes,
 			backers,
 			relay_parent_number,
@@ -256,8 +267,11 @@ pub type MaxUmpMessageLenOf<T> =
 pub mod pallet {
 	use super::*;
 
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::without_storage_info]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::config]
@@ -320,10 +334,6 @@ pub mod pallet {
 		InvalidBitfieldSignature,
 		/// Candidate submitted but para not scheduled.
 		UnscheduledCandidate,
-		/// Candidate scheduled despite pending candidate already existing for the para.
-		CandidateScheduledBeforeParaFree,
-		/// Scheduled cores out of order.
-		ScheduledOutOfOrder,
 		/// Head data exceeds the configured maximum.
 		HeadDataTooLarge,
 		/// Code upgrade prematurely.
@@ -371,19 +381,17 @@ pub mod pallet {
 		StorageMap<_, Twox64Concat, ValidatorIndex, AvailabilityBitfieldRecord<BlockNumberFor<T>>>;
 
 	/// Candidates pending availability by `ParaId`.
+	///
+	/// A para occupying several cores has several candidates pending availability. They form a
+	/// chain on top of the para's latest included head, in the order they are stored in.
 	#[pallet::storage]
 	pub(crate) type PendingAvailability<T: Config> = StorageMap<
 		_,
 		Twox64Concat,
 		ParaId,
-		CandidatePendingAvailability<T::Hash, BlockNumberFor<T>>,
+		VecDeque<CandidatePendingAvailability<T::Hash, BlockNumberFor<T>>>,
 	>;
 
-	/// The commitments of candidates pending availability, by `ParaId`.
-	#[pallet::storage]
-	pub(crate) type PendingAvailabilityCommitments<T: Config> =
-		StorageMap<_, Twox64Concat, ParaId, CandidateCommitments>;
-
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {}
 }
@@ -472,7 +480,6 @@ impl<T: Config> Pallet<T> {
 	) {
 		// unlike most drain methods, drained elements are not cleared on `Drop` of the iterator
 		// and require consumption.
-		for _ in <PendingAvailabilityCommitments<T>>::drain() {}
 		for _ in <PendingAvailability<T>>::drain() {}
 		for _ in <AvailabilityBitfields<T>>::drain() {}
 
@@ -506,12 +513,14 @@ impl<T: Config> Pallet<T> {
 	where
 		F: Fn(CoreIndex) -> Option<ParaId>,
 	{
-		let mut assigned_paras_record = (0..expected_bits)
+		let core_paras = (0..expected_bits)
 			.map(|bit_index| core_lookup(CoreIndex::from(bit_index as u32)))
-			.map(|opt_para_id| {
-				opt_para_id.map(|para_id| (para_id, PendingAvailability::<T>::get(&para_id)))
-			})
 			.collect::<Vec<_>>();
+		let mut pending_by_para = core_paras
+			.iter()
+			.flatten()
+			.filter_map(|para_id| Some((*para_id, PendingAvailability::<T>::get(para_id)?)))
+			.collect::<BTreeMap<_, _>>();
 
 		let now = <frame_system::Pallet<T>>::block_number();
 		for (checked_bitfield, validator_index) in
@@ -521,25 +530,29 @@ impl<T: Config> Pallet<T> {
 				(checked_bitfield, validator_idx)
 			}) {
 			for (bit_idx, _) in checked_bitfield.0.iter().enumerate().filter(|(_, is_av)| **is_av) {
-				let pending_availability = if let Some((_, pending_availability)) =
-					assigned_paras_record[bit_idx].as_mut()
+				let core_index = CoreIndex::from(bit_idx as u32);
+				let pending_availability = if let Some(pending_availability) = core_paras
+					.get(bit_idx)
+					.copied()
+					.flatten()
+					.and_then(|para_id| pending_by_para.get_mut(&para_id))
+					.and_then(|candidates| candidates.iter_mut().find(|c| c.core == core_index))
 				{
 					pending_availability
 				} else {
 					// For honest validators, this happens in case of unoccupied cores,
 					// which in turn happens in case of a disputed candidate.
-					// A malicious one might include arbitrary indices, but they are represented
-					// by `None` values and will be sorted out in the next if case.
+					// A malicious one might include arbitrary indices, which don't match any
+					// candidate pending availability.
 					continue
 				};
 
-				// defensive check - this is constructed by loading the availability bitfield
-				// record, which is always `Some` if the core is occupied - that's why we're here.
+				// defensive check - the votes are sized to the number of validators and the
+				// validator index has been checked while sanitizing the bitfields.
 				let validator_index = validator_index.0 as usize;
 				if let Some(mut bit) =
-					pending_availability.as_mut().and_then(|candidate_pending_availability| {
-						candidate_pending_availability.availability_votes.get_mut(validator_index)
-					}) {
+					pending_availability.availability_votes.get_mut(validator_index)
+				{
 					*bit = true;
 				}
 			}
@@ -553,28 +566,19 @@ impl<T: Config> Pallet<T> {
 		let threshold = availability_threshold(validators.len());
 
 		let mut freed_cores = Vec::with_capacity(expected_bits);
-		for (para_id, pending_availability) in assigned_paras_record
-			.into_iter()
-			.flatten()
-			.filter_map(|(id, p)| p.map(|p| (id, p)))
-		{
-			if pending_availability.availability_votes.count_ones() >= threshold {
-				<PendingAvailability<T>>::remove(&para_id);
-				let commitments = match PendingAvailabilityCommitments::<T>::take(&para_id) {
-					Some(commitments) => commitments,
-					None => {
-						log::warn!(
-							target: LOG_TARGET,
-							"Inclusion::process_bitfields: PendingAvailability and PendingAvailabilityCommitments
-							are out of sync, did someone mess with the storage?",
-						);
-						continue
-					},
-				};
+		for (para_id, mut candidates) in pending_by_para {
+			// Each candidate builds on top of its predecessor, so an available candidate can only
+			// be enacted once all candidates before it have been.
+			while candidates
+				.front()
+				.map_or(false, |c| c.availability_votes.count_ones() >= threshold)
+			{
+				let pending_availability =
+					candidates.pop_front().expect("front candidate exists; qed");
 
 				let receipt = CommittedCandidateReceipt {
 					descriptor: pending_availability.descriptor,
-					commitments,
+					commitments: pending_availability.commitments,
 				};
 				let _weight = Self::enact_candidate(
 					pending_availability.relay_parent_number,
@@ -586,23 +590,29 @@ impl<T: Config> Pallet<T> {
 				);
 
 				freed_cores.push((pending_availability.core, pending_availability.hash));
+			}
+
+			if candidates.is_empty() {
+				<PendingAvailability<T>>::remove(&para_id);
 			} else {
-				<PendingAvailability<T>>::insert(&para_id, &pending_availability);
+				<PendingAvailability<T>>::insert(&para_id, candidates);
 			}
 		}
 
 		freed_cores
 	}
 
-	/// Process candidates that have been backed. Provide the relay storage root, a set of
-	/// candidates and scheduled cores.
+	/// Process candidates that have been backed. Provide the relay storage root, the candidates
+	/// of each para and the cores scheduled for each para.
 	///
-	/// Both should be sorted ascending by core index, and the candidates should be a subset of
-	/// scheduled cores. If these conditions are not met, the execution of the function fails.
+	/// The candidates of a para must form a chain on top of the para's latest head, which is the
+	/// head of its latest candidate pending availability if there is one, and each of them must
+	/// be backed by the group assigned to a distinct core scheduled for the para. If these
+	/// conditions are not met, the execution of the function fails.
 	pub(crate) fn process_candidates<GV>(
 		allowed_relay_parents: &AllowedRelayParentsTracker<T::Hash, BlockNumberFor<T>>,
-		candidates: Vec<BackedCandidate<T::Hash>>,
-		scheduled: &BTreeMap<ParaId, CoreIndex>,
+		candidates: BTreeMap<ParaId, Vec<BackedCandidate<T::Hash>>>,
+		scheduled: &BTreeMap<ParaId, BTreeSet<CoreIndex>>,
 		group_validators: GV,
 	) -> Result<ProcessedCandidates<T::Hash>, DispatchError>
 	where
@@ -610,62 +620,29 @@ impl<T: Config> Pallet<T> {
 	{
 		let now = <frame_system::Pallet<T>>::block_number();
 
-		ensure!(candidates.len() <= scheduled.len(), Error::<T>::UnscheduledCandidate);
-
-		if scheduled.is_empty() {
+		if candidates.is_empty() {
 			return Ok(ProcessedCandidates::default())
 		}
 
 		let minimum_backing_votes = configuration::Pallet::<T>::config().minimum_backing_votes;
 		let validators = shared::Pallet::<T>::active_validator_keys();
+		let session_index = shared::Pallet::<T>::session_index();
 
 		// Collect candidate receipts with backers.
-		let mut candidate_receipt_with_backing_validator_indices =
-			Vec::with_capacity(candidates.len());
+		let mut candidate_receipt_with_backing_validator_indices = Vec::new();
 
 		// Do all checks before writing storage.
-		let core_indices_and_backers = {
-			let mut core_indices_and_backers = Vec::with_capacity(candidates.len());
-			let mut last_core = None;
-
-			let mut check_assignment_in_order = |core_idx| -> DispatchResult {
-				ensure!(
-					last_core.map_or(true, |core| core_idx > core),
-					Error::<T>::ScheduledOutOfOrder,
-				);
-
-				last_core = Some(core_idx);
-				Ok(())
-			};
-
-			// We combine an outer loop over candidates with an inner loop over the scheduled,
-			// where each iteration of the outer loop picks up at the position
-			// in scheduled just after the past iteration left off.
-			//
-			// If the candidates appear in the same order as they appear in `scheduled`,
-			// then they should always be found. If the end of `scheduled` is reached,
-			// then the candidate was either not scheduled or out-of-order.
-			//
-			// In the meantime, we do certain sanity checks on the candidates and on the scheduled
-			// list.
-			for (candidate_idx, backed_candidate) in candidates.iter().enumerate() {
-				let relay_parent_hash = backed_candidate.descriptor().relay_parent;
-				let para_id = backed_candidate.descriptor().para_id;
-
-				let prev_context = <paras::Pallet<T>>::para_most_recent_context(para_id);
-
-				let check_ctx = CandidateCheckContext::<T>::new(prev_context);
-				let signing_context = SigningContext {
-					parent_hash: relay_parent_hash,
-					session_index: shared::Pallet::<T>::session_index(),
-				};
-
-				let relay_parent_number = match check_ctx.verify_backed_candidate(
-					&allowed_relay_parents,
-					candidate_idx,
-					backed_candidate,
-				)? {
-					Err(FailedToCreatePVD) => {
+		let mut core_indices_and_backers = Vec::new();
+		let mut candidate_idx = 0;
+		for (para_id, para_candidates) in &candidates {
+			let mut free_cores = scheduled.get(para_id).cloned().unwrap_or_default();
+			ensure!(para_candidates.len() <= free_cores.len(), Error::<T>::UnscheduledCandidate);
+
+			let mut pending_outputs = Self::pending_outputs(*para_id);
+			let (mut latest_head, mut latest_context) =
+				match Self::para_latest_head_data_and_context(*para_id) {
+					Some(head_and_context) => head_and_context,
+					None => {
 						log::debug!(
 							target: LOG_TARGET,
 							"Failed to create PVD for candidate {}",
@@ -676,104 +653,91 @@ impl<T: Config> Pallet<T> {
 						// doing anything.
 						return Ok(ProcessedCandidates::default())
 					},
-					Ok(rpn) => rpn,
 				};
 
-				let para_id = backed_candidate.descriptor().para_id;
-				let mut backers = bitvec::bitvec![u8, BitOrderLsb0; 0; validators.len()];
-
-				let core_idx = *scheduled.get(&para_id).ok_or(Error::<T>::UnscheduledCandidate)?;
-				check_assignment_in_order(core_idx)?;
+			for backed_candidate in para_candidates {
 				ensure!(
-					<PendingAvailability<T>>::get(&para_id).is_none() &&
-						<PendingAvailabilityCommitments<T>>::get(&para_id).is_none(),
-					Error::<T>::CandidateScheduledBeforeParaFree,
+					backed_candidate.descriptor().para_id == *para_id,
+					Error::<T>::UnscheduledCandidate,
 				);
 
-				// The candidate based upon relay parent `N` should be backed by a group
-				// assigned to core at block `N + 1`. Thus, `relay_parent_number + 1`
-				// will always land in the current session.
-				let group_idx = <scheduler::Pallet<T>>::group_assigned_to_core(
-					core_idx,
-					relay_parent_number + One::one(),
-				)
-				.ok_or_else(|| {
-					log::warn!(
-						target: LOG_TARGET,
-						"Failed to compute group index for candidate {}",
-						candidate_idx
-					);
-					Error::<T>::InvalidAssignment
-				})?;
-				let group_vals =
-					group_validators(group_idx).ok_or_else(|| Error::<T>::InvalidGroupIndex)?;
+				let check_ctx = CandidateCheckContext::<T>::new(latest_context);
+				let relay_parent_number = check_ctx.verify_backed_candidate(
+					&allowed_relay_parents,
+					candidate_idx,
+					backed_candidate,
+					latest_head,
+					&pending_outputs,
+				)?;
 
-				// check the signatures in the backing and that it is a majority.
-				{
-					let maybe_amount_validated = primitives::check_candidate_backing(
-						&backed_candidate,
-						&signing_context,
-						group_vals.len(),
-						|intra_group_vi| {
-							group_vals
-								.get(intra_group_vi)
-								.and_then(|vi| validators.get(vi.0 as usize))
-								.map(|v| v.clone())
-						},
-					);
+				let signing_context = SigningContext {
+					parent_hash: backed_candidate.descriptor().relay_parent,
+					session_index,
+				};
 
-					match maybe_amount_validated {
-						Ok(amount_validated) => ensure!(
-							amount_validated >=
-								effective_minimum_backing_votes(
-									group_vals.len(),
-									minimum_backing_votes
-								),
-							Error::<T>::InsufficientBacking,
-						),
-						Err(()) => {
-							Err(Error::<T>::InvalidBacking)?;
-						},
+				// A para scheduled on several cores is backed by several groups. The candidate
+				// occupies the first free core whose group has backed it.
+				let mut backing = Err(Error::<T>::UnscheduledCandidate);
+				for core_idx in free_cores.iter().copied() {
+					backing = Self::check_backing_on_core(
+						core_idx,
+						candidate_idx,
+						relay_parent_number,
+						backed_candidate,
+						&signing_context,
+						&validators,
+						minimum_backing_votes,
+						&group_validators,
+					)
+					.map(|(group_idx, group_vals)| (core_idx, group_idx, group_vals));
+
+					if backing.is_ok() {
+						break
 					}
+				}
+				let (core_idx, group_idx, group_vals) = backing?;
+				free_cores.remove(&core_idx);
 
-					let mut backer_idx_and_attestation =
-						Vec::<(ValidatorIndex, ValidityAttestation)>::with_capacity(
-							backed_candidate.validator_indices.count_ones(),
-						);
-					let candidate_receipt = backed_candidate.receipt();
-
-					for ((bit_idx, _), attestation) in backed_candidate
-						.validator_indices
-						.iter()
-						.enumerate()
-						.filter(|(_, signed)| **signed)
-						.zip(backed_candidate.validity_votes.iter().cloned())
-					{
-						let val_idx =
-							group_vals.get(bit_idx).expect("this query succeeded above; qed");
-						backer_idx_and_attestation.push((*val_idx, attestation));
-
-						backers.set(val_idx.0 as _, true);
-					}
-					candidate_receipt_with_backing_validator_indices
-						.push((candidate_receipt, backer_idx_and_attestation));
+				let mut backers = bitvec::bitvec![u8, BitOrderLsb0; 0; validators.len()];
+				let mut backer_idx_and_attestation =
+					Vec::<(ValidatorIndex, ValidityAttestation)>::with_capacity(
+						backed_candidate.validator_indices.count_ones(),
+					);
+				let candidate_receipt = backed_candidate.receipt();
+
+				for ((bit_idx, _), attestation) in backed_candidate
+					.validator_indices
+					.iter()
+					.enumerate()
+					.filter(|(_, signed)| **signed)
+					.zip(backed_candidate.validity_votes.iter().cloned())
+				{
+					let val_idx = group_vals.get(bit_idx).expect("this query succeeded above; qed");
+					backer_idx_and_attestation.push((*val_idx, attestation));
+
+					backers.set(val_idx.0 as _, true);
 				}
+				candidate_receipt_with_backing_validator_indices
+					.push((candidate_receipt, backer_idx_and_attestation));
 
 				core_indices_and_backers.push((
-					(core_idx, para_id),
+					(core_idx, *para_id),
 					backers,
 					group_idx,
 					relay_parent_number,
 				));
-			}
 
-			core_indices_and_backers
-		};
+				latest_head = backed_candidate.candidate.commitments.head_data.clone();
+				latest_context = Some(relay_parent_number);
+				pending_outputs.note(&backed_candidate.candidate.commitments);
+				candidate_idx += 1;
+			}
+		}
 
 		// one more sweep for actually writing to storage.
 		let core_indices = core_indices_and_backers.iter().map(|(c, ..)| *c).collect();
 		for (candidate, (core, backers, group, relay_parent_number)) in
-			candidates.into_iter().zip(core_indices_and_backers)
+			candidates.into_values().flatten().zip(core_indices_and_backers)
 		{
 			let para_id = candidate.descriptor().para_id;
 
@@ -793,20 +757,21 @@ impl<T: Config> Pallet<T> {
 			let (descriptor, commitments) =
 				(candidate.candidate.descriptor, candidate.candidate.commitments);
 
-			<PendingAvailability<T>>::insert(
-				&para_id,
-				CandidatePendingAvailability {
-					core: core.0,
-					hash: candidate_hash,
-					descriptor,
-					availability_votes,
-					relay_parent_number,
-					backers: backers.to_bitvec(),
-					backed_in_number: now,
-					backing_group: group,
-				},
-			);
-			<PendingAvailabilityCommitments<T>>::insert(&para_id, commitments);
+			<PendingAvailability<T>>::mutate(&para_id, |candidates| {
+				candidates.get_or_insert_with(VecDeque::new).push_back(
+					CandidatePendingAvailability {
+						core: core.0,
+						hash: candidate_hash,
+						descriptor,
+						commitments,
+						availability_votes,
+						relay_parent_number,
+						backers: backers.to_bitvec(),
+						backed_in_number: now,
+						backing_group: group,
+					},
+				)
+			});
 		}
 
 		Ok(ProcessedCandidates::<T::Hash> {
@@ -815,6 +780,64 @@ impl<T: Config> Pallet<T> {
 		})
 	}
 
+	/// Check that the candidate is backed by enough validators of the group assigned to the given
+	/// core, returning that group and its validators.
+	fn check_backing_on_core<GV>(
+		core_idx: CoreIndex,
+		candidate_idx: usize,
+		relay_parent_number: BlockNumberFor<T>,
+		backed_candidate: &BackedCandidate<T::Hash>,
+		signing_context: &SigningContext<T::Hash>,
+		validators: &[ValidatorId],
+		minimum_backing_votes: u32,
+		group_validators: &GV,
+	) -> Result<(GroupIndex, Vec<ValidatorIndex>), Error<T>>
+	where
+		GV: Fn(GroupIndex) -> Option<Vec<ValidatorIndex>>,
+	{
+		// The candidate based upon relay parent `N` should be backed by a group
+		// assigned to core at block `N + 1`. Thus, `relay_parent_number + 1`
+		// will always land in the current session.
+		let group_idx = <scheduler::Pallet<T>>::group_assigned_to_core(
+			core_idx,
+			relay_parent_number + One::one(),
+		)
+		.ok_or_else(|| {
+			log::warn!(
+				target: LOG_TARGET,
+				"Failed to compute group index for candidate {}",
+				candidate_idx
+			);
+			Error::<T>::InvalidAssignment
+		})?;
+		let group_vals =
+			group_validators(group_idx).ok_or_else(|| Error::<T>::InvalidGroupIndex)?;
+
+		// check the signatures in the backing and that it is a majority.
+		let maybe_amount_validated = primitives::check_candidate_backing(
+			&backed_candidate,
+			signing_context,
+			group_vals.len(),
+			|intra_group_vi| {
+				group_vals
+					.get(intra_group_vi)
+					.and_then(|vi| validators.get(vi.0 as usize))
+					.map(|v| v.clone())
+			},
+		);
+
+		match maybe_amount_validated {
+			Ok(amount_validated) => ensure!(
+				amount_validated >=
+					effective_minimum_backing_votes(group_vals.len(), minimum_backing_votes),
+				Error::<T>::InsufficientBacking,
+			),
+			Err(()) => return Err(Error::<T>::InvalidBacking),
+		}
+
+		Ok((group_idx, group_vals))
+	}
+
 	/// Run the acceptance criteria checks on the given candidate commitments.
 	pub(crate) fn check_validation_outputs_for_runtime_api(
 		para_id: ParaId,
@@ -834,6 +857,7 @@ impl<T: Config> Pallet<T> {
 				&validation_outputs.upward_messages,
 				BlockNumberFor::<T>::from(validation_outputs.hrmp_watermark),
 				&validation_outputs.horizontal_messages,
+				&Default::default(),
 			)
 			.is_err()
 		{
@@ -929,10 +953,14 @@ impl<T: Config> Pallet<T> {
 	}
 
 	/// Check that all the upward messages sent by a candidate pass the acceptance criteria.
+	///
+	/// `pending_upward_messages` are the number and total size of the messages sent by the
+	/// candidates of the para which are not enacted yet.
 	pub(crate) fn check_upward_messages(
 		config: &HostConfiguration<BlockNumberFor<T>>,
 		para: ParaId,
 		upward_messages: &[UpwardMessage],
+		pending_upward_messages: (u32, u32),
 	) -> Result<(), UmpAcceptanceCheckErr> {
 		// Cannot send UMP messages while off-boarding.
 		if <paras::Pallet<T>>::is_offboarding(para) {
@@ -948,6 +976,8 @@ impl<T: Config> Pallet<T> {
 		}
 
 		let (para_queue_count, mut para_queue_size) = Self::relay_dispatch_queue_size(para);
+		let para_queue_count = para_queue_count.saturating_add(pending_upward_messages.0);
+		para_queue_size.saturating_accrue(pending_upward_messages.1);
 
 		if para_queue_count.saturating_add(additional_msgs) > config.max_upward_queue_count {
 			return Err(UmpAcceptanceCheckErr::CapacityExceeded {
@@ -1019,82 +1049,94 @@ impl<T: Config> Pallet<T> {
 		weight
 	}
 
-	/// Cleans up all paras pending availability that the predicate returns true for.
+	/// Cleans up all candidates pending availability that the predicate returns true for, along
+	/// with all candidates building on top of them.
 	///
-	/// The predicate accepts the index of the core and the block number the core has been occupied
-	/// since (i.e. the block number the candidate was backed at in this fork of the relay chain).
+	/// The predicate accepts the block number the core has been occupied since (i.e. the block
+	/// number the candidate was backed at in this fork of the relay chain).
 	///
 	/// Returns a vector of cleaned-up core IDs.
 	pub(crate) fn collect_pending(
 		pred: impl Fn(BlockNumberFor<T>) -> AvailabilityTimeoutStatus<BlockNumberFor<T>>,
 	) -> Vec<CoreIndex> {
-		let mut cleaned_up_ids = Vec::new();
-		let mut cleaned_up_cores = Vec::new();
-
-		for (para_id, pending_record) in <PendingAvailability<T>>::iter() {
-			if pred(pending_record.backed_in_number).timed_out {
-				cleaned_up_ids.push(para_id);
-				cleaned_up_cores.push(pending_record.core);
-			}
-		}
-
-		for para_id in cleaned_up_ids {
-			let pending = <PendingAvailability<T>>::take(&para_id);
-			let commitments = <PendingAvailabilityCommitments<T>>::take(&para_id);
+		let timed_out = <PendingAvailability<T>>::iter()
+			.filter_map(|(para_id, candidates)| {
+				candidates
+					.iter()
+					.position(|c| pred(c.backed_in_number).timed_out)
+					.map(|position| (para_id, position))
+			})
+			.collect::<Vec<_>>();
 
-			if let (Some(pending), Some(commitments)) = (pending, commitments) {
-				// defensive: this should always be true.
+		let mut cleaned_up_cores = Vec::new();
+		for (para_id, position) in timed_out {
+			for pending in Self::remove_pending_from(para_id, position) {
 				let candidate = CandidateReceipt {
 					descriptor: pending.descriptor,
-					commitments_hash: commitments.hash(),
+					commitments_hash: pending.commitments.hash(),
 				};
 
 				Self::deposit_event(Event::<T>::CandidateTimedOut(
 					candidate,
-					commitments.head_data,
+					pending.commitments.head_data,
 					pending.core,
 				));
+				cleaned_up_cores.push(pending.core);
 			}
 		}
 
 		cleaned_up_cores
 	}
 
-	/// Cleans up all paras pending availability that are in the given list of disputed candidates.
+	/// Cleans up all candidates pending availability that are in the given list of disputed
+	/// candidates, along with all candidates building on top of them.
 	///
 	/// Returns a vector of cleaned-up core IDs.
 	pub(crate) fn collect_disputed(disputed: &BTreeSet<CandidateHash>) -> Vec<CoreIndex> {
-		let mut cleaned_up_ids = Vec::new();
-		let mut cleaned_up_cores = Vec::new();
-
-		for (para_id, pending_record) in <PendingAvailability<T>>::iter() {
-			if disputed.contains(&pending_record.hash) {
-				cleaned_up_ids.push(para_id);
-				cleaned_up_cores.push(pending_record.core);
-			}
-		}
+		let disputed_pending = <PendingAvailability<T>>::iter()
+			.filter_map(|(para_id, candidates)| {
+				candidates
+					.iter()
+					.position(|c| disputed.contains(&c.hash))
+					.map(|position| (para_id, position))
+			})
+			.collect::<Vec<_>>();
 
-		for para_id in cleaned_up_ids {
-			let _ = <PendingAvailability<T>>::take(&para_id);
-			let _ = <PendingAvailabilityCommitments<T>>::take(&para_id);
-		}
+		disputed_pending
+			.into_iter()
+			.flat_map(|(para_id, position)| Self::remove_pending_from(para_id, position))
+			.map(|pending| pending.core)
+			.collect()
+	}
 
-		cleaned_up_cores
+	/// Removes the candidates pending availability of the para from the given position in its
+	/// chain onwards and returns them.
+	fn remove_pending_from(
+		para: ParaId,
+		position: usize,
+	) -> Vec<CandidatePendingAvailability<T::Hash, BlockNumberFor<T>>> {
+		<PendingAvailability<T>>::mutate_exists(&para, |maybe_candidates| {
+			let Some(candidates) = maybe_candidates else { return Vec::new() };
+			let removed = candidates.drain(position.min(candidates.len())..).collect();
+			if candidates.is_empty() {
+				*maybe_candidates = None;
+			}
+			removed
+		})
 	}
 
-	/// Forcibly enact the candidate with the given ID as though it had been deemed available
-	/// by bitfields.
+	/// Forcibly enact all candidates pending availability of the given para as though they had
+	/// been deemed available by bitfields.
 	///
 	/// Is a no-op if there is no candidate pending availability for this para-id.
 	/// This should generally not be used but it is useful during execution of Runtime APIs,
 	/// where the changes to the state are expected to be discarded directly after.
 	pub(crate) fn force_enact(para: ParaId) {
-		let pending = <PendingAvailability<T>>::take(&para);
-		let commitments = <PendingAvailabilityCommitments<T>>::take(&para);
-
-		if let (Some(pending), Some(commitments)) = (pending, commitments) {
-			let candidate =
-				CommittedCandidateReceipt { descriptor: pending.descriptor, commitments };
+		for pending in <PendingAvailability<T>>::take(&para).into_iter().flatten() {
+			let candidate = CommittedCandidateReceipt {
+				descriptor: pending.descriptor,
+				commitments: pending.commitments,
+			};
 
 			Self::enact_candidate(
 				pending.relay_parent_number,
@@ -1107,22 +1149,82 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
-	/// Returns the `CommittedCandidateReceipt` pending availability for the para provided, if any.
+	/// Returns the first `CommittedCandidateReceipt` pending availability for the para provided,
+	/// if any.
 	pub(crate) fn candidate_pending_availability(
 		para: ParaId,
 	) -> Option<CommittedCandidateReceipt<T::Hash>> {
+		<PendingAvailability<T>>::get(&para).and_then(|mut candidates| {
+			candidates.pop_front().map(|c| CommittedCandidateReceipt {
+				descriptor: c.descriptor,
+				commitments: c.commitments,
+			})
+		})
+	}
+
+	/// Returns all `CommittedCandidateReceipt`s pending availability for the para provided, in
+	/// the order they build on top of each other.
+	pub(crate) fn candidates_pending_availability(
+		para: ParaId,
+	) -> Vec<CommittedCandidateReceipt<T::Hash>> {
 		<PendingAvailability<T>>::get(&para)
-			.map(|p| p.descriptor)
-			.and_then(|d| <PendingAvailabilityCommitments<T>>::get(&para).map(move |c| (d, c)))
-			.map(|(d, c)| CommittedCandidateReceipt { descriptor: d, commitments: c })
+			.map(|candidates| {
+				candidates
+					.into_iter()
+					.map(|c| CommittedCandidateReceipt {
+						descriptor: c.descriptor,
+						commitments: c.commitments,
+					})
+					.collect()
+			})
+			.unwrap_or_default()
 	}
 
-	/// Returns the metadata around the candidate pending availability for the
+	/// Returns the metadata around the candidates pending availability for the
 	/// para provided, if any.
 	pub(crate) fn pending_availability(
 		para: ParaId,
+	) -> Option<VecDeque<CandidatePendingAvailability<T::Hash, BlockNumberFor<T>>>> {
+		<PendingAvailability<T>>::get(&para)
+	}
+
+	/// Returns the metadata around the candidate of the para provided which is pending
+	/// availability on the given core, if any.
+	pub(crate) fn pending_availability_with_core(
+		para: ParaId,
+		core: CoreIndex,
 	) -> Option<CandidatePendingAvailability<T::Hash, BlockNumberFor<T>>> {
 		<PendingAvailability<T>>::get(&para)
+			.and_then(|candidates| candidates.into_iter().find(|c| c.core == core))
+	}
+
+	/// Returns the outputs of the candidates of the para pending availability, which a new
+	/// candidate of the para builds on.
+	pub(crate) fn pending_outputs(para: ParaId) -> PendingOutputs<BlockNumberFor<T>> {
+		let mut outputs = PendingOutputs::default();
+		for candidate in <PendingAvailability<T>>::get(&para).into_iter().flatten() {
+			outputs.note(&candidate.commitments);
+		}
+
+		outputs
+	}
+
+	/// Returns the head data a new candidate of the para has to build on, along with the
+	/// relay-parent number of the candidate that produced it.
+	///
+	/// That is the head of the latest candidate pending availability, if there is one, and the
+	/// latest included head otherwise.
+	pub(crate) fn para_latest_head_data_and_context(
+		para: ParaId,
+	) -> Option<(HeadData, Option<BlockNumberFor<T>>)> {
+		match <PendingAvailability<T>>::get(&para).and_then(|mut candidates| candidates.pop_back())
+		{
+			Some(latest) => Some((latest.commitments.head_data, Some(latest.relay_parent_number))),
+			None => Some((
+				<paras::Pallet<T>>::para_head(&para)?,
+				<paras::Pallet<T>>::para_most_recent_context(&para),
+			)),
+		}
 	}
 }
 
@@ -1167,16 +1269,52 @@ impl<T: Config> OnQueueChanged<AggregateMessageOrigin> for Pallet<T> {
 	}
 }
 
+/// The outputs of the candidates of a para which are backed but not enacted yet.
+///
+/// The candidates are enacted in order, so a candidate building on top of them must pass the
+/// acceptance criteria against the state these outputs lead to.
+#[derive(Default)]
+pub(crate) struct PendingOutputs<N> {
+	/// The number of processed downward messages.
+	processed_downward_messages: u32,
+	/// The latest HRMP watermark.
+	hrmp_watermark: Option<N>,
+	/// The number and total size of the upward messages.
+	upward_messages: (u32, u32),
+	/// The number and total size of the horizontal messages, per recipient.
+	horizontal_messages: BTreeMap<ParaId, (u32, u32)>,
+	/// Whether a code upgrade is signalled.
+	code_upgrade: bool,
+}
+
+impl<N: Ord + From<u32>> PendingOutputs<N> {
+	/// Add the outputs of the next candidate.
+	pub(crate) fn note(&mut self, commitments: &CandidateCommitments) {
+		self.processed_downward_messages
+			.saturating_accrue(commitments.processed_downward_messages);
+		self.hrmp_watermark =
+			self.hrmp_watermark.take().max(Some(N::from(commitments.hrmp_watermark)));
+
+		for msg in &commitments.upward_messages {
+			self.upward_messages.0.saturating_inc();
+			self.upward_messages.1.saturating_accrue(msg.len() as u32);
+		}
+		for msg in &commitments.horizontal_messages {
+			let (count, size) = self.horizontal_messages.entry(msg.recipient).or_default();
+			count.saturating_inc();
+			size.saturating_accrue(msg.data.len() as u32);
+		}
+
+		self.code_upgrade |= commitments.new_validation_code.is_some();
+	}
+}
+
 /// A collection of data required for checking a candidate.
 pub(crate) struct CandidateCheckContext<T: Config> {
 	config: configuration::HostConfiguration<BlockNumberFor<T>>,
 	prev_context: Option<BlockNumberFor<T>>,
 }
 
-/// An error indicating that creating Persisted Validation Data failed
-/// while checking a candidate's validity.
-pub(crate) struct FailedToCreatePVD;
-
 impl<T: Config> CandidateCheckContext<T> {
 	pub(crate) fn new(prev_context: Option<BlockNumberFor<T>>) -> Self {
 		Self { config: <configuration::Pallet<T>>::config(), prev_context }
@@ -1188,7 +1326,10 @@ impl<T: Config> CandidateCheckContext<T> {
 	///  * relay-parent in-bounds
 	///  * collator signature check passes
 	///  * code hash of commitments matches current code hash
+	///  * persisted validation data matches the one built on top of `parent_head_data`
 	///  * para head in the descriptor and commitments match
+	///  * the outputs pass the acceptance criteria on top of the `pending_outputs` of the
+	///    candidates the candidate builds on
 	///
 	/// Returns the relay-parent block number.
 	pub(crate) fn verify_backed_candidate(
@@ -1196,7 +1337,9 @@ impl<T: Config> CandidateCheckContext<T> {
 		allowed_relay_parents: &AllowedRelayParentsTracker<T::Hash, BlockNumberFor<T>>,
 		candidate_idx: usize,
 		backed_candidate: &BackedCandidate<<T as frame_system::Config>::Hash>,
-	) -> Result<Result<BlockNumberFor<T>, FailedToCreatePVD>, Error<T>> {
+		parent_head_data: HeadData,
+		pending_outputs: &PendingOutputs<BlockNumberFor<T>>,
+	) -> Result<BlockNumberFor<T>, Error<T>> {
 		let para_id = backed_candidate.descriptor().para_id;
 		let relay_parent = backed_candidate.descriptor().relay_parent;
 
@@ -1209,16 +1352,12 @@ impl<T: Config> CandidateCheckContext<T> {
 		};
 
 		{
-			let persisted_validation_data = match crate::util::make_persisted_validation_data::<T>(
-				para_id,
-				relay_parent_number,
-				relay_parent_storage_root,
-			)
-			.defensive_proof("the para is registered")
-			{
-				Some(l) => l,
-				None => return Ok(Err(FailedToCreatePVD)),
-			};
+			let persisted_validation_data =
+				crate::util::make_persisted_validation_data_with_parent::<T>(
+					relay_parent_number,
+					relay_parent_storage_root,
+					parent_head_data,
+				);
 
 			let expected = persisted_validation_data.hash();
 
@@ -1256,6 +1395,7 @@ impl<T: Config> CandidateCheckContext<T> {
 			&backed_candidate.candidate.commitments.upward_messages,
 			BlockNumberFor::<T>::from(backed_candidate.candidate.commitments.hrmp_watermark),
 			&backed_candidate.candidate.commitments.horizontal_messages,
+			pending_outputs,
 		) {
 			log::debug!(
 				target: LOG_TARGET,
@@ -1265,7 +1405,7 @@ impl<T: Config> CandidateCheckContext<T> {
 			);
 			Err(err.strip_into_dispatch_err::<T>())?;
 		};
-		Ok(Ok(relay_parent_number))
+		Ok(relay_parent_number)
 	}
 
 	/// Check the given outputs after candidate validation on whether it passes the acceptance
@@ -1284,6 +1424,9 @@ impl<T: Config> CandidateCheckContext<T> {
 	///
 	/// Minimums are checked against the current state but modulated by
 	/// considering the information available at the relay-parent of the parachain block.
+	///
+	/// Both are checked on top of the `pending_outputs` of the candidates of the para which are
+	/// not enacted yet.
 	fn check_validation_outputs(
 		&self,
 		para_id: ParaId,
@@ -1294,6 +1437,7 @@ impl<T: Config> CandidateCheckContext<T> {
 		upward_messages: &[primitives::UpwardMessage],
 		hrmp_watermark: BlockNumberFor<T>,
 		horizontal_messages: &[primitives::OutboundHrmpMessage<ParaId>],
+		pending_outputs: &PendingOutputs<BlockNumberFor<T>>,
 	) -> Result<(), AcceptanceCheckErr<BlockNumberFor<T>>> {
 		ensure!(
 			head_data.0.len() <= self.config.max_head_data_size as _,
@@ -1303,7 +1447,8 @@ impl<T: Config> CandidateCheckContext<T> {
 		// if any, the code upgrade attempt is allowed.
 		if let Some(new_validation_code) = new_validation_code {
 			ensure!(
-				<paras::Pallet<T>>::can_upgrade_validation_code(para_id),
+				!pending_outputs.code_upgrade &&
+					<paras::Pallet<T>>::can_upgrade_validation_code(para_id),
 				AcceptanceCheckErr::PrematureCodeUpgrade,
 			);
 			ensure!(
@@ -1317,10 +1462,26 @@ impl<T: Config> CandidateCheckContext<T> {
 			para_id,
 			relay_parent_number,
 			processed_downward_messages,
+			pending_outputs.processed_downward_messages,
+		)?;
+		Pallet::<T>::check_upward_messages(
+			&self.config,
+			para_id,
+			upward_messages,
+			pending_outputs.upward_messages,
+		)?;
+		<hrmp::Pallet<T>>::check_hrmp_watermark(
+			para_id,
+			relay_parent_number,
+			hrmp_watermark,
+			pending_outputs.hrmp_watermark,
+		)?;
+		<hrmp::Pallet<T>>::check_outbound_hrmp(
+			&self.config,
+			para_id,
+			horizontal_messages,
+			&pending_outputs.horizontal_messages,
 		)?;
-		Pallet::<T>::check_upward_messages(&self.config, para_id, upward_messages)?;
-		<hrmp::Pallet<T>>::check_hrmp_watermark(para_id, relay_parent_number, hrmp_watermark)?;
-		<hrmp::Pallet<T>>::check_outbound_hrmp(&self.config, para_id, horizontal_messages)?;
 
 		Ok(())
 	}
@@ -87,6 +87,26 @@ fn default_allowed_relay_parent_tracker() -> AllowedRelayParentsTracker<Hash, Bl
 	allowed
 }
 
+/// Group the candidates by para, keeping their order.
+fn candidates_by_para(
+	candidates: Vec<BackedCandidate<Hash>>,
+) -> BTreeMap<ParaId, Vec<BackedCandidate<Hash>>> {
+	let mut by_para: BTreeMap<_, Vec<_>> = BTreeMap::new();
+	for candidate in candidates {
+		by_para.entry(candidate.descriptor().para_id).or_default().push(candidate);
+	}
+	by_para
+}
+
+/// Collect the cores each para is scheduled on.
+fn scheduled_cores(assignments: &[(ParaId, CoreIndex)]) -> BTreeMap<ParaId, BTreeSet<CoreIndex>> {
+	let mut scheduled: BTreeMap<_, BTreeSet<_>> = BTreeMap::new();
+	for (para_id, core) in assignments {
+		scheduled.entry(*para_id).or_default().insert(*core);
+	}
+	scheduled
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub(crate) enum BackingKind {
 	#[allow(unused)]
@@ -321,6 +341,16 @@ pub(crate) fn make_vdata_hash(para_id: ParaId) -> Option<Hash> {
 	make_vdata_hash_with_block_number(para_id, relay_parent_number)
 }
 
+fn make_vdata_hash_with_parent(parent_head: HeadData) -> Hash {
+	let relay_parent_number = <frame_system::Pallet<Test>>::block_number() - 1;
+	crate::util::make_persisted_validation_data_with_parent::<Test>(
+		relay_parent_number,
+		Default::default(),
+		parent_head,
+	)
+	.hash()
+}
+
 fn make_vdata_hash_with_block_number(
 	para_id: ParaId,
 	relay_parent_number: BlockNumber,
@@ -385,50 +415,43 @@ fn collect_pending_cleans_up_pending() {
 		let default_candidate = TestCandidateBuilder::default().build();
 		<PendingAvailability<Test>>::insert(
 			chain_a,
-			CandidatePendingAvailability {
+			VecDeque::from([CandidatePendingAvailability {
 				core: CoreIndex::from(0),
 				hash: default_candidate.hash(),
 				descriptor: default_candidate.descriptor.clone(),
+				commitments: default_candidate.commitments.clone(),
 				availability_votes: default_availability_votes(),
 				relay_parent_number: 0,
 				backed_in_number: 0,
 				backers: default_backing_bitfield(),
 				backing_group: GroupIndex::from(0),
-			},
-		);
-		PendingAvailabilityCommitments::<Test>::insert(
-			chain_a,
-			default_candidate.commitments.clone(),
+			}]),
 		);
 
 		<PendingAvailability<Test>>::insert(
 			&chain_b,
-			CandidatePendingAvailability {
+			VecDeque::from([CandidatePendingAvailability {
 				core: CoreIndex::from(1),
 				hash: default_candidate.hash(),
 				descriptor: default_candidate.descriptor,
+				commitments: default_candidate.commitments,
 				availability_votes: default_availability_votes(),
 				relay_parent_number: 0,
 				backed_in_number: 5,
 				backers: default_backing_bitfield(),
 				backing_group: GroupIndex::from(1),
-			},
+			}]),
 		);
-		PendingAvailabilityCommitments::<Test>::insert(chain_b, default_candidate.commitments);
 
 		run_to_block(5, |_| None);
 
 		assert!(<PendingAvailability<Test>>::get(&chain_a).is_some());
 		assert!(<PendingAvailability<Test>>::get(&chain_b).is_some());
-		assert!(<PendingAvailabilityCommitments<Test>>::get(&chain_a).is_some());
-		assert!(<PendingAvailabilityCommitments<Test>>::get(&chain_b).is_some());
 
 		ParaInclusion::collect_pending(Scheduler::availability_timeout_predicate());
 
 		assert!(<PendingAvailability<Test>>::get(&chain_a).is_none());
 		assert!(<PendingAvailability<Test>>::get(&chain_b).is_some());
-		assert!(<PendingAvailabilityCommitments<Test>>::get(&chain_a).is_none());
-		assert!(<PendingAvailabilityCommitments<Test>>::get(&chain_b).is_some());
 	});
 }
 
@@ -578,18 +601,18 @@ fn bitfield_checks() {
 			let default_candidate = TestCandidateBuilder::default().build();
 			<PendingAvailability<Test>>::insert(
 				chain_a,
-				CandidatePendingAvailability {
+				VecDeque::from([CandidatePendingAvailability {
 					core: CoreIndex::from(0),
 					hash: default_candidate.hash(),
 					descriptor: default_candidate.descriptor,
+					commitments: default_candidate.commitments,
 					availability_votes: default_availability_votes(),
 					relay_parent_number: 0,
 					backed_in_number: 0,
 					backers: default_backing_bitfield(),
 					backing_group: GroupIndex::from(0),
-				},
+				}]),
 			);
-			PendingAvailabilityCommitments::<Test>::insert(chain_a, default_candidate.commitments);
 
 			*bare_bitfield.0.get_mut(0).unwrap() = true;
 			let signed = sign_bitfield(
@@ -611,49 +634,6 @@ fn bitfield_checks() {
 			assert!(x.is_empty(), "No core should be freed.");
 
 			<PendingAvailability<Test>>::remove(chain_a);
-			PendingAvailabilityCommitments::<Test>::remove(chain_a);
-		}
-
-		// bitfield signed with pending bit signed, but no commitments.
-		{
-			let mut bare_bitfield = default_bitfield();
-
-			assert_eq!(core_lookup(CoreIndex::from(0)), Some(chain_a));
-
-			let default_candidate = TestCandidateBuilder::default().build();
-			<PendingAvailability<Test>>::insert(
-				chain_a,
-				CandidatePendingAvailability {
-					core: CoreIndex::from(0),
-					hash: default_candidate.hash(),
-					descriptor: default_candidate.descriptor,
-					availability_votes: default_availability_votes(),
-					relay_parent_number: 0,
-					backed_in_number: 0,
-					backers: default_backing_bitfield(),
-					backing_group: GroupIndex::from(0),
-				},
-			);
-
-			*bare_bitfield.0.get_mut(0).unwrap() = true;
-			let signed = sign_bitfield(
-				&keystore,
-				&validators[0],
-				ValidatorIndex(0),
-				bare_bitfield,
-				&signing_context,
-			);
-
-			let checked_bitfields = simple_sanitize_bitfields(
-				vec![signed.into()],
-				DisputedBitfield::zeros(expected_bits()),
-				expected_bits(),
-			);
-			assert_eq!(checked_bitfields.len(), 1, "No bitfields should have been filtered!");
-
-			let x = process_bitfields(expected_bits(), checked_bitfields, core_lookup);
-			// no core is freed
-			assert!(x.is_empty(), "No core should be freed.");
 		}
 	});
 }
@@ -717,18 +697,18 @@ fn supermajority_bitfields_trigger_availability() {
 
 		<PendingAvailability<Test>>::insert(
 			chain_a,
-			CandidatePendingAvailability {
+			VecDeque::from([CandidatePendingAvailability {
 				core: CoreIndex::from(0),
 				hash: candidate_a.hash(),
 				descriptor: candidate_a.clone().descriptor,
+				commitments: candidate_a.clone().commitments,
 				availability_votes: default_availability_votes(),
 				relay_parent_number: 0,
 				backed_in_number: 0,
 				backers: backing_bitfield(&[3, 4]),
 				backing_group: GroupIndex::from(0),
-			},
+			}]),
 		);
-		PendingAvailabilityCommitments::<Test>::insert(chain_a, candidate_a.clone().commitments);
 
 		let candidate_b = TestCandidateBuilder {
 			para_id: chain_b,
@@ -739,18 +719,18 @@ fn supermajority_bitfields_trigger_availability() {
 
 		<PendingAvailability<Test>>::insert(
 			chain_b,
-			CandidatePendingAvailability {
+			VecDeque::from([CandidatePendingAvailability {
 				core: CoreIndex::from(1),
 				hash: candidate_b.hash(),
 				descriptor: candidate_b.descriptor,
+				commitments: candidate_b.commitments,
 				availability_votes: default_availability_votes(),
 				relay_parent_number: 0,
 				backed_in_number: 0,
 				backers: backing_bitfield(&[0, 2]),
 				backing_group: GroupIndex::from(1),
-			},
+			}]),
 		);
-		PendingAvailabilityCommitments::<Test>::insert(chain_b, candidate_b.commitments);
 
 		// this bitfield signals that a and b are available.
 		let a_and_b_available = {
@@ -815,9 +795,7 @@ fn supermajority_bitfields_trigger_availability() {
 		// chain A had 4 signing off, which is >= threshold.
 		// chain B has 3 signing off, which is < threshold.
 		assert!(<PendingAvailability<Test>>::get(&chain_a).is_none());
-		assert!(<PendingAvailabilityCommitments<Test>>::get(&chain_a).is_none());
-		assert!(<PendingAvailabilityCommitments<Test>>::get(&chain_b).is_some());
-		assert_eq!(<PendingAvailability<Test>>::get(&chain_b).unwrap().availability_votes, {
+		assert_eq!(<PendingAvailability<Test>>::get(&chain_b).unwrap()[0].availability_votes, {
 			// check that votes from first 3 were tracked.
 
 			let mut votes = default_availability_votes();
@@ -944,15 +922,15 @@ fn candidate_checks() {
 			assert_noop!(
 				ParaInclusion::process_candidates(
 					&allowed_relay_parents,
-					vec![backed],
-					&[chain_b_assignment].into_iter().collect(),
+					candidates_by_para(vec![backed]),
+					&scheduled_cores(&[chain_b_assignment]),
 					&group_validators,
 				),
 				Error::<Test>::UnscheduledCandidate
 			);
 		}
 
-		// candidates out of order.
+		// candidates not building on top of each other.
 		{
 			let mut candidate_a = TestCandidateBuilder {
 				para_id: chain_a,
@@ -960,22 +938,24 @@ fn candidate_checks() {
 				pov_hash: Hash::repeat_byte(1),
 				persisted_validation_data_hash: make_vdata_hash(chain_a).unwrap(),
 				hrmp_watermark: RELAY_PARENT_NUM,
+				head_data: vec![1].into(),
 				..Default::default()
 			}
 			.build();
 			let mut candidate_b = TestCandidateBuilder {
-				para_id: chain_b,
+				para_id: chain_a,
 				relay_parent: System::parent_hash(),
 				pov_hash: Hash::repeat_byte(2),
-				persisted_validation_data_hash: make_vdata_hash(chain_b).unwrap(),
+				persisted_validation_data_hash: make_vdata_hash(chain_a).unwrap(),
 				hrmp_watermark: RELAY_PARENT_NUM,
+				head_data: vec![2].into(),
 				..Default::default()
 			}
 			.build();
 
 			collator_sign_candidate(Sr25519Keyring::One, &mut candidate_a);
 
-			collator_sign_candidate(Sr25519Keyring::Two, &mut candidate_b);
+			collator_sign_candidate(Sr25519Keyring::One, &mut candidate_b);
 
 			let backed_a = back_candidate(
 				candidate_a,
@@ -995,15 +975,71 @@ fn candidate_checks() {
 				BackingKind::Threshold,
 			);
 
-			// out-of-order manifests as unscheduled.
+			// `candidate_b` builds on the para head instead of on `candidate_a`.
+			assert_noop!(
+				ParaInclusion::process_candidates(
+					&allowed_relay_parents,
+					candidates_by_para(vec![backed_a, backed_b]),
+					&scheduled_cores(&[chain_a_assignment, (chain_a, CoreIndex::from(1))]),
+					&group_validators,
+				),
+				Error::<Test>::ValidationDataHashMismatch
+			);
+		}
+
+		// more candidates than scheduled cores.
+		{
+			let mut candidate_a = TestCandidateBuilder {
+				para_id: chain_a,
+				relay_parent: System::parent_hash(),
+				pov_hash: Hash::repeat_byte(1),
+				persisted_validation_data_hash: make_vdata_hash(chain_a).unwrap(),
+				hrmp_watermark: RELAY_PARENT_NUM,
+				head_data: vec![1].into(),
+				..Default::default()
+			}
+			.build();
+			let mut candidate_b = TestCandidateBuilder {
+				para_id: chain_a,
+				relay_parent: System::parent_hash(),
+				pov_hash: Hash::repeat_byte(2),
+				persisted_validation_data_hash: make_vdata_hash_with_parent(vec![1].into()),
+				hrmp_watermark: RELAY_PARENT_NUM,
+				head_data: vec![2].into(),
+				..Default::default()
+			}
+			.build();
+
+			collator_sign_candidate(Sr25519Keyring::One, &mut candidate_a);
+
+			collator_sign_candidate(Sr25519Keyring::One, &mut candidate_b);
+
+			let backed_a = back_candidate(
+				candidate_a,
+				&validators,
+				group_validators(GroupIndex::from(0)).unwrap().as_ref(),
+				&keystore,
+				&signing_context,
+				BackingKind::Threshold,
+			);
+
+			let backed_b = back_candidate(
+				candidate_b,
+				&validators,
+				group_validators(GroupIndex::from(0)).unwrap().as_ref(),
+				&keystore,
+				&signing_context,
+				BackingKind::Threshold,
+			);
+
 			assert_noop!(
 				ParaInclusion::process_candidates(
 					&allowed_relay_parents,
-					vec![backed_b, backed_a],
-					&[chain_a_assignment, chain_b_assignment].into_iter().collect(),
+					candidates_by_para(vec![backed_a, backed_b]),
+					&scheduled_cores(&[chain_a_assignment]),
 					&group_validators,
 				),
-				Error::<Test>::ScheduledOutOfOrder
+				Error::<Test>::UnscheduledCandidate
 			);
 		}
 
@@ -1032,8 +1068,8 @@ fn candidate_checks() {
 			assert_noop!(
 				ParaInclusion::process_candidates(
 					&allowed_relay_parents,
-					vec![backed],
-					&[chain_a_assignment].into_iter().collect(),
+					candidates_by_para(vec![backed]),
+					&scheduled_cores(&[chain_a_assignment]),
 					&group_validators,
 				),
 				Error::<Test>::InsufficientBacking
@@ -1089,8 +1125,8 @@ fn candidate_checks() {
 			assert_noop!(
 				ParaInclusion::process_candidates(
 					&allowed_relay_parents,
-					vec![backed_b, backed_a],
-					&[chain_a_assignment, chain_b_assignment].into_iter().collect(),
+					candidates_by_para(vec![backed_b, backed_a]),
+					&scheduled_cores(&[chain_a_assignment, chain_b_assignment]),
 					&group_validators,
 				),
 				Error::<Test>::DisallowedRelayParent
@@ -1127,15 +1163,15 @@ fn candidate_checks() {
 			assert_noop!(
 				ParaInclusion::process_candidates(
 					&allowed_relay_parents,
-					vec![backed],
-					&[thread_a_assignment].into_iter().collect(),
+					candidates_by_para(vec![backed]),
+					&scheduled_cores(&[thread_a_assignment]),
 					&group_validators,
 				),
 				Error::<Test>::NotCollatorSigned
 			);
 		}
 
-		// para occupied - reject.
+		// candidate not building on top of the candidate pending availability - reject.
 		{
 			let mut candidate = TestCandidateBuilder {
 				para_id: chain_a,
@@ -1158,73 +1194,35 @@ fn candidate_checks() {
 				BackingKind::Threshold,
 			);
 
-			let candidate = TestCandidateBuilder::default().build();
+			let candidate =
+				TestCandidateBuilder { head_data: vec![1, 2, 3].into(), ..Default::default() }
+					.build();
 			<PendingAvailability<Test>>::insert(
 				&chain_a,
-				CandidatePendingAvailability {
+				VecDeque::from([CandidatePendingAvailability {
 					core: CoreIndex::from(0),
 					hash: candidate.hash(),
 					descriptor: candidate.descriptor,
+					commitments: candidate.commitments,
 					availability_votes: default_availability_votes(),
 					relay_parent_number: 3,
 					backed_in_number: 4,
 					backers: default_backing_bitfield(),
 					backing_group: GroupIndex::from(0),
-				},
+				}]),
 			);
-			<PendingAvailabilityCommitments<Test>>::insert(&chain_a, candidate.commitments);
 
 			assert_noop!(
 				ParaInclusion::process_candidates(
 					&allowed_relay_parents,
-					vec![backed],
-					&[chain_a_assignment].into_iter().collect(),
+					candidates_by_para(vec![backed]),
+					&scheduled_cores(&[chain_a_assignment]),
 					&group_validators,
 				),
-				Error::<Test>::CandidateScheduledBeforeParaFree
+				Error::<Test>::ValidationDataHashMismatch
 			);
 
 			<PendingAvailability<Test>>::remove(&chain_a);
-			<PendingAvailabilityCommitments<Test>>::remove(&chain_a);
-		}
-
-		// messed up commitments storage - do not panic - reject.
-		{
-			let mut candidate = TestCandidateBuilder {
-				para_id: chain_a,
-				relay_parent: System::parent_hash(),
-				pov_hash: Hash::repeat_byte(1),
-				persisted_validation_data_hash: make_vdata_hash(chain_a).unwrap(),
-				hrmp_watermark: RELAY_PARENT_NUM,
-				..Default::default()
-			}
-			.build();
-
-			collator_sign_candidate(Sr25519Keyring::One, &mut candidate);
-
-			// this is not supposed to happen
-			<PendingAvailabilityCommitments<Test>>::insert(&chain_a, candidate.commitments.clone());
-
-			let backed = back_candidate(
-				candidate,
-				&validators,
-				group_validators(GroupIndex::from(0)).unwrap().as_ref(),
-				&keystore,
-				&signing_context,
-				BackingKind::Threshold,
-			);
-
-			assert_noop!(
-				ParaInclusion::process_candidates(
-					&allowed_relay_parents,
-					vec![backed],
-					&[chain_a_assignment].into_iter().collect(),
-					&group_validators,
-				),
-				Error::<Test>::CandidateScheduledBeforeParaFree
-			);
-
-			<PendingAvailabilityCommitments<Test>>::remove(&chain_a);
 		}
 
 		// interfering code upgrade - reject
@@ -1267,8 +1265,8 @@ fn candidate_checks() {
 			assert_noop!(
 				ParaInclusion::process_candidates(
 					&allowed_relay_parents,
-					vec![backed],
-					&[chain_a_assignment].into_iter().collect(),
+					candidates_by_para(vec![backed]),
+					&scheduled_cores(&[chain_a_assignment]),
 					&group_validators,
 				),
 				Error::<Test>::PrematureCodeUpgrade
@@ -1301,8 +1299,8 @@ fn candidate_checks() {
 			assert_eq!(
 				ParaInclusion::process_candidates(
 					&allowed_relay_parents,
-					vec![backed],
-					&[chain_a_assignment].into_iter().collect(),
+					candidates_by_para(vec![backed]),
+					&scheduled_cores(&[chain_a_assignment]),
 					&group_validators,
 				),
 				Err(Error::<Test>::ValidationDataHashMismatch.into()),
@@ -1336,8 +1334,8 @@ fn candidate_checks() {
 			assert_noop!(
 				ParaInclusion::process_candidates(
 					&allowed_relay_parents,
-					vec![backed],
-					&[chain_a_assignment].into_iter().collect(),
+					candidates_by_para(vec![backed]),
+					&scheduled_cores(&[chain_a_assignment]),
 					&group_validators,
 				),
 				Error::<Test>::InvalidValidationCodeHash
@@ -1371,8 +1369,8 @@ fn candidate_checks() {
 			assert_noop!(
 				ParaInclusion::process_candidates(
 					&allowed_relay_parents,
-					vec![backed],
-					&[chain_a_assignment].into_iter().collect(),
+					candidates_by_para(vec![backed]),
+					&scheduled_cores(&[chain_a_assignment]),
 					&group_validators,
 				),
 				Error::<Test>::ParaHeadMismatch
@@ -1531,10 +1529,8 @@ fn backing_works() {
 			candidate_receipt_with_backing_validator_indices,
 		} = ParaInclusion::process_candidates(
 			&allowed_relay_parents,
-			backed_candidates.clone(),
-			&[chain_a_assignment, chain_b_assignment, thread_a_assignment]
-				.into_iter()
-				.collect(),
+			candidates_by_para(backed_candidates.clone()),
+			&scheduled_cores(&[chain_a_assignment, chain_b_assignment, thread_a_assignment]),
 			&group_validators,
 		)
 		.expect("candidates scheduled, in order, and backed");
@@ -1605,20 +1601,17 @@ fn backing_works() {
 		};
 		assert_eq!(
 			<PendingAvailability<Test>>::get(&chain_a),
-			Some(CandidatePendingAvailability {
+			Some(VecDeque::from([CandidatePendingAvailability {
 				core: CoreIndex::from(0),
 				hash: candidate_a.hash(),
 				descriptor: candidate_a.descriptor,
+				commitments: candidate_a.commitments,
 				availability_votes: default_availability_votes(),
 				relay_parent_number: System::block_number() - 1,
 				backed_in_number: System::block_number(),
 				backers,
 				backing_group: GroupIndex::from(0),
-			})
-		);
-		assert_eq!(
-			<PendingAvailabilityCommitments<Test>>::get(&chain_a),
-			Some(candidate_a.commitments),
+			}]))
 		);
 
 		let backers = {
@@ -1630,38 +1623,32 @@ fn backing_works() {
 		};
 		assert_eq!(
 			<PendingAvailability<Test>>::get(&chain_b),
-			Some(CandidatePendingAvailability {
+			Some(VecDeque::from([CandidatePendingAvailability {
 				core: CoreIndex::from(1),
 				hash: candidate_b.hash(),
 				descriptor: candidate_b.descriptor,
+				commitments: candidate_b.commitments,
 				availability_votes: default_availability_votes(),
 				relay_parent_number: System::block_number() - 1,
 				backed_in_number: System::block_number(),
 				backers,
 				backing_group: GroupIndex::from(1),
-			})
-		);
-		assert_eq!(
-			<PendingAvailabilityCommitments<Test>>::get(&chain_b),
-			Some(candidate_b.commitments),
+			}]))
 		);
 
 		assert_eq!(
 			<PendingAvailability<Test>>::get(&thread_a),
-			Some(CandidatePendingAvailability {
+			Some(VecDeque::from([CandidatePendingAvailability {
 				core: CoreIndex::from(2),
 				hash: candidate_c.hash(),
 				descriptor: candidate_c.descriptor,
+				commitments: candidate_c.commitments,
 				availability_votes: default_availability_votes(),
 				relay_parent_number: System::block_number() - 1,
 				backed_in_number: System::block_number(),
 				backers: backing_bitfield(&[4]),
 				backing_group: GroupIndex::from(2),
-			})
-		);
-		assert_eq!(
-			<PendingAvailabilityCommitments<Test>>::get(&thread_a),
-			Some(candidate_c.commitments),
+			}]))
 		);
 	});
 }
@@ -1745,8 +1732,8 @@ fn can_include_candidate_with_ok_code_upgrade() {
 		let ProcessedCandidates { core_indices: occupied_cores, .. } =
 			ParaInclusion::process_candidates(
 				&allowed_relay_parents,
-				vec![backed_a],
-				&[chain_a_assignment].into_iter().collect(),
+				candidates_by_para(vec![backed_a]),
+				&scheduled_cores(&[chain_a_assignment]),
 				&group_validators,
 			)
 			.expect("candidates scheduled, in order, and backed");
@@ -1762,20 +1749,17 @@ fn can_include_candidate_with_ok_code_upgrade() {
 		};
 		assert_eq!(
 			<PendingAvailability<Test>>::get(&chain_a),
-			Some(CandidatePendingAvailability {
+			Some(VecDeque::from([CandidatePendingAvailability {
 				core: CoreIndex::from(0),
 				hash: candidate_a.hash(),
 				descriptor: candidate_a.descriptor,
+				commitments: candidate_a.commitments,
 				availability_votes: default_availability_votes(),
 				relay_parent_number: System::block_number() - 1,
 				backed_in_number: System::block_number(),
 				backers,
 				backing_group: GroupIndex::from(0),
-			})
-		);
-		assert_eq!(
-			<PendingAvailabilityCommitments<Test>>::get(&chain_a),
-			Some(candidate_a.commitments),
+			}]))
 		);
 	});
 }
@@ -1956,10 +1940,8 @@ fn check_allowed_relay_parents() {
 
 		ParaInclusion::process_candidates(
 			&allowed_relay_parents,
-			backed_candidates.clone(),
-			&[chain_a_assignment, chain_b_assignment, thread_a_assignment]
-				.into_iter()
-				.collect(),
+			candidates_by_para(backed_candidates.clone()),
+			&scheduled_cores(&[chain_a_assignment, chain_b_assignment, thread_a_assignment]),
 			&group_validators,
 		)
 		.expect("candidates scheduled, in order, and backed");
@@ -2024,33 +2006,33 @@ fn session_change_wipes() {
 		let candidate = TestCandidateBuilder::default().build();
 		<PendingAvailability<Test>>::insert(
 			&chain_a,
-			CandidatePendingAvailability {
+			VecDeque::from([CandidatePendingAvailability {
 				core: CoreIndex::from(0),
 				hash: candidate.hash(),
 				descriptor: candidate.descriptor.clone(),
+				commitments: candidate.commitments.clone(),
 				availability_votes: default_availability_votes(),
 				relay_parent_number: 5,
 				backed_in_number: 6,
 				backers: default_backing_bitfield(),
 				backing_group: GroupIndex::from(0),
-			},
+			}]),
 		);
-		<PendingAvailabilityCommitments<Test>>::insert(&chain_a, candidate.commitments.clone());
 
 		<PendingAvailability<Test>>::insert(
 			&chain_b,
-			CandidatePendingAvailability {
+			VecDeque::from([CandidatePendingAvailability {
 				core: CoreIndex::from(1),
 				hash: candidate.hash(),
 				descriptor: candidate.descriptor,
+				commitments: candidate.commitments,
 				availability_votes: default_availability_votes(),
 				relay_parent_number: 6,
 				backed_in_number: 7,
 				backers: default_backing_bitfield(),
 				backing_group: GroupIndex::from(1),
-			},
+			}]),
 		);
-		<PendingAvailabilityCommitments<Test>>::insert(&chain_b, candidate.commitments);
 
 		run_to_block(11, |_| None);
 
@@ -2062,8 +2044,6 @@ fn session_change_wipes() {
 
 		assert!(<PendingAvailability<Test>>::get(&chain_a).is_some());
 		assert!(<PendingAvailability<Test>>::get(&chain_b).is_some());
-		assert!(<PendingAvailabilityCommitments<Test>>::get(&chain_a).is_some());
-		assert!(<PendingAvailabilityCommitments<Test>>::get(&chain_b).is_some());
 
 		run_to_block(12, |n| match n {
 			12 => Some(SessionChangeNotification {
@@ -2085,12 +2065,9 @@ fn session_change_wipes() {
 
 		assert!(<PendingAvailability<Test>>::get(&chain_a).is_none());
 		assert!(<PendingAvailability<Test>>::get(&chain_b).is_none());
-		assert!(<PendingAvailabilityCommitments<Test>>::get(&chain_a).is_none());
-		assert!(<PendingAvailabilityCommitments<Test>>::get(&chain_b).is_none());
 
 		assert!(<AvailabilityBitfields<Test>>::iter().collect::<Vec<_>>().is_empty());
 		assert!(<PendingAvailability<Test>>::iter().collect::<Vec<_>>().is_empty());
-		assert!(<PendingAvailabilityCommitments<Test>>::iter().collect::<Vec<_>>().is_empty());
 	});
 }
 
@@ -2194,8 +2171,8 @@ fn para_upgrade_delay_scheduled_from_inclusion() {
 		let ProcessedCandidates { core_indices: occupied_cores, .. } =
 			ParaInclusion::process_candidates(
 				&allowed_relay_parents,
-				vec![backed_a],
-				&[chain_a_assignment].into_iter().collect(),
+				candidates_by_para(vec![backed_a]),
+				&scheduled_cores(&[chain_a_assignment]),
 				&group_validators,
 			)
 			.expect("candidates scheduled, in order, and backed");
@@ -2233,7 +2210,6 @@ fn para_upgrade_delay_scheduled_from_inclusion() {
 		assert_eq!(vec![(CoreIndex(0), candidate_a.hash())], v);
 
 		assert!(<PendingAvailability<Test>>::get(&chain_a).is_none());
-		assert!(<PendingAvailabilityCommitments<Test>>::get(&chain_a).is_none());
 
 		let active_vote_state = paras::Pallet::<Test>::active_vote_state(&new_validation_code_hash)
 			.expect("prechecking must be initiated");
@@ -2246,3 +2222,392 @@ fn para_upgrade_delay_scheduled_from_inclusion() {
 		);
 	});
 }
+
+#[test]
+fn backing_and_availability_work_across_multiple_cores() {
+	let chain_a = ParaId::from(1_u32);
+	let chain_b = ParaId::from(2_u32);
+	let thread_a = ParaId::from(3_u32);
+
+	// The block number of the relay-parent for testing.
+	const RELAY_PARENT_NUM: BlockNumber = 4;
+
+	let paras = vec![
+		(chain_a, ParaKind::Parachain),
+		(chain_b, ParaKind::Parachain),
+		(thread_a, ParaKind::Parathread),
+	];
+	let validators = vec![
+		Sr25519Keyring::Alice,
+		Sr25519Keyring::Bob,
+		Sr25519Keyring::Charlie,
+		Sr25519Keyring::Dave,
+		Sr25519Keyring::Ferdie,
+	];
+	let keystore: KeystorePtr = Arc::new(LocalKeystore::in_memory());
+	for validator in validators.iter() {
+		Keystore::sr25519_generate_new(
+			&*keystore,
+			PARACHAIN_KEY_TYPE_ID,
+			Some(&validator.to_seed()),
+		)
+		.unwrap();
+	}
+	let validator_public = validator_pubkeys(&validators);
+
+	new_test_ext(genesis_config(paras)).execute_with(|| {
+		shared::Pallet::<Test>::set_active_validators_ascending(validator_public.clone());
+		shared::Pallet::<Test>::set_session_index(5);
+
+		run_to_block(5, |_| None);
+
+		let signing_context =
+			SigningContext { parent_hash: System::parent_hash(), session_index: 5 };
+
+		let group_validators = |group_index: GroupIndex| {
+			match group_index {
+				group_index if group_index == GroupIndex::from(0) => Some(vec![0, 1]),
+				group_index if group_index == GroupIndex::from(1) => Some(vec![2, 3]),
+				group_index if group_index == GroupIndex::from(2) => Some(vec![4]),
+				_ => panic!("Group index out of bounds for 2 parachains and 1 parathread core"),
+			}
+			.map(|m| m.into_iter().map(ValidatorIndex).collect::<Vec<_>>())
+		};
+
+		// When processing candidates, we compute the group index from scheduler.
+		let validator_groups = vec![
+			vec![ValidatorIndex(0), ValidatorIndex(1)],
+			vec![ValidatorIndex(2), ValidatorIndex(3)],
+			vec![ValidatorIndex(4)],
+		];
+		Scheduler::set_validator_groups(validator_groups);
+
+		let allowed_relay_parents = default_allowed_relay_parent_tracker();
+
+		// Chain A occupies the cores 0 and 1 with two candidates, the second building on the
+		// first.
+		let mut candidate_a_1 = TestCandidateBuilder {
+			para_id: chain_a,
+			relay_parent: System::parent_hash(),
+			pov_hash: Hash::repeat_byte(1),
+			persisted_validation_data_hash: make_vdata_hash(chain_a).unwrap(),
+			hrmp_watermark: RELAY_PARENT_NUM,
+			head_data: vec![1].into(),
+			..Default::default()
+		}
+		.build();
+		collator_sign_candidate(Sr25519Keyring::One, &mut candidate_a_1);
+
+		let mut candidate_a_2 = TestCandidateBuilder {
+			para_id: chain_a,
+			relay_parent: System::parent_hash(),
+			pov_hash: Hash::repeat_byte(2),
+			persisted_validation_data_hash: make_vdata_hash_with_parent(vec![1].into()),
+			hrmp_watermark: RELAY_PARENT_NUM,
+			head_data: vec![2].into(),
+			..Default::default()
+		}
+		.build();
+		collator_sign_candidate(Sr25519Keyring::One, &mut candidate_a_2);
+
+		let backed_a_1 = back_candidate(
+			candidate_a_1.clone(),
+			&validators,
+			group_validators(GroupIndex::from(0)).unwrap().as_ref(),
+			&keystore,
+			&signing_context,
+			BackingKind::Threshold,
+		);
+		let backed_a_2 = back_candidate(
+			candidate_a_2.clone(),
+			&validators,
+			group_validators(GroupIndex::from(1)).unwrap().as_ref(),
+			&keystore,
+			&signing_context,
+			BackingKind::Threshold,
+		);
+
+		let ProcessedCandidates { core_indices: occupied_cores, .. } =
+			ParaInclusion::process_candidates(
+				&allowed_relay_parents,
+				candidates_by_para(vec![backed_a_1, backed_a_2]),
+				&scheduled_cores(&[(chain_a, CoreIndex::from(0)), (chain_a, CoreIndex::from(1))]),
+				&group_validators,
+			)
+			.expect("candidates scheduled, chained, and backed");
+
+		assert_eq!(
+			occupied_cores,
+			vec![(CoreIndex::from(0), chain_a), (CoreIndex::from(1), chain_a)]
+		);
+		assert_eq!(
+			<PendingAvailability<Test>>::get(&chain_a)
+				.unwrap()
+				.iter()
+				.map(|c| (c.core, c.hash))
+				.collect::<Vec<_>>(),
+			vec![
+				(CoreIndex::from(0), candidate_a_1.hash()),
+				(CoreIndex::from(1), candidate_a_2.hash())
+			],
+		);
+		assert_eq!(ParaInclusion::candidates_pending_availability(chain_a).len(), 2);
+		assert_eq!(
+			ParaInclusion::candidate_pending_availability(chain_a),
+			Some(candidate_a_1.clone())
+		);
+
+		let core_lookup = |core: CoreIndex| match core.0 {
+			0 | 1 => Some(chain_a),
+			_ => None,
+		};
+		let sign_all = |available_core: usize| {
+			let mut bare_bitfield = default_bitfield();
+			*bare_bitfield.0.get_mut(available_core).unwrap() = true;
+
+			let signed_bitfields = validators
+				.iter()
+				.enumerate()
+				.map(|(i, key)| {
+					sign_bitfield(
+						&keystore,
+						key,
+						ValidatorIndex(i as _),
+						bare_bitfield.clone(),
+						&signing_context,
+					)
+					.into()
+				})
+				.collect::<Vec<_>>();
+
+			simple_sanitize_bitfields(
+				signed_bitfields,
+				DisputedBitfield::zeros(expected_bits()),
+				expected_bits(),
+			)
+		};
+
+		// The second candidate is available, but can't be enacted before the first one.
+		let freed = process_bitfields(expected_bits(), sign_all(1), core_lookup);
+		assert!(freed.is_empty());
+		assert_eq!(<PendingAvailability<Test>>::get(&chain_a).unwrap().len(), 2);
+		assert_eq!(Paras::para_head(&chain_a), Some(Vec::new().into()));
+
+		// Once the first one is available both are enacted, in order.
+		let freed = process_bitfields(expected_bits(), sign_all(0), core_lookup);
+		assert_eq!(
+			freed,
+			vec![
+				(CoreIndex::from(0), candidate_a_1.hash()),
+				(CoreIndex::from(1), candidate_a_2.hash()),
+			],
+		);
+		assert!(<PendingAvailability<Test>>::get(&chain_a).is_none());
+		assert_eq!(Paras::para_head(&chain_a), Some(vec![2].into()));
+	});
+}
+
+#[test]
+fn chained_candidates_are_checked_on_top_of_their_predecessors() {
+	let chain_a = ParaId::from(1_u32);
+
+	// The block number of the relay-parent for testing.
+	const RELAY_PARENT_NUM: BlockNumber = 4;
+
+	let paras = vec![(chain_a, ParaKind::Parachain)];
+	let validators = vec![
+		Sr25519Keyring::Alice,
+		Sr25519Keyring::Bob,
+		Sr25519Keyring::Charlie,
+		Sr25519Keyring::Dave,
+		Sr25519Keyring::Ferdie,
+	];
+	let keystore: KeystorePtr = Arc::new(LocalKeystore::in_memory());
+	for validator in validators.iter() {
+		Keystore::sr25519_generate_new(
+			&*keystore,
+			PARACHAIN_KEY_TYPE_ID,
+			Some(&validator.to_seed()),
+		)
+		.unwrap();
+	}
+	let validator_public = validator_pubkeys(&validators);
+
+	new_test_ext(genesis_config(paras)).execute_with(|| {
+		shared::Pallet::<Test>::set_active_validators_ascending(validator_public.clone());
+		shared::Pallet::<Test>::set_session_index(5);
+
+		run_to_block(5, |_| None);
+
+		let signing_context =
+			SigningContext { parent_hash: System::parent_hash(), session_index: 5 };
+
+		let group_validators = |group_index: GroupIndex| {
+			match group_index {
+				group_index if group_index == GroupIndex::from(0) => Some(vec![0, 1]),
+				group_index if group_index == GroupIndex::from(1) => Some(vec![2, 3]),
+				_ => panic!("Group index out of bounds for 2 cores"),
+			}
+			.map(|m| m.into_iter().map(ValidatorIndex).collect::<Vec<_>>())
+		};
+
+		// When processing candidates, we compute the group index from scheduler.
+		let validator_groups = vec![
+			vec![ValidatorIndex(0), ValidatorIndex(1)],
+			vec![ValidatorIndex(2), ValidatorIndex(3)],
+		];
+		Scheduler::set_validator_groups(validator_groups);
+
+		let allowed_relay_parents = default_allowed_relay_parent_tracker();
+
+		// Both candidates signal a code upgrade. The upgrade of the first one is not enacted yet
+		// when the second one is checked, but must still be taken into account.
+		let mut candidate_a_1 = TestCandidateBuilder {
+			para_id: chain_a,
+			relay_parent: System::parent_hash(),
+			pov_hash: Hash::repeat_byte(1),
+			persisted_validation_data_hash: make_vdata_hash(chain_a).unwrap(),
+			new_validation_code: Some(vec![1, 2, 3].into()),
+			hrmp_watermark: RELAY_PARENT_NUM,
+			head_data: vec![1].into(),
+			..Default::default()
+		}
+		.build();
+		collator_sign_candidate(Sr25519Keyring::One, &mut candidate_a_1);
+
+		let mut candidate_a_2 = TestCandidateBuilder {
+			para_id: chain_a,
+			relay_parent: System::parent_hash(),
+			pov_hash: Hash::repeat_byte(2),
+			persisted_validation_data_hash: make_vdata_hash_with_parent(vec![1].into()),
+			new_validation_code: Some(vec![4, 5, 6].into()),
+			hrmp_watermark: RELAY_PARENT_NUM,
+			head_data: vec![2].into(),
+			..Default::default()
+		}
+		.build();
+		collator_sign_candidate(Sr25519Keyring::One, &mut candidate_a_2);
+
+		let backed_a_1 = back_candidate(
+			candidate_a_1.clone(),
+			&validators,
+			group_validators(GroupIndex::from(0)).unwrap().as_ref(),
+			&keystore,
+			&signing_context,
+			BackingKind::Threshold,
+		);
+		let backed_a_2 = back_candidate(
+			candidate_a_2.clone(),
+			&validators,
+			group_validators(GroupIndex::from(1)).unwrap().as_ref(),
+			&keystore,
+			&signing_context,
+			BackingKind::Threshold,
+		);
+
+		let scheduled =
+			scheduled_cores(&[(chain_a, CoreIndex::from(0)), (chain_a, CoreIndex::from(1))]);
+
+		// Backed in the same block.
+		assert_noop!(
+			ParaInclusion::process_candidates(
+				&allowed_relay_parents,
+				candidates_by_para(vec![backed_a_1.clone(), backed_a_2.clone()]),
+				&scheduled,
+				&group_validators,
+			),
+			Error::<Test>::PrematureCodeUpgrade
+		);
+
+		// Backed on top of the first candidate pending availability.
+		ParaInclusion::process_candidates(
+			&allowed_relay_parents,
+			candidates_by_para(vec![backed_a_1]),
+			&scheduled,
+			&group_validators,
+		)
+		.expect("candidate scheduled and backed");
+		assert_noop!(
+			ParaInclusion::process_candidates(
+				&allowed_relay_parents,
+				candidates_by_para(vec![backed_a_2]),
+				&scheduled_cores(&[(chain_a, CoreIndex::from(1))]),
+				&group_validators,
+			),
+			Error::<Test>::PrematureCodeUpgrade
+		);
+	});
+}
+
+#[test]
+fn freeing_a_candidate_frees_the_candidates_building_on_it() {
+	let chain_a = ParaId::from(1_u32);
+	let chain_b = ParaId::from(2_u32);
+
+	let paras = vec![(chain_a, ParaKind::Parachain), (chain_b, ParaKind::Parachain)];
+	let mut config = genesis_config(paras);
+	config.configuration.config.group_rotation_frequency = 3;
+	new_test_ext(config).execute_with(|| {
+		let candidate_1 = TestCandidateBuilder {
+			para_id: chain_a,
+			head_data: vec![1].into(),
+			..Default::default()
+		}
+		.build();
+		let candidate_2 = TestCandidateBuilder {
+			para_id: chain_a,
+			head_data: vec![2].into(),
+			..Default::default()
+		}
+		.build();
+		let pending = |candidate: &CommittedCandidateReceipt, core: u32, backed_in_number| {
+			CandidatePendingAvailability {
+				core: CoreIndex::from(core),
+				hash: candidate.hash(),
+				descriptor: candidate.descriptor.clone(),
+				commitments: candidate.commitments.clone(),
+				availability_votes: default_availability_votes(),
+				relay_parent_number: 0,
+				backed_in_number,
+				backers: default_backing_bitfield(),
+				backing_group: GroupIndex::from(core),
+			}
+		};
+
+		run_to_block(5, |_| None);
+
+		// Only the first candidate timed out, but the second one builds on it.
+		<PendingAvailability<Test>>::insert(
+			chain_a,
+			VecDeque::from([pending(&candidate_1, 0, 0), pending(&candidate_2, 1, 5)]),
+		);
+		let freed = ParaInclusion::collect_pending(Scheduler::availability_timeout_predicate());
+		assert_eq!(freed, vec![CoreIndex::from(0), CoreIndex::from(1)]);
+		assert!(<PendingAvailability<Test>>::get(&chain_a).is_none());
+
+		// Disputing the first candidate frees both.
+		<PendingAvailability<Test>>::insert(
+			chain_a,
+			VecDeque::from([pending(&candidate_1, 0, 5), pending(&candidate_2, 1, 5)]),
+		);
+		let freed = ParaInclusion::collect_disputed(&[candidate_1.hash()].into_iter().collect());
+		assert_eq!(freed, vec![CoreIndex::from(0), CoreIndex::from(1)]);
+		assert!(<PendingAvailability<Test>>::get(&chain_a).is_none());
+
+		// Disputing the second candidate leaves the first one pending.
+		<PendingAvailability<Test>>::insert(
+			chain_a,
+			VecDeque::from([pending(&candidate_1, 0, 5), pending(&candidate_2, 1, 5)]),
+		);
+		let freed = ParaInclusion::collect_disputed(&[candidate_2.hash()].into_iter().collect());
+		assert_eq!(freed, vec![CoreIndex::from(1)]);
+		assert_eq!(
+			<PendingAvailability<Test>>::get(&chain_a)
+				.unwrap()
+				.iter()
+				.map(|c| c.hash)
+				.collect::<Vec<_>>(),
+			vec![candidate_1.hash()],
+		);
+	});
+}
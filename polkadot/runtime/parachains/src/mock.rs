@@ -17,7 +17,8 @@
 //! Mocks for all the traits.
 
 use crate::{
-	assigner, assigner_on_demand, assigner_parachains, configuration, disputes, dmp, hrmp,
+	assigner, assigner_coretime, assigner_on_demand, assigner_parachains, configuration, disputes,
+	dmp, hrmp,
 	inclusion::{self, AggregateMessageOrigin, UmpQueueId},
 	initializer, origin, paras,
 	paras::ParaKind,
@@ -30,6 +31,7 @@ use frame_support::{
 		Currency, ProcessMessage, ProcessMessageError, ValidatorSet, ValidatorSetWithIdentification,
 	},
 	weights::{Weight, WeightMeter},
+	PalletId,
 };
 use frame_support_test::TestRandomness;
 use frame_system::limits;
@@ -65,6 +67,8 @@ frame_support::construct_runtime!(
 		Assigner: assigner,
 		OnDemandAssigner: assigner_on_demand,
 		ParachainsAssigner: assigner_parachains,
+		CoretimeAssigner: assigner_coretime,
+		Broker: pallet_broker,
 		Initializer: initializer,
 		Dmp: dmp,
 		Hrmp: hrmp,
@@ -349,6 +353,32 @@ impl assigner::Config for Test {
 
 impl assigner_parachains::Config for Test {}
 
+parameter_types! {
+	pub const BrokerId: ParaId = ParaId::new(1005);
+	pub const BrokerPalletId: PalletId = PalletId(*b"py/broke");
+}
+
+impl assigner_coretime::Config for Test {
+	type RuntimeOrigin = RuntimeOrigin;
+	type BrokerId = BrokerId;
+	type WeightInfo = assigner_coretime::TestWeightInfo;
+}
+
+impl pallet_broker::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type OnRevenue = ();
+	type TimeslicePeriod = ConstU32<2>;
+	type MaxLeasedCores = ConstU32<5>;
+	type MaxReservedCores = ConstU32<5>;
+	type Coretime = CoretimeAssigner;
+	type ConvertBalance = sp_runtime::traits::Identity;
+	type WeightInfo = ();
+	type PalletId = BrokerPalletId;
+	type AdminOrigin = frame_system::EnsureRoot<u64>;
+	type PriceAdapter = pallet_broker::Linear;
+}
+
 parameter_types! {
 	pub const OnDemandTrafficDefaultValue: FixedU128 = FixedU128::from_u32(1);
 }
@@ -30,7 +30,8 @@ use crate::{
 	metrics::METRICS,
 	paras,
 	scheduler::{self, FreedReason},
-	shared, ParaId,
+	shared::{self, AllowedRelayParentsTracker},
+	ParaId,
 };
 use bitvec::prelude::BitVec;
 use frame_support::{
@@ -581,37 +582,31 @@ impl<T: Config> Pallet<T> {
 		let freed = collect_all_freed_cores::<T, _>(freed_concluded.iter().cloned());
 
 		<scheduler::Pallet<T>>::update_claimqueue(freed, now);
-		let scheduled = <scheduler::Pallet<T>>::scheduled_paras()
-			.map(|(core_idx, para_id)| (para_id, core_idx))
-			.collect();
+		let mut scheduled: BTreeMap<ParaId, BTreeSet<CoreIndex>> = BTreeMap::new();
+		for (core_idx, para_id) in <scheduler::Pallet<T>>::scheduled_paras() {
+			scheduled.entry(para_id).or_default().insert(core_idx);
+		}
 
 		METRICS.on_candidates_processed_total(backed_candidates.len() as u64);
 
-		let backed_candidates = sanitize_backed_candidates::<T, _>(
+		let mut backed_candidates = sanitize_backed_candidates::<T, _>(
 			backed_candidates,
-			|candidate_idx: usize,
+			|_candidate_idx: usize,
 			 backed_candidate: &BackedCandidate<<T as frame_system::Config>::Hash>|
 			 -> bool {
-				let para_id = backed_candidate.descriptor().para_id;
-				let prev_context = <paras::Pallet<T>>::para_most_recent_context(para_id);
-				let check_ctx = CandidateCheckContext::<T>::new(prev_context);
-
 				// never include a concluded-invalid candidate
-				current_concluded_invalid_disputes.contains(&backed_candidate.hash()) ||
-					// Instead of checking the candidates with code upgrades twice
-					// move the checking up here and skip it in the training wheels fallback.
-					// That way we avoid possible duplicate checks while assuring all
-					// backed candidates fine to pass on.
-					//
-					// NOTE: this is the only place where we check the relay-parent.
-					check_ctx
-						.verify_backed_candidate(&allowed_relay_parents, candidate_idx, backed_candidate)
-						.is_err()
+				current_concluded_invalid_disputes.contains(&backed_candidate.hash())
 			},
 			&scheduled,
 		);
+		// Instead of checking the candidates with code upgrades twice
+		// do the checking here and skip it in the training wheels fallback.
+		// That way we avoid possible duplicate checks while assuring all
+		// backed candidates fine to pass on.
+		filter_unchained_candidates::<T>(&mut backed_candidates, &allowed_relay_parents);
 
-		METRICS.on_candidates_sanitized(backed_candidates.len() as u64);
+		METRICS
+			.on_candidates_sanitized(backed_candidates.values().map(Vec::len).sum::<usize>() as u64);
 
 		// Process backed candidates according to scheduled cores.
 		let inclusion::ProcessedCandidates::<<HeaderFor<T> as HeaderT>::Hash> {
@@ -626,6 +621,8 @@ impl<T: Config> Pallet<T> {
 		// Note which of the scheduled cores were actually occupied by a backed candidate.
 		<scheduler::Pallet<T>>::occupied(occupied.into_iter().map(|e| (e.0, e.1)).collect());
 
+		let backed_candidates = backed_candidates.into_values().flatten().collect();
+
 		set_scrapable_on_chain_backings::<T>(
 			current_session,
 			candidate_receipt_with_backing_validator_indices,
@@ -900,7 +897,8 @@ pub(crate) fn sanitize_bitfields<T: crate::inclusion::Config>(
 	bitfields
 }
 
-/// Filter out any candidates that have a concluded invalid dispute.
+/// Filter out any candidates that have a concluded invalid dispute and group the remaining ones
+/// by para.
 ///
 /// `scheduled` follows the same naming scheme as provided in the
 /// guide: Currently `free` but might become `occupied`.
@@ -910,43 +908,94 @@ pub(crate) fn sanitize_bitfields<T: crate::inclusion::Config>(
 /// `candidate_has_concluded_invalid_dispute` must return `true` if the candidate
 /// is disputed, false otherwise. The passed `usize` is the candidate index.
 ///
-/// The returned `Vec` is sorted according to the occupied core index.
+/// The candidates of each para keep their relative order, which is the order they are expected to
+/// build on top of each other in. Candidates exceeding the number of cores scheduled for their para
+/// are dropped.
 fn sanitize_backed_candidates<
 	T: crate::inclusion::Config,
 	F: FnMut(usize, &BackedCandidate<T::Hash>) -> bool,
 >(
 	mut backed_candidates: Vec<BackedCandidate<T::Hash>>,
-	mut candidate_has_concluded_invalid_dispute_or_is_invalid: F,
-	scheduled: &BTreeMap<ParaId, CoreIndex>,
-) -> Vec<BackedCandidate<T::Hash>> {
+	mut candidate_has_concluded_invalid_dispute: F,
+	scheduled: &BTreeMap<ParaId, BTreeSet<CoreIndex>>,
+) -> BTreeMap<ParaId, Vec<BackedCandidate<T::Hash>>> {
 	// Remove any candidates that were concluded invalid.
 	// This does not assume sorting.
 	backed_candidates.indexed_retain(move |candidate_idx, backed_candidate| {
-		!candidate_has_concluded_invalid_dispute_or_is_invalid(candidate_idx, backed_candidate)
+		!candidate_has_concluded_invalid_dispute(candidate_idx, backed_candidate)
 	});
 
-	// Assure the backed candidate's `ParaId`'s core is free.
+	// Assure the backed candidate's `ParaId` has a free core.
 	// This holds under the assumption that `Scheduler::schedule` is called _before_.
-	// We don't check the relay-parent because this is done in the closure when
-	// constructing the inherent and during actual processing otherwise.
+	// We don't check the relay-parent because this is done when checking the
+	// candidates chain and during actual processing otherwise.
+	let mut candidates_per_para: BTreeMap<ParaId, Vec<_>> = BTreeMap::new();
+	for backed_candidate in backed_candidates {
+		let para_id = backed_candidate.descriptor().para_id;
+		let Some(cores) = scheduled.get(&para_id) else { continue };
+
+		let para_candidates = candidates_per_para.entry(para_id).or_default();
+		if para_candidates.len() < cores.len() {
+			para_candidates.push(backed_candidate);
+		}
+	}
 
-	backed_candidates.retain(|backed_candidate| {
-		let desc = backed_candidate.descriptor();
+	candidates_per_para
+}
 
-		scheduled.get(&desc.para_id).is_some()
-	});
+/// Filter out the candidates which fail verification, most notably those which don't build on top
+/// of the latest head of their para, along with all candidates building on top of them.
+///
+/// The first candidate of a para must build on top of the head of the latest candidate of the para
+/// pending availability, or on top of the para head if there is none. Every further candidate must
+/// build on top of its predecessor.
+///
+/// NOTE: this is the only place where we check the relay-parent.
+fn filter_unchained_candidates<T: inclusion::Config + paras::Config>(
+	candidates: &mut BTreeMap<ParaId, Vec<BackedCandidate<T::Hash>>>,
+	allowed_relay_parents: &AllowedRelayParentsTracker<T::Hash, BlockNumberFor<T>>,
+) {
+	let mut candidate_idx = 0;
+	candidates.retain(|para_id, para_candidates| {
+		let Some((mut latest_head, mut latest_context)) =
+			<inclusion::Pallet<T>>::para_latest_head_data_and_context(*para_id)
+		else {
+			return false
+		};
+		let mut pending_outputs = <inclusion::Pallet<T>>::pending_outputs(*para_id);
+
+		let mut chained = 0;
+		for backed_candidate in para_candidates.iter() {
+			let check_ctx = CandidateCheckContext::<T>::new(latest_context);
+			match check_ctx.verify_backed_candidate(
+				allowed_relay_parents,
+				candidate_idx,
+				backed_candidate,
+				latest_head,
+				&pending_outputs,
+			) {
+				Ok(relay_parent_number) => {
+					latest_head = backed_candidate.candidate.commitments.head_data.clone();
+					latest_context = Some(relay_parent_number);
+					pending_outputs.note(&backed_candidate.candidate.commitments);
+					chained += 1;
+					candidate_idx += 1;
+				},
+				Err(_) => {
+					log::debug!(
+						target: LOG_TARGET,
+						"Dropping candidate {:?} of para {:?} and all candidates building on it",
+						backed_candidate.hash(),
+						para_id,
+					);
+					break
+				},
+			}
+		}
 
-	// Sort the `Vec` last, once there is a guarantee that these
-	// `BackedCandidates` references the expected relay chain parent,
-	// but more importantly are scheduled for a free core.
-	// This both avoids extra work for obviously invalid candidates,
-	// but also allows this to be done in place.
-	backed_candidates.sort_by(|x, y| {
-		// Never panics, since we filtered all panic arguments out in the previous `fn retain`.
-		scheduled[&x.descriptor().para_id].cmp(&scheduled[&y.descriptor().para_id])
+		para_candidates.truncate(chained);
+		!para_candidates.is_empty()
 	});
-
-	backed_candidates
 }
 
 /// Derive entropy from babe provided per block randomness.
@@ -1211,7 +1211,7 @@ mod sanitizers {
 		// Backed candidates and scheduled parachains used for `sanitize_backed_candidates` testing
 		struct TestData {
 			backed_candidates: Vec<BackedCandidate>,
-			scheduled_paras: BTreeMap<primitives::Id, CoreIndex>,
+			scheduled_paras: BTreeMap<primitives::Id, BTreeSet<CoreIndex>>,
 		}
 
 		// Generate test data for the candidates test
@@ -1243,7 +1243,12 @@ mod sanitizers {
 
 			let scheduled = (0_usize..2)
 				.into_iter()
-				.map(|idx| (ParaId::from(1_u32 + idx as u32), CoreIndex::from(idx as u32)))
+				.map(|idx| {
+					(
+						ParaId::from(1_u32 + idx as u32),
+						[CoreIndex::from(idx as u32)].into_iter().collect::<BTreeSet<_>>(),
+					)
+				})
 				.collect::<BTreeMap<_, _>>();
 
 			let group_validators = |group_index: GroupIndex| {
@@ -1301,9 +1306,41 @@ mod sanitizers {
 						&scheduled
 					),
 					backed_candidates
+						.into_iter()
+						.map(|candidate| (candidate.descriptor().para_id, vec![candidate]))
+						.collect::<BTreeMap<_, _>>()
 				);
+			});
+		}
+
+		// candidates exceeding the number of cores scheduled for their para are filtered out,
+		// the remaining ones keep their order
+		#[test]
+		fn excess_candidates_are_filtered_out() {
+			new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+				let TestData { backed_candidates, scheduled_paras: mut scheduled } =
+					get_test_data();
+				let para_id = backed_candidates[0].descriptor().para_id;
+				scheduled.get_mut(&para_id).unwrap().insert(CoreIndex::from(2));
+
+				let para_candidates = (1..=3u8)
+					.map(|byte| {
+						let mut candidate = backed_candidates[0].clone();
+						candidate.candidate.descriptor.pov_hash = Hash::repeat_byte(byte);
+						candidate
+					})
+					.collect::<Vec<_>>();
+
+				let has_concluded_invalid =
+					|_idx: usize, _backed_candidate: &BackedCandidate| -> bool { false };
 
-				{}
+				let sanitized = sanitize_backed_candidates::<Test, _>(
+					para_candidates.clone(),
+					has_concluded_invalid,
+					&scheduled,
+				);
+				assert_eq!(sanitized.len(), 1);
+				assert_eq!(sanitized[&para_id], para_candidates[..2].to_vec());
 			});
 		}
 
@@ -90,9 +90,11 @@ pub fn availability_cores<T: initializer::Config>() -> Vec<CoreState<T::Hash, Bl
 		.enumerate()
 		.map(|(i, core)| match core {
 			CoreOccupied::Paras(entry) => {
-				let pending_availability =
-					<inclusion::Pallet<T>>::pending_availability(entry.para_id())
-						.expect("Occupied core always has pending availability; qed");
+				let pending_availability = <inclusion::Pallet<T>>::pending_availability_with_core(
+					entry.para_id(),
+					CoreIndex(i as u32),
+				)
+				.expect("Occupied core always has pending availability; qed");
 
 				let backed_in_number = *pending_availability.backed_in_number();
 				CoreState::Occupied(OccupiedCore {
@@ -457,29 +459,19 @@ pub fn backing_state<T: initializer::Config>(
 		future_validation_code,
 	};
 
-	let pending_availability = {
-		// Note: the API deals with a `Vec` as it is future-proof for cases
-		// where there may be multiple candidates pending availability at a time.
-		// But at the moment only one candidate can be pending availability per
-		// parachain.
-		crate::inclusion::PendingAvailability::<T>::get(&para_id)
-			.and_then(|pending| {
-				let commitments =
-					crate::inclusion::PendingAvailabilityCommitments::<T>::get(&para_id);
-				commitments.map(move |c| (pending, c))
-			})
-			.map(|(pending, commitments)| {
-				CandidatePendingAvailability {
-					candidate_hash: pending.candidate_hash(),
-					descriptor: pending.candidate_descriptor().clone(),
-					commitments,
-					relay_parent_number: pending.relay_parent_number(),
-					max_pov_size: constraints.max_pov_size, // assume always same in session.
-				}
-			})
-			.into_iter()
-			.collect()
-	};
+	let pending_availability = crate::inclusion::PendingAvailability::<T>::get(&para_id)
+		.unwrap_or_default()
+		.into_iter()
+		.map(|pending| {
+			CandidatePendingAvailability {
+				candidate_hash: pending.candidate_hash(),
+				descriptor: pending.candidate_descriptor().clone(),
+				commitments: pending.candidate_commitments().clone(),
+				relay_parent_number: pending.relay_parent_number(),
+				max_pov_size: constraints.max_pov_size, // assume always same in session.
+			}
+		})
+		.collect();
 
 	Some(BackingState { constraints, pending_availability })
 }
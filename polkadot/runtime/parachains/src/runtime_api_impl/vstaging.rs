@@ -16,12 +16,15 @@
 
 //! Put implementations of functions from staging APIs here.
 
-use crate::{configuration, initializer, shared};
+use crate::{configuration, inclusion, initializer, scheduler, shared};
 use primitives::{
 	vstaging::{ApprovalVotingParams, NodeFeatures},
-	ValidatorIndex,
+	CommittedCandidateReceipt, CoreIndex, Id as ParaId, ValidatorIndex,
+};
+use sp_std::{
+	collections::{btree_map::BTreeMap, vec_deque::VecDeque},
+	prelude::Vec,
 };
-use sp_std::{collections::btree_map::BTreeMap, prelude::Vec};
 
 /// Implementation for `DisabledValidators`
 // CAVEAT: this should only be called on the node side
@@ -56,3 +59,24 @@ pub fn approval_voting_params<T: initializer::Config>() -> ApprovalVotingParams
 	let config = <configuration::Pallet<T>>::config();
 	config.approval_voting_params
 }
+
+/// Returns all the candidates pending availability for the given para, in the order they build on
+/// top of each other.
+pub fn candidates_pending_availability<T: initializer::Config>(
+	para_id: ParaId,
+) -> Vec<CommittedCandidateReceipt<T::Hash>> {
+	<inclusion::Pallet<T>>::candidates_pending_availability(para_id)
+}
+
+/// Returns the claim queue, i.e. the paras that are going to be scheduled on each core next.
+///
+/// Placeholders for claims that are currently being scheduled are skipped, so the first entry of
+/// each queue is the para that can be backed on the core in the next block.
+pub fn claim_queue<T: scheduler::Config>() -> BTreeMap<CoreIndex, VecDeque<ParaId>> {
+	<scheduler::Pallet<T>>::claimqueue()
+		.into_iter()
+		.map(|(core_idx, entries)| {
+			(core_idx, entries.into_iter().filter_map(|e| e.map(|e| e.para_id())).collect())
+		})
+		.collect()
+}
@@ -615,7 +615,7 @@ impl<T: Config> Pallet<T> {
 	}
 
 	/// Frees cores and fills the free claimqueue spots by popping from the `AssignmentProvider`.
-	fn free_cores_and_fill_claimqueue(
+	pub(crate) fn free_cores_and_fill_claimqueue(
 		just_freed_cores: impl IntoIterator<Item = (CoreIndex, FreedReason)>,
 		now: BlockNumberFor<T>,
 	) {
@@ -89,7 +89,7 @@ fn queue_upward_msg(para: ParaId, msg: UpwardMessage) {
 
 fn try_queue_upward_msg(para: ParaId, msg: UpwardMessage) -> Result<(), UmpAcceptanceCheckErr> {
 	let msgs = vec![msg];
-	ParaInclusion::check_upward_messages(&Configuration::config(), para, &msgs)?;
+	ParaInclusion::check_upward_messages(&Configuration::config(), para, &msgs, (0, 0))?;
 	ParaInclusion::receive_upward_messages(para, msgs.as_slice());
 	Ok(())
 }
@@ -107,8 +107,24 @@ mod check_upward_messages {
 
 	/// Check that these messages *could* be queued.
 	fn check(para: ParaId, msgs: Vec<UpwardMessage>, err: Option<UmpAcceptanceCheckErr>) {
+		check_with_pending(para, msgs, (0, 0), err)
+	}
+
+	/// Check that these messages *could* be queued after the `pending` ones.
+	fn check_with_pending(
+		para: ParaId,
+		msgs: Vec<UpwardMessage>,
+		pending: (u32, u32),
+		err: Option<UmpAcceptanceCheckErr>,
+	) {
 		assert_eq!(
-			ParaInclusion::check_upward_messages(&Configuration::config(), para, &msgs[..]).err(),
+			ParaInclusion::check_upward_messages(
+				&Configuration::config(),
+				para,
+				&msgs[..],
+				pending
+			)
+			.err(),
 			err
 		);
 	}
@@ -224,6 +240,36 @@ mod check_upward_messages {
 			);
 		});
 	}
+
+	#[test]
+	fn pending_messages_count_against_the_queue() {
+		new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
+			let _g = frame_support::StorageNoopGuard::default();
+			let count_limit = Configuration::config().max_upward_queue_count;
+			let size_limit = Configuration::config().max_upward_queue_size;
+
+			check_with_pending(P_0, vec![msg("")], (count_limit - 1, 0), None);
+			check_with_pending(
+				P_0,
+				vec![msg("")],
+				(count_limit, 0),
+				Some(UmpAcceptanceCheckErr::CapacityExceeded {
+					count: count_limit as u64 + 1,
+					limit: count_limit as u64,
+				}),
+			);
+			check_with_pending(P_0, vec![msg("1")], (1, size_limit - 1), None);
+			check_with_pending(
+				P_0,
+				vec![msg("1")],
+				(1, size_limit),
+				Some(UmpAcceptanceCheckErr::TotalSizeExceeded {
+					total_size: size_limit as u64 + 1,
+					limit: size_limit as u64,
+				}),
+			);
+		});
+	}
 }
 
 #[test]
@@ -456,10 +502,11 @@ fn verify_relay_dispatch_queue_size_is_externally_accessible() {
 
 fn assert_queue_size(para: ParaId, count: u32, size: u32) {
 	#[allow(deprecated)]
-	let raw_queue_size = sp_io::storage::get(&well_known_keys::relay_dispatch_queue_size(para)).expect(
-		"enqueing a message should create the dispatch queue\
+	let raw_queue_size = sp_io::storage::get(&well_known_keys::relay_dispatch_queue_size(para))
+		.expect(
+			"enqueing a message should create the dispatch queue\
 				and it should be accessible via the well known keys",
-	);
+		);
 	let (c, s) = <(u32, u32)>::decode(&mut &raw_queue_size[..])
 		.expect("the dispatch queue size should be decodable into (u32, u32)");
 	assert_eq!((c, s), (count, size));
@@ -18,7 +18,7 @@
 //! on all modules.
 
 use frame_system::pallet_prelude::BlockNumberFor;
-use primitives::{Id as ParaId, PersistedValidationData, ValidatorIndex};
+use primitives::{HeadData, Id as ParaId, PersistedValidationData, ValidatorIndex};
 use sp_std::{collections::btree_set::BTreeSet, vec::Vec};
 
 use crate::{configuration, hrmp, paras};
@@ -32,14 +32,28 @@ pub fn make_persisted_validation_data<T: paras::Config + hrmp::Config>(
 	relay_parent_number: BlockNumberFor<T>,
 	relay_parent_storage_root: T::Hash,
 ) -> Option<PersistedValidationData<T::Hash, BlockNumberFor<T>>> {
+	Some(make_persisted_validation_data_with_parent::<T>(
+		relay_parent_number,
+		relay_parent_storage_root,
+		<paras::Pallet<T>>::para_head(&para_id)?,
+	))
+}
+
+/// Make the persisted validation data for a candidate building on top of `parent_head`, which need
+/// not be included yet.
+pub fn make_persisted_validation_data_with_parent<T: configuration::Config>(
+	relay_parent_number: BlockNumberFor<T>,
+	relay_parent_storage_root: T::Hash,
+	parent_head: HeadData,
+) -> PersistedValidationData<T::Hash, BlockNumberFor<T>> {
 	let config = <configuration::Pallet<T>>::config();
 
-	Some(PersistedValidationData {
-		parent_head: <paras::Pallet<T>>::para_head(&para_id)?,
+	PersistedValidationData {
+		parent_head,
 		relay_parent_number,
 		relay_parent_storage_root,
 		max_pov_size: config.max_pov_size,
-	})
+	}
 }
 
 /// Take an active subset of a set containing all validators.
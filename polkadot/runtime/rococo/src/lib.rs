@@ -26,10 +26,11 @@ use primitives::{
 	slashing,
 	vstaging::{ApprovalVotingParams, NodeFeatures},
 	AccountId, AccountIndex, Balance, BlockNumber, CandidateEvent, CandidateHash,
-	CommittedCandidateReceipt, CoreState, DisputeState, ExecutorParams, GroupRotationInfo, Hash,
-	Id as ParaId, InboundDownwardMessage, InboundHrmpMessage, Moment, Nonce,
-	OccupiedCoreAssumption, PersistedValidationData, ScrapedOnChainVotes, SessionInfo, Signature,
-	ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex, PARACHAIN_KEY_TYPE_ID,
+	CommittedCandidateReceipt, CoreIndex, CoreState, DisputeState, ExecutorParams,
+	GroupRotationInfo, Hash, Id as ParaId, InboundDownwardMessage, InboundHrmpMessage, Moment,
+	Nonce, OccupiedCoreAssumption, PersistedValidationData, ScrapedOnChainVotes, SessionInfo,
+	Signature, ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex,
+	PARACHAIN_KEY_TYPE_ID,
 };
 use runtime_common::{
 	assigned_slots, auctions, claims, crowdloan, identity_migrator, impl_runtime_weights,
@@ -40,10 +41,15 @@ use runtime_common::{
 	SlowAdjustingFeeUpdate,
 };
 use scale_info::TypeInfo;
-use sp_std::{cmp::Ordering, collections::btree_map::BTreeMap, prelude::*};
+use sp_std::{
+	cmp::Ordering,
+	collections::{btree_map::BTreeMap, vec_deque::VecDeque},
+	prelude::*,
+};
 
 use runtime_parachains::{
-	assigner as parachains_assigner, assigner_on_demand as parachains_assigner_on_demand,
+	assigner as parachains_assigner, assigner_coretime as parachains_assigner_coretime,
+	assigner_on_demand as parachains_assigner_on_demand,
 	assigner_parachains as parachains_assigner_parachains,
 	configuration as parachains_configuration, disputes as parachains_disputes,
 	disputes::slashing as parachains_slashing,
@@ -106,7 +112,7 @@ pub use frame_system::Call as SystemCall;
 pub use pallet_balances::Call as BalancesCall;
 
 /// Constant values used within the runtime.
-use rococo_runtime_constants::{currency::*, fee::*, time::*};
+use rococo_runtime_constants::{currency::*, fee::*, system_parachain, time::*};
 
 // Weights used in the runtime.
 mod weights;
@@ -1017,6 +1023,16 @@ impl parachains_assigner_on_demand::Config for Runtime {
 
 impl parachains_assigner_parachains::Config for Runtime {}
 
+parameter_types! {
+	pub const BrokerId: ParaId = ParaId::new(system_parachain::BROKER_ID);
+}
+
+impl parachains_assigner_coretime::Config for Runtime {
+	type RuntimeOrigin = RuntimeOrigin;
+	type BrokerId = BrokerId;
+	type WeightInfo = parachains_assigner_coretime::TestWeightInfo;
+}
+
 impl parachains_assigner::Config for Runtime {
 	type OnDemandAssignmentProvider = OnDemandAssignmentProvider;
 	type ParachainsAssignmentProvider = ParachainsAssignmentProvider;
@@ -1408,6 +1424,7 @@ construct_runtime! {
 		ParaAssignmentProvider: parachains_assigner::{Pallet, Storage} = 65,
 		OnDemandAssignmentProvider: parachains_assigner_on_demand::{Pallet, Call, Storage, Event<T>} = 66,
 		ParachainsAssignmentProvider: parachains_assigner_parachains::{Pallet} = 67,
+		CoretimeAssignmentProvider: parachains_assigner_coretime::{Pallet, Call, Storage} = 68,
 
 		// Parachain Onboarding Pallets. Start indices at 70 to leave room.
 		Registrar: paras_registrar::{Pallet, Call, Storage, Event<T>, Config<T>} = 70,
@@ -1633,6 +1650,8 @@ pub mod migrations {
 		// Remove `im-online` pallet on-chain storage
 		frame_support::migrations::RemovePallet<ImOnlinePalletName, <Runtime as frame_system::Config>::DbWeight>,
 		parachains_configuration::migration::v11::MigrateToV11<Runtime>,
+		parachains_configuration::migration::v12::MigrateToV12<Runtime>,
+		parachains_inclusion::migration::v1::MigrateToV1<Runtime>,
 	);
 }
 
@@ -1694,6 +1713,7 @@ mod benches {
 		[runtime_parachains::paras_inherent, ParaInherent]
 		[runtime_parachains::paras, Paras]
 		[runtime_parachains::assigner_on_demand, OnDemandAssignmentProvider]
+		[runtime_parachains::assigner_coretime, CoretimeAssignmentProvider]
 		// Substrate
 		[pallet_balances, Balances]
 		[pallet_balances, NisCounterpartBalances]
@@ -1794,7 +1814,7 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
-	#[api_version(10)]
+	#[api_version(12)]
 	impl primitives::runtime_api::ParachainHost<Block> for Runtime {
 		fn validators() -> Vec<ValidatorId> {
 			parachains_runtime_api_impl::validators::<Runtime>()
@@ -1949,6 +1969,14 @@ sp_api::impl_runtime_apis! {
 		fn node_features() -> NodeFeatures {
 			parachains_staging_runtime_api_impl::node_features::<Runtime>()
 		}
+
+		fn candidates_pending_availability(para_id: ParaId) -> Vec<CommittedCandidateReceipt<Hash>> {
+			parachains_staging_runtime_api_impl::candidates_pending_availability::<Runtime>(para_id)
+		}
+
+		fn claim_queue() -> BTreeMap<CoreIndex, VecDeque<ParaId>> {
+			parachains_staging_runtime_api_impl::claim_queue::<Runtime>()
+		}
 	}
 
 	#[api_version(3)]
@@ -46,7 +46,7 @@ pub fn new_partial(
 		FullBackend,
 		FullSelectChain,
 		sc_consensus::DefaultImportQueue<Block>,
-		sc_transaction_pool::FullPool<Block, FullClient>,
+		sc_transaction_pool::FullTransactionPool<Block, FullClient>,
 		Option<Telemetry>,
 	>,
 	ServiceError,
@@ -79,7 +79,7 @@ pub fn new_partial(
 
 	let select_chain = sc_consensus::LongestChain::new(backend.clone());
 
-	let transaction_pool = sc_transaction_pool::BasicPool::new_full(
+	let transaction_pool = sc_transaction_pool::FullTransactionPool::new_full(
 		config.transaction_pool.clone(),
 		config.role.is_authority().into(),
 		config.prometheus_registry(),
@@ -174,7 +174,7 @@ pub fn new_full(config: Configuration, consensus: Consensus) -> Result<TaskManag
 		task_manager: &mut task_manager,
 		transaction_pool: transaction_pool.clone(),
 		rpc_builder: rpc_extensions_builder,
-		backend,
+		backend: backend.clone(),
 		system_rpc_tx,
 		tx_handler_controller,
 		sync_service,
@@ -238,6 +238,7 @@ pub fn new_full(config: Configuration, consensus: Consensus) -> Result<TaskManag
 				create_inherent_data_providers: move |_, ()| async move {
 					Ok(sp_timestamp::InherentDataProvider::from_system_time())
 				},
+				backend: Some(backend),
 			};
 			let authorship_future = sc_consensus_manual_seal::run_manual_seal(params);
 
@@ -61,11 +61,14 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 			future: PoolLimit { count: 100_000, total_bytes: 100 * 1024 * 1024 },
 			reject_future_transactions: false,
 			ban_time: Duration::from_secs(30 * 60),
+			..Default::default()
 		},
 		network: network_config,
 		keystore: KeystoreConfig::InMemory,
 		database: DatabaseSource::RocksDb { path: root.join("db"), cache_size: 128 },
 		trie_cache_maximum_size: Some(64 * 1024 * 1024),
+		trie_cache_snapshot: None,
+		trie_cache_warmup: false,
 		state_pruning: Some(PruningMode::ArchiveAll),
 		blocks_pruning: BlocksPruning::KeepAll,
 		chain_spec: spec,
@@ -90,11 +93,13 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 		tracing_receiver: Default::default(),
 		max_runtime_instances: 8,
 		runtime_cache_size: 2,
+		offchain_execution_limits: Default::default(),
 		announce_block: true,
 		data_path: base_path.path().into(),
 		base_path,
 		informant_output_format: Default::default(),
 		wasm_runtime_overrides: None,
+		runtime_artifact_cache: false,
 	};
 
 	node_cli::service::new_full_base(config, None, false, |_, _| ()).expect("Creates node")
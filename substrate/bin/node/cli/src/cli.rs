@@ -102,4 +102,7 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Copy a RocksDB database into a new ParityDB database.
+	ConvertDb(sc_cli::ConvertDbCmd),
 }
@@ -21,7 +21,7 @@
 //! Service implementation. Specialized wrapper over substrate service.
 
 use crate::Cli;
-use codec::Encode;
+use codec::{DecodeAll, Encode};
 use frame_benchmarking_cli::SUBSTRATE_REFERENCE_HARDWARE;
 use frame_system_rpc_runtime_api::AccountNonceApi;
 use futures::prelude::*;
@@ -30,7 +30,13 @@ use node_primitives::Block;
 use sc_client_api::{Backend, BlockBackend};
 use sc_consensus_babe::{self, SlotProportion};
 use sc_network::{event::Event, NetworkEventStream, NetworkService};
-use sc_network_sync::{warp::WarpSyncParams, SyncingService};
+use sc_network_sync::{
+	warp::{
+		CheckpointWarpSyncProvider, EncodedAuthorities, WarpSyncCheckpoint, WarpSyncParams,
+		WarpSyncProvider,
+	},
+	SyncingService,
+};
 use sc_service::{config::Configuration, error::Error as ServiceError, RpcHandlers, TaskManager};
 use sc_statement_store::Store as StatementStore;
 use sc_telemetry::{Telemetry, TelemetryWorker};
@@ -65,7 +71,7 @@ type FullGrandpaBlockImport =
 	grandpa::GrandpaBlockImport<FullBackend, Block, FullClient, FullSelectChain>;
 
 /// The transaction pool type definition.
-pub type TransactionPool = sc_transaction_pool::FullPool<Block, FullClient>;
+pub type TransactionPool = sc_transaction_pool::FullTransactionPool<Block, FullClient>;
 
 /// The minimum period of blocks on which justifications will be
 /// imported and generated.
@@ -158,7 +164,7 @@ pub fn new_partial(
 		FullBackend,
 		FullSelectChain,
 		sc_consensus::DefaultImportQueue<Block>,
-		sc_transaction_pool::FullPool<Block, FullClient>,
+		sc_transaction_pool::FullTransactionPool<Block, FullClient>,
 		(
 			impl Fn(
 				node_rpc::DenyUnsafe,
@@ -205,12 +211,16 @@ pub fn new_partial(
 
 	let select_chain = sc_consensus::LongestChain::new(backend.clone());
 
-	let transaction_pool = sc_transaction_pool::BasicPool::new_full(
+	let transaction_pool = sc_transaction_pool::FullTransactionPool::new_full_with_transaction_cost(
 		config.transaction_pool.clone(),
 		config.role.is_authority().into(),
 		config.prometheus_registry(),
 		task_manager.spawn_essential_handle(),
 		client.clone(),
+		Some(Arc::new(pallet_transaction_payment_rpc::TransactionPaymentCost::<
+			_,
+			node_primitives::Balance,
+		>::new(client.clone()))),
 	);
 
 	let (grandpa_block_import, grandpa_link) = grandpa::block_import(
@@ -407,11 +417,23 @@ pub fn new_full_base(
 		notification_service
 	});
 
-	let warp_sync = Arc::new(grandpa::warp_proof::NetworkProvider::new(
-		backend.clone(),
-		import_setup.1.shared_authority_set().clone(),
-		Vec::default(),
-	));
+	let mut warp_sync: Arc<dyn WarpSyncProvider<Block>> =
+		Arc::new(grandpa::warp_proof::NetworkProvider::new(
+			backend.clone(),
+			import_setup.1.shared_authority_set().clone(),
+			Vec::default(),
+		));
+	if let Some(checkpoint) = config.chain_spec.warp_sync_checkpoint() {
+		let hash = DecodeAll::decode_all(&mut &checkpoint.hash[..]).map_err(|e| {
+			ServiceError::Other(format!("Invalid warp sync checkpoint hash: {:?}", e))
+		})?;
+		let checkpoint = WarpSyncCheckpoint {
+			hash,
+			set_id: checkpoint.set_id,
+			authorities: EncodedAuthorities(checkpoint.authorities.0),
+		};
+		warp_sync = Arc::new(CheckpointWarpSyncProvider::new(checkpoint, warp_sync));
+	}
 
 	let (network, system_rpc_tx, tx_handler_controller, network_starter, sync_service) =
 		sc_service::build_network(sc_service::BuildNetworkParams {
@@ -426,6 +448,19 @@ pub fn new_full_base(
 			block_relay: None,
 		})?;
 
+	sc_service::spawn_indexed_transactions_backfill(
+		&config,
+		client.clone(),
+		backend.clone(),
+		sc_network_bitswap::TransactionStoragePallet::<_, FullBackend>::new(
+			client.clone(),
+			b"TransactionStorage",
+		),
+		network.clone(),
+		sync_service.clone(),
+		&task_manager.spawn_handle(),
+	);
+
 	if let Some(mixnet_config) = mixnet_config {
 		let mixnet = sc_mixnet::run(
 			mixnet_config,
@@ -664,6 +699,14 @@ pub fn new_full_base(
 			.run(client.clone(), task_manager.spawn_handle())
 			.boxed(),
 		);
+
+		task_manager.spawn_handle().spawn(
+			"offchain-tasks-runner",
+			"offchain-work",
+			sc_offchain::TaskSubmitter::new(client.clone(), transaction_pool.clone())
+				.run(client.clone(), task_manager.spawn_handle())
+				.boxed(),
+		);
 	}
 
 	network_starter.start_network();
@@ -2044,6 +2044,25 @@ impl pallet_broker::Config for Runtime {
 	type PriceAdapter = pallet_broker::Linear;
 }
 
+parameter_types! {
+	pub MbmServiceWeight: Weight = Perbill::from_percent(80) * RuntimeBlockWeights::get().max_block;
+}
+
+impl pallet_migrations::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	#[cfg(not(feature = "runtime-benchmarks"))]
+	type Migrations = ();
+	// Benchmarks need mocked migrations to guarantee that they succeed.
+	#[cfg(feature = "runtime-benchmarks")]
+	type Migrations = pallet_migrations::mock_helpers::MockedMigrations;
+	type CursorMaxLen = ConstU32<65_536>;
+	type IdentifierMaxLen = ConstU32<256>;
+	type MigrationStatusHandler = ();
+	type FailedMigrationHandler = frame_support::migrations::FreezeChainOnFailedMigration;
+	type MaxServiceWeight = MbmServiceWeight;
+	type WeightInfo = pallet_migrations::weights::SubstrateWeight<Runtime>;
+}
+
 parameter_types! {
 	pub const MixnetNumCoverToCurrentBlocks: BlockNumber = 3;
 	pub const MixnetNumRequestsToCurrentBlocks: BlockNumber = 3;
@@ -2146,6 +2165,7 @@ construct_runtime!(
 		TasksExample: pallet_example_tasks,
 		Mixnet: pallet_mixnet,
 		SkipFeelessPayment: pallet_skip_feeless_payment,
+		MultiBlockMigrations: pallet_migrations,
 	}
 );
 
@@ -2193,6 +2213,7 @@ pub type Executive = frame_executive::Executive<
 	Runtime,
 	AllPalletsWithSystem,
 	Migrations,
+	MultiBlockMigrations,
 >;
 
 // All migrations executed on runtime upgrade as a nested tuple of types implementing
@@ -2283,6 +2304,7 @@ mod benches {
 		[pallet_whitelist, Whitelist]
 		[pallet_tx_pause, TxPause]
 		[pallet_safe_mode, SafeMode]
+		[pallet_migrations, MultiBlockMigrations]
 	);
 }
 
@@ -2358,6 +2380,17 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl sp_offchain::TasksApi<Block> for Runtime {
+		fn valid_task_extrinsics(max: u32) -> Vec<<Block as BlockT>::Extrinsic> {
+			System::valid_tasks(max)
+				.into_iter()
+				.map(|task| {
+					UncheckedExtrinsic::new_unsigned(frame_system::Call::do_task { task }.into())
+				})
+				.collect()
+		}
+	}
+
 	impl sp_consensus_grandpa::GrandpaApi<Block> for Runtime {
 		fn grandpa_authorities() -> sp_consensus_grandpa::AuthorityList {
 			Grandpa::grandpa_authorities()
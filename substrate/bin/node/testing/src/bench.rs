@@ -381,6 +381,7 @@ impl BenchDb {
 	) -> (Client, std::sync::Arc<Backend>, TaskExecutor) {
 		let db_config = sc_client_db::DatabaseSettings {
 			trie_cache_maximum_size: Some(16 * 1024 * 1024),
+			trie_cache_snapshot: None,
 			state_pruning: Some(PruningMode::ArchiveAll),
 			source: database_type.into_settings(dir.into()),
 			blocks_pruning: sc_client_db::BlocksPruning::KeepAll,
@@ -408,6 +408,22 @@ where
 
 		Ok(Self { inner: state.raw_iter(args)?, state })
 	}
+
+	/// Create a new iterator over a child storage's key and value pairs.
+	pub fn new_child(
+		state: State,
+		child_info: ChildInfo,
+		prefix: Option<&StorageKey>,
+		start_at: Option<&StorageKey>,
+	) -> Result<Self, State::Error> {
+		let mut args = IterArgs::default();
+		args.prefix = prefix.as_ref().map(|prefix| prefix.0.as_slice());
+		args.start_at = start_at.as_ref().map(|start_at| start_at.0.as_slice());
+		args.child_info = Some(child_info);
+		args.start_at_exclusive = true;
+
+		Ok(Self { inner: state.raw_iter(args)?, state })
+	}
 }
 
 /// Provides access to storage primitives
@@ -463,6 +479,16 @@ pub trait StorageProvider<Block: BlockT, B: Backend<Block>> {
 		start_key: Option<&StorageKey>,
 	) -> sp_blockchain::Result<KeysIter<B::State, Block>>;
 
+	/// Given a block's `Hash`, a key `prefix` and a child storage key, returns an iterator over
+	/// the storage keys and values of the child storage in that block.
+	fn child_storage_pairs(
+		&self,
+		hash: Block::Hash,
+		child_info: ChildInfo,
+		prefix: Option<&StorageKey>,
+		start_key: Option<&StorageKey>,
+	) -> sp_blockchain::Result<PairsIter<B::State, Block>>;
+
 	/// Given a block's `Hash`, a key and a child storage key, return the hash under the key in that
 	/// block.
 	fn child_storage_hash(
@@ -602,6 +628,18 @@ pub trait Backend<Block: BlockT>: AuxStore + Send + Sync {
 	/// Discard non-best, unfinalized leaf block.
 	fn remove_leaf_block(&self, hash: Block::Hash) -> sp_blockchain::Result<()>;
 
+	/// Store an indexed transaction that was obtained outside of block import, e.g. from a peer.
+	///
+	/// The transaction is referenced by block `at`, in addition to the blocks that index it in
+	/// their body, and it is released once `at` is pruned.
+	fn insert_indexed_transaction(
+		&self,
+		_at: Block::Hash,
+		_transaction: Vec<u8>,
+	) -> sp_blockchain::Result<()> {
+		Err(sp_blockchain::Error::Backend("Indexed transactions are not supported".into()))
+	}
+
 	/// Insert auxiliary data into key-value store.
 	fn insert_aux<
 		'a,
@@ -30,7 +30,7 @@ use futures::{
 use log::{debug, error, info, trace, warn};
 use sc_block_builder::{BlockBuilderApi, BlockBuilderBuilder};
 use sc_telemetry::{telemetry, TelemetryHandle, CONSENSUS_INFO};
-use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
+use sc_transaction_pool_api::{ordering::TransactionOrdering, InPoolTransaction, TransactionPool};
 use sp_api::{ApiExt, CallApiAt, ProvideRuntimeApi};
 use sp_blockchain::{ApplyExtrinsicFailed::Validity, Error::ApplyExtrinsicFailed, HeaderBackend};
 use sp_consensus::{DisableProofRecording, EnableProofRecording, ProofRecording, Proposal};
@@ -83,6 +83,10 @@ pub struct ProposerFactory<A, C, PR> {
 	telemetry: Option<TelemetryHandle>,
 	/// When estimating the block size, should the proof be included?
 	include_proof_in_block_size_estimation: bool,
+	/// Order in which transactions are taken from the pool.
+	///
+	/// If `None`, the order configured in the transaction pool is used.
+	transaction_ordering: Option<Arc<dyn TransactionOrdering>>,
 	/// phantom member to pin the `ProofRecording` type.
 	_phantom: PhantomData<PR>,
 }
@@ -108,6 +112,7 @@ impl<A, C> ProposerFactory<A, C, DisableProofRecording> {
 			telemetry,
 			client,
 			include_proof_in_block_size_estimation: false,
+			transaction_ordering: None,
 			_phantom: PhantomData,
 		}
 	}
@@ -136,6 +141,7 @@ impl<A, C> ProposerFactory<A, C, EnableProofRecording> {
 			soft_deadline_percent: DEFAULT_SOFT_DEADLINE_PERCENT,
 			telemetry,
 			include_proof_in_block_size_estimation: true,
+			transaction_ordering: None,
 			_phantom: PhantomData,
 		}
 	}
@@ -173,6 +179,13 @@ impl<A, C, PR> ProposerFactory<A, C, PR> {
 	pub fn set_soft_deadline(&mut self, percent: Percent) {
 		self.soft_deadline_percent = percent;
 	}
+
+	/// Set the order in which transactions are taken from the pool.
+	///
+	/// By default the order configured in the transaction pool is used.
+	pub fn set_transaction_ordering(&mut self, ordering: Arc<dyn TransactionOrdering>) {
+		self.transaction_ordering = Some(ordering);
+	}
 }
 
 impl<Block, C, A, PR> ProposerFactory<A, C, PR>
@@ -204,6 +217,7 @@ where
 			telemetry: self.telemetry.clone(),
 			_phantom: PhantomData,
 			include_proof_in_block_size_estimation: self.include_proof_in_block_size_estimation,
+			transaction_ordering: self.transaction_ordering.clone(),
 		};
 
 		proposer
@@ -240,6 +254,7 @@ pub struct Proposer<Block: BlockT, C, A: TransactionPool, PR> {
 	include_proof_in_block_size_estimation: bool,
 	soft_deadline_percent: Percent,
 	telemetry: Option<TelemetryHandle>,
+	transaction_ordering: Option<Arc<dyn TransactionOrdering>>,
 	_phantom: PhantomData<PR>,
 }
 
@@ -395,7 +410,13 @@ where
 		let mut skipped = 0;
 		let mut unqueue_invalid = Vec::new();
 
-		let mut t1 = self.transaction_pool.ready_at(self.parent_number).fuse();
+		let mut t1 = match &self.transaction_ordering {
+			Some(ordering) => self
+				.transaction_pool
+				.ready_at_with_ordering(self.parent_number, ordering.clone()),
+			None => self.transaction_pool.ready_at(self.parent_number),
+		}
+		.fuse();
 		let mut t2 =
 			futures_timer::Delay::new(deadline.saturating_duration_since((self.now)()) / 8).fuse();
 
@@ -317,6 +317,26 @@ struct ClientSpec<E> {
 	/// given block number until the `spec_version` on chain changes.
 	#[serde(default)]
 	code_substitutes: BTreeMap<String, Bytes>,
+	/// Trusted block warp sync starts from, instead of genesis.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	warp_sync_checkpoint: Option<WarpSyncCheckpoint>,
+}
+
+/// Trusted block to start warp sync from.
+///
+/// Lets chains whose finality can't be proven from genesis warp sync, e.g. chains without a
+/// finality gadget, or whose gadget only proves recent blocks.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct WarpSyncCheckpoint {
+	/// Hash of the trusted block.
+	pub hash: Bytes,
+	/// Id of the authority set finalizing the blocks after the trusted one.
+	#[serde(default)]
+	pub set_id: u64,
+	/// SCALE encoded authorities of that set, in the format of the chain's finality gadget.
+	#[serde(default)]
+	pub authorities: Bytes,
 }
 
 /// A type denoting empty extensions.
@@ -337,6 +357,7 @@ pub struct ChainSpecBuilder<G, E = NoExtension, EHF = ()> {
 	protocol_id: Option<String>,
 	fork_id: Option<String>,
 	properties: Option<Properties>,
+	warp_sync_checkpoint: Option<WarpSyncCheckpoint>,
 	_genesis: PhantomData<(G, EHF)>,
 }
 
@@ -355,6 +376,7 @@ impl<G, E, EHF> ChainSpecBuilder<G, E, EHF> {
 			protocol_id: None,
 			fork_id: None,
 			properties: None,
+			warp_sync_checkpoint: None,
 			_genesis: Default::default(),
 		}
 	}
@@ -407,6 +429,12 @@ impl<G, E, EHF> ChainSpecBuilder<G, E, EHF> {
 		self
 	}
 
+	/// Sets the trusted block warp sync starts from.
+	pub fn with_warp_sync_checkpoint(mut self, checkpoint: WarpSyncCheckpoint) -> Self {
+		self.warp_sync_checkpoint = Some(checkpoint);
+		self
+	}
+
 	/// Sets chain spec extensions.
 	pub fn with_extensions(mut self, extensions: E) -> Self {
 		self.extensions = extensions;
@@ -446,6 +474,7 @@ impl<G, E, EHF> ChainSpecBuilder<G, E, EHF> {
 			consensus_engine: (),
 			genesis: Default::default(),
 			code_substitutes: BTreeMap::new(),
+			warp_sync_checkpoint: self.warp_sync_checkpoint,
 		};
 
 		ChainSpec {
@@ -515,6 +544,11 @@ impl<G, E, EHF> ChainSpec<G, E, EHF> {
 		self.client_spec.properties.as_ref().unwrap_or(&json::map::Map::new()).clone()
 	}
 
+	/// Trusted block warp sync starts from, if any.
+	pub fn warp_sync_checkpoint(&self) -> Option<&WarpSyncCheckpoint> {
+		self.client_spec.warp_sync_checkpoint.as_ref()
+	}
+
 	/// Add a bootnode to the list.
 	pub fn add_boot_node(&mut self, addr: MultiaddrWithPeerId) {
 		self.client_spec.boot_nodes.push(addr)
@@ -561,6 +595,7 @@ impl<G, E, EHF> ChainSpec<G, E, EHF> {
 			consensus_engine: (),
 			genesis: Default::default(),
 			code_substitutes: BTreeMap::new(),
+			warp_sync_checkpoint: None,
 		};
 
 		ChainSpec {
@@ -764,6 +799,10 @@ where
 			.map(|(h, c)| (h.clone(), c.0.clone()))
 			.collect()
 	}
+
+	fn warp_sync_checkpoint(&self) -> Option<WarpSyncCheckpoint> {
+		ChainSpec::warp_sync_checkpoint(self).cloned()
+	}
 }
 
 /// The `fun` will be called with the value at `path`.
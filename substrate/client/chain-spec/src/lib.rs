@@ -330,7 +330,7 @@ mod json_patch;
 pub use self::{
 	chain_spec::{
 		update_code_in_json_chain_spec, ChainSpec as GenericChainSpec, ChainSpecBuilder,
-		NoExtension,
+		NoExtension, WarpSyncCheckpoint,
 	},
 	extension::{get_extension, get_extension_mut, Extension, Fork, Forks, GetExtension, Group},
 	genesis_block::{
@@ -414,6 +414,10 @@ pub trait ChainSpec: BuildStorage + Send + Sync {
 	fn set_storage(&mut self, storage: Storage);
 	/// Returns code substitutes that should be used for the on chain wasm.
 	fn code_substitutes(&self) -> std::collections::BTreeMap<String, Vec<u8>>;
+	/// Returns the trusted block warp sync starts from, if any.
+	fn warp_sync_checkpoint(&self) -> Option<WarpSyncCheckpoint> {
+		None
+	}
 }
 
 impl std::fmt::Debug for dyn ChainSpec {
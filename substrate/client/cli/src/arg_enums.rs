@@ -251,3 +251,42 @@ impl Into<sc_network::config::SyncMode> for SyncMode {
 		}
 	}
 }
+
+/// Compression of the sections of a block archive.
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq)]
+#[value(rename_all = "kebab-case")]
+pub enum ArchiveCompression {
+	/// Store the sections uncompressed.
+	None,
+	/// Compress the sections with zstd.
+	Zstd,
+}
+
+impl Into<sc_service::chain_ops::ArchiveCodec> for ArchiveCompression {
+	fn into(self) -> sc_service::chain_ops::ArchiveCodec {
+		match self {
+			ArchiveCompression::None => sc_service::chain_ops::ArchiveCodec::Scale,
+			ArchiveCompression::Zstd => sc_service::chain_ops::ArchiveCodec::ScaleZstd,
+		}
+	}
+}
+
+/// The type of transaction pool.
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq)]
+#[value(rename_all = "kebab-case")]
+pub enum TransactionPoolType {
+	/// Keep one set of transactions that follows the best block.
+	SingleState,
+	/// Keep the transactions valid on every fork that is not finalized away.
+	ForkAware,
+}
+
+impl Into<sc_service::config::TransactionPoolType> for TransactionPoolType {
+	fn into(self) -> sc_service::config::TransactionPoolType {
+		match self {
+			TransactionPoolType::SingleState =>
+				sc_service::config::TransactionPoolType::SingleState,
+			TransactionPoolType::ForkAware => sc_service::config::TransactionPoolType::ForkAware,
+		}
+	}
+}
@@ -74,6 +74,7 @@ impl ChainInfoCmd {
 	{
 		let db_config = sc_client_db::DatabaseSettings {
 			trie_cache_maximum_size: config.trie_cache_maximum_size,
+			trie_cache_snapshot: None,
 			state_pruning: config.state_pruning.clone(),
 			source: config.database.clone(),
 			blocks_pruning: config.blocks_pruning,
@@ -0,0 +1,93 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{error, CliConfiguration, DatabaseParams, SharedParams};
+use clap::Parser;
+use log::info;
+use sc_service::DatabaseSource;
+use sp_runtime::traits::Block as BlockT;
+use std::{fmt::Debug, path::PathBuf};
+
+/// The `convert-db` command used to copy a RocksDB database into a new ParityDB database.
+///
+/// All columns are copied, state nodes and indexed transactions keep their reference counts.
+/// Afterwards every copied entry, the chain and the state of the finalized block are compared
+/// between both databases. Converting a ParityDB database back into RocksDB is not supported.
+#[derive(Debug, Clone, Parser)]
+pub struct ConvertDbCmd {
+	/// Path of the new ParityDB database.
+	///
+	/// Defaults to the path the node opens with `--database paritydb`.
+	#[arg(long, value_name = "PATH")]
+	pub output: Option<PathBuf>,
+
+	/// Skip comparing the converted database with the original one.
+	#[arg(long)]
+	pub skip_verification: bool,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ConvertDbCmd {
+	/// Run the convert-db command
+	pub fn run<B>(&self, config: &sc_service::Configuration) -> error::Result<()>
+	where
+		B: BlockT,
+	{
+		// With the default `auto` database, an existing RocksDB database is the one to convert.
+		let from = match config.database.clone() {
+			DatabaseSource::Auto { rocksdb_path, cache_size, .. } =>
+				DatabaseSource::RocksDb { path: rocksdb_path, cache_size },
+			source => source,
+		};
+		let to = DatabaseSource::ParityDb {
+			path: self
+				.output
+				.clone()
+				.unwrap_or_else(|| config.data_path.join("paritydb").join("full")),
+		};
+
+		let stats = sc_client_db::convert::convert_database::<B>(&from, &to)?;
+		let entries: u64 = stats.iter().map(|column| column.entries).sum();
+		let bytes: u64 = stats.iter().map(|column| column.bytes).sum();
+		info!("Copied {} entries ({} MiB) into {:?}", entries, bytes / (1024 * 1024), to.path());
+
+		if !self.skip_verification {
+			sc_client_db::convert::verify_conversion::<B>(&from, &to, &stats)?;
+			info!("The converted database holds the same entries, chain and state");
+		}
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ConvertDbCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -17,14 +17,18 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
+	arg_enums::ArchiveCompression,
 	error,
 	params::{DatabaseParams, GenericNumber, PruningParams, SharedParams},
 	CliConfiguration,
 };
 use clap::Parser;
 use log::info;
-use sc_client_api::{BlockBackend, HeaderBackend, UsageProvider};
-use sc_service::{chain_ops::export_blocks, config::DatabaseSource};
+use sc_client_api::{Backend, BlockBackend, HeaderBackend, StorageProvider, UsageProvider};
+use sc_service::{
+	chain_ops::{export_archive, export_blocks, ArchiveExportOptions},
+	config::DatabaseSource,
+};
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 use std::{fmt::Debug, fs, io, path::PathBuf, str::FromStr, sync::Arc};
 
@@ -41,7 +45,7 @@ pub struct ExportBlocksCmd {
 	pub from: Option<GenericNumber>,
 
 	/// Specify last block number.
-	/// Default is best block.
+	/// Default is best block, or the last finalized block when `--with-state` is used.
 	#[arg(long, value_name = "BLOCK")]
 	pub to: Option<GenericNumber>,
 
@@ -49,6 +53,33 @@ pub struct ExportBlocksCmd {
 	#[arg(long)]
 	pub binary: bool,
 
+	/// Export into a versioned archive with checksums rather than a plain stream of blocks.
+	///
+	/// Archives are detected automatically by `import-blocks`.
+	#[arg(long, conflicts_with = "binary")]
+	pub archive: bool,
+
+	/// Compression of the archive.
+	#[arg(
+		long,
+		value_enum,
+		value_name = "COMPRESSION",
+		default_value_t = ArchiveCompression::Zstd,
+		requires = "archive"
+	)]
+	pub compression: ArchiveCompression,
+
+	/// Maximum number of blocks per archive section.
+	#[arg(long, value_name = "COUNT", default_value_t = 1000, requires = "archive")]
+	pub blocks_per_section: u32,
+
+	/// Bundle the state of the last exported block into the archive.
+	///
+	/// Nodes importing the archive into an empty database bootstrap from this state instead of
+	/// executing all the blocks. The last exported block must be finalized.
+	#[arg(long, requires = "archive")]
+	pub with_state: bool,
+
 	#[allow(missing_docs)]
 	#[clap(flatten)]
 	pub shared_params: SharedParams,
@@ -64,14 +95,15 @@ pub struct ExportBlocksCmd {
 
 impl ExportBlocksCmd {
 	/// Run the export-blocks command
-	pub async fn run<B, C>(
+	pub async fn run<B, BA, C>(
 		&self,
 		client: Arc<C>,
 		database_config: DatabaseSource,
 	) -> error::Result<()>
 	where
 		B: BlockT,
-		C: HeaderBackend<B> + BlockBackend<B> + UsageProvider<B> + 'static,
+		BA: Backend<B>,
+		C: HeaderBackend<B> + BlockBackend<B> + UsageProvider<B> + StorageProvider<B, BA> + 'static,
 		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
 	{
 		if let Some(path) = database_config.path() {
@@ -88,6 +120,15 @@ impl ExportBlocksCmd {
 			None => Box::new(io::stdout()),
 		};
 
+		if self.archive {
+			let options = ArchiveExportOptions {
+				codec: self.compression.into(),
+				blocks_per_section: self.blocks_per_section.max(1),
+				with_state: self.with_state,
+			};
+			return export_archive(client, file, from.into(), to, options).await.map_err(Into::into)
+		}
+
 		export_blocks(client, file, from.into(), to, binary).await.map_err(Into::into)
 	}
 }
@@ -46,9 +46,15 @@ pub struct ImportBlocksCmd {
 	pub default_heap_pages: Option<u32>,
 
 	/// Try importing blocks from binary format rather than JSON.
+	///
+	/// Ignored for archives, their format is detected automatically.
 	#[arg(long)]
 	pub binary: bool,
 
+	/// Execute all the blocks of an archive rather than importing its bundled state.
+	#[arg(long)]
+	pub ignore_state: bool,
+
 	#[allow(missing_docs)]
 	#[clap(flatten)]
 	pub shared_params: SharedParams,
@@ -76,7 +82,7 @@ impl ImportBlocksCmd {
 			None => Box::new(io::stdin()),
 		};
 
-		import_blocks(client, import_queue, file, false, self.binary)
+		import_blocks(client, import_queue, file, false, self.binary, !self.ignore_state)
 			.await
 			.map_err(Into::into)
 	}
@@ -21,6 +21,8 @@
 mod build_spec_cmd;
 mod chain_info_cmd;
 mod check_block_cmd;
+#[cfg(feature = "rocksdb")]
+mod convert_db_cmd;
 mod export_blocks_cmd;
 mod export_state_cmd;
 mod generate;
@@ -47,3 +49,6 @@ pub use self::{
 	key::KeySubcommand, purge_chain_cmd::PurgeChainCmd, revert_cmd::RevertCmd, run_cmd::RunCmd,
 	sign::SignCmd, vanity::VanityCmd, verify::VerifyCmd,
 };
+
+#[cfg(feature = "rocksdb")]
+pub use self::convert_db_cmd::ConvertDbCmd;
@@ -30,7 +30,7 @@ use crate::{
 use clap::Parser;
 use regex::Regex;
 use sc_service::{
-	config::{BasePath, PrometheusConfig, TransactionPoolOptions},
+	config::{BasePath, ExecutionLimits, PrometheusConfig, TransactionPoolOptions},
 	ChainSpec, Role,
 };
 use sc_telemetry::TelemetryEndpoints;
@@ -400,6 +400,10 @@ impl CliConfiguration for RunCmd {
 		Ok(self.runtime_params.runtime_cache_size)
 	}
 
+	fn offchain_execution_limits(&self) -> Result<ExecutionLimits> {
+		Ok(self.runtime_params.offchain_execution_limits())
+	}
+
 	fn base_path(&self) -> Result<Option<BasePath>> {
 		Ok(if self.tmp {
 			Some(BasePath::new_temp_dir()?)
@@ -24,16 +24,20 @@ use crate::{
 };
 use log::warn;
 use names::{Generator, Name};
+use sc_network::config::Secret;
 use sc_service::{
 	config::{
-		BasePath, Configuration, DatabaseSource, KeystoreConfig, NetworkConfiguration,
-		NodeKeyConfig, OffchainWorkerConfig, PrometheusConfig, PruningMode, Role, RpcMethods,
-		TelemetryEndpoints, TransactionPoolOptions, WasmExecutionMethod,
+		BasePath, Configuration, DatabaseSource, ExecutionLimits, KeystoreConfig,
+		NetworkConfiguration, NodeKeyConfig, OffchainWorkerConfig, PrometheusConfig, PruningMode,
+		Role, RpcMethods, TelemetryEndpoints, TransactionPoolOptions, WasmExecutionMethod,
 	},
 	BlocksPruning, ChainSpec, TracingReceiver,
 };
 use sc_tracing::logging::LoggerBuilder;
-use std::{net::SocketAddr, path::PathBuf};
+use std::{
+	net::SocketAddr,
+	path::{Path, PathBuf},
+};
 
 /// The maximum number of characters for a node name.
 pub(crate) const NODE_NAME_MAX_LENGTH: usize = 64;
@@ -41,6 +45,10 @@ pub(crate) const NODE_NAME_MAX_LENGTH: usize = 64;
 /// Default sub directory to store network config.
 pub(crate) const DEFAULT_NETWORK_CONFIG_PATH: &str = "network";
 
+/// The file name of the WebRTC transport certificate inside the network config directory, next
+/// to the node key.
+pub(crate) const WEBRTC_CERTIFICATE_FILE: &str = "webrtc_certificate.pem";
+
 /// The recommended open file descriptor limit to be configured for the process.
 const RECOMMENDED_OPEN_FILE_DESCRIPTOR_LIMIT: u64 = 10_000;
 
@@ -180,7 +188,11 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 				default_listen_port,
 			)
 		} else {
-			NetworkConfiguration::new(node_name, client_id, node_key, Some(net_config_dir))
+			let webrtc_certificate = Secret::File(net_config_dir.join(WEBRTC_CERTIFICATE_FILE));
+			NetworkConfiguration {
+				webrtc_certificate,
+				..NetworkConfiguration::new(node_name, client_id, node_key, Some(net_config_dir))
+			}
 		})
 	}
 
@@ -241,6 +253,20 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(self.import_params().map(|x| x.trie_cache_maximum_size()).unwrap_or_default())
 	}
 
+	/// Get the path the trie cache is kept at across restarts.
+	///
+	/// By default this is retrieved from `ImportParams` if it is available. Otherwise its `None`.
+	fn trie_cache_snapshot(&self, data_path: &Path) -> Result<Option<PathBuf>> {
+		Ok(self.import_params().and_then(|x| x.trie_cache_snapshot(data_path)))
+	}
+
+	/// Should the trie cache be populated with the best state on startup?
+	///
+	/// By default this is retrieved from `ImportParams` if it is available. Otherwise its `false`.
+	fn trie_cache_warmup(&self) -> Result<bool> {
+		Ok(self.import_params().map_or(false, |x| x.trie_cache_warmup))
+	}
+
 	/// Get the state pruning mode.
 	///
 	/// By default this is retrieved from `PruningMode` if it is available. Otherwise its
@@ -290,6 +316,13 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		self.import_params().map(|x| x.wasm_runtime_overrides()).unwrap_or_default()
 	}
 
+	/// Should the compiled runtimes be kept on disk across restarts?
+	///
+	/// By default this is retrieved from `ImportParams` if it is available. Otherwise its `true`.
+	fn runtime_artifact_cache(&self) -> Result<bool> {
+		Ok(self.import_params().map_or(true, |x| !x.no_runtime_artifact_cache))
+	}
+
 	/// Get the RPC address.
 	fn rpc_addr(&self, _default_listen_port: u16) -> Result<Option<SocketAddr>> {
 		Ok(None)
@@ -428,6 +461,13 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(2)
 	}
 
+	/// Get the limits enforced on offchain runtime calls.
+	///
+	/// By default no limits are enforced.
+	fn offchain_execution_limits(&self) -> Result<ExecutionLimits> {
+		Ok(Default::default())
+	}
+
 	/// Activate or not the automatic announcing of blocks after import
 	///
 	/// By default this is `false`.
@@ -468,12 +508,17 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		let keystore = self.keystore_config(&config_dir)?;
 		let telemetry_endpoints = self.telemetry_endpoints(&chain_spec)?;
 		let runtime_cache_size = self.runtime_cache_size()?;
+		let mut transaction_pool = self.transaction_pool(is_dev)?;
+		// Relative journal paths are relative to the chain's data directory.
+		if let Some(journal) = transaction_pool.journal.as_mut() {
+			journal.path = config_dir.join(&journal.path);
+		}
 
 		Ok(Configuration {
 			impl_name: C::impl_name(),
 			impl_version: C::impl_version(),
 			tokio_handle,
-			transaction_pool: self.transaction_pool(is_dev)?,
+			transaction_pool,
 			network: self.network_config(
 				&chain_spec,
 				is_dev,
@@ -486,12 +531,15 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			)?,
 			keystore,
 			database: self.database_config(&config_dir, database_cache_size, database)?,
-			data_path: config_dir,
 			trie_cache_maximum_size: self.trie_cache_maximum_size()?,
+			trie_cache_snapshot: self.trie_cache_snapshot(&config_dir)?,
+			trie_cache_warmup: self.trie_cache_warmup()?,
+			data_path: config_dir,
 			state_pruning: self.state_pruning()?,
 			blocks_pruning: self.blocks_pruning()?,
 			wasm_method: self.wasm_method()?,
 			wasm_runtime_overrides: self.wasm_runtime_overrides(),
+			runtime_artifact_cache: self.runtime_artifact_cache()?,
 			rpc_addr: self.rpc_addr(DCV::rpc_listen_port())?,
 			rpc_methods: self.rpc_methods()?,
 			rpc_max_connections: self.rpc_max_connections()?,
@@ -518,6 +566,7 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			base_path,
 			informant_output_format: Default::default(),
 			runtime_cache_size,
+			offchain_execution_limits: self.offchain_execution_limits()?,
 		})
 	}
 
@@ -24,7 +24,10 @@ use crate::{
 	params::{DatabaseParams, PruningParams},
 };
 use clap::Args;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Path of the trie cache snapshot, relative to the chain's data directory.
+pub const DEFAULT_TRIE_CACHE_SNAPSHOT_PATH: &str = "trie-cache";
 
 /// Parameters for block import.
 #[derive(Debug, Clone, Args)]
@@ -71,6 +74,14 @@ pub struct ImportParams {
 	#[arg(long, value_name = "PATH")]
 	pub wasm_runtime_overrides: Option<PathBuf>,
 
+	/// Do not keep the runtimes compiled by wasmtime on disk.
+	///
+	/// By default the most recently used compiled runtimes are stored in the `executor` directory
+	/// of the chain's data directory, so that they don't need to be compiled again after a
+	/// restart.
+	#[arg(long)]
+	pub no_runtime_artifact_cache: bool,
+
 	#[allow(missing_docs)]
 	#[clap(flatten)]
 	pub execution_strategies: ExecutionStrategiesParams,
@@ -84,6 +95,19 @@ pub struct ImportParams {
 	/// DEPRECATED: switch to `--trie-cache-size`.
 	#[arg(long)]
 	state_cache_size: Option<usize>,
+
+	/// Keep the trie cache across restarts.
+	///
+	/// The cache is written to the chain's data directory on shutdown and loaded on startup.
+	/// Cached values are only loaded if the best block didn't change in the meantime.
+	#[arg(long)]
+	pub trie_cache_persist: bool,
+
+	/// Populate the trie cache on startup by reading the state of the best block.
+	///
+	/// Reading happens in the background and stops once the cache is full.
+	#[arg(long)]
+	pub trie_cache_warmup: bool,
 }
 
 impl ImportParams {
@@ -100,6 +124,12 @@ impl ImportParams {
 		}
 	}
 
+	/// Path of the trie cache snapshot in `data_path`, if the cache should be kept across restarts.
+	pub fn trie_cache_snapshot(&self, data_path: &Path) -> Option<PathBuf> {
+		self.trie_cache_persist
+			.then(|| data_path.join(DEFAULT_TRIE_CACHE_SNAPSHOT_PATH))
+	}
+
 	/// Get the WASM execution method from the parameters
 	pub fn wasm_method(&self) -> sc_service::config::WasmExecutionMethod {
 		self.execution_strategies.check_usage_and_print_deprecation_warning();
@@ -16,11 +16,14 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{arg_enums::SyncMode, params::node_key_params::NodeKeyParams};
+use crate::{
+	arg_enums::SyncMode, config::WEBRTC_CERTIFICATE_FILE, params::node_key_params::NodeKeyParams,
+};
 use clap::Args;
 use sc_network::{
 	config::{
-		NetworkConfiguration, NodeKeyConfig, NonReservedPeerMode, SetConfig, TransportConfig,
+		NetworkConfiguration, NodeKeyConfig, NonReservedPeerMode, Secret, SetConfig,
+		TransportConfig,
 	},
 	multiaddr::Protocol,
 };
@@ -109,6 +112,20 @@ pub struct NetworkParams {
 	#[arg(long)]
 	pub no_mdns: bool,
 
+	/// Disable the QUIC transport.
+	///
+	/// By default, the node can also listen on and dial `/udp/.../quic-v1` addresses.
+	#[arg(long)]
+	pub no_quic: bool,
+
+	/// Disable the WebRTC transport.
+	///
+	/// By default, the node can also listen on and dial `/udp/.../webrtc-direct` addresses, and
+	/// persists the certificate of the transport in the network config directory. No certificate
+	/// is generated or read if this is passed.
+	#[arg(long)]
+	pub no_webrtc: bool,
+
 	/// Maximum number of peers from which to ask for the same blocks in parallel.
 	///
 	/// This allows downloading announced blocks from multiple peers.
@@ -211,6 +228,11 @@ impl NetworkParams {
 
 		let public_addresses = self.public_addr.clone();
 
+		let webrtc_certificate = match &net_config_path {
+			Some(path) if !self.no_webrtc => Secret::File(path.join(WEBRTC_CERTIFICATE_FILE)),
+			_ => Secret::New,
+		};
+
 		let mut boot_nodes = chain_spec.boot_nodes().to_vec();
 		boot_nodes.extend(self.bootnodes.clone());
 
@@ -246,11 +268,14 @@ impl NetworkParams {
 			listen_addresses,
 			public_addresses,
 			node_key,
+			webrtc_certificate,
 			node_name: node_name.to_string(),
 			client_version: client_id.to_string(),
 			transport: TransportConfig::Normal {
 				enable_mdns: !is_dev && !self.no_mdns,
 				allow_private_ip,
+				enable_quic: !self.no_quic,
+				enable_webrtc: !self.no_webrtc,
 			},
 			max_parallel_downloads: self.max_parallel_downloads,
 			max_blocks_per_request: self.max_blocks_per_request,
@@ -34,9 +34,11 @@ pub struct PruningParams {
 	///  - archive: Keep the state of all blocks.
 	///  - 'archive-canonical' Keep only the state of finalized blocks.
 	///  - number Keep the state of the last number of finalized blocks.
+	///  - 'archive-from:N' Keep the state of the finalized blocks from block N onward.
+	///  - 'checkpoints:K' Keep the state of every K-th finalized block. Requires paritydb.
 	/// [default: 256]
 	#[arg(alias = "pruning", long, value_name = "PRUNING_MODE")]
-	pub state_pruning: Option<DatabasePruningMode>,
+	pub state_pruning: Option<StatePruningMode>,
 
 	/// Specify the blocks pruning mode.
 	///
@@ -117,3 +119,48 @@ impl Into<BlocksPruning> for DatabasePruningMode {
 		}
 	}
 }
+
+/// Specifies the state pruning mode of the database.
+///
+/// Extends [`DatabasePruningMode`] with the modes that only apply to the state.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatePruningMode {
+	/// A mode shared with the blocks pruning.
+	Database(DatabasePruningMode),
+	/// Keep the state of the finalized blocks from the given block onward.
+	ArchiveFrom(u64),
+	/// Keep the state of every given number of finalized blocks.
+	Checkpoints(u32),
+}
+
+impl std::str::FromStr for StatePruningMode {
+	type Err = String;
+
+	fn from_str(input: &str) -> Result<Self, Self::Err> {
+		if let Some(from) = input.strip_prefix("archive-from:") {
+			return from
+				.parse()
+				.map_err(|_| "Invalid archive start block specified".to_string())
+				.map(Self::ArchiveFrom)
+		}
+		if let Some(period) = input.strip_prefix("checkpoints:") {
+			return period
+				.parse::<u32>()
+				.ok()
+				.filter(|period| *period != 0)
+				.ok_or_else(|| "Invalid checkpoint period specified".to_string())
+				.map(Self::Checkpoints)
+		}
+		input.parse().map(Self::Database)
+	}
+}
+
+impl Into<PruningMode> for StatePruningMode {
+	fn into(self) -> PruningMode {
+		match self {
+			StatePruningMode::Database(mode) => mode.into(),
+			StatePruningMode::ArchiveFrom(from) => PruningMode::ArchiveFrom(from),
+			StatePruningMode::Checkpoints(period) => PruningMode::Checkpoints(period),
+		}
+	}
+}
@@ -17,6 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use clap::Args;
+use sc_service::config::ExecutionLimits;
 use std::str::FromStr;
 
 /// Parameters used to config runtime.
@@ -31,6 +32,30 @@ pub struct RuntimeParams {
 	/// Maximum number of different runtimes that can be cached.
 	#[arg(long, default_value_t = 2)]
 	pub runtime_cache_size: u8,
+
+	/// Maximum amount of fuel an offchain runtime call may consume, e.g. `state_call` from RPC.
+	///
+	/// The fuel roughly corresponds to the number of executed wasm instructions. Calls that run
+	/// out of fuel are aborted. By default offchain calls are not metered.
+	#[arg(long, value_name = "FUEL")]
+	pub offchain_max_fuel: Option<u64>,
+
+	/// Maximum wasm stack depth of an offchain runtime call, in logical stack values.
+	///
+	/// Enables deterministic stack limiting for offchain calls, so that deeply recursing calls
+	/// are aborted before exhausting the native stack.
+	#[arg(long, value_name = "VALUES")]
+	pub offchain_max_stack_depth: Option<u32>,
+}
+
+impl RuntimeParams {
+	/// Limits enforced on offchain runtime calls.
+	pub fn offchain_execution_limits(&self) -> ExecutionLimits {
+		ExecutionLimits {
+			max_fuel: self.offchain_max_fuel,
+			max_stack_depth: self.offchain_max_stack_depth,
+		}
+	}
 }
 
 fn parse_max_runtime_instances(s: &str) -> Result<usize, String> {
@@ -16,8 +16,12 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use crate::arg_enums::TransactionPoolType;
 use clap::Args;
-use sc_service::config::TransactionPoolOptions;
+use sc_service::config::{TransactionPoolJournalOptions, TransactionPoolOptions};
+
+/// Path of the transaction pool journal, relative to the chain's data directory.
+pub const DEFAULT_POOL_JOURNAL_PATH: &str = "txpool/journal";
 
 /// Parameters used to create the pool configuration.
 #[derive(Debug, Clone, Args)]
@@ -35,6 +39,24 @@ pub struct TransactionPoolParams {
 	/// If it is considered invalid. Defaults to 1800s.
 	#[arg(long, value_name = "SECONDS")]
 	pub tx_ban_seconds: Option<u64>,
+
+	/// The type of transaction pool.
+	///
+	/// The fork aware pool keeps the transactions of every fork validated, so they are not
+	/// missing from the pool after a reorg.
+	#[arg(long, value_name = "TYPE", value_enum, default_value_t = TransactionPoolType::SingleState)]
+	pub pool_type: TransactionPoolType,
+
+	/// Persist the transaction pool across restarts.
+	///
+	/// Pending transactions are written to a journal in the chain's data directory and are
+	/// revalidated and submitted again when the node starts.
+	#[arg(long)]
+	pub pool_journal: bool,
+
+	/// Maximum number of kilobytes of transactions written to the pool journal.
+	#[arg(long, value_name = "KBYTES", default_value_t = 10240, requires = "pool_journal")]
+	pub pool_journal_kbytes: usize,
 }
 
 impl TransactionPoolParams {
@@ -59,6 +81,14 @@ impl TransactionPoolParams {
 			std::time::Duration::from_secs(30 * 60)
 		};
 
+		opts.pool_type = self.pool_type.into();
+
+		if self.pool_journal {
+			let mut journal = TransactionPoolJournalOptions::new(DEFAULT_POOL_JOURNAL_PATH.into());
+			journal.max_bytes = self.pool_journal_kbytes * 1024;
+			opts.journal = Some(journal);
+		}
+
 		opts
 	}
 }
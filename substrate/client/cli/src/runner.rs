@@ -249,6 +249,8 @@ mod tests {
 				keystore: sc_service::config::KeystoreConfig::InMemory,
 				database: sc_client_db::DatabaseSource::ParityDb { path: root.clone() },
 				trie_cache_maximum_size: None,
+				trie_cache_snapshot: None,
+				trie_cache_warmup: false,
 				state_pruning: None,
 				blocks_pruning: sc_client_db::BlocksPruning::KeepAll,
 				chain_spec: Box::new(
@@ -261,6 +263,7 @@ mod tests {
 				),
 				wasm_method: Default::default(),
 				wasm_runtime_overrides: None,
+				runtime_artifact_cache: false,
 				rpc_addr: None,
 				rpc_max_connections: Default::default(),
 				rpc_cors: None,
@@ -285,6 +288,7 @@ mod tests {
 				data_path: root,
 				informant_output_format: Default::default(),
 				runtime_cache_size: 2,
+				offchain_execution_limits: Default::default(),
 			},
 			runtime,
 			Signals::dummy(),
@@ -68,6 +68,7 @@ mod worker;
 pub mod communication;
 pub mod import;
 pub mod justification;
+pub mod warp_proof;
 
 pub use communication::beefy_protocol_name::{
 	gossip_protocol_name, justifications_protocol_name as justifs_protocol_name,
@@ -0,0 +1,585 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Warp sync proofs for chains finalized by BEEFY.
+//!
+//! BEEFY signs the root of the chain's MMR, whose latest leaf commits to the parent of the
+//! justified block and to the next validator set. A proof is a list of fragments, each one a
+//! BEEFY justification together with the MMR leaf of the justified block and the header of its
+//! parent. The first fragment is verified against the validator set the syncing node already
+//! trusts: the genesis one, or the one of a
+//! [`WarpSyncCheckpoint`](sc_network_sync::warp::WarpSyncCheckpoint). A fragment hands off to
+//! the next validator set by carrying its authorities, which are checked against the commitment
+//! in the leaf.
+//!
+//! Proofs are generated from the MMR of the serving node, which must index it offchain.
+
+use crate::{
+	justification::{
+		decode_and_verify_finality_proof, proof_block_num_and_set_id, BeefyVersionedFinalityProof,
+	},
+	worker::find_authorities_change,
+};
+use codec::{Decode, DecodeAll, Encode};
+use sc_client_api::Backend as ClientBackend;
+use sc_network_sync::warp::{
+	EncodedAuthorities, EncodedProof, SetId, VerificationResult, WarpSyncProvider,
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::{Backend as BlockchainBackend, HeaderBackend};
+use sp_consensus_beefy::{
+	ecdsa_crypto::AuthorityId,
+	known_payloads::MMR_ROOT_ID,
+	mmr::{BeefyNextAuthoritySet, MmrLeaf},
+	MmrHashing, MmrRootHash, ValidatorSet, ValidatorSetId, VersionedFinalityProof, BEEFY_ENGINE_ID,
+};
+use sp_core::{crypto::ByteArray, hashing::keccak_256};
+use sp_mmr_primitives::{
+	mmr_lib, utils::NodesUtils, DataOrHash, EncodableOpaqueLeaf, MmrApi, OpaqueLeaf,
+	Proof as LeafProof,
+};
+use sp_runtime::traits::{Block as BlockT, Hash, Header as HeaderT, NumberFor, One, Saturating};
+use std::{collections::HashMap, marker::PhantomData, sync::Arc};
+
+/// Number of finalized blocks searched for BEEFY justifications by a proof that doesn't reach
+/// the finalized block.
+const MAX_BLOCKS_SEARCHED: u32 = 1 << 16;
+
+/// The part of the MMR leaves of `pallet-beefy-mmr` the proofs rely on, the leaf extra data is
+/// ignored.
+type MmrLeafPrefix<Block> = MmrLeaf<NumberFor<Block>, <Block as BlockT>::Hash, MmrRootHash, ()>;
+
+/// Warp proof processing error.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// Client backend error.
+	#[error("{0}")]
+	Client(#[from] sp_blockchain::Error),
+	/// Invalid warp proof.
+	#[error("{0}")]
+	InvalidProof(String),
+	/// No recent block with a BEEFY justification.
+	#[error("No BEEFY justification found after the requested block.")]
+	MissingJustification,
+}
+
+/// A BEEFY justification and the MMR leaf tying the parent of the justified block to it.
+#[derive(Decode, Encode, Debug)]
+pub struct BeefyWarpProofFragment<Block: BlockT> {
+	/// The header of the parent of the justified block.
+	pub header: Block::Header,
+	/// Encoded [`BeefyVersionedFinalityProof`] of the child of `header`.
+	pub justification: Vec<u8>,
+	/// The MMR leaf added by the justified block.
+	pub leaf: EncodableOpaqueLeaf,
+	/// Proof of `leaf` against the MMR root signed by `justification`.
+	pub leaf_proof: LeafProof<MmrRootHash>,
+	/// The authorities of the next validator set, if the fragment hands off to it.
+	pub next_authorities: Option<Vec<AuthorityId>>,
+}
+
+impl<Block: BlockT> BeefyWarpProofFragment<Block> {
+	/// Verify the fragment against `validator_set`, returning the validator set trusted after it.
+	///
+	/// Checks that the justification is signed by `validator_set`, that the signed MMR root
+	/// contains the leaf as its latest one, and that the leaf commits to `header`.
+	pub fn verify(
+		&self,
+		validator_set: &ValidatorSet<AuthorityId>,
+	) -> Result<ValidatorSet<AuthorityId>, Error> {
+		let justified = self.header.number().saturating_add(One::one());
+		let proof = decode_and_verify_finality_proof::<Block>(
+			&self.justification,
+			justified,
+			validator_set,
+		)
+		.map_err(|(e, _)| invalid(format!("Invalid BEEFY justification: {e}")))?;
+		let root = match &proof {
+			VersionedFinalityProof::V1(signed_commitment) =>
+				signed_commitment.commitment.payload.get_decoded::<MmrRootHash>(&MMR_ROOT_ID),
+		}
+		.ok_or_else(|| invalid("The BEEFY commitment has no MMR root"))?;
+
+		// The latest leaf of the signed MMR is the one added by the justified block.
+		if self.leaf_proof.leaf_indices != [self.leaf_proof.leaf_count.saturating_sub(1)] {
+			return Err(invalid("The MMR leaf is not the one of the justified block"))
+		}
+		verify_leaf_proof(root, &self.leaf, self.leaf_proof.clone())?;
+		let leaf = MmrLeafPrefix::<Block>::decode(&mut &self.leaf.0[..])
+			.map_err(|e| invalid(format!("MMR leaf decoding error: {e}")))?;
+		if leaf.parent_number_and_hash != (*self.header.number(), self.header.hash()) {
+			return Err(invalid("The MMR leaf doesn't commit to the header"))
+		}
+
+		match &self.next_authorities {
+			None => Ok(validator_set.clone()),
+			Some(authorities) =>
+				next_validator_set(validator_set, &leaf.beefy_next_authority_set, authorities),
+		}
+	}
+}
+
+/// Fragments proving the finality of a block, starting from a trusted validator set.
+#[derive(Decode, Encode, Debug)]
+pub struct BeefyWarpProof<Block: BlockT> {
+	/// Fragments in increasing block order, the last one proves the target of the proof.
+	pub fragments: Vec<BeefyWarpProofFragment<Block>>,
+	/// Whether the last fragment is the latest one of the serving node.
+	pub is_finished: bool,
+}
+
+impl<Block: BlockT> BeefyWarpProof<Block> {
+	/// Generate a proof for the finalized blocks following `begin`.
+	///
+	/// Contains the last justification of each validator set, handing off to the next one if
+	/// it was announced. Searches up to [`MAX_BLOCKS_SEARCHED`] blocks, or further until a
+	/// justification is found.
+	pub fn generate<Backend, Client>(
+		backend: &Backend,
+		client: &Client,
+		begin: Block::Hash,
+	) -> Result<Self, Error>
+	where
+		Backend: ClientBackend<Block>,
+		Client: ProvideRuntimeApi<Block>,
+		Client::Api: MmrApi<Block, MmrRootHash, NumberFor<Block>>,
+	{
+		let blockchain = backend.blockchain();
+		let begin_number =
+			blockchain.number(begin)?.ok_or_else(|| invalid("Missing start block"))?;
+		let info = blockchain.info();
+		if begin_number > info.finalized_number {
+			return Err(invalid("Start block is not finalized"))
+		}
+
+		// The last justification of each validator set, and the authorities of the sets.
+		let mut justified = Vec::<(ValidatorSetId, Block::Hash, Vec<u8>)>::new();
+		let mut announced = HashMap::<ValidatorSetId, Vec<AuthorityId>>::new();
+		let search_end = begin_number.saturating_add(MAX_BLOCKS_SEARCHED.into());
+		let mut number = begin_number;
+		while number < info.finalized_number {
+			if number >= search_end && !justified.is_empty() {
+				break
+			}
+			number += One::one();
+
+			let hash = blockchain
+				.hash(number)?
+				.ok_or_else(|| invalid(format!("Missing finalized block {number}")))?;
+			let header = blockchain.expect_header(hash)?;
+			if let Some(validator_set) = find_authorities_change::<Block>(&header) {
+				announced.insert(validator_set.id(), validator_set.validators().to_vec());
+			}
+			let justification = blockchain
+				.justifications(hash)?
+				.and_then(|justifications| justifications.into_justification(BEEFY_ENGINE_ID));
+			// A justification of the block following `begin` was the last fragment of the previous
+			// proof, since fragments end with the parent of the justified block.
+			let justification = justification.filter(|_| number > begin_number + One::one());
+			if let Some(justification) = justification {
+				let proof =
+					BeefyVersionedFinalityProof::<Block>::decode_all(&mut &justification[..])
+						.map_err(|e| {
+							invalid(format!("Stored BEEFY justification decoding error: {e}"))
+						})?;
+				let (_, set_id) = proof_block_num_and_set_id::<Block>(&proof);
+				match justified.last_mut() {
+					Some(last) if last.0 == set_id => *last = (set_id, hash, justification),
+					_ => justified.push((set_id, hash, justification)),
+				}
+			}
+		}
+		if justified.is_empty() {
+			return Err(Error::MissingJustification)
+		}
+
+		let mut fragments = Vec::with_capacity(justified.len());
+		for (set_id, hash, justification) in justified {
+			let justified_header = blockchain.expect_header(hash)?;
+			let header = blockchain.expect_header(*justified_header.parent_hash())?;
+			let justified_number = *justified_header.number();
+			let (mut leaves, leaf_proof) = client
+				.runtime_api()
+				.generate_proof(info.finalized_hash, vec![justified_number], Some(justified_number))
+				.map_err(sp_blockchain::Error::RuntimeApiError)?
+				.map_err(|e| invalid(format!("MMR proof generation error: {e:?}")))?;
+			let leaf = leaves.pop().ok_or_else(|| invalid("Empty MMR proof"))?;
+			fragments.push(BeefyWarpProofFragment {
+				header,
+				justification,
+				leaf,
+				leaf_proof,
+				next_authorities: announced.get(&(set_id + 1)).cloned(),
+			});
+		}
+
+		Ok(BeefyWarpProof { fragments, is_finished: number == info.finalized_number })
+	}
+
+	/// Verify the fragments in order, starting from `validator_set`.
+	///
+	/// Returns the validator set trusted after the last fragment.
+	pub fn verify(
+		&self,
+		validator_set: ValidatorSet<AuthorityId>,
+	) -> Result<ValidatorSet<AuthorityId>, Error> {
+		if self.fragments.is_empty() {
+			return Err(invalid("Empty proof"))
+		}
+
+		let mut validator_set = validator_set;
+		let mut previous: Option<NumberFor<Block>> = None;
+		for fragment in &self.fragments {
+			let number = *fragment.header.number();
+			if previous.map_or(false, |previous| number <= previous) {
+				return Err(invalid("Fragments are not in increasing block order"))
+			}
+			previous = Some(number);
+			validator_set = fragment.verify(&validator_set)?;
+		}
+
+		Ok(validator_set)
+	}
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+	Error::InvalidProof(message.into())
+}
+
+/// Merges MMR nodes the way `pallet-mmr` does.
+struct MmrHasher;
+
+impl mmr_lib::Merge for MmrHasher {
+	type Item = DataOrHash<MmrHashing, OpaqueLeaf>;
+
+	fn merge(left: &Self::Item, right: &Self::Item) -> mmr_lib::Result<Self::Item> {
+		let mut concat = left.hash().as_ref().to_vec();
+		concat.extend_from_slice(right.hash().as_ref());
+		Ok(DataOrHash::Hash(MmrHashing::hash(&concat)))
+	}
+}
+
+/// Check that `leaf` is part of the MMR with the given `root`.
+fn verify_leaf_proof(
+	root: MmrRootHash,
+	leaf: &EncodableOpaqueLeaf,
+	proof: LeafProof<MmrRootHash>,
+) -> Result<(), Error> {
+	let size = NodesUtils::new(proof.leaf_count).size();
+	let leaves = proof
+		.leaf_indices
+		.iter()
+		.map(|index| {
+			(mmr_lib::leaf_index_to_pos(*index), DataOrHash::Data(leaf.clone().into_opaque_leaf()))
+		})
+		.collect();
+	let proof = mmr_lib::MerkleProof::<_, MmrHasher>::new(
+		size,
+		proof.items.into_iter().map(DataOrHash::Hash).collect(),
+	);
+	match proof.verify(DataOrHash::Hash(root), leaves) {
+		Ok(true) => Ok(()),
+		Ok(false) => Err(invalid("The MMR leaf is not part of the signed MMR")),
+		Err(e) => Err(invalid(format!("Invalid MMR proof: {e:?}"))),
+	}
+}
+
+/// The validator set following `validator_set`, given its `authorities` and the commitment to
+/// them in an MMR leaf.
+fn next_validator_set(
+	validator_set: &ValidatorSet<AuthorityId>,
+	next: &BeefyNextAuthoritySet<MmrRootHash>,
+	authorities: &[AuthorityId],
+) -> Result<ValidatorSet<AuthorityId>, Error> {
+	if next.id != validator_set.id() + 1 {
+		return Err(invalid(format!("Unexpected handoff to validator set {}", next.id)))
+	}
+	if next.len as usize != authorities.len() ||
+		keyset_commitment(authorities) != next.keyset_commitment
+	{
+		return Err(invalid("The next authorities don't match the MMR leaf"))
+	}
+	ValidatorSet::new(authorities.to_vec(), next.id)
+		.ok_or_else(|| invalid("Empty next validator set"))
+}
+
+/// Merkle root of the Ethereum addresses of `authorities`, as committed to by `pallet-beefy-mmr`.
+pub fn keyset_commitment(authorities: &[AuthorityId]) -> MmrRootHash {
+	let addresses = authorities.iter().map(|authority| eth_address(authority).unwrap_or_default());
+	binary_merkle_tree::merkle_root::<MmrHashing, _>(addresses)
+}
+
+/// The Ethereum address of `authority`, the last 20 bytes of the hash of its uncompressed key.
+fn eth_address(authority: &AuthorityId) -> Option<[u8; 20]> {
+	use k256::{elliptic_curve::sec1::ToEncodedPoint, PublicKey};
+
+	let public = PublicKey::from_sec1_bytes(authority.as_slice()).ok()?;
+	let uncompressed = public.to_encoded_point(false);
+	<[u8; 20]>::try_from(&keccak_256(&uncompressed.as_bytes()[1..])[12..]).ok()
+}
+
+/// Implements network API for warp sync.
+pub struct NetworkProvider<Block: BlockT, Backend: ClientBackend<Block>, Client> {
+	backend: Arc<Backend>,
+	client: Arc<Client>,
+	genesis_authorities: Vec<AuthorityId>,
+	_phantom: PhantomData<Block>,
+}
+
+impl<Block: BlockT, Backend: ClientBackend<Block>, Client> NetworkProvider<Block, Backend, Client> {
+	/// Create a new instance for a given backend, the client generating MMR proofs and the
+	/// authorities of the genesis validator set.
+	pub fn new(
+		backend: Arc<Backend>,
+		client: Arc<Client>,
+		genesis_authorities: Vec<AuthorityId>,
+	) -> Self {
+		NetworkProvider { backend, client, genesis_authorities, _phantom: PhantomData }
+	}
+}
+
+impl<Block, Backend, Client> WarpSyncProvider<Block> for NetworkProvider<Block, Backend, Client>
+where
+	Block: BlockT,
+	Backend: ClientBackend<Block>,
+	Client: ProvideRuntimeApi<Block> + Send + Sync,
+	Client::Api: MmrApi<Block, MmrRootHash, NumberFor<Block>>,
+{
+	fn generate(
+		&self,
+		start: Block::Hash,
+	) -> Result<EncodedProof, Box<dyn std::error::Error + Send + Sync>> {
+		let proof = BeefyWarpProof::<Block>::generate(&*self.backend, &*self.client, start)
+			.map_err(Box::new)?;
+		Ok(EncodedProof(proof.encode()))
+	}
+
+	fn verify(
+		&self,
+		proof: &EncodedProof,
+		set_id: SetId,
+		authorities: EncodedAuthorities,
+	) -> Result<VerificationResult<Block>, Box<dyn std::error::Error + Send + Sync>> {
+		let validators = Vec::<AuthorityId>::decode_all(&mut authorities.0.as_slice())
+			.map_err(|e| format!("Authorities decoding error: {:?}", e))?;
+		let validator_set = ValidatorSet::new(validators, set_id)
+			.ok_or_else(|| "Empty validator set".to_string())?;
+		let EncodedProof(proof) = proof;
+		let proof = BeefyWarpProof::<Block>::decode_all(&mut proof.as_slice())
+			.map_err(|e| format!("Proof decoding error: {:?}", e))?;
+		let validator_set = proof.verify(validator_set).map_err(Box::new)?;
+		let header = proof
+			.fragments
+			.last()
+			.expect("verified proofs have at least one fragment; qed")
+			.header
+			.clone();
+		let authorities = EncodedAuthorities(validator_set.validators().encode());
+		if proof.is_finished {
+			Ok(VerificationResult::Complete(validator_set.id(), authorities, header))
+		} else {
+			Ok(VerificationResult::Partial(validator_set.id(), authorities, header.hash()))
+		}
+	}
+
+	fn current_authorities(&self) -> EncodedAuthorities {
+		EncodedAuthorities(self.genesis_authorities.encode())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::justification::tests::new_finality_proof;
+	use sp_consensus_beefy::{mmr::MmrLeafVersion, Commitment, Keyring, Payload, SignedCommitment};
+	use sp_core::H256;
+	use substrate_test_runtime_client::runtime::{Block, Header};
+
+	fn header(number: u64) -> Header {
+		Header::new(
+			number,
+			Default::default(),
+			Default::default(),
+			H256::random(),
+			Default::default(),
+		)
+	}
+
+	/// The MMR leaf added by the child of `parent`, announcing `next`.
+	fn leaf(parent: &Header, next: BeefyNextAuthoritySet<MmrRootHash>) -> EncodableOpaqueLeaf {
+		EncodableOpaqueLeaf::from_leaf(&MmrLeaf {
+			version: MmrLeafVersion::new(0, 0),
+			parent_number_and_hash: (parent.number, parent.hash()),
+			beefy_next_authority_set: next,
+			leaf_extra: H256::repeat_byte(0x42),
+		})
+	}
+
+	fn next_set(id: ValidatorSetId, keys: &[Keyring]) -> BeefyNextAuthoritySet<MmrRootHash> {
+		let authorities = crate::tests::make_beefy_ids(keys);
+		BeefyNextAuthoritySet {
+			id,
+			len: authorities.len() as u32,
+			keyset_commitment: keyset_commitment(&authorities),
+		}
+	}
+
+	fn signed_proof(
+		number: u64,
+		root: MmrRootHash,
+		validator_set: &ValidatorSet<AuthorityId>,
+		keys: &[Keyring],
+	) -> Vec<u8> {
+		let commitment = Commitment {
+			payload: Payload::from_single_entry(MMR_ROOT_ID, root.encode()),
+			block_number: number,
+			validator_set_id: validator_set.id(),
+		};
+		let message = commitment.encode();
+		let signatures = keys.iter().map(|key| Some(key.sign(&message))).collect();
+		BeefyVersionedFinalityProof::<Block>::V1(SignedCommitment { commitment, signatures })
+			.encode()
+	}
+
+	/// A fragment justifying the child of `parent` with an MMR made of `leaf` only.
+	fn new_fragment(
+		parent: Header,
+		leaf: EncodableOpaqueLeaf,
+		validator_set: &ValidatorSet<AuthorityId>,
+		keys: &[Keyring],
+	) -> BeefyWarpProofFragment<Block> {
+		let root = DataOrHash::<MmrHashing, _>::Data(leaf.clone().into_opaque_leaf()).hash();
+		BeefyWarpProofFragment {
+			justification: signed_proof(parent.number + 1, root, validator_set, keys),
+			header: parent,
+			leaf,
+			leaf_proof: LeafProof { leaf_indices: vec![0], leaf_count: 1, items: vec![] },
+			next_authorities: None,
+		}
+	}
+
+	#[test]
+	fn verifies_justified_header() {
+		let keys = &[Keyring::Alice, Keyring::Bob];
+		let validator_set = ValidatorSet::new(crate::tests::make_beefy_ids(keys), 0).unwrap();
+		let parent = header(4);
+
+		let fragment =
+			new_fragment(parent.clone(), leaf(&parent, next_set(1, keys)), &validator_set, keys);
+		assert_eq!(fragment.verify(&validator_set).unwrap(), validator_set);
+
+		// wrong validator set id
+		let other_set = ValidatorSet::new(crate::tests::make_beefy_ids(keys), 1).unwrap();
+		assert!(fragment.verify(&other_set).is_err());
+	}
+
+	#[test]
+	fn rejects_forged_header() {
+		let keys = &[Keyring::Alice, Keyring::Bob];
+		let validator_set = ValidatorSet::new(crate::tests::make_beefy_ids(keys), 0).unwrap();
+		let parent = header(4);
+
+		// the header is not the one the signed leaf commits to
+		let mut fragment =
+			new_fragment(parent.clone(), leaf(&parent, next_set(1, keys)), &validator_set, keys);
+		fragment.header = header(4);
+		assert!(fragment.verify(&validator_set).is_err());
+
+		// the leaf is not part of the signed MMR
+		let mut fragment = fragment_with_other_leaf(parent.clone(), &validator_set, keys);
+		assert!(fragment.verify(&validator_set).is_err());
+		fragment.leaf_proof.leaf_count = 2;
+		assert!(fragment.verify(&validator_set).is_err());
+
+		// the commitment has no MMR root at all
+		let mut fragment =
+			new_fragment(parent.clone(), leaf(&parent, next_set(1, keys)), &validator_set, keys);
+		fragment.justification = new_finality_proof(5, &validator_set, keys).encode();
+		assert!(fragment.verify(&validator_set).is_err());
+
+		// the justification is for another block than the child of the header
+		let mut fragment =
+			new_fragment(parent.clone(), leaf(&parent, next_set(1, keys)), &validator_set, keys);
+		fragment.header = header(5);
+		assert!(fragment.verify(&validator_set).is_err());
+	}
+
+	fn fragment_with_other_leaf(
+		parent: Header,
+		validator_set: &ValidatorSet<AuthorityId>,
+		keys: &[Keyring],
+	) -> BeefyWarpProofFragment<Block> {
+		let signed =
+			new_fragment(parent.clone(), leaf(&parent, next_set(1, keys)), validator_set, keys);
+		let other =
+			new_fragment(parent.clone(), leaf(&parent, next_set(2, keys)), validator_set, keys);
+		BeefyWarpProofFragment { leaf: other.leaf, ..signed }
+	}
+
+	#[test]
+	fn follows_validator_set_change() {
+		let keys = &[Keyring::Alice, Keyring::Bob];
+		let next_keys = &[Keyring::Charlie, Keyring::Dave, Keyring::Eve];
+		let validator_set = ValidatorSet::new(crate::tests::make_beefy_ids(keys), 0).unwrap();
+		let next_validator_set =
+			ValidatorSet::new(crate::tests::make_beefy_ids(next_keys), 1).unwrap();
+
+		// set 0 justifies block 5, whose leaf announces set 1
+		let parent = header(4);
+		let mut handoff = new_fragment(
+			parent.clone(),
+			leaf(&parent, next_set(1, next_keys)),
+			&validator_set,
+			keys,
+		);
+		handoff.next_authorities = Some(next_validator_set.validators().to_vec());
+		// set 1 justifies block 10
+		let target = header(9);
+		let last = new_fragment(
+			target.clone(),
+			leaf(&target, next_set(2, next_keys)),
+			&next_validator_set,
+			next_keys,
+		);
+
+		let proof = BeefyWarpProof::<Block> { fragments: vec![handoff, last], is_finished: true };
+		assert_eq!(proof.verify(validator_set.clone()).unwrap(), next_validator_set);
+
+		// the handoff announces other authorities than the ones signing the next fragment
+		let mut forged = proof;
+		forged.fragments[0].next_authorities = Some(crate::tests::make_beefy_ids(keys));
+		assert!(forged.verify(validator_set.clone()).is_err());
+
+		// without the handoff, the next fragment is signed by an unknown validator set
+		forged.fragments[0].next_authorities = None;
+		assert!(forged.verify(validator_set.clone()).is_err());
+
+		// the fragments are out of order
+		let first =
+			new_fragment(parent.clone(), leaf(&parent, next_set(1, keys)), &validator_set, keys);
+		let second =
+			new_fragment(target.clone(), leaf(&target, next_set(1, keys)), &validator_set, keys);
+		let proof = BeefyWarpProof::<Block> { fragments: vec![first, second], is_finished: true };
+		assert_eq!(proof.verify(validator_set.clone()).unwrap(), validator_set);
+		let reversed = BeefyWarpProof::<Block> {
+			fragments: proof.fragments.into_iter().rev().collect(),
+			is_finished: true,
+		};
+		assert!(reversed.verify(validator_set).is_err());
+	}
+}
@@ -23,7 +23,9 @@ use crate::{
 	BlockNumberOps, GrandpaJustification, SharedAuthoritySet,
 };
 use sc_client_api::Backend as ClientBackend;
-use sc_network_sync::warp::{EncodedProof, VerificationResult, WarpSyncProvider};
+use sc_network_sync::warp::{
+	EncodedAuthorities, EncodedProof, VerificationResult, WarpSyncProvider,
+};
 use sp_blockchain::{Backend as BlockchainBackend, HeaderBackend};
 use sp_consensus_grandpa::{AuthorityList, SetId, GRANDPA_ENGINE_ID};
 use sp_runtime::{
@@ -288,8 +290,10 @@ where
 		&self,
 		proof: &EncodedProof,
 		set_id: SetId,
-		authorities: AuthorityList,
+		authorities: EncodedAuthorities,
 	) -> Result<VerificationResult<Block>, Box<dyn std::error::Error + Send + Sync>> {
+		let authorities = AuthorityList::decode_all(&mut authorities.0.as_slice())
+			.map_err(|e| format!("Authorities decoding error: {:?}", e))?;
 		let EncodedProof(proof) = proof;
 		let proof = WarpSyncProof::<Block>::decode_all(&mut proof.as_slice())
 			.map_err(|e| format!("Proof decoding error: {:?}", e))?;
@@ -300,6 +304,7 @@ where
 			.ok_or_else(|| "Empty proof".to_string())?;
 		let (next_set_id, next_authorities) =
 			proof.verify(set_id, authorities, &self.hard_forks).map_err(Box::new)?;
+		let next_authorities = EncodedAuthorities(next_authorities.encode());
 		if proof.is_finished {
 			Ok(VerificationResult::<Block>::Complete(next_set_id, next_authorities, last_header))
 		} else {
@@ -311,8 +316,8 @@ where
 		}
 	}
 
-	fn current_authorities(&self) -> AuthorityList {
-		self.authority_set.inner().current_authorities.clone()
+	fn current_authorities(&self) -> EncodedAuthorities {
+		EncodedAuthorities(self.authority_set.inner().current_authorities.encode())
 	}
 }
 
@@ -37,11 +37,14 @@ pub trait ConsensusDataProvider<B: BlockT>: Send + Sync {
 	fn create_digest(&self, parent: &B::Header, inherents: &InherentData) -> Result<Digest, Error>;
 
 	/// Set up the necessary import params.
+	///
+	/// The `proof` is `None` if the block was not built by the proposer, e.g. because the
+	/// transactions to include were given explicitly.
 	fn append_block_import(
 		&self,
 		parent: &B::Header,
 		params: &mut BlockImportParams<B>,
 		inherents: &InherentData,
-		proof: Self::Proof,
+		proof: Option<Self::Proof>,
 	) -> Result<(), Error>;
 }
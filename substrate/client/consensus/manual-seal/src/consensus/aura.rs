@@ -93,7 +93,7 @@ where
 		_parent: &B::Header,
 		_params: &mut BlockImportParams<B>,
 		_inherents: &InherentData,
-		_proof: Self::Proof,
+		_proof: Option<Self::Proof>,
 	) -> Result<(), Error> {
 		Ok(())
 	}
@@ -265,7 +265,7 @@ where
 		parent: &B::Header,
 		params: &mut BlockImportParams<B>,
 		inherents: &InherentData,
-		_proof: Self::Proof,
+		_proof: Option<Self::Proof>,
 	) -> Result<(), Error> {
 		let slot = inherents
 			.babe_inherent_data()?
@@ -30,6 +30,7 @@ use sc_consensus::{
 	block_import::{BlockImport, BlockImportParams, ForkChoiceStrategy},
 	import_queue::{BasicQueue, BoxBlockImport, Verifier},
 };
+use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::HeaderBackend;
 use sp_consensus::{Environment, Proposer, SelectChain};
 use sp_core::traits::SpawnNamed;
@@ -40,6 +41,8 @@ use std::{marker::PhantomData, sync::Arc, time::Duration};
 mod error;
 mod finalize_block;
 mod seal_block;
+mod snapshot;
+mod time_control;
 
 pub mod consensus;
 pub mod rpc;
@@ -50,9 +53,11 @@ pub use self::{
 	finalize_block::{finalize_block, FinalizeBlockParams},
 	rpc::{CreatedBlock, EngineCommand},
 	seal_block::{seal_block, SealBlockParams, MAX_PROPOSAL_DURATION},
+	snapshot::Snapshots,
+	time_control::{AppliedTimestamp, TimeControl},
 };
 use sc_transaction_pool_api::TransactionPool;
-use sp_api::ProvideRuntimeApi;
+use sp_api::{CallApiAt, ProvideRuntimeApi};
 
 const LOG_TARGET: &str = "manual-seal";
 
@@ -87,7 +92,7 @@ where
 }
 
 /// Params required to start the instant sealing authorship task.
-pub struct ManualSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, TP, SC, CS, CIDP, P> {
+pub struct ManualSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, TP, SC, CS, CIDP, P, CB> {
 	/// Block import instance.
 	pub block_import: BI,
 
@@ -112,6 +117,9 @@ pub struct ManualSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, TP, SC, C
 
 	/// Something that can create the inherent data providers.
 	pub create_inherent_data_providers: CIDP,
+
+	/// Backend of the client, required to handle [`EngineCommand::Revert`].
+	pub backend: Option<Arc<CB>>,
 }
 
 /// Params required to start the manual sealing authorship task.
@@ -161,20 +169,25 @@ pub async fn run_manual_seal<B, BI, CB, E, C, TP, SC, CS, CIDP, P>(
 		select_chain,
 		consensus_data_provider,
 		create_inherent_data_providers,
-	}: ManualSealParams<B, BI, E, C, TP, SC, CS, CIDP, P>,
+		backend,
+	}: ManualSealParams<B, BI, E, C, TP, SC, CS, CIDP, P, CB>,
 ) where
 	B: BlockT + 'static,
 	BI: BlockImport<B, Error = sp_consensus::Error> + Send + Sync + 'static,
-	C: HeaderBackend<B> + Finalizer<B, CB> + ProvideRuntimeApi<B> + 'static,
+	C: HeaderBackend<B> + Finalizer<B, CB> + ProvideRuntimeApi<B> + CallApiAt<B> + 'static,
+	C::Api: BlockBuilderApi<B>,
 	CB: ClientBackend<B> + 'static,
 	E: Environment<B> + 'static,
 	E::Proposer: Proposer<B, Proof = P>,
 	CS: Stream<Item = EngineCommand<<B as BlockT>::Hash>> + Unpin + 'static,
 	SC: SelectChain<B> + 'static,
-	TP: TransactionPool<Block = B>,
+	TP: TransactionPool<Block = B, Hash = <B as BlockT>::Hash>,
 	CIDP: CreateInherentDataProviders<B, ()>,
 	P: codec::Encode + Send + Sync + 'static,
 {
+	let mut time_control = TimeControl::default();
+	let mut snapshots = Snapshots::<B>::default();
+
 	while let Some(command) = commands_stream.next().await {
 		match command {
 			EngineCommand::SealNewBlock { create_empty, finalize, parent_hash, sender } => {
@@ -190,9 +203,43 @@ pub async fn run_manual_seal<B, BI, CB, E, C, TP, SC, CS, CIDP, P>(
 					pool: pool.clone(),
 					client: client.clone(),
 					create_inherent_data_providers: &create_inherent_data_providers,
+					transactions: None,
+					time_control: Some(&mut time_control),
+				})
+				.await;
+			},
+			EngineCommand::SealNewBlockWith { transactions, finalize, parent_hash, sender } => {
+				seal_block(SealBlockParams {
+					sender,
+					parent_hash,
+					finalize,
+					create_empty: true,
+					env: &mut env,
+					select_chain: &select_chain,
+					block_import: &mut block_import,
+					consensus_data_provider: consensus_data_provider.as_deref(),
+					pool: pool.clone(),
+					client: client.clone(),
+					create_inherent_data_providers: &create_inherent_data_providers,
+					transactions: Some(transactions),
+					time_control: Some(&mut time_control),
 				})
 				.await;
 			},
+			EngineCommand::SetNextBlockTimestamp { timestamp, mut sender } => {
+				let result = time_control.set_next_block_timestamp(timestamp);
+				rpc::send_result(&mut sender, result)
+			},
+			EngineCommand::IncreaseTime { millis, mut sender } =>
+				rpc::send_result(&mut sender, Ok(time_control.increase_time(millis))),
+			EngineCommand::Snapshot { mut sender } => {
+				let id = snapshots.take(client.info().best_hash, &time_control);
+				rpc::send_result(&mut sender, Ok(id))
+			},
+			EngineCommand::Revert { id, mut sender } => {
+				let result = snapshots.revert(id, &*client, backend.as_deref(), &mut time_control);
+				rpc::send_result(&mut sender, result)
+			},
 			EngineCommand::FinalizeBlock { hash, sender, justification } => {
 				let justification = justification.map(|j| (MANUAL_SEAL_ENGINE_ID, j));
 				finalize_block(FinalizeBlockParams {
@@ -224,12 +271,13 @@ pub async fn run_instant_seal<B, BI, CB, E, C, TP, SC, CIDP, P>(
 ) where
 	B: BlockT + 'static,
 	BI: BlockImport<B, Error = sp_consensus::Error> + Send + Sync + 'static,
-	C: HeaderBackend<B> + Finalizer<B, CB> + ProvideRuntimeApi<B> + 'static,
+	C: HeaderBackend<B> + Finalizer<B, CB> + ProvideRuntimeApi<B> + CallApiAt<B> + 'static,
+	C::Api: BlockBuilderApi<B>,
 	CB: ClientBackend<B> + 'static,
 	E: Environment<B> + 'static,
 	E::Proposer: Proposer<B, Proof = P>,
 	SC: SelectChain<B> + 'static,
-	TP: TransactionPool<Block = B>,
+	TP: TransactionPool<Block = B, Hash = <B as BlockT>::Hash>,
 	CIDP: CreateInherentDataProviders<B, ()>,
 	P: codec::Encode + Send + Sync + 'static,
 {
@@ -251,6 +299,7 @@ pub async fn run_instant_seal<B, BI, CB, E, C, TP, SC, CIDP, P>(
 		select_chain,
 		consensus_data_provider,
 		create_inherent_data_providers,
+		backend: None,
 	})
 	.await
 }
@@ -274,12 +323,13 @@ pub async fn run_instant_seal_and_finalize<B, BI, CB, E, C, TP, SC, CIDP, P>(
 ) where
 	B: BlockT + 'static,
 	BI: BlockImport<B, Error = sp_consensus::Error> + Send + Sync + 'static,
-	C: HeaderBackend<B> + Finalizer<B, CB> + ProvideRuntimeApi<B> + 'static,
+	C: HeaderBackend<B> + Finalizer<B, CB> + ProvideRuntimeApi<B> + CallApiAt<B> + 'static,
+	C::Api: BlockBuilderApi<B>,
 	CB: ClientBackend<B> + 'static,
 	E: Environment<B> + 'static,
 	E::Proposer: Proposer<B, Proof = P>,
 	SC: SelectChain<B> + 'static,
-	TP: TransactionPool<Block = B>,
+	TP: TransactionPool<Block = B, Hash = <B as BlockT>::Hash>,
 	CIDP: CreateInherentDataProviders<B, ()>,
 	P: codec::Encode + Send + Sync + 'static,
 {
@@ -301,6 +351,7 @@ pub async fn run_instant_seal_and_finalize<B, BI, CB, E, C, TP, SC, CIDP, P>(
 		select_chain,
 		consensus_data_provider,
 		create_inherent_data_providers,
+		backend: None,
 	})
 	.await
 }
@@ -347,6 +398,7 @@ pub async fn run_delayed_finalize<B, CB, C, S>(
 mod tests {
 	use super::*;
 	use sc_basic_authorship::ProposerFactory;
+	use sc_client_api::BlockBackend;
 	use sc_consensus::ImportedAux;
 	use sc_transaction_pool::{BasicPool, FullChainApi, Options, RevalidationType};
 	use sc_transaction_pool_api::{MaintainedTransactionPool, TransactionPool, TransactionSource};
@@ -386,7 +438,7 @@ mod tests {
 			_parent: &B::Header,
 			params: &mut BlockImportParams<B>,
 			_inherents: &InherentData,
-			_proof: Self::Proof,
+			_proof: Option<Self::Proof>,
 		) -> Result<(), Error> {
 			params.post_digests.push(DigestItem::Other(vec![1]));
 			Ok(())
@@ -438,6 +490,7 @@ mod tests {
 			select_chain,
 			create_inherent_data_providers: |_, _| async { Ok(()) },
 			consensus_data_provider: None,
+			backend: None,
 		});
 		std::thread::spawn(|| {
 			let rt = tokio::runtime::Runtime::new().unwrap();
@@ -516,6 +569,7 @@ mod tests {
 			select_chain,
 			create_inherent_data_providers: |_, _| async { Ok(()) },
 			consensus_data_provider: None,
+			backend: None,
 		});
 		std::thread::spawn(|| {
 			let rt = tokio::runtime::Runtime::new().unwrap();
@@ -598,6 +652,7 @@ mod tests {
 			select_chain,
 			consensus_data_provider: None,
 			create_inherent_data_providers: |_, _| async { Ok(()) },
+			backend: None,
 		});
 		std::thread::spawn(|| {
 			let rt = tokio::runtime::Runtime::new().unwrap();
@@ -684,6 +739,7 @@ mod tests {
 			select_chain,
 			consensus_data_provider: None,
 			create_inherent_data_providers: |_, _| async { Ok(()) },
+			backend: None,
 		});
 		std::thread::spawn(|| {
 			let rt = tokio::runtime::Runtime::new().unwrap();
@@ -791,6 +847,7 @@ mod tests {
 			// use a provider that pushes some post digest data
 			consensus_data_provider: Some(Box::new(TestDigestProvider { _client: client.clone() })),
 			create_inherent_data_providers: |_, _| async { Ok(()) },
+			backend: None,
 		});
 		std::thread::spawn(|| {
 			let rt = tokio::runtime::Runtime::new().unwrap();
@@ -811,4 +868,148 @@ mod tests {
 		let header = client.header(created_block.hash).unwrap().unwrap();
 		assert_eq!(header.number, 1);
 	}
+
+	#[tokio::test]
+	async fn manual_seal_snapshot_and_revert() {
+		let builder = TestClientBuilder::new();
+		let backend = builder.backend();
+		let (client, select_chain) = builder.build_with_longest_chain();
+		let client = Arc::new(client);
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let genesis_hash = client.info().genesis_hash;
+		let pool_api = Arc::new(FullChainApi::new(client.clone(), None, &spawner.clone()));
+		let pool = Arc::new(BasicPool::with_revalidation_type(
+			Options::default(),
+			true.into(),
+			pool_api,
+			None,
+			RevalidationType::Full,
+			spawner.clone(),
+			0,
+			genesis_hash,
+			genesis_hash,
+		));
+		let env = ProposerFactory::new(spawner.clone(), client.clone(), pool.clone(), None, None);
+		let (mut sink, commands_stream) = futures::channel::mpsc::channel(1024);
+		let future = run_manual_seal(ManualSealParams {
+			block_import: client.clone(),
+			env,
+			client: client.clone(),
+			pool: pool.clone(),
+			commands_stream,
+			select_chain,
+			consensus_data_provider: None,
+			create_inherent_data_providers: |_, _| async {
+				Ok(sp_timestamp::InherentDataProvider::new(sp_timestamp::Timestamp::new(1_000)))
+			},
+			backend: Some(backend),
+		});
+		std::thread::spawn(|| {
+			let rt = tokio::runtime::Runtime::new().unwrap();
+			rt.block_on(future);
+		});
+
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::Snapshot { sender: Some(tx) }).await.unwrap();
+		let id = rx.await.unwrap().unwrap();
+
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::SetNextBlockTimestamp { timestamp: 10_000, sender: Some(tx) })
+			.await
+			.unwrap();
+		rx.await.unwrap().unwrap();
+
+		// the first block is finalized, reverting must still drop it.
+		for finalize in [true, false] {
+			let (tx, rx) = futures::channel::oneshot::channel();
+			sink.send(EngineCommand::SealNewBlock {
+				parent_hash: None,
+				sender: Some(tx),
+				create_empty: true,
+				finalize,
+			})
+			.await
+			.unwrap();
+			rx.await.unwrap().unwrap();
+		}
+		assert_eq!(client.info().best_number, 2);
+
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::Revert { id, sender: Some(tx) }).await.unwrap();
+		assert!(rx.await.unwrap().unwrap());
+		assert_eq!(client.info().best_hash, genesis_hash);
+		assert_eq!(client.info().finalized_hash, genesis_hash);
+
+		// the block time is reverted as well, earlier timestamps are accepted again.
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::SetNextBlockTimestamp { timestamp: 5_000, sender: Some(tx) })
+			.await
+			.unwrap();
+		rx.await.unwrap().unwrap();
+
+		// the snapshot is consumed by the revert.
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::Revert { id, sender: Some(tx) }).await.unwrap();
+		assert!(!rx.await.unwrap().unwrap());
+	}
+
+	#[tokio::test]
+	async fn manual_seal_with_transactions_keeps_order() {
+		let builder = TestClientBuilder::new();
+		let (client, select_chain) = builder.build_with_longest_chain();
+		let client = Arc::new(client);
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let genesis_hash = client.info().genesis_hash;
+		let pool_api = Arc::new(FullChainApi::new(client.clone(), None, &spawner.clone()));
+		let pool = Arc::new(BasicPool::with_revalidation_type(
+			Options::default(),
+			true.into(),
+			pool_api,
+			None,
+			RevalidationType::Full,
+			spawner.clone(),
+			0,
+			genesis_hash,
+			genesis_hash,
+		));
+		let env = ProposerFactory::new(spawner.clone(), client.clone(), pool.clone(), None, None);
+		let (mut sink, commands_stream) = futures::channel::mpsc::channel(1024);
+		let future = run_manual_seal(ManualSealParams {
+			block_import: client.clone(),
+			env,
+			client: client.clone(),
+			pool: pool.clone(),
+			commands_stream,
+			select_chain,
+			consensus_data_provider: None,
+			create_inherent_data_providers: |_, _| async { Ok(()) },
+			backend: None,
+		});
+		std::thread::spawn(|| {
+			let rt = tokio::runtime::Runtime::new().unwrap();
+			rt.block_on(future);
+		});
+
+		let alice = uxt(Alice, 0);
+		let bob = uxt(Bob, 0);
+		let charlie = uxt(Charlie, 0);
+		for xt in [&alice, &bob, &charlie] {
+			assert!(pool.submit_one(genesis_hash, SOURCE, xt.clone()).await.is_ok());
+		}
+
+		// include only bob and alice, in this exact order.
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::SealNewBlockWith {
+			transactions: vec![pool.hash_of(&bob), pool.hash_of(&alice)],
+			finalize: false,
+			parent_hash: None,
+			sender: Some(tx),
+		})
+		.await
+		.unwrap();
+		let created_block = rx.await.unwrap().unwrap();
+
+		let block = client.block(created_block.hash).unwrap().unwrap().block;
+		assert_eq!(block.extrinsics, vec![bob, alice]);
+	}
 }
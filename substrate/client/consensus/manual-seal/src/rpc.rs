@@ -31,6 +31,9 @@ use sc_consensus::ImportedAux;
 use serde::{Deserialize, Serialize};
 use sp_runtime::EncodedJustification;
 
+/// The maximum number of blocks `engine_createBlocks` creates at a time.
+pub const MAX_CREATE_BLOCKS: u32 = 1000;
+
 /// Sender passed to the authorship task to report errors or successes.
 pub type Sender<T> = Option<oneshot::Sender<std::result::Result<T, Error>>>;
 
@@ -53,6 +56,20 @@ pub enum EngineCommand<Hash> {
 		/// sender to report errors/success to the rpc.
 		sender: Sender<CreatedBlock<Hash>>,
 	},
+	/// Tells the engine to propose a new block with exactly the given transactions from the
+	/// transaction pool, in the given order.
+	///
+	/// if finalize == true, the block will be instantly finalized.
+	SealNewBlockWith {
+		/// hashes of the ready pool transactions to include.
+		transactions: Vec<Hash>,
+		/// instantly finalize this block?
+		finalize: bool,
+		/// specify the parent hash of the about-to-created block
+		parent_hash: Option<Hash>,
+		/// sender to report errors/success to the rpc.
+		sender: Sender<CreatedBlock<Hash>>,
+	},
 	/// Tells the engine to finalize the block with the supplied hash
 	FinalizeBlock {
 		/// hash of the block
@@ -62,6 +79,36 @@ pub enum EngineCommand<Hash> {
 		/// finalization justification
 		justification: Option<EncodedJustification>,
 	},
+	/// Tells the engine to use the given timestamp, in milliseconds, for the next block.
+	///
+	/// Later blocks advance from this timestamp.
+	SetNextBlockTimestamp {
+		/// unix timestamp in milliseconds.
+		timestamp: u64,
+		/// sender to report errors/success to the rpc.
+		sender: Sender<()>,
+	},
+	/// Tells the engine to move the timestamps of all later blocks into the future.
+	IncreaseTime {
+		/// milliseconds to move the timestamps by.
+		millis: u64,
+		/// sender to report the total milliseconds the timestamps are moved by to the rpc.
+		sender: Sender<u64>,
+	},
+	/// Tells the engine to remember the current best block.
+	Snapshot {
+		/// sender to report the id of the snapshot to the rpc.
+		sender: Sender<u64>,
+	},
+	/// Tells the engine to revert the chain to the best block of a snapshot.
+	///
+	/// The snapshot and all later ones are discarded.
+	Revert {
+		/// id of the snapshot.
+		id: u64,
+		/// sender to report whether the snapshot exists to the rpc.
+		sender: Sender<bool>,
+	},
 }
 
 /// RPC trait that provides methods for interacting with the manual-seal authorship task over rpc.
@@ -76,6 +123,27 @@ pub trait ManualSealApi<Hash> {
 		parent_hash: Option<Hash>,
 	) -> RpcResult<CreatedBlock<Hash>>;
 
+	/// Instructs the manual-seal authorship task to create `count` new blocks on top of each
+	/// other, at most [`MAX_CREATE_BLOCKS`] at a time
+	#[method(name = "engine_createBlocks")]
+	async fn create_blocks(
+		&self,
+		count: u32,
+		create_empty: bool,
+		finalize: bool,
+		parent_hash: Option<Hash>,
+	) -> RpcResult<Vec<CreatedBlock<Hash>>>;
+
+	/// Instructs the manual-seal authorship task to create a new block with exactly the given
+	/// pool transactions, in order
+	#[method(name = "engine_createBlockWithTransactions")]
+	async fn create_block_with_transactions(
+		&self,
+		transactions: Vec<Hash>,
+		finalize: bool,
+		parent_hash: Option<Hash>,
+	) -> RpcResult<CreatedBlock<Hash>>;
+
 	/// Instructs the manual-seal authorship task to finalize a block
 	#[method(name = "engine_finalizeBlock")]
 	async fn finalize_block(
@@ -83,6 +151,26 @@ pub trait ManualSealApi<Hash> {
 		hash: Hash,
 		justification: Option<EncodedJustification>,
 	) -> RpcResult<bool>;
+
+	/// Instructs the manual-seal authorship task to use the given timestamp, in milliseconds,
+	/// for the next block
+	#[method(name = "engine_setNextBlockTimestamp")]
+	async fn set_next_block_timestamp(&self, timestamp: u64) -> RpcResult<bool>;
+
+	/// Instructs the manual-seal authorship task to move the timestamps of later blocks by the
+	/// given milliseconds. Returns the total milliseconds they are moved by
+	#[method(name = "engine_increaseTime")]
+	async fn increase_time(&self, millis: u64) -> RpcResult<u64>;
+
+	/// Instructs the manual-seal authorship task to remember the current best block. Returns the
+	/// id to revert to it
+	#[method(name = "engine_snapshot")]
+	async fn snapshot(&self) -> RpcResult<u64>;
+
+	/// Instructs the manual-seal authorship task to revert the chain to a snapshot. Returns
+	/// false if the snapshot does not exist
+	#[method(name = "engine_revert")]
+	async fn revert(&self, id: u64) -> RpcResult<bool>;
 }
 
 /// A struct that implements the [`ManualSealApiServer`].
@@ -109,7 +197,7 @@ impl<Hash> ManualSeal<Hash> {
 }
 
 #[async_trait]
-impl<Hash: Send + 'static> ManualSealApiServer<Hash> for ManualSeal<Hash> {
+impl<Hash: Clone + Send + 'static> ManualSealApiServer<Hash> for ManualSeal<Hash> {
 	async fn create_block(
 		&self,
 		create_empty: bool,
@@ -135,6 +223,46 @@ impl<Hash: Send + 'static> ManualSealApiServer<Hash> for ManualSeal<Hash> {
 		}
 	}
 
+	async fn create_blocks(
+		&self,
+		count: u32,
+		create_empty: bool,
+		finalize: bool,
+		mut parent_hash: Option<Hash>,
+	) -> RpcResult<Vec<CreatedBlock<Hash>>> {
+		if count > MAX_CREATE_BLOCKS {
+			return Err(Error::StringError(format!(
+				"At most {} blocks can be created at a time",
+				MAX_CREATE_BLOCKS
+			))
+			.into())
+		}
+
+		let mut created = Vec::new();
+		for _ in 0..count {
+			let block = self.create_block(create_empty, finalize, parent_hash.take()).await?;
+			// build the next block on top of this one, even if it is not the best block.
+			parent_hash = Some(block.hash.clone());
+			created.push(block);
+		}
+		Ok(created)
+	}
+
+	async fn create_block_with_transactions(
+		&self,
+		transactions: Vec<Hash>,
+		finalize: bool,
+		parent_hash: Option<Hash>,
+	) -> RpcResult<CreatedBlock<Hash>> {
+		self.send_command(|sender| EngineCommand::SealNewBlockWith {
+			transactions,
+			finalize,
+			parent_hash,
+			sender,
+		})
+		.await
+	}
+
 	async fn finalize_block(
 		&self,
 		hash: Hash,
@@ -146,6 +274,42 @@ impl<Hash: Send + 'static> ManualSealApiServer<Hash> for ManualSeal<Hash> {
 		sink.send(command).await?;
 		receiver.await.map(|_| true).map_err(|e| JsonRpseeError::to_call_error(e))
 	}
+
+	async fn set_next_block_timestamp(&self, timestamp: u64) -> RpcResult<bool> {
+		self.send_command(|sender| EngineCommand::SetNextBlockTimestamp { timestamp, sender })
+			.await
+			.map(|_| true)
+	}
+
+	async fn increase_time(&self, millis: u64) -> RpcResult<u64> {
+		self.send_command(|sender| EngineCommand::IncreaseTime { millis, sender }).await
+	}
+
+	async fn snapshot(&self) -> RpcResult<u64> {
+		self.send_command(|sender| EngineCommand::Snapshot { sender }).await
+	}
+
+	async fn revert(&self, id: u64) -> RpcResult<bool> {
+		self.send_command(|sender| EngineCommand::Revert { id, sender }).await
+	}
+}
+
+impl<Hash> ManualSeal<Hash> {
+	/// Send the command built by `command` to the authorship task and wait for its result.
+	async fn send_command<T>(
+		&self,
+		command: impl FnOnce(Sender<T>) -> EngineCommand<Hash>,
+	) -> RpcResult<T> {
+		let mut sink = self.import_block_channel.clone();
+		let (sender, receiver) = oneshot::channel();
+		sink.send(command(Some(sender))).await?;
+
+		match receiver.await {
+			Ok(Ok(rx)) => Ok(rx),
+			Ok(Err(e)) => Err(e.into()),
+			Err(e) => Err(JsonRpseeError::to_call_error(e)),
+		}
+	}
 }
 
 /// report any errors or successes encountered by the authorship task back
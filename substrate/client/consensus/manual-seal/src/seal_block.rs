@@ -18,15 +18,20 @@
 
 //! Block sealing utilities
 
-use crate::{rpc, ConsensusDataProvider, CreatedBlock, Error};
+use crate::{rpc, ConsensusDataProvider, CreatedBlock, Error, TimeControl};
 use futures::prelude::*;
+use sc_block_builder::{BlockBuilderBuilder, BuiltBlock};
 use sc_consensus::{BlockImport, BlockImportParams, ForkChoiceStrategy, ImportResult, StateAction};
-use sc_transaction_pool_api::TransactionPool;
-use sp_api::ProvideRuntimeApi;
+use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
+use sp_api::{CallApiAt, ProvideRuntimeApi};
+use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::HeaderBackend;
 use sp_consensus::{self, BlockOrigin, Environment, Proposer, SelectChain};
-use sp_inherents::{CreateInherentDataProviders, InherentDataProvider};
-use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use sp_inherents::{CreateInherentDataProviders, InherentData, InherentDataProvider};
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT},
+	Digest,
+};
 use std::{sync::Arc, time::Duration};
 
 /// max duration for creating a proposal in secs
@@ -57,6 +62,10 @@ pub struct SealBlockParams<'a, B: BlockT, BI, SC, C: ProvideRuntimeApi<B>, E, TP
 	pub block_import: &'a mut BI,
 	/// Something that can create the inherent data providers.
 	pub create_inherent_data_providers: &'a CIDP,
+	/// The pool transactions to include, in this order, instead of letting the proposer pick.
+	pub transactions: Option<Vec<<B as BlockT>::Hash>>,
+	/// Adjustments of the timestamp inherent.
+	pub time_control: Option<&'a mut TimeControl>,
 }
 
 /// seals a new block with the given params
@@ -73,20 +82,23 @@ pub async fn seal_block<B, BI, SC, C, E, TP, CIDP, P>(
 		create_inherent_data_providers,
 		consensus_data_provider: digest_provider,
 		mut sender,
+		transactions,
+		time_control,
 	}: SealBlockParams<'_, B, BI, SC, C, E, TP, CIDP, P>,
 ) where
 	B: BlockT,
 	BI: BlockImport<B, Error = sp_consensus::Error> + Send + Sync + 'static,
-	C: HeaderBackend<B> + ProvideRuntimeApi<B>,
+	C: HeaderBackend<B> + ProvideRuntimeApi<B> + CallApiAt<B>,
+	C::Api: BlockBuilderApi<B>,
 	E: Environment<B>,
 	E::Proposer: Proposer<B, Proof = P>,
-	TP: TransactionPool<Block = B>,
+	TP: TransactionPool<Block = B, Hash = <B as BlockT>::Hash>,
 	SC: SelectChain<B>,
 	CIDP: CreateInherentDataProviders<B, ()>,
 	P: codec::Encode + Send + Sync + 'static,
 {
 	let future = async {
-		if pool.status().ready == 0 && !create_empty {
+		if transactions.is_none() && pool.status().ready == 0 && !create_empty {
 			return Err(Error::EmptyTransactionPool)
 		}
 
@@ -104,9 +116,12 @@ pub async fn seal_block<B, BI, SC, C, E, TP, CIDP, P>(
 			.await
 			.map_err(|e| Error::Other(e))?;
 
-		let inherent_data = inherent_data_providers.create_inherent_data().await?;
+		let mut inherent_data = inherent_data_providers.create_inherent_data().await?;
+		let applied_timestamp = match &time_control {
+			Some(time_control) => time_control.apply(&mut inherent_data)?,
+			None => None,
+		};
 
-		let proposer = env.init(&parent).map_err(|err| Error::StringError(err.to_string())).await?;
 		let inherents_len = inherent_data.len();
 
 		let digest = if let Some(digest_provider) = digest_provider {
@@ -115,30 +130,49 @@ pub async fn seal_block<B, BI, SC, C, E, TP, CIDP, P>(
 			Default::default()
 		};
 
-		let proposal = proposer
-			.propose(
-				inherent_data.clone(),
-				digest,
-				Duration::from_secs(MAX_PROPOSAL_DURATION),
-				None,
-			)
-			.map_err(|err| Error::StringError(err.to_string()))
-			.await?;
-
-		if proposal.block.extrinsics().len() == inherents_len && !create_empty {
-			return Err(Error::EmptyTransactionPool)
-		}
+		let (block, storage_changes, proof) = match transactions {
+			Some(transactions) => {
+				let (block, storage_changes, _) = build_with_transactions(
+					&*client,
+					&*pool,
+					&parent,
+					inherent_data.clone(),
+					digest,
+					transactions,
+				)?
+				.into_inner();
+				// The block is not built by the proposer, hence there is no proof.
+				(block, storage_changes, None)
+			},
+			None => {
+				let proposer =
+					env.init(&parent).map_err(|err| Error::StringError(err.to_string())).await?;
+				let proposal = proposer
+					.propose(
+						inherent_data.clone(),
+						digest,
+						Duration::from_secs(MAX_PROPOSAL_DURATION),
+						None,
+					)
+					.map_err(|err| Error::StringError(err.to_string()))
+					.await?;
+
+				if proposal.block.extrinsics().len() == inherents_len && !create_empty {
+					return Err(Error::EmptyTransactionPool)
+				}
+
+				(proposal.block, proposal.storage_changes, Some(proposal.proof))
+			},
+		};
 
-		let (header, body) = proposal.block.deconstruct();
-		let proof = proposal.proof;
-		let proof_size = proof.encoded_size();
+		let (header, body) = block.deconstruct();
+		let proof_size = proof.as_ref().map_or(0, |proof| proof.encoded_size());
 		let mut params = BlockImportParams::new(BlockOrigin::Own, header.clone());
 		params.body = Some(body);
 		params.finalized = finalize;
 		params.fork_choice = Some(ForkChoiceStrategy::LongestChain);
-		params.state_action = StateAction::ApplyChanges(sc_consensus::StorageChanges::Changes(
-			proposal.storage_changes,
-		));
+		params.state_action =
+			StateAction::ApplyChanges(sc_consensus::StorageChanges::Changes(storage_changes));
 
 		if let Some(digest_provider) = digest_provider {
 			digest_provider.append_block_import(&parent, &mut params, &inherent_data, proof)?;
@@ -150,14 +184,56 @@ pub async fn seal_block<B, BI, SC, C, E, TP, CIDP, P>(
 		post_header.digest_mut().logs.extend(params.post_digests.iter().cloned());
 
 		match block_import.import_block(params).await? {
-			ImportResult::Imported(aux) => Ok(CreatedBlock {
-				hash: <B as BlockT>::Header::hash(&post_header),
-				aux,
-				proof_size,
-			}),
+			ImportResult::Imported(aux) => {
+				if let (Some(time_control), Some(applied)) = (time_control, applied_timestamp) {
+					time_control.sealed(applied);
+				}
+
+				Ok(CreatedBlock {
+					hash: <B as BlockT>::Header::hash(&post_header),
+					aux,
+					proof_size,
+				})
+			},
 			other => Err(other.into()),
 		}
 	};
 
 	rpc::send_result(&mut sender, future.await)
 }
+
+/// Build a block on top of `parent` with the inherents and then exactly the given ready pool
+/// `transactions`, in order.
+fn build_with_transactions<'a, B, C, TP>(
+	client: &'a C,
+	pool: &TP,
+	parent: &B::Header,
+	inherent_data: InherentData,
+	digest: Digest,
+	transactions: Vec<<B as BlockT>::Hash>,
+) -> Result<BuiltBlock<B>, Error>
+where
+	B: BlockT,
+	C: HeaderBackend<B> + ProvideRuntimeApi<B> + CallApiAt<B>,
+	C::Api: BlockBuilderApi<B>,
+	TP: TransactionPool<Block = B, Hash = <B as BlockT>::Hash>,
+{
+	let mut block_builder = BlockBuilderBuilder::new(client)
+		.on_parent_block(parent.hash())
+		.with_parent_block_number(*parent.number())
+		.with_inherent_digests(digest)
+		.build()?;
+
+	for inherent in block_builder.create_inherents(inherent_data)? {
+		block_builder.push(inherent)?;
+	}
+
+	for hash in transactions {
+		let transaction = pool
+			.ready_transaction(&hash)
+			.ok_or_else(|| Error::TransactionNotReady(format!("{}", hash)))?;
+		block_builder.push(transaction.data().clone())?;
+	}
+
+	Ok(block_builder.build()?)
+}
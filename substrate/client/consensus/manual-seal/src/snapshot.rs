@@ -0,0 +1,97 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Snapshots of the chain head, to revert the chain to later on.
+
+use crate::{Error, TimeControl, LOG_TARGET};
+use sc_client_api::backend::Backend as ClientBackend;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, CheckedSub};
+use std::collections::BTreeMap;
+
+/// The chain heads remembered through [`EngineCommand::Snapshot`], together with the block time
+/// at that point.
+///
+/// [`EngineCommand::Snapshot`]: crate::EngineCommand::Snapshot
+pub struct Snapshots<B: BlockT> {
+	next_id: u64,
+	heads: BTreeMap<u64, (B::Hash, TimeControl)>,
+}
+
+impl<B: BlockT> Default for Snapshots<B> {
+	fn default() -> Self {
+		Self { next_id: 0, heads: BTreeMap::new() }
+	}
+}
+
+impl<B: BlockT> Snapshots<B> {
+	/// Remember `head` and the block `time`, returning the id to revert to them.
+	pub fn take(&mut self, head: B::Hash, time: &TimeControl) -> u64 {
+		let id = self.next_id;
+		self.next_id += 1;
+		self.heads.insert(id, (head, time.clone()));
+		id
+	}
+
+	/// Revert the chain to the head remembered by snapshot `id`, removing all blocks built on top
+	/// of it, finalized or not. The block `time` is restored to what it was when the snapshot was
+	/// taken.
+	///
+	/// The snapshot and all snapshots taken after it are discarded. Returns `false` if there is
+	/// no such snapshot.
+	pub fn revert<C, CB>(
+		&mut self,
+		id: u64,
+		client: &C,
+		backend: Option<&CB>,
+		time: &mut TimeControl,
+	) -> Result<bool, Error>
+	where
+		C: HeaderBackend<B>,
+		CB: ClientBackend<B>,
+	{
+		let Some(hash) = self.heads.get(&id).map(|(hash, _)| *hash) else { return Ok(false) };
+		let backend = backend.ok_or_else(|| {
+			Error::StringError("Reverting requires the manual seal engine to have a backend".into())
+		})?;
+
+		let number =
+			client.number(hash)?.ok_or_else(|| Error::BlockNotFound(format!("{}", hash)))?;
+		if client.hash(number)? != Some(hash) {
+			return Err(Error::StringError(format!(
+				"Snapshot block {} is not on the best chain",
+				hash
+			)))
+		}
+
+		let best_number = client.info().best_number;
+		let to_revert = best_number.checked_sub(&number).ok_or_else(|| {
+			Error::StringError(format!(
+				"Snapshot block #{} is above the best block #{}",
+				number, best_number
+			))
+		})?;
+		let (reverted, _) = backend.revert(to_revert, true)?;
+		if let Some((_, snapshot_time)) = self.heads.split_off(&id).remove(&id) {
+			*time = snapshot_time;
+		}
+		log::info!(target: LOG_TARGET, "⏪ Reverted {} blocks to {}", reverted, hash);
+
+		Ok(true)
+	}
+}
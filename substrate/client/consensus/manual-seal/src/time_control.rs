@@ -0,0 +1,183 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Control over the timestamps of the blocks created by the manual seal engine.
+
+use crate::{Error, LOG_TARGET};
+use sp_inherents::InherentData;
+use sp_timestamp::{InherentType, INHERENT_IDENTIFIER};
+
+/// Adjusts the timestamp inherent of the blocks created by the manual seal engine.
+///
+/// The timestamp is still provided by the inherent data providers of the engine, this only moves
+/// it, as requested through [`EngineCommand::SetNextBlockTimestamp`] and
+/// [`EngineCommand::IncreaseTime`].
+///
+/// [`EngineCommand::SetNextBlockTimestamp`]: crate::EngineCommand::SetNextBlockTimestamp
+/// [`EngineCommand::IncreaseTime`]: crate::EngineCommand::IncreaseTime
+#[derive(Debug, Default, Clone)]
+pub struct TimeControl {
+	// milliseconds added to the provided timestamp
+	offset: u64,
+	// timestamp to use for the next block instead of the provided one
+	next_timestamp: Option<u64>,
+	// timestamp of the last sealed block
+	last_timestamp: Option<u64>,
+}
+
+/// The timestamp [`TimeControl::apply`] put into the inherent data of a block.
+///
+/// It only takes effect once the block is sealed, see [`TimeControl::sealed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppliedTimestamp {
+	// timestamp provided by the inherent data providers
+	provided: u64,
+	// timestamp used instead
+	timestamp: u64,
+}
+
+impl TimeControl {
+	/// Use `timestamp` for the next block. Later blocks advance from there.
+	///
+	/// Fails if `timestamp` is not after the timestamp of the last sealed block.
+	pub fn set_next_block_timestamp(&mut self, timestamp: u64) -> Result<(), Error> {
+		match self.last_timestamp {
+			Some(last) if timestamp <= last => Err(Error::StringError(format!(
+				"Timestamp {} is not after the timestamp of the last block {}",
+				timestamp, last
+			))),
+			_ => {
+				self.next_timestamp = Some(timestamp);
+				Ok(())
+			},
+		}
+	}
+
+	/// Move the timestamps of all later blocks `millis` into the future.
+	///
+	/// Returns the total number of milliseconds the timestamps are moved by.
+	pub fn increase_time(&mut self, millis: u64) -> u64 {
+		self.offset = self.offset.saturating_add(millis);
+		self.offset
+	}
+
+	/// Adjust the timestamp inherent in `inherent_data`, if there is one.
+	///
+	/// Nothing changes until the block is sealed and the returned timestamp is passed to
+	/// [`Self::sealed`], so a failed block doesn't consume the requested timestamp.
+	pub fn apply(
+		&self,
+		inherent_data: &mut InherentData,
+	) -> Result<Option<AppliedTimestamp>, Error> {
+		let Some(provided) = inherent_data.get_data::<InherentType>(&INHERENT_IDENTIFIER)? else {
+			if self.next_timestamp.is_some() || self.offset != 0 {
+				log::warn!(
+					target: LOG_TARGET,
+					"No timestamp inherent provided, the requested block time is ignored",
+				);
+			}
+			return Ok(None)
+		};
+
+		let provided = provided.as_millis();
+		let timestamp = self.next_timestamp.unwrap_or_else(|| provided.saturating_add(self.offset));
+		inherent_data.replace_data(INHERENT_IDENTIFIER, &InherentType::new(timestamp));
+
+		Ok(Some(AppliedTimestamp { provided, timestamp }))
+	}
+
+	/// The block with the `applied` timestamp was sealed, later blocks advance from there.
+	pub fn sealed(&mut self, applied: AppliedTimestamp) {
+		self.offset = applied.timestamp.saturating_sub(applied.provided);
+		self.next_timestamp = None;
+		self.last_timestamp = Some(applied.timestamp);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn apply(time: &TimeControl, provided: u64) -> (u64, AppliedTimestamp) {
+		let mut inherent_data = InherentData::new();
+		inherent_data
+			.put_data(INHERENT_IDENTIFIER, &InherentType::new(provided))
+			.unwrap();
+		let applied = time.apply(&mut inherent_data).unwrap().unwrap();
+		let timestamp = inherent_data
+			.get_data::<InherentType>(&INHERENT_IDENTIFIER)
+			.unwrap()
+			.unwrap()
+			.as_millis();
+		(timestamp, applied)
+	}
+
+	fn seal(time: &mut TimeControl, provided: u64) -> u64 {
+		let (timestamp, applied) = apply(time, provided);
+		time.sealed(applied);
+		timestamp
+	}
+
+	#[test]
+	fn timestamps_are_adjusted() {
+		let mut time = TimeControl::default();
+		assert_eq!(seal(&mut time, 1_000), 1_000);
+
+		assert_eq!(time.increase_time(500), 500);
+		assert_eq!(time.increase_time(500), 1_000);
+		assert_eq!(seal(&mut time, 2_000), 3_000);
+
+		// the next block uses the requested timestamp, later ones advance from it.
+		time.set_next_block_timestamp(10_000).unwrap();
+		assert_eq!(seal(&mut time, 3_000), 10_000);
+		assert_eq!(seal(&mut time, 4_000), 11_000);
+	}
+
+	#[test]
+	fn requested_timestamp_is_kept_until_sealed() {
+		let mut time = TimeControl::default();
+		time.set_next_block_timestamp(10_000).unwrap();
+
+		// the block failed to be sealed.
+		assert_eq!(apply(&time, 1_000).0, 10_000);
+		assert_eq!(seal(&mut time, 2_000), 10_000);
+		assert_eq!(seal(&mut time, 3_000), 11_000);
+	}
+
+	#[test]
+	fn past_timestamps_are_rejected() {
+		let mut time = TimeControl::default();
+		assert_eq!(seal(&mut time, 5_000), 5_000);
+
+		assert!(time.set_next_block_timestamp(4_000).is_err());
+		assert!(time.set_next_block_timestamp(5_000).is_err());
+		assert_eq!(seal(&mut time, 6_000), 6_000);
+
+		time.set_next_block_timestamp(6_001).unwrap();
+		assert_eq!(seal(&mut time, 7_000), 6_001);
+	}
+
+	#[test]
+	fn missing_timestamp_is_ignored() {
+		let mut time = TimeControl::default();
+		time.set_next_block_timestamp(10_000).unwrap();
+		let mut inherent_data = InherentData::new();
+		time.apply(&mut inherent_data).unwrap();
+		assert!(inherent_data.get_data::<InherentType>(&INHERENT_IDENTIFIER).unwrap().is_none());
+	}
+}
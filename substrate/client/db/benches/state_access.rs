@@ -119,6 +119,7 @@ fn create_backend(config: BenchmarkConfig, temp_dir: &TempDir) -> Backend<Block>
 
 	let settings = DatabaseSettings {
 		trie_cache_maximum_size,
+		trie_cache_snapshot: None,
 		state_pruning: Some(PruningMode::ArchiveAll),
 		source: DatabaseSource::ParityDb { path },
 		blocks_pruning: BlocksPruning::KeepAll,
@@ -37,7 +37,9 @@ use sp_state_machine::{
 };
 use sp_trie::{
 	cache::{CacheSize, SharedTrieCache},
-	prefixed_key, MemoryDB, MerkleValue,
+	prefixed_key,
+	recorder::ProofSizeAttribution,
+	MemoryDB, MerkleValue,
 };
 use std::{
 	cell::{Cell, RefCell},
@@ -86,6 +88,8 @@ pub struct BenchmarkingState<B: BlockT> {
 	whitelist: RefCell<Vec<TrackedStorageKey>>,
 	proof_recorder: Option<sp_trie::recorder::Recorder<HashingFor<B>>>,
 	proof_recorder_root: Cell<B::Hash>,
+	/// The proof size attribution at the time the proof size was last queried.
+	proof_size_attribution: RefCell<Option<ProofSizeAttribution>>,
 	shared_trie_cache: SharedTrieCache<HashingFor<B>>,
 }
 
@@ -156,6 +160,7 @@ impl<B: BlockT> BenchmarkingState<B> {
 			whitelist: Default::default(),
 			proof_recorder: record_proof.then(Default::default),
 			proof_recorder_root: Cell::new(root),
+			proof_size_attribution: Default::default(),
 			// Enable the cache, but do not sync anything to the shared state.
 			shared_trie_cache: SharedTrieCache::new(CacheSize::new(0)),
 		};
@@ -182,6 +187,24 @@ impl<B: BlockT> BenchmarkingState<B> {
 		Ok(state)
 	}
 
+	/// Attribute the recorded proof to the storage keys that were accessed.
+	///
+	/// Only has an effect if the proof is recorded.
+	pub fn enable_proof_size_attribution(&self) {
+		if let Some(recorder) = &self.proof_recorder {
+			recorder.enable_proof_size_attribution();
+		}
+	}
+
+	/// Take the proof size attribution of the proof as it was when the proof size was last
+	/// queried.
+	///
+	/// The benchmarking runtime queries the proof size right after executing the benchmarked
+	/// call, so this attributes the proof of the last benchmarked call.
+	pub fn take_proof_size_attribution(&self) -> Option<ProofSizeAttribution> {
+		self.proof_size_attribution.take()
+	}
+
 	fn reopen(&self) -> Result<(), String> {
 		*self.state.borrow_mut() = None;
 		let db = match self.db.take() {
@@ -626,6 +649,10 @@ impl<B: BlockT> StateBackend<HashingFor<B>> for BenchmarkingState<B> {
 		self.proof_recorder.as_ref().map(|recorder| {
 			let proof_size = recorder.estimate_encoded_size() as u32;
 
+			if let Some(attribution) = recorder.proof_size_attribution() {
+				*self.proof_size_attribution.borrow_mut() = Some(attribution);
+			}
+
 			let proof = recorder.to_storage_proof();
 
 			let proof_recorder_root = self.proof_recorder_root.get();
@@ -688,6 +715,36 @@ mod test {
 		assert_eq!(bench_state.read_write_count(), (1, 0, 0, 0));
 	}
 
+	#[test]
+	fn proof_size_is_attributed_to_keys() {
+		let storage = sp_runtime::Storage {
+			top: vec![(b"foo".to_vec(), vec![1; 64]), (b"bar".to_vec(), vec![2; 64])]
+				.into_iter()
+				.collect(),
+			..sp_runtime::Storage::default()
+		};
+		let bench_state =
+			BenchmarkingState::<crate::tests::Block>::new(storage, None, true, false).unwrap();
+		bench_state.enable_proof_size_attribution();
+
+		assert!(bench_state.take_proof_size_attribution().is_none());
+		bench_state.storage(b"foo").unwrap();
+		bench_state.proof_size().unwrap();
+
+		let attribution = bench_state.take_proof_size_attribution().unwrap();
+		assert_eq!(attribution.keys.len(), 1);
+		assert!(attribution.keys[&b"foo"[..]] > 64);
+		assert!(bench_state.take_proof_size_attribution().is_none());
+
+		// The attribution stays enabled after the recorder has been reset.
+		bench_state.wipe().unwrap();
+		bench_state.storage(b"bar").unwrap();
+		bench_state.proof_size().unwrap();
+
+		let attribution = bench_state.take_proof_size_attribution().unwrap();
+		assert_eq!(attribution.keys.keys().collect::<Vec<_>>(), vec![&b"bar".to_vec()]);
+	}
+
 	#[test]
 	fn read_to_main_and_child_tries() {
 		let bench_state =
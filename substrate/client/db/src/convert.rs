@@ -0,0 +1,528 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Offline conversion of a database to a different backend.
+//!
+//! Copying the columns is a lot faster than syncing an archive node again. Only RocksDB databases
+//! can be converted into ParityDB databases.
+//!
+//! Converting a ParityDB database back into RocksDB is out of scope: ParityDB does not store the
+//! keys of its hash-indexed columns, so the trie nodes would have to be found again by walking the
+//! state of every block from its root. Syncing a new RocksDB node is the way to go there.
+
+use std::{
+	io,
+	time::{Duration, Instant},
+};
+
+use kvdb::KeyValueDB;
+use log::info;
+use sc_client_api::backend::Backend as _;
+use sp_blockchain::{Error as ClientError, HeaderBackend, Result as ClientResult};
+use sp_core::{
+	hexdisplay::HexDisplay,
+	storage::{well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX, ChildInfo},
+	Hasher,
+};
+use sp_database::Transaction;
+use sp_runtime::traits::{Block as BlockT, HashingFor, Header as HeaderT};
+use sp_state_machine::{backend::Backend as StateBackend, IterArgs};
+
+use crate::{
+	columns,
+	utils::{self, DatabaseType, NUM_COLUMNS},
+	Backend, BlocksPruning, Database, DatabaseSettings, DatabaseSource, DbHash, DB_HASH_LEN,
+};
+
+/// Size of the changes that are written to the destination in one transaction.
+const BATCH_SIZE: usize = 64 * 1024 * 1024;
+
+/// How often the progress of a column is logged.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// What has been copied from a single column.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnStats {
+	/// The column.
+	pub column: u32,
+	/// Number of entries.
+	pub entries: u64,
+	/// Total size of the keys and values.
+	pub bytes: u64,
+}
+
+/// Copy all columns of the database at `from` into a new database at `to`.
+///
+/// Only archive databases are supported. With state pruning, RocksDB tracks the trie nodes to
+/// delete in a way that can not be translated into the reference counts of ParityDB. For the same
+/// reason, all blocks have to be canonicalized: the journal of a non-canonical block lists the
+/// nodes it inserted under their RocksDB keys.
+pub fn convert_database<Block: BlockT>(
+	from: &DatabaseSource,
+	to: &DatabaseSource,
+) -> ClientResult<Vec<ColumnStats>> {
+	let (source_path, cache_size, destination_path) = match (from, to) {
+		(
+			DatabaseSource::RocksDb { path: source_path, cache_size },
+			DatabaseSource::ParityDb { path: destination_path },
+		) => (source_path, *cache_size, destination_path),
+		(DatabaseSource::ParityDb { .. }, _) =>
+			return Err(ClientError::Backend(
+				"ParityDB databases can not be converted, the keys of their columns are not stored"
+					.into(),
+			)),
+		_ =>
+			return Err(ClientError::Backend(format!(
+				"Converting a `{}` database into a `{}` database is not supported",
+				from, to
+			))),
+	};
+
+	if destination_path.read_dir().map_or(false, |mut dir| dir.next().is_some()) {
+		return Err(ClientError::Backend(format!(
+			"The destination {} is not empty",
+			destination_path.display()
+		)))
+	}
+
+	let source =
+		utils::open_raw_kvdb_rocksdb_read_only(source_path, DatabaseType::Full, cache_size)?;
+	match sc_state_db::fetch_stored_pruning_mode(&SourceMeta(&source)) {
+		Ok(Some(mode)) if !mode.is_archive() =>
+			return Err(ClientError::Backend(format!(
+				"Only archive databases can be converted, the state of this one is pruned \
+				 ({mode:?}). Sync the chain again instead, warp sync makes that cheap.",
+			))),
+		Ok(_) => (),
+		Err(e) => return Err(ClientError::Backend(format!("{e:?}"))),
+	}
+	match sc_state_db::has_non_canonical_blocks::<Block::Hash, Vec<u8>, _>(&SourceMeta(&source)) {
+		Ok(false) => (),
+		Ok(true) =>
+			return Err(ClientError::Backend(
+				"The database has blocks that are not canonicalized yet. Finalize them, or remove \
+				 them with the `revert` command, before converting the database."
+					.into(),
+			)),
+		Err(e) => return Err(ClientError::Backend(format!("{e:?}"))),
+	}
+
+	let destination = utils::open_database::<Block>(to, DatabaseType::Full, true)?;
+
+	info!("Converting {} into {}", source_path.display(), destination_path.display());
+	(0..NUM_COLUMNS)
+		.map(|column| copy_column(&source, &*destination, column))
+		.collect()
+}
+
+/// Check that the database at `to` holds the same chain as the one at `from`.
+///
+/// Every entry of the source is looked up in the destination and must have the same value, trie
+/// nodes and indexed transactions must hash to their key. The number of entries of every column
+/// must match `copied`, the result of [`convert_database`]. Then the chain info and the headers of
+/// the genesis, finalized and best blocks are compared, and the whole state of the finalized
+/// block, child tries included, is read from both databases.
+pub fn verify_conversion<Block: BlockT>(
+	from: &DatabaseSource,
+	to: &DatabaseSource,
+	copied: &[ColumnStats],
+) -> ClientResult<()> {
+	let DatabaseSource::RocksDb { path: source_path, cache_size } = from else {
+		return Err(ClientError::Backend(format!("Can not verify a `{from}` database")))
+	};
+	{
+		let source =
+			utils::open_raw_kvdb_rocksdb_read_only(source_path, DatabaseType::Full, *cache_size)?;
+		let destination = utils::open_database_read_only::<Block>(to, DatabaseType::Full)?;
+		for column in 0..NUM_COLUMNS {
+			let stats = verify_column::<HashingFor<Block>>(&source, &*destination, column)?;
+			if copied.get(column as usize) != Some(&stats) {
+				return Err(mismatch(&format!("The number of entries of column {column}")))
+			}
+		}
+	}
+
+	let open = |source: &DatabaseSource| {
+		Backend::<Block>::new_read_only(DatabaseSettings {
+			trie_cache_maximum_size: None,
+			trie_cache_snapshot: None,
+			state_pruning: None,
+			source: source.clone(),
+			blocks_pruning: BlocksPruning::KeepAll,
+		})
+	};
+	let source = open(from)?;
+	let destination = open(to)?;
+
+	let info = source.blockchain().info();
+	if info != destination.blockchain().info() {
+		return Err(mismatch("The chain info"))
+	}
+
+	for hash in [info.genesis_hash, info.finalized_hash, info.best_hash] {
+		if source.blockchain().header(hash)? != destination.blockchain().header(hash)? {
+			return Err(mismatch(&format!("The header of {hash:?}")))
+		}
+	}
+
+	let state_root = destination
+		.blockchain()
+		.header(info.finalized_hash)?
+		.map(|header| *header.state_root())
+		.ok_or_else(|| ClientError::UnknownBlock(format!("{:?}", info.finalized_hash)))?;
+	info!(
+		"Verifying the state of block #{} with state root {:?}",
+		info.finalized_number, state_root
+	);
+
+	let mut progress = Progress::new();
+	let entries = compare_state(
+		&source.state_at(info.finalized_hash)?,
+		&destination.state_at(info.finalized_hash)?,
+		None,
+		&mut progress,
+	)?;
+	info!("Verified {} state entries in {:?}", entries, progress.started.elapsed());
+
+	Ok(())
+}
+
+/// Reads the state meta column of the source database.
+struct SourceMeta<'a>(&'a kvdb_rocksdb::Database);
+
+impl sc_state_db::MetaDb for SourceMeta<'_> {
+	type Error = io::Error;
+
+	fn get_meta(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+		self.0.get(columns::STATE_META, key)
+	}
+}
+
+struct Progress {
+	started: Instant,
+	last_report: Instant,
+}
+
+impl Progress {
+	fn new() -> Self {
+		let now = Instant::now();
+		Progress { started: now, last_report: now }
+	}
+
+	/// Returns `true` if the progress should be logged again.
+	fn due(&mut self) -> bool {
+		if self.last_report.elapsed() < PROGRESS_INTERVAL {
+			return false
+		}
+		self.last_report = Instant::now();
+		true
+	}
+}
+
+fn copy_column(
+	source: &kvdb_rocksdb::Database,
+	destination: &dyn Database<DbHash>,
+	column: u32,
+) -> ClientResult<ColumnStats> {
+	let source_err =
+		|e: io::Error| ClientError::Backend(format!("Error reading column {column}: {e}"));
+	let commit = |transaction| {
+		destination
+			.commit(transaction)
+			.map_err(|e| ClientError::Backend(format!("Error writing column {column}: {e}")))
+	};
+
+	let mut stats = ColumnStats { column, ..Default::default() };
+	let mut progress = Progress::new();
+	let mut transaction = Transaction::new();
+	let mut pending = 0;
+	for entry in source.iter(column) {
+		let (key, value) = entry.map_err(source_err)?;
+		let size = key.len() + value.len();
+
+		match column {
+			// RocksDB stores a node once for every position it has in a trie, so that it can be
+			// deleted on its own. ParityDB keys nodes by hash and counts the references instead.
+			columns::STATE => {
+				if key.len() < DB_HASH_LEN {
+					return Err(ClientError::Backend(format!(
+						"Invalid state key {}",
+						HexDisplay::from(&&key[..])
+					)))
+				}
+				let hash = DbHash::from_slice(&key[key.len() - DB_HASH_LEN..]);
+				transaction.store(column, hash, value);
+			},
+			// The reference counter of a value is stored under its key with a `0` appended.
+			columns::TRANSACTION => {
+				if key.len() != DB_HASH_LEN {
+					continue
+				}
+				let mut counter_key = key.to_vec();
+				counter_key.push(0);
+				let references = match source.get(column, &counter_key).map_err(source_err)? {
+					Some(counter) => counter
+						.try_into()
+						.map(u32::from_le_bytes)
+						.map_err(|_| ClientError::Backend("Invalid reference counter".into()))?,
+					None => 1,
+				};
+				let hash = DbHash::from_slice(&key);
+				for _ in 1..references {
+					transaction.store(column, hash, value.clone());
+				}
+				transaction.store(column, hash, value);
+			},
+			_ => transaction.set_from_vec(column, &key, value),
+		}
+
+		stats.entries += 1;
+		stats.bytes += size as u64;
+		pending += size;
+		if pending >= BATCH_SIZE {
+			commit(std::mem::take(&mut transaction))?;
+			pending = 0;
+		}
+
+		if progress.due() {
+			info!(
+				"Column {}: copied {} entries ({} MiB)",
+				column,
+				stats.entries,
+				stats.bytes / (1024 * 1024)
+			);
+		}
+	}
+	commit(transaction)?;
+
+	info!(
+		"Column {}: copied {} entries ({} MiB) in {:?}",
+		column,
+		stats.entries,
+		stats.bytes / (1024 * 1024),
+		progress.started.elapsed()
+	);
+	Ok(stats)
+}
+
+/// Look up every entry of a source column in the destination and count them like [`copy_column`].
+fn verify_column<H: Hasher>(
+	source: &kvdb_rocksdb::Database,
+	destination: &dyn Database<DbHash>,
+	column: u32,
+) -> ClientResult<ColumnStats> {
+	let source_err =
+		|e: io::Error| ClientError::Backend(format!("Error reading column {column}: {e}"));
+
+	let mut stats = ColumnStats { column, ..Default::default() };
+	let mut progress = Progress::new();
+	for entry in source.iter(column) {
+		let (key, value) = entry.map_err(source_err)?;
+		let found = match column {
+			columns::STATE | columns::TRANSACTION => {
+				if column == columns::TRANSACTION && key.len() != DB_HASH_LEN {
+					continue
+				}
+				let hash = &key[key.len().saturating_sub(DB_HASH_LEN)..];
+				if H::hash(&value).as_ref() != hash {
+					return Err(ClientError::Backend(format!(
+						"The value of {} in column {} does not match its hash",
+						HexDisplay::from(&&key[..]),
+						column
+					)))
+				}
+				destination.get(column, hash)
+			},
+			_ => destination.get(column, &key),
+		};
+		if found.as_deref() != Some(&value[..]) {
+			return Err(mismatch(&format!(
+				"The entry {} of column {}",
+				HexDisplay::from(&&key[..]),
+				column
+			)))
+		}
+
+		stats.entries += 1;
+		stats.bytes += (key.len() + value.len()) as u64;
+		if progress.due() {
+			info!("Column {}: verified {} entries", column, stats.entries);
+		}
+	}
+
+	info!(
+		"Column {}: verified {} entries in {:?}",
+		column,
+		stats.entries,
+		progress.started.elapsed()
+	);
+	Ok(stats)
+}
+
+/// Iterate over the entries of both states in lockstep and return how many there are.
+fn compare_state<H: Hasher, S: StateBackend<H>>(
+	source: &S,
+	destination: &S,
+	child_info: Option<ChildInfo>,
+	progress: &mut Progress,
+) -> ClientResult<u64> {
+	let state_err = |e: S::Error| ClientError::Backend(format!("Error reading the state: {e}"));
+	let args = || IterArgs { child_info: child_info.clone(), ..Default::default() };
+	let mut expected_pairs = source.pairs(args()).map_err(state_err)?;
+	let mut found_pairs = destination.pairs(args()).map_err(state_err)?;
+
+	let mut entries = 0;
+	loop {
+		let (expected, found) = match (expected_pairs.next(), found_pairs.next()) {
+			(None, None) => break,
+			(Some(expected), Some(found)) =>
+				(expected.map_err(state_err)?, found.map_err(state_err)?),
+			_ => return Err(mismatch("The number of state entries")),
+		};
+		if expected != found {
+			return Err(mismatch(&format!("The state entry {}", HexDisplay::from(&expected.0))))
+		}
+
+		entries += 1;
+		if child_info.is_none() {
+			if let Some(storage_key) = expected.0.strip_prefix(DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+				let child_info = Some(ChildInfo::new_default(storage_key));
+				entries += compare_state(source, destination, child_info, progress)?;
+			}
+		}
+
+		if progress.due() {
+			info!("Verified {} state entries", entries);
+		}
+	}
+
+	Ok(entries)
+}
+
+fn mismatch(what: &str) -> ClientError {
+	ClientError::Backend(format!("Verification failed: {what} differs in the converted database"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		tests::{insert_header, Block},
+		PruningMode,
+	};
+	use sc_client_api::blockchain::Backend as _;
+
+	fn settings(source: DatabaseSource, state_pruning: PruningMode) -> DatabaseSettings {
+		DatabaseSettings {
+			trie_cache_maximum_size: None,
+			trie_cache_snapshot: None,
+			state_pruning: Some(state_pruning),
+			source,
+			blocks_pruning: BlocksPruning::KeepAll,
+		}
+	}
+
+	#[test]
+	fn convert_rocksdb_to_paritydb() {
+		let tmp = tempfile::tempdir().unwrap();
+		let from = DatabaseSource::RocksDb { path: tmp.path().join("db"), cache_size: 16 };
+		let to = DatabaseSource::ParityDb { path: tmp.path().join("paritydb") };
+		let transaction = vec![0x13, 0x37];
+		let transaction_hash = <HashingFor<Block> as Hasher>::hash(&transaction);
+
+		let (parent, best) = {
+			let backend =
+				Backend::<Block>::new(settings(from.clone(), PruningMode::ArchiveAll), 0).unwrap();
+			let mut parent = Default::default();
+			let mut best = insert_header(&backend, 0, parent, None, Default::default());
+			for number in 1..10 {
+				parent = best;
+				best = insert_header(&backend, number, parent, None, Default::default());
+			}
+			backend.finalize_block(best, None).unwrap();
+			for _ in 0..2 {
+				let mut commit = Transaction::new();
+				commit.store(columns::TRANSACTION, transaction_hash, transaction.clone());
+				backend.storage.db.commit(commit).unwrap();
+			}
+			(parent, best)
+		};
+
+		let stats = convert_database::<Block>(&from, &to).unwrap();
+		assert_eq!(stats.len(), NUM_COLUMNS as usize);
+		assert!(stats[columns::STATE as usize].entries > 0);
+		assert_eq!(stats[columns::TRANSACTION as usize].entries, 1);
+		verify_conversion::<Block>(&from, &to, &stats).unwrap();
+
+		// A different number of copied entries is detected.
+		let mut missing = stats.clone();
+		missing[columns::HEADER as usize].entries -= 1;
+		assert!(verify_conversion::<Block>(&from, &to, &missing).is_err());
+
+		// The destination is not overwritten.
+		assert!(convert_database::<Block>(&from, &to).is_err());
+
+		let backend =
+			Backend::<Block>::new(settings(to.clone(), PruningMode::ArchiveAll), 0).unwrap();
+		assert_eq!(backend.blockchain().info().best_hash, best);
+		assert_eq!(
+			backend.state_at(best).unwrap().storage(parent.as_ref()).unwrap(),
+			Some(parent.as_ref().to_vec())
+		);
+
+		// Both references to the indexed transaction have been copied.
+		let mut commit = Transaction::new();
+		commit.release(columns::TRANSACTION, transaction_hash);
+		backend.storage.db.commit(commit).unwrap();
+		assert_eq!(
+			backend.blockchain().indexed_transaction(transaction_hash).unwrap(),
+			Some(transaction)
+		);
+	}
+
+	#[test]
+	fn pruned_database_is_rejected() {
+		let tmp = tempfile::tempdir().unwrap();
+		let from = DatabaseSource::RocksDb { path: tmp.path().join("db"), cache_size: 16 };
+		let to = DatabaseSource::ParityDb { path: tmp.path().join("paritydb") };
+		drop(
+			Backend::<Block>::new(settings(from.clone(), PruningMode::blocks_pruning(16)), 0)
+				.unwrap(),
+		);
+
+		assert!(convert_database::<Block>(&from, &to).is_err());
+		assert!(convert_database::<Block>(&to, &from).is_err());
+	}
+
+	#[test]
+	fn non_canonical_blocks_are_rejected() {
+		let tmp = tempfile::tempdir().unwrap();
+		let from = DatabaseSource::RocksDb { path: tmp.path().join("db"), cache_size: 16 };
+		let to = DatabaseSource::ParityDb { path: tmp.path().join("paritydb") };
+		{
+			let backend =
+				Backend::<Block>::new(settings(from.clone(), PruningMode::ArchiveCanonical), 0)
+					.unwrap();
+			let genesis = insert_header(&backend, 0, Default::default(), None, Default::default());
+			backend.finalize_block(genesis, None).unwrap();
+			insert_header(&backend, 1, genesis, None, Default::default());
+		}
+
+		assert!(convert_database::<Block>(&from, &to).is_err());
+	}
+}
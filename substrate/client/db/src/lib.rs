@@ -32,6 +32,9 @@ pub mod offchain;
 
 pub mod bench;
 
+#[cfg(feature = "rocksdb")]
+pub mod convert;
+
 mod children;
 mod parity_db;
 mod pinned_blocks_cache;
@@ -42,7 +45,7 @@ mod upgrade;
 mod utils;
 
 use linked_hash_map::LinkedHashMap;
-use log::{debug, trace, warn};
+use log::{debug, info, trace, warn};
 use parking_lot::{Mutex, RwLock};
 use std::{
 	collections::{HashMap, HashSet},
@@ -299,6 +302,11 @@ pub struct DatabaseSettings {
 	///
 	/// If `None` is given, the cache is disabled.
 	pub trie_cache_maximum_size: Option<usize>,
+	/// Where the trie cache is loaded from on startup, and written to by
+	/// [`Backend::export_trie_cache_snapshot`].
+	///
+	/// If `None` is given, the cache starts empty.
+	pub trie_cache_snapshot: Option<PathBuf>,
 	/// Requested state pruning mode.
 	pub state_pruning: Option<PruningMode>,
 	/// Where to find the database.
@@ -320,6 +328,16 @@ pub enum BlocksPruning {
 	Some(u32),
 }
 
+impl BlocksPruning {
+	/// True if this is an archive pruning mode (either KeepAll or KeepFinalized).
+	pub fn is_archive(&self) -> bool {
+		match *self {
+			BlocksPruning::KeepAll | BlocksPruning::KeepFinalized => true,
+			BlocksPruning::Some(_) => false,
+		}
+	}
+}
+
 /// Where to find the database..
 #[derive(Debug, Clone)]
 pub enum DatabaseSource {
@@ -1117,6 +1135,7 @@ pub struct Backend<Block: BlockT> {
 	state_usage: Arc<StateUsageStats>,
 	genesis_state: RwLock<Option<Arc<DbGenesisStorage<Block>>>>,
 	shared_trie_cache: Option<sp_trie::cache::SharedTrieCache<HashingFor<Block>>>,
+	trie_cache_snapshot: Option<PathBuf>,
 }
 
 impl<Block: BlockT> Backend<Block> {
@@ -1142,6 +1161,17 @@ impl<Block: BlockT> Backend<Block> {
 		Self::from_database(db as Arc<_>, canonicalization_delay, &db_config, needs_init)
 	}
 
+	/// Open an existing database in read-only mode.
+	///
+	/// The backend can be used to read blocks and state, e.g. by offline tools, while a node is
+	/// using the database. Importing blocks or any other write fails.
+	pub fn new_read_only(db_config: DatabaseSettings) -> ClientResult<Self> {
+		let db =
+			crate::utils::open_database_read_only::<Block>(&db_config.source, DatabaseType::Full)?;
+
+		Self::from_database(db, 0, &db_config, false)
+	}
+
 	/// Reset the shared trie cache.
 	pub fn reset_trie_cache(&self) {
 		if let Some(cache) = &self.shared_trie_cache {
@@ -1170,6 +1200,7 @@ impl<Block: BlockT> Backend<Block> {
 		};
 		let db_setting = DatabaseSettings {
 			trie_cache_maximum_size: Some(16 * 1024 * 1024),
+			trie_cache_snapshot: None,
 			state_pruning: Some(state_pruning),
 			source: DatabaseSource::Custom { db, require_create_flag: true },
 			blocks_pruning,
@@ -1240,6 +1271,7 @@ impl<Block: BlockT> Backend<Block> {
 			shared_trie_cache: config.trie_cache_maximum_size.map(|maximum_size| {
 				SharedTrieCache::new(sp_trie::cache::CacheSize::new(maximum_size))
 			}),
+			trie_cache_snapshot: config.trie_cache_snapshot.clone(),
 		};
 
 		// Older DB versions have no last state key. Check if the state is available and set it.
@@ -1262,9 +1294,121 @@ impl<Block: BlockT> Backend<Block> {
 
 		db.commit(db_init_transaction)?;
 
+		backend.import_trie_cache_snapshot();
+
 		Ok(backend)
 	}
 
+	/// State root of the best block, if its state is available.
+	fn best_state_root(&self) -> Option<Block::Hash> {
+		let info = self.blockchain.info();
+		if !sc_client_api::Backend::have_state_at(self, info.best_hash, info.best_number) {
+			return None
+		}
+		self.blockchain
+			.header_metadata(info.best_hash)
+			.ok()
+			.map(|header| header.state_root)
+	}
+
+	/// Load the trie cache written by the previous run of the node.
+	fn import_trie_cache_snapshot(&self) {
+		let (Some(cache), Some(path)) = (&self.shared_trie_cache, &self.trie_cache_snapshot) else {
+			return
+		};
+		let snapshot = match std::fs::read(path) {
+			Ok(snapshot) => snapshot,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => return,
+			Err(e) => {
+				warn!("Failed to read the trie cache snapshot {}: {}", path.display(), e);
+				return
+			},
+		};
+		let storage_root = self.best_state_root().unwrap_or_default();
+		match cache.import(&snapshot, &storage_root) {
+			Ok((nodes, values)) => {
+				info!("📦 Loaded {} trie nodes and {} values into the trie cache", nodes, values)
+			},
+			Err(e) => warn!("Ignoring invalid trie cache snapshot {}: {}", path.display(), e),
+		}
+	}
+
+	/// Write the trie cache, to be loaded by the next run of the node.
+	///
+	/// Should be called on shutdown, once no more blocks are imported.
+	pub fn export_trie_cache_snapshot(&self) {
+		let (Some(cache), Some(path)) = (&self.shared_trie_cache, &self.trie_cache_snapshot) else {
+			return
+		};
+		let snapshot = cache.export(&self.best_state_root().unwrap_or_default());
+		let tmp_path = path.with_extension("tmp");
+		let result = path
+			.parent()
+			.map_or(Ok(()), std::fs::create_dir_all)
+			.and_then(|_| std::fs::write(&tmp_path, snapshot))
+			.and_then(|_| std::fs::rename(&tmp_path, path));
+		if let Err(e) = result {
+			warn!("Failed to write the trie cache snapshot {}: {}", path.display(), e);
+		}
+	}
+
+	/// Read the state of the best block until the trie cache holds `maximum_size` bytes.
+	///
+	/// Blocks until done, or until `stop` returns `true` which is checked between batches of keys.
+	pub fn warm_up_trie_cache(&self, maximum_size: usize, stop: impl Fn() -> bool) {
+		/// Keys read through one state instance, whose local cache is merged into the shared
+		/// cache when dropped.
+		const KEYS_PER_BATCH: usize = 10_000;
+
+		let Some(cache) = &self.shared_trie_cache else { return };
+		let best_hash = self.blockchain.info().best_hash;
+		let started = std::time::Instant::now();
+		let mut last_key: Option<StorageKey> = None;
+		let mut keys_read = 0;
+		let result: Result<(), String> = 'batches: loop {
+			if stop() || cache.used_memory_size() >= maximum_size / 10 * 9 {
+				break Ok(())
+			}
+			let state = match sc_client_api::Backend::state_at(self, best_hash) {
+				Ok(state) => state,
+				Err(e) => break Err(e.to_string()),
+			};
+			let start_at = last_key.clone();
+			let mut args = IterArgs::default();
+			args.start_at = start_at.as_deref();
+			args.start_at_exclusive = true;
+			let keys = match state.keys(args) {
+				Ok(keys) => keys,
+				Err(e) => break Err(e),
+			};
+			let mut batch = 0;
+			for key in keys.take(KEYS_PER_BATCH) {
+				let key = match key {
+					Ok(key) => key,
+					Err(e) => break 'batches Err(e),
+				};
+				if let Err(e) = state.storage(&key) {
+					break 'batches Err(e)
+				}
+				last_key = Some(key);
+				batch += 1;
+			}
+			keys_read += batch;
+			if batch < KEYS_PER_BATCH {
+				break Ok(())
+			}
+		};
+		match result {
+			Ok(()) => info!(
+				"🔥 Warmed up the trie cache with {} keys of the best state in {:?} ({} bytes)",
+				keys_read,
+				started.elapsed(),
+				cache.used_memory_size(),
+			),
+			Err(e) => warn!("Failed to warm up the trie cache: {}", e),
+		}
+	}
+
 	/// Handle setting head within a transaction. `route_to` should be the last
 	/// block that existed in the database. `best_to` should be the best block
 	/// to be set.
@@ -1921,6 +2065,18 @@ impl<Block: BlockT> Backend<Block> {
 					))),
 			}
 		}
+		if let Some(lookup_key) =
+			utils::block_id_to_lookup_key(&*self.storage.db, columns::KEY_LOOKUP, id)?
+		{
+			let key = extra_indexed_transactions_key(&lookup_key);
+			let hashes = read_extra_indexed_transactions(&*self.storage.db, &key)?;
+			if !hashes.is_empty() {
+				transaction.remove(columns::AUX, &key);
+				for hash in hashes {
+					transaction.release(columns::TRANSACTION, hash);
+				}
+			}
+		}
 		Ok(())
 	}
 
@@ -2011,6 +2167,25 @@ fn apply_index_ops<Block: BlockT>(
 	extrinsic_index.encode()
 }
 
+/// Key in the `AUX` column of the transactions stored on behalf of the block with the given
+/// lookup key by [`Backend::insert_indexed_transaction`].
+fn extra_indexed_transactions_key(lookup_key: &[u8]) -> Vec<u8> {
+	[&b"extra_indexed_transactions"[..], lookup_key].concat()
+}
+
+/// Reads the hashes of the transactions stored under `key` on behalf of a block.
+fn read_extra_indexed_transactions(
+	db: &dyn Database<DbHash>,
+	key: &[u8],
+) -> ClientResult<Vec<DbHash>> {
+	match db.get(columns::AUX, key) {
+		Some(hashes) => Vec::<DbHash>::decode(&mut &hashes[..]).map_err(|err| {
+			sp_blockchain::Error::Backend(format!("Error decoding indexed transactions: {}", err))
+		}),
+		None => Ok(Vec::new()),
+	}
+}
+
 fn apply_indexed_body<Block: BlockT>(transaction: &mut Transaction<DbHash>, body: Vec<Vec<u8>>) {
 	for extrinsic in body {
 		let hash = sp_runtime::traits::BlakeTwo256::hash(&extrinsic);
@@ -2410,6 +2585,38 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 		Ok(())
 	}
 
+	fn insert_indexed_transaction(
+		&self,
+		at: Block::Hash,
+		transaction: Vec<u8>,
+	) -> ClientResult<()> {
+		let hash = DbHash::from_slice(sp_runtime::traits::BlakeTwo256::hash(&transaction).as_ref());
+		// Pruning `at` must not run between reading and writing its references.
+		let _lock = self.import_lock.write();
+		let lookup_key = utils::block_id_to_lookup_key::<Block>(
+			&*self.storage.db,
+			columns::KEY_LOOKUP,
+			BlockId::Hash(at),
+		)?
+		.ok_or_else(|| sp_blockchain::Error::UnknownBlock(format!("{:?}", at)))?;
+		let key = extra_indexed_transactions_key(&lookup_key);
+		let mut hashes = read_extra_indexed_transactions(&*self.storage.db, &key)?;
+		if hashes.contains(&hash) {
+			return Ok(())
+		}
+
+		let mut db_transaction = Transaction::new();
+		if self.storage.db.contains(columns::TRANSACTION, hash.as_ref()) {
+			db_transaction.reference(columns::TRANSACTION, hash);
+		} else {
+			db_transaction.store(columns::TRANSACTION, hash, transaction);
+		}
+		hashes.push(hash);
+		db_transaction.set_from_vec(columns::AUX, &key, hashes.encode());
+		self.storage.db.commit(db_transaction)?;
+		Ok(())
+	}
+
 	fn blockchain(&self) -> &BlockchainDb<Block> {
 		&self.blockchain
 	}
@@ -2492,10 +2699,17 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 	}
 
 	fn requires_full_sync(&self) -> bool {
-		matches!(
-			self.storage.state_db.pruning_mode(),
-			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical
-		)
+		match self.storage.state_db.pruning_mode() {
+			// Fast and warp sync only download the state of their target block, while these modes
+			// keep states of older blocks: all of them, the ones from a given block onward, or
+			// periodic checkpoints. Producing those requires importing every block from genesis.
+			PruningMode::ArchiveAll |
+			PruningMode::ArchiveCanonical |
+			PruningMode::ArchiveFrom(_) |
+			PruningMode::Checkpoints(_) => true,
+			// Only the states of the latest blocks are kept, which are imported after the target.
+			PruningMode::Constrained(_) => false,
+		}
 	}
 
 	fn pin_block(&self, hash: <Block as BlockT>::Hash) -> sp_blockchain::Result<()> {
@@ -2688,6 +2902,7 @@ pub(crate) mod tests {
 		let backend = Backend::<Block>::new(
 			DatabaseSettings {
 				trie_cache_maximum_size: Some(16 * 1024 * 1024),
+				trie_cache_snapshot: None,
 				state_pruning: Some(PruningMode::blocks_pruning(1)),
 				source: DatabaseSource::Custom { db: backing, require_create_flag: false },
 				blocks_pruning: BlocksPruning::KeepFinalized,
@@ -2783,6 +2998,77 @@ pub(crate) mod tests {
 		}
 	}
 
+	#[test]
+	fn trie_cache_survives_restart() {
+		let tmp = tempfile::tempdir().unwrap();
+		let snapshot = tmp.path().join("trie-cache");
+		let kvdb = sp_database::as_database(kvdb_memorydb::create(crate::utils::NUM_COLUMNS));
+		let settings = |require_create_flag| DatabaseSettings {
+			trie_cache_maximum_size: Some(16 * 1024 * 1024),
+			trie_cache_snapshot: Some(snapshot.clone()),
+			state_pruning: Some(PruningMode::ArchiveAll),
+			source: DatabaseSource::Custom { db: kvdb.clone(), require_create_flag },
+			blocks_pruning: BlocksPruning::KeepAll,
+		};
+		let cache_size = |backend: &Backend<Block>| {
+			backend.shared_trie_cache.as_ref().unwrap().used_memory_size()
+		};
+
+		let empty_cache_size = {
+			let backend = Backend::<Block>::new(settings(true), 0).unwrap();
+			let empty_cache_size = cache_size(&backend);
+			let mut op = backend.begin_operation().unwrap();
+			let storage = (0u8..100).map(|i| (vec![i; 8], vec![i; 64])).collect::<Vec<_>>();
+			let (root, _) = op.old_state.storage_root(
+				storage.iter().map(|(k, v)| (&k[..], Some(&v[..]))),
+				StateVersion::V1,
+			);
+			let header = Header {
+				number: 0,
+				parent_hash: Default::default(),
+				state_root: root,
+				digest: Default::default(),
+				extrinsics_root: Default::default(),
+			};
+			op.reset_storage(
+				Storage {
+					top: storage.into_iter().collect(),
+					children_default: Default::default(),
+				},
+				StateVersion::V1,
+			)
+			.unwrap();
+			op.set_block_data(header.clone(), Some(vec![]), None, None, NewBlockState::Final)
+				.unwrap();
+			backend.commit_operation(op).unwrap();
+
+			let state = backend.state_at(header.hash()).unwrap();
+			assert_eq!(state.storage(&[7; 8]).unwrap(), Some(vec![7; 64]));
+			drop(state);
+			assert!(cache_size(&backend) > empty_cache_size);
+
+			// Nothing is written until the cache is exported.
+			assert!(!snapshot.exists());
+			backend.export_trie_cache_snapshot();
+			empty_cache_size
+		};
+
+		// The exported cache is loaded on startup.
+		assert!(snapshot.exists());
+		let backend = Backend::<Block>::new(settings(false), 0).unwrap();
+		assert!(cache_size(&backend) > empty_cache_size);
+		drop(backend);
+
+		// Without snapshot, the warm up reads the best state.
+		std::fs::remove_file(&snapshot).unwrap();
+		let backend = Backend::<Block>::new(settings(false), 0).unwrap();
+		assert_eq!(cache_size(&backend), empty_cache_size);
+		backend.warm_up_trie_cache(16 * 1024 * 1024, || true);
+		assert_eq!(cache_size(&backend), empty_cache_size);
+		backend.warm_up_trie_cache(16 * 1024 * 1024, || false);
+		assert!(cache_size(&backend) > empty_cache_size);
+	}
+
 	#[test]
 	fn delete_only_when_negative_rc() {
 		sp_tracing::try_init_simple();
@@ -3598,6 +3884,35 @@ pub(crate) mod tests {
 		assert_eq!(bc.indexed_transaction(x1_hash).unwrap(), None);
 	}
 
+	#[test]
+	fn insert_indexed_transaction_is_released_with_its_block() {
+		let backend = Backend::<Block>::new_test_with_tx_storage(BlocksPruning::Some(1), 10);
+		let bc = backend.blockchain();
+		let transaction = vec![0x13, 0x37];
+		let hash = <HashingFor<Block> as sp_core::Hasher>::hash(&transaction);
+
+		let block0 =
+			insert_block(&backend, 0, Default::default(), None, Default::default(), vec![], None)
+				.unwrap();
+		backend.insert_indexed_transaction(block0, transaction.clone()).unwrap();
+		// Inserting it again for the same block is a no-op.
+		backend.insert_indexed_transaction(block0, transaction.clone()).unwrap();
+		assert_eq!(bc.indexed_transaction(hash).unwrap(), Some(transaction.clone()));
+
+		let block1 =
+			insert_block(&backend, 1, block0, None, Default::default(), vec![], None).unwrap();
+		backend.insert_indexed_transaction(block1, transaction.clone()).unwrap();
+
+		// Pruning block 0 keeps the transaction referenced by block 1.
+		backend.finalize_block(block1, None).unwrap();
+		assert_eq!(bc.indexed_transaction(hash).unwrap(), Some(transaction));
+
+		let block2 =
+			insert_block(&backend, 2, block1, None, Default::default(), vec![], None).unwrap();
+		backend.finalize_block(block2, None).unwrap();
+		assert_eq!(bc.indexed_transaction(hash).unwrap(), None);
+	}
+
 	#[test]
 	fn index_invalid_size() {
 		let backend = Backend::<Block>::new_test_with_tx_storage(BlocksPruning::Some(1), 10);
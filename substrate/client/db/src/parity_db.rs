@@ -40,6 +40,36 @@ pub fn open<H: Clone + AsRef<[u8]>>(
 	create: bool,
 	upgrade: bool,
 ) -> parity_db::Result<std::sync::Arc<dyn Database<H>>> {
+	let config = options(path, db_type);
+
+	if upgrade {
+		log::info!("Upgrading database metadata.");
+		if let Some(meta) = parity_db::Options::load_metadata(path)? {
+			config.write_metadata_with_version(path, &meta.salt, Some(meta.version))?;
+		}
+	}
+
+	let db = if create {
+		parity_db::Db::open_or_create(&config)?
+	} else {
+		parity_db::Db::open(&config)?
+	};
+
+	Ok(std::sync::Arc::new(DbAdapter(db)))
+}
+
+/// Wrap parity-db database opened in read-only mode into a trait object that implements
+/// `sp_database::Database`.
+pub fn open_read_only<H: Clone + AsRef<[u8]>>(
+	path: &std::path::Path,
+	db_type: DatabaseType,
+) -> parity_db::Result<std::sync::Arc<dyn Database<H>>> {
+	let db = parity_db::Db::open_read_only(&options(path, db_type))?;
+	Ok(std::sync::Arc::new(DbAdapter(db)))
+}
+
+/// The parity-db options of the given database type.
+fn options(path: &std::path::Path, db_type: DatabaseType) -> parity_db::Options {
 	let mut config = parity_db::Options::with_columns(path, NUM_COLUMNS as u8);
 
 	match db_type {
@@ -70,20 +100,7 @@ pub fn open<H: Clone + AsRef<[u8]>>(
 		},
 	}
 
-	if upgrade {
-		log::info!("Upgrading database metadata.");
-		if let Some(meta) = parity_db::Options::load_metadata(path)? {
-			config.write_metadata_with_version(path, &meta.salt, Some(meta.version))?;
-		}
-	}
-
-	let db = if create {
-		parity_db::Db::open_or_create(&config)?
-	} else {
-		parity_db::Db::open(&config)?
-	};
-
-	Ok(std::sync::Arc::new(DbAdapter(db)))
+	config
 }
 
 fn ref_counted_column(col: u32) -> bool {
@@ -216,6 +216,84 @@ fn open_database_at<Block: BlockT>(
 	Ok(db)
 }
 
+/// Opens the configured database in read-only mode.
+///
+/// The database is neither created nor upgraded, and all writes to it are rejected. A RocksDB
+/// database is opened as a secondary instance, so it can be read while a node is using it.
+pub fn open_database_read_only<Block: BlockT>(
+	db_source: &DatabaseSource,
+	db_type: DatabaseType,
+) -> OpenDbResult {
+	let db = match &db_source {
+		DatabaseSource::ParityDb { path } =>
+			ReadOnlyDatabase::new(crate::parity_db::open_read_only(path, db_type)?),
+		#[cfg(feature = "rocksdb")]
+		DatabaseSource::RocksDb { path, cache_size } =>
+			open_kvdb_rocksdb_read_only::<Block>(path, db_type, *cache_size)?,
+		DatabaseSource::Custom { db, .. } => ReadOnlyDatabase::new(db.clone()),
+		DatabaseSource::Auto { paritydb_path, rocksdb_path, cache_size } =>
+			match open_kvdb_rocksdb_read_only::<Block>(rocksdb_path, db_type, *cache_size) {
+				Ok(db) => db,
+				Err(OpenDbError::NotEnabled(_)) | Err(OpenDbError::DoesNotExist) =>
+					ReadOnlyDatabase::new(crate::parity_db::open_read_only(paritydb_path, db_type)?),
+				Err(as_is) => return Err(as_is),
+			},
+	};
+
+	check_database_type(&db, db_type)?;
+	Ok(Arc::new(db))
+}
+
+/// A database that rejects all writes.
+struct ReadOnlyDatabase {
+	db: Arc<dyn Database<DbHash>>,
+	// The directory of a RocksDB secondary instance, removed after the database is dropped.
+	_secondary_path: Option<tempfile::TempDir>,
+}
+
+impl ReadOnlyDatabase {
+	fn new(db: Arc<dyn Database<DbHash>>) -> Self {
+		Self { db, _secondary_path: None }
+	}
+}
+
+impl Database<DbHash> for ReadOnlyDatabase {
+	fn commit(&self, transaction: Transaction<DbHash>) -> sp_database::error::Result<()> {
+		if transaction.0.is_empty() {
+			return Ok(())
+		}
+
+		Err(sp_database::error::DatabaseError(Box::new(io::Error::new(
+			io::ErrorKind::PermissionDenied,
+			"The database is opened in read-only mode",
+		))))
+	}
+
+	fn get(&self, col: sp_database::ColumnId, key: &[u8]) -> Option<Vec<u8>> {
+		self.db.get(col, key)
+	}
+
+	fn contains(&self, col: sp_database::ColumnId, key: &[u8]) -> bool {
+		self.db.contains(col, key)
+	}
+
+	fn value_size(&self, col: sp_database::ColumnId, key: &[u8]) -> Option<usize> {
+		self.db.value_size(col, key)
+	}
+
+	fn with_get(&self, col: sp_database::ColumnId, key: &[u8], f: &mut dyn FnMut(&[u8])) {
+		self.db.with_get(col, key, f)
+	}
+
+	fn supports_ref_counting(&self) -> bool {
+		self.db.supports_ref_counting()
+	}
+
+	fn sanitize_key(&self, key: &mut Vec<u8>) {
+		self.db.sanitize_key(key)
+	}
+}
+
 #[derive(Debug)]
 pub enum OpenDbError {
 	// constructed only when rocksdb and paritydb are disabled
@@ -309,9 +387,81 @@ fn open_kvdb_rocksdb<Block: BlockT>(
 	}
 
 	// and now open database assuming that it has the latest version
-	let mut db_config = kvdb_rocksdb::DatabaseConfig::with_columns(NUM_COLUMNS);
+	let mut db_config = kvdb_rocksdb_config(path, db_type, cache_size);
 	db_config.create_if_missing = create;
 
+	let db = kvdb_rocksdb::Database::open(&db_config, path)?;
+	// write database version only after the database is succesfully opened
+	crate::upgrade::update_version(path)?;
+	Ok(sp_database::as_database(db))
+}
+
+#[cfg(any(feature = "rocksdb", test))]
+fn open_kvdb_rocksdb_read_only<Block: BlockT>(
+	path: &Path,
+	db_type: DatabaseType,
+	cache_size: usize,
+) -> Result<ReadOnlyDatabase, OpenDbError> {
+	let SecondaryRocksDb { db, secondary_path } =
+		open_raw_kvdb_rocksdb_read_only(path, db_type, cache_size)?;
+	Ok(ReadOnlyDatabase { db: sp_database::as_database(db), _secondary_path: Some(secondary_path) })
+}
+
+/// A RocksDB database opened as a secondary instance.
+#[cfg(any(feature = "rocksdb", test))]
+pub(crate) struct SecondaryRocksDb {
+	/// The database. Declared first, so that it's dropped before its directory is removed.
+	db: kvdb_rocksdb::Database,
+	/// The temporary directory of the secondary instance, where it keeps its info logs.
+	secondary_path: tempfile::TempDir,
+}
+
+#[cfg(any(feature = "rocksdb", test))]
+impl std::ops::Deref for SecondaryRocksDb {
+	type Target = kvdb_rocksdb::Database;
+
+	fn deref(&self) -> &Self::Target {
+		&self.db
+	}
+}
+
+/// Opens an existing RocksDB database as a secondary instance, without the `sp_database`
+/// wrapper, so that its columns can be iterated.
+#[cfg(any(feature = "rocksdb", test))]
+pub(crate) fn open_raw_kvdb_rocksdb_read_only(
+	path: &Path,
+	db_type: DatabaseType,
+	cache_size: usize,
+) -> Result<SecondaryRocksDb, OpenDbError> {
+	match crate::upgrade::check_version(path) {
+		Ok(()) => (),
+		Err(crate::upgrade::UpgradeError::MissingDatabaseVersionFile) =>
+			return Err(OpenDbError::DoesNotExist),
+		Err(err) => return Err(OpenDbError::Internal(err.to_string())),
+	}
+
+	let mut db_config = kvdb_rocksdb_config(path, db_type, cache_size);
+	db_config.create_if_missing = false;
+	// A secondary instance only needs a directory of its own for its info logs.
+	let secondary_path = tempfile::Builder::new()
+		.prefix("substrate-rocksdb-secondary")
+		.tempdir()
+		.map_err(|e| OpenDbError::Internal(e.to_string()))?;
+	db_config.secondary = Some(secondary_path.path().to_path_buf());
+
+	let db = kvdb_rocksdb::Database::open(&db_config, path)?;
+	Ok(SecondaryRocksDb { db, secondary_path })
+}
+
+/// The RocksDB configuration of the given database type.
+#[cfg(any(feature = "rocksdb", test))]
+fn kvdb_rocksdb_config(
+	path: &Path,
+	db_type: DatabaseType,
+	cache_size: usize,
+) -> kvdb_rocksdb::DatabaseConfig {
+	let mut db_config = kvdb_rocksdb::DatabaseConfig::with_columns(NUM_COLUMNS);
+
 	let mut memory_budget = std::collections::HashMap::new();
 	match db_type {
 		DatabaseType::Full => {
@@ -337,10 +487,7 @@ fn open_kvdb_rocksdb<Block: BlockT>(
 	}
 	db_config.memory_budget = memory_budget;
 
-	let db = kvdb_rocksdb::Database::open(&db_config, path)?;
-	// write database version only after the database is succesfully opened
-	crate::upgrade::update_version(path)?;
-	Ok(sp_database::as_database(db))
+	db_config
 }
 
 #[cfg(not(any(feature = "rocksdb", test)))]
@@ -353,6 +500,15 @@ fn open_kvdb_rocksdb<Block: BlockT>(
 	Err(OpenDbError::NotEnabled("with-kvdb-rocksdb"))
 }
 
+#[cfg(not(any(feature = "rocksdb", test)))]
+fn open_kvdb_rocksdb_read_only<Block: BlockT>(
+	_path: &Path,
+	_db_type: DatabaseType,
+	_cache_size: usize,
+) -> Result<ReadOnlyDatabase, OpenDbError> {
+	Err(OpenDbError::NotEnabled("with-kvdb-rocksdb"))
+}
+
 /// Check database type.
 pub fn check_database_type(
 	db: &dyn Database<DbHash>,
@@ -60,6 +60,7 @@ fn initialize(
 					heap_alloc_strategy: DEFAULT_HEAP_ALLOC_STRATEGY,
 					instantiation_strategy,
 					deterministic_stack_limit: None,
+					fuel_limit: None,
 					canonicalize_nans: false,
 					parallel_compilation: true,
 					wasm_multi_value: false,
@@ -0,0 +1,338 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! On-disk cache of the runtimes compiled by wasmtime.
+//!
+//! Compiling a runtime takes seconds, so the compiled artifacts are kept across restarts. An
+//! artifact is identified by the wasmtime version, the hash of the runtime code and the
+//! [`Semantics`] it was compiled with. Every file starts with a checksum of the artifact, which is
+//! verified before the artifact is loaded.
+//!
+//! Only the [`MAX_ARTIFACTS`] most recently used artifacts are kept. The modification time of an
+//! artifact is updated whenever it is loaded, so old runtimes are removed after an upgrade.
+
+use crate::error::WasmError;
+
+use sc_executor_common::runtime_blob::RuntimeBlob;
+use sc_executor_wasmtime::{Config, Semantics, WasmtimeRuntime, WASMTIME_VERSION};
+use sp_core::{blake2_256, hexdisplay::HexDisplay};
+use sp_wasm_interface::HostFunctions;
+
+use std::{
+	fs,
+	io::{self, Write},
+	path::{Path, PathBuf},
+	time::SystemTime,
+};
+
+/// Length of the checksum in front of every artifact.
+const CHECKSUM_LEN: usize = 32;
+
+/// Number of artifacts that are kept in the cache.
+///
+/// A few runtimes are used at the same time, e.g. around a runtime upgrade or by a collator that
+/// also follows the relay chain.
+const MAX_ARTIFACTS: usize = 8;
+
+/// The compiled runtimes in a directory.
+pub(crate) struct ArtifactCache {
+	dir: PathBuf,
+}
+
+impl ArtifactCache {
+	/// Use the `runtimes` directory in `cache_path`.
+	///
+	/// Artifacts of other wasmtime versions can not be loaded anymore, so they are removed.
+	pub fn new(cache_path: &Path) -> Self {
+		let cache = ArtifactCache { dir: cache_path.join("runtimes") };
+		cache.remove_stale();
+		cache.evict();
+		cache
+	}
+
+	/// Create the runtime from its cached artifact, or compile it and add it to the cache.
+	pub fn create_runtime<H: HostFunctions>(
+		&self,
+		code_hash: &[u8],
+		blob: RuntimeBlob,
+		config: Config,
+	) -> Result<WasmtimeRuntime, WasmError> {
+		let path = self.dir.join(artifact_file_name(code_hash, &config.semantics));
+
+		if let Some(artifact) = read_artifact(&path) {
+			// Mark the artifact as recently used.
+			let _ = fs::File::options()
+				.write(true)
+				.open(&path)
+				.and_then(|file| file.set_modified(SystemTime::now()));
+
+			// SAFETY: The artifact was produced by `prepare_runtime_artifact` and the checksum
+			//         shows that it has not been modified since.
+			match unsafe {
+				sc_executor_wasmtime::create_runtime_from_artifact_bytes::<H>(
+					&artifact,
+					config.clone(),
+				)
+			} {
+				Ok(runtime) => return Ok(runtime),
+				Err(error) => tracing::warn!(
+					target: "wasm-runtime",
+					%error,
+					path = %path.display(),
+					"Cannot load the cached runtime, compiling it again",
+				),
+			}
+		}
+
+		let artifact = sc_executor_wasmtime::prepare_runtime_artifact(blob, &config.semantics)?;
+		if let Err(error) = write_artifact(&path, &artifact) {
+			tracing::warn!(
+				target: "wasm-runtime",
+				%error,
+				path = %path.display(),
+				"Failed to cache the compiled runtime",
+			);
+		}
+		self.evict();
+
+		// SAFETY: The artifact was just produced by `prepare_runtime_artifact`.
+		unsafe { sc_executor_wasmtime::create_runtime_from_artifact_bytes::<H>(&artifact, config) }
+	}
+
+	/// Remove the artifacts of other wasmtime versions and leftover temporary files.
+	fn remove_stale(&self) {
+		let Ok(entries) = fs::read_dir(&self.dir) else { return };
+
+		let prefix = artifact_prefix();
+		for entry in entries.flatten() {
+			let name = entry.file_name();
+			if !name.to_str().map_or(false, |name| name.starts_with(&prefix)) {
+				let _ = fs::remove_file(entry.path());
+			}
+		}
+	}
+
+	/// Remove all but the [`MAX_ARTIFACTS`] most recently used artifacts.
+	fn evict(&self) {
+		let Ok(entries) = fs::read_dir(&self.dir) else { return };
+
+		let prefix = artifact_prefix();
+		let mut artifacts = entries
+			.flatten()
+			.filter(|entry| {
+				entry.file_name().to_str().map_or(false, |name| name.starts_with(&prefix))
+			})
+			.map(|entry| {
+				let used = entry
+					.metadata()
+					.and_then(|metadata| metadata.modified())
+					.unwrap_or(SystemTime::UNIX_EPOCH);
+				(used, entry.path())
+			})
+			.collect::<Vec<_>>();
+		if artifacts.len() <= MAX_ARTIFACTS {
+			return
+		}
+
+		artifacts.sort_by(|a, b| b.0.cmp(&a.0));
+		for (_, path) in artifacts.drain(MAX_ARTIFACTS..) {
+			tracing::debug!(
+				target: "wasm-runtime",
+				path = %path.display(),
+				"Removing least recently used runtime artifact",
+			);
+			let _ = fs::remove_file(path);
+		}
+	}
+}
+
+fn artifact_prefix() -> String {
+	format!("wasmtime_v{}_", WASMTIME_VERSION.unwrap_or("unknown"))
+}
+
+fn artifact_file_name(code_hash: &[u8], semantics: &Semantics) -> String {
+	format!(
+		"{}0x{}_0x{}",
+		artifact_prefix(),
+		HexDisplay::from(&code_hash),
+		HexDisplay::from(&semantics_hash(semantics))
+	)
+}
+
+/// Hash everything in `semantics` that has an influence on the compiled code.
+fn semantics_hash(semantics: &Semantics) -> [u8; 32] {
+	// Destructured, so that a new field has to be added here as well.
+	let Semantics {
+		instantiation_strategy,
+		deterministic_stack_limit,
+		fuel_limit,
+		canonicalize_nans,
+		parallel_compilation: _,
+		heap_alloc_strategy,
+		wasm_multi_value,
+		wasm_bulk_memory,
+		wasm_reference_types,
+		wasm_simd,
+	} = semantics;
+	let stack_limit = deterministic_stack_limit
+		.as_ref()
+		.map(|limit| (limit.logical_max, limit.native_stack_max));
+	// Only whether fuel is metered at all is compiled in, the amount is set per instance.
+	let fuel_metering = fuel_limit.is_some();
+
+	blake2_256(
+		format!(
+			"{instantiation_strategy:?} {stack_limit:?} {fuel_metering} {canonicalize_nans} \
+			 {heap_alloc_strategy:?} {wasm_multi_value} {wasm_bulk_memory} \
+			 {wasm_reference_types} {wasm_simd}"
+		)
+		.as_bytes(),
+	)
+}
+
+/// Read the artifact at `path`, removing the file if its checksum does not match.
+fn read_artifact(path: &Path) -> Option<Vec<u8>> {
+	let mut content = fs::read(path).ok()?;
+
+	if content.len() >= CHECKSUM_LEN &&
+		content[..CHECKSUM_LEN] == blake2_256(&content[CHECKSUM_LEN..])
+	{
+		content.drain(..CHECKSUM_LEN);
+		return Some(content)
+	}
+
+	tracing::warn!(
+		target: "wasm-runtime",
+		path = %path.display(),
+		"Removing corrupted runtime artifact",
+	);
+	let _ = fs::remove_file(path);
+	None
+}
+
+/// Write the artifact with its checksum.
+///
+/// The file is written under a temporary name first, so that `path` never holds a partial artifact.
+fn write_artifact(path: &Path, artifact: &[u8]) -> io::Result<()> {
+	let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+		return Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid artifact path"))
+	};
+	fs::create_dir_all(dir)?;
+
+	let tmp_path = dir.join(format!(".{}.tmp", name.to_string_lossy()));
+	let mut file = fs::File::create(&tmp_path)?;
+	file.write_all(&blake2_256(artifact))?;
+	file.write_all(artifact)?;
+	file.sync_all()?;
+
+	fs::rename(&tmp_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::wasm_runtime::{wasmtime_config, ExecutionLimits};
+	use sc_executor_common::wasm_runtime::{HeapAllocStrategy, WasmModule};
+	use sc_executor_wasmtime::InstantiationStrategy;
+	use sc_runtime_test::wasm_binary_unwrap;
+	use std::time::Duration;
+
+	type HostFunctions = sp_io::SubstrateHostFunctions;
+
+	fn create_runtime(cache: &ArtifactCache) -> WasmtimeRuntime {
+		let blob = RuntimeBlob::uncompress_if_needed(wasm_binary_unwrap()).unwrap();
+		let config = wasmtime_config(
+			InstantiationStrategy::RecreateInstance,
+			HeapAllocStrategy::Static { extra_pages: 1024 },
+			false,
+			ExecutionLimits::default(),
+			None,
+		);
+		cache.create_runtime::<HostFunctions>(&[1; 32], blob, config).unwrap()
+	}
+
+	fn artifacts(cache: &ArtifactCache) -> Vec<PathBuf> {
+		fs::read_dir(&cache.dir).unwrap().map(|entry| entry.unwrap().path()).collect()
+	}
+
+	#[test]
+	fn artifacts_are_reused_and_verified() {
+		let tmp = tempfile::tempdir().unwrap();
+		let cache = ArtifactCache::new(tmp.path());
+
+		create_runtime(&cache).new_instance().unwrap();
+		let artifacts = artifacts(&cache);
+		assert_eq!(artifacts.len(), 1);
+		let artifact = fs::read(&artifacts[0]).unwrap();
+
+		// The cached artifact is loaded by a new cache in the same directory.
+		let cache = ArtifactCache::new(tmp.path());
+		create_runtime(&cache).new_instance().unwrap();
+		assert_eq!(fs::read(&artifacts[0]).unwrap(), artifact);
+
+		// A corrupted artifact is compiled again.
+		let mut corrupted = artifact;
+		*corrupted.last_mut().unwrap() ^= 1;
+		fs::write(&artifacts[0], corrupted).unwrap();
+		assert!(read_artifact(&artifacts[0]).is_none());
+		assert!(!artifacts[0].exists());
+		create_runtime(&cache).new_instance().unwrap();
+		assert!(read_artifact(&artifacts[0]).is_some());
+	}
+
+	#[test]
+	fn stale_artifacts_are_removed() {
+		let tmp = tempfile::tempdir().unwrap();
+		let dir = tmp.path().join("runtimes");
+		fs::create_dir_all(&dir).unwrap();
+		fs::write(dir.join("wasmtime_v0.0.1_0x01_0x02"), b"stale").unwrap();
+		fs::write(dir.join(".interrupted.tmp"), b"partial").unwrap();
+
+		let cache = ArtifactCache::new(tmp.path());
+		assert!(artifacts(&cache).is_empty());
+	}
+
+	#[test]
+	fn least_recently_used_artifacts_are_evicted() {
+		let tmp = tempfile::tempdir().unwrap();
+		let dir = tmp.path().join("runtimes");
+		fs::create_dir_all(&dir).unwrap();
+		let now = SystemTime::now();
+		let paths = (0..MAX_ARTIFACTS + 2)
+			.map(|i| {
+				let path = dir.join(format!("{}0x{:02x}", artifact_prefix(), i));
+				let file = fs::File::create(&path).unwrap();
+				file.set_modified(now - Duration::from_secs(60 * (i as u64 + 1))).unwrap();
+				path
+			})
+			.collect::<Vec<_>>();
+
+		let cache = ArtifactCache::new(tmp.path());
+		let mut remaining = artifacts(&cache);
+		remaining.sort();
+		let mut expected = paths[..MAX_ARTIFACTS].to_vec();
+		expected.sort();
+		assert_eq!(remaining, expected);
+
+		// A newly compiled artifact replaces the least recently used one.
+		create_runtime(&cache).new_instance().unwrap();
+		assert_eq!(artifacts(&cache).len(), MAX_ARTIFACTS);
+		assert!(!paths[MAX_ARTIFACTS - 1].exists());
+		assert!(paths[0].exists());
+	}
+}
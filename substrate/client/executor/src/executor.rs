@@ -18,7 +18,7 @@
 
 use crate::{
 	error::{Error, Result},
-	wasm_runtime::{RuntimeCache, WasmExecutionMethod},
+	wasm_runtime::{ExecutionLimits, RuntimeCache, WasmExecutionMethod},
 	RuntimeVersionOf,
 };
 
@@ -93,6 +93,7 @@ pub struct WasmExecutorBuilder<H> {
 	cache_path: Option<PathBuf>,
 	allow_missing_host_functions: bool,
 	runtime_cache_size: u8,
+	offchain_execution_limits: ExecutionLimits,
 }
 
 impl<H> WasmExecutorBuilder<H> {
@@ -110,6 +111,7 @@ impl<H> WasmExecutorBuilder<H> {
 			runtime_cache_size: 4,
 			allow_missing_host_functions: false,
 			cache_path: None,
+			offchain_execution_limits: ExecutionLimits::default(),
 		}
 	}
 
@@ -162,7 +164,8 @@ impl<H> WasmExecutorBuilder<H> {
 	///
 	/// The `cache_path` is A path to a directory where the executor can place its files for
 	/// purposes of caching. This may be important in cases when there are many different modules
-	/// with the compiled execution method is used.
+	/// with the compiled execution method is used. The compiled runtimes are stored there as well,
+	/// so that they don't need to be compiled again after a restart.
 	///
 	/// By default there is no `cache_path` given.
 	pub fn with_cache_path(mut self, cache_path: impl Into<PathBuf>) -> Self {
@@ -193,6 +196,18 @@ impl<H> WasmExecutorBuilder<H> {
 		self
 	}
 
+	/// Create the wasm executor with the given `limits` for offchain runtime calls.
+	///
+	/// Offchain calls, e.g. `state_call` from RPC, will trap once they exceed any of the limits.
+	/// Runtimes are compiled separately for every set of limits, so offchain calls with limits
+	/// set don't share the compiled runtime with onchain calls.
+	///
+	/// By default no limits are enforced.
+	pub fn with_offchain_execution_limits(mut self, limits: ExecutionLimits) -> Self {
+		self.offchain_execution_limits = limits;
+		self
+	}
+
 	/// Build the configured [`WasmExecutor`].
 	pub fn build(self) -> WasmExecutor<H> {
 		WasmExecutor {
@@ -204,6 +219,7 @@ impl<H> WasmExecutorBuilder<H> {
 				self.onchain_heap_alloc_strategy,
 			),
 			ignore_onchain_heap_pages: self.ignore_onchain_heap_pages,
+			offchain_execution_limits: self.offchain_execution_limits,
 			cache: Arc::new(RuntimeCache::new(
 				self.max_runtime_instances,
 				self.cache_path.clone(),
@@ -227,6 +243,8 @@ pub struct WasmExecutor<H> {
 	default_offchain_heap_alloc_strategy: HeapAllocStrategy,
 	/// Ignore onchain heap pages value.
 	ignore_onchain_heap_pages: bool,
+	/// The limits enforced on offchain Wasm calls.
+	offchain_execution_limits: ExecutionLimits,
 	/// WASM runtime cache.
 	cache: Arc<RuntimeCache>,
 	/// The path to a directory which the executor can leverage for a file cache, e.g. put there
@@ -244,6 +262,7 @@ impl<H> Clone for WasmExecutor<H> {
 			default_onchain_heap_alloc_strategy: self.default_onchain_heap_alloc_strategy,
 			default_offchain_heap_alloc_strategy: self.default_offchain_heap_alloc_strategy,
 			ignore_onchain_heap_pages: self.ignore_onchain_heap_pages,
+			offchain_execution_limits: self.offchain_execution_limits,
 			cache: self.cache.clone(),
 			cache_path: self.cache_path.clone(),
 			allow_missing_host_functions: self.allow_missing_host_functions,
@@ -291,6 +310,7 @@ where
 				default_heap_pages.map(|h| HeapAllocStrategy::Static { extra_pages: h as _ }),
 			),
 			ignore_onchain_heap_pages: false,
+			offchain_execution_limits: ExecutionLimits::default(),
 			cache: Arc::new(RuntimeCache::new(
 				max_runtime_instances,
 				cache_path.clone(),
@@ -333,6 +353,32 @@ where
 		heap_alloc_strategy: HeapAllocStrategy,
 		f: F,
 	) -> Result<R>
+	where
+		F: FnOnce(
+			AssertUnwindSafe<&dyn WasmModule>,
+			AssertUnwindSafe<&mut dyn WasmInstance>,
+			Option<&RuntimeVersion>,
+			AssertUnwindSafe<&mut dyn Externalities>,
+		) -> Result<Result<R>>,
+	{
+		self.with_instance_and_limits(
+			runtime_code,
+			ext,
+			heap_alloc_strategy,
+			ExecutionLimits::default(),
+			f,
+		)
+	}
+
+	/// Same as [`Self::with_instance`], except that the runtime enforces the given `limits`.
+	fn with_instance_and_limits<R, F>(
+		&self,
+		runtime_code: &RuntimeCode,
+		ext: &mut dyn Externalities,
+		heap_alloc_strategy: HeapAllocStrategy,
+		limits: ExecutionLimits,
+		f: F,
+	) -> Result<R>
 	where
 		F: FnOnce(
 			AssertUnwindSafe<&dyn WasmModule>,
@@ -347,6 +393,7 @@ where
 			self.method,
 			heap_alloc_strategy,
 			self.allow_missing_host_functions,
+			limits,
 			|module, instance, version, ext| {
 				let module = AssertUnwindSafe(module);
 				let instance = AssertUnwindSafe(instance);
@@ -509,15 +556,17 @@ where
 				.unwrap_or_else(|| self.default_onchain_heap_alloc_strategy)
 		};
 
-		let heap_alloc_strategy = match context {
-			CallContext::Offchain => self.default_offchain_heap_alloc_strategy,
-			CallContext::Onchain => on_chain_heap_alloc_strategy,
+		let (heap_alloc_strategy, limits) = match context {
+			CallContext::Offchain =>
+				(self.default_offchain_heap_alloc_strategy, self.offchain_execution_limits),
+			CallContext::Onchain => (on_chain_heap_alloc_strategy, ExecutionLimits::default()),
 		};
 
-		let result = self.with_instance(
+		let result = self.with_instance_and_limits(
 			runtime_code,
 			ext,
 			heap_alloc_strategy,
+			limits,
 			|_, mut instance, _onchain_version, mut ext| {
 				with_externalities_safe(&mut **ext, move || instance.call_export(method, data))
 			},
@@ -672,16 +721,20 @@ impl<D: NativeExecutionDispatch + 'static> CodeExecutor for NativeElseWasmExecut
 				.unwrap_or_else(|| self.wasm.default_onchain_heap_alloc_strategy)
 		};
 
-		let heap_alloc_strategy = match context {
-			CallContext::Offchain => self.wasm.default_offchain_heap_alloc_strategy,
-			CallContext::Onchain => on_chain_heap_alloc_strategy,
+		let (heap_alloc_strategy, limits) = match context {
+			CallContext::Offchain => (
+				self.wasm.default_offchain_heap_alloc_strategy,
+				self.wasm.offchain_execution_limits,
+			),
+			CallContext::Onchain => (on_chain_heap_alloc_strategy, ExecutionLimits::default()),
 		};
 
 		let mut used_native = false;
-		let result = self.wasm.with_instance(
+		let result = self.wasm.with_instance_and_limits(
 			runtime_code,
 			ext,
 			heap_alloc_strategy,
+			limits,
 			|_, mut instance, onchain_version, mut ext| {
 				let onchain_version =
 					onchain_version.ok_or_else(|| Error::ApiError("Unknown version".into()))?;
@@ -28,7 +28,7 @@ use sp_core::{
 	blake2_128, blake2_256, ed25519, map,
 	offchain::{testing, OffchainDbExt, OffchainWorkerExt},
 	sr25519,
-	traits::Externalities,
+	traits::{CallContext, CodeExecutor, Externalities, RuntimeCode, WrappedRuntimeCode},
 	Pair,
 };
 use sp_runtime::traits::BlakeTwo256;
@@ -37,7 +37,7 @@ use sp_trie::{LayoutV1 as Layout, TrieConfiguration};
 use std::sync::Arc;
 use tracing_subscriber::layer::SubscriberExt;
 
-use crate::WasmExecutionMethod;
+use crate::{ExecutionLimits, WasmExecutionMethod};
 
 pub type TestExternalities = CoreTestExternalities<BlakeTwo256>;
 type HostFunctions = sp_io::SubstrateHostFunctions;
@@ -451,6 +451,38 @@ fn should_trap_when_heap_exhausted(wasm_method: WasmExecutionMethod) {
 	}
 }
 
+test_wasm_execution!(offchain_execution_limits_are_enforced);
+fn offchain_execution_limits_are_enforced(wasm_method: WasmExecutionMethod) {
+	let mut ext = TestExternalities::default();
+	let mut ext = ext.ext();
+
+	let executor = crate::WasmExecutor::<HostFunctions>::builder()
+		.with_execution_method(wasm_method)
+		.with_allow_missing_host_functions(true)
+		.with_offchain_execution_limits(ExecutionLimits {
+			max_fuel: Some(1),
+			max_stack_depth: None,
+		})
+		.build();
+
+	let code_fetcher = WrappedRuntimeCode(wasm_binary_unwrap().into());
+	let runtime_code = RuntimeCode { code_fetcher: &code_fetcher, hash: vec![1], heap_pages: None };
+
+	// Onchain calls are never limited.
+	let (result, _) =
+		executor.call(&mut ext, &runtime_code, "test_empty_return", &[], CallContext::Onchain);
+	assert_eq!(result.unwrap(), Vec::<u8>::new());
+
+	let (result, _) =
+		executor.call(&mut ext, &runtime_code, "test_empty_return", &[], CallContext::Offchain);
+	match result.unwrap_err() {
+		Error::AbortedDueToTrap(error) => {
+			assert_eq!(error.message, "wasm trap: all fuel consumed by WebAssembly");
+		},
+		error => panic!("unexpected error: {:?}", error),
+	}
+}
+
 fn mk_test_runtime(
 	wasm_method: WasmExecutionMethod,
 	pages: HeapAllocStrategy,
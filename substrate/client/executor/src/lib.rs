@@ -31,6 +31,7 @@
 #![warn(missing_docs)]
 #![recursion_limit = "128"]
 
+mod artifact_cache;
 #[macro_use]
 mod executor;
 #[cfg(test)]
@@ -41,7 +42,7 @@ pub use self::{
 	executor::{
 		with_externalities_safe, NativeElseWasmExecutor, NativeExecutionDispatch, WasmExecutor,
 	},
-	wasm_runtime::{read_embedded_version, WasmExecutionMethod},
+	wasm_runtime::{read_embedded_version, ExecutionLimits, WasmExecutionMethod},
 };
 pub use codec::Codec;
 #[doc(hidden)]
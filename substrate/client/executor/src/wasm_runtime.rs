@@ -21,7 +21,10 @@
 //! The primary means of accessing the runtimes is through a cache which saves the reusable
 //! components of the runtime that are expensive to initialize.
 
-use crate::error::{Error, WasmError};
+use crate::{
+	artifact_cache::ArtifactCache,
+	error::{Error, WasmError},
+};
 
 use codec::Decode;
 use parking_lot::Mutex;
@@ -58,6 +61,23 @@ impl Default for WasmExecutionMethod {
 	}
 }
 
+/// Limits enforced on a single runtime call.
+///
+/// Both limits make the call trap deterministically instead of running for an unbounded amount
+/// of time or until the native stack is exhausted. They are meant to protect the node against
+/// runaway offchain calls, e.g. `state_call` issued over RPC.
+#[derive(Debug, Default, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct ExecutionLimits {
+	/// The amount of fuel a call is given, roughly the number of wasm instructions it may execute.
+	///
+	/// `None` disables fuel metering.
+	pub max_fuel: Option<u64>,
+	/// The maximum number of logical values that can be on the wasm stack.
+	///
+	/// `None` disables the deterministic stack limit and only the native stack limit applies.
+	pub max_stack_depth: Option<u32>,
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 struct VersionedRuntimeId {
 	/// Runtime code hash.
@@ -66,6 +86,8 @@ struct VersionedRuntimeId {
 	wasm_method: WasmExecutionMethod,
 	/// The heap allocation strategy this runtime was created with.
 	heap_alloc_strategy: HeapAllocStrategy,
+	/// The execution limits this runtime was created with.
+	limits: ExecutionLimits,
 }
 
 /// A Wasm runtime object along with its cached runtime version.
@@ -166,7 +188,8 @@ pub struct RuntimeCache {
 	runtimes: Mutex<LruMap<VersionedRuntimeId, Arc<VersionedRuntime>>>,
 	/// The size of the instances cache for each runtime.
 	max_runtime_instances: usize,
-	cache_path: Option<PathBuf>,
+	/// The compiled runtimes kept on disk.
+	artifact_cache: Option<ArtifactCache>,
 }
 
 impl RuntimeCache {
@@ -176,7 +199,8 @@ impl RuntimeCache {
 	/// in-memory cache.
 	///
 	/// `cache_path` allows to specify an optional directory where the executor can store files
-	/// for caching.
+	/// for caching. The compiled runtimes are kept there, so that they are not compiled again
+	/// after a restart.
 	///
 	/// `runtime_cache_size` specifies the number of different runtimes versions preserved in an
 	/// in-memory cache, must always be at least 1.
@@ -186,7 +210,11 @@ impl RuntimeCache {
 		runtime_cache_size: u8,
 	) -> RuntimeCache {
 		let cap = ByLength::new(runtime_cache_size.max(1) as u32);
-		RuntimeCache { runtimes: Mutex::new(LruMap::new(cap)), max_runtime_instances, cache_path }
+		RuntimeCache {
+			runtimes: Mutex::new(LruMap::new(cap)),
+			max_runtime_instances,
+			artifact_cache: cache_path.as_deref().map(ArtifactCache::new),
+		}
 	}
 
 	/// Prepares a WASM module instance and executes given function for it.
@@ -204,6 +232,8 @@ impl RuntimeCache {
 	///
 	/// `allow_missing_func_imports` - Ignore missing function imports.
 	///
+	/// `limits` - The limits enforced on every call into the runtime.
+	///
 	/// `f` - Function to execute.
 	///
 	/// `H` - A compile-time list of host functions to expose to the runtime.
@@ -222,6 +252,7 @@ impl RuntimeCache {
 		wasm_method: WasmExecutionMethod,
 		heap_alloc_strategy: HeapAllocStrategy,
 		allow_missing_func_imports: bool,
+		limits: ExecutionLimits,
 		f: F,
 	) -> Result<Result<R, Error>, Error>
 	where
@@ -235,8 +266,12 @@ impl RuntimeCache {
 	{
 		let code_hash = &runtime_code.hash;
 
-		let versioned_runtime_id =
-			VersionedRuntimeId { code_hash: code_hash.clone(), heap_alloc_strategy, wasm_method };
+		let versioned_runtime_id = VersionedRuntimeId {
+			code_hash: code_hash.clone(),
+			heap_alloc_strategy,
+			wasm_method,
+			limits,
+		};
 
 		let mut runtimes = self.runtimes.lock(); // this must be released prior to calling f
 		let versioned_runtime = if let Some(versioned_runtime) = runtimes.get(&versioned_runtime_id)
@@ -249,12 +284,14 @@ impl RuntimeCache {
 
 			let result = create_versioned_wasm_runtime::<H>(
 				&code,
+				code_hash,
 				ext,
 				wasm_method,
 				heap_alloc_strategy,
 				allow_missing_func_imports,
+				limits,
 				self.max_runtime_instances,
-				self.cache_path.as_deref(),
+				self.artifact_cache.as_ref(),
 			);
 
 			match result {
@@ -301,26 +338,56 @@ where
 		WasmExecutionMethod::Compiled { instantiation_strategy } =>
 			sc_executor_wasmtime::create_runtime::<H>(
 				blob,
-				sc_executor_wasmtime::Config {
+				wasmtime_config(
+					instantiation_strategy,
+					heap_alloc_strategy,
 					allow_missing_func_imports,
-					cache_path: cache_path.map(ToOwned::to_owned),
-					semantics: sc_executor_wasmtime::Semantics {
-						heap_alloc_strategy,
-						instantiation_strategy,
-						deterministic_stack_limit: None,
-						canonicalize_nans: false,
-						parallel_compilation: true,
-						wasm_multi_value: false,
-						wasm_bulk_memory: false,
-						wasm_reference_types: false,
-						wasm_simd: false,
-					},
-				},
+					ExecutionLimits::default(),
+					cache_path,
+				),
 			)
 			.map(|runtime| -> Box<dyn WasmModule> { Box::new(runtime) }),
 	}
 }
 
+/// The native stack limit used together with a deterministic stack limit.
+///
+/// Runtime calls are executed on the caller's thread, so unlike for PVF execution this can't be
+/// raised. It's the same limit that applies without the deterministic limit, so enabling it can
+/// only make calls fail earlier.
+const NATIVE_STACK_MAX: u32 = 1024 * 1024;
+
+/// The wasmtime configuration runtimes are created with.
+pub(crate) fn wasmtime_config(
+	instantiation_strategy: sc_executor_wasmtime::InstantiationStrategy,
+	heap_alloc_strategy: HeapAllocStrategy,
+	allow_missing_func_imports: bool,
+	limits: ExecutionLimits,
+	cache_path: Option<&Path>,
+) -> sc_executor_wasmtime::Config {
+	sc_executor_wasmtime::Config {
+		allow_missing_func_imports,
+		cache_path: cache_path.map(ToOwned::to_owned),
+		semantics: sc_executor_wasmtime::Semantics {
+			heap_alloc_strategy,
+			instantiation_strategy,
+			deterministic_stack_limit: limits.max_stack_depth.map(|logical_max| {
+				sc_executor_wasmtime::DeterministicStackLimit {
+					logical_max,
+					native_stack_max: NATIVE_STACK_MAX,
+				}
+			}),
+			fuel_limit: limits.max_fuel,
+			canonicalize_nans: false,
+			parallel_compilation: true,
+			wasm_multi_value: false,
+			wasm_bulk_memory: false,
+			wasm_reference_types: false,
+			wasm_simd: false,
+		},
+	}
+}
+
 fn decode_version(mut version: &[u8]) -> Result<RuntimeVersion, WasmError> {
 	Decode::decode(&mut version).map_err(|_| {
 		WasmError::Instantiation(
@@ -379,12 +446,14 @@ pub fn read_embedded_version(blob: &RuntimeBlob) -> Result<Option<RuntimeVersion
 
 fn create_versioned_wasm_runtime<H>(
 	code: &[u8],
+	code_hash: &[u8],
 	ext: &mut dyn Externalities,
 	wasm_method: WasmExecutionMethod,
 	heap_alloc_strategy: HeapAllocStrategy,
 	allow_missing_func_imports: bool,
+	limits: ExecutionLimits,
 	max_instances: usize,
-	cache_path: Option<&Path>,
+	artifact_cache: Option<&ArtifactCache>,
 ) -> Result<VersionedRuntime, WasmError>
 where
 	H: HostFunctions,
@@ -398,13 +467,19 @@ where
 	// runtime.
 	let mut version = read_embedded_version(&blob)?;
 
-	let runtime = create_wasm_runtime_with_code::<H>(
-		wasm_method,
+	let WasmExecutionMethod::Compiled { instantiation_strategy } = wasm_method;
+	let config = wasmtime_config(
+		instantiation_strategy,
 		heap_alloc_strategy,
-		blob,
 		allow_missing_func_imports,
-		cache_path,
-	)?;
+		limits,
+		None,
+	);
+	let runtime: Box<dyn WasmModule> = match artifact_cache {
+		Some(artifact_cache) =>
+			Box::new(artifact_cache.create_runtime::<H>(code_hash, blob, config)?),
+		None => Box::new(sc_executor_wasmtime::create_runtime::<H>(blob, config)?),
+	};
 
 	// If the runtime blob doesn't embed the runtime version then use the legacy version query
 	// mechanism: call the runtime.
@@ -22,4 +22,6 @@ fn main() {
 	if let Ok(profile) = env::var("PROFILE") {
 		println!("cargo:rustc-cfg=build_type=\"{}\"", profile);
 	}
+
+	substrate_build_script_utils::generate_wasmtime_version();
 }
@@ -41,3 +41,8 @@ pub use runtime::{
 	prepare_runtime_artifact, Config, DeterministicStackLimit, InstantiationStrategy, Semantics,
 	WasmtimeRuntime,
 };
+
+/// The version of wasmtime, if it could be determined when building this crate.
+///
+/// Compiled artifacts are only compatible with the wasmtime version that produced them.
+pub const WASMTIME_VERSION: Option<&str> = option_env!("SUBSTRATE_WASMTIME_VERSION");
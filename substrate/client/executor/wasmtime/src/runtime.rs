@@ -77,11 +77,17 @@ struct InstanceCreator {
 	engine: Engine,
 	instance_pre: Arc<wasmtime::InstancePre<StoreData>>,
 	instance_counter: Arc<InstanceCounter>,
+	fuel_limit: Option<u64>,
 }
 
 impl InstanceCreator {
 	fn instantiate(&mut self) -> Result<InstanceWrapper> {
-		InstanceWrapper::new(&self.engine, &self.instance_pre, self.instance_counter.clone())
+		InstanceWrapper::new(
+			&self.engine,
+			&self.instance_pre,
+			self.instance_counter.clone(),
+			self.fuel_limit,
+		)
 	}
 }
 
@@ -139,6 +145,7 @@ pub struct WasmtimeRuntime {
 	instance_pre: Arc<wasmtime::InstancePre<StoreData>>,
 	instantiation_strategy: InternalInstantiationStrategy,
 	instance_counter: Arc<InstanceCounter>,
+	fuel_limit: Option<u64>,
 }
 
 impl WasmModule for WasmtimeRuntime {
@@ -148,6 +155,7 @@ impl WasmModule for WasmtimeRuntime {
 				engine: self.engine.clone(),
 				instance_pre: self.instance_pre.clone(),
 				instance_counter: self.instance_counter.clone(),
+				fuel_limit: self.fuel_limit,
 			}),
 		};
 
@@ -275,6 +283,10 @@ fn common_config(semantics: &Semantics) -> std::result::Result<wasmtime::Config,
 
 	config.max_wasm_stack(native_stack_max as usize);
 
+	// Fuel consumption has to be compiled into the code, so it's only enabled when a limit
+	// was actually requested.
+	config.consume_fuel(semantics.fuel_limit.is_some());
+
 	config.parallel_compilation(semantics.parallel_compilation);
 
 	// Be clear and specific about the extensions we support. If an update brings new features
@@ -434,6 +446,15 @@ pub struct Semantics {
 	// I.e. if [`CodeSupplyMode::Verbatim`] is used.
 	pub deterministic_stack_limit: Option<DeterministicStackLimit>,
 
+	/// Specifying `Some` will enable fuel metering. Every instance gets the given amount of fuel
+	/// and the call traps once it has been exhausted.
+	///
+	/// The fuel is roughly proportional to the number of executed wasm instructions, so this can
+	/// be used to bound the amount of work a single call is allowed to do. Note that this is not
+	/// a replacement for benchmarked weights and is not meant to be used for on-chain execution;
+	/// enabling it also makes the compiled code slightly slower.
+	pub fuel_limit: Option<u64>,
+
 	/// Controls whether wasmtime should compile floating point in a way that doesn't allow for
 	/// non-determinism.
 	///
@@ -645,6 +666,7 @@ where
 		instance_pre: Arc::new(instance_pre),
 		instantiation_strategy,
 		instance_counter: Default::default(),
+		fuel_limit: config.semantics.fuel_limit,
 	})
 }
 
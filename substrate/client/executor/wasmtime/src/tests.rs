@@ -68,6 +68,7 @@ struct RuntimeBuilder {
 	instantiation_strategy: InstantiationStrategy,
 	canonicalize_nans: bool,
 	deterministic_stack: bool,
+	fuel_limit: Option<u64>,
 	heap_pages: HeapAllocStrategy,
 	precompile_runtime: bool,
 	tmpdir: Option<tempfile::TempDir>,
@@ -80,6 +81,7 @@ impl RuntimeBuilder {
 			instantiation_strategy,
 			canonicalize_nans: false,
 			deterministic_stack: false,
+			fuel_limit: None,
 			heap_pages: DEFAULT_HEAP_ALLOC_STRATEGY,
 			precompile_runtime: false,
 			tmpdir: None,
@@ -101,6 +103,11 @@ impl RuntimeBuilder {
 		self
 	}
 
+	fn fuel_limit(mut self, fuel_limit: Option<u64>) -> Self {
+		self.fuel_limit = fuel_limit;
+		self
+	}
+
 	fn precompile_runtime(mut self, precompile_runtime: bool) -> Self {
 		self.precompile_runtime = precompile_runtime;
 		self
@@ -139,6 +146,7 @@ impl RuntimeBuilder {
 					}),
 					false => None,
 				},
+				fuel_limit: self.fuel_limit,
 				canonicalize_nans: self.canonicalize_nans,
 				parallel_compilation: true,
 				heap_alloc_strategy: self.heap_pages,
@@ -303,6 +311,51 @@ fn test_stack_depth_reaching(instantiation_strategy: InstantiationStrategy) {
 	}
 }
 
+test_wasm_execution!(test_fuel_exhaustion_traps);
+fn test_fuel_exhaustion_traps(instantiation_strategy: InstantiationStrategy) {
+	let wat = r#"
+		(module
+		  (memory $0 32)
+		  (export "memory" (memory $0))
+		  (global (export "__heap_base") i32 (i32.const 0))
+
+		  (func (export "main")
+		    (param i32 i32) (result i64)
+		    (loop $label$1
+		      (br $label$1)
+		    )
+		    (i64.const 0)
+		  )
+		)
+	"#;
+
+	let mut builder = RuntimeBuilder::new(instantiation_strategy)
+		.use_wat(wat.to_string())
+		.fuel_limit(Some(1_000_000));
+	let runtime = builder.build();
+	let mut instance = runtime.new_instance().expect("failed to instantiate a runtime");
+
+	match instance.call_export("main", &[]).unwrap_err() {
+		Error::AbortedDueToTrap(error) => {
+			let expected = "wasm trap: all fuel consumed by WebAssembly";
+			assert_eq!(error.message, expected);
+		},
+		error => panic!("unexpected error: {:?}", error),
+	}
+}
+
+test_wasm_execution!(test_fuel_is_refilled_for_every_call);
+fn test_fuel_is_refilled_for_every_call(instantiation_strategy: InstantiationStrategy) {
+	let mut builder = RuntimeBuilder::new(instantiation_strategy).fuel_limit(Some(1_000_000));
+	let runtime = builder.build();
+	let mut instance = runtime.new_instance().expect("failed to instantiate a runtime");
+
+	// Each call gets a fresh store, so the fuel consumed by one call must not leak into the next.
+	for _ in 0..10 {
+		instance.call_export("test_empty_return", &[0]).unwrap();
+	}
+}
+
 test_wasm_execution!(test_max_memory_pages_imported_memory_without_precompilation);
 fn test_max_memory_pages_imported_memory_without_precompilation(
 	instantiation_strategy: InstantiationStrategy,
@@ -466,6 +519,7 @@ fn test_instances_without_reuse_are_not_leaked() {
 			semantics: crate::Semantics {
 				instantiation_strategy: InstantiationStrategy::RecreateInstance,
 				deterministic_stack_limit: None,
+				fuel_limit: None,
 				canonicalize_nans: false,
 				parallel_compilation: true,
 				heap_alloc_strategy: DEFAULT_HEAP_ALLOC_STRATEGY,
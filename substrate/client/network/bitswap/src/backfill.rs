@@ -0,0 +1,176 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Backfill of indexed transactions that a node missed while syncing.
+//!
+//! A node syncing in storage chain mode downloads the latest state and the indexed bodies of the
+//! blocks it imports, but not the indexed transactions of the blocks before the point it synced
+//! to. Those are still referenced by the runtime until their storage period ends, so they are
+//! listed by an [`IndexedTransactionsProvider`] and fetched from peers over bitswap.
+
+use crate::{indexed_transaction_cid, BitswapClient, BitswapError, LOG_TARGET};
+use codec::Decode;
+use log::{debug, info};
+use sc_client_api::{Backend, BlockBackend, StorageProvider};
+use sc_network_sync::SyncingService;
+use sp_blockchain::HeaderBackend;
+use sp_consensus::SyncOracle;
+use sp_core::{hashing::twox_128, storage::StorageKey};
+use sp_runtime::traits::Block as BlockT;
+use std::{marker::PhantomData, sync::Arc, time::Duration};
+
+/// Interval between backfill attempts.
+const BACKFILL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Lists the indexed transactions that the runtime still references.
+pub trait IndexedTransactionsProvider<B: BlockT>: Send + Sync {
+	/// Content hashes and sizes of the indexed transactions referenced by the state of block `at`.
+	fn indexed_transactions(&self, at: B::Hash) -> sp_blockchain::Result<Vec<(B::Hash, u32)>>;
+}
+
+/// [`IndexedTransactionsProvider`] of runtimes that store transactions with
+/// `pallet-transaction-storage`.
+///
+/// Reads the pallet's map from block number to the `TransactionInfo`s of the transactions stored
+/// in that block.
+pub struct TransactionStoragePallet<Client, BE> {
+	client: Arc<Client>,
+	transactions_prefix: StorageKey,
+	_phantom: PhantomData<fn() -> BE>,
+}
+
+impl<Client, BE> TransactionStoragePallet<Client, BE> {
+	/// Create a provider for the pallet named `pallet_name` in the runtime.
+	pub fn new(client: Arc<Client>, pallet_name: &[u8]) -> Self {
+		let transactions_prefix =
+			StorageKey([twox_128(pallet_name), twox_128(b"Transactions")].concat());
+		Self { client, transactions_prefix, _phantom: PhantomData }
+	}
+}
+
+impl<B, Client, BE> IndexedTransactionsProvider<B> for TransactionStoragePallet<Client, BE>
+where
+	B: BlockT,
+	Client: StorageProvider<B, BE> + Send + Sync,
+	BE: Backend<B>,
+{
+	fn indexed_transactions(&self, at: B::Hash) -> sp_blockchain::Result<Vec<(B::Hash, u32)>> {
+		let mut hashes = Vec::new();
+		for (_, value) in self.client.storage_pairs(at, Some(&self.transactions_prefix), None)? {
+			// `TransactionInfo` is `(chunk_root, content_hash, size, block_chunks)`.
+			let transactions = Vec::<(B::Hash, B::Hash, u32, u32)>::decode(&mut &value.0[..])
+				.map_err(|e| sp_blockchain::Error::Backend(e.to_string()))?;
+			hashes.extend(
+				transactions.into_iter().map(|(_, content_hash, size, _)| (content_hash, size)),
+			);
+		}
+		Ok(hashes)
+	}
+}
+
+/// Fetches the indexed transactions referenced by the finalized state that are missing locally.
+///
+/// Runs until all of them are stored, retrying periodically while peers don't have them. The
+/// fetched transactions are kept until the block that was finalized when they were fetched is
+/// pruned.
+pub struct IndexedTransactionsBackfill<B: BlockT, Client, BE, P> {
+	client: Arc<Client>,
+	backend: Arc<BE>,
+	provider: P,
+	bitswap: BitswapClient,
+	sync_service: Arc<SyncingService<B>>,
+}
+
+impl<B, Client, BE, P> IndexedTransactionsBackfill<B, Client, BE, P>
+where
+	B: BlockT,
+	Client: HeaderBackend<B> + BlockBackend<B> + Send + Sync + 'static,
+	BE: Backend<B> + 'static,
+	P: IndexedTransactionsProvider<B>,
+{
+	/// Create a new [`IndexedTransactionsBackfill`].
+	pub fn new(
+		client: Arc<Client>,
+		backend: Arc<BE>,
+		provider: P,
+		bitswap: BitswapClient,
+		sync_service: Arc<SyncingService<B>>,
+	) -> Self {
+		Self { client, backend, provider, bitswap, sync_service }
+	}
+
+	/// Run [`IndexedTransactionsBackfill`].
+	pub async fn run(self) {
+		loop {
+			futures_timer::Delay::new(BACKFILL_INTERVAL).await;
+
+			if self.sync_service.is_major_syncing() {
+				continue
+			}
+
+			match self.backfill().await {
+				Ok(0) => {
+					info!(target: LOG_TARGET, "All indexed transactions are available");
+					return
+				},
+				Ok(missing) =>
+					debug!(target: LOG_TARGET, "{missing} indexed transactions are still missing"),
+				Err(e) =>
+					debug!(target: LOG_TARGET, "Failed to backfill indexed transactions: {e}"),
+			}
+		}
+	}
+
+	/// Fetch and store the missing indexed transactions, returning how many are still missing.
+	async fn backfill(&self) -> Result<usize, BitswapError> {
+		let finalized = self.client.info().finalized_hash;
+		let mut missing = Vec::new();
+		for (content_hash, size) in self.provider.indexed_transactions(finalized)? {
+			if !self.client.has_indexed_transaction(content_hash)? {
+				missing.push((content_hash, size));
+			}
+		}
+
+		if missing.is_empty() {
+			return Ok(0)
+		}
+
+		// Sized, so that large transactions are spread over several responses.
+		let cids = missing
+			.iter()
+			.map(|(hash, size)| (indexed_transaction_cid::<B>(hash), *size as usize))
+			.collect::<Vec<_>>();
+		let peers = self.sync_service.peers_info().await.unwrap_or_default();
+		let fetched = self
+			.bitswap
+			.fetch_from_any(peers.into_iter().map(|(peer, _)| peer), &cids)
+			.await;
+		debug!(
+			target: LOG_TARGET,
+			"Fetched {} of {} missing indexed transactions",
+			fetched.len(),
+			missing.len(),
+		);
+
+		let still_missing = missing.len() - fetched.len();
+		for transaction in fetched.into_values() {
+			self.backend.insert_indexed_transaction(finalized, transaction)?;
+		}
+
+		Ok(still_missing)
+	}
+}
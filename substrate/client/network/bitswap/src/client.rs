@@ -0,0 +1,336 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Bitswap client, fetching blocks referenced by CID from peers.
+
+use crate::{
+	protocol_config,
+	schema::bitswap::{
+		message::{
+			wantlist::{Entry, WantType},
+			Wantlist,
+		},
+		Message as BitswapMessage,
+	},
+	BitswapError, Prefix, LOG_TARGET, MAX_PACKET_SIZE, MAX_WANTED_BLOCKS, PROTOCOL_NAME,
+};
+use cid::{
+	multihash::{Code, Multihash, MultihashDigest},
+	Cid,
+};
+use libp2p_identity::PeerId;
+use log::{debug, trace};
+use prost::Message;
+use sc_network::{
+	request_responses::{IfDisconnected, ProtocolConfig},
+	types::ProtocolName,
+	NetworkRequest,
+};
+use sp_runtime::traits::Block as BlockT;
+use std::{collections::HashMap, sync::Arc};
+
+/// Multicodec of raw binary data.
+const RAW_CODEC: u64 = 0x55;
+
+/// Upper bound of the bytes a block adds to a response on top of its data: the prefix and the
+/// protobuf framing.
+const BLOCK_OVERHEAD: usize = 64;
+
+/// Returns the CID under which bitswap serves the indexed transaction with the given hash.
+pub fn indexed_transaction_cid<B: BlockT>(hash: &B::Hash) -> Cid {
+	let multihash = Multihash::wrap(u64::from(Code::Blake2b256), hash.as_ref())
+		.expect("Blake2b256 digests fit into a multihash; qed");
+	Cid::new_v1(RAW_CODEC, multihash)
+}
+
+/// Fetches blocks from peers over bitswap.
+///
+/// Only blocks whose content matches the blake2b-256 or sha2-256 multihash of the requested CID
+/// are returned; anything else a peer sends is dropped.
+#[derive(Clone)]
+pub struct BitswapClient {
+	network: Arc<dyn NetworkRequest + Send + Sync>,
+}
+
+impl BitswapClient {
+	/// Create a new [`BitswapClient`] sending requests through `network`.
+	pub fn new(network: Arc<dyn NetworkRequest + Send + Sync>) -> Self {
+		Self { network }
+	}
+
+	/// Configuration of the bitswap protocol for nodes that only fetch, but don't serve blocks.
+	pub fn protocol_config() -> ProtocolConfig {
+		protocol_config(None)
+	}
+
+	/// Fetch `cids` from `peer`.
+	///
+	/// Returns the blocks the peer had, keyed by their CID. Long lists of CIDs are split over
+	/// several requests, as peers only accept a few wanted blocks per message.
+	///
+	/// The blocks of a request must fit into one response, use [`Self::fetch_sized`] if they may
+	/// be large.
+	pub async fn fetch(
+		&self,
+		peer: PeerId,
+		cids: &[Cid],
+	) -> Result<HashMap<Cid, Vec<u8>>, BitswapError> {
+		let mut blocks = HashMap::new();
+		for chunk in cids.chunks(MAX_WANTED_BLOCKS) {
+			self.fetch_chunk(peer, chunk, &mut blocks).await?;
+		}
+
+		Ok(blocks)
+	}
+
+	/// Fetch the blocks with the given CIDs and sizes from `peer`.
+	///
+	/// Like [`Self::fetch`], but the blocks are also split over requests such that each response
+	/// stays below the maximum packet size.
+	pub async fn fetch_sized(
+		&self,
+		peer: PeerId,
+		cids: &[(Cid, usize)],
+	) -> Result<HashMap<Cid, Vec<u8>>, BitswapError> {
+		let mut blocks = HashMap::new();
+		for chunk in size_chunks(cids) {
+			let chunk = chunk.iter().map(|(cid, _)| *cid).collect::<Vec<_>>();
+			self.fetch_chunk(peer, &chunk, &mut blocks).await?;
+		}
+
+		Ok(blocks)
+	}
+
+	/// Fetch `chunk` from `peer` with a single request, adding the received blocks to `blocks`.
+	async fn fetch_chunk(
+		&self,
+		peer: PeerId,
+		chunk: &[Cid],
+		blocks: &mut HashMap<Cid, Vec<u8>>,
+	) -> Result<(), BitswapError> {
+		let request = BitswapMessage {
+			wantlist: Some(Wantlist {
+				entries: chunk
+					.iter()
+					.map(|cid| Entry {
+						block: cid.to_bytes(),
+						want_type: WantType::Block as i32,
+						send_dont_have: true,
+						..Default::default()
+					})
+					.collect(),
+				full: false,
+			}),
+			..Default::default()
+		};
+
+		let response = self
+			.network
+			.request(
+				peer,
+				ProtocolName::from(PROTOCOL_NAME),
+				request.encode_to_vec(),
+				IfDisconnected::ImmediateError,
+			)
+			.await?;
+		let response = BitswapMessage::decode(&response[..])?;
+
+		for block in response.payload {
+			match verify_block(&block.prefix, &block.data) {
+				Ok(cid) if chunk.contains(&cid) => {
+					trace!(target: LOG_TARGET, "Received {cid} from {peer}");
+					blocks.insert(cid, block.data);
+				},
+				Ok(cid) => debug!(target: LOG_TARGET, "Unrequested {cid} from {peer}"),
+				Err(e) => debug!(target: LOG_TARGET, "Invalid block from {peer}: {e}"),
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Fetch the blocks with the given CIDs and sizes from the first of `peers` that has them.
+	///
+	/// Peers are asked in order for the CIDs that are still missing. Returns whatever could be
+	/// fetched, which may be less than requested.
+	pub async fn fetch_from_any(
+		&self,
+		peers: impl IntoIterator<Item = PeerId>,
+		cids: &[(Cid, usize)],
+	) -> HashMap<Cid, Vec<u8>> {
+		let mut blocks = HashMap::new();
+
+		for peer in peers {
+			let missing = cids
+				.iter()
+				.filter(|(cid, _)| !blocks.contains_key(cid))
+				.cloned()
+				.collect::<Vec<_>>();
+			if missing.is_empty() {
+				break
+			}
+
+			match self.fetch_sized(peer, &missing).await {
+				Ok(fetched) => blocks.extend(fetched),
+				Err(e) => debug!(target: LOG_TARGET, "Failed to fetch blocks from {peer}: {e}"),
+			}
+		}
+
+		blocks
+	}
+}
+
+/// Splits `cids` into runs of at most [`MAX_WANTED_BLOCKS`] blocks whose response fits into
+/// [`MAX_PACKET_SIZE`], given the size of each block.
+///
+/// A block that doesn't fit into a response even on its own is requested alone.
+fn size_chunks(cids: &[(Cid, usize)]) -> Vec<&[(Cid, usize)]> {
+	let mut chunks = Vec::new();
+	let mut start = 0;
+	let mut response_size = 0usize;
+	for (index, (_, size)) in cids.iter().enumerate() {
+		let block_size = size.saturating_add(BLOCK_OVERHEAD);
+		if index > start &&
+			(index - start == MAX_WANTED_BLOCKS ||
+				response_size.saturating_add(block_size) > MAX_PACKET_SIZE as usize)
+		{
+			chunks.push(&cids[start..index]);
+			start = index;
+			response_size = 0;
+		}
+		response_size = response_size.saturating_add(block_size);
+	}
+	if start < cids.len() {
+		chunks.push(&cids[start..]);
+	}
+
+	chunks
+}
+
+/// Returns the CID of `data` sent with the given `prefix`, if its multihash is supported.
+fn verify_block(prefix: &[u8], data: &[u8]) -> Result<Cid, BitswapError> {
+	let prefix = Prefix::from_bytes(prefix)?;
+	let code = match Code::try_from(prefix.mh_type) {
+		Ok(code @ (Code::Blake2b256 | Code::Sha2_256)) => code,
+		_ => return Err(BitswapError::UnsupportedMultihash(prefix.mh_type)),
+	};
+	let multihash = code.digest(data);
+	if multihash.size() != prefix.mh_len {
+		return Err(BitswapError::UnsupportedMultihash(prefix.mh_type))
+	}
+
+	Ok(Cid::new(prefix.version, prefix.codec, multihash)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::schema::bitswap::message::Block as MessageBlock;
+	use async_trait::async_trait;
+	use futures::channel::oneshot;
+	use sc_network::request_responses::RequestFailure;
+	use substrate_test_runtime::Block;
+
+	/// Network answering every request with the given blocks.
+	struct TestNetwork(Vec<MessageBlock>);
+
+	impl TestNetwork {
+		fn response(&self) -> Vec<u8> {
+			BitswapMessage { payload: self.0.clone(), ..Default::default() }.encode_to_vec()
+		}
+	}
+
+	#[async_trait]
+	impl NetworkRequest for TestNetwork {
+		async fn request(
+			&self,
+			_target: PeerId,
+			_protocol: ProtocolName,
+			_request: Vec<u8>,
+			_connect: IfDisconnected,
+		) -> Result<Vec<u8>, RequestFailure> {
+			Ok(self.response())
+		}
+
+		fn start_request(
+			&self,
+			_target: PeerId,
+			_protocol: ProtocolName,
+			_request: Vec<u8>,
+			tx: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+			_connect: IfDisconnected,
+		) {
+			let _ = tx.send(Ok(self.response()));
+		}
+	}
+
+	fn block_for(cid: &Cid, data: Vec<u8>) -> MessageBlock {
+		let prefix = Prefix {
+			version: cid.version(),
+			codec: cid.codec(),
+			mh_type: cid.hash().code(),
+			mh_len: cid.hash().size(),
+		};
+		MessageBlock { prefix: prefix.to_bytes(), data }
+	}
+
+	#[tokio::test]
+	async fn fetch_returns_verified_blocks() {
+		let data = vec![0x13, 0x37];
+		let blake2 = indexed_transaction_cid::<Block>(&sp_core::hashing::blake2_256(&data).into());
+		let sha2 = Cid::new_v1(RAW_CODEC, Code::Sha2_256.digest(&data));
+		let client = BitswapClient::new(Arc::new(TestNetwork(vec![
+			block_for(&blake2, data.clone()),
+			block_for(&sha2, data.clone()),
+		])));
+
+		let blocks = client.fetch(PeerId::random(), &[blake2, sha2]).await.unwrap();
+
+		assert_eq!(blocks, HashMap::from([(blake2, data.clone()), (sha2, data)]));
+	}
+
+	#[tokio::test]
+	async fn fetch_drops_blocks_not_matching_the_cid() {
+		let cid = indexed_transaction_cid::<Block>(&sp_core::hashing::blake2_256(&[1]).into());
+		let client = BitswapClient::new(Arc::new(TestNetwork(vec![block_for(&cid, vec![2])])));
+
+		let blocks = client.fetch(PeerId::random(), &[cid]).await.unwrap();
+
+		assert!(blocks.is_empty());
+	}
+
+	#[test]
+	fn size_chunks_keep_responses_below_the_packet_size() {
+		let cid =
+			|i: u8| indexed_transaction_cid::<Block>(&sp_core::hashing::blake2_256(&[i]).into());
+		let max = MAX_PACKET_SIZE as usize;
+		let lengths = |cids: &[(Cid, usize)]| {
+			size_chunks(cids).into_iter().map(|chunk| chunk.len()).collect::<Vec<_>>()
+		};
+
+		// Small blocks are only limited by the number of wanted blocks.
+		let small = (0..40).map(|i| (cid(i), 1024)).collect::<Vec<_>>();
+		assert_eq!(lengths(&small), vec![16, 16, 8]);
+
+		// Large blocks are split by their size, a block too large for a response goes alone.
+		let large =
+			vec![(cid(0), max / 2), (cid(1), max / 2), (cid(2), max / 4), (cid(3), max * 2)];
+		assert_eq!(lengths(&large), vec![1, 2, 1]);
+
+		assert!(size_chunks(&[]).is_empty());
+	}
+}
@@ -14,11 +14,14 @@
 // You should have received a copy of the GNU General Public License
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
-//! Bitswap server for Substrate.
+//! Bitswap server and client for Substrate.
 //!
 //! Allows querying transactions by hash over standard bitswap protocol
 //! Only supports bitswap 1.2.0.
 //! CID is expected to reference 256-bit Blake2b transaction hash.
+//!
+//! [`BitswapClient`] fetches blocks by CID from peers, and [`IndexedTransactionsBackfill`] uses it
+//! to download indexed transactions a node missed while syncing in storage chain mode.
 
 use cid::{self, Version};
 use futures::StreamExt;
@@ -27,7 +30,7 @@ use log::{debug, error, trace};
 use prost::Message;
 use sc_client_api::BlockBackend;
 use sc_network::{
-	request_responses::{IncomingRequest, OutgoingResponse, ProtocolConfig},
+	request_responses::{IncomingRequest, OutgoingResponse, ProtocolConfig, RequestFailure},
 	types::ProtocolName,
 };
 use schema::bitswap::{
@@ -36,10 +39,17 @@ use schema::bitswap::{
 };
 use sp_runtime::traits::Block as BlockT;
 use std::{io, sync::Arc, time::Duration};
-use unsigned_varint::encode as varint_encode;
+use unsigned_varint::{decode as varint_decode, encode as varint_encode};
 
+mod backfill;
+mod client;
 mod schema;
 
+pub use backfill::{
+	IndexedTransactionsBackfill, IndexedTransactionsProvider, TransactionStoragePallet,
+};
+pub use client::{indexed_transaction_cid, BitswapClient};
+
 const LOG_TARGET: &str = "bitswap";
 
 // Undocumented, but according to JS the bitswap messages have a max size of 512*1024 bytes
@@ -88,6 +98,30 @@ impl Prefix {
 		res.extend_from_slice(mh_len);
 		res
 	}
+
+	/// Decode the prefix from its encoded bytes.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, BitswapError> {
+		let decode_error = |_| BitswapError::DecodePrefix;
+		let (version, rest) = varint_decode::u64(bytes).map_err(decode_error)?;
+		let (codec, rest) = varint_decode::u64(rest).map_err(decode_error)?;
+		let (mh_type, rest) = varint_decode::u64(rest).map_err(decode_error)?;
+		let (mh_len, _) = varint_decode::u8(rest).map_err(decode_error)?;
+		Ok(Self { version: Version::try_from(version)?, codec, mh_type, mh_len })
+	}
+}
+
+/// Configuration of the bitswap protocol, serving requests received on `inbound_queue`, if any.
+fn protocol_config(
+	inbound_queue: Option<async_channel::Sender<IncomingRequest>>,
+) -> ProtocolConfig {
+	ProtocolConfig {
+		name: ProtocolName::from(PROTOCOL_NAME),
+		fallback_names: vec![],
+		max_request_size: MAX_PACKET_SIZE,
+		max_response_size: MAX_PACKET_SIZE,
+		request_timeout: Duration::from_secs(15),
+		inbound_queue,
+	}
 }
 
 /// Bitswap request handler
@@ -101,16 +135,7 @@ impl<B: BlockT> BitswapRequestHandler<B> {
 	pub fn new(client: Arc<dyn BlockBackend<B> + Send + Sync>) -> (Self, ProtocolConfig) {
 		let (tx, request_receiver) = async_channel::bounded(MAX_REQUEST_QUEUE);
 
-		let config = ProtocolConfig {
-			name: ProtocolName::from(PROTOCOL_NAME),
-			fallback_names: vec![],
-			max_request_size: MAX_PACKET_SIZE,
-			max_response_size: MAX_PACKET_SIZE,
-			request_timeout: Duration::from_secs(15),
-			inbound_queue: Some(tx),
-		};
-
-		(Self { client, request_receiver }, config)
+		(Self { client, request_receiver }, protocol_config(Some(tx)))
 	}
 
 	/// Run [`BitswapRequestHandler`].
@@ -284,6 +309,18 @@ pub enum BitswapError {
 	/// Too many blocks requested.
 	#[error("Too many block entries in the request.")]
 	TooManyEntries,
+
+	/// Outgoing request failed.
+	#[error(transparent)]
+	Request(#[from] RequestFailure),
+
+	/// Error decoding a CID prefix.
+	#[error("Failed to decode CID prefix.")]
+	DecodePrefix,
+
+	/// Received block uses a multihash we can't verify.
+	#[error("Unsupported multihash {0:#x}.")]
+	UnsupportedMultihash(u64),
 }
 
 #[cfg(test)]
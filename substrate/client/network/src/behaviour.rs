@@ -32,7 +32,7 @@ use crate::{
 use futures::channel::oneshot;
 use libp2p::{
 	core::Multiaddr, identify::Info as IdentifyInfo, identity::PublicKey, kad::RecordKey,
-	swarm::NetworkBehaviour, PeerId,
+	multiaddr::multihash::Multihash, swarm::NetworkBehaviour, PeerId,
 };
 
 use parking_lot::Mutex;
@@ -175,6 +175,7 @@ impl<B: BlockT> Behaviour<B> {
 		request_response_protocols: Vec<ProtocolConfig>,
 		peer_store_handle: PeerStoreHandle,
 		external_addresses: Arc<Mutex<HashSet<Multiaddr>>>,
+		webrtc_certhash: Option<Multihash>,
 	) -> Result<Self, request_responses::RegisterError> {
 		Ok(Self {
 			substrate,
@@ -182,6 +183,7 @@ impl<B: BlockT> Behaviour<B> {
 				user_agent,
 				local_public_key,
 				external_addresses,
+				webrtc_certhash,
 			),
 			discovery: disco_config.finish(),
 			request_responses: request_responses::RequestResponsesBehaviour::new(
@@ -34,7 +34,9 @@ pub use crate::{
 pub use libp2p::{
 	build_multiaddr,
 	identity::{self, ed25519, Keypair},
-	multiaddr, Multiaddr, PeerId,
+	multiaddr,
+	webrtc::tokio::Certificate as WebRTCCertificate,
+	Multiaddr, PeerId,
 };
 
 use crate::peer_store::PeerStoreHandle;
@@ -251,6 +253,15 @@ pub enum TransportConfig {
 		/// [RFC1918](https://tools.ietf.org/html/rfc1918)). Irrelevant for addresses that have
 		/// been passed in `::sc_network::config::NetworkConfiguration::boot_nodes`.
 		allow_private_ip: bool,
+
+		/// If true, the node can listen on and dial QUIC (`/udp/.../quic-v1`) addresses.
+		enable_quic: bool,
+
+		/// If true, the node can listen on and dial WebRTC (`/udp/.../webrtc-direct`) addresses.
+		///
+		/// The certificate of the transport is read from or persisted to
+		/// [`NetworkConfiguration::webrtc_certificate`] only if this is enabled.
+		enable_webrtc: bool,
 	},
 
 	/// Only allow connections within the same process.
@@ -311,6 +322,7 @@ pub enum Secret<K> {
 	/// of the file is determined by `K`:
 	///
 	///   * `ed25519::SecretKey`: An unencoded 32 bytes Ed25519 secret key.
+	///   * `WebRTCCertificate`: A PEM encoded certificate followed by its PEM encoded private key.
 	File(PathBuf),
 	/// Always generate a new secret key `K`.
 	New,
@@ -356,7 +368,7 @@ impl NodeKeyConfig {
 					Some(s) => ed25519::SecretKey::try_from_bytes(s),
 					_ => ed25519::SecretKey::try_from_bytes(&mut b),
 				},
-				ed25519::SecretKey::generate,
+				|| Ok(ed25519::SecretKey::generate()),
 				|b| b.as_ref().to_vec(),
 			)
 			.map(ed25519::Keypair::from)
@@ -365,6 +377,36 @@ impl NodeKeyConfig {
 	}
 }
 
+impl Secret<WebRTCCertificate> {
+	/// Evaluate the configuration to obtain the certificate of the WebRTC transport, following
+	/// the same rules as [`NodeKeyConfig::into_keypair`].
+	///
+	/// The fingerprint of the certificate is part of the node's WebRTC addresses, so the
+	/// certificate must be persisted for these addresses to survive a restart.
+	pub fn into_certificate(self) -> io::Result<WebRTCCertificate> {
+		let generate = || {
+			WebRTCCertificate::generate(&mut rand::thread_rng())
+				.map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+		};
+
+		match self {
+			Secret::New => generate(),
+			Secret::Input(certificate) => Ok(certificate),
+			Secret::File(f) => get_secret(
+				f,
+				|b| {
+					let pem = str::from_utf8(b)
+						.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+					WebRTCCertificate::from_pem(pem)
+						.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+				},
+				generate,
+				|certificate| certificate.serialize_pem().into_bytes(),
+			),
+		}
+	}
+}
+
 /// Load a secret key from a file, if it exists, or generate a
 /// new secret key and write it to that file. In either case,
 /// the secret key is returned.
@@ -372,7 +414,7 @@ fn get_secret<P, F, G, E, W, K>(file: P, parse: F, generate: G, serialize: W) ->
 where
 	P: AsRef<Path>,
 	F: for<'r> FnOnce(&'r mut [u8]) -> Result<K, E>,
-	G: FnOnce() -> K,
+	G: FnOnce() -> io::Result<K>,
 	E: Error + Send + Sync + 'static,
 	W: Fn(&K) -> Vec<u8>,
 {
@@ -383,7 +425,7 @@ where
 		.or_else(|e| {
 			if e.kind() == io::ErrorKind::NotFound {
 				file.as_ref().parent().map_or(Ok(()), fs::create_dir_all)?;
-				let sk = generate();
+				let sk = generate()?;
 				let mut sk_vec = serialize(&sk);
 				write_secret_file(file, &sk_vec)?;
 				sk_vec.zeroize();
@@ -587,6 +629,12 @@ pub struct NetworkConfiguration {
 	/// The node key configuration, which determines the node's network identity keypair.
 	pub node_key: NodeKeyConfig,
 
+	/// The configuration of the certificate used by the WebRTC transport.
+	///
+	/// Only `/webrtc-direct` addresses carrying the fingerprint of this certificate can be dialed,
+	/// hence it should be persisted next to the node key.
+	pub webrtc_certificate: Secret<WebRTCCertificate>,
+
 	/// Configuration for the default set of nodes used for block syncing and transactions.
 	pub default_peers_set: SetConfig,
 
@@ -672,11 +720,17 @@ impl NetworkConfiguration {
 			public_addresses: Vec::new(),
 			boot_nodes: Vec::new(),
 			node_key,
+			webrtc_certificate: Secret::New,
 			default_peers_set_num_full: default_peers_set.in_peers + default_peers_set.out_peers,
 			default_peers_set,
 			client_version: client_version.into(),
 			node_name: node_name.into(),
-			transport: TransportConfig::Normal { enable_mdns: false, allow_private_ip: true },
+			transport: TransportConfig::Normal {
+				enable_mdns: false,
+				allow_private_ip: true,
+				enable_quic: true,
+				enable_webrtc: true,
+			},
 			max_parallel_downloads: 5,
 			max_blocks_per_request: 64,
 			sync_mode: SyncMode::Full,
@@ -819,6 +873,16 @@ mod tests {
 		assert!(file.is_file() && secret_bytes(kp1) == secret_bytes(kp2))
 	}
 
+	#[test]
+	fn test_webrtc_certificate_file() {
+		let tmp = tempdir_with_prefix("x");
+		let file = tmp.path().join("webrtc_certificate.pem");
+		let cert1 = Secret::<WebRTCCertificate>::File(file.clone()).into_certificate().unwrap();
+		let cert2 = Secret::<WebRTCCertificate>::File(file.clone()).into_certificate().unwrap();
+		assert!(file.is_file());
+		assert_eq!(cert1.fingerprint(), cert2.fingerprint());
+	}
+
 	#[test]
 	fn test_secret_input() {
 		let sk = ed25519::SecretKey::generate();
@@ -46,7 +46,7 @@
 //! active mechanism that asks nodes for the addresses they are listening on. Whenever we learn
 //! of a node's address, you must call `add_self_reported_address`.
 
-use crate::{config::ProtocolId, utils::LruHashSet};
+use crate::{config::ProtocolId, transport, utils::LruHashSet};
 
 use array_bytes::bytes2hex;
 use futures::prelude::*;
@@ -61,11 +61,11 @@ use libp2p::{
 		KademliaEvent, QueryId, QueryResult, Quorum, Record, RecordKey,
 	},
 	mdns::{self, tokio::Behaviour as TokioMdns},
-	multiaddr::Protocol,
+	multiaddr::{multihash::Multihash, Protocol},
 	swarm::{
 		behaviour::{
 			toggle::{Toggle, ToggleConnectionHandler},
-			DialFailure, FromSwarm, NewExternalAddr,
+			DialFailure, ExpiredExternalAddr, FromSwarm, NewExternalAddr,
 		},
 		ConnectionDenied, ConnectionId, DialError, NetworkBehaviour, PollParameters, THandler,
 		THandlerInEvent, THandlerOutEvent, ToSwarm,
@@ -106,6 +106,7 @@ pub struct DiscoveryConfig {
 	kademlia_disjoint_query_paths: bool,
 	kademlia_protocols: Vec<Vec<u8>>,
 	kademlia_replication_factor: NonZeroUsize,
+	webrtc_certhash: Option<Multihash>,
 }
 
 impl DiscoveryConfig {
@@ -123,6 +124,7 @@ impl DiscoveryConfig {
 			kademlia_protocols: Vec::new(),
 			kademlia_replication_factor: NonZeroUsize::new(DEFAULT_KADEMLIA_REPLICATION_FACTOR)
 				.expect("value is a constant; constant is non-zero; qed."),
+			webrtc_certhash: None,
 		}
 	}
 
@@ -195,6 +197,13 @@ impl DiscoveryConfig {
 		self
 	}
 
+	/// Sets the fingerprint of the WebRTC certificate, which is inserted into the observed
+	/// `/webrtc-direct` external addresses.
+	pub fn with_webrtc_certhash(&mut self, value: Option<Multihash>) -> &mut Self {
+		self.webrtc_certhash = value;
+		self
+	}
+
 	/// Create a `DiscoveryBehaviour` from this config.
 	pub fn finish(self) -> DiscoveryBehaviour {
 		let Self {
@@ -208,6 +217,7 @@ impl DiscoveryConfig {
 			kademlia_disjoint_query_paths,
 			kademlia_protocols,
 			kademlia_replication_factor,
+			webrtc_certhash,
 		} = self;
 
 		let kademlia = if !kademlia_protocols.is_empty() {
@@ -265,6 +275,7 @@ impl DiscoveryConfig {
 					.expect("value is a constant; constant is non-zero; qed."),
 			),
 			records_to_publish: Default::default(),
+			webrtc_certhash,
 		}
 	}
 }
@@ -308,6 +319,9 @@ pub struct DiscoveryBehaviour {
 	/// did not return the record(in `FinishedWithNoAdditionalRecord`). We will then put the record
 	/// to these peers.
 	records_to_publish: HashMap<QueryId, Record>,
+	/// Fingerprint of the WebRTC certificate, inserted into the observed `/webrtc-direct`
+	/// external addresses. `None` if WebRTC is disabled.
+	webrtc_certhash: Option<Multihash>,
 }
 
 impl DiscoveryBehaviour {
@@ -451,6 +465,16 @@ impl DiscoveryBehaviour {
 		};
 		ip.is_global()
 	}
+
+	/// Returns the external address as advertised to other nodes.
+	///
+	/// Addresses observed by remotes lack the fingerprint of our WebRTC certificate.
+	fn advertised_address(&self, addr: &Multiaddr) -> Multiaddr {
+		match &self.webrtc_certhash {
+			Some(certhash) => transport::with_webrtc_certhash(addr.clone(), certhash),
+			None => addr.clone(),
+		}
+	}
 }
 
 /// Event generated by the `DiscoveryBehaviour`.
@@ -627,10 +651,12 @@ impl NetworkBehaviour for DiscoveryBehaviour {
 			FromSwarm::ListenerError(e) => {
 				self.kademlia.on_swarm_event(FromSwarm::ListenerError(e));
 			},
-			FromSwarm::ExpiredExternalAddr(e) => {
+			FromSwarm::ExpiredExternalAddr(ExpiredExternalAddr { addr }) => {
 				// We intentionally don't remove the element from `known_external_addresses` in
 				// order to not print the log line again.
 
+				let addr = self.advertised_address(addr);
+				let e = ExpiredExternalAddr { addr: &addr };
 				self.kademlia.on_swarm_event(FromSwarm::ExpiredExternalAddr(e));
 			},
 			FromSwarm::NewListener(e) => {
@@ -639,10 +665,11 @@ impl NetworkBehaviour for DiscoveryBehaviour {
 			FromSwarm::ExpiredListenAddr(e) => {
 				self.kademlia.on_swarm_event(FromSwarm::ExpiredListenAddr(e));
 			},
-			FromSwarm::NewExternalAddr(e @ NewExternalAddr { addr }) => {
+			FromSwarm::NewExternalAddr(NewExternalAddr { addr }) => {
+				let addr = self.advertised_address(addr);
 				let new_addr = addr.clone().with(Protocol::P2p(self.local_peer_id.into()));
 
-				if Self::can_add_to_dht(addr) {
+				if Self::can_add_to_dht(&addr) {
 					// NOTE: we might re-discover the same address multiple times
 					// in which case we just want to refrain from logging.
 					if self.known_external_addresses.insert(new_addr.clone()) {
@@ -654,6 +681,7 @@ impl NetworkBehaviour for DiscoveryBehaviour {
 					}
 				}
 
+				let e = NewExternalAddr { addr: &addr };
 				self.kademlia.on_swarm_event(FromSwarm::NewExternalAddr(e));
 			},
 			FromSwarm::AddressChange(e) => {
@@ -19,7 +19,7 @@
 //! [`PeerInfoBehaviour`] is implementation of `NetworkBehaviour` that holds information about peers
 //! in cache.
 
-use crate::utils::interval;
+use crate::{transport, utils::interval};
 use either::Either;
 
 use fnv::FnvHashMap;
@@ -31,11 +31,12 @@ use libp2p::{
 		Info as IdentifyInfo,
 	},
 	identity::PublicKey,
+	multiaddr::multihash::Multihash,
 	ping::{Behaviour as Ping, Config as PingConfig, Event as PingEvent, Success as PingSuccess},
 	swarm::{
 		behaviour::{
-			AddressChange, ConnectionClosed, ConnectionEstablished, DialFailure, FromSwarm,
-			ListenFailure,
+			AddressChange, ConnectionClosed, ConnectionEstablished, DialFailure,
+			ExpiredExternalAddr, FromSwarm, ListenFailure, NewExternalAddr,
 		},
 		ConnectionDenied, ConnectionHandler, ConnectionId, IntoConnectionHandlerSelect,
 		NetworkBehaviour, PollParameters, THandler, THandlerInEvent, THandlerOutEvent, ToSwarm,
@@ -71,6 +72,9 @@ pub struct PeerInfoBehaviour {
 	garbage_collect: Pin<Box<dyn Stream<Item = ()> + Send>>,
 	/// Record keeping of external addresses. Data is queried by the `NetworkService`.
 	external_addresses: ExternalAddresses,
+	/// Fingerprint of the WebRTC certificate, inserted into the observed `/webrtc-direct`
+	/// external addresses before they are advertised. `None` if WebRTC is disabled.
+	webrtc_certhash: Option<Multihash>,
 }
 
 /// Information about a node we're connected to.
@@ -119,6 +123,7 @@ impl PeerInfoBehaviour {
 		user_agent: String,
 		local_public_key: PublicKey,
 		external_addresses: Arc<Mutex<HashSet<Multiaddr>>>,
+		webrtc_certhash: Option<Multihash>,
 	) -> Self {
 		let identify = {
 			let cfg = IdentifyConfig::new("/substrate/1.0".to_string(), local_public_key)
@@ -134,6 +139,17 @@ impl PeerInfoBehaviour {
 			nodes_info: FnvHashMap::default(),
 			garbage_collect: Box::pin(interval(GARBAGE_COLLECT_INTERVAL)),
 			external_addresses: ExternalAddresses { addresses: external_addresses },
+			webrtc_certhash,
+		}
+	}
+
+	/// Returns the external address as advertised to other nodes.
+	///
+	/// Addresses observed by remotes lack the fingerprint of our WebRTC certificate.
+	fn advertised_address(&self, addr: &Multiaddr) -> Multiaddr {
+		match &self.webrtc_certhash {
+			Some(certhash) => transport::with_webrtc_certhash(addr.clone(), certhash),
+			None => addr.clone(),
 		}
 	}
 
@@ -378,7 +394,9 @@ impl NetworkBehaviour for PeerInfoBehaviour {
 				self.ping.on_swarm_event(FromSwarm::ListenerError(e));
 				self.identify.on_swarm_event(FromSwarm::ListenerError(e));
 			},
-			FromSwarm::ExpiredExternalAddr(e) => {
+			FromSwarm::ExpiredExternalAddr(ExpiredExternalAddr { addr }) => {
+				let addr = self.advertised_address(addr);
+				let e = ExpiredExternalAddr { addr: &addr };
 				self.ping.on_swarm_event(FromSwarm::ExpiredExternalAddr(e));
 				self.identify.on_swarm_event(FromSwarm::ExpiredExternalAddr(e));
 			},
@@ -391,10 +409,12 @@ impl NetworkBehaviour for PeerInfoBehaviour {
 				self.identify.on_swarm_event(FromSwarm::ExpiredListenAddr(e));
 				self.external_addresses.remove(e.addr);
 			},
-			FromSwarm::NewExternalAddr(e) => {
+			FromSwarm::NewExternalAddr(NewExternalAddr { addr }) => {
+				let addr = self.advertised_address(addr);
+				let e = NewExternalAddr { addr: &addr };
 				self.ping.on_swarm_event(FromSwarm::NewExternalAddr(e));
 				self.identify.on_swarm_event(FromSwarm::NewExternalAddr(e));
-				self.external_addresses.add(e.addr.clone());
+				self.external_addresses.add(addr);
 			},
 			FromSwarm::AddressChange(e @ AddressChange { peer_id, old, new, .. }) => {
 				self.ping.on_swarm_event(FromSwarm::AddressChange(e));
@@ -220,10 +220,19 @@ where
 			local_peer_id.to_base58(),
 		);
 
+		let webrtc_certificate = match network_config.transport {
+			TransportConfig::Normal { enable_webrtc: true, .. } =>
+				Some(network_config.webrtc_certificate.clone().into_certificate()?),
+			_ => None,
+		};
+		let webrtc_certhash = webrtc_certificate
+			.as_ref()
+			.map(|certificate| certificate.fingerprint().to_multihash());
+
 		let (transport, bandwidth) = {
-			let config_mem = match network_config.transport {
-				TransportConfig::MemoryOnly => true,
-				TransportConfig::Normal { .. } => false,
+			let (config_mem, enable_quic) = match network_config.transport {
+				TransportConfig::MemoryOnly => (true, false),
+				TransportConfig::Normal { enable_quic, .. } => (false, enable_quic),
 			};
 
 			// The yamux buffer size limit is configured to be equal to the maximum frame size
@@ -265,6 +274,8 @@ where
 				config_mem,
 				network_config.yamux_window_size,
 				yamux_maximum_buffer_size,
+				enable_quic,
+				webrtc_certificate,
 			)
 		};
 
@@ -407,6 +418,7 @@ where
 					network_config.kademlia_disjoint_query_paths,
 				);
 				config.with_kademlia_replication_factor(network_config.kademlia_replication_factor);
+				config.with_webrtc_certhash(webrtc_certhash);
 
 				match network_config.transport {
 					TransportConfig::MemoryOnly => {
@@ -435,6 +447,7 @@ where
 					request_response_protocols,
 					params.peer_store.clone(),
 					external_addresses.clone(),
+					webrtc_certhash,
 				);
 
 				match result {
@@ -498,11 +511,11 @@ where
 
 		// Add external addresses.
 		for addr in &network_config.public_addresses {
-			Swarm::<Behaviour<B>>::add_external_address(
-				&mut swarm,
-				addr.clone(),
-				AddressScore::Infinite,
-			);
+			let addr = match &webrtc_certhash {
+				Some(certhash) => transport::with_webrtc_certhash(addr.clone(), certhash),
+				None => addr.clone(),
+			};
+			Swarm::<Behaviour<B>>::add_external_address(&mut swarm, addr, AddressScore::Infinite);
 		}
 
 		let listen_addresses = Arc::new(Mutex::new(HashSet::new()));
@@ -25,9 +25,11 @@ use libp2p::{
 		transport::{Boxed, OptionalTransport},
 		upgrade,
 	},
-	dns, identity, noise, tcp, websocket, PeerId, Transport, TransportExt,
+	dns, identity,
+	multiaddr::{multihash::Multihash, Protocol},
+	noise, quic, tcp, webrtc, websocket, Multiaddr, PeerId, Transport, TransportExt,
 };
-use std::{sync::Arc, time::Duration};
+use std::{iter, sync::Arc, time::Duration};
 
 pub use libp2p::bandwidth::BandwidthSinks;
 
@@ -44,6 +46,11 @@ pub use libp2p::bandwidth::BandwidthSinks;
 /// high-level protocols combined, or to some generously high value if you are sure that a maximum
 /// size is enforced on all high-level protocols.
 ///
+/// Unless `memory_only` is true, QUIC (`/udp/.../quic-v1`) addresses are supported as well if
+/// `enable_quic` is true, and so are WebRTC (`/udp/.../webrtc-direct`) addresses if a
+/// `webrtc_certificate` is given. Both come with their own encryption and multiplexing and bypass
+/// the Noise/Yamux upgrade.
+///
 /// Returns a `BandwidthSinks` object that allows querying the average bandwidth produced by all
 /// the connections spawned with this transport.
 pub fn build_transport(
@@ -51,6 +58,8 @@ pub fn build_transport(
 	memory_only: bool,
 	yamux_window_size: Option<u32>,
 	yamux_maximum_buffer_size: usize,
+	enable_quic: bool,
+	webrtc_certificate: Option<webrtc::tokio::Certificate>,
 ) -> (Boxed<(PeerId, StreamMuxerBox)>, Arc<BandwidthSinks>) {
 	// Build the base layer of the transport.
 	let transport = if !memory_only {
@@ -100,7 +109,78 @@ pub fn build_transport(
 		.authenticate(authentication_config)
 		.multiplex(multiplexing_config)
 		.timeout(Duration::from_secs(20))
-		.boxed();
+		.map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer)));
+
+	if memory_only {
+		return transport.boxed().with_bandwidth_logging()
+	}
+
+	let quic = if enable_quic {
+		OptionalTransport::some(
+			quic::tokio::Transport::new(quic::Config::new(&keypair))
+				.map(|(peer_id, connection), _| (peer_id, StreamMuxerBox::new(connection))),
+		)
+	} else {
+		OptionalTransport::none()
+	};
+	let webrtc = match webrtc_certificate {
+		Some(certificate) => OptionalTransport::some(
+			webrtc::tokio::Transport::new(keypair, certificate)
+				.map(|(peer_id, connection), _| (peer_id, StreamMuxerBox::new(connection))),
+		),
+		None => OptionalTransport::none(),
+	};
+
+	quic.or_transport(webrtc)
+		.or_transport(transport)
+		.map(|output, _| output.map_left(Either::into_inner).into_inner())
+		.boxed()
+		.with_bandwidth_logging()
+}
+
+/// Inserts the fingerprint of the WebRTC certificate into a `/webrtc-direct` address lacking it.
+///
+/// Remotes need the fingerprint to authenticate the node when dialing, so addresses handed out to
+/// other nodes must carry it.
+pub(crate) fn with_webrtc_certhash(address: Multiaddr, certhash: &Multihash) -> Multiaddr {
+	let is_webrtc = address.iter().any(|protocol| matches!(protocol, Protocol::WebRTCDirect));
+	if !is_webrtc || address.iter().any(|protocol| matches!(protocol, Protocol::Certhash(_))) {
+		return address
+	}
+
+	address
+		.iter()
+		.flat_map(|protocol| {
+			let certhash =
+				matches!(protocol, Protocol::WebRTCDirect).then(|| Protocol::Certhash(*certhash));
+			iter::once(protocol).chain(certhash)
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn webrtc_certhash_is_inserted_before_peer_id() {
+		let certificate = webrtc::tokio::Certificate::generate(&mut rand::thread_rng()).unwrap();
+		let certhash = certificate.fingerprint().to_multihash();
+		let peer_id = PeerId::random();
+
+		let address: Multiaddr =
+			format!("/ip4/1.2.3.4/udp/30333/webrtc-direct/p2p/{peer_id}").parse().unwrap();
+		let expected = Multiaddr::empty()
+			.with(Protocol::Ip4([1, 2, 3, 4].into()))
+			.with(Protocol::Udp(30333))
+			.with(Protocol::WebRTCDirect)
+			.with(Protocol::Certhash(certhash))
+			.with(Protocol::P2p(peer_id.into()));
+		assert_eq!(with_webrtc_certhash(address, &certhash), expected);
 
-	transport.with_bandwidth_logging()
+		// Addresses which already carry a fingerprint or aren't WebRTC ones are left untouched.
+		assert_eq!(with_webrtc_certhash(expected.clone(), &certhash), expected);
+		let tcp: Multiaddr = "/ip4/1.2.3.4/tcp/30333".parse().unwrap();
+		assert_eq!(with_webrtc_certhash(tcp.clone(), &certhash), tcp);
+	}
 }
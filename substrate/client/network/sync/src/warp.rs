@@ -24,7 +24,7 @@ use crate::{
 	schema::v1::{StateRequest, StateResponse},
 	state::{ImportResult, StateSync},
 };
-use codec::{Decode, Encode};
+use codec::{Decode, DecodeAll, Encode};
 use futures::channel::oneshot;
 use log::error;
 use sc_client_api::ProofProvider;
@@ -48,12 +48,30 @@ pub struct WarpProofRequest<B: BlockT> {
 	pub begin: B::Hash,
 }
 
+/// Authorities a warp proof is verified against.
+///
+/// Warp sync only passes them along: they are encoded and interpreted by the
+/// [`WarpSyncProvider`], so that any finality gadget can provide warp proofs.
+#[derive(Encode, Decode, Debug, Clone, Default, PartialEq, Eq)]
+pub struct EncodedAuthorities(pub Vec<u8>);
+
+/// Trusted block to start downloading warp proofs from, instead of genesis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WarpSyncCheckpoint<Block: BlockT> {
+	/// Hash of the trusted block.
+	pub hash: Block::Hash,
+	/// Id of the authority set that finalizes the blocks following the trusted one.
+	pub set_id: SetId,
+	/// The authorities of that set.
+	pub authorities: EncodedAuthorities,
+}
+
 /// Proof verification result.
 pub enum VerificationResult<Block: BlockT> {
 	/// Proof is valid, but the target was not reached.
-	Partial(SetId, AuthorityList, Block::Hash),
+	Partial(SetId, EncodedAuthorities, Block::Hash),
 	/// Target finality is proved.
-	Complete(SetId, AuthorityList, Block::Header),
+	Complete(SetId, EncodedAuthorities, Block::Header),
 }
 
 /// Warp sync backend. Handles retrieving and verifying warp sync proofs.
@@ -69,11 +87,110 @@ pub trait WarpSyncProvider<Block: BlockT>: Send + Sync {
 		&self,
 		proof: &EncodedProof,
 		set_id: SetId,
-		authorities: AuthorityList,
+		authorities: EncodedAuthorities,
 	) -> Result<VerificationResult<Block>, Box<dyn std::error::Error + Send + Sync>>;
 	/// Get current list of authorities. This is supposed to be genesis authorities when starting
 	/// sync.
-	fn current_authorities(&self) -> AuthorityList;
+	fn current_authorities(&self) -> EncodedAuthorities;
+	/// Trusted block to request the first proof from.
+	///
+	/// When `None`, proofs are requested from genesis and verified against the
+	/// [`current_authorities`](Self::current_authorities) with set id `0`.
+	fn checkpoint(&self) -> Option<WarpSyncCheckpoint<Block>> {
+		None
+	}
+}
+
+/// Warp sync proof of a chain without a finality gadget: the header of a trusted checkpoint.
+///
+/// Nodes serve the header of the checkpoint block, and syncing nodes accept it if its hash matches
+/// their own checkpoint. Warp sync then downloads the state of the checkpoint block.
+pub struct TrustedCheckpointProvider<Block: BlockT> {
+	checkpoint: Block::Hash,
+	client: Arc<dyn HeaderBackend<Block>>,
+}
+
+impl<Block: BlockT> TrustedCheckpointProvider<Block> {
+	/// Create a provider for the trusted block `checkpoint`.
+	pub fn new(checkpoint: Block::Hash, client: Arc<dyn HeaderBackend<Block>>) -> Self {
+		Self { checkpoint, client }
+	}
+}
+
+impl<Block: BlockT> WarpSyncProvider<Block> for TrustedCheckpointProvider<Block> {
+	fn generate(
+		&self,
+		_start: Block::Hash,
+	) -> Result<EncodedProof, Box<dyn std::error::Error + Send + Sync>> {
+		let header = self
+			.client
+			.header(self.checkpoint)?
+			.ok_or_else(|| format!("Missing checkpoint header {:?}", self.checkpoint))?;
+		Ok(EncodedProof(header.encode()))
+	}
+
+	fn verify(
+		&self,
+		proof: &EncodedProof,
+		set_id: SetId,
+		authorities: EncodedAuthorities,
+	) -> Result<VerificationResult<Block>, Box<dyn std::error::Error + Send + Sync>> {
+		let header = Block::Header::decode_all(&mut proof.0.as_slice())
+			.map_err(|e| format!("Proof decoding error: {e}"))?;
+		if header.hash() != self.checkpoint {
+			return Err(format!("Header {:?} is not the checkpoint", header.hash()).into())
+		}
+		Ok(VerificationResult::Complete(set_id, authorities, header))
+	}
+
+	fn current_authorities(&self) -> EncodedAuthorities {
+		EncodedAuthorities::default()
+	}
+}
+
+/// Seeds the warp proofs of another provider with a trusted checkpoint.
+///
+/// Proofs are requested from the checkpoint and verified against its authorities, so the
+/// authority set changes before it don't need to be proven, or even be provable.
+pub struct CheckpointWarpSyncProvider<Block: BlockT> {
+	checkpoint: WarpSyncCheckpoint<Block>,
+	inner: Arc<dyn WarpSyncProvider<Block>>,
+}
+
+impl<Block: BlockT> CheckpointWarpSyncProvider<Block> {
+	/// Start the proofs of `inner` at `checkpoint`.
+	pub fn new(
+		checkpoint: WarpSyncCheckpoint<Block>,
+		inner: Arc<dyn WarpSyncProvider<Block>>,
+	) -> Self {
+		Self { checkpoint, inner }
+	}
+}
+
+impl<Block: BlockT> WarpSyncProvider<Block> for CheckpointWarpSyncProvider<Block> {
+	fn generate(
+		&self,
+		start: Block::Hash,
+	) -> Result<EncodedProof, Box<dyn std::error::Error + Send + Sync>> {
+		self.inner.generate(start)
+	}
+
+	fn verify(
+		&self,
+		proof: &EncodedProof,
+		set_id: SetId,
+		authorities: EncodedAuthorities,
+	) -> Result<VerificationResult<Block>, Box<dyn std::error::Error + Send + Sync>> {
+		self.inner.verify(proof, set_id, authorities)
+	}
+
+	fn current_authorities(&self) -> EncodedAuthorities {
+		self.checkpoint.authorities.clone()
+	}
+
+	fn checkpoint(&self) -> Option<WarpSyncCheckpoint<Block>> {
+		Some(self.checkpoint.clone())
+	}
 }
 
 /// Reported warp sync phase.
@@ -157,7 +274,7 @@ enum Phase<B: BlockT, Client> {
 	/// Downloading warp proofs.
 	WarpProof {
 		set_id: SetId,
-		authorities: AuthorityList,
+		authorities: EncodedAuthorities,
 		last_hash: B::Hash,
 		warp_sync_provider: Arc<dyn WarpSyncProvider<B>>,
 	},
@@ -202,12 +319,20 @@ where
 	/// authorities. Alternatively we can pass a target block when we want to skip downloading
 	/// proofs, in this case we will continue polling until the target block is known.
 	pub fn new(client: Arc<Client>, warp_sync_config: WarpSyncConfig<B>) -> Self {
-		let last_hash = client.hash(Zero::zero()).unwrap().expect("Genesis header always exists");
 		match warp_sync_config {
 			WarpSyncConfig::WithProvider(warp_sync_provider) => {
+				let (set_id, authorities, last_hash) = match warp_sync_provider.checkpoint() {
+					Some(WarpSyncCheckpoint { hash, set_id, authorities }) =>
+						(set_id, authorities, hash),
+					None => (
+						0,
+						warp_sync_provider.current_authorities(),
+						client.hash(Zero::zero()).unwrap().expect("Genesis header always exists"),
+					),
+				};
 				let phase = Phase::WarpProof {
-					set_id: 0,
-					authorities: warp_sync_provider.current_authorities(),
+					set_id,
+					authorities,
 					last_hash,
 					warp_sync_provider: warp_sync_provider.clone(),
 				};
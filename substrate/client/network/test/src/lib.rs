@@ -67,7 +67,8 @@ use sc_network_sync::{
 	service::{network::NetworkServiceProvider, syncing_service::SyncingService},
 	state_request_handler::StateRequestHandler,
 	warp::{
-		AuthorityList, EncodedProof, SetId, VerificationResult, WarpSyncParams, WarpSyncProvider,
+		EncodedAuthorities, EncodedProof, SetId, TrustedCheckpointProvider, VerificationResult,
+		WarpSyncParams, WarpSyncProvider,
 	},
 	warp_request_handler,
 };
@@ -661,13 +662,13 @@ impl<B: BlockT> WarpSyncProvider<B> for TestWarpSyncProvider<B> {
 		&self,
 		proof: &EncodedProof,
 		_set_id: SetId,
-		_authorities: AuthorityList,
+		_authorities: EncodedAuthorities,
 	) -> Result<VerificationResult<B>, Box<dyn std::error::Error + Send + Sync>> {
 		let EncodedProof(encoded) = proof;
 		let header = B::Header::decode(&mut encoded.as_slice()).unwrap();
 		Ok(VerificationResult::Complete(0, Default::default(), header))
 	}
-	fn current_authorities(&self) -> AuthorityList {
+	fn current_authorities(&self) -> EncodedAuthorities {
 		Default::default()
 	}
 }
@@ -699,6 +700,8 @@ pub struct FullPeerConfig {
 	pub storage_chain: bool,
 	/// Optional target block header to sync to
 	pub target_block: Option<<Block as BlockT>::Header>,
+	/// Optional trusted block to warp sync to, instead of the best block of the peers.
+	pub warp_sync_checkpoint: Option<<Block as BlockT>::Hash>,
 }
 
 #[async_trait::async_trait]
@@ -850,7 +853,11 @@ pub trait TestNetFactory: Default + Sized + Send {
 			protocol_config
 		};
 
-		let warp_sync = Arc::new(TestWarpSyncProvider(client.clone()));
+		let warp_sync: Arc<dyn WarpSyncProvider<Block>> = match config.warp_sync_checkpoint {
+			Some(checkpoint) =>
+				Arc::new(TrustedCheckpointProvider::new(checkpoint, client.clone())),
+			None => Arc::new(TestWarpSyncProvider(client.clone())),
+		};
 
 		let warp_sync_params = match config.target_block {
 			Some(target_block) => {
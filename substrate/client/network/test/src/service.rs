@@ -17,7 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use futures::prelude::*;
-use libp2p::{Multiaddr, PeerId};
+use libp2p::{multiaddr::Protocol, Multiaddr, PeerId};
 
 use sc_consensus::{ImportQueue, Link};
 use sc_network::{
@@ -623,6 +623,98 @@ async fn fallback_name_working() {
 	receiver.await.unwrap();
 }
 
+/// Waits for the `PROTOCOL_NAME` substream to open, accepting it if asked to.
+async fn wait_for_notification_stream(handle: &mut Box<dyn NotificationService>) {
+	loop {
+		match handle.next_event().await.unwrap() {
+			NotificationEvent::ValidateInboundSubstream { result_tx, .. } => {
+				result_tx.send(ValidationResult::Accept).unwrap();
+			},
+			NotificationEvent::NotificationStreamOpened { .. } => break,
+			_ => {},
+		}
+	}
+}
+
+/// Builds two nodes listening on `listen_addr` and connects them over the `PROTOCOL_NAME`
+/// protocol, the second node dialing the address the first one actually listens on.
+async fn connect_nodes_over(
+	listen_addr: Multiaddr,
+	public_addresses: Vec<Multiaddr>,
+) -> Arc<TestNetworkService> {
+	let network_config = |public_addresses| config::NetworkConfiguration {
+		listen_addresses: vec![listen_addr.clone()],
+		public_addresses,
+		transport: TransportConfig::Normal {
+			enable_mdns: false,
+			allow_private_ip: true,
+			enable_quic: true,
+			enable_webrtc: true,
+		},
+		..config::NetworkConfiguration::new_local()
+	};
+
+	let (network1, handle1) =
+		TestNetworkBuilder::new().with_config(network_config(public_addresses)).build();
+	let mut handle1 = handle1.unwrap();
+	let (node1, _) = network1.start_network();
+
+	// The port, and for WebRTC the certificate fingerprint, are only known once listening.
+	let node1_addr = loop {
+		if let Some(addr) = node1.listen_addresses().pop() {
+			break addr
+		}
+		tokio::time::sleep(Duration::from_millis(50)).await;
+	};
+
+	let (network2, handle2) = TestNetworkBuilder::new()
+		.with_config(network_config(Vec::new()))
+		.with_set_config(config::SetConfig {
+			reserved_nodes: vec![MultiaddrWithPeerId {
+				multiaddr: node1_addr,
+				peer_id: node1.local_peer_id(),
+			}],
+			..Default::default()
+		})
+		.build();
+	let mut handle2 = handle2.unwrap();
+	let _ = network2.start_network();
+
+	tokio::time::timeout(
+		Duration::from_secs(30),
+		future::join(
+			wait_for_notification_stream(&mut handle1),
+			wait_for_notification_stream(&mut handle2),
+		),
+	)
+	.await
+	.expect("nodes to connect");
+
+	node1
+}
+
+#[tokio::test]
+async fn connect_over_quic() {
+	let listen_addr = config::build_multiaddr![Ip4([127, 0, 0, 1]), Udp(0_u16), QuicV1];
+
+	let _ = connect_nodes_over(listen_addr, Vec::new()).await;
+}
+
+#[tokio::test]
+async fn connect_over_webrtc() {
+	let listen_addr = config::build_multiaddr![Ip4([127, 0, 0, 1]), Udp(0_u16), WebRTCDirect];
+	let public_address = config::build_multiaddr![Ip4([1, 2, 3, 4]), Udp(30333_u16), WebRTCDirect];
+
+	let node1 = connect_nodes_over(listen_addr, vec![public_address]).await;
+
+	// The advertised address must carry the certificate fingerprint to be dialable.
+	let external_addresses = node1.external_addresses();
+	assert!(!external_addresses.is_empty());
+	assert!(external_addresses
+		.iter()
+		.all(|addr| addr.iter().any(|protocol| matches!(protocol, Protocol::Certhash(_)))));
+}
+
 #[tokio::test]
 #[should_panic(expected = "don't match the transport")]
 async fn ensure_listen_addresses_consistent_with_transport_memory() {
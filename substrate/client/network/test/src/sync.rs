@@ -1287,6 +1287,70 @@ async fn warp_sync_to_target_block() {
 	.await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn warp_sync_to_trusted_checkpoint() {
+	sp_tracing::try_init_simple();
+	// The checkpoint is below the best block of the peers. Peers build the same blocks, so it
+	// is known before they serve it.
+	let checkpoint = TestNet::new(1).peer(0).push_blocks(64, false)[31];
+
+	let mut net = TestNet::new(0);
+	// Create 3 synced peers serving the checkpoint and 1 peer trying to warp sync.
+	for _ in 0..3 {
+		net.add_full_peer_with_config(FullPeerConfig {
+			warp_sync_checkpoint: Some(checkpoint),
+			..Default::default()
+		});
+	}
+	net.peer(0).push_blocks(64, false);
+	net.peer(1).push_blocks(64, false);
+	net.peer(2).push_blocks(64, false);
+
+	net.add_full_peer_with_config(FullPeerConfig {
+		sync_mode: SyncMode::Warp,
+		warp_sync_checkpoint: Some(checkpoint),
+		..Default::default()
+	});
+
+	net.run_until_sync().await;
+	assert!(!net.peer(3).client().has_state_at(&BlockId::Number(1)));
+	assert!(net.peer(3).client().has_state_at(&BlockId::Number(32)));
+	assert!(net.peer(3).client().has_state_at(&BlockId::Number(64)));
+	assert_eq!(net.peer(3).client().info().best_number, 64);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn warp_sync_rejects_mismatching_checkpoint() {
+	sp_tracing::try_init_simple();
+	let mut net = TestNet::new(0);
+	// Create 3 synced peers and 1 peer trying to warp sync to a block they don't have.
+	net.add_full_peer_with_config(Default::default());
+	net.add_full_peer_with_config(Default::default());
+	net.add_full_peer_with_config(Default::default());
+
+	net.peer(0).push_blocks(64, false);
+	net.peer(1).push_blocks(64, false);
+	net.peer(2).push_blocks(64, false);
+
+	net.add_full_peer_with_config(FullPeerConfig {
+		sync_mode: SyncMode::Warp,
+		warp_sync_checkpoint: Some(H256::random()),
+		..Default::default()
+	});
+
+	// The proofs served by the peers don't match the checkpoint, so nothing is imported.
+	let synced = futures::future::poll_fn::<(), _>(|cx| {
+		net.poll(cx);
+		if net.peer(3).client().info().best_number > 0 {
+			Poll::Ready(())
+		} else {
+			Poll::Pending
+		}
+	});
+	assert!(tokio::time::timeout(std::time::Duration::from_secs(10), synced).await.is_err());
+	assert!(!net.peer(3).client().has_state_at(&BlockId::Number(64)));
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn syncs_huge_blocks() {
 	use sp_core::storage::well_known_keys::HEAP_PAGES;
@@ -0,0 +1,207 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Automatic submission of the tasks of the runtime.
+//!
+//! Pallets declare service work with `#[pallet::task]`. The [`TaskSubmitter`] discovers all
+//! currently valid tasks through the [`TasksApi`] on every new best block and submits them as
+//! unsigned `do_task` transactions to the local transaction pool. At most
+//! [`MAX_TASKS_PER_BLOCK`] tasks are submitted per block, the remaining ones are picked up at
+//! one of the next blocks.
+
+use std::sync::Arc;
+
+use futures::{future::ready, prelude::*};
+use sc_client_api::BlockchainEvents;
+use sc_transaction_pool_api::{
+	error::{Error as PoolError, IntoPoolError},
+	LocalTransactionPool,
+};
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_core::traits::SpawnNamed;
+use sp_offchain::TasksApi;
+use sp_runtime::traits::Block as BlockT;
+
+const LOG_TARGET: &str = "offchain-tasks";
+
+/// The maximum number of tasks that are collected and submitted per new best block.
+const MAX_TASKS_PER_BLOCK: u32 = 64;
+
+/// Submits the valid tasks of the runtime on every new best block.
+pub struct TaskSubmitter<Client, Pool> {
+	client: Arc<Client>,
+	transaction_pool: Arc<Pool>,
+}
+
+impl<Client, Pool> TaskSubmitter<Client, Pool> {
+	/// Creates a new [`TaskSubmitter`].
+	pub fn new(client: Arc<Client>, transaction_pool: Arc<Pool>) -> Self {
+		Self { client, transaction_pool }
+	}
+}
+
+impl<Client, Pool> TaskSubmitter<Client, Pool>
+where
+	Pool: LocalTransactionPool + 'static,
+	Client: ProvideRuntimeApi<Pool::Block> + Send + Sync + 'static,
+	Client::Api: TasksApi<Pool::Block>,
+{
+	/// Submit the valid tasks on every new best block.
+	pub async fn run<BE: BlockchainEvents<Pool::Block>>(
+		self,
+		import_events: Arc<BE>,
+		spawner: impl SpawnNamed,
+	) {
+		import_events
+			.import_notification_stream()
+			.for_each(move |n| {
+				if n.is_new_best {
+					let client = self.client.clone();
+					let transaction_pool = self.transaction_pool.clone();
+
+					// Collecting and validating the tasks calls into the runtime.
+					spawner.spawn_blocking(
+						"offchain-tasks-on-block",
+						Some("offchain-worker"),
+						async move { submit_tasks(&*client, &*transaction_pool, n.hash) }.boxed(),
+					);
+				}
+
+				ready(())
+			})
+			.await;
+	}
+}
+
+/// Submit all tasks that are valid at the block `hash` to the `transaction_pool`.
+fn submit_tasks<Client, Pool>(
+	client: &Client,
+	transaction_pool: &Pool,
+	hash: <Pool::Block as BlockT>::Hash,
+) where
+	Pool: LocalTransactionPool,
+	Client: ProvideRuntimeApi<Pool::Block>,
+	Client::Api: TasksApi<Pool::Block>,
+{
+	let runtime = client.runtime_api();
+	match runtime.has_api::<dyn TasksApi<Pool::Block>>(hash) {
+		Ok(true) => {},
+		Ok(false) => {
+			tracing::trace!(target: LOG_TARGET, "Runtime at {hash:?} does not support tasks");
+			return
+		},
+		Err(e) => {
+			tracing::error!(target: LOG_TARGET, "Failed to check the tasks api at {hash:?}: {e}");
+			return
+		},
+	}
+
+	let extrinsics = match runtime.valid_task_extrinsics(hash, MAX_TASKS_PER_BLOCK) {
+		Ok(extrinsics) => extrinsics,
+		Err(e) => {
+			tracing::error!(target: LOG_TARGET, "Failed to collect the tasks at {hash:?}: {e}");
+			return
+		},
+	};
+	tracing::debug!(target: LOG_TARGET, "Submitting {} tasks at {hash:?}", extrinsics.len());
+
+	for extrinsic in extrinsics {
+		let Err(e) = transaction_pool.submit_local(hash, extrinsic) else { continue };
+
+		match e.into_pool_error() {
+			// Tasks remain valid until they are executed, so most of them were already submitted
+			// at one of the previous blocks.
+			Ok(PoolError::AlreadyImported(_) | PoolError::TemporarilyBanned) => {},
+			Ok(e) => tracing::debug!(target: LOG_TARGET, "Failed to submit task: {e}"),
+			Err(e) => tracing::debug!(target: LOG_TARGET, "Failed to submit task: {e}"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_transaction_pool::BasicPool;
+	use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
+	use sp_api::ApiRef;
+	use substrate_test_runtime_client::runtime::{Block, Extrinsic, ExtrinsicBuilder};
+
+	#[derive(Clone)]
+	struct TestApi {
+		tasks: Vec<Extrinsic>,
+	}
+
+	struct RuntimeApi {
+		tasks: Vec<Extrinsic>,
+	}
+
+	impl ProvideRuntimeApi<Block> for TestApi {
+		type Api = RuntimeApi;
+
+		fn runtime_api(&self) -> ApiRef<'_, Self::Api> {
+			RuntimeApi { tasks: self.tasks.clone() }.into()
+		}
+	}
+
+	sp_api::mock_impl_runtime_apis! {
+		impl TasksApi<Block> for RuntimeApi {
+			fn valid_task_extrinsics(max: u32) -> Vec<Extrinsic> {
+				self.tasks.iter().take(max as usize).cloned().collect()
+			}
+		}
+	}
+
+	fn task(i: u32) -> Extrinsic {
+		ExtrinsicBuilder::new_storage_change(i.to_le_bytes().to_vec(), Some(vec![1])).build()
+	}
+
+	#[test]
+	fn valid_tasks_are_submitted_to_the_pool() {
+		sp_tracing::try_init_simple();
+
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let pool =
+			BasicPool::new_full(Default::default(), true.into(), None, spawner, client.clone());
+		let hash = client.chain_info().genesis_hash;
+		let api = TestApi { tasks: (0..3).map(task).collect() };
+
+		submit_tasks(&api, &*pool, hash);
+		assert_eq!(pool.status().ready, 3);
+		let ready = pool.ready().map(|tx| tx.data().clone()).collect::<Vec<_>>();
+		assert!(api.tasks.iter().all(|task| ready.contains(task)));
+
+		// Tasks that are still valid at the next block are already in the pool.
+		submit_tasks(&api, &*pool, hash);
+		assert_eq!(pool.status().ready, 3);
+	}
+
+	#[test]
+	fn tasks_submitted_per_block_are_bounded() {
+		sp_tracing::try_init_simple();
+
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let pool =
+			BasicPool::new_full(Default::default(), true.into(), None, spawner, client.clone());
+		let hash = client.chain_info().genesis_hash;
+		let api = TestApi { tasks: (0..MAX_TASKS_PER_BLOCK * 2).map(task).collect() };
+
+		submit_tasks(&api, &*pool, hash);
+		assert_eq!(pool.status().ready, MAX_TASKS_PER_BLOCK as usize);
+	}
+}
@@ -49,6 +49,12 @@ pub enum Error {
 		/// Maximum allowed value
 		max: u32,
 	},
+	/// Too many storage iterations are running at the same time.
+	#[error("too many storage iterations, at most {} can run at the same time", .max)]
+	TooManyStorageIterations {
+		/// Maximum number of storage iterations
+		max: usize,
+	},
 	/// Call to an unsafe RPC was denied.
 	#[error(transparent)]
 	UnsafeRpcCalled(#[from] crate::policy::UnsafeRpcError),
@@ -66,6 +72,9 @@ impl From<Error> for JsonRpseeError {
 			Error::InvalidCount { .. } =>
 				CallError::Custom(ErrorObject::owned(BASE_ERROR + 2, e.to_string(), None::<()>))
 					.into(),
+			Error::TooManyStorageIterations { .. } =>
+				CallError::Custom(ErrorObject::owned(BASE_ERROR + 3, e.to_string(), None::<()>))
+					.into(),
 			e => Self::to_call_error(e),
 		}
 	}
@@ -19,7 +19,10 @@
 //! Substrate state API helpers.
 
 use serde::{Deserialize, Serialize};
-use sp_core::Bytes;
+use sp_core::{
+	storage::{PrefixedStorageKey, StorageData, StorageKey},
+	Bytes,
+};
 
 /// ReadProof struct returned by the RPC
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -30,3 +33,29 @@ pub struct ReadProof<Hash> {
 	/// A proof used to prove that storage entries are included in the storage trie
 	pub proof: Vec<Bytes>,
 }
+
+/// Position at which a storage iteration of `state_subscribeStorageIter` continues.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageIterCursor {
+	/// Continue in the main trie after the given key.
+	After(StorageKey),
+	/// Continue in the child trie stored under the given main trie key, after the given child
+	/// trie key or from its beginning. Once the child trie is done, the iteration continues in
+	/// the main trie after the child trie key.
+	Child(PrefixedStorageKey, Option<StorageKey>),
+}
+
+/// A page of key/value pairs sent by `state_subscribeStorageIter`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageIterPage<Hash> {
+	/// Block hash the pairs were read at.
+	pub at: Hash,
+	/// The child trie the pairs are stored in, `None` for the main trie.
+	pub child_storage_key: Option<PrefixedStorageKey>,
+	/// The key/value pairs in lexicographic order of the keys.
+	pub pairs: Vec<(StorageKey, StorageData)>,
+	/// The cursor to resume the iteration after this page, `None` if this is the last page.
+	pub cursor: Option<StorageIterCursor>,
+}
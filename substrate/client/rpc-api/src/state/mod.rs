@@ -28,7 +28,7 @@ use sp_version::RuntimeVersion;
 pub mod error;
 pub mod helpers;
 
-pub use self::helpers::ReadProof;
+pub use self::helpers::{ReadProof, StorageIterCursor, StorageIterPage};
 
 /// Substrate state API
 #[rpc(client, server)]
@@ -129,6 +129,34 @@ pub trait StateApi<Hash> {
 	)]
 	fn subscribe_storage(&self, keys: Option<Vec<StorageKey>>);
 
+	/// Stream the key/value pairs with the given prefix, leave empty to stream all the pairs.
+	///
+	/// The pairs are sent in pages of at most `page_size` pairs, each page containing pairs of a
+	/// single trie. If `include_child_tries` is set, the pairs of every child trie are sent right
+	/// after the main trie key the child trie is stored under. The last page has no cursor. An
+	/// interrupted iteration can be resumed by passing the `cursor` of the last received page.
+	///
+	/// Only a few pages are sent without being requested, more pages need to be requested with
+	/// `state_storageIterContinue`. The subscription is closed if no page is requested for a
+	/// while.
+	#[subscription(
+		name = "state_subscribeStorageIter" => "state_storageIter",
+		unsubscribe = "state_unsubscribeStorageIter",
+		item = StorageIterPage<Hash>,
+	)]
+	fn subscribe_storage_iter(
+		&self,
+		prefix: Option<StorageKey>,
+		include_child_tries: bool,
+		page_size: Option<u32>,
+		cursor: Option<StorageIterCursor>,
+		hash: Option<Hash>,
+	);
+
+	/// Request `pages` more pages of the given `state_subscribeStorageIter` subscription.
+	#[method(name = "state_storageIterContinue")]
+	fn storage_iter_continue(&self, subscription: String, pages: u32) -> RpcResult<()>;
+
 	/// The `traceBlock` RPC provides a way to trace the re-execution of a single
 	/// block, collecting Spans and Events from both the client and the relevant WASM runtime.
 	/// The Spans and Events are conceptually equivalent to those from the [Tracing][1] crate.
@@ -18,7 +18,13 @@
 
 //! API trait of the archive methods.
 
-use crate::MethodResult;
+use crate::{
+	common::events::{
+		ArchiveStorageDiffItem, ArchiveStorageDiffResult, ArchiveStorageResult,
+		PaginatedStorageQuery,
+	},
+	MethodResult,
+};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 
 #[rpc(client, server)]
@@ -88,4 +94,36 @@ pub trait ArchiveApi<Hash> {
 		function: String,
 		call_parameters: String,
 	) -> RpcResult<MethodResult>;
+
+	/// Returns storage entries at a specific block's state.
+	///
+	/// Descendants queries return at most a configured number of items. The iteration can be
+	/// resumed by providing the last reported key as `paginationStartKey`.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "archive_unstable_storage", blocking)]
+	fn archive_unstable_storage(
+		&self,
+		hash: Hash,
+		items: Vec<PaginatedStorageQuery<String>>,
+		child_trie: Option<String>,
+	) -> RpcResult<ArchiveStorageResult>;
+
+	/// Returns the storage keys that have been added, modified or removed between two blocks.
+	///
+	/// The `previous_hash` defaults to the parent of the provided block. If no items are
+	/// provided, the entire main trie is compared.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "archive_unstable_storageDiff", blocking)]
+	fn archive_unstable_storage_diff(
+		&self,
+		hash: Hash,
+		items: Vec<ArchiveStorageDiffItem<String>>,
+		previous_hash: Option<Hash>,
+	) -> RpcResult<ArchiveStorageDiffResult>;
 }
@@ -19,15 +19,24 @@
 //! API implementation for `archive`.
 
 use crate::{
-	archive::{error::Error as ArchiveError, ArchiveApiServer},
+	archive::{
+		archive_storage::{ArchiveStorage, DiffQuery},
+		error::Error as ArchiveError,
+		ArchiveApiServer,
+	},
 	chain_head::hex_string,
+	common::events::{
+		ArchiveStorageDiffItem, ArchiveStorageDiffResult, ArchiveStorageResult,
+		PaginatedStorageQuery,
+	},
 	MethodResult,
 };
 
 use codec::Encode;
 use jsonrpsee::core::{async_trait, RpcResult};
 use sc_client_api::{
-	Backend, BlockBackend, BlockchainEvents, CallExecutor, ExecutorProvider, StorageProvider,
+	Backend, BlockBackend, BlockchainEvents, CallExecutor, ChildInfo, ExecutorProvider, StorageKey,
+	StorageProvider,
 };
 use sp_api::{CallApiAt, CallContext};
 use sp_blockchain::{
@@ -40,6 +49,46 @@ use sp_runtime::{
 };
 use std::{collections::HashSet, marker::PhantomData, sync::Arc};
 
+/// The configuration of [`Archive`].
+pub struct ArchiveConfig {
+	/// The maximum number of items the `archive_storage` can return for a descendant query before
+	/// pagination is required.
+	pub max_descendant_responses: usize,
+	/// The maximum number of queried items allowed for the `archive_storage` and
+	/// `archive_storageDiff` at a time.
+	pub max_queried_items: usize,
+	/// The maximum number of storage keys the `archive_storageDiff` compares at a time.
+	pub max_diff_keys: usize,
+}
+
+/// The maximum number of items the `archive_storage` can return for a descendant query before
+/// pagination is required.
+///
+/// Note: this is identical to the `chainHead` value.
+const MAX_DESCENDANT_RESPONSES: usize = 5;
+
+/// The maximum number of queried items allowed for the `archive_storage` at a time.
+///
+/// Note: A queried item can also be a descendant query which can return up to
+/// `MAX_DESCENDANT_RESPONSES`.
+const MAX_QUERIED_ITEMS: usize = 8;
+
+/// The maximum number of storage keys the `archive_storageDiff` compares at a time.
+///
+/// Note: Every key present in either block counts, whether or not it changed, so that an
+/// unrestricted diff of the whole trie is bounded as well.
+const MAX_DIFF_KEYS: usize = 10_000;
+
+impl Default for ArchiveConfig {
+	fn default() -> Self {
+		Self {
+			max_descendant_responses: MAX_DESCENDANT_RESPONSES,
+			max_queried_items: MAX_QUERIED_ITEMS,
+			max_diff_keys: MAX_DIFF_KEYS,
+		}
+	}
+}
+
 /// An API for archive RPC calls.
 pub struct Archive<BE: Backend<Block>, Block: BlockT, Client> {
 	/// Substrate client.
@@ -48,6 +97,13 @@ pub struct Archive<BE: Backend<Block>, Block: BlockT, Client> {
 	backend: Arc<BE>,
 	/// The hexadecimal encoded hash of the genesis block.
 	genesis_hash: String,
+	/// The maximum number of items the `archive_storage` can return for a descendant query before
+	/// pagination is required.
+	storage_max_descendant_responses: usize,
+	/// The maximum number of queried items allowed for the `archive_storage` at a time.
+	storage_max_queried_items: usize,
+	/// The maximum number of storage keys the `archive_storageDiff` compares at a time.
+	storage_max_diff_keys: usize,
 	/// Phantom member to pin the block type.
 	_phantom: PhantomData<(Block, BE)>,
 }
@@ -58,9 +114,18 @@ impl<BE: Backend<Block>, Block: BlockT, Client> Archive<BE, Block, Client> {
 		client: Arc<Client>,
 		backend: Arc<BE>,
 		genesis_hash: GenesisHash,
+		config: ArchiveConfig,
 	) -> Self {
 		let genesis_hash = hex_string(&genesis_hash.as_ref());
-		Self { client, backend, genesis_hash, _phantom: PhantomData }
+		Self {
+			client,
+			backend,
+			genesis_hash,
+			storage_max_descendant_responses: config.max_descendant_responses,
+			storage_max_queried_items: config.max_queried_items,
+			storage_max_diff_keys: config.max_diff_keys,
+			_phantom: PhantomData,
+		}
 	}
 }
 
@@ -81,7 +146,6 @@ impl<BE, Block, Client> ArchiveApiServer<Block::Hash> for Archive<BE, Block, Cli
 where
 	Block: BlockT + 'static,
 	Block::Header: Unpin,
-	<<Block as BlockT>::Header as HeaderT>::Number: From<u64>,
 	BE: Backend<Block> + 'static,
 	Client: BlockBackend<Block>
 		+ ExecutorProvider<Block>
@@ -120,7 +184,8 @@ where
 	}
 
 	fn archive_unstable_hash_by_height(&self, height: u64) -> RpcResult<Vec<String>> {
-		let height: NumberFor<Block> = height.into();
+		// Heights beyond the range of the block number type have no blocks.
+		let Ok(height) = NumberFor::<Block>::try_from(height) else { return Ok(vec![]) };
 		let finalized_num = self.client.info().finalized_number;
 
 		if finalized_num >= height {
@@ -185,4 +250,91 @@ where
 			Err(error) => MethodResult::err(error.to_string()),
 		})
 	}
+
+	fn archive_unstable_storage(
+		&self,
+		hash: Block::Hash,
+		items: Vec<PaginatedStorageQuery<String>>,
+		child_trie: Option<String>,
+	) -> RpcResult<ArchiveStorageResult> {
+		let items = items
+			.into_iter()
+			.map(|query| {
+				let key = StorageKey(parse_hex_param(query.key)?);
+				let pagination_start_key = query
+					.pagination_start_key
+					.map(|key| parse_hex_param(key).map(StorageKey))
+					.transpose()?;
+
+				// The pagination start key is only meaningful when iterating.
+				if pagination_start_key.is_some() && !query.query_type.is_descendant_query() {
+					return Err(ArchiveError::InvalidParam(
+						"Pagination start key is only supported for descendants queries"
+							.to_string(),
+					))
+				}
+
+				Ok(PaginatedStorageQuery {
+					key,
+					query_type: query.query_type,
+					pagination_start_key,
+				})
+			})
+			.collect::<Result<Vec<_>, ArchiveError>>()?;
+
+		let child_trie = child_trie
+			.map(|child_trie| parse_hex_param(child_trie))
+			.transpose()?
+			.map(ChildInfo::new_default_from_vec);
+
+		let storage_client = ArchiveStorage::new(
+			self.client.clone(),
+			self.storage_max_descendant_responses,
+			self.storage_max_queried_items,
+			self.storage_max_diff_keys,
+		);
+		Ok(storage_client.handle_query(hash, items, child_trie))
+	}
+
+	fn archive_unstable_storage_diff(
+		&self,
+		hash: Block::Hash,
+		items: Vec<ArchiveStorageDiffItem<String>>,
+		previous_hash: Option<Block::Hash>,
+	) -> RpcResult<ArchiveStorageDiffResult> {
+		let items = items
+			.into_iter()
+			.map(|item| {
+				Ok(DiffQuery {
+					key: StorageKey(parse_hex_param(item.key)?),
+					return_type: item.return_type,
+					child_trie: item
+						.child_trie_key
+						.map(|child_trie| parse_hex_param(child_trie))
+						.transpose()?
+						.map(ChildInfo::new_default_from_vec),
+				})
+			})
+			.collect::<Result<Vec<_>, ArchiveError>>()?;
+
+		let previous_hash = match previous_hash {
+			Some(previous_hash) => previous_hash,
+			None => {
+				let Ok(Some(header)) = self.client.header(hash) else {
+					return Ok(ArchiveStorageDiffResult::err(format!(
+						"Block header is not present: {hash}"
+					)))
+				};
+				*header.parent_hash()
+			},
+		};
+
+		let storage_client = ArchiveStorage::new(
+			self.client.clone(),
+			self.storage_max_descendant_responses,
+			self.storage_max_queried_items,
+			self.storage_max_diff_keys,
+		);
+		Ok(storage_client.handle_diff(hash, previous_hash, items))
+	}
 }
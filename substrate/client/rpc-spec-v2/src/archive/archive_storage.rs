@@ -0,0 +1,334 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Implementation of the `archive_storage` and `archive_storageDiff` methods.
+
+use std::{cmp::Ordering, iter::Peekable, sync::Arc};
+
+use sc_client_api::{Backend, ChildInfo, StorageKey, StorageProvider};
+use sp_runtime::traits::Block as BlockT;
+
+use crate::{
+	chain_head::hex_string,
+	common::{
+		events::{
+			ArchiveStorageDiffEvent, ArchiveStorageDiffOperationType, ArchiveStorageDiffResult,
+			ArchiveStorageDiffResultType, ArchiveStorageResult, PaginatedStorageQuery,
+			StorageQueryType, StorageResult,
+		},
+		storage::{is_key_queryable, IterQueryType, QueryIter, QueryResult, Storage},
+	},
+};
+
+/// A single storage diff query after the parameters have been parsed.
+pub struct DiffQuery {
+	/// The prefix of the keys to compare.
+	pub key: StorageKey,
+	/// The type of the reported values.
+	pub return_type: ArchiveStorageDiffResultType,
+	/// The child trie to compare, if any.
+	pub child_trie: Option<ChildInfo>,
+}
+
+/// Generates the results of the `archive_storage` and `archive_storageDiff` methods.
+pub struct ArchiveStorage<Client, Block, BE> {
+	/// Storage client.
+	client: Storage<Client, Block, BE>,
+	/// The maximum number of responses the API can return for a descendant query at a time.
+	storage_max_descendant_responses: usize,
+	/// The maximum number of queried items allowed for the `archive_storage` at a time.
+	storage_max_queried_items: usize,
+	/// The maximum number of storage keys the `archive_storageDiff` compares at a time.
+	storage_max_diff_keys: usize,
+}
+
+impl<Client, Block, BE> ArchiveStorage<Client, Block, BE> {
+	/// Constructs a new [`ArchiveStorage`].
+	pub fn new(
+		client: Arc<Client>,
+		storage_max_descendant_responses: usize,
+		storage_max_queried_items: usize,
+		storage_max_diff_keys: usize,
+	) -> Self {
+		Self {
+			client: Storage::new(client),
+			storage_max_descendant_responses,
+			storage_max_queried_items,
+			storage_max_diff_keys,
+		}
+	}
+}
+
+impl<Client, Block, BE> ArchiveStorage<Client, Block, BE>
+where
+	Block: BlockT + 'static,
+	BE: Backend<Block> + 'static,
+	Client: StorageProvider<Block, BE> + 'static,
+{
+	/// Generate the response of the `archive_storage` method.
+	pub fn handle_query(
+		&self,
+		hash: Block::Hash,
+		mut items: Vec<PaginatedStorageQuery<StorageKey>>,
+		child_key: Option<ChildInfo>,
+	) -> ArchiveStorageResult {
+		if let Some(child_key) = child_key.as_ref() {
+			if !is_key_queryable(child_key.storage_key()) {
+				return ArchiveStorageResult::ok(Vec::new(), 0)
+			}
+		}
+
+		let discarded_items = items.len().saturating_sub(self.storage_max_queried_items);
+		items.truncate(self.storage_max_queried_items);
+
+		let mut storage_results = Vec::with_capacity(items.len());
+		for item in items {
+			if !is_key_queryable(&item.key.0) {
+				continue
+			}
+
+			let result = match item.query_type {
+				StorageQueryType::Value =>
+					self.client.query_value(hash, &item.key, child_key.as_ref()),
+				StorageQueryType::Hash =>
+					self.client.query_hash(hash, &item.key, child_key.as_ref()),
+				StorageQueryType::ClosestDescendantMerkleValue =>
+					self.client.query_merkle_value(hash, &item.key, child_key.as_ref()),
+				StorageQueryType::DescendantsValues | StorageQueryType::DescendantsHashes => {
+					let ty = if item.query_type == StorageQueryType::DescendantsValues {
+						IterQueryType::Value
+					} else {
+						IterQueryType::Hash
+					};
+
+					match self.client.query_iter_pagination(
+						QueryIter {
+							query_key: item.key,
+							ty,
+							pagination_start_key: item.pagination_start_key,
+						},
+						hash,
+						child_key.as_ref(),
+						self.storage_max_descendant_responses,
+					) {
+						Ok((results, _)) => {
+							storage_results.extend(results);
+							continue
+						},
+						Err(error) => return ArchiveStorageResult::err(error),
+					}
+				},
+			};
+
+			match result {
+				Ok(Some(value)) => storage_results.push(value),
+				Ok(None) => continue,
+				Err(error) => return ArchiveStorageResult::err(error),
+			}
+		}
+
+		ArchiveStorageResult::ok(storage_results, discarded_items)
+	}
+
+	/// Generate the response of the `archive_storageDiff` method.
+	///
+	/// The keys of both blocks are iterated in lexicographic order for every query, which
+	/// allows the differences to be computed without holding the keys in memory. At most
+	/// `storage_max_diff_keys` keys are compared over all queries; larger diffs are rejected and
+	/// have to be split into narrower queries.
+	pub fn handle_diff(
+		&self,
+		hash: Block::Hash,
+		previous_hash: Block::Hash,
+		mut items: Vec<DiffQuery>,
+	) -> ArchiveStorageDiffResult {
+		if items.len() > self.storage_max_queried_items {
+			return ArchiveStorageDiffResult::err(format!(
+				"At most {} items can be queried at a time",
+				self.storage_max_queried_items
+			))
+		}
+
+		// Compare the entire main trie when no items are provided.
+		if items.is_empty() {
+			items.push(DiffQuery {
+				key: StorageKey(Vec::new()),
+				return_type: ArchiveStorageDiffResultType::Value,
+				child_trie: None,
+			});
+		}
+
+		let mut diff_results = Vec::new();
+		let mut remaining_keys = self.storage_max_diff_keys;
+		for item in items {
+			if let Some(child_trie) = item.child_trie.as_ref() {
+				if !is_key_queryable(child_trie.storage_key()) {
+					continue
+				}
+			}
+
+			if let Err(error) =
+				self.diff_query(hash, previous_hash, item, &mut remaining_keys, &mut diff_results)
+			{
+				return ArchiveStorageDiffResult::err(error)
+			}
+		}
+
+		ArchiveStorageDiffResult::ok(diff_results)
+	}
+
+	/// Compare the descendants of a single query between the two blocks.
+	fn diff_query(
+		&self,
+		hash: Block::Hash,
+		previous_hash: Block::Hash,
+		query: DiffQuery,
+		remaining_keys: &mut usize,
+		diff_results: &mut Vec<ArchiveStorageDiffEvent>,
+	) -> Result<(), String> {
+		let DiffQuery { key, return_type, child_trie } = query;
+		let child_key = child_trie.as_ref();
+		let child_trie_key = child_trie.as_ref().map(|child| hex_string(&child.storage_key()));
+
+		let mut keys = self.client.raw_keys_iter(hash, &key, None, child_key)?.peekable();
+		let mut previous_keys =
+			self.client.raw_keys_iter(previous_hash, &key, None, child_key)?.peekable();
+
+		loop {
+			let diff_key = next_diff_key(&mut keys, &mut previous_keys);
+			if diff_key.is_some() {
+				if *remaining_keys == 0 {
+					return Err(format!(
+						"The diff compares more than {} keys; narrow down the queried keys",
+						self.storage_max_diff_keys
+					))
+				}
+				*remaining_keys -= 1;
+			}
+
+			let (operation_type, key) = match diff_key {
+				Some(DiffKey::Added(key)) => (ArchiveStorageDiffOperationType::Added, key),
+				Some(DiffKey::Deleted(key)) => (ArchiveStorageDiffOperationType::Deleted, key),
+				Some(DiffKey::Both(key)) => {
+					let current = self.client.query_hash(hash, &key, child_key)?;
+					let previous = self.client.query_hash(previous_hash, &key, child_key)?;
+					if current == previous {
+						continue
+					}
+
+					(ArchiveStorageDiffOperationType::Modified, key)
+				},
+				None => return Ok(()),
+			};
+
+			// Deleted keys report the state they had before the removal.
+			let at = match operation_type {
+				ArchiveStorageDiffOperationType::Deleted => previous_hash,
+				_ => hash,
+			};
+			let result: QueryResult = match return_type {
+				ArchiveStorageDiffResultType::Value => self.client.query_value(at, &key, child_key),
+				ArchiveStorageDiffResultType::Hash => self.client.query_hash(at, &key, child_key),
+			};
+
+			if let Some(StorageResult { key, result }) = result? {
+				diff_results.push(ArchiveStorageDiffEvent {
+					key,
+					result,
+					operation_type,
+					child_trie_key: child_trie_key.clone(),
+				});
+			}
+		}
+	}
+}
+
+/// The next key reported by two lexicographically ordered key iterators.
+enum DiffKey {
+	/// The key is only present in the current block.
+	Added(StorageKey),
+	/// The key is only present in the previous block.
+	Deleted(StorageKey),
+	/// The key is present in both blocks.
+	Both(StorageKey),
+}
+
+/// Advance the two key iterators, returning the smallest key and where it was found.
+fn next_diff_key<Current, Previous>(
+	keys: &mut Peekable<Current>,
+	previous_keys: &mut Peekable<Previous>,
+) -> Option<DiffKey>
+where
+	Current: Iterator<Item = StorageKey>,
+	Previous: Iterator<Item = StorageKey>,
+{
+	let ordering = match (keys.peek(), previous_keys.peek()) {
+		(None, None) => return None,
+		(Some(_), None) => Ordering::Less,
+		(None, Some(_)) => Ordering::Greater,
+		(Some(key), Some(previous_key)) => key.cmp(previous_key),
+	};
+
+	match ordering {
+		Ordering::Less => keys.next().map(DiffKey::Added),
+		Ordering::Greater => previous_keys.next().map(DiffKey::Deleted),
+		Ordering::Equal => {
+			previous_keys.next();
+			keys.next().map(DiffKey::Both)
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn keys(keys: &[&[u8]]) -> Peekable<std::vec::IntoIter<StorageKey>> {
+		keys.iter()
+			.map(|key| StorageKey(key.to_vec()))
+			.collect::<Vec<_>>()
+			.into_iter()
+			.peekable()
+	}
+
+	#[test]
+	fn next_diff_key_merges_sorted_keys() {
+		let mut current = keys(&[b"a", b"b", b"d"]);
+		let mut previous = keys(&[b"b", b"c", b"d", b"e"]);
+
+		let mut result = Vec::new();
+		while let Some(diff) = next_diff_key(&mut current, &mut previous) {
+			result.push(match diff {
+				DiffKey::Added(key) => ("added", key.0),
+				DiffKey::Deleted(key) => ("deleted", key.0),
+				DiffKey::Both(key) => ("both", key.0),
+			});
+		}
+
+		assert_eq!(
+			result,
+			vec![
+				("added", b"a".to_vec()),
+				("both", b"b".to_vec()),
+				("deleted", b"c".to_vec()),
+				("both", b"d".to_vec()),
+				("deleted", b"e".to_vec()),
+			]
+		);
+	}
+}
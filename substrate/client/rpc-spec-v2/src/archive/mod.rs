@@ -29,4 +29,7 @@ pub mod api;
 pub mod archive;
 pub mod error;
 
+mod archive_storage;
+
 pub use api::ArchiveApiServer;
+pub use archive::{Archive, ArchiveConfig};
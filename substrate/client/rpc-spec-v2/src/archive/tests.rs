@@ -16,20 +16,35 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{chain_head::hex_string, MethodResult};
+use crate::{
+	chain_head::hex_string,
+	common::events::{
+		ArchiveStorageDiffEvent, ArchiveStorageDiffItem, ArchiveStorageDiffOperationType,
+		ArchiveStorageDiffResult, ArchiveStorageDiffResultType, ArchiveStorageMethodOk,
+		ArchiveStorageResult, PaginatedStorageQuery, StorageQueryType, StorageResult,
+		StorageResultType,
+	},
+	MethodResult,
+};
 
-use super::{archive::Archive, *};
+use super::{
+	archive::{Archive, ArchiveConfig},
+	*,
+};
 
 use assert_matches::assert_matches;
 use codec::{Decode, Encode};
 use jsonrpsee::{
 	core::error::Error,
+	rpc_params,
 	types::{error::CallError, EmptyServerParams as EmptyParams},
 	RpcModule,
 };
 use sc_block_builder::BlockBuilderBuilder;
+use sc_client_api::ChildInfo;
 use sp_blockchain::HeaderBackend;
 use sp_consensus::BlockOrigin;
+use sp_core::{Blake2Hasher, Hasher};
 use sp_runtime::{
 	traits::{Block as BlockT, Header as HeaderT},
 	SaturatedConversion,
@@ -42,23 +57,52 @@ use substrate_test_runtime_client::{
 
 const CHAIN_GENESIS: [u8; 32] = [0; 32];
 const INVALID_HASH: [u8; 32] = [1; 32];
+const MAX_PAGINATION_LIMIT: usize = 5;
+const MAX_QUERIED_LIMIT: usize = 5;
+const MAX_DIFF_KEYS: usize = 100;
+const KEY: &[u8] = b":mock";
+const VALUE: &[u8] = b"hello world";
+const CHILD_STORAGE_KEY: &[u8] = b"child";
+const CHILD_VALUE: &[u8] = b"child value";
 
 type Header = substrate_test_runtime_client::runtime::Header;
 type Block = substrate_test_runtime_client::runtime::Block;
 
-fn setup_api() -> (Arc<Client<Backend>>, RpcModule<Archive<Backend, Block, Client<Backend>>>) {
-	let builder = TestClientBuilder::new();
+fn setup_api(
+	max_descendant_responses: usize,
+	max_queried_items: usize,
+) -> (Arc<Client<Backend>>, RpcModule<Archive<Backend, Block, Client<Backend>>>) {
+	setup_api_with_diff_limit(max_descendant_responses, max_queried_items, MAX_DIFF_KEYS)
+}
+
+fn setup_api_with_diff_limit(
+	max_descendant_responses: usize,
+	max_queried_items: usize,
+	max_diff_keys: usize,
+) -> (Arc<Client<Backend>>, RpcModule<Archive<Backend, Block, Client<Backend>>>) {
+	let child_info = ChildInfo::new_default(CHILD_STORAGE_KEY);
+	let builder = TestClientBuilder::new().add_extra_child_storage(
+		&child_info,
+		KEY.to_vec(),
+		CHILD_VALUE.to_vec(),
+	);
 	let backend = builder.backend();
 	let client = Arc::new(builder.build());
 
-	let api = Archive::new(client.clone(), backend, CHAIN_GENESIS).into_rpc();
+	let api = Archive::new(
+		client.clone(),
+		backend,
+		CHAIN_GENESIS,
+		ArchiveConfig { max_descendant_responses, max_queried_items, max_diff_keys },
+	)
+	.into_rpc();
 
 	(client, api)
 }
 
 #[tokio::test]
 async fn archive_genesis() {
-	let (_client, api) = setup_api();
+	let (_client, api) = setup_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
 
 	let genesis: String =
 		api.call("archive_unstable_genesisHash", EmptyParams::new()).await.unwrap();
@@ -67,7 +111,7 @@ async fn archive_genesis() {
 
 #[tokio::test]
 async fn archive_body() {
-	let (mut client, api) = setup_api();
+	let (mut client, api) = setup_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
 
 	// Invalid block hash.
 	let invalid_hash = hex_string(&INVALID_HASH);
@@ -101,7 +145,7 @@ async fn archive_body() {
 
 #[tokio::test]
 async fn archive_header() {
-	let (mut client, api) = setup_api();
+	let (mut client, api) = setup_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
 
 	// Invalid block hash.
 	let invalid_hash = hex_string(&INVALID_HASH);
@@ -135,7 +179,7 @@ async fn archive_header() {
 
 #[tokio::test]
 async fn archive_finalized_height() {
-	let (client, api) = setup_api();
+	let (client, api) = setup_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
 
 	let client_height: u32 = client.info().finalized_number.saturated_into();
 
@@ -147,7 +191,7 @@ async fn archive_finalized_height() {
 
 #[tokio::test]
 async fn archive_hash_by_height() {
-	let (mut client, api) = setup_api();
+	let (mut client, api) = setup_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
 
 	// Genesis height.
 	let hashes: Vec<String> = api.call("archive_unstable_hashByHeight", [0]).await.unwrap();
@@ -253,7 +297,7 @@ async fn archive_hash_by_height() {
 
 #[tokio::test]
 async fn archive_call() {
-	let (mut client, api) = setup_api();
+	let (mut client, api) = setup_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
 	let invalid_hash = hex_string(&INVALID_HASH);
 
 	// Invalid parameter (non-hex).
@@ -309,3 +353,419 @@ async fn archive_call() {
 	let expected = MethodResult::ok("0x0000000000000000");
 	assert_eq!(result, expected);
 }
+
+#[tokio::test]
+async fn archive_storage_hashes_values() {
+	let (mut client, api) = setup_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
+
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(client.chain_info().genesis_hash)
+		.with_parent_block_number(0)
+		.build()
+		.unwrap();
+	builder.push_storage_change(b":A".to_vec(), Some(b"B".to_vec())).unwrap();
+	builder.push_storage_change(b":AA".to_vec(), Some(b"BB".to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	let key = hex_string(b":A");
+	let items: Vec<PaginatedStorageQuery<String>> = vec![
+		PaginatedStorageQuery {
+			key: key.clone(),
+			query_type: StorageQueryType::DescendantsHashes,
+			pagination_start_key: None,
+		},
+		PaginatedStorageQuery {
+			key: key.clone(),
+			query_type: StorageQueryType::DescendantsValues,
+			pagination_start_key: None,
+		},
+		PaginatedStorageQuery {
+			key: key.clone(),
+			query_type: StorageQueryType::Hash,
+			pagination_start_key: None,
+		},
+		PaginatedStorageQuery {
+			key: key.clone(),
+			query_type: StorageQueryType::Value,
+			pagination_start_key: None,
+		},
+	];
+
+	let result: ArchiveStorageResult = api
+		.call("archive_unstable_storage", rpc_params![&block_hash, items.clone()])
+		.await
+		.unwrap();
+
+	match result {
+		ArchiveStorageResult::Ok(ArchiveStorageMethodOk { result, discarded_items }) => {
+			// Key has not been imported yet.
+			assert_eq!(result.len(), 0);
+			assert_eq!(discarded_items, 0);
+		},
+		_ => panic!("Unexpected result"),
+	};
+
+	// Import a block with the given key value pair.
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(block.hash())
+		.with_parent_block_number(1)
+		.build()
+		.unwrap();
+	builder.push_storage_change(b":A".to_vec(), Some(VALUE.to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	let expected_hash = format!("{:?}", Blake2Hasher::hash(&VALUE));
+	let expected_value = hex_string(&VALUE);
+
+	let result: ArchiveStorageResult = api
+		.call("archive_unstable_storage", rpc_params![&block_hash, items])
+		.await
+		.unwrap();
+
+	match result {
+		ArchiveStorageResult::Ok(ArchiveStorageMethodOk { result, discarded_items }) => {
+			assert_eq!(result.len(), 4);
+			assert_eq!(discarded_items, 0);
+
+			assert_eq!(result[0].key, key);
+			assert_eq!(result[0].result, StorageResultType::Hash(expected_hash.clone()));
+			assert_eq!(result[1].key, key);
+			assert_eq!(result[1].result, StorageResultType::Value(expected_value.clone()));
+			assert_eq!(result[2].key, key);
+			assert_eq!(result[2].result, StorageResultType::Hash(expected_hash));
+			assert_eq!(result[3].key, key);
+			assert_eq!(result[3].result, StorageResultType::Value(expected_value));
+		},
+		_ => panic!("Unexpected result"),
+	};
+}
+
+#[tokio::test]
+async fn archive_storage_child_trie() {
+	let (client, api) = setup_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
+
+	let genesis_hash = format!("{:?}", client.genesis_hash());
+	let child_info = hex_string(&CHILD_STORAGE_KEY);
+	let key = hex_string(&KEY);
+
+	let result: ArchiveStorageResult = api
+		.call(
+			"archive_unstable_storage",
+			rpc_params![
+				&genesis_hash,
+				vec![PaginatedStorageQuery {
+					key: key.clone(),
+					query_type: StorageQueryType::Value,
+					pagination_start_key: None,
+				}],
+				&child_info
+			],
+		)
+		.await
+		.unwrap();
+
+	match result {
+		ArchiveStorageResult::Ok(ArchiveStorageMethodOk { result, discarded_items }) => {
+			assert_eq!(result.len(), 1);
+			assert_eq!(discarded_items, 0);
+			assert_eq!(result[0].key, key);
+			assert_eq!(result[0].result, StorageResultType::Value(hex_string(&CHILD_VALUE)));
+		},
+		_ => panic!("Unexpected result"),
+	};
+}
+
+#[tokio::test]
+async fn archive_storage_closest_merkle_value() {
+	let (client, api) = setup_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
+
+	let genesis_hash = format!("{:?}", client.genesis_hash());
+	let result: ArchiveStorageResult = api
+		.call(
+			"archive_unstable_storage",
+			rpc_params![
+				&genesis_hash,
+				vec![PaginatedStorageQuery {
+					key: hex_string(b":"),
+					query_type: StorageQueryType::ClosestDescendantMerkleValue,
+					pagination_start_key: None,
+				}]
+			],
+		)
+		.await
+		.unwrap();
+
+	match result {
+		ArchiveStorageResult::Ok(ArchiveStorageMethodOk { result, .. }) => {
+			assert_eq!(result.len(), 1);
+			assert_matches!(result[0].result, StorageResultType::ClosestDescendantMerkleValue(_));
+		},
+		_ => panic!("Unexpected result"),
+	};
+}
+
+#[tokio::test]
+async fn archive_storage_paginate_iterations() {
+	// 1 iteration allowed before pagination kicks in.
+	let (mut client, api) = setup_api(1, MAX_QUERIED_LIMIT);
+
+	// Import a new block with storage changes.
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(client.chain_info().genesis_hash)
+		.with_parent_block_number(0)
+		.build()
+		.unwrap();
+	builder.push_storage_change(b":m".to_vec(), Some(b"a".to_vec())).unwrap();
+	builder.push_storage_change(b":mo".to_vec(), Some(b"ab".to_vec())).unwrap();
+	builder.push_storage_change(b":moc".to_vec(), Some(b"abc".to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	let mut pagination_start_key = None;
+	let mut reported = Vec::new();
+	loop {
+		let result: ArchiveStorageResult = api
+			.call(
+				"archive_unstable_storage",
+				rpc_params![
+					&block_hash,
+					vec![PaginatedStorageQuery {
+						key: hex_string(b":m"),
+						query_type: StorageQueryType::DescendantsValues,
+						pagination_start_key: pagination_start_key.clone(),
+					}]
+				],
+			)
+			.await
+			.unwrap();
+
+		let ArchiveStorageResult::Ok(ArchiveStorageMethodOk { result, discarded_items }) = result
+		else {
+			panic!("Unexpected result")
+		};
+		assert_eq!(discarded_items, 0);
+
+		let Some(StorageResult { key, result }) = result.into_iter().next() else { break };
+		pagination_start_key = Some(key.clone());
+		reported.push((key, result));
+	}
+
+	assert_eq!(
+		reported,
+		vec![
+			(hex_string(b":m"), StorageResultType::Value(hex_string(b"a"))),
+			(hex_string(b":mo"), StorageResultType::Value(hex_string(b"ab"))),
+			(hex_string(b":moc"), StorageResultType::Value(hex_string(b"abc"))),
+		]
+	);
+}
+
+#[tokio::test]
+async fn archive_storage_discarded_items() {
+	// One query at a time
+	let (mut client, api) = setup_api(MAX_PAGINATION_LIMIT, 1);
+
+	// Import a new block with storage changes.
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(client.chain_info().genesis_hash)
+		.with_parent_block_number(0)
+		.build()
+		.unwrap();
+	builder.push_storage_change(b":m".to_vec(), Some(b"a".to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	// Valid call with storage at the key.
+	let result: ArchiveStorageResult = api
+		.call(
+			"archive_unstable_storage",
+			rpc_params![
+				&block_hash,
+				vec![
+					PaginatedStorageQuery {
+						key: hex_string(b":m"),
+						query_type: StorageQueryType::Value,
+						pagination_start_key: None,
+					},
+					PaginatedStorageQuery {
+						key: hex_string(b":m"),
+						query_type: StorageQueryType::Hash,
+						pagination_start_key: None,
+					},
+					PaginatedStorageQuery {
+						key: hex_string(b":m"),
+						query_type: StorageQueryType::Hash,
+						pagination_start_key: None,
+					}
+				]
+			],
+		)
+		.await
+		.unwrap();
+	match result {
+		ArchiveStorageResult::Ok(ArchiveStorageMethodOk { result, discarded_items }) => {
+			assert_eq!(result.len(), 1);
+			assert_eq!(discarded_items, 2);
+
+			assert_eq!(result[0].key, hex_string(b":m"));
+			assert_eq!(result[0].result, StorageResultType::Value(hex_string(b"a")));
+		},
+		_ => panic!("Unexpected result"),
+	};
+
+	// Pagination start key is rejected for non-descendant queries.
+	let err = api
+		.call::<_, serde_json::Value>(
+			"archive_unstable_storage",
+			rpc_params![
+				&block_hash,
+				vec![PaginatedStorageQuery {
+					key: hex_string(b":m"),
+					query_type: StorageQueryType::Value,
+					pagination_start_key: Some(hex_string(b":m")),
+				}]
+			],
+		)
+		.await
+		.unwrap_err();
+	assert_matches!(err, Error::Call(CallError::Custom(ref err)) if err.code() == 3001 && err.message().contains("Invalid parameter"));
+}
+
+#[tokio::test]
+async fn archive_storage_diff() {
+	let (mut client, api) = setup_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
+
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(client.chain_info().genesis_hash)
+		.with_parent_block_number(0)
+		.build()
+		.unwrap();
+	builder.push_storage_change(b":A".to_vec(), Some(b"B".to_vec())).unwrap();
+	builder.push_storage_change(b":AA".to_vec(), Some(b"BB".to_vec())).unwrap();
+	let prev_block = builder.build().unwrap().block;
+	let prev_hash = format!("{:?}", prev_block.header.hash());
+	client.import(BlockOrigin::Own, prev_block.clone()).await.unwrap();
+
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(prev_block.hash())
+		.with_parent_block_number(1)
+		.build()
+		.unwrap();
+	// Modified.
+	builder.push_storage_change(b":A".to_vec(), Some(b"11".to_vec())).unwrap();
+	// Deleted.
+	builder.push_storage_change(b":AA".to_vec(), None).unwrap();
+	// Added.
+	builder.push_storage_change(b":AAA".to_vec(), Some(b"222".to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	let items = vec![ArchiveStorageDiffItem::<String> {
+		key: hex_string(b":A"),
+		return_type: ArchiveStorageDiffResultType::Value,
+		child_trie_key: None,
+	}];
+
+	// The previous block defaults to the parent.
+	let result: ArchiveStorageDiffResult = api
+		.call("archive_unstable_storageDiff", rpc_params![&block_hash, items.clone()])
+		.await
+		.unwrap();
+	let expected = ArchiveStorageDiffResult::ok(vec![
+		ArchiveStorageDiffEvent {
+			key: hex_string(b":A"),
+			result: StorageResultType::Value(hex_string(b"11")),
+			operation_type: ArchiveStorageDiffOperationType::Modified,
+			child_trie_key: None,
+		},
+		ArchiveStorageDiffEvent {
+			key: hex_string(b":AA"),
+			result: StorageResultType::Value(hex_string(b"BB")),
+			operation_type: ArchiveStorageDiffOperationType::Deleted,
+			child_trie_key: None,
+		},
+		ArchiveStorageDiffEvent {
+			key: hex_string(b":AAA"),
+			result: StorageResultType::Value(hex_string(b"222")),
+			operation_type: ArchiveStorageDiffOperationType::Added,
+			child_trie_key: None,
+		},
+	]);
+	assert_eq!(result, expected);
+
+	// Providing the previous hash explicitly yields the same result.
+	let result: ArchiveStorageDiffResult = api
+		.call("archive_unstable_storageDiff", rpc_params![&block_hash, items.clone(), &prev_hash])
+		.await
+		.unwrap();
+	assert_eq!(result, expected);
+
+	// Comparing a block with itself reports no differences.
+	let result: ArchiveStorageDiffResult = api
+		.call("archive_unstable_storageDiff", rpc_params![&block_hash, items, &block_hash])
+		.await
+		.unwrap();
+	assert_eq!(result, ArchiveStorageDiffResult::ok(vec![]));
+
+	// Hashes are reported when requested.
+	let items = vec![ArchiveStorageDiffItem::<String> {
+		key: hex_string(b":AAA"),
+		return_type: ArchiveStorageDiffResultType::Hash,
+		child_trie_key: None,
+	}];
+	let result: ArchiveStorageDiffResult = api
+		.call("archive_unstable_storageDiff", rpc_params![&block_hash, items])
+		.await
+		.unwrap();
+	assert_eq!(
+		result,
+		ArchiveStorageDiffResult::ok(vec![ArchiveStorageDiffEvent {
+			key: hex_string(b":AAA"),
+			result: StorageResultType::Hash(format!("{:?}", Blake2Hasher::hash(b"222"))),
+			operation_type: ArchiveStorageDiffOperationType::Added,
+			child_trie_key: None,
+		}])
+	);
+}
+
+#[tokio::test]
+async fn archive_storage_diff_is_bounded() {
+	let (mut client, api) = setup_api_with_diff_limit(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT, 2);
+
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(client.chain_info().genesis_hash)
+		.with_parent_block_number(0)
+		.build()
+		.unwrap();
+	builder.push_storage_change(b":A".to_vec(), Some(b"B".to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	// Narrow queries within the bound are served.
+	let items = vec![ArchiveStorageDiffItem::<String> {
+		key: hex_string(b":A"),
+		return_type: ArchiveStorageDiffResultType::Value,
+		child_trie_key: None,
+	}];
+	let result: ArchiveStorageDiffResult = api
+		.call("archive_unstable_storageDiff", rpc_params![&block_hash, items])
+		.await
+		.unwrap();
+	assert_matches!(result, ArchiveStorageDiffResult::Ok(ok) if ok.result.len() == 1);
+
+	// An empty list of items compares the whole trie, which exceeds the bound.
+	let items: Vec<ArchiveStorageDiffItem<String>> = vec![];
+	let result: ArchiveStorageDiffResult = api
+		.call("archive_unstable_storageDiff", rpc_params![&block_hash, items])
+		.await
+		.unwrap();
+	assert_matches!(result, ArchiveStorageDiffResult::Err(err) if err.error.contains("narrow down"));
+}
@@ -23,6 +23,8 @@ use sp_api::ApiError;
 use sp_version::RuntimeVersion;
 use std::collections::BTreeMap;
 
+pub use crate::common::events::{StorageQuery, StorageQueryType, StorageResult, StorageResultType};
+
 /// The operation could not be processed due to an error.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -313,56 +315,6 @@ pub enum FollowEvent<Hash> {
 	Stop,
 }
 
-/// The storage item received as paramter.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct StorageQuery<Key> {
-	/// The provided key.
-	pub key: Key,
-	/// The type of the storage query.
-	#[serde(rename = "type")]
-	pub query_type: StorageQueryType,
-}
-
-/// The type of the storage query.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub enum StorageQueryType {
-	/// Fetch the value of the provided key.
-	Value,
-	/// Fetch the hash of the value of the provided key.
-	Hash,
-	/// Fetch the closest descendant merkle value.
-	ClosestDescendantMerkleValue,
-	/// Fetch the values of all descendants of they provided key.
-	DescendantsValues,
-	/// Fetch the hashes of the values of all descendants of they provided key.
-	DescendantsHashes,
-}
-
-/// The storage result.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct StorageResult {
-	/// The hex-encoded key of the result.
-	pub key: String,
-	/// The result of the query.
-	#[serde(flatten)]
-	pub result: StorageResultType,
-}
-
-/// The type of the storage query.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub enum StorageResultType {
-	/// Fetch the value of the provided key.
-	Value(String),
-	/// Fetch the hash of the value of the provided key.
-	Hash(String),
-	/// Fetch the closest descendant merkle value.
-	ClosestDescendantMerkleValue(String),
-}
-
 /// The method respose of `chainHead_body`, `chainHead_call` and `chainHead_storage`.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
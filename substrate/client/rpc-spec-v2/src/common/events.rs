@@ -0,0 +1,318 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Common events for RPC-V2 spec.
+
+use serde::{Deserialize, Serialize};
+
+/// The storage item received as paramter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageQuery<Key> {
+	/// The provided key.
+	pub key: Key,
+	/// The type of the storage query.
+	#[serde(rename = "type")]
+	pub query_type: StorageQueryType,
+}
+
+/// The storage item received as paramter with an optional pagination start key.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaginatedStorageQuery<Key> {
+	/// The provided key.
+	pub key: Key,
+	/// The type of the storage query.
+	#[serde(rename = "type")]
+	pub query_type: StorageQueryType,
+	/// The pagination key from which the iteration should resume.
+	///
+	/// Only relevant for the descendants queries, the iteration resumes
+	/// with the first key that follows the provided one.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[serde(default)]
+	pub pagination_start_key: Option<Key>,
+}
+
+/// The type of the storage query.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageQueryType {
+	/// Fetch the value of the provided key.
+	Value,
+	/// Fetch the hash of the value of the provided key.
+	Hash,
+	/// Fetch the closest descendant merkle value.
+	ClosestDescendantMerkleValue,
+	/// Fetch the values of all descendants of they provided key.
+	DescendantsValues,
+	/// Fetch the hashes of the values of all descendants of they provided key.
+	DescendantsHashes,
+}
+
+impl StorageQueryType {
+	/// Returns `true` if the query is a descendant query.
+	pub fn is_descendant_query(&self) -> bool {
+		matches!(self, Self::DescendantsValues | Self::DescendantsHashes)
+	}
+}
+
+/// The storage result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageResult {
+	/// The hex-encoded key of the result.
+	pub key: String,
+	/// The result of the query.
+	#[serde(flatten)]
+	pub result: StorageResultType,
+}
+
+/// The type of the storage query.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageResultType {
+	/// Fetch the value of the provided key.
+	Value(String),
+	/// Fetch the hash of the value of the provided key.
+	Hash(String),
+	/// Fetch the closest descendant merkle value.
+	ClosestDescendantMerkleValue(String),
+}
+
+/// The result of a storage call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ArchiveStorageResult {
+	/// Query generated a result.
+	Ok(ArchiveStorageMethodOk),
+	/// Query encountered an error.
+	Err(ArchiveStorageMethodErr),
+}
+
+impl ArchiveStorageResult {
+	/// Create a new `ArchiveStorageResult::Ok` result.
+	pub fn ok(result: Vec<StorageResult>, discarded_items: usize) -> Self {
+		Self::Ok(ArchiveStorageMethodOk { result, discarded_items })
+	}
+
+	/// Create a new `ArchiveStorageResult::Err` result.
+	pub fn err(error: String) -> Self {
+		Self::Err(ArchiveStorageMethodErr { error })
+	}
+}
+
+/// The result of a storage call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveStorageMethodOk {
+	/// Reported results.
+	pub result: Vec<StorageResult>,
+	/// Number of discarded items.
+	pub discarded_items: usize,
+}
+
+/// The error of a storage call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveStorageMethodErr {
+	/// Reported error.
+	pub error: String,
+}
+
+/// The type of the value reported by a storage diff item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArchiveStorageDiffResultType {
+	/// Report the value of the modified keys.
+	Value,
+	/// Report the hash of the value of the modified keys.
+	Hash,
+}
+
+/// The storage item received as parameter by the `archive_unstable_storageDiff` method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveStorageDiffItem<Key> {
+	/// The provided key. All the descendants of this key are compared.
+	pub key: Key,
+	/// The type of the value reported for each difference.
+	pub return_type: ArchiveStorageDiffResultType,
+	/// The child trie key if provided.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[serde(default)]
+	pub child_trie_key: Option<Key>,
+}
+
+/// The kind of change a storage key experienced between two blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArchiveStorageDiffOperationType {
+	/// The key was added.
+	Added,
+	/// The key was modified.
+	Modified,
+	/// The key was removed.
+	Deleted,
+}
+
+/// A single difference reported by the `archive_unstable_storageDiff` method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveStorageDiffEvent {
+	/// The hex-encoded key of the result.
+	pub key: String,
+	/// The result of the query.
+	///
+	/// For deleted keys this is the value (or hash) the key had at the previous block.
+	#[serde(flatten)]
+	pub result: StorageResultType,
+	/// The operation type.
+	#[serde(rename = "type")]
+	pub operation_type: ArchiveStorageDiffOperationType,
+	/// The child trie key if this is a child trie difference.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[serde(default)]
+	pub child_trie_key: Option<String>,
+}
+
+/// The result of the `archive_unstable_storageDiff` method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ArchiveStorageDiffResult {
+	/// Query generated a result.
+	Ok(ArchiveStorageDiffMethodOk),
+	/// Query encountered an error.
+	Err(ArchiveStorageMethodErr),
+}
+
+impl ArchiveStorageDiffResult {
+	/// Create a new `ArchiveStorageDiffResult::Ok` result.
+	pub fn ok(result: Vec<ArchiveStorageDiffEvent>) -> Self {
+		Self::Ok(ArchiveStorageDiffMethodOk { result })
+	}
+
+	/// Create a new `ArchiveStorageDiffResult::Err` result.
+	pub fn err(error: String) -> Self {
+		Self::Err(ArchiveStorageMethodErr { error })
+	}
+}
+
+/// The successful result of the `archive_unstable_storageDiff` method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveStorageDiffMethodOk {
+	/// Reported differences.
+	pub result: Vec<ArchiveStorageDiffEvent>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn paginated_storage_query() {
+		// Without pagination key.
+		let item = PaginatedStorageQuery {
+			key: "0x1",
+			query_type: StorageQueryType::DescendantsValues,
+			pagination_start_key: None,
+		};
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","type":"descendantsValues"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: PaginatedStorageQuery<&str> = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+
+		// With pagination key.
+		let item = PaginatedStorageQuery {
+			key: "0x1",
+			query_type: StorageQueryType::DescendantsHashes,
+			pagination_start_key: Some("0x12"),
+		};
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","type":"descendantsHashes","paginationStartKey":"0x12"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: PaginatedStorageQuery<&str> = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+	}
+
+	#[test]
+	fn archive_storage_result() {
+		let item = ArchiveStorageResult::ok(
+			vec![StorageResult {
+				key: "0x1".into(),
+				result: StorageResultType::Value("res".into()),
+			}],
+			1,
+		);
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"result":[{"key":"0x1","value":"res"}],"discardedItems":1}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: ArchiveStorageResult = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+
+		let item = ArchiveStorageResult::err("error".into());
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"error":"error"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: ArchiveStorageResult = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+	}
+
+	#[test]
+	fn archive_storage_diff_item() {
+		let item = ArchiveStorageDiffItem {
+			key: "0x1",
+			return_type: ArchiveStorageDiffResultType::Hash,
+			child_trie_key: Some("0x2"),
+		};
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","returnType":"hash","childTrieKey":"0x2"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: ArchiveStorageDiffItem<&str> = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+	}
+
+	#[test]
+	fn archive_storage_diff_result() {
+		let item = ArchiveStorageDiffResult::ok(vec![ArchiveStorageDiffEvent {
+			key: "0x1".into(),
+			result: StorageResultType::Value("res".into()),
+			operation_type: ArchiveStorageDiffOperationType::Modified,
+			child_trie_key: None,
+		}]);
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"result":[{"key":"0x1","value":"res","type":"modified"}]}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: ArchiveStorageDiffResult = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+	}
+}
@@ -19,6 +19,7 @@
 //! Substrate state API.
 
 mod state_full;
+mod storage_iter;
 mod utils;
 
 #[cfg(test)]
@@ -53,6 +54,9 @@ use sp_blockchain::{HeaderBackend, HeaderMetadata};
 
 const STORAGE_KEYS_PAGED_MAX_COUNT: u32 = 1000;
 
+const STORAGE_ITER_DEFAULT_PAGE_SIZE: u32 = 256;
+const STORAGE_ITER_MAX_PAGE_SIZE: u32 = 1000;
+
 /// State backend API.
 #[async_trait]
 pub trait StateBackend<Block: BlockT, Client>: Send + Sync + 'static
@@ -162,6 +166,20 @@ where
 
 	/// New storage subscription
 	fn subscribe_storage(&self, sink: SubscriptionSink, keys: Option<Vec<StorageKey>>);
+
+	/// Stream the storage pairs with the given prefix in pages of `page_size` pairs.
+	fn subscribe_storage_iter(
+		&self,
+		sink: SubscriptionSink,
+		block: Option<Block::Hash>,
+		prefix: Option<StorageKey>,
+		include_child_tries: bool,
+		page_size: u32,
+		cursor: Option<StorageIterCursor>,
+	);
+
+	/// Allow a storage iteration subscription to send `pages` more pages.
+	fn storage_iter_continue(&self, subscription: String, pages: u32);
 }
 
 /// Create new state API that works on full node.
@@ -345,6 +363,46 @@ where
 		self.backend.subscribe_storage(sink, keys);
 		Ok(())
 	}
+
+	fn subscribe_storage_iter(
+		&self,
+		mut sink: SubscriptionSink,
+		prefix: Option<StorageKey>,
+		include_child_tries: bool,
+		page_size: Option<u32>,
+		cursor: Option<StorageIterCursor>,
+		block: Option<Block::Hash>,
+	) -> SubscriptionResult {
+		if let Err(err) = self.deny_unsafe.check_if_safe() {
+			let _ = sink.reject(JsonRpseeError::from(err));
+			return Ok(())
+		}
+
+		let page_size = page_size.unwrap_or(STORAGE_ITER_DEFAULT_PAGE_SIZE);
+		if page_size > STORAGE_ITER_MAX_PAGE_SIZE {
+			let _ = sink.reject(JsonRpseeError::from(Error::InvalidCount {
+				value: page_size,
+				max: STORAGE_ITER_MAX_PAGE_SIZE,
+			}));
+			return Ok(())
+		}
+
+		self.backend.subscribe_storage_iter(
+			sink,
+			block,
+			prefix,
+			include_child_tries,
+			page_size,
+			cursor,
+		);
+		Ok(())
+	}
+
+	fn storage_iter_continue(&self, subscription: String, pages: u32) -> RpcResult<()> {
+		self.deny_unsafe.check_if_safe()?;
+		self.backend.storage_iter_continue(subscription, pages);
+		Ok(())
+	}
 }
 
 /// Child state backend API.
@@ -23,6 +23,7 @@ use std::{collections::HashMap, marker::PhantomData, sync::Arc, time::Duration};
 use super::{
 	client_err,
 	error::{Error, Result},
+	storage_iter::{StorageIter, StorageIterSubscriptions, MAX_STORAGE_ITERS},
 	ChildStateBackend, StateBackend,
 };
 use crate::{DenyUnsafe, SubscriptionTaskExecutor};
@@ -30,13 +31,14 @@ use crate::{DenyUnsafe, SubscriptionTaskExecutor};
 use futures::{future, stream, FutureExt, StreamExt};
 use jsonrpsee::{
 	core::{async_trait, Error as JsonRpseeError},
+	types::SubscriptionId,
 	SubscriptionSink,
 };
 use sc_client_api::{
 	Backend, BlockBackend, BlockchainEvents, CallExecutor, ExecutorProvider, ProofProvider,
 	StorageProvider,
 };
-use sc_rpc_api::state::ReadProof;
+use sc_rpc_api::state::{ReadProof, StorageIterCursor};
 use sp_api::{CallApiAt, Metadata, ProvideRuntimeApi};
 use sp_blockchain::{
 	CachedHeaderMetadata, Error as ClientError, HeaderBackend, HeaderMetadata,
@@ -65,6 +67,7 @@ struct QueryStorageRange<Block: BlockT> {
 pub struct FullState<BE, Block: BlockT, Client> {
 	client: Arc<Client>,
 	executor: SubscriptionTaskExecutor,
+	storage_iters: StorageIterSubscriptions,
 	_phantom: PhantomData<(BE, Block)>,
 }
 
@@ -79,7 +82,7 @@ where
 {
 	/// Create new state API backend for full nodes.
 	pub fn new(client: Arc<Client>, executor: SubscriptionTaskExecutor) -> Self {
-		Self { client, executor, _phantom: PhantomData }
+		Self { client, executor, storage_iters: Default::default(), _phantom: PhantomData }
 	}
 
 	/// Returns given block hash or best block hash if None is passed.
@@ -457,6 +460,58 @@ where
 		self.executor.spawn("substrate-rpc-subscription", Some("rpc"), fut.boxed());
 	}
 
+	fn subscribe_storage_iter(
+		&self,
+		mut sink: SubscriptionSink,
+		block: Option<Block::Hash>,
+		prefix: Option<StorageKey>,
+		include_child_tries: bool,
+		page_size: u32,
+		cursor: Option<StorageIterCursor>,
+	) {
+		let block = match self.block_or_best(block) {
+			Ok(block) => block,
+			Err(e) => {
+				let _ = sink.reject(JsonRpseeError::from(client_err(e)));
+				return
+			},
+		};
+
+		let Some(slot) = self.storage_iters.reserve() else {
+			let _ = sink.reject(JsonRpseeError::from(Error::TooManyStorageIterations {
+				max: MAX_STORAGE_ITERS,
+			}));
+			return
+		};
+
+		// The subscription id is needed to match `state_storageIterContinue` calls, and is only
+		// available once the subscription is accepted.
+		if sink.accept().is_err() {
+			return
+		}
+		let subscription = match sink.subscription_id() {
+			Some(SubscriptionId::Num(num)) => num.to_string(),
+			Some(SubscriptionId::Str(id)) => id.into_owned(),
+			None => return,
+		};
+
+		let iter = StorageIter::new(
+			self.client.clone(),
+			block,
+			prefix,
+			include_child_tries,
+			page_size,
+			cursor,
+		);
+		let fut = self.storage_iters.clone().run(slot, subscription, sink, iter);
+
+		self.executor.spawn("substrate-rpc-subscription", Some("rpc"), fut.boxed());
+	}
+
+	fn storage_iter_continue(&self, subscription: String, pages: u32) {
+		self.storage_iters.grant(&subscription, pages);
+	}
+
 	fn trace_block(
 		&self,
 		block: Block::Hash,
@@ -0,0 +1,290 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Implementation of the `state_subscribeStorageIter` subscription.
+
+use std::{collections::HashMap, marker::PhantomData, sync::Arc, time::Duration};
+
+use jsonrpsee::{
+	types::error::{ErrorObject, CALL_EXECUTION_FAILED_CODE},
+	SubscriptionSink,
+};
+use parking_lot::Mutex;
+use sc_client_api::{Backend, StorageProvider};
+use sc_rpc_api::state::{StorageIterCursor, StorageIterPage};
+use sp_blockchain::{Error as ClientError, Result as ClientResult};
+use sp_core::storage::{
+	well_known_keys::is_default_child_storage_key, ChildInfo, ChildType, PrefixedStorageKey,
+	StorageKey,
+};
+use sp_runtime::traits::Block as BlockT;
+use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore};
+
+/// The maximum number of storage iterations running at the same time.
+pub(super) const MAX_STORAGE_ITERS: usize = 16;
+
+/// The number of pages sent before the client has requested any.
+const INITIAL_PAGE_CREDIT: u32 = 4;
+
+/// The maximum number of pages a client can request ahead.
+const MAX_PAGE_CREDIT: u32 = 64;
+
+/// The time to wait for the client to request more pages before closing the subscription.
+const CONTINUE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The number of pages a storage iteration is still allowed to send.
+#[derive(Default)]
+struct PageCredit {
+	pages: Mutex<u32>,
+	granted: Notify,
+}
+
+impl PageCredit {
+	fn grant(&self, pages: u32) {
+		let mut credit = self.pages.lock();
+		*credit = credit.saturating_add(pages).min(MAX_PAGE_CREDIT);
+		self.granted.notify_one();
+	}
+
+	/// Take the credit for one page, waiting at most `timeout` for it to be granted.
+	///
+	/// Returns `false` if no credit was granted in time.
+	async fn take(&self, timeout: Duration) -> bool {
+		loop {
+			{
+				let mut credit = self.pages.lock();
+				if *credit > 0 {
+					*credit -= 1;
+					return true
+				}
+			}
+
+			// A grant in between stores a permit, so `notified` returns right away.
+			if tokio::time::timeout(timeout, self.granted.notified()).await.is_err() {
+				return false
+			}
+		}
+	}
+}
+
+/// The page credits of the running storage iterations, by subscription id.
+#[derive(Clone)]
+pub(super) struct StorageIterSubscriptions {
+	credits: Arc<Mutex<HashMap<String, Arc<PageCredit>>>>,
+	/// Limits the number of storage iterations running at the same time.
+	slots: Arc<Semaphore>,
+}
+
+impl Default for StorageIterSubscriptions {
+	fn default() -> Self {
+		Self { credits: Default::default(), slots: Arc::new(Semaphore::new(MAX_STORAGE_ITERS)) }
+	}
+}
+
+impl StorageIterSubscriptions {
+	/// Reserve a slot for a new storage iteration.
+	///
+	/// Returns `None` if [`MAX_STORAGE_ITERS`] iterations are running already.
+	pub fn reserve(&self) -> Option<OwnedSemaphorePermit> {
+		self.slots.clone().try_acquire_owned().ok()
+	}
+
+	/// Grant `pages` more pages to the given subscription.
+	///
+	/// Unknown subscriptions are ignored, as they might have finished in the meantime.
+	pub fn grant(&self, subscription: &str, pages: u32) {
+		if let Some(credit) = self.credits.lock().get(subscription) {
+			credit.grant(pages);
+		}
+	}
+
+	/// Run `iter` for the given subscription until all pages are sent, the subscription is
+	/// closed or the client stops requesting pages.
+	///
+	/// The `slot` is released once the iteration is done.
+	pub async fn run<Client, Block, BE>(
+		self,
+		_slot: OwnedSemaphorePermit,
+		subscription: String,
+		mut sink: SubscriptionSink,
+		mut iter: StorageIter<Client, Block, BE>,
+	) where
+		Block: BlockT,
+		BE: Backend<Block>,
+		Client: StorageProvider<Block, BE>,
+	{
+		let credit = Arc::new(PageCredit::default());
+		credit.grant(INITIAL_PAGE_CREDIT);
+		self.credits.lock().insert(subscription.clone(), credit.clone());
+
+		while credit.take(CONTINUE_TIMEOUT).await {
+			let page = match iter.next_page() {
+				Ok(page) => page,
+				Err(error) => {
+					sink.close(ErrorObject::owned(
+						CALL_EXECUTION_FAILED_CODE,
+						error.to_string(),
+						None::<()>,
+					));
+					break
+				},
+			};
+
+			let is_last = page.cursor.is_none();
+			// Stop if the client disconnected or the page could not be serialized.
+			if !matches!(sink.send(&page), Ok(true)) || is_last {
+				break
+			}
+		}
+
+		self.credits.lock().remove(&subscription);
+	}
+}
+
+/// Iterates the pairs of the main trie and optionally of the child tries in pages.
+pub(super) struct StorageIter<Client, Block: BlockT, BE> {
+	client: Arc<Client>,
+	block: Block::Hash,
+	prefix: Option<StorageKey>,
+	include_child_tries: bool,
+	page_size: usize,
+	/// Where the next page starts, `None` before the first page.
+	cursor: Option<StorageIterCursor>,
+	_phantom: PhantomData<BE>,
+}
+
+impl<Client, Block, BE> StorageIter<Client, Block, BE>
+where
+	Block: BlockT,
+	BE: Backend<Block>,
+	Client: StorageProvider<Block, BE>,
+{
+	/// Create a new storage iteration, starting at `cursor` if given.
+	pub fn new(
+		client: Arc<Client>,
+		block: Block::Hash,
+		prefix: Option<StorageKey>,
+		include_child_tries: bool,
+		page_size: u32,
+		cursor: Option<StorageIterCursor>,
+	) -> Self {
+		Self {
+			client,
+			block,
+			prefix,
+			include_child_tries,
+			page_size: page_size.max(1) as usize,
+			cursor,
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Read the next page.
+	///
+	/// Pages are only empty if they are the last one. Must not be called after the last page.
+	pub fn next_page(&mut self) -> ClientResult<StorageIterPage<Block::Hash>> {
+		loop {
+			let page = match self.cursor.take() {
+				Some(StorageIterCursor::Child(child_root, last_key))
+					if self.include_child_tries =>
+					self.child_page(child_root, last_key)?,
+				Some(StorageIterCursor::Child(child_root, _)) =>
+					self.main_page(Some(StorageKey(child_root.into_inner())))?,
+				Some(StorageIterCursor::After(key)) => self.main_page(Some(key))?,
+				None => self.main_page(None)?,
+			};
+
+			match &page.cursor {
+				Some(cursor) if page.pairs.is_empty() => self.cursor = Some(cursor.clone()),
+				cursor => {
+					self.cursor = cursor.clone();
+					return Ok(page)
+				},
+			}
+		}
+	}
+
+	/// Read a page of the main trie, starting after `start_key`.
+	///
+	/// The page ends after a child trie root if child tries are included.
+	fn main_page(
+		&self,
+		start_key: Option<StorageKey>,
+	) -> ClientResult<StorageIterPage<Block::Hash>> {
+		let mut pairs = Vec::new();
+		let mut cursor = None;
+
+		for (key, value) in
+			self.client
+				.storage_pairs(self.block, self.prefix.as_ref(), start_key.as_ref())?
+		{
+			let child_root = (self.include_child_tries && is_default_child_storage_key(&key.0))
+				.then(|| PrefixedStorageKey::new(key.0.clone()));
+			pairs.push((key.clone(), value));
+
+			if let Some(child_root) = child_root {
+				cursor = Some(StorageIterCursor::Child(child_root, None));
+				break
+			}
+			if pairs.len() == self.page_size {
+				cursor = Some(StorageIterCursor::After(key));
+				break
+			}
+		}
+
+		Ok(StorageIterPage { at: self.block, child_storage_key: None, pairs, cursor })
+	}
+
+	/// Read a page of the child trie stored under `child_root`, starting after `start_key`.
+	fn child_page(
+		&self,
+		child_root: PrefixedStorageKey,
+		start_key: Option<StorageKey>,
+	) -> ClientResult<StorageIterPage<Block::Hash>> {
+		let child_info = match ChildType::from_prefixed_key(&child_root) {
+			Some((ChildType::ParentKeyId, storage_key)) => ChildInfo::new_default(storage_key),
+			None => return Err(ClientError::InvalidChildStorageKey),
+		};
+
+		let mut pairs = Vec::new();
+		for (key, value) in
+			self.client
+				.child_storage_pairs(self.block, child_info, None, start_key.as_ref())?
+		{
+			pairs.push((key, value));
+
+			if pairs.len() == self.page_size {
+				let last_key = pairs.last().map(|(key, _)| key.clone());
+				return Ok(StorageIterPage {
+					at: self.block,
+					child_storage_key: Some(child_root.clone()),
+					pairs,
+					cursor: Some(StorageIterCursor::Child(child_root, last_key)),
+				})
+			}
+		}
+
+		let cursor = StorageIterCursor::After(StorageKey(child_root.clone().into_inner()));
+		Ok(StorageIterPage {
+			at: self.block,
+			child_storage_key: Some(child_root),
+			pairs,
+			cursor: Some(cursor),
+		})
+	}
+}
@@ -22,7 +22,7 @@ use crate::testing::{test_executor, timeout_secs};
 use assert_matches::assert_matches;
 use futures::executor;
 use jsonrpsee::{
-	core::Error as RpcError,
+	core::{server::rpc_module::Subscription as RpcSubscription, Error as RpcError},
 	types::{error::CallError as RpcCallError, EmptyServerParams as EmptyParams, ErrorObject},
 };
 use sc_block_builder::BlockBuilderBuilder;
@@ -562,6 +562,124 @@ async fn should_notify_on_runtime_version_initially() {
 	assert_matches!(timeout_secs(10, sub.next::<RuntimeVersion>()).await, Ok(None));
 }
 
+async fn next_storage_iter_page(sub: &mut RpcSubscription) -> StorageIterPage<H256> {
+	let (page, _sub_id) = timeout_secs(10, sub.next::<StorageIterPage<H256>>())
+		.await
+		.unwrap()
+		.unwrap()
+		.unwrap();
+	page
+}
+
+#[tokio::test]
+async fn should_stream_storage_pairs_with_child_tries() {
+	let child1 = ChildInfo::new_default(b"child1");
+	let child2 = ChildInfo::new_default(b"child2");
+	let client = TestClientBuilder::new()
+		.add_extra_child_storage(&child1, b"a".to_vec(), vec![1])
+		.add_extra_child_storage(&child1, b"b".to_vec(), vec![2])
+		.add_extra_child_storage(&child1, b"c".to_vec(), vec![3])
+		.add_extra_child_storage(&child2, b"a".to_vec(), vec![4])
+		.build();
+	let (api, _child) = new_full(Arc::new(client), test_executor(), DenyUnsafe::No);
+	let api_rpc = api.into_rpc();
+
+	let root1 = child1.prefixed_storage_key();
+	let root2 = child2.prefixed_storage_key();
+	let prefix = StorageKey(b":child_storage:default:child".to_vec());
+	let key = |key: &[u8]| StorageKey(key.to_vec());
+	let keys_of = |page: &StorageIterPage<H256>| {
+		page.pairs.iter().map(|(key, _)| key.0.clone()).collect::<Vec<_>>()
+	};
+	let mut sub = api_rpc
+		.subscribe(
+			"state_subscribeStorageIter",
+			[
+				serde_json::json!(prefix),
+				serde_json::json!(true),
+				serde_json::json!(2),
+				serde_json::Value::Null,
+				serde_json::Value::Null,
+			],
+		)
+		.await
+		.unwrap();
+	let sub_id = serde_json::to_string(sub.subscription_id()).unwrap();
+
+	let page = next_storage_iter_page(&mut sub).await;
+	assert_eq!(page.child_storage_key, None);
+	assert_eq!(keys_of(&page), vec![root1.clone().into_inner()]);
+	assert_eq!(page.cursor, Some(StorageIterCursor::Child(root1.clone(), None)));
+
+	let page = next_storage_iter_page(&mut sub).await;
+	assert_eq!(page.child_storage_key, Some(root1.clone()));
+	assert_eq!(
+		page.pairs,
+		vec![(key(b"a"), StorageData(vec![1])), (key(b"b"), StorageData(vec![2]))]
+	);
+	assert_eq!(page.cursor, Some(StorageIterCursor::Child(root1.clone(), Some(key(b"b")))));
+
+	let page = next_storage_iter_page(&mut sub).await;
+	assert_eq!(page.child_storage_key, Some(root1.clone()));
+	assert_eq!(keys_of(&page), vec![b"c".to_vec()]);
+	assert_eq!(page.cursor, Some(StorageIterCursor::After(StorageKey(root1.clone().into_inner()))));
+
+	let page = next_storage_iter_page(&mut sub).await;
+	assert_eq!(keys_of(&page), vec![root2.clone().into_inner()]);
+
+	// The initial pages are used up, no page is sent until more are requested.
+	assert!(timeout_secs(1, sub.next::<StorageIterPage<H256>>()).await.is_err());
+	let _: () = api_rpc
+		.call("state_storageIterContinue", [serde_json::json!(sub_id), serde_json::json!(2)])
+		.await
+		.unwrap();
+
+	let page = next_storage_iter_page(&mut sub).await;
+	assert_eq!(page.child_storage_key, Some(root2.clone()));
+	assert_eq!(keys_of(&page), vec![b"a".to_vec()]);
+
+	let page = next_storage_iter_page(&mut sub).await;
+	assert!(page.pairs.is_empty());
+	assert_eq!(page.cursor, None);
+
+	// Resume the iteration in the middle of the first child trie.
+	let mut sub = api_rpc
+		.subscribe(
+			"state_subscribeStorageIter",
+			[
+				serde_json::json!(prefix),
+				serde_json::json!(true),
+				serde_json::json!(2),
+				serde_json::json!(StorageIterCursor::Child(root1.clone(), Some(key(b"b")))),
+				serde_json::Value::Null,
+			],
+		)
+		.await
+		.unwrap();
+	assert_eq!(keys_of(&next_storage_iter_page(&mut sub).await), vec![b"c".to_vec()]);
+	assert_eq!(keys_of(&next_storage_iter_page(&mut sub).await), vec![root2.into_inner()]);
+}
+
+#[tokio::test]
+async fn storage_iter_subscriptions_are_rpc_unsafe() {
+	let client = Arc::new(substrate_test_runtime_client::new());
+	let (api, _child) = new_full(client, test_executor(), DenyUnsafe::Yes);
+
+	let api_rpc = api.into_rpc();
+	let err = api_rpc
+		.subscribe(
+			"state_subscribeStorageIter",
+			[serde_json::Value::Null, serde_json::json!(false)],
+		)
+		.await;
+	assert_matches!(err, Err(RpcError::Call(RpcCallError::Custom(e))) if e.message() == "RPC call is unsafe to be called externally");
+
+	let err = api_rpc
+		.call::<_, ()>("state_storageIterContinue", [serde_json::json!("0"), serde_json::json!(1)])
+		.await;
+	assert_matches!(err, Err(RpcError::Call(RpcCallError::Custom(e))) if e.message() == "RPC call is unsafe to be called externally");
+}
+
 #[test]
 fn should_deserialize_storage_key() {
 	let k = "\"0x7f864e18e3dd8b58386310d2fe0919eef27c6e558564b7f67f22d99d20f587b\"";
@@ -44,9 +44,11 @@ use sc_keystore::LocalKeystore;
 use sc_network::{
 	config::{FullNetworkConfiguration, SyncMode},
 	peer_store::PeerStore,
-	NetworkService, NetworkStateInfo, NetworkStatusProvider,
+	NetworkRequest, NetworkService, NetworkStateInfo, NetworkStatusProvider,
+};
+use sc_network_bitswap::{
+	BitswapClient, BitswapRequestHandler, IndexedTransactionsBackfill, IndexedTransactionsProvider,
 };
-use sc_network_bitswap::BitswapRequestHandler;
 use sc_network_common::role::Roles;
 use sc_network_light::light_client_requests::handler::LightClientRequestHandler;
 use sc_network_sync::{
@@ -63,7 +65,9 @@ use sc_rpc::{
 	system::SystemApiServer,
 	DenyUnsafe, SubscriptionTaskExecutor,
 };
-use sc_rpc_spec_v2::{chain_head::ChainHeadApiServer, transaction::TransactionApiServer};
+use sc_rpc_spec_v2::{
+	archive::ArchiveApiServer, chain_head::ChainHeadApiServer, transaction::TransactionApiServer,
+};
 use sc_telemetry::{telemetry, ConnectionMessage, Telemetry, TelemetryHandle, SUBSTRATE_INFO};
 use sc_transaction_pool_api::{MaintainedTransactionPool, TransactionPool};
 use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedSender};
@@ -75,7 +79,14 @@ use sp_consensus::block_validation::{
 use sp_core::traits::{CodeExecutor, SpawnNamed};
 use sp_keystore::KeystorePtr;
 use sp_runtime::traits::{Block as BlockT, BlockIdTo, NumberFor, Zero};
-use std::{str::FromStr, sync::Arc, time::SystemTime};
+use std::{
+	str::FromStr,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc,
+	},
+	time::SystemTime,
+};
 
 /// Full client type.
 pub type TFullClient<TBl, TRtApi, TExec> =
@@ -190,7 +201,7 @@ where
 {
 	let keystore_container = KeystoreContainer::new(&config.keystore)?;
 
-	let task_manager = {
+	let mut task_manager = {
 		let registry = config.prometheus_config.as_ref().map(|cfg| &cfg.registry);
 		TaskManager::new(config.tokio_handle.clone(), registry)?
 	};
@@ -253,9 +264,40 @@ where
 		client
 	};
 
+	let trie_cache_shutdown = TrieCacheShutdown::new(backend.clone());
+	if let (true, Some(maximum_size)) = (config.trie_cache_warmup, config.trie_cache_maximum_size) {
+		let backend = backend.clone();
+		let stopped = trie_cache_shutdown.stopped.clone();
+		task_manager
+			.spawn_handle()
+			.spawn_blocking("trie-cache-warmup", None, async move {
+				backend.warm_up_trie_cache(maximum_size, || stopped.load(Ordering::Relaxed))
+			});
+	}
+	task_manager.keep_alive(trie_cache_shutdown);
+
 	Ok((client, backend, keystore_container, task_manager))
 }
 
+/// Stops the trie cache warm-up and exports the trie cache when the node shuts down.
+struct TrieCacheShutdown<Block: BlockT> {
+	backend: Arc<Backend<Block>>,
+	stopped: Arc<AtomicBool>,
+}
+
+impl<Block: BlockT> TrieCacheShutdown<Block> {
+	fn new(backend: Arc<Backend<Block>>) -> Self {
+		Self { backend, stopped: Default::default() }
+	}
+}
+
+impl<Block: BlockT> Drop for TrieCacheShutdown<Block> {
+	fn drop(&mut self) {
+		self.stopped.store(true, Ordering::Relaxed);
+		self.backend.export_trie_cache_snapshot();
+	}
+}
+
 /// Creates a [`NativeElseWasmExecutor`] according to [`Configuration`].
 pub fn new_native_or_wasm_executor<D: NativeExecutionDispatch>(
 	config: &Configuration,
@@ -268,13 +310,17 @@ pub fn new_wasm_executor<H: HostFunctions>(config: &Configuration) -> WasmExecut
 	let strategy = config
 		.default_heap_pages
 		.map_or(DEFAULT_HEAP_ALLOC_STRATEGY, |p| HeapAllocStrategy::Static { extra_pages: p as _ });
-	WasmExecutor::<H>::builder()
+	let mut builder = WasmExecutor::<H>::builder()
 		.with_execution_method(config.wasm_method)
 		.with_onchain_heap_alloc_strategy(strategy)
 		.with_offchain_heap_alloc_strategy(strategy)
 		.with_max_runtime_instances(config.max_runtime_instances)
 		.with_runtime_cache_size(config.runtime_cache_size)
-		.build()
+		.with_offchain_execution_limits(config.offchain_execution_limits);
+	if let Some(cache_path) = config.executor_cache_path() {
+		builder = builder.with_cache_path(cache_path);
+	}
+	builder.build()
 }
 
 /// Create an instance of default DB-backend backend.
@@ -685,6 +731,23 @@ where
 	rpc_api.merge(transaction_v2).map_err(|e| Error::Application(e.into()))?;
 	rpc_api.merge(chain_head_v2).map_err(|e| Error::Application(e.into()))?;
 
+	// The `archive` methods can only be answered by nodes keeping the state and the bodies of
+	// all blocks.
+	let is_archive_node = config.state_pruning.as_ref().map_or(false, |mode| mode.is_archive()) &&
+		config.blocks_pruning.is_archive();
+	if is_archive_node {
+		let genesis_hash = client.info().genesis_hash;
+		let archive_v2 = sc_rpc_spec_v2::archive::Archive::new(
+			client.clone(),
+			backend.clone(),
+			genesis_hash,
+			// Defaults to sensible limits for the `Archive`.
+			sc_rpc_spec_v2::archive::ArchiveConfig::default(),
+		)
+		.into_rpc();
+		rpc_api.merge(archive_v2).map_err(|e| Error::Application(e.into()))?;
+	}
+
 	// Part of the old RPC spec.
 	rpc_api.merge(chain).map_err(|e| Error::Application(e.into()))?;
 	rpc_api.merge(author).map_err(|e| Error::Application(e.into()))?;
@@ -722,6 +785,40 @@ pub struct BuildNetworkParams<'a, TBl: BlockT, TExPool, TImpQu, TCl> {
 	pub block_relay: Option<BlockRelayParams<TBl>>,
 }
 
+/// Spawn the backfill of the indexed transactions that were missed while syncing in storage chain
+/// mode.
+///
+/// Nothing is spawned unless the node syncs in storage chain mode. The indexed transactions the
+/// runtime still references are listed by `provider`, e.g. a
+/// [`TransactionStoragePallet`](sc_network_bitswap::TransactionStoragePallet).
+pub fn spawn_indexed_transactions_backfill<TBl, TCl, TBackend, TProvider>(
+	config: &Configuration,
+	client: Arc<TCl>,
+	backend: Arc<TBackend>,
+	provider: TProvider,
+	network: Arc<dyn NetworkRequest + Send + Sync>,
+	sync_service: Arc<SyncingService<TBl>>,
+	spawn_handle: &SpawnTaskHandle,
+) where
+	TBl: BlockT,
+	TCl: HeaderBackend<TBl> + BlockBackend<TBl> + Send + Sync + 'static,
+	TBackend: sc_client_api::backend::Backend<TBl> + 'static,
+	TProvider: IndexedTransactionsProvider<TBl> + 'static,
+{
+	if !matches!(config.network.sync_mode, SyncMode::LightState { storage_chain_mode: true, .. }) {
+		return
+	}
+
+	let backfill = IndexedTransactionsBackfill::new(
+		client,
+		backend,
+		provider,
+		BitswapClient::new(network),
+		sync_service,
+	);
+	spawn_handle.spawn("indexed-transactions-backfill", Some("networking"), backfill.run());
+}
+
 /// Build the network service, the network status sinks and an RPC sender.
 pub fn build_network<TBl, TExPool, TImpQu, TCl>(
 	params: BuildNetworkParams<TBl, TExPool, TImpQu, TCl>,
@@ -865,6 +962,12 @@ where
 		let (handler, protocol_config) = BitswapRequestHandler::new(client.clone());
 		spawn_handle.spawn("bitswap-request-handler", Some("networking"), handler.run());
 		net_config.add_request_response_protocol(protocol_config);
+	} else if matches!(
+		config.network.sync_mode,
+		SyncMode::LightState { storage_chain_mode: true, .. }
+	) {
+		// Needed to backfill indexed transactions, even if we don't serve them.
+		net_config.add_request_response_protocol(BitswapClient::protocol_config());
 	}
 
 	// create transactions protocol and add it to the list of supported protocols of
@@ -0,0 +1,472 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Versioned, self-describing archive format for blocks and state snapshots.
+//!
+//! An archive starts with [`ARCHIVE_MAGIC`], followed by the SCALE encoded format version and an
+//! [`ArchiveHeader`]. The header is followed by a sequence of sections. Every section starts with
+//! a SCALE encoded [`SectionHeader`] that describes the content of the payload and carries the
+//! `blake2_256` hash of the payload as it is stored in the archive. Payloads are compressed
+//! according to the [`ArchiveCodec`] of the archive.
+//!
+//! The last section is always [`SectionKind::End`]. Its payload is the manifest of the archive:
+//! the list of the hashes of all the previous sections. As the archive is written as a stream,
+//! the manifest is written at the end instead of being part of the header.
+
+use codec::{Decode, Encode};
+use sp_core::hashing::blake2_256;
+use std::io::{self, Cursor, Read, Write};
+
+/// The bytes every archive starts with.
+pub const ARCHIVE_MAGIC: [u8; 8] = *b"SUBARCHV";
+
+/// The version of the archive format written by this implementation.
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// The maximum size of a single section payload, before and after decompression.
+const MAX_SECTION_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// The zstd compression level used when writing archives.
+const ZSTD_LEVEL: i32 = 3;
+
+/// The encoding of the section payloads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum ArchiveCodec {
+	/// SCALE encoded payloads.
+	Scale,
+	/// SCALE encoded payloads compressed with zstd.
+	ScaleZstd,
+}
+
+/// Describes the state snapshot bundled in an archive.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct ArchiveStateInfo {
+	/// The number of the block the state belongs to.
+	pub number: u64,
+	/// The hash of the block the state belongs to.
+	pub hash: Vec<u8>,
+}
+
+/// The header of an archive.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct ArchiveHeader {
+	/// The genesis hash of the chain the archive was exported from.
+	pub genesis_hash: Vec<u8>,
+	/// The number of the first exported block.
+	pub from: u64,
+	/// The number of the last exported block.
+	pub to: u64,
+	/// The encoding of the section payloads.
+	pub codec: ArchiveCodec,
+	/// The state snapshot bundled in the archive, if any.
+	pub state: Option<ArchiveStateInfo>,
+}
+
+/// The content of a section.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub enum SectionKind {
+	/// A chunk of consecutive blocks, encoded as `Vec<SignedBlock>`.
+	Blocks {
+		/// The number of the first block of the chunk.
+		first: u64,
+		/// The number of blocks in the chunk.
+		count: u32,
+		/// The hash of the last block of the chunk.
+		last_hash: Vec<u8>,
+	},
+	/// A part of the state snapshot, encoded as [`ArchiveStateLevel`].
+	State,
+	/// The end of the archive. The payload is the manifest, the hashes of all previous sections.
+	End,
+}
+
+/// The header of a section.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct SectionHeader {
+	/// The content of the section.
+	pub kind: SectionKind,
+	/// The length of the payload as stored in the archive.
+	pub length: u64,
+	/// The `blake2_256` hash of the payload as stored in the archive.
+	pub hash: [u8; 32],
+}
+
+/// Key values of the top trie or of a child trie of the state snapshot.
+///
+/// The key values of a single trie may be split over multiple sections.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct ArchiveStateLevel {
+	/// The root of the child trie, empty for the top trie.
+	pub state_root: Vec<u8>,
+	/// The prefixed storage keys of the child trie, empty for the top trie.
+	pub parent_storage_keys: Vec<Vec<u8>>,
+	/// The key values.
+	pub key_values: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// Reads the first bytes of `input` to check if it is an archive.
+///
+/// Returns the result together with a reader that yields the complete `input`.
+pub fn detect_archive<R: Read>(mut input: R) -> io::Result<(bool, impl Read)> {
+	let mut prefix = Vec::with_capacity(ARCHIVE_MAGIC.len());
+	(&mut input).take(ARCHIVE_MAGIC.len() as u64).read_to_end(&mut prefix)?;
+
+	Ok((prefix == ARCHIVE_MAGIC, Cursor::new(prefix).chain(input)))
+}
+
+/// Writes an archive section by section.
+pub struct ArchiveWriter<W> {
+	output: W,
+	codec: ArchiveCodec,
+	manifest: Vec<[u8; 32]>,
+}
+
+impl<W: Write> ArchiveWriter<W> {
+	/// Creates a new [`ArchiveWriter`] and writes the header of the archive.
+	pub fn new(mut output: W, header: &ArchiveHeader) -> io::Result<Self> {
+		output.write_all(&ARCHIVE_MAGIC)?;
+		output.write_all(&ARCHIVE_VERSION.encode())?;
+		output.write_all(&header.encode())?;
+
+		Ok(Self { output, codec: header.codec, manifest: Vec::new() })
+	}
+
+	/// Writes a section with the given `payload`.
+	pub fn write_section(&mut self, kind: SectionKind, payload: &[u8]) -> io::Result<()> {
+		if kind == SectionKind::End {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"The end of the archive is written by `finish`",
+			))
+		}
+
+		let hash = self.write_raw_section(kind, payload)?;
+		self.manifest.push(hash);
+		Ok(())
+	}
+
+	/// Writes the manifest and returns the output.
+	pub fn finish(mut self) -> io::Result<W> {
+		let manifest = self.manifest.encode();
+		self.write_raw_section(SectionKind::End, &manifest)?;
+		self.output.flush()?;
+		Ok(self.output)
+	}
+
+	fn write_raw_section(&mut self, kind: SectionKind, payload: &[u8]) -> io::Result<[u8; 32]> {
+		let stored = match self.codec {
+			ArchiveCodec::Scale => payload.to_vec(),
+			ArchiveCodec::ScaleZstd => zstd::stream::encode_all(payload, ZSTD_LEVEL)?,
+		};
+		let hash = blake2_256(&stored);
+		let header = SectionHeader { kind, length: stored.len() as u64, hash };
+
+		self.output.write_all(&header.encode())?;
+		self.output.write_all(&stored)?;
+		Ok(hash)
+	}
+}
+
+/// Reads an archive section by section.
+///
+/// Every section is announced by [`ArchiveReader::next_section`]. The payload of the section can
+/// then be read with [`ArchiveReader::read_payload`], otherwise it is skipped.
+pub struct ArchiveReader<R> {
+	input: codec::IoReader<R>,
+	header: ArchiveHeader,
+	current: Option<SectionHeader>,
+	manifest: Vec<[u8; 32]>,
+	finished: bool,
+}
+
+impl<R: Read> ArchiveReader<R> {
+	/// Creates a new [`ArchiveReader`] and reads the header of the archive.
+	pub fn new(mut input: R) -> Result<Self, String> {
+		let mut magic = [0u8; ARCHIVE_MAGIC.len()];
+		input
+			.read_exact(&mut magic)
+			.map_err(|e| format!("Failed to read the archive magic: {}", e))?;
+		if magic != ARCHIVE_MAGIC {
+			return Err("The input is not an archive".into())
+		}
+
+		let mut input = codec::IoReader(input);
+		let version = u32::decode(&mut input)
+			.map_err(|e| format!("Failed to decode the archive version: {}", e))?;
+		if version != ARCHIVE_VERSION {
+			return Err(format!(
+				"Unsupported archive version {}, expected {}",
+				version, ARCHIVE_VERSION
+			))
+		}
+
+		let header = ArchiveHeader::decode(&mut input)
+			.map_err(|e| format!("Failed to decode the archive header: {}", e))?;
+
+		Ok(Self { input, header, current: None, manifest: Vec::new(), finished: false })
+	}
+
+	/// Returns the header of the archive.
+	pub fn header(&self) -> &ArchiveHeader {
+		&self.header
+	}
+
+	/// Advances to the next section, skipping the payload of the current one if it wasn't read.
+	///
+	/// Returns `None` once the end of the archive is reached and the manifest was verified.
+	pub fn next_section(&mut self) -> Result<Option<SectionKind>, String> {
+		if self.finished {
+			return Ok(None)
+		}
+
+		if let Some(section) = self.current.take() {
+			io::copy(&mut (&mut self.input.0).take(section.length), &mut io::sink())
+				.map_err(|e| format!("Failed to skip the archive section: {}", e))?;
+		}
+
+		let section = SectionHeader::decode(&mut self.input)
+			.map_err(|e| format!("Failed to decode the archive section header: {}", e))?;
+		if section.length > MAX_SECTION_SIZE {
+			return Err(format!("The archive section is too large: {} bytes", section.length))
+		}
+
+		if section.kind == SectionKind::End {
+			self.current = Some(section);
+			let manifest = Vec::<[u8; 32]>::decode(&mut &self.read_payload()?[..])
+				.map_err(|e| format!("Failed to decode the archive manifest: {}", e))?;
+			if manifest != self.manifest {
+				return Err("The archive manifest doesn't match the archive sections".into())
+			}
+
+			self.finished = true;
+			return Ok(None)
+		}
+
+		let kind = section.kind.clone();
+		self.manifest.push(section.hash);
+		self.current = Some(section);
+		Ok(Some(kind))
+	}
+
+	/// Reads, verifies and decompresses the payload of the current section.
+	pub fn read_payload(&mut self) -> Result<Vec<u8>, String> {
+		let section = self.current.take().ok_or("No archive section to read")?;
+
+		let mut stored = Vec::new();
+		(&mut self.input.0)
+			.take(section.length)
+			.read_to_end(&mut stored)
+			.map_err(|e| format!("Failed to read the archive section: {}", e))?;
+		if stored.len() as u64 != section.length {
+			return Err("The archive ended unexpectedly".into())
+		}
+		if blake2_256(&stored) != section.hash {
+			return Err("The archive section doesn't match its checksum".into())
+		}
+
+		match self.header.codec {
+			ArchiveCodec::Scale => Ok(stored),
+			ArchiveCodec::ScaleZstd => {
+				let mut payload = Vec::new();
+				zstd::stream::read::Decoder::new(&stored[..])
+					.and_then(|decoder| {
+						decoder.take(MAX_SECTION_SIZE + 1).read_to_end(&mut payload)
+					})
+					.map_err(|e| format!("Failed to decompress the archive section: {}", e))?;
+				if payload.len() as u64 > MAX_SECTION_SIZE {
+					return Err("The decompressed archive section is too large".into())
+				}
+
+				Ok(payload)
+			},
+		}
+	}
+
+	/// Decodes the payload of the current section without buffering it.
+	///
+	/// The payload is verified while it is decoded, the decoded value is only returned if the
+	/// complete payload matches its checksum.
+	pub fn decode_payload<T: Decode>(&mut self) -> Result<T, String> {
+		let section = self.current.take().ok_or("No archive section to read")?;
+
+		let mut stored = HashingReader {
+			input: (&mut self.input.0).take(section.length),
+			hasher: blake2b_simd::Params::new().hash_length(32).to_state(),
+		};
+		let decoded = match self.header.codec {
+			ArchiveCodec::Scale => T::decode(&mut codec::IoReader(&mut stored)),
+			ArchiveCodec::ScaleZstd => {
+				let decoder = zstd::stream::read::Decoder::new(&mut stored)
+					.map_err(|e| format!("Failed to decompress the archive section: {}", e))?;
+				T::decode(&mut codec::IoReader(decoder.take(MAX_SECTION_SIZE)))
+			},
+		};
+
+		// Bytes not consumed by the decoding are still covered by the checksum.
+		io::copy(&mut stored, &mut io::sink())
+			.map_err(|e| format!("Failed to read the archive section: {}", e))?;
+		if stored.input.limit() != 0 {
+			return Err("The archive ended unexpectedly".into())
+		}
+		if stored.hasher.finalize().as_bytes() != &section.hash[..] {
+			return Err("The archive section doesn't match its checksum".into())
+		}
+
+		decoded.map_err(|e| format!("Failed to decode the archive section: {}", e))
+	}
+}
+
+/// Hashes all the bytes read from `input`.
+struct HashingReader<R> {
+	input: io::Take<R>,
+	hasher: blake2b_simd::State,
+}
+
+impl<R: Read> Read for HashingReader<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let read = self.input.read(buf)?;
+		self.hasher.update(&buf[..read]);
+		Ok(read)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn header(codec: ArchiveCodec) -> ArchiveHeader {
+		ArchiveHeader {
+			genesis_hash: vec![1; 32],
+			from: 1,
+			to: 2,
+			codec,
+			state: Some(ArchiveStateInfo { number: 2, hash: vec![2; 32] }),
+		}
+	}
+
+	fn blocks_section() -> SectionKind {
+		SectionKind::Blocks { first: 1, count: 2, last_hash: vec![2; 32] }
+	}
+
+	fn write_archive(codec: ArchiveCodec) -> Vec<u8> {
+		let mut writer = ArchiveWriter::new(Vec::new(), &header(codec)).unwrap();
+		writer.write_section(blocks_section(), &[1, 2, 3]).unwrap();
+		writer.write_section(SectionKind::State, &[4, 5, 6]).unwrap();
+		writer.finish().unwrap()
+	}
+
+	#[test]
+	fn archive_roundtrip_works() {
+		for codec in [ArchiveCodec::Scale, ArchiveCodec::ScaleZstd] {
+			let archive = write_archive(codec);
+
+			let (is_archive, input) = detect_archive(&archive[..]).unwrap();
+			assert!(is_archive);
+
+			let mut reader = ArchiveReader::new(input).unwrap();
+			assert_eq!(reader.header(), &header(codec));
+			assert_eq!(reader.next_section().unwrap(), Some(blocks_section()));
+			assert_eq!(reader.read_payload().unwrap(), vec![1, 2, 3]);
+			assert_eq!(reader.next_section().unwrap(), Some(SectionKind::State));
+			assert_eq!(reader.read_payload().unwrap(), vec![4, 5, 6]);
+			assert_eq!(reader.next_section().unwrap(), None);
+			assert_eq!(reader.next_section().unwrap(), None);
+		}
+	}
+
+	#[test]
+	fn unread_sections_are_skipped() {
+		let archive = write_archive(ArchiveCodec::ScaleZstd);
+
+		let mut reader = ArchiveReader::new(&archive[..]).unwrap();
+		assert_eq!(reader.next_section().unwrap(), Some(blocks_section()));
+		assert_eq!(reader.next_section().unwrap(), Some(SectionKind::State));
+		assert_eq!(reader.read_payload().unwrap(), vec![4, 5, 6]);
+		assert_eq!(reader.next_section().unwrap(), None);
+	}
+
+	#[test]
+	fn payloads_are_decoded_without_buffering() {
+		for codec in [ArchiveCodec::Scale, ArchiveCodec::ScaleZstd] {
+			let level = ArchiveStateLevel {
+				state_root: Vec::new(),
+				parent_storage_keys: Vec::new(),
+				key_values: vec![(vec![1], vec![2]), (vec![3], vec![4])],
+			};
+			let mut writer = ArchiveWriter::new(Vec::new(), &header(codec)).unwrap();
+			writer.write_section(SectionKind::State, &level.encode()).unwrap();
+			let archive = writer.finish().unwrap();
+
+			let mut reader = ArchiveReader::new(&archive[..]).unwrap();
+			assert_eq!(reader.next_section().unwrap(), Some(SectionKind::State));
+			assert_eq!(reader.decode_payload::<ArchiveStateLevel>().unwrap(), level);
+			assert_eq!(reader.next_section().unwrap(), None);
+		}
+	}
+
+	#[test]
+	fn corrupted_section_is_rejected_when_decoding() {
+		let mut archive = write_archive(ArchiveCodec::Scale);
+		let offset = archive.windows(3).position(|window| window == [4, 5, 6]).unwrap();
+		archive[offset + 2] ^= 0xff;
+
+		let mut reader = ArchiveReader::new(&archive[..]).unwrap();
+		assert_eq!(reader.next_section().unwrap(), Some(blocks_section()));
+		assert_eq!(reader.next_section().unwrap(), Some(SectionKind::State));
+		assert_eq!(
+			reader.decode_payload::<u8>().unwrap_err(),
+			"The archive section doesn't match its checksum"
+		);
+	}
+
+	#[test]
+	fn legacy_input_is_not_detected_as_archive() {
+		let input = 1u64.encode();
+
+		let (is_archive, mut input) = detect_archive(&input[..]).unwrap();
+		assert!(!is_archive);
+
+		let mut read = Vec::new();
+		input.read_to_end(&mut read).unwrap();
+		assert_eq!(read, 1u64.encode());
+	}
+
+	#[test]
+	fn corrupted_section_is_rejected() {
+		let mut archive = write_archive(ArchiveCodec::Scale);
+		let offset = archive.windows(3).position(|window| window == [1, 2, 3]).unwrap();
+		archive[offset] ^= 0xff;
+
+		let mut reader = ArchiveReader::new(&archive[..]).unwrap();
+		assert_eq!(reader.next_section().unwrap(), Some(blocks_section()));
+		assert_eq!(
+			reader.read_payload().unwrap_err(),
+			"The archive section doesn't match its checksum"
+		);
+	}
+
+	#[test]
+	fn unsupported_version_is_rejected() {
+		let mut archive = write_archive(ArchiveCodec::Scale);
+		archive[ARCHIVE_MAGIC.len()] = 2;
+
+		assert_eq!(
+			ArchiveReader::new(&archive[..]).err().unwrap(),
+			"Unsupported archive version 2, expected 1"
+		);
+	}
+}
@@ -47,7 +47,7 @@ where
 			1u64.encode_to(&mut buf);
 			block.encode_to(&mut buf);
 			let reader = std::io::Cursor::new(buf);
-			import_blocks(client, import_queue, reader, true, true).await
+			import_blocks(client, import_queue, reader, true, true, false).await
 		},
 		None => Err("Unknown block")?,
 	}
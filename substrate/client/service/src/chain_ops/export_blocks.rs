@@ -16,18 +16,28 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::error::Error;
+use crate::{
+	chain_ops::{
+		ArchiveCodec, ArchiveHeader, ArchiveStateInfo, ArchiveStateLevel, ArchiveWriter,
+		SectionKind,
+	},
+	error::Error,
+};
 use codec::Encode;
 use futures::{future, prelude::*};
 use log::info;
+use sp_core::storage::{well_known_keys, ChildInfo};
 use sp_runtime::{
 	generic::BlockId,
 	traits::{Block as BlockT, NumberFor, One, SaturatedConversion, Zero},
 };
 
-use sc_client_api::{BlockBackend, HeaderBackend, UsageProvider};
+use sc_client_api::{Backend, BlockBackend, HeaderBackend, StorageProvider, UsageProvider};
 use std::{io::Write, pin::Pin, sync::Arc, task::Poll};
 
+/// Number of key value bytes after which a new state section is started.
+const STATE_SECTION_SIZE: usize = 16 * 1024 * 1024;
+
 /// Performs the blocks export.
 pub fn export_blocks<B, C>(
 	client: Arc<C>,
@@ -105,3 +115,207 @@ where
 
 	Box::pin(export)
 }
+
+/// Options of [`export_archive`].
+#[derive(Debug, Clone, Copy)]
+pub struct ArchiveExportOptions {
+	/// The encoding of the archive sections.
+	pub codec: ArchiveCodec,
+	/// The maximum number of blocks per section.
+	pub blocks_per_section: u32,
+	/// Bundle the state of the last exported block.
+	pub with_state: bool,
+}
+
+/// Performs the blocks export into an archive.
+///
+/// If `to` is not provided, the export ends at the best block, or at the last finalized block if
+/// the state is bundled. The state can only be bundled for a finalized block.
+pub fn export_archive<B, BA, C>(
+	client: Arc<C>,
+	output: impl Write + 'static,
+	from: NumberFor<B>,
+	to: Option<NumberFor<B>>,
+	options: ArchiveExportOptions,
+) -> Pin<Box<dyn Future<Output = Result<(), Error>>>>
+where
+	C: HeaderBackend<B> + BlockBackend<B> + UsageProvider<B> + StorageProvider<B, BA> + 'static,
+	B: BlockT,
+	BA: Backend<B>,
+{
+	let info = client.usage_info().chain;
+	let last = match to {
+		Some(v) if v.is_zero() => One::one(),
+		Some(v) => v,
+		None if options.with_state => info.finalized_number,
+		None => info.best_number,
+	};
+
+	let prepare = || -> Result<_, Error> {
+		if last < from {
+			return Err("Invalid block range specified".into())
+		}
+
+		let state = if options.with_state {
+			if last > info.finalized_number {
+				return Err(format!(
+					"The state can only be bundled for a finalized block, #{} is not finalized",
+					last
+				)
+				.into())
+			}
+
+			let hash = client.hash(last)?.ok_or_else(|| format!("Block #{} not found", last))?;
+			Some((hash, ArchiveStateInfo { number: last.saturated_into(), hash: hash.encode() }))
+		} else {
+			None
+		};
+
+		let header = ArchiveHeader {
+			genesis_hash: info.genesis_hash.encode(),
+			from: from.saturated_into(),
+			to: last.saturated_into(),
+			codec: options.codec,
+			state: state.as_ref().map(|(_, info)| info.clone()),
+		};
+		let writer = ArchiveWriter::new(output, &header)?;
+
+		Ok((writer, state.map(|(hash, _)| hash)))
+	};
+	let (writer, state_hash) = match prepare() {
+		Ok(prepared) => prepared,
+		Err(e) => return Box::pin(future::ready(Err(e))),
+	};
+
+	info!("Exporting blocks from #{} to #{} into an archive", from, last);
+	let mut writer = Some(writer);
+	let mut block = from;
+
+	// Like `export_blocks`, the export is interruptible: every poll writes a single section.
+	let export = future::poll_fn(move |cx| {
+		let client = &client;
+		let writer_ref = writer.as_mut().expect("writer is only taken when finishing; qed");
+
+		if block > last {
+			if let Some(hash) = state_hash {
+				info!("Exporting the state of #{}", last);
+				write_state::<B, BA, C>(&**client, hash, writer_ref)?;
+			}
+
+			writer.take().expect("writer is only taken when finishing; qed").finish()?;
+			return Poll::Ready(Ok(()))
+		}
+
+		let first = block;
+		let mut blocks = Vec::new();
+		let mut last_hash = Vec::new();
+		while block <= last && blocks.len() < options.blocks_per_section as usize {
+			let hash = client.hash(block)?.ok_or_else(|| format!("Block #{} not found", block))?;
+			let signed_block =
+				client.block(hash)?.ok_or_else(|| format!("Block #{} not found", block))?;
+
+			blocks.push(signed_block);
+			last_hash = hash.encode();
+			if (block % 10000u32.into()).is_zero() {
+				info!("#{}", block);
+			}
+			block += One::one();
+		}
+
+		let kind = SectionKind::Blocks {
+			first: first.saturated_into(),
+			count: blocks.len() as u32,
+			last_hash,
+		};
+		writer_ref.write_section(kind, &blocks.encode())?;
+
+		// Re-schedule the task in order to continue the operation.
+		cx.waker().wake_by_ref();
+		Poll::Pending
+	});
+
+	Box::pin(export)
+}
+
+/// Writes the state at `hash` into the archive, split into sections of bounded size.
+fn write_state<B, BA, C>(
+	client: &C,
+	hash: B::Hash,
+	writer: &mut ArchiveWriter<impl Write>,
+) -> Result<(), Error>
+where
+	C: StorageProvider<B, BA>,
+	B: BlockT,
+	BA: Backend<B>,
+{
+	let mut child_roots = Vec::new();
+	let mut level = StateLevelWriter::new(Vec::new(), Vec::new());
+
+	for (key, value) in client.storage_pairs(hash, None, None)? {
+		// Child trie roots are recalculated on import.
+		if key.0.starts_with(well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+			child_roots.push((key.0, value.0));
+			continue
+		}
+
+		level.push(writer, key.0, value.0)?;
+	}
+	level.flush(writer)?;
+
+	for (prefixed_key, root) in child_roots {
+		let child_info = ChildInfo::new_default(
+			&prefixed_key[well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX.len()..],
+		);
+		let mut level = StateLevelWriter::new(root, vec![prefixed_key]);
+
+		for key in client.child_storage_keys(hash, child_info.clone(), None, None)? {
+			if let Some(value) = client.child_storage(hash, &child_info, &key)? {
+				level.push(writer, key.0, value.0)?;
+			}
+		}
+		level.flush(writer)?;
+	}
+
+	Ok(())
+}
+
+/// Collects the key values of a single trie and writes them as state sections.
+struct StateLevelWriter {
+	level: ArchiveStateLevel,
+	size: usize,
+}
+
+impl StateLevelWriter {
+	fn new(state_root: Vec<u8>, parent_storage_keys: Vec<Vec<u8>>) -> Self {
+		Self {
+			level: ArchiveStateLevel { state_root, parent_storage_keys, key_values: Vec::new() },
+			size: 0,
+		}
+	}
+
+	fn push(
+		&mut self,
+		writer: &mut ArchiveWriter<impl Write>,
+		key: Vec<u8>,
+		value: Vec<u8>,
+	) -> std::io::Result<()> {
+		self.size += key.len() + value.len();
+		self.level.key_values.push((key, value));
+
+		if self.size >= STATE_SECTION_SIZE {
+			self.flush(writer)?;
+		}
+		Ok(())
+	}
+
+	fn flush(&mut self, writer: &mut ArchiveWriter<impl Write>) -> std::io::Result<()> {
+		if self.level.key_values.is_empty() {
+			return Ok(())
+		}
+
+		writer.write_section(SectionKind::State, &self.level.encode())?;
+		self.level.key_values.clear();
+		self.size = 0;
+		Ok(())
+	}
+}
@@ -16,24 +16,31 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{error, error::Error};
-use codec::{Decode, IoReader as CodecIoReader};
+use crate::{
+	chain_ops::{detect_archive, ArchiveReader, ArchiveStateInfo, ArchiveStateLevel, SectionKind},
+	error,
+	error::Error,
+};
+use codec::{Decode, Encode, IoReader as CodecIoReader};
 use futures::{future, prelude::*};
 use futures_timer::Delay;
 use log::{info, warn};
 use sc_chain_spec::ChainSpec;
 use sc_client_api::HeaderBackend;
-use sc_consensus::import_queue::{
-	BlockImportError, BlockImportStatus, ImportQueue, IncomingBlock, Link,
+use sc_consensus::{
+	import_queue::{BlockImportError, BlockImportStatus, ImportQueue, IncomingBlock, Link},
+	ImportedState,
 };
 use serde_json::{de::IoRead as JsonIoRead, Deserializer, StreamDeserializer};
 use sp_consensus::BlockOrigin;
 use sp_runtime::{
 	generic::SignedBlock,
 	traits::{
-		Block as BlockT, CheckedDiv, Header, MaybeSerializeDeserialize, NumberFor, Saturating, Zero,
+		Block as BlockT, CheckedDiv, Header, MaybeSerializeDeserialize, NumberFor,
+		SaturatedConversion, Saturating, Zero,
 	},
 };
+use sp_state_machine::{KeyValueStates, KeyValueStorageLevel};
 use std::{
 	io::Read,
 	pin::Pin,
@@ -57,12 +64,13 @@ pub fn build_spec(spec: &dyn ChainSpec, raw: bool) -> error::Result<String> {
 	spec.as_json(raw).map_err(Into::into)
 }
 
-/// Helper enum that wraps either a binary decoder (from parity-scale-codec), or a JSON decoder
-/// (from serde_json). Implements the Iterator Trait, calling `next()` will decode the next
-/// SignedBlock and return it.
+/// Helper enum that wraps either a binary decoder (from parity-scale-codec), a JSON decoder
+/// (from serde_json) or an archive reader. Implements the Iterator Trait, calling `next()` will
+/// decode the next SignedBlock and return it.
 enum BlockIter<R, B>
 where
 	R: std::io::Read,
+	B: BlockT,
 {
 	Binary {
 		// Total number of blocks we are expecting to decode.
@@ -78,6 +86,12 @@ where
 		// Stream to the data, used for decoding new blocks.
 		reader: StreamDeserializer<'static, JsonIoRead<R>, SignedBlock<B>>,
 	},
+	Archive {
+		// Number of blocks we have decoded thus far.
+		read_block_count: u64,
+		// The blocks of the archive.
+		blocks: ArchiveBlocks<R, B>,
+	},
 }
 
 impl<R, B> BlockIter<R, B>
@@ -99,11 +113,22 @@ where
 		}
 	}
 
+	fn new_archive(
+		input: R,
+		genesis_hash: &[u8],
+		is_imported: Box<dyn Fn(u64, &[u8]) -> bool + Send>,
+		bootstrap_from_state: bool,
+	) -> Result<Self, String> {
+		let blocks = ArchiveBlocks::new(input, genesis_hash, is_imported, bootstrap_from_state)?;
+		Ok(BlockIter::Archive { read_block_count: 0, blocks })
+	}
+
 	/// Returns the number of blocks read thus far.
 	fn read_block_count(&self) -> u64 {
 		match self {
 			BlockIter::Binary { read_block_count, .. } |
-			BlockIter::Json { read_block_count, .. } => *read_block_count,
+			BlockIter::Json { read_block_count, .. } |
+			BlockIter::Archive { read_block_count, .. } => *read_block_count,
 		}
 	}
 
@@ -111,7 +136,17 @@ where
 	fn num_expected_blocks(&self) -> Option<u64> {
 		match self {
 			BlockIter::Binary { num_expected_blocks, .. } => Some(*num_expected_blocks),
-			BlockIter::Json { .. } => None,
+			BlockIter::Json { .. } | BlockIter::Archive { .. } => None,
+		}
+	}
+
+	/// Returns the state that has to be imported together with the block `hash`, if any.
+	fn take_state(&mut self, hash: &B::Hash) -> Option<ImportedState<B>> {
+		match self {
+			BlockIter::Archive { blocks, .. }
+				if blocks.state.as_ref().map_or(false, |state| state.block == *hash) =>
+				blocks.state.take(),
+			_ => None,
 		}
 	}
 }
@@ -141,15 +176,154 @@ where
 				*read_block_count += 1;
 				res
 			},
+			BlockIter::Archive { blocks, read_block_count } => {
+				let res = blocks.next();
+				if res.is_some() {
+					*read_block_count += 1;
+				}
+				res
+			},
+		}
+	}
+}
+
+/// Reads the blocks of an archive written by
+/// [`export_archive`](crate::chain_ops::export_archive).
+///
+/// Blocks that are already imported are skipped, which allows to resume an interrupted import.
+struct ArchiveBlocks<R, B: BlockT> {
+	reader: ArchiveReader<R>,
+	// Decoded blocks of the current section that were not returned yet.
+	pending: std::vec::IntoIter<SignedBlock<B>>,
+	// The bundled state, if the import bootstraps from it.
+	state: Option<ImportedState<B>>,
+	// Returns `true` if the block with the given number and encoded hash is already imported.
+	is_imported: Box<dyn Fn(u64, &[u8]) -> bool + Send>,
+}
+
+impl<R: Read, B: BlockT> ArchiveBlocks<R, B> {
+	fn new(
+		input: R,
+		genesis_hash: &[u8],
+		is_imported: Box<dyn Fn(u64, &[u8]) -> bool + Send>,
+		bootstrap_from_state: bool,
+	) -> Result<Self, String> {
+		let reader = ArchiveReader::new(input)?;
+		let header = reader.header().clone();
+		if header.genesis_hash != genesis_hash {
+			return Err("The archive was exported from a different chain".into())
+		}
+
+		info!("Importing blocks #{} to #{} from an archive", header.from, header.to);
+		let mut blocks = Self { reader, pending: Vec::new().into_iter(), state: None, is_imported };
+
+		match header.state {
+			Some(state) if bootstrap_from_state => blocks.read_state(state)?,
+			_ => {},
+		}
+
+		Ok(blocks)
+	}
+
+	/// Reads the complete archive to collect the state and the block it belongs to.
+	///
+	/// Sections are decoded as they are read, only the decoded state is kept in memory, as the
+	/// import queue imports it at once. Sections of other blocks are skipped without being
+	/// decompressed. The block is then the only block returned by the iterator.
+	fn read_state(&mut self, info: ArchiveStateInfo) -> Result<(), String> {
+		let mut target = None;
+		let mut levels = Vec::<KeyValueStorageLevel>::new();
+
+		while let Some(kind) = self.reader.next_section()? {
+			match kind {
+				SectionKind::Blocks { first, count, .. }
+					if (first..first + count as u64).contains(&info.number) =>
+				{
+					target = self
+						.reader
+						.decode_payload::<Vec<SignedBlock<B>>>()?
+						.into_iter()
+						.find(|block| block.block.header().hash().encode() == info.hash);
+				},
+				SectionKind::State => {
+					let level = self.reader.decode_payload::<ArchiveStateLevel>()?;
+
+					// The key values of a trie are split over consecutive sections.
+					match levels.last_mut() {
+						Some(last) if last.parent_storage_keys == level.parent_storage_keys =>
+							last.key_values.extend(level.key_values),
+						_ => levels.push(KeyValueStorageLevel {
+							state_root: level.state_root,
+							parent_storage_keys: level.parent_storage_keys,
+							key_values: level.key_values,
+						}),
+					}
+				},
+				_ => {},
+			}
 		}
+
+		let target = target.ok_or("The archive doesn't contain the block of the state snapshot")?;
+		info!("Bootstrapping from the state of #{}", info.number);
+
+		self.state = Some(ImportedState {
+			block: target.block.header().hash(),
+			state: KeyValueStates(levels),
+		});
+		self.pending = vec![target].into_iter();
+		Ok(())
+	}
+
+	/// Decodes the next section of blocks that isn't imported yet.
+	///
+	/// Returns `false` once the end of the archive is reached.
+	fn next_blocks(&mut self) -> Result<bool, String> {
+		while let Some(kind) = self.reader.next_section()? {
+			let SectionKind::Blocks { first, count, last_hash } = kind else { continue };
+
+			// Sections that end with an imported block are skipped without being decompressed.
+			if count > 0 && (self.is_imported)(first + count as u64 - 1, &last_hash) {
+				continue
+			}
+
+			self.pending = self.reader.decode_payload::<Vec<SignedBlock<B>>>()?.into_iter();
+			return Ok(true)
+		}
+
+		Ok(false)
 	}
 }
 
-/// Imports the SignedBlock to the queue.
+impl<R: Read, B: BlockT> Iterator for ArchiveBlocks<R, B> {
+	type Item = Result<SignedBlock<B>, String>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			if let Some(block) = self.pending.next() {
+				let header = block.block.header();
+				let number = (*header.number()).saturated_into::<u64>();
+				if self.state.is_none() && (self.is_imported)(number, &header.hash().encode()) {
+					continue
+				}
+
+				return Some(Ok(block))
+			}
+
+			match self.next_blocks() {
+				Ok(true) => continue,
+				Ok(false) => return None,
+				Err(e) => return Some(Err(e)),
+			}
+		}
+	}
+}
+
+/// Imports the SignedBlock to the queue, together with its state if provided.
 fn import_block_to_queue<TBl, TImpQu>(
 	signed_block: SignedBlock<TBl>,
 	queue: &mut TImpQu,
 	force: bool,
+	state: Option<ImportedState<TBl>>,
 ) where
 	TBl: BlockT + MaybeSerializeDeserialize,
 	TImpQu: 'static + ImportQueue<TBl>,
@@ -168,7 +342,7 @@ fn import_block_to_queue<TBl, TImpQu>(
 			origin: None,
 			allow_missing_state: false,
 			import_existing: force,
-			state: None,
+			state,
 			skip_execution: false,
 		}],
 	);
@@ -288,12 +462,18 @@ where
 }
 
 /// Starts the process of importing blocks.
+///
+/// Archives written by [`export_archive`](crate::chain_ops::export_archive) are detected
+/// automatically, `binary` is then ignored. If `bootstrap_from_state` is set and the chain only
+/// contains the genesis block, the state bundled in the archive is imported instead of executing
+/// all the blocks.
 pub fn import_blocks<B, IQ, C>(
 	client: Arc<C>,
 	mut import_queue: IQ,
 	input: impl Read + Send + 'static,
 	force: bool,
 	binary: bool,
+	bootstrap_from_state: bool,
 ) -> Pin<Box<dyn Future<Output = Result<(), Error>> + Send>>
 where
 	C: HeaderBackend<B> + Send + Sync + 'static,
@@ -331,7 +511,30 @@ where
 	}
 
 	let mut link = WaitLink::new();
-	let block_iter_res: Result<BlockIter<_, B>, String> = BlockIter::new(input, binary);
+	let block_iter_res: Result<BlockIter<_, B>, String> = match detect_archive(input) {
+		Ok((true, input)) => {
+			let info = client.info();
+			let is_imported = {
+				let client = client.clone();
+				Box::new(move |number: u64, hash: &[u8]| {
+					client
+						.hash(number.saturated_into())
+						.ok()
+						.flatten()
+						.map_or(false, |imported| imported.encode() == hash)
+				})
+			};
+
+			BlockIter::new_archive(
+				input,
+				&info.genesis_hash.encode(),
+				is_imported,
+				bootstrap_from_state && info.best_number.is_zero(),
+			)
+		},
+		Ok((false, input)) => BlockIter::new(input, binary),
+		Err(e) => Err(format!("Failed to read the input: {}", e)),
+	};
 
 	let block_iter = match block_iter_res {
 		Ok(block_iter) => block_iter,
@@ -384,7 +587,9 @@ where
 									});
 								} else {
 									// Queue is not full, we can keep on adding blocks to the queue.
-									import_block_to_queue(block, queue, force);
+									let block_state =
+										block_iter.take_state(&block.block.header().hash());
+									import_block_to_queue(block, queue, force, block_state);
 									state = Some(ImportState::Reading { block_iter });
 								}
 							},
@@ -397,7 +602,7 @@ where
 					},
 				}
 			},
-			ImportState::WaitingForImportQueueToCatchUp { block_iter, mut delay, block } => {
+			ImportState::WaitingForImportQueueToCatchUp { mut block_iter, mut delay, block } => {
 				let read_block_count = block_iter.read_block_count();
 				if read_block_count - link.imported_blocks >= MAX_PENDING_BLOCKS {
 					// Queue is still full, so wait until there is room to insert our block.
@@ -421,7 +626,8 @@ where
 					});
 				} else {
 					// Queue is no longer full, so we can add our block to the queue.
-					import_block_to_queue(block, queue, force);
+					let block_state = block_iter.take_state(&block.block.header().hash());
+					import_block_to_queue(block, queue, force, block_state);
 					// Switch back to Reading state.
 					state = Some(ImportState::Reading { block_iter });
 				}
@@ -484,3 +690,162 @@ where
 	});
 	Box::pin(import)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::chain_ops::{ArchiveCodec, ArchiveHeader, ArchiveWriter};
+	use sp_runtime::traits::{BlakeTwo256, Hash};
+	use substrate_test_runtime::{Block, Header as TestHeader};
+
+	/// Returns a chain of `count` blocks following the genesis block.
+	fn chain(count: u64) -> Vec<SignedBlock<Block>> {
+		let mut parent_hash = genesis_hash();
+		(1..=count)
+			.map(|number| {
+				let header = TestHeader::new(
+					number,
+					Default::default(),
+					BlakeTwo256::hash(&number.encode()),
+					parent_hash,
+					Default::default(),
+				);
+				parent_hash = header.hash();
+				SignedBlock {
+					block: Block { header, extrinsics: Vec::new() },
+					justifications: None,
+				}
+			})
+			.collect()
+	}
+
+	fn genesis_hash() -> <Block as BlockT>::Hash {
+		BlakeTwo256::hash(b"genesis")
+	}
+
+	fn state_level(key_values: Vec<(Vec<u8>, Vec<u8>)>) -> ArchiveStateLevel {
+		ArchiveStateLevel { state_root: Vec::new(), parent_storage_keys: Vec::new(), key_values }
+	}
+
+	/// Writes the `blocks` to an archive, in sections of two blocks, followed by the `state`
+	/// sections of the block `state_of`.
+	fn archive(
+		blocks: &[SignedBlock<Block>],
+		state_of: Option<&SignedBlock<Block>>,
+		state: &[ArchiveStateLevel],
+	) -> Vec<u8> {
+		let header = ArchiveHeader {
+			genesis_hash: genesis_hash().encode(),
+			from: 1,
+			to: blocks.len() as u64,
+			codec: ArchiveCodec::ScaleZstd,
+			state: state_of.map(|block| ArchiveStateInfo {
+				number: *block.block.header.number(),
+				hash: block.block.header.hash().encode(),
+			}),
+		};
+		let mut writer = ArchiveWriter::new(Vec::new(), &header).unwrap();
+		for chunk in blocks.chunks(2) {
+			let kind = SectionKind::Blocks {
+				first: *chunk[0].block.header.number(),
+				count: chunk.len() as u32,
+				last_hash: chunk.last().unwrap().block.header.hash().encode(),
+			};
+			writer.write_section(kind, &chunk.encode()).unwrap();
+		}
+		for level in state {
+			writer.write_section(SectionKind::State, &level.encode()).unwrap();
+		}
+		writer.finish().unwrap()
+	}
+
+	/// Returns an `is_imported` function for the given imported `blocks`.
+	fn imported(blocks: &[SignedBlock<Block>]) -> Box<dyn Fn(u64, &[u8]) -> bool + Send> {
+		let imported = blocks
+			.iter()
+			.map(|block| (*block.block.header.number(), block.block.header.hash().encode()))
+			.collect::<Vec<_>>();
+		Box::new(move |number, hash| imported.iter().any(|(n, h)| *n == number && h == hash))
+	}
+
+	fn read_all(blocks: ArchiveBlocks<&[u8], Block>) -> Vec<u64> {
+		blocks.map(|block| *block.unwrap().block.header.number()).collect()
+	}
+
+	#[test]
+	fn archive_import_resumes_after_the_imported_blocks() {
+		let blocks = chain(5);
+		let archive = archive(&blocks, None, &[]);
+		let genesis = genesis_hash().encode();
+
+		let all = ArchiveBlocks::new(&archive[..], &genesis, imported(&[]), false).unwrap();
+		assert_eq!(read_all(all), vec![1, 2, 3, 4, 5]);
+
+		// The import was interrupted within the second section.
+		let resumed =
+			ArchiveBlocks::new(&archive[..], &genesis, imported(&blocks[..3]), false).unwrap();
+		assert_eq!(read_all(resumed), vec![4, 5]);
+
+		// Everything was imported already.
+		let done = ArchiveBlocks::new(&archive[..], &genesis, imported(&blocks), false).unwrap();
+		assert!(read_all(done).is_empty());
+
+		// Blocks of another fork at the same heights are not skipped.
+		let fork = chain(3)
+			.into_iter()
+			.map(|mut block| {
+				block.block.header.state_root = Default::default();
+				block
+			})
+			.collect::<Vec<_>>();
+		let forked = ArchiveBlocks::new(&archive[..], &genesis, imported(&fork), false).unwrap();
+		assert_eq!(read_all(forked), vec![1, 2, 3, 4, 5]);
+	}
+
+	#[test]
+	fn archive_import_bootstraps_from_the_state_snapshot() {
+		let blocks = chain(5);
+		let state = [state_level(vec![(vec![1], vec![1])]), state_level(vec![(vec![2], vec![2])])];
+		let archive = archive(&blocks, Some(&blocks[2]), &state);
+		let genesis = genesis_hash().encode();
+
+		let mut iter =
+			BlockIter::<_, Block>::new_archive(&archive[..], &genesis, imported(&[]), true)
+				.unwrap();
+
+		// Only the block of the snapshot is imported, together with the merged state.
+		let block = iter.next().unwrap().unwrap();
+		assert_eq!(block.block.header, blocks[2].block.header);
+		let imported_state = iter.take_state(&block.block.header.hash()).unwrap();
+		assert_eq!(imported_state.block, blocks[2].block.header.hash());
+		assert_eq!(imported_state.state.0.len(), 1);
+		assert_eq!(
+			imported_state.state.0[0].key_values,
+			vec![(vec![1], vec![1]), (vec![2], vec![2])]
+		);
+		assert!(iter.take_state(&block.block.header.hash()).is_none());
+		assert!(iter.next().is_none());
+
+		// Without bootstrapping, the state is ignored and all the blocks are imported.
+		let blocks = ArchiveBlocks::new(&archive[..], &genesis, imported(&[]), false).unwrap();
+		assert_eq!(read_all(blocks), vec![1, 2, 3, 4, 5]);
+	}
+
+	#[test]
+	fn archive_import_fails_without_the_block_of_the_snapshot() {
+		let blocks = chain(5);
+		let archive = archive(&blocks[..2], Some(&blocks[2]), &[state_level(Vec::new())]);
+
+		assert_eq!(
+			ArchiveBlocks::<_, Block>::new(
+				&archive[..],
+				&genesis_hash().encode(),
+				imported(&[]),
+				true
+			)
+			.err()
+			.unwrap(),
+			"The archive doesn't contain the block of the state snapshot"
+		);
+	}
+}
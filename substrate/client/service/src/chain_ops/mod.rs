@@ -18,12 +18,14 @@
 
 //! Chain utilities.
 
+mod archive;
 mod check_block;
 mod export_blocks;
 mod export_raw_state;
 mod import_blocks;
 mod revert_chain;
 
+pub use archive::*;
 pub use check_block::*;
 pub use export_blocks::*;
 pub use export_raw_state::*;
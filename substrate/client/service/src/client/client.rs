@@ -1458,6 +1458,18 @@ where
 			.map_err(|e| sp_blockchain::Error::from_state(Box::new(e)))
 	}
 
+	fn child_storage_pairs(
+		&self,
+		hash: <Block as BlockT>::Hash,
+		child_info: ChildInfo,
+		prefix: Option<&StorageKey>,
+		start_key: Option<&StorageKey>,
+	) -> sp_blockchain::Result<PairsIter<B::State, Block>> {
+		let state = self.state_at(hash)?;
+		PairsIter::new_child(state, child_info, prefix, start_key)
+			.map_err(|e| sp_blockchain::Error::from_state(Box::new(e)))
+	}
+
 	fn storage_pairs(
 		&self,
 		hash: <Block as BlockT>::Hash,
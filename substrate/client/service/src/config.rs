@@ -19,7 +19,7 @@
 //! Service configuration.
 
 pub use sc_client_db::{BlocksPruning, Database, DatabaseSource, PruningMode};
-pub use sc_executor::{WasmExecutionMethod, WasmtimeInstantiationStrategy};
+pub use sc_executor::{ExecutionLimits, WasmExecutionMethod, WasmtimeInstantiationStrategy};
 pub use sc_network::{
 	config::{
 		MultiaddrWithPeerId, NetworkConfiguration, NodeKeyConfig, NonDefaultSetConfig, ProtocolId,
@@ -34,7 +34,10 @@ pub use sc_network::{
 use prometheus_endpoint::Registry;
 use sc_chain_spec::ChainSpec;
 pub use sc_telemetry::TelemetryEndpoints;
-pub use sc_transaction_pool::Options as TransactionPoolOptions;
+pub use sc_transaction_pool::{
+	JournalOptions as TransactionPoolJournalOptions, Options as TransactionPoolOptions,
+	TransactionPoolType,
+};
 use sp_core::crypto::SecretString;
 use std::{
 	io, iter,
@@ -66,6 +69,12 @@ pub struct Configuration {
 	///
 	/// If `None` is given the cache is disabled.
 	pub trie_cache_maximum_size: Option<usize>,
+	/// Where the trie cache is kept across restarts.
+	///
+	/// If `None` is given the cache starts empty.
+	pub trie_cache_snapshot: Option<PathBuf>,
+	/// Populate the trie cache in the background with the state of the best block on startup.
+	pub trie_cache_warmup: bool,
 	/// State pruning settings.
 	pub state_pruning: Option<PruningMode>,
 	/// Number of blocks to keep in the db.
@@ -80,6 +89,8 @@ pub struct Configuration {
 	/// over on-chain runtimes when the spec version matches. Set to `None` to
 	/// disable overrides (default).
 	pub wasm_runtime_overrides: Option<PathBuf>,
+	/// Keep the compiled runtimes in [`Self::executor_cache_path`] across restarts.
+	pub runtime_artifact_cache: bool,
 	/// JSON-RPC server binding address.
 	pub rpc_addr: Option<SocketAddr>,
 	/// Maximum number of connections for JSON-RPC server.
@@ -137,6 +148,8 @@ pub struct Configuration {
 	pub informant_output_format: sc_informant::OutputFormat,
 	/// Maximum number of different runtime versions that can be cached.
 	pub runtime_cache_size: u8,
+	/// Limits enforced on offchain runtime calls, e.g. `state_call` from RPC.
+	pub offchain_execution_limits: ExecutionLimits,
 }
 
 /// Type for tasks spawned by the executor.
@@ -238,11 +251,19 @@ impl Configuration {
 	pub fn db_config(&self) -> sc_client_db::DatabaseSettings {
 		sc_client_db::DatabaseSettings {
 			trie_cache_maximum_size: self.trie_cache_maximum_size,
+			trie_cache_snapshot: self.trie_cache_snapshot.clone(),
 			state_pruning: self.state_pruning.clone(),
 			source: self.database.clone(),
 			blocks_pruning: self.blocks_pruning,
 		}
 	}
+
+	/// Returns the directory the executor keeps its files in, e.g. the compiled runtimes.
+	///
+	/// Returns `None` if the runtime artifact cache is disabled.
+	pub fn executor_cache_path(&self) -> Option<PathBuf> {
+		self.runtime_artifact_cache.then(|| self.data_path.join("executor"))
+	}
 }
 
 /// Available RPC methods.
@@ -1480,6 +1480,7 @@ fn doesnt_import_blocks_that_revert_finality() {
 		Backend::new(
 			DatabaseSettings {
 				trie_cache_maximum_size: Some(1 << 20),
+				trie_cache_snapshot: None,
 				state_pruning: Some(PruningMode::ArchiveAll),
 				blocks_pruning: BlocksPruning::KeepAll,
 				source: DatabaseSource::RocksDb { path: tmp.path().into(), cache_size: 1024 },
@@ -1759,6 +1760,7 @@ fn returns_status_for_pruned_blocks() {
 		Backend::new(
 			DatabaseSettings {
 				trie_cache_maximum_size: Some(1 << 20),
+				trie_cache_snapshot: None,
 				state_pruning: Some(PruningMode::blocks_pruning(1)),
 				blocks_pruning: BlocksPruning::KeepFinalized,
 				source: DatabaseSource::RocksDb { path: tmp.path().into(), cache_size: 1024 },
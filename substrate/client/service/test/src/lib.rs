@@ -226,8 +226,12 @@ fn node_config<
 			.collect(),
 	);
 
-	network_config.transport =
-		TransportConfig::Normal { enable_mdns: false, allow_private_ip: true };
+	network_config.transport = TransportConfig::Normal {
+		enable_mdns: false,
+		allow_private_ip: true,
+		enable_quic: false,
+		enable_webrtc: false,
+	};
 
 	Configuration {
 		impl_name: String::from("network-test-impl"),
@@ -239,11 +243,14 @@ fn node_config<
 		keystore: KeystoreConfig::Path { path: root.join("key"), password: None },
 		database: DatabaseSource::RocksDb { path: root.join("db"), cache_size: 128 },
 		trie_cache_maximum_size: Some(16 * 1024 * 1024),
+		trie_cache_snapshot: None,
+		trie_cache_warmup: false,
 		state_pruning: Default::default(),
 		blocks_pruning: BlocksPruning::KeepFinalized,
 		chain_spec: Box::new((*spec).clone()),
 		wasm_method: Default::default(),
 		wasm_runtime_overrides: Default::default(),
+		runtime_artifact_cache: false,
 		rpc_addr: Default::default(),
 		rpc_max_connections: Default::default(),
 		rpc_cors: None,
@@ -268,6 +275,7 @@ fn node_config<
 		data_path: root,
 		informant_output_format: Default::default(),
 		runtime_cache_size: 2,
+		offchain_execution_limits: Default::default(),
 	}
 }
 
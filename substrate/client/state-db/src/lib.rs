@@ -46,13 +46,13 @@ mod pruning;
 #[cfg(test)]
 mod test;
 
-use codec::Codec;
+use codec::{Codec, DecodeAll, Encode};
 use log::trace;
 use noncanonical::NonCanonicalOverlay;
 use parking_lot::RwLock;
 use pruning::{HaveBlock, RefWindow};
 use std::{
-	collections::{hash_map::Entry, HashMap},
+	collections::{hash_map::Entry, HashMap, HashSet},
 	fmt,
 };
 
@@ -62,7 +62,11 @@ const PRUNING_MODE: &[u8] = b"mode";
 const PRUNING_MODE_ARCHIVE: &[u8] = b"archive";
 const PRUNING_MODE_ARCHIVE_CANON: &[u8] = b"archive_canonical";
 const PRUNING_MODE_CONSTRAINED: &[u8] = b"constrained";
+const PRUNING_MODE_ARCHIVE_FROM: &[u8] = b"archive_from";
+const PRUNING_MODE_CHECKPOINTS: &[u8] = b"checkpoints";
 pub(crate) const DEFAULT_MAX_BLOCK_CONSTRAINT: u32 = 256;
+/// Maximum number of nodes tracked between two checkpoints in [`PruningMode::Checkpoints`] mode.
+const MAX_CHECKPOINT_TRACKED_NODES: usize = 1 << 20;
 
 /// Database value type.
 pub type DBValue = Vec<u8>;
@@ -145,6 +149,8 @@ pub enum StateDbError {
 	BlockUnavailable,
 	/// Block record is missing from the pruning window
 	BlockMissing,
+	/// The pruning mode can't be used with this database.
+	UnsupportedPruningMode { mode: PruningMode, reason: &'static str },
 }
 
 impl<E> From<StateDbError> for Error<E> {
@@ -196,6 +202,8 @@ impl fmt::Debug for StateDbError {
 				write!(f, "Trying to get a block record from db while it is not commit to db yet")
 			},
 			Self::BlockMissing => write!(f, "Block record is missing from the pruning window"),
+			Self::UnsupportedPruningMode { mode, reason } =>
+				write!(f, "Unsupported pruning mode {:?}: {}", mode, reason),
 		}
 	}
 }
@@ -235,6 +243,16 @@ pub enum PruningMode {
 	ArchiveAll,
 	/// Canonicalization discards non-canonical nodes. All the canonical nodes are kept in the DB.
 	ArchiveCanonical,
+	/// Canonical states before the given block number are pruned like with the default
+	/// constraints, the canonical states from it onward are all kept.
+	ArchiveFrom(u64),
+	/// The canonical state of every block whose number is a multiple of the given period is kept,
+	/// the other states are pruned like with the default constraints.
+	///
+	/// Requires a database with reference counting. States are kept conservatively: nodes
+	/// inserted before a restart, or once too many nodes were inserted since the last checkpoint,
+	/// are not deleted until the next checkpoint.
+	Checkpoints(u32),
 }
 
 impl PruningMode {
@@ -245,18 +263,33 @@ impl PruningMode {
 
 	/// Is this an archive (either ArchiveAll or ArchiveCanonical) pruning mode?
 	pub fn is_archive(&self) -> bool {
+		match *self {
+			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical => true,
+			PruningMode::Constrained(_) |
+			PruningMode::ArchiveFrom(_) |
+			PruningMode::Checkpoints(_) => false,
+		}
+	}
+
+	/// Is the canonical state of block `number` never pruned?
+	pub fn keeps_canonical(&self, number: u64) -> bool {
 		match *self {
 			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical => true,
 			PruningMode::Constrained(_) => false,
+			PruningMode::ArchiveFrom(from) => number >= from,
+			PruningMode::Checkpoints(period) => period != 0 && number % period as u64 == 0,
 		}
 	}
 
-	/// Returns the pruning mode
-	pub fn id(&self) -> &[u8] {
+	/// Returns the pruning mode, with its parameters if they must be kept across restarts.
+	pub fn id(&self) -> Vec<u8> {
 		match self {
-			PruningMode::ArchiveAll => PRUNING_MODE_ARCHIVE,
-			PruningMode::ArchiveCanonical => PRUNING_MODE_ARCHIVE_CANON,
-			PruningMode::Constrained(_) => PRUNING_MODE_CONSTRAINED,
+			PruningMode::ArchiveAll => PRUNING_MODE_ARCHIVE.to_vec(),
+			PruningMode::ArchiveCanonical => PRUNING_MODE_ARCHIVE_CANON.to_vec(),
+			PruningMode::Constrained(_) => PRUNING_MODE_CONSTRAINED.to_vec(),
+			PruningMode::ArchiveFrom(from) => [PRUNING_MODE_ARCHIVE_FROM, &from.encode()].concat(),
+			PruningMode::Checkpoints(period) =>
+				[PRUNING_MODE_CHECKPOINTS, &period.encode()].concat(),
 		}
 	}
 
@@ -265,7 +298,27 @@ impl PruningMode {
 			PRUNING_MODE_ARCHIVE => Some(Self::ArchiveAll),
 			PRUNING_MODE_ARCHIVE_CANON => Some(Self::ArchiveCanonical),
 			PRUNING_MODE_CONSTRAINED => Some(Self::Constrained(Default::default())),
-			_ => None,
+			_ =>
+				if let Some(mut from) = id.strip_prefix(PRUNING_MODE_ARCHIVE_FROM) {
+					u64::decode_all(&mut from).ok().map(Self::ArchiveFrom)
+				} else if let Some(mut period) = id.strip_prefix(PRUNING_MODE_CHECKPOINTS) {
+					u32::decode_all(&mut period)
+						.ok()
+						.filter(|period| *period != 0)
+						.map(Self::Checkpoints)
+				} else {
+					None
+				},
+		}
+	}
+
+	/// Size of the pruning window, if states are pruned at all.
+	fn max_blocks(&self) -> Option<u32> {
+		match self {
+			PruningMode::Constrained(Constraints { max_blocks }) => Some(max_blocks.unwrap_or(0)),
+			PruningMode::ArchiveFrom(_) | PruningMode::Checkpoints(_) =>
+				Some(DEFAULT_MAX_BLOCK_CONSTRAINT),
+			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical => None,
 		}
 	}
 }
@@ -305,6 +358,10 @@ pub struct StateDbSync<BlockHash: Hash, Key: Hash, D: MetaDb> {
 	pruning: Option<RefWindow<BlockHash, Key, D>>,
 	pinned: HashMap<BlockHash, u32>,
 	ref_counting: bool,
+	/// Nodes inserted since the last checkpoint, the only ones that may be deleted in
+	/// [`PruningMode::Checkpoints`] mode. `None` if they aren't all known, in which case no node
+	/// is deleted until the next checkpoint.
+	inserted_since_checkpoint: Option<HashSet<Key>>,
 }
 
 impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
@@ -316,13 +373,28 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 		trace!(target: LOG_TARGET, "StateDb settings: {:?}. Ref-counting: {}", mode, ref_counting);
 
 		let non_canonical: NonCanonicalOverlay<BlockHash, Key> = NonCanonicalOverlay::new(&db)?;
-		let pruning: Option<RefWindow<BlockHash, Key, D>> = match mode {
-			PruningMode::Constrained(Constraints { max_blocks }) =>
-				Some(RefWindow::new(db, max_blocks.unwrap_or(0), ref_counting)?),
-			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical => None,
+		let pruning: Option<RefWindow<BlockHash, Key, D>> = match mode.max_blocks() {
+			Some(max_blocks) => Some(RefWindow::new(db, max_blocks, ref_counting)?),
+			None => None,
 		};
 
-		Ok(StateDbSync { mode, non_canonical, pruning, pinned: Default::default(), ref_counting })
+		// The nodes inserted since the last checkpoint are only known if there is none.
+		let inserted_since_checkpoint =
+			match (&mode, non_canonical.last_canonicalized_block_number()) {
+				(PruningMode::Checkpoints(period), Some(number))
+					if number % *period as u64 != 0 =>
+					None,
+				_ => Some(Default::default()),
+			};
+
+		Ok(StateDbSync {
+			mode,
+			non_canonical,
+			pruning,
+			pinned: Default::default(),
+			ref_counting,
+			inserted_since_checkpoint,
+		})
 	}
 
 	fn insert_block(
@@ -338,7 +410,10 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 				// write changes immediately
 				Ok(CommitSet { data: changeset, meta: Default::default() })
 			},
-			PruningMode::Constrained(_) | PruningMode::ArchiveCanonical => self
+			PruningMode::Constrained(_) |
+			PruningMode::ArchiveCanonical |
+			PruningMode::ArchiveFrom(_) |
+			PruningMode::Checkpoints(_) => self
 				.non_canonical
 				.insert(hash, number, parent_hash, changeset)
 				.map_err(Into::into),
@@ -355,8 +430,30 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 			return Ok(commit)
 		}
 		let number = self.non_canonical.canonicalize(hash, &mut commit)?;
-		if self.mode == PruningMode::ArchiveCanonical {
-			commit.data.deleted.clear();
+		match self.mode {
+			PruningMode::Checkpoints(period) => {
+				// Nodes of the last checkpoint state must survive, so only the nodes inserted
+				// after it may be deleted.
+				match self.inserted_since_checkpoint {
+					Some(ref mut inserted) =>
+						commit.data.deleted.retain(|key| inserted.remove(key)),
+					None => commit.data.deleted.clear(),
+				}
+				if number % period as u64 == 0 {
+					self.inserted_since_checkpoint = Some(Default::default());
+				} else if let Some(inserted) = self.inserted_since_checkpoint.as_mut() {
+					inserted.extend(commit.data.inserted.iter().map(|(key, _)| key.clone()));
+					if inserted.len() > MAX_CHECKPOINT_TRACKED_NODES {
+						trace!(
+							target: LOG_TARGET,
+							"Too many nodes inserted since the last checkpoint, keeping them all",
+						);
+						self.inserted_since_checkpoint = None;
+					}
+				}
+			},
+			ref mode if mode.keeps_canonical(number) => commit.data.deleted.clear(),
+			_ => {},
 		}
 		if let Some(ref mut pruning) = self.pruning {
 			pruning.note_canonical(hash, number, &mut commit)?;
@@ -380,7 +477,10 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 	fn is_pruned(&self, hash: &BlockHash, number: u64) -> IsPruned {
 		match self.mode {
 			PruningMode::ArchiveAll => IsPruned::NotPruned,
-			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) => {
+			PruningMode::ArchiveCanonical |
+			PruningMode::Constrained(_) |
+			PruningMode::ArchiveFrom(_) |
+			PruningMode::Checkpoints(_) => {
 				if self
 					.non_canonical
 					.last_canonicalized_block_number()
@@ -396,6 +496,8 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 					match self.pruning.as_ref() {
 						// We don't know for sure.
 						None => IsPruned::MaybePruned,
+						// Kept canonical states are no longer tracked by the pruning window.
+						Some(_) if self.mode.keeps_canonical(number) => IsPruned::MaybePruned,
 						Some(pruning) => match pruning.have_block(hash, number) {
 							HaveBlock::No => IsPruned::Pruned,
 							HaveBlock::Yes => IsPruned::NotPruned,
@@ -408,11 +510,11 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 	}
 
 	fn prune(&mut self, commit: &mut CommitSet<Key>) -> Result<(), Error<D::Error>> {
-		if let (&mut Some(ref mut pruning), PruningMode::Constrained(constraints)) =
-			(&mut self.pruning, &self.mode)
+		if let (&mut Some(ref mut pruning), Some(max_blocks)) =
+			(&mut self.pruning, self.mode.max_blocks())
 		{
 			loop {
-				if pruning.window_size() <= constraints.max_blocks.unwrap_or(0) as u64 {
+				if pruning.window_size() <= max_blocks as u64 {
 					break
 				}
 
@@ -442,16 +544,20 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 	fn revert_one(&mut self) -> Option<CommitSet<Key>> {
 		match self.mode {
 			PruningMode::ArchiveAll => Some(CommitSet::default()),
-			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) =>
-				self.non_canonical.revert_one(),
+			PruningMode::ArchiveCanonical |
+			PruningMode::Constrained(_) |
+			PruningMode::ArchiveFrom(_) |
+			PruningMode::Checkpoints(_) => self.non_canonical.revert_one(),
 		}
 	}
 
 	fn remove(&mut self, hash: &BlockHash) -> Option<CommitSet<Key>> {
 		match self.mode {
 			PruningMode::ArchiveAll => Some(CommitSet::default()),
-			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) =>
-				self.non_canonical.remove(hash),
+			PruningMode::ArchiveCanonical |
+			PruningMode::Constrained(_) |
+			PruningMode::ArchiveFrom(_) |
+			PruningMode::Checkpoints(_) => self.non_canonical.remove(hash),
 		}
 	}
 
@@ -461,9 +567,13 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 	{
 		match self.mode {
 			PruningMode::ArchiveAll => Ok(()),
-			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) => {
+			PruningMode::ArchiveCanonical |
+			PruningMode::Constrained(_) |
+			PruningMode::ArchiveFrom(_) |
+			PruningMode::Checkpoints(_) => {
+				let pruning = self.pruning.as_ref().filter(|_| !self.mode.keeps_canonical(number));
 				let have_block = self.non_canonical.have_block(hash) ||
-					self.pruning.as_ref().map_or_else(
+					pruning.map_or_else(
 						|| hint(),
 						|pruning| match pruning.have_block(hash, number) {
 							HaveBlock::No => false,
@@ -556,11 +666,27 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDb<BlockHash, Key, D> {
 			(false, Some(stored), Some(requested)) => choose_pruning_mode(stored, requested)?,
 		};
 
+		match selected_mode {
+			PruningMode::Checkpoints(0) =>
+				return Err(StateDbError::UnsupportedPruningMode {
+					mode: selected_mode,
+					reason: "the checkpoint period can't be zero",
+				}
+				.into()),
+			PruningMode::Checkpoints(_) if ref_counting =>
+				return Err(StateDbError::UnsupportedPruningMode {
+					mode: selected_mode,
+					reason: "the database doesn't support reference counting",
+				}
+				.into()),
+			_ => {},
+		}
+
 		let db_init_commit_set = if should_init {
 			let mut cs: CommitSet<Key> = Default::default();
 
 			let key = to_meta_key(PRUNING_MODE, &());
-			let value = selected_mode.id().to_owned();
+			let value = selected_mode.id();
 
 			cs.meta.inserted.push((key, value));
 
@@ -671,7 +797,12 @@ pub enum IsPruned {
 	MaybePruned,
 }
 
-fn fetch_stored_pruning_mode<D: MetaDb>(db: &D) -> Result<Option<PruningMode>, Error<D::Error>> {
+/// Read the pruning mode a database was created with.
+///
+/// Returns `None` if the database has never been opened by a `StateDb`.
+pub fn fetch_stored_pruning_mode<D: MetaDb>(
+	db: &D,
+) -> Result<Option<PruningMode>, Error<D::Error>> {
 	let meta_key_mode = to_meta_key(PRUNING_MODE, &());
 	if let Some(stored_mode) = db.get_meta(&meta_key_mode).map_err(Error::Db)? {
 		if let Some(mode) = PruningMode::from_id(&stored_mode) {
@@ -688,6 +819,13 @@ fn fetch_stored_pruning_mode<D: MetaDb>(db: &D) -> Result<Option<PruningMode>, E
 	}
 }
 
+/// Returns `true` if the database has a journal of blocks that are not canonicalized yet.
+pub fn has_non_canonical_blocks<BlockHash: Hash, Key: Hash, D: MetaDb>(
+	db: &D,
+) -> Result<bool, Error<D::Error>> {
+	Ok(NonCanonicalOverlay::<BlockHash, Key>::new(db)?.has_blocks())
+}
+
 fn choose_pruning_mode(
 	stored: PruningMode,
 	requested: PruningMode,
@@ -698,6 +836,12 @@ fn choose_pruning_mode(
 			Ok(PruningMode::ArchiveCanonical),
 		(PruningMode::Constrained(_), PruningMode::Constrained(requested)) =>
 			Ok(PruningMode::Constrained(requested)),
+		(PruningMode::ArchiveFrom(stored), PruningMode::ArchiveFrom(requested))
+			if stored == requested =>
+			Ok(PruningMode::ArchiveFrom(stored)),
+		(PruningMode::Checkpoints(stored), PruningMode::Checkpoints(requested))
+			if stored == requested =>
+			Ok(PruningMode::Checkpoints(stored)),
 		(stored, requested) => Err(StateDbError::IncompatiblePruningModes { requested, stored }),
 	}
 }
@@ -706,7 +850,8 @@ fn choose_pruning_mode(
 mod tests {
 	use crate::{
 		test::{make_changeset, make_db, TestDb},
-		Constraints, Error, IsPruned, PruningMode, StateDb, StateDbError,
+		Constraints, Error, IsPruned, NodeDb, PruningMode, StateDb, StateDbError,
+		DEFAULT_MAX_BLOCK_CONSTRAINT,
 	};
 	use sp_core::H256;
 
@@ -942,8 +1087,129 @@ mod tests {
 				Some(PruningMode::ArchiveCanonical),
 				Ok(PruningMode::ArchiveCanonical),
 			),
+			(None, Some(PruningMode::ArchiveFrom(10)), Err(())),
+			(Some(PruningMode::ArchiveFrom(10)), None, Ok(PruningMode::ArchiveFrom(10))),
+			(
+				Some(PruningMode::ArchiveFrom(10)),
+				Some(PruningMode::ArchiveFrom(10)),
+				Ok(PruningMode::ArchiveFrom(10)),
+			),
+			(Some(PruningMode::ArchiveFrom(10)), Some(PruningMode::ArchiveFrom(20)), Err(())),
+			(Some(PruningMode::ArchiveFrom(10)), Some(PruningMode::ArchiveCanonical), Err(())),
+			(Some(PruningMode::Checkpoints(100)), None, Ok(PruningMode::Checkpoints(100))),
+			(
+				Some(PruningMode::Checkpoints(100)),
+				Some(PruningMode::Checkpoints(100)),
+				Ok(PruningMode::Checkpoints(100)),
+			),
+			(Some(PruningMode::Checkpoints(100)), Some(PruningMode::Checkpoints(10)), Err(())),
+			(Some(PruningMode::Checkpoints(100)), Some(PruningMode::blocks_pruning(256)), Err(())),
 		] {
 			check_stored_and_requested_mode_compatibility(created, reopened, expected);
 		}
 	}
+
+	#[test]
+	fn pruning_mode_id_roundtrip() {
+		for mode in [
+			PruningMode::ArchiveAll,
+			PruningMode::ArchiveCanonical,
+			PruningMode::blocks_pruning(DEFAULT_MAX_BLOCK_CONSTRAINT),
+			PruningMode::ArchiveFrom(0),
+			PruningMode::ArchiveFrom(1_234_567),
+			PruningMode::Checkpoints(1000),
+		] {
+			assert_eq!(PruningMode::from_id(&mode.id()), Some(mode));
+		}
+		assert_eq!(PruningMode::from_id(&PruningMode::Checkpoints(0).id()), None);
+		assert_eq!(PruningMode::from_id(b"archive_from"), None);
+	}
+
+	/// Canonicalizes a chain of `blocks` blocks where block `n` replaces node `1000 + n - 1` with
+	/// node `1000 + n`.
+	fn make_linear_chain(mode: PruningMode, blocks: u64) -> (TestDb, StateDb<H256, H256, TestDb>) {
+		let mut db = make_db(&[1000]);
+		let (state_db_init, state_db) = StateDb::open(db.clone(), Some(mode), false, true).unwrap();
+		db.commit(&state_db_init);
+		extend_linear_chain(&mut db, &state_db, 1..=blocks);
+		(db, state_db)
+	}
+
+	fn extend_linear_chain(
+		db: &mut TestDb,
+		state_db: &StateDb<H256, H256, TestDb>,
+		blocks: std::ops::RangeInclusive<u64>,
+	) {
+		for n in blocks {
+			let hash = H256::from_low_u64_be(n);
+			db.commit(
+				&state_db
+					.insert_block(
+						&hash,
+						n,
+						&H256::from_low_u64_be(n - 1),
+						make_changeset(&[1000 + n], &[1000 + n - 1]),
+					)
+					.unwrap(),
+			);
+			db.commit(&state_db.canonicalize_block(&hash).unwrap());
+		}
+	}
+
+	fn has_node(db: &TestDb, node: u64) -> bool {
+		NodeDb::get(db, &H256::from_low_u64_be(node)).unwrap().is_some()
+	}
+
+	#[test]
+	fn archive_from_keeps_states_after_block() {
+		let (db, sdb) = make_linear_chain(PruningMode::ArchiveFrom(20), 300);
+		// states older than the pruning window and the archive start are pruned
+		assert!((1000..1019).all(|node| !has_node(&db, node)));
+		// the parent state of the first archived block is kept as well
+		assert!((1019..=1300).all(|node| has_node(&db, node)));
+		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(10), 10), IsPruned::Pruned);
+		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(25), 25), IsPruned::MaybePruned);
+	}
+
+	#[test]
+	fn checkpoints_keep_periodic_states() {
+		let (db, sdb) = make_linear_chain(PruningMode::Checkpoints(100), 400);
+		let window_start = 400 - DEFAULT_MAX_BLOCK_CONSTRAINT as u64;
+		for n in 0..=400 {
+			let kept = n % 100 == 0 || n >= window_start;
+			assert_eq!(has_node(&db, 1000 + n), kept, "state of block {n}");
+		}
+		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(99), 99), IsPruned::Pruned);
+		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(100), 100), IsPruned::MaybePruned);
+	}
+
+	#[test]
+	fn checkpoints_keep_untracked_nodes_until_next_checkpoint() {
+		let (mut db, state_db) = make_linear_chain(PruningMode::Checkpoints(100), 50);
+		drop(state_db);
+
+		// the nodes inserted before the restart are unknown
+		let (_, state_db) = StateDb::open(db.clone(), None, false, false).unwrap();
+		extend_linear_chain(&mut db, &state_db, 51..=400);
+		let window_start = 400 - DEFAULT_MAX_BLOCK_CONSTRAINT as u64;
+		for n in 50..=400 {
+			let kept = n <= 100 || n % 100 == 0 || n >= window_start;
+			assert_eq!(has_node(&db, 1000 + n), kept, "state of block {n}");
+		}
+	}
+
+	#[test]
+	fn checkpoints_require_ref_counting() {
+		let db = make_db(&[]);
+		let result = StateDb::<H256, H256, TestDb>::open(
+			db.clone(),
+			Some(PruningMode::Checkpoints(100)),
+			true,
+			true,
+		);
+		assert!(matches!(result, Err(Error::StateDb(StateDbError::UnsupportedPruningMode { .. }))));
+		let result =
+			StateDb::<H256, H256, TestDb>::open(db, Some(PruningMode::Checkpoints(0)), false, true);
+		assert!(matches!(result, Err(Error::StateDb(StateDbError::UnsupportedPruningMode { .. }))));
+	}
 }
@@ -365,6 +365,11 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 		self.last_canonicalized.as_ref().map(|&(_, n)| n + 1).unwrap_or(0)
 	}
 
+	/// Returns `true` if there are blocks that are not canonicalized yet.
+	pub fn has_blocks(&self) -> bool {
+		!self.levels.is_empty()
+	}
+
 	pub fn last_canonicalized_block_number(&self) -> Option<u64> {
 		self.last_canonicalized.as_ref().map(|&(_, n)| n)
 	}
@@ -20,6 +20,7 @@
 #![warn(missing_docs)]
 
 pub mod error;
+pub mod ordering;
 
 use async_trait::async_trait;
 use codec::Codec;
@@ -240,6 +241,24 @@ pub trait TransactionPool: Send + Sync {
 	/// Get an iterator for ready transactions ordered by priority.
 	fn ready(&self) -> Box<dyn ReadyTransactions<Item = Arc<Self::InPoolTransaction>> + Send>;
 
+	/// Like [`Self::ready_at`], but orders the ready transactions by the given policy instead of
+	/// the one the pool was configured with.
+	///
+	/// Pools that can't apply a custom ordering fall back to [`Self::ready_at`].
+	fn ready_at_with_ordering(
+		&self,
+		at: NumberFor<Self::Block>,
+		_ordering: Arc<dyn ordering::TransactionOrdering>,
+	) -> Pin<
+		Box<
+			dyn Future<
+					Output = Box<dyn ReadyTransactions<Item = Arc<Self::InPoolTransaction>> + Send>,
+				> + Send,
+		>,
+	> {
+		self.ready_at(at)
+	}
+
 	// *** Block production
 	/// Remove transactions identified by given hashes (and dependent transactions) from the pool.
 	fn remove_invalid(&self, hashes: &[TxHash<Self>]) -> Vec<Arc<Self::InPoolTransaction>>;
@@ -0,0 +1,168 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Policies deciding in which order ready transactions are handed out for block production.
+
+use crate::{TransactionLongevity, TransactionPriority, TransactionTag};
+use sp_runtime::traits::Block as BlockT;
+use std::fmt;
+
+/// Fee and weight of a transaction, as reported by the chain.
+///
+/// The pool itself has no notion of fees, they are provided by the chain specific part of the
+/// pool, if at all.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransactionCost {
+	/// Fee paid by the transaction, in the smallest unit of the native currency.
+	pub fee: u128,
+	/// Weight of the transaction, in reference time.
+	pub weight: u64,
+}
+
+/// Reports the [`TransactionCost`] of transactions to the pool.
+///
+/// The pool has no notion of fees. Chains that want to order ready transactions by fee, e.g. with
+/// [`FeePerWeightOrdering`], provide them through this trait, for example by querying the
+/// `TransactionPaymentApi` runtime API.
+pub trait TransactionCostProvider<Block: BlockT>: Send + Sync {
+	/// Returns the fee and weight of `uxt` at the block `at`, if known.
+	fn transaction_cost(&self, at: Block::Hash, uxt: &Block::Extrinsic) -> Option<TransactionCost>;
+}
+
+/// A ready transaction as seen by a [`TransactionOrdering`] policy.
+#[derive(Debug, Clone, Copy)]
+pub struct ReadyTransactionInfo<'a> {
+	/// Priority reported by the runtime.
+	pub priority: TransactionPriority,
+	/// Block number until which the transaction is valid.
+	pub valid_till: TransactionLongevity,
+	/// Tags required by the transaction.
+	pub requires: &'a [TransactionTag],
+	/// Tags provided by the transaction.
+	pub provides: &'a [TransactionTag],
+	/// Size of the encoded transaction.
+	pub bytes: usize,
+	/// Fee paid by the transaction, if reported by the chain.
+	pub fee: Option<u128>,
+	/// Weight of the transaction, if reported by the chain.
+	pub weight: Option<u64>,
+}
+
+/// Decides in which order ready transactions are returned.
+///
+/// A policy only ever ranks transactions whose requirements are already met, so transactions
+/// depending on each other are always returned in dependency order. Transactions with equal keys
+/// are returned in the order they entered the pool.
+///
+/// Policies that need chain specific knowledge, like ordering by fee per weight, can be
+/// implemented outside of the pool and passed in through the pool options or the block
+/// proposer.
+pub trait TransactionOrdering: Send + Sync + fmt::Debug {
+	/// Returns the key of a transaction that just had all of its requirements met.
+	///
+	/// Transactions with a higher key are returned first. `served` is the number of transactions
+	/// with the same [sender](Self::sender) that were already returned by the iterator.
+	///
+	/// The key is evaluated again right before the transaction is returned, so that it reflects
+	/// the current `served`. It must not increase with `served`.
+	fn key(&self, tx: &ReadyTransactionInfo, served: usize) -> u128;
+
+	/// Returns the sender of the transaction, if the policy cares about it.
+	fn sender(&self, _tx: &ReadyTransactionInfo) -> Option<Vec<u8>> {
+		None
+	}
+}
+
+/// Orders by priority, then by the shortest remaining validity.
+///
+/// This is the default ordering of the pool.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PriorityOrdering;
+
+impl TransactionOrdering for PriorityOrdering {
+	fn key(&self, tx: &ReadyTransactionInfo, _served: usize) -> u128 {
+		((tx.priority as u128) << 64) | (u64::MAX - tx.valid_till) as u128
+	}
+}
+
+/// Returns transactions in the order they entered the pool.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FifoOrdering;
+
+impl TransactionOrdering for FifoOrdering {
+	fn key(&self, _tx: &ReadyTransactionInfo, _served: usize) -> u128 {
+		0
+	}
+}
+
+/// Takes turns between senders, so that a single sender can't fill a block on its own.
+///
+/// Among senders that were served equally often, transactions with higher priority go first.
+/// The sender is read from the first tag a transaction provides, which for FRAME based chains is
+/// the encoded `(AccountId, Nonce)` pair of `CheckNonce`: the sender is the first
+/// `sender_len` bytes of that tag. Transactions without such a tag are treated as coming from
+/// distinct senders.
+#[derive(Debug, Clone, Copy)]
+pub struct RoundRobinOrdering {
+	sender_len: usize,
+}
+
+impl RoundRobinOrdering {
+	/// Creates the policy for senders that are encoded in `sender_len` bytes.
+	pub fn new(sender_len: usize) -> Self {
+		Self { sender_len }
+	}
+}
+
+impl TransactionOrdering for RoundRobinOrdering {
+	fn key(&self, tx: &ReadyTransactionInfo, served: usize) -> u128 {
+		(((u64::MAX - served as u64) as u128) << 64) | tx.priority as u128
+	}
+
+	fn sender(&self, tx: &ReadyTransactionInfo) -> Option<Vec<u8>> {
+		tx.provides
+			.first()
+			.filter(|tag| tag.len() >= self.sender_len)
+			.map(|tag| tag[..self.sender_len].to_vec())
+	}
+}
+
+/// Orders by the fee paid per unit of weight, see [`TransactionCostProvider`].
+///
+/// Transactions without a reported fee and weight go after all others, ordered by priority.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FeePerWeightOrdering;
+
+impl FeePerWeightOrdering {
+	/// The fee per weight is computed in units of `1 / PRECISION`.
+	const PRECISION: u128 = 1_000_000_000;
+	/// Set in the keys of transactions with a known fee per weight.
+	const KNOWN: u128 = 1 << 127;
+}
+
+impl TransactionOrdering for FeePerWeightOrdering {
+	fn key(&self, tx: &ReadyTransactionInfo, _served: usize) -> u128 {
+		match (tx.fee, tx.weight) {
+			(Some(fee), Some(weight)) => {
+				let fee_per_weight = fee.saturating_mul(Self::PRECISION) / weight.max(1) as u128;
+				Self::KNOWN | fee_per_weight.min(Self::KNOWN - 1)
+			},
+			_ => tx.priority as u128,
+		}
+	}
+}
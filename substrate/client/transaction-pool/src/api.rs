@@ -30,6 +30,7 @@ use std::{marker::PhantomData, pin::Pin, sync::Arc};
 
 use prometheus_endpoint::Registry as PrometheusRegistry;
 use sc_client_api::{blockchain::HeaderBackend, BlockBackend};
+use sc_transaction_pool_api::ordering::{TransactionCost, TransactionCostProvider};
 use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::{HeaderMetadata, TreeRoute};
 use sp_core::traits::SpawnEssentialNamed;
@@ -47,11 +48,12 @@ use crate::{
 };
 
 /// The transaction pool logic for full client.
-pub struct FullChainApi<Client, Block> {
+pub struct FullChainApi<Client, Block: BlockT> {
 	client: Arc<Client>,
 	_marker: PhantomData<Block>,
 	metrics: Option<Arc<ApiMetrics>>,
 	validation_pool: Arc<Mutex<mpsc::Sender<Pin<Box<dyn Future<Output = ()> + Send>>>>>,
+	transaction_cost: Option<Arc<dyn TransactionCostProvider<Block>>>,
 }
 
 /// Spawn a validation task that will be used by the transaction pool to validate transactions.
@@ -76,7 +78,7 @@ fn spawn_validation_pool_task(
 	);
 }
 
-impl<Client, Block> FullChainApi<Client, Block> {
+impl<Client, Block: BlockT> FullChainApi<Client, Block> {
 	/// Create new transaction pool logic.
 	pub fn new(
 		client: Arc<Client>,
@@ -106,8 +108,18 @@ impl<Client, Block> FullChainApi<Client, Block> {
 			validation_pool: Arc::new(Mutex::new(sender)),
 			_marker: Default::default(),
 			metrics,
+			transaction_cost: None,
 		}
 	}
+
+	/// Report the fee and weight of transactions from `provider` to the ordering policy.
+	pub fn with_transaction_cost(
+		mut self,
+		provider: Option<Arc<dyn TransactionCostProvider<Block>>>,
+	) -> Self {
+		self.transaction_cost = provider;
+		self
+	}
 }
 
 impl<Client, Block> graph::ChainApi for FullChainApi<Client, Block>
@@ -202,6 +214,16 @@ where
 	) -> Result<TreeRoute<Self::Block>, Self::Error> {
 		sp_blockchain::tree_route::<Block, Client>(&*self.client, from, to).map_err(Into::into)
 	}
+
+	fn transaction_cost(
+		&self,
+		at: <Self::Block as BlockT>::Hash,
+		uxt: &graph::ExtrinsicFor<Self>,
+	) -> Option<TransactionCost> {
+		self.transaction_cost
+			.as_ref()
+			.and_then(|provider| provider.transaction_cost(at, uxt))
+	}
 }
 
 /// Helper function to validate a transaction using a full chain API.
@@ -0,0 +1,1046 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! A transaction pool that keeps a separate view of its transactions for every fork.
+//!
+//! [`BasicPool`](crate::BasicPool) keeps a single set of transactions that follows the best
+//! block. When the best block moves to another fork, the transactions of the retracted blocks
+//! need to be resubmitted and until then they are missing from the pool or reported as invalid.
+//!
+//! The fork aware pool keeps instead:
+//! - a mempool with every submitted transaction that is neither finalized nor invalid,
+//! - a view for every leaf that was the best block and is not yet finalized away, holding the
+//!   transactions valid at that block.
+//!
+//! The view of a new best block is derived from the view of its closest ancestor by validating
+//! its transactions again at the new block, the view of the ancestor is dropped afterwards. So
+//! when a fork becomes the best chain its transactions are already validated against it.
+//! Transactions leave the mempool once they are finalized, or if they are invalid at the finalized
+//! block and not part of any view.
+
+mod tx_mem_pool;
+mod view;
+
+use crate::{
+	api::FullChainApi,
+	error,
+	graph::{
+		self, base_pool::Limit, watcher, BlockHash, ExtrinsicFor, ExtrinsicHash, IsValidator,
+		ValidatedTransaction,
+	},
+	journal::{Journal, JournalEntry},
+	metrics::MetricsLink as PrometheusMetrics,
+	prune_known_txs_for_block, ready_iterator, PolledIterator, ReadyIteratorFor, ReadyPoll,
+	LOG_TARGET,
+};
+use async_trait::async_trait;
+use futures::{
+	channel::mpsc::{channel, Sender},
+	future, FutureExt, StreamExt,
+};
+use parking_lot::{Mutex, RwLock};
+use prometheus_endpoint::Registry as PrometheusRegistry;
+use sc_transaction_pool_api::{
+	error::Error as TxPoolError,
+	ordering::{TransactionCostProvider, TransactionOrdering},
+	ChainEvent, ImportNotificationStream, MaintainedTransactionPool, PoolFuture, PoolStatus,
+	TransactionFor, TransactionPool, TransactionSource, TransactionStatusStreamFor, TxHash,
+};
+use sp_blockchain::{HashAndNumber, TreeRoute};
+use sp_core::traits::SpawnEssentialNamed;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, NumberFor, SaturatedConversion},
+	transaction_validity::TransactionValidityError,
+};
+use std::{
+	collections::{HashMap, HashSet},
+	pin::Pin,
+	sync::Arc,
+};
+use tx_mem_pool::{TxInMemPool, TxMemPool};
+use view::View;
+
+/// A fork aware transaction pool for a full node.
+pub type FullForkAwarePool<Block, Client> = ForkAwareTxPool<FullChainApi<Client, Block>, Block>;
+
+/// The views of the pool, by the block they were created at.
+struct ViewStore<ChainApi: graph::ChainApi> {
+	views: HashMap<BlockHash<ChainApi>, Arc<View<ChainApi>>>,
+	/// The current best block, there is always a view for it.
+	best: BlockHash<ChainApi>,
+}
+
+impl<ChainApi: graph::ChainApi> ViewStore<ChainApi> {
+	fn best_view(&self) -> Arc<View<ChainApi>> {
+		self.views
+			.get(&self.best)
+			.expect("there is always a view for the best block; qed")
+			.clone()
+	}
+
+	fn all(&self) -> Vec<Arc<View<ChainApi>>> {
+		self.views.values().cloned().collect()
+	}
+}
+
+/// Status of a watched transaction, as last reported to its watchers.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ReportedStatus<H> {
+	Ready,
+	Future,
+	InBlock(H),
+}
+
+/// A transaction submitted with [`TransactionPool::submit_and_watch`].
+struct Watched<ChainApi: graph::ChainApi> {
+	sender: watcher::Sender<ExtrinsicHash<ChainApi>, BlockHash<ChainApi>>,
+	status: Option<ReportedStatus<BlockHash<ChainApi>>>,
+}
+
+/// Transaction pool that keeps a view of its transactions for every fork.
+///
+/// See the [module documentation](self) for details.
+pub struct ForkAwareTxPool<ChainApi, Block>
+where
+	Block: BlockT,
+	ChainApi: graph::ChainApi<Block = Block>,
+{
+	api: Arc<ChainApi>,
+	options: graph::Options,
+	is_validator: Arc<IsValidator>,
+	mempool: Arc<TxMemPool<ChainApi>>,
+	views: Arc<RwLock<ViewStore<ChainApi>>>,
+	/// Transactions included in recently seen blocks.
+	included: Mutex<HashMap<Block::Hash, Arc<Vec<ExtrinsicHash<ChainApi>>>>>,
+	watchers: Arc<Mutex<HashMap<ExtrinsicHash<ChainApi>, Watched<ChainApi>>>>,
+	import_notification_sinks: Arc<Mutex<Vec<Sender<ExtrinsicHash<ChainApi>>>>>,
+	ready_poll: Mutex<ReadyPoll<ReadyIteratorFor<ChainApi>, Block>>,
+	metrics: PrometheusMetrics,
+	journal: Option<Arc<Journal<ChainApi>>>,
+	/// Journaled transactions that are submitted again on the next maintenance.
+	journal_replay: Mutex<Vec<JournalEntry<ExtrinsicFor<ChainApi>>>>,
+}
+
+impl<ChainApi, Block> ForkAwareTxPool<ChainApi, Block>
+where
+	Block: BlockT,
+	ChainApi: 'static + graph::ChainApi<Block = Block>,
+{
+	/// Create a new fork aware pool, with an empty view at the given best block.
+	pub fn new(
+		options: graph::Options,
+		is_validator: IsValidator,
+		api: Arc<ChainApi>,
+		prometheus: Option<&PrometheusRegistry>,
+		best_block: HashAndNumber<Block>,
+	) -> Self {
+		let (journal, journal_replay) = match options.journal.clone() {
+			Some(journal_options) => {
+				let (journal, replay) = Journal::open(journal_options, &api);
+				(Some(Arc::new(journal)), replay)
+			},
+			None => (None, Vec::new()),
+		};
+		let is_validator = Arc::new(is_validator);
+		let mempool = TxMemPool::new(Limit {
+			count: options.ready.count + options.future.count,
+			total_bytes: options.ready.total_bytes + options.future.total_bytes,
+		});
+		let view = View::new(
+			api.clone(),
+			best_block.clone(),
+			options.clone(),
+			view_is_validator(&is_validator),
+		);
+		let views = ViewStore {
+			views: HashMap::from([(best_block.hash, Arc::new(view))]),
+			best: best_block.hash,
+		};
+
+		Self {
+			api,
+			options,
+			is_validator,
+			mempool: Arc::new(mempool),
+			views: Arc::new(RwLock::new(views)),
+			included: Default::default(),
+			watchers: Default::default(),
+			import_notification_sinks: Default::default(),
+			ready_poll: Mutex::new(ReadyPoll::new(best_block.number)),
+			metrics: PrometheusMetrics::new(prometheus),
+			journal,
+			journal_replay: Mutex::new(journal_replay),
+		}
+	}
+
+	/// Get access to the underlying api.
+	pub fn api(&self) -> &ChainApi {
+		&self.api
+	}
+
+	/// Returns the blocks that the pool currently keeps views for.
+	pub fn views(&self) -> Vec<HashAndNumber<Block>> {
+		self.views.read().views.values().map(|view| view.at.clone()).collect()
+	}
+
+	fn best_view(&self) -> Arc<View<ChainApi>> {
+		self.views.read().best_view()
+	}
+
+	fn new_view(&self, at: HashAndNumber<Block>) -> View<ChainApi> {
+		View::new(self.api.clone(), at, self.options.clone(), view_is_validator(&self.is_validator))
+	}
+
+	/// Returns the hashes of the transactions included in the given block.
+	async fn included_in(&self, block: Block::Hash) -> Arc<Vec<ExtrinsicHash<ChainApi>>> {
+		if let Some(included) = self.included.lock().get(&block) {
+			return included.clone()
+		}
+
+		let extrinsics = self
+			.api
+			.block_body(block)
+			.await
+			.unwrap_or_else(|e| {
+				log::warn!(target: LOG_TARGET, "Failed to fetch block body of {block:?}: {e}");
+				None
+			})
+			.unwrap_or_default();
+		let included = Arc::new(
+			extrinsics.iter().map(|xt| self.api.hash_and_length(xt).0).collect::<Vec<_>>(),
+		);
+		self.included.lock().insert(block, included.clone());
+		included
+	}
+
+	/// Returns the view of the closest ancestor of `at`, together with the route to `at`.
+	fn closest_ancestor_view(
+		&self,
+		at: &HashAndNumber<Block>,
+	) -> Option<(Arc<View<ChainApi>>, TreeRoute<Block>)> {
+		let views = self.views.read().all();
+		views
+			.into_iter()
+			.filter(|view| view.at.number < at.number)
+			.filter_map(|view| {
+				let tree_route = self.api.tree_route(view.at.hash, at.hash).ok()?;
+				tree_route.retracted().is_empty().then_some((view, tree_route))
+			})
+			.max_by_key(|(view, _)| view.at.number)
+	}
+
+	/// Creates the view for the given block.
+	async fn build_view(&self, at: HashAndNumber<Block>) -> Arc<View<ChainApi>> {
+		// The blocks whose transactions are pruned from the view once it is populated.
+		let (view, enacted) = match self.closest_ancestor_view(&at) {
+			Some((ancestor, tree_route)) => {
+				log::debug!(
+					target: LOG_TARGET,
+					"Creating view at {:?} from view at {:?}",
+					at,
+					ancestor.at
+				);
+				let view = ancestor
+					.clone_at(
+						self.api.clone(),
+						at.clone(),
+						self.options.clone(),
+						view_is_validator(&self.is_validator),
+					)
+					.await;
+				(view, tree_route.enacted().iter().map(|block| block.hash).collect())
+			},
+			None => {
+				log::debug!(target: LOG_TARGET, "Creating empty view at {:?}", at);
+				let hash = at.hash;
+				(self.new_view(at), vec![hash])
+			},
+		};
+		let view = Arc::new(view);
+		self.views.write().views.insert(view.at.hash, view.clone());
+
+		// Transactions that are not part of the ancestor view, including those submitted while
+		// the view was being built.
+		let mut missing = HashMap::<TransactionSource, Vec<ExtrinsicFor<ChainApi>>>::new();
+		for (hash, tx) in self.mempool.all() {
+			if !view.contains(&hash) {
+				missing.entry(tx.source).or_default().push(tx.tx);
+			}
+		}
+		for (source, xts) in missing {
+			if let Err(e) = view.pool.resubmit_at(view.at.hash, source, xts).await {
+				log::debug!(
+					target: LOG_TARGET,
+					"Error submitting transactions to view at {:?}: {}",
+					view.at,
+					e
+				);
+			}
+		}
+
+		for block in enacted {
+			let pruned = prune_known_txs_for_block(block, &*self.api, &view.pool).await;
+			self.metrics
+				.report(|metrics| metrics.block_transactions_pruned.inc_by(pruned.len() as u64));
+		}
+
+		view
+	}
+
+	/// Makes `at` the best block, creating its view if needed.
+	async fn handle_new_best_block(&self, hash: Block::Hash) {
+		let number = match self.api.block_id_to_number(&BlockId::Hash(hash)) {
+			Ok(Some(number)) => number,
+			Ok(None) => {
+				log::debug!(target: LOG_TARGET, "Unknown new best block {:?}", hash);
+				return
+			},
+			Err(e) => {
+				log::debug!(target: LOG_TARGET, "Failed to resolve new best block {hash:?}: {e}");
+				return
+			},
+		};
+
+		let existing = self.views.read().views.get(&hash).cloned();
+		let view = match existing {
+			Some(view) => view,
+			None => self.build_view(HashAndNumber { hash, number }).await,
+		};
+
+		let previous_best = std::mem::replace(&mut self.views.write().best, hash);
+		if previous_best != hash {
+			self.report_best_block_change(previous_best, &view).await;
+		}
+		self.drop_ancestor_views(&view.at);
+
+		self.ready_poll
+			.lock()
+			.trigger(number, move |ordering| ready_iterator(&view.pool, ordering));
+	}
+
+	/// Drops the views of the ancestors of `at`, which are not leaves anymore.
+	///
+	/// Their transactions are in the mempool, so a fork branching off an ancestor still gets
+	/// them when its view is built.
+	fn drop_ancestor_views(&self, at: &HashAndNumber<Block>) {
+		let views = self.views.read().all();
+		let ancestors = views
+			.iter()
+			.filter(|view| {
+				view.at.number < at.number &&
+					self.api
+						.tree_route(view.at.hash, at.hash)
+						.map(|tree_route| tree_route.retracted().is_empty())
+						.unwrap_or(false)
+			})
+			.map(|view| view.at.hash)
+			.collect::<HashSet<_>>();
+		if ancestors.is_empty() {
+			return
+		}
+
+		let mut store = self.views.write();
+		let best = store.best;
+		store.views.retain(|block, _| *block == best || !ancestors.contains(block));
+		log::debug!(
+			target: LOG_TARGET,
+			"Dropped {} views of ancestors of {:?}, {} views left",
+			ancestors.len(),
+			at,
+			store.views.len()
+		);
+	}
+
+	/// Informs the watchers about the transactions that were retracted and enacted when the best
+	/// block moved from `previous_best` to the block of `view`.
+	async fn report_best_block_change(&self, previous_best: Block::Hash, view: &View<ChainApi>) {
+		if self.watchers.lock().is_empty() {
+			return
+		}
+
+		let tree_route = match self.api.tree_route(previous_best, view.at.hash) {
+			Ok(tree_route) => tree_route,
+			Err(e) => {
+				log::debug!(
+					target: LOG_TARGET,
+					"Failed to compute tree route from {previous_best:?} to {:?}: {e}",
+					view.at
+				);
+				return
+			},
+		};
+
+		for retracted in tree_route.retracted() {
+			let included = self.included_in(retracted.hash).await;
+			let mut watchers = self.watchers.lock();
+			for tx in included.iter() {
+				if let Some(watched) = watchers.get_mut(tx) {
+					watched.sender.retracted(retracted.hash);
+					watched.status = None;
+				}
+			}
+		}
+
+		for enacted in tree_route.enacted() {
+			let included = self.included_in(enacted.hash).await;
+			let mut watchers = self.watchers.lock();
+			for (index, tx) in included.iter().enumerate() {
+				if let Some(watched) = watchers.get_mut(tx) {
+					watched.sender.in_block(enacted.hash, index);
+					watched.status = Some(ReportedStatus::InBlock(enacted.hash));
+				}
+			}
+		}
+
+		let mut watchers = self.watchers.lock();
+		for (hash, watched) in watchers.iter_mut() {
+			report_view_status(view, hash, watched);
+		}
+		watchers.retain(|_, watched| !watched.sender.is_done());
+	}
+
+	/// Handles the finalization of `hash` and of the blocks in `tree_route` leading to it.
+	async fn handle_finalized(&self, hash: Block::Hash, tree_route: &[Block::Hash]) {
+		let number = match self.api.block_id_to_number(&BlockId::Hash(hash)) {
+			Ok(Some(number)) => number,
+			_ => {
+				log::debug!(target: LOG_TARGET, "Unknown finalized block {:?}", hash);
+				return
+			},
+		};
+
+		for block in tree_route.iter().chain(std::iter::once(&hash)) {
+			let included = self.included_in(*block).await;
+			self.mempool.remove(included.iter());
+
+			let mut watchers = self.watchers.lock();
+			for (index, tx) in included.iter().enumerate() {
+				if let Some(mut watched) = watchers.remove(tx) {
+					if watched.status != Some(ReportedStatus::InBlock(*block)) {
+						watched.sender.in_block(*block, index);
+					}
+					watched.sender.finalized(*block, index);
+				}
+			}
+		}
+
+		// Keep the views of the finalized block and of its descendants.
+		let views = self.views.read().all();
+		let stale = views
+			.iter()
+			.filter(|view| {
+				view.at.hash != hash &&
+					(view.at.number <= number ||
+						self.api
+							.tree_route(hash, view.at.hash)
+							.map(|tree_route| !tree_route.retracted().is_empty())
+							.unwrap_or(true))
+			})
+			.map(|view| view.at.hash)
+			.collect::<HashSet<_>>();
+		{
+			let mut store = self.views.write();
+			let best = store.best;
+			store.views.retain(|block, _| *block == best || !stale.contains(block));
+			let views = &store.views;
+			self.included.lock().retain(|block, _| views.contains_key(block));
+		}
+		log::debug!(
+			target: LOG_TARGET,
+			"Finalized {:?}, dropped {} views, mempool: {} transactions",
+			hash,
+			stale.len(),
+			self.mempool.len()
+		);
+
+		self.remove_invalid_at_finalized(hash).await;
+	}
+
+	/// Removes the transactions that are not part of any view and are invalid at the finalized
+	/// block.
+	async fn remove_invalid_at_finalized(&self, finalized: Block::Hash) {
+		let views = self.views.read().all();
+		let orphans = self
+			.mempool
+			.all()
+			.into_iter()
+			.filter(|(hash, _)| !views.iter().any(|view| view.contains(hash)))
+			.collect::<Vec<_>>();
+
+		let validity = future::join_all(
+			orphans
+				.iter()
+				.map(|(_, tx)| self.api.validate_transaction(finalized, tx.source, tx.tx.clone())),
+		)
+		.await;
+
+		let invalid = orphans
+			.iter()
+			.zip(validity)
+			.filter(|(_, validity)| {
+				matches!(validity, Ok(Err(TransactionValidityError::Invalid(_))))
+			})
+			.map(|((hash, _), _)| *hash)
+			.collect::<Vec<_>>();
+		if invalid.is_empty() {
+			return
+		}
+
+		log::debug!(target: LOG_TARGET, "Removing invalid transactions: {:?}", invalid);
+		self.mempool.remove(invalid.iter());
+		self.metrics
+			.report(|metrics| metrics.validations_invalid.inc_by(invalid.len() as u64));
+		let mut watchers = self.watchers.lock();
+		for hash in &invalid {
+			if let Some(mut watched) = watchers.remove(hash) {
+				watched.sender.invalid();
+			}
+		}
+	}
+
+	/// Submits the transactions loaded from the journal at the best block, if not done yet.
+	async fn replay_journal(&self) {
+		let entries = std::mem::take(&mut *self.journal_replay.lock());
+		if entries.is_empty() {
+			return
+		}
+
+		log::debug!(target: LOG_TARGET, "Resubmitting {} journaled transactions", entries.len());
+
+		// Submit consecutive transactions of the same source together, keeping the journal order.
+		let best = self.views.read().best;
+		let mut entries = entries.into_iter().peekable();
+		while let Some(first) = entries.next() {
+			let source = first.source;
+			let mut xts = vec![first.extrinsic];
+			while let Some(entry) = entries.next_if(|entry| entry.source == source) {
+				xts.push(entry.extrinsic);
+			}
+
+			if let Err(e) = self.submit_at(best, source, xts).await {
+				log::debug!(target: LOG_TARGET, "Failed to resubmit journaled transactions: {e}");
+			}
+		}
+	}
+
+	fn ready_at_impl(
+		&self,
+		at: NumberFor<Block>,
+		ordering: Option<Arc<dyn TransactionOrdering>>,
+	) -> PolledIterator<ChainApi> {
+		let view = self.best_view();
+		if view.pool.validated_pool().status().is_empty() && self.mempool.len() == 0 {
+			return async { Box::new(std::iter::empty()) as Box<_> }.boxed()
+		}
+
+		if self.ready_poll.lock().updated_at() >= at {
+			let iterator = ready_iterator(&view.pool, ordering);
+			return async move { iterator }.boxed()
+		}
+
+		self.ready_poll
+			.lock()
+			.add(at, ordering)
+			.map(|received| {
+				received.unwrap_or_else(|e| {
+					log::warn!(target: LOG_TARGET, "Error receiving pending set: {:?}", e);
+					Box::new(std::iter::empty())
+				})
+			})
+			.boxed()
+	}
+}
+
+/// A validator check for a new view, sharing the one of the pool.
+fn view_is_validator(is_validator: &Arc<IsValidator>) -> IsValidator {
+	let is_validator = is_validator.clone();
+	IsValidator::from(Box::new(move || is_validator.get()) as Box<dyn Fn() -> bool + Send + Sync>)
+}
+
+/// Reports the status of a watched transaction in `view`, if it changed.
+fn report_view_status<ChainApi: graph::ChainApi + 'static>(
+	view: &View<ChainApi>,
+	hash: &ExtrinsicHash<ChainApi>,
+	watched: &mut Watched<ChainApi>,
+) {
+	if matches!(watched.status, Some(ReportedStatus::InBlock(_))) {
+		return
+	}
+	let status = if view.is_ready(hash) {
+		ReportedStatus::Ready
+	} else if view.contains(hash) {
+		ReportedStatus::Future
+	} else {
+		return
+	};
+	if watched.status != Some(status) {
+		match status {
+			ReportedStatus::Ready => watched.sender.ready(),
+			_ => watched.sender.future(),
+		}
+		watched.status = Some(status);
+	}
+}
+
+/// Submits the transactions to the mempool and to every view.
+///
+/// A transaction is kept if it is valid in at least one view. The returned result of each
+/// transaction is the one of the view at `at`, falling back to the best view.
+async fn submit_to_views<ChainApi: graph::ChainApi + 'static>(
+	api: Arc<ChainApi>,
+	mempool: Arc<TxMemPool<ChainApi>>,
+	views: Vec<Arc<View<ChainApi>>>,
+	reference: BlockHash<ChainApi>,
+	source: TransactionSource,
+	xts: Vec<ExtrinsicFor<ChainApi>>,
+) -> Vec<Result<ExtrinsicHash<ChainApi>, ChainApi::Error>> {
+	let mut results = Vec::with_capacity(xts.len());
+	let mut accepted = Vec::new();
+	for xt in xts {
+		let (hash, bytes) = api.hash_and_length(&xt);
+		if mempool.contains(&hash) {
+			results.push(Some(Err(TxPoolError::AlreadyImported(Box::new(hash)).into())));
+		} else if !mempool.insert(hash, TxInMemPool { tx: xt.clone(), source, bytes }) {
+			results.push(Some(Err(TxPoolError::ImmediatelyDropped.into())));
+		} else {
+			results.push(None);
+			accepted.push((hash, xt));
+		}
+	}
+
+	let per_view = future::join_all(views.iter().map(|view| {
+		view.pool
+			.submit_at(view.at.hash, source, accepted.iter().map(|(_, xt)| xt.clone()))
+	}))
+	.await;
+
+	let mut reference_results = None;
+	let mut valid_anywhere = HashSet::new();
+	for (view, view_results) in views.iter().zip(per_view) {
+		let view_results = match view_results {
+			Ok(view_results) => view_results,
+			Err(e) => {
+				log::debug!(target: LOG_TARGET, "Failed to submit to view at {:?}: {}", view.at, e);
+				continue
+			},
+		};
+		valid_anywhere
+			.extend(view_results.iter().filter_map(|result| result.as_ref().ok()).cloned());
+		if view.at.hash == reference {
+			reference_results = Some(view_results);
+		}
+	}
+	let mut reference_results = reference_results.map(|results| results.into_iter());
+
+	let mut accepted = accepted.into_iter();
+	for result in results.iter_mut().filter(|result| result.is_none()) {
+		let (hash, _) = accepted.next().expect("one accepted transaction per empty result; qed");
+		let reference_result = reference_results.as_mut().and_then(|results| results.next());
+		*result = Some(if valid_anywhere.contains(&hash) {
+			Ok(hash)
+		} else {
+			mempool.remove(std::iter::once(&hash));
+			reference_result.unwrap_or_else(|| Err(TxPoolError::ImmediatelyDropped.into()))
+		});
+	}
+
+	results
+		.into_iter()
+		.map(|result| result.expect("all results are filled above; qed"))
+		.collect()
+}
+
+/// Notifies the import notification sinks about newly imported transactions.
+fn notify_imported<Hash: Copy + std::fmt::Debug>(
+	sinks: &Mutex<Vec<Sender<Hash>>>,
+	hashes: &[Hash],
+) {
+	let mut sinks = sinks.lock();
+	for hash in hashes {
+		sinks.retain_mut(|sink| match sink.try_send(*hash) {
+			Ok(()) => true,
+			Err(e) =>
+				if e.is_full() {
+					log::warn!(
+						target: LOG_TARGET,
+						"[{:?}] Trying to notify an import but the channel is full",
+						hash,
+					);
+					true
+				} else {
+					false
+				},
+		});
+	}
+}
+
+impl<ChainApi, Block> TransactionPool for ForkAwareTxPool<ChainApi, Block>
+where
+	Block: BlockT,
+	ChainApi: 'static + graph::ChainApi<Block = Block>,
+{
+	type Block = ChainApi::Block;
+	type Hash = ExtrinsicHash<ChainApi>;
+	type InPoolTransaction = graph::base_pool::Transaction<TxHash<Self>, TransactionFor<Self>>;
+	type Error = ChainApi::Error;
+
+	fn submit_at(
+		&self,
+		at: Block::Hash,
+		source: TransactionSource,
+		xts: Vec<TransactionFor<Self>>,
+	) -> PoolFuture<Vec<Result<TxHash<Self>, Self::Error>>, Self::Error> {
+		self.metrics
+			.report(|metrics| metrics.submitted_transactions.inc_by(xts.len() as u64));
+
+		let (views, best) = {
+			let store = self.views.read();
+			(store.all(), store.best)
+		};
+		let reference = if views.iter().any(|view| view.at.hash == at) { at } else { best };
+		let api = self.api.clone();
+		let mempool = self.mempool.clone();
+		let sinks = self.import_notification_sinks.clone();
+		let journal = self.journal.clone();
+
+		async move {
+			let results = submit_to_views(api, mempool, views, reference, source, xts).await;
+			let imported = results
+				.iter()
+				.filter_map(|result| result.as_ref().ok())
+				.cloned()
+				.collect::<Vec<_>>();
+			if let Some(journal) = journal {
+				journal.inserted(imported.iter().cloned());
+			}
+			notify_imported(&sinks, &imported);
+			Ok(results)
+		}
+		.boxed()
+	}
+
+	fn submit_one(
+		&self,
+		at: Block::Hash,
+		source: TransactionSource,
+		xt: TransactionFor<Self>,
+	) -> PoolFuture<TxHash<Self>, Self::Error> {
+		let submit = self.submit_at(at, source, vec![xt]);
+		async move {
+			let mut results = submit.await?;
+			results.pop().expect("One extrinsic passed; one result returned; qed")
+		}
+		.boxed()
+	}
+
+	fn submit_and_watch(
+		&self,
+		at: Block::Hash,
+		source: TransactionSource,
+		xt: TransactionFor<Self>,
+	) -> PoolFuture<Pin<Box<TransactionStatusStreamFor<Self>>>, Self::Error> {
+		let submit = self.submit_one(at, source, xt);
+		let views = self.views.clone();
+		let watchers = self.watchers.clone();
+
+		async move {
+			let hash = submit.await?;
+			let mut sender = watcher::Sender::default();
+			let watcher = sender.new_watcher(hash);
+			let mut watched = Watched { sender, status: None };
+			report_view_status(&views.read().best_view(), &hash, &mut watched);
+			watchers.lock().insert(hash, watched);
+			Ok(watcher.into_stream().boxed())
+		}
+		.boxed()
+	}
+
+	fn remove_invalid(&self, hashes: &[TxHash<Self>]) -> Vec<Arc<Self::InPoolTransaction>> {
+		self.mempool.remove(hashes.iter());
+		let (views, best) = {
+			let store = self.views.read();
+			(store.all(), store.best)
+		};
+		let mut removed = Vec::new();
+		for view in views {
+			let view_removed = view.pool.validated_pool().remove_invalid(hashes);
+			if view.at.hash == best {
+				removed = view_removed;
+			}
+		}
+
+		let mut watchers = self.watchers.lock();
+		for hash in hashes {
+			if let Some(mut watched) = watchers.remove(hash) {
+				watched.sender.invalid();
+			}
+		}
+
+		self.metrics
+			.report(|metrics| metrics.validations_invalid.inc_by(removed.len() as u64));
+		removed
+	}
+
+	fn status(&self) -> PoolStatus {
+		self.best_view().pool.validated_pool().status()
+	}
+
+	fn import_notification_stream(&self) -> ImportNotificationStream<TxHash<Self>> {
+		const CHANNEL_BUFFER_SIZE: usize = 1024;
+
+		let (sink, stream) = channel(CHANNEL_BUFFER_SIZE);
+		self.import_notification_sinks.lock().push(sink);
+		stream
+	}
+
+	fn hash_of(&self, xt: &TransactionFor<Self>) -> TxHash<Self> {
+		self.api.hash_and_length(xt).0
+	}
+
+	fn on_broadcasted(&self, propagations: HashMap<TxHash<Self>, Vec<String>>) {
+		let mut watchers = self.watchers.lock();
+		for (hash, peers) in propagations {
+			if let Some(watched) = watchers.get_mut(&hash) {
+				watched.sender.broadcast(peers);
+			}
+		}
+	}
+
+	fn ready_transaction(&self, hash: &TxHash<Self>) -> Option<Arc<Self::InPoolTransaction>> {
+		self.best_view().pool.validated_pool().ready_by_hash(hash)
+	}
+
+	fn ready_at(&self, at: NumberFor<Self::Block>) -> PolledIterator<ChainApi> {
+		self.ready_at_impl(at, None)
+	}
+
+	fn ready_at_with_ordering(
+		&self,
+		at: NumberFor<Self::Block>,
+		ordering: Arc<dyn TransactionOrdering>,
+	) -> PolledIterator<ChainApi> {
+		self.ready_at_impl(at, Some(ordering))
+	}
+
+	fn ready(&self) -> ReadyIteratorFor<ChainApi> {
+		Box::new(self.best_view().pool.validated_pool().ready())
+	}
+
+	fn futures(&self) -> Vec<Self::InPoolTransaction> {
+		let view = self.best_view();
+		let pool = view.pool.validated_pool().pool.read();
+		pool.futures().cloned().collect()
+	}
+}
+
+#[async_trait]
+impl<ChainApi, Block> MaintainedTransactionPool for ForkAwareTxPool<ChainApi, Block>
+where
+	Block: BlockT,
+	ChainApi: 'static + graph::ChainApi<Block = Block>,
+{
+	async fn maintain(&self, event: ChainEvent<Self::Block>) {
+		let finalized = matches!(event, ChainEvent::Finalized { .. });
+		match event {
+			ChainEvent::NewBestBlock { hash, .. } => self.handle_new_best_block(hash).await,
+			ChainEvent::Finalized { hash, tree_route } => {
+				// Finality may move to a fork that was never reported as the best chain, the
+				// best view has to follow it.
+				let best = self.views.read().best;
+				let on_best_chain = self
+					.api
+					.tree_route(hash, best)
+					.map(|tree_route| tree_route.retracted().is_empty())
+					.unwrap_or(false);
+				if !on_best_chain {
+					self.handle_new_best_block(hash).await;
+				}
+				self.handle_finalized(hash, &tree_route).await;
+			},
+		}
+
+		self.replay_journal().await;
+
+		if let Some(journal) = self.journal.as_ref().filter(|_| finalized) {
+			journal.write_in_background(self.best_view().pool.validated_pool());
+		}
+	}
+}
+
+impl<ChainApi, Block> Drop for ForkAwareTxPool<ChainApi, Block>
+where
+	Block: BlockT,
+	ChainApi: graph::ChainApi<Block = Block>,
+{
+	fn drop(&mut self) {
+		// Until the journal is replayed its transactions are not in the pool, keep the journal.
+		if !self.journal_replay.get_mut().is_empty() {
+			return
+		}
+
+		if let Some(journal) = &self.journal {
+			journal.write(self.views.read().best_view().pool.validated_pool());
+		}
+	}
+}
+
+impl<Block, Client> FullForkAwarePool<Block, Client>
+where
+	Block: BlockT,
+	Client: sp_api::ProvideRuntimeApi<Block>
+		+ sc_client_api::BlockBackend<Block>
+		+ sc_client_api::blockchain::HeaderBackend<Block>
+		+ sp_runtime::traits::BlockIdTo<Block>
+		+ sc_client_api::ExecutorProvider<Block>
+		+ sc_client_api::UsageProvider<Block>
+		+ sp_blockchain::HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api: sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
+{
+	/// Create new fork aware transaction pool for a full node with the provided api.
+	pub fn new_full(
+		options: graph::Options,
+		is_validator: IsValidator,
+		prometheus: Option<&PrometheusRegistry>,
+		spawner: impl SpawnEssentialNamed,
+		client: Arc<Client>,
+	) -> Arc<Self> {
+		Self::new_full_with_transaction_cost(
+			options,
+			is_validator,
+			prometheus,
+			spawner,
+			client,
+			None,
+		)
+	}
+
+	/// Create new fork aware transaction pool for a full node, reporting the fee and weight of
+	/// transactions from `transaction_cost` to the ordering policy.
+	pub fn new_full_with_transaction_cost(
+		options: graph::Options,
+		is_validator: IsValidator,
+		prometheus: Option<&PrometheusRegistry>,
+		spawner: impl SpawnEssentialNamed,
+		client: Arc<Client>,
+		transaction_cost: Option<Arc<dyn TransactionCostProvider<Block>>>,
+	) -> Arc<Self> {
+		let pool_api = Arc::new(
+			FullChainApi::new(client.clone(), prometheus, &spawner)
+				.with_transaction_cost(transaction_cost),
+		);
+		let info = client.usage_info().chain;
+		let mut pool = Self::new(
+			options,
+			is_validator,
+			pool_api,
+			prometheus,
+			HashAndNumber { hash: info.best_hash, number: info.best_number },
+		);
+		if let Some(journal) = pool.journal.as_mut().and_then(Arc::get_mut) {
+			journal.spawn_writer(&spawner);
+		}
+		Arc::new(pool)
+	}
+}
+
+impl<Block, Client> sc_transaction_pool_api::LocalTransactionPool
+	for ForkAwareTxPool<FullChainApi<Client, Block>, Block>
+where
+	Block: BlockT,
+	Client: sp_api::ProvideRuntimeApi<Block>
+		+ sc_client_api::BlockBackend<Block>
+		+ sc_client_api::blockchain::HeaderBackend<Block>
+		+ sp_runtime::traits::BlockIdTo<Block>
+		+ sp_blockchain::HeaderMetadata<Block, Error = sp_blockchain::Error>,
+	Client: Send + Sync + 'static,
+	Client::Api: sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
+{
+	type Block = Block;
+	type Hash = ExtrinsicHash<FullChainApi<Client, Block>>;
+	type Error = <FullChainApi<Client, Block> as graph::ChainApi>::Error;
+
+	fn submit_local(
+		&self,
+		at: Block::Hash,
+		xt: sc_transaction_pool_api::LocalTransactionFor<Self>,
+	) -> Result<Self::Hash, Self::Error> {
+		let (hash, bytes) = self.api.hash_and_length(&xt);
+		if self.mempool.contains(&hash) {
+			return Err(TxPoolError::AlreadyImported(Box::new(hash)).into())
+		}
+		let tx = TxInMemPool { tx: xt.clone(), source: TransactionSource::Local, bytes };
+		if !self.mempool.insert(hash, tx) {
+			return Err(TxPoolError::ImmediatelyDropped.into())
+		}
+
+		let (views, best) = {
+			let store = self.views.read();
+			(store.all(), store.best)
+		};
+		let reference = if views.iter().any(|view| view.at.hash == at) { at } else { best };
+
+		let mut reference_result = None;
+		let mut valid_anywhere = false;
+		for view in views {
+			let result = self
+				.api
+				.validate_transaction_blocking(view.at.hash, TransactionSource::Local, xt.clone())
+				.and_then(|validity| {
+					validity.map_err(|e| {
+						error::Error::Pool(match e {
+							TransactionValidityError::Invalid(i) =>
+								TxPoolError::InvalidTransaction(i),
+							TransactionValidityError::Unknown(u) =>
+								TxPoolError::UnknownTransaction(u),
+						})
+					})
+				})
+				.and_then(|validity| {
+					let validated = ValidatedTransaction::valid_at(
+						view.at.number.saturated_into::<u64>(),
+						hash,
+						TransactionSource::Local,
+						xt.clone(),
+						bytes,
+						validity,
+						self.api.transaction_cost(view.at.hash, &xt),
+					);
+					view.pool.validated_pool().submit(vec![validated]).remove(0)
+				});
+			valid_anywhere |= result.is_ok();
+			if view.at.hash == reference {
+				reference_result = Some(result);
+			}
+		}
+
+		if valid_anywhere {
+			if let Some(journal) = &self.journal {
+				journal.inserted([hash]);
+			}
+			notify_imported(&self.import_notification_sinks, &[hash]);
+			Ok(hash)
+		} else {
+			self.mempool.remove(std::iter::once(&hash));
+			reference_result.unwrap_or_else(|| Err(TxPoolError::ImmediatelyDropped.into()))
+		}
+	}
+}
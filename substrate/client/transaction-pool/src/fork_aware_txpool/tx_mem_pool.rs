@@ -0,0 +1,121 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Transactions known to the fork aware pool, independently of the views they are valid in.
+
+use crate::graph::{self, base_pool::Limit, ExtrinsicFor, ExtrinsicHash};
+use parking_lot::RwLock;
+use sc_transaction_pool_api::TransactionSource;
+use std::collections::HashMap;
+
+/// A transaction submitted to the pool.
+pub(super) struct TxInMemPool<ChainApi: graph::ChainApi> {
+	/// The transaction itself.
+	pub(super) tx: ExtrinsicFor<ChainApi>,
+	/// Where the transaction came from.
+	pub(super) source: TransactionSource,
+	/// Size of the encoded transaction.
+	pub(super) bytes: usize,
+}
+
+impl<ChainApi: graph::ChainApi> Clone for TxInMemPool<ChainApi> {
+	fn clone(&self) -> Self {
+		Self { tx: self.tx.clone(), source: self.source, bytes: self.bytes }
+	}
+}
+
+/// The transactions of the [`TxMemPool`] and their total size.
+struct Transactions<ChainApi: graph::ChainApi> {
+	by_hash: HashMap<ExtrinsicHash<ChainApi>, TxInMemPool<ChainApi>>,
+	bytes: usize,
+}
+
+/// All transactions that were submitted to the pool and are not finalized or invalid yet.
+///
+/// A transaction stays here as long as it might become valid on some fork, views are populated
+/// from this set.
+pub(super) struct TxMemPool<ChainApi: graph::ChainApi> {
+	transactions: RwLock<Transactions<ChainApi>>,
+	limit: Limit,
+}
+
+impl<ChainApi: graph::ChainApi> TxMemPool<ChainApi> {
+	/// Creates an empty pool holding at most `limit` transactions.
+	pub(super) fn new(limit: Limit) -> Self {
+		Self {
+			transactions: RwLock::new(Transactions { by_hash: HashMap::new(), bytes: 0 }),
+			limit,
+		}
+	}
+
+	/// Inserts a transaction.
+	///
+	/// Returns `false` if the transaction is already known or the limit is reached.
+	pub(super) fn insert(&self, hash: ExtrinsicHash<ChainApi>, tx: TxInMemPool<ChainApi>) -> bool {
+		let mut transactions = self.transactions.write();
+		if transactions.by_hash.contains_key(&hash) {
+			return false
+		}
+		let bytes = transactions.bytes + tx.bytes;
+		if self.limit.is_exceeded(transactions.by_hash.len() + 1, bytes) {
+			return false
+		}
+		transactions.by_hash.insert(hash, tx);
+		transactions.bytes = bytes;
+		true
+	}
+
+	/// Removes the given transactions, returning those that were known.
+	pub(super) fn remove<'a>(
+		&self,
+		hashes: impl IntoIterator<Item = &'a ExtrinsicHash<ChainApi>>,
+	) -> Vec<ExtrinsicHash<ChainApi>> {
+		let mut transactions = self.transactions.write();
+		hashes
+			.into_iter()
+			.filter(|hash| match transactions.by_hash.remove(hash) {
+				Some(tx) => {
+					transactions.bytes -= tx.bytes;
+					true
+				},
+				None => false,
+			})
+			.cloned()
+			.collect()
+	}
+
+	/// Returns true if the transaction is known.
+	pub(super) fn contains(&self, hash: &ExtrinsicHash<ChainApi>) -> bool {
+		self.transactions.read().by_hash.contains_key(hash)
+	}
+
+	/// Returns a snapshot of all known transactions.
+	pub(super) fn all(&self) -> Vec<(ExtrinsicHash<ChainApi>, TxInMemPool<ChainApi>)> {
+		self.transactions
+			.read()
+			.by_hash
+			.iter()
+			.map(|(hash, tx)| (*hash, tx.clone()))
+			.collect()
+	}
+
+	/// Number of known transactions.
+	pub(super) fn len(&self) -> usize {
+		self.transactions.read().by_hash.len()
+	}
+}
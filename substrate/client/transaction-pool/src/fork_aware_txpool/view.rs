@@ -0,0 +1,99 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! A view of the transaction pool at a single block.
+
+use crate::{
+	graph::{self, ExtrinsicFor, ExtrinsicHash, IsValidator},
+	LOG_TARGET,
+};
+use sc_transaction_pool_api::TransactionSource;
+use sp_blockchain::HashAndNumber;
+use std::{collections::HashMap, sync::Arc};
+
+/// Transactions validated against the state of a single block.
+///
+/// Every block that became the best block at some point gets its own view, so transactions keep
+/// their validity on every fork that is still alive.
+pub(super) struct View<ChainApi: graph::ChainApi> {
+	/// The block the transactions are valid at.
+	pub(super) at: HashAndNumber<ChainApi::Block>,
+	/// The transactions valid at `at`.
+	pub(super) pool: graph::Pool<ChainApi>,
+}
+
+impl<ChainApi> View<ChainApi>
+where
+	ChainApi: graph::ChainApi + 'static,
+{
+	/// Creates an empty view.
+	pub(super) fn new(
+		api: Arc<ChainApi>,
+		at: HashAndNumber<ChainApi::Block>,
+		options: graph::Options,
+		is_validator: IsValidator,
+	) -> Self {
+		Self { at, pool: graph::Pool::new(options, is_validator, api) }
+	}
+
+	/// Creates a view at `at` from the transactions of this view.
+	///
+	/// The ready and future transactions are validated again at `at`, the ones that are invalid
+	/// there, e.g. because they were included in the blocks between the two views, are dropped.
+	pub(super) async fn clone_at(
+		&self,
+		api: Arc<ChainApi>,
+		at: HashAndNumber<ChainApi::Block>,
+		options: graph::Options,
+		is_validator: IsValidator,
+	) -> Self {
+		let view = Self::new(api, at, options, is_validator);
+		let mut by_source = HashMap::<TransactionSource, Vec<ExtrinsicFor<ChainApi>>>::new();
+		{
+			let pool = self.pool.validated_pool().pool.read();
+			for tx in pool.ready() {
+				by_source.entry(tx.source).or_default().push(tx.data.clone());
+			}
+			for tx in pool.futures() {
+				by_source.entry(tx.source).or_default().push(tx.data.clone());
+			}
+		}
+		for (source, xts) in by_source {
+			if let Err(e) = view.pool.resubmit_at(view.at.hash, source, xts).await {
+				log::debug!(
+					target: LOG_TARGET,
+					"Error revalidating transactions of view at {:?} at {:?}: {}",
+					self.at,
+					view.at,
+					e
+				);
+			}
+		}
+		view
+	}
+
+	/// Returns true if the transaction is in the ready or future queue of this view.
+	pub(super) fn contains(&self, hash: &ExtrinsicHash<ChainApi>) -> bool {
+		self.pool.validated_pool().pool.read().is_imported(hash)
+	}
+
+	/// Returns true if the transaction is in the ready queue of this view.
+	pub(super) fn is_ready(&self, hash: &ExtrinsicHash<ChainApi>) -> bool {
+		self.pool.validated_pool().ready_by_hash(hash).is_some()
+	}
+}
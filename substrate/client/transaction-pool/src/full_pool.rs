@@ -0,0 +1,250 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Transaction pool of a full node, of the type selected in the pool [`Options`].
+
+use crate::{
+	graph::{self, IsValidator},
+	FullChainApi, FullForkAwarePool, FullPool, Options, PolledIterator, ReadyIteratorFor,
+};
+use async_trait::async_trait;
+use prometheus_endpoint::Registry as PrometheusRegistry;
+use sc_transaction_pool_api::{
+	ordering::{TransactionCostProvider, TransactionOrdering},
+	ChainEvent, ImportNotificationStream, LocalTransactionFor, LocalTransactionPool,
+	MaintainedTransactionPool, PoolFuture, PoolStatus, TransactionFor, TransactionPool,
+	TransactionSource, TransactionStatusStreamFor, TxHash,
+};
+use sp_core::traits::SpawnEssentialNamed;
+use sp_runtime::traits::{Block as BlockT, NumberFor};
+use std::{collections::HashMap, pin::Pin, sync::Arc};
+
+/// The type of transaction pool run by a full node.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransactionPoolType {
+	/// One set of transactions following the best block, see [`BasicPool`](crate::BasicPool).
+	#[default]
+	SingleState,
+	/// A set of transactions for every fork, see [`ForkAwareTxPool`](crate::ForkAwareTxPool).
+	ForkAware,
+}
+
+/// A transaction pool for a full node, of the type given by [`Options::pool_type`].
+pub enum FullTransactionPool<Block, Client>
+where
+	Block: BlockT,
+	FullChainApi<Client, Block>: graph::ChainApi<Block = Block>,
+{
+	/// A [`TransactionPoolType::SingleState`] pool.
+	SingleState(Arc<FullPool<Block, Client>>),
+	/// A [`TransactionPoolType::ForkAware`] pool.
+	ForkAware(Arc<FullForkAwarePool<Block, Client>>),
+}
+
+/// Forwards a call to the pool that is in use.
+macro_rules! with_pool {
+	($self:ident, $pool:ident => $call:expr) => {
+		match $self {
+			Self::SingleState($pool) => $call,
+			Self::ForkAware($pool) => $call,
+		}
+	};
+}
+
+impl<Block, Client> FullTransactionPool<Block, Client>
+where
+	Block: BlockT,
+	Client: sp_api::ProvideRuntimeApi<Block>
+		+ sc_client_api::BlockBackend<Block>
+		+ sc_client_api::blockchain::HeaderBackend<Block>
+		+ sp_runtime::traits::BlockIdTo<Block>
+		+ sc_client_api::ExecutorProvider<Block>
+		+ sc_client_api::UsageProvider<Block>
+		+ sp_blockchain::HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api: sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
+{
+	/// Create the transaction pool selected by `options.pool_type` for a full node.
+	pub fn new_full(
+		options: Options,
+		is_validator: IsValidator,
+		prometheus: Option<&PrometheusRegistry>,
+		spawner: impl SpawnEssentialNamed,
+		client: Arc<Client>,
+	) -> Arc<Self> {
+		Self::new_full_with_transaction_cost(
+			options,
+			is_validator,
+			prometheus,
+			spawner,
+			client,
+			None,
+		)
+	}
+
+	/// Create the transaction pool selected by `options.pool_type` for a full node, reporting the
+	/// fee and weight of transactions from `transaction_cost` to the ordering policy.
+	pub fn new_full_with_transaction_cost(
+		options: Options,
+		is_validator: IsValidator,
+		prometheus: Option<&PrometheusRegistry>,
+		spawner: impl SpawnEssentialNamed,
+		client: Arc<Client>,
+		transaction_cost: Option<Arc<dyn TransactionCostProvider<Block>>>,
+	) -> Arc<Self> {
+		Arc::new(match options.pool_type {
+			TransactionPoolType::SingleState =>
+				Self::SingleState(FullPool::new_full_with_transaction_cost(
+					options,
+					is_validator,
+					prometheus,
+					spawner,
+					client,
+					transaction_cost,
+				)),
+			TransactionPoolType::ForkAware =>
+				Self::ForkAware(FullForkAwarePool::new_full_with_transaction_cost(
+					options,
+					is_validator,
+					prometheus,
+					spawner,
+					client,
+					transaction_cost,
+				)),
+		})
+	}
+}
+
+impl<Block, Client> TransactionPool for FullTransactionPool<Block, Client>
+where
+	Block: BlockT,
+	FullChainApi<Client, Block>: graph::ChainApi<Block = Block> + 'static,
+{
+	type Block = Block;
+	type Hash = graph::ExtrinsicHash<FullChainApi<Client, Block>>;
+	type InPoolTransaction = graph::base_pool::Transaction<TxHash<Self>, TransactionFor<Self>>;
+	type Error = <FullChainApi<Client, Block> as graph::ChainApi>::Error;
+
+	fn submit_at(
+		&self,
+		at: Block::Hash,
+		source: TransactionSource,
+		xts: Vec<TransactionFor<Self>>,
+	) -> PoolFuture<Vec<Result<TxHash<Self>, Self::Error>>, Self::Error> {
+		with_pool!(self, pool => pool.submit_at(at, source, xts))
+	}
+
+	fn submit_one(
+		&self,
+		at: Block::Hash,
+		source: TransactionSource,
+		xt: TransactionFor<Self>,
+	) -> PoolFuture<TxHash<Self>, Self::Error> {
+		with_pool!(self, pool => pool.submit_one(at, source, xt))
+	}
+
+	fn submit_and_watch(
+		&self,
+		at: Block::Hash,
+		source: TransactionSource,
+		xt: TransactionFor<Self>,
+	) -> PoolFuture<Pin<Box<TransactionStatusStreamFor<Self>>>, Self::Error> {
+		with_pool!(self, pool => pool.submit_and_watch(at, source, xt))
+	}
+
+	fn remove_invalid(&self, hashes: &[TxHash<Self>]) -> Vec<Arc<Self::InPoolTransaction>> {
+		with_pool!(self, pool => pool.remove_invalid(hashes))
+	}
+
+	fn status(&self) -> PoolStatus {
+		with_pool!(self, pool => pool.status())
+	}
+
+	fn import_notification_stream(&self) -> ImportNotificationStream<TxHash<Self>> {
+		with_pool!(self, pool => pool.import_notification_stream())
+	}
+
+	fn hash_of(&self, xt: &TransactionFor<Self>) -> TxHash<Self> {
+		with_pool!(self, pool => pool.hash_of(xt))
+	}
+
+	fn on_broadcasted(&self, propagations: HashMap<TxHash<Self>, Vec<String>>) {
+		with_pool!(self, pool => pool.on_broadcasted(propagations))
+	}
+
+	fn ready_transaction(&self, hash: &TxHash<Self>) -> Option<Arc<Self::InPoolTransaction>> {
+		with_pool!(self, pool => pool.ready_transaction(hash))
+	}
+
+	fn ready_at(&self, at: NumberFor<Self::Block>) -> PolledIterator<FullChainApi<Client, Block>> {
+		with_pool!(self, pool => pool.ready_at(at))
+	}
+
+	fn ready_at_with_ordering(
+		&self,
+		at: NumberFor<Self::Block>,
+		ordering: Arc<dyn TransactionOrdering>,
+	) -> PolledIterator<FullChainApi<Client, Block>> {
+		with_pool!(self, pool => pool.ready_at_with_ordering(at, ordering))
+	}
+
+	fn ready(&self) -> ReadyIteratorFor<FullChainApi<Client, Block>> {
+		with_pool!(self, pool => pool.ready())
+	}
+
+	fn futures(&self) -> Vec<Self::InPoolTransaction> {
+		with_pool!(self, pool => pool.futures())
+	}
+}
+
+#[async_trait]
+impl<Block, Client> MaintainedTransactionPool for FullTransactionPool<Block, Client>
+where
+	Block: BlockT,
+	FullChainApi<Client, Block>: graph::ChainApi<Block = Block> + 'static,
+{
+	async fn maintain(&self, event: ChainEvent<Self::Block>) {
+		with_pool!(self, pool => pool.maintain(event).await)
+	}
+}
+
+impl<Block, Client> LocalTransactionPool for FullTransactionPool<Block, Client>
+where
+	Block: BlockT,
+	Client: sp_api::ProvideRuntimeApi<Block>
+		+ sc_client_api::BlockBackend<Block>
+		+ sc_client_api::blockchain::HeaderBackend<Block>
+		+ sp_runtime::traits::BlockIdTo<Block>
+		+ sp_blockchain::HeaderMetadata<Block, Error = sp_blockchain::Error>,
+	Client: Send + Sync + 'static,
+	Client::Api: sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
+{
+	type Block = Block;
+	type Hash = graph::ExtrinsicHash<FullChainApi<Client, Block>>;
+	type Error = <FullChainApi<Client, Block> as graph::ChainApi>::Error;
+
+	fn submit_local(
+		&self,
+		at: Block::Hash,
+		xt: LocalTransactionFor<Self>,
+	) -> Result<Self::Hash, Self::Error> {
+		with_pool!(self, pool => pool.submit_local(at, xt))
+	}
+}
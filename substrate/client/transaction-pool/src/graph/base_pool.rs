@@ -24,7 +24,11 @@ use std::{cmp::Ordering, collections::HashSet, fmt, hash, sync::Arc};
 
 use crate::LOG_TARGET;
 use log::{debug, trace, warn};
-use sc_transaction_pool_api::{error, InPoolTransaction, PoolStatus};
+use sc_transaction_pool_api::{
+	error,
+	ordering::{TransactionCost, TransactionOrdering},
+	InPoolTransaction, PoolStatus,
+};
 use serde::Serialize;
 use sp_core::hexdisplay::HexDisplay;
 use sp_runtime::{
@@ -104,6 +108,8 @@ pub struct Transaction<Hash, Extrinsic> {
 	pub propagate: bool,
 	/// Source of that transaction.
 	pub source: Source,
+	/// Fee and weight of the transaction, if reported by the chain.
+	pub cost: Option<TransactionCost>,
 }
 
 impl<Hash, Extrinsic> AsRef<Extrinsic> for Transaction<Hash, Extrinsic> {
@@ -162,6 +168,7 @@ impl<Hash: Clone, Extrinsic: Clone> Transaction<Hash, Extrinsic> {
 			requires: self.requires.clone(),
 			provides: self.provides.clone(),
 			propagate: self.propagate,
+			cost: self.cost,
 		}
 	}
 }
@@ -359,6 +366,14 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		self.ready.get()
 	}
 
+	/// Returns an iterator over ready transactions in the pool, ordered by the given policy.
+	pub fn ready_with_ordering(
+		&self,
+		ordering: Arc<dyn TransactionOrdering>,
+	) -> BestIterator<Hash, Ex> {
+		self.ready.get_with_ordering(ordering)
+	}
+
 	/// Returns an iterator over future transactions in the pool.
 	pub fn futures(&self) -> impl Iterator<Item = &Transaction<Hash, Ex>> {
 		self.future.all()
@@ -549,6 +564,7 @@ mod tests {
 		provides: vec![],
 		propagate: true,
 		source: Source::External,
+		cost: None,
 	};
 
 	#[test]
@@ -41,4 +41,5 @@ pub use self::{
 	base_pool::Transaction,
 	pool::{BlockHash, ChainApi, ExtrinsicFor, ExtrinsicHash, NumberFor, Options, Pool},
 };
+pub(crate) use validated_pool::ValidatedPool;
 pub use validated_pool::{IsValidator, ValidatedTransaction};
@@ -18,9 +18,12 @@
 
 use std::{collections::HashMap, sync::Arc, time::Duration};
 
-use crate::LOG_TARGET;
+use crate::{JournalOptions, TransactionPoolType, LOG_TARGET};
 use futures::{channel::mpsc::Receiver, Future};
-use sc_transaction_pool_api::error;
+use sc_transaction_pool_api::{
+	error,
+	ordering::{PriorityOrdering, TransactionCost, TransactionOrdering},
+};
 use sp_blockchain::TreeRoute;
 use sp_runtime::{
 	generic::BlockId,
@@ -106,6 +109,18 @@ pub trait ChainApi: Send + Sync {
 		from: <Self::Block as BlockT>::Hash,
 		to: <Self::Block as BlockT>::Hash,
 	) -> Result<TreeRoute<Self::Block>, Self::Error>;
+
+	/// Returns the fee and weight of the extrinsic at the given block, if known.
+	///
+	/// They are handed to the [`TransactionOrdering`] policy, e.g. to order by fee per weight.
+	/// The pool has no notion of fees, so by default nothing is reported.
+	fn transaction_cost(
+		&self,
+		_at: <Self::Block as BlockT>::Hash,
+		_uxt: &ExtrinsicFor<Self>,
+	) -> Option<TransactionCost> {
+		None
+	}
 }
 
 /// Pool configuration options.
@@ -119,6 +134,12 @@ pub struct Options {
 	pub reject_future_transactions: bool,
 	/// How long the extrinsic is banned for.
 	pub ban_time: Duration,
+	/// Order in which ready transactions are returned for block production.
+	pub ordering: Arc<dyn TransactionOrdering>,
+	/// Journal the transactions are persisted to across restarts, if any.
+	pub journal: Option<JournalOptions>,
+	/// Type of pool a full node runs, see [`FullTransactionPool`](crate::FullTransactionPool).
+	pub pool_type: TransactionPoolType,
 }
 
 impl Default for Options {
@@ -128,6 +149,9 @@ impl Default for Options {
 			future: base::Limit { count: 512, total_bytes: 1 * 1024 * 1024 },
 			reject_future_transactions: false,
 			ban_time: Duration::from_secs(60 * 30),
+			ordering: Arc::new(PriorityOrdering),
+			journal: None,
+			pool_type: TransactionPoolType::SingleState,
 		}
 	}
 }
@@ -423,6 +447,7 @@ impl<B: ChainApi> Pool<B> {
 				if validity.provides.is_empty() {
 					ValidatedTransaction::Invalid(hash, error::Error::NoTagsProvided.into())
 				} else {
+					let cost = self.validated_pool.api().transaction_cost(block_hash, &xt);
 					ValidatedTransaction::valid_at(
 						block_number.saturated_into::<u64>(),
 						hash,
@@ -430,6 +455,7 @@ impl<B: ChainApi> Pool<B> {
 						xt,
 						bytes,
 						validity,
+						cost,
 					)
 				},
 			Err(TransactionValidityError::Invalid(e)) =>
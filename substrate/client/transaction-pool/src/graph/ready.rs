@@ -25,7 +25,10 @@ use std::{
 
 use crate::LOG_TARGET;
 use log::{debug, trace};
-use sc_transaction_pool_api::error;
+use sc_transaction_pool_api::{
+	error,
+	ordering::{PriorityOrdering, ReadyTransactionInfo, TransactionOrdering},
+};
 use serde::Serialize;
 use sp_runtime::{traits::Member, transaction_validity::TransactionTag as Tag};
 
@@ -75,6 +78,47 @@ impl<Hash, Ex> PartialEq for TransactionRef<Hash, Ex> {
 }
 impl<Hash, Ex> Eq for TransactionRef<Hash, Ex> {}
 
+/// A transaction reference queued under the key assigned by a [`TransactionOrdering`].
+///
+/// Ties are broken by insertion order, transactions that are longer in the queue go first.
+struct OrderedRef<Hash, Ex> {
+	key: u128,
+	transaction: TransactionRef<Hash, Ex>,
+}
+
+impl<Hash, Ex> Ord for OrderedRef<Hash, Ex> {
+	fn cmp(&self, other: &Self) -> cmp::Ordering {
+		self.key
+			.cmp(&other.key)
+			.then_with(|| other.transaction.insertion_id.cmp(&self.transaction.insertion_id))
+	}
+}
+
+impl<Hash, Ex> PartialOrd for OrderedRef<Hash, Ex> {
+	fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<Hash, Ex> PartialEq for OrderedRef<Hash, Ex> {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == cmp::Ordering::Equal
+	}
+}
+impl<Hash, Ex> Eq for OrderedRef<Hash, Ex> {}
+
+fn ordering_info<Hash, Ex>(tx: &Transaction<Hash, Ex>) -> ReadyTransactionInfo<'_> {
+	ReadyTransactionInfo {
+		priority: tx.priority,
+		valid_till: tx.valid_till,
+		requires: &tx.requires,
+		provides: &tx.provides,
+		bytes: tx.bytes,
+		fee: tx.cost.map(|cost| cost.fee),
+		weight: tx.cost.map(|cost| cost.weight),
+	}
+}
+
 #[derive(Debug)]
 pub struct ReadyTx<Hash, Ex> {
 	/// A reference to a transaction
@@ -162,12 +206,29 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 	/// In such case the entire subgraph of transactions that depend on the reported one will be
 	/// skipped.
 	pub fn get(&self) -> BestIterator<Hash, Ex> {
-		BestIterator {
+		self.get_with_ordering(Arc::new(PriorityOrdering))
+	}
+
+	/// Returns an iterator of ready transactions, ordered by the given policy.
+	///
+	/// Like [`Self::get`], dependencies are always returned first, the policy decides
+	/// on the order of transactions that have all their requirements satisfied.
+	pub fn get_with_ordering(
+		&self,
+		ordering: Arc<dyn TransactionOrdering>,
+	) -> BestIterator<Hash, Ex> {
+		let mut iterator = BestIterator {
 			all: self.ready.clone_map(),
-			best: self.best.clone(),
+			best: Default::default(),
 			awaiting: Default::default(),
 			invalid: Default::default(),
+			ordering,
+			served: Default::default(),
+		};
+		for tx_ref in &self.best {
+			iterator.insert_best(tx_ref.clone());
 		}
+		iterator
 	}
 
 	/// Imports transactions to the pool of ready transactions.
@@ -483,21 +544,43 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 	}
 }
 
-/// Iterator of ready transactions ordered by priority.
+/// Iterator of ready transactions ordered by a [`TransactionOrdering`] policy.
 pub struct BestIterator<Hash, Ex> {
 	all: HashMap<Hash, ReadyTx<Hash, Ex>>,
 	awaiting: HashMap<Hash, (usize, TransactionRef<Hash, Ex>)>,
-	best: BTreeSet<TransactionRef<Hash, Ex>>,
+	best: BTreeSet<OrderedRef<Hash, Ex>>,
 	invalid: HashSet<Hash>,
+	ordering: Arc<dyn TransactionOrdering>,
+	/// Number of transactions returned so far, per sender.
+	served: HashMap<Vec<u8>, usize>,
 }
 
 impl<Hash: hash::Hash + Member, Ex> BestIterator<Hash, Ex> {
+	/// The key the ordering policy assigns to the transaction, given how often its sender was
+	/// served so far.
+	fn key(&self, tx_ref: &TransactionRef<Hash, Ex>) -> u128 {
+		let info = ordering_info(&tx_ref.transaction);
+		let served = self
+			.ordering
+			.sender(&info)
+			.and_then(|sender| self.served.get(&sender).copied())
+			.unwrap_or(0);
+		self.ordering.key(&info, served)
+	}
+
+	/// Insert a transaction with all requirements satisfied to the best set,
+	/// under the key assigned by the ordering policy.
+	fn insert_best(&mut self, tx_ref: TransactionRef<Hash, Ex>) {
+		let key = self.key(&tx_ref);
+		self.best.insert(OrderedRef { key, transaction: tx_ref });
+	}
+
 	/// Depending on number of satisfied requirements insert given ref
 	/// either to awaiting set or to best set.
 	fn best_or_awaiting(&mut self, satisfied: usize, tx_ref: TransactionRef<Hash, Ex>) {
 		if satisfied >= tx_ref.transaction.requires.len() {
 			// If we have satisfied all deps insert to best
-			self.best.insert(tx_ref);
+			self.insert_best(tx_ref);
 		} else {
 			// otherwise we're still awaiting for some deps
 			self.awaiting.insert(tx_ref.transaction.hash.clone(), (satisfied, tx_ref));
@@ -538,8 +621,16 @@ impl<Hash: hash::Hash + Member, Ex> Iterator for BestIterator<Hash, Ex> {
 
 	fn next(&mut self) -> Option<Self::Item> {
 		loop {
-			let best = self.best.iter().next_back()?.clone();
-			let best = self.best.take(&best)?;
+			let OrderedRef { key, transaction: best } = self.best.pop_last()?;
+
+			// The sender may have been served since the transaction was queued. Keys only
+			// decrease with that, so re-queueing under the current key is enough to keep the order.
+			let current_key = self.key(&best);
+			if current_key != key {
+				self.best.insert(OrderedRef { key: current_key, transaction: best });
+				continue
+			}
+
 			let hash = &best.transaction.hash;
 
 			// Check if the transaction was marked invalid.
@@ -557,6 +648,11 @@ impl<Hash: hash::Hash + Member, Ex> Iterator for BestIterator<Hash, Ex> {
 				None => continue,
 			};
 
+			// Account for the sender before unlocking its next transactions.
+			if let Some(sender) = self.ordering.sender(&ordering_info(&best.transaction)) {
+				*self.served.entry(sender).or_default() += 1;
+			}
+
 			// Insert transactions that just got unlocked.
 			for hash in &ready.unlocks {
 				// first check local awaiting transactions
@@ -589,6 +685,9 @@ fn remove_item<T: PartialEq>(vec: &mut Vec<T>, item: &T) {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use sc_transaction_pool_api::ordering::{
+		FeePerWeightOrdering, FifoOrdering, RoundRobinOrdering, TransactionCost,
+	};
 	use sp_runtime::transaction_validity::TransactionSource as Source;
 
 	fn tx(id: u8) -> Transaction<u64, Vec<u8>> {
@@ -602,6 +701,7 @@ mod tests {
 			provides: vec![vec![3], vec![4]],
 			propagate: true,
 			source: Source::External,
+			cost: None,
 		}
 	}
 
@@ -712,6 +812,7 @@ mod tests {
 			provides: vec![],
 			propagate: true,
 			source: Source::External,
+			cost: None,
 		};
 
 		// when
@@ -790,4 +891,109 @@ mod tests {
 		assert_eq!(it.next().as_ref().map(data), Some(7));
 		assert_eq!(it.next().as_ref().map(data), None);
 	}
+
+	#[test]
+	fn should_return_transactions_in_insertion_order_with_fifo_ordering() {
+		// given
+		let mut ready = ReadyTransactions::default();
+		for (id, priority) in [(1, 1), (2, 10), (3, 5)] {
+			let mut tx = tx(id);
+			tx.requires.clear();
+			tx.provides = vec![vec![id]];
+			tx.priority = priority;
+			import(&mut ready, tx).unwrap();
+		}
+
+		// when
+		let by_priority = ready.get().map(|tx| tx.data[0]).collect::<Vec<_>>();
+		let fifo = ready
+			.get_with_ordering(Arc::new(FifoOrdering))
+			.map(|tx| tx.data[0])
+			.collect::<Vec<_>>();
+
+		// then
+		assert_eq!(by_priority, vec![2, 3, 1]);
+		assert_eq!(fifo, vec![1, 2, 3]);
+	}
+
+	#[test]
+	fn should_alternate_senders_with_round_robin_ordering() {
+		// given
+		let mut ready = ReadyTransactions::default();
+		// sender 100 sends three transactions with high priority, sender 200 two with low priority.
+		let mut id = 0;
+		for (sender, priority, count) in [(100u8, 10, 3), (200, 1, 2)] {
+			for nonce in 0..count {
+				id += 1;
+				let mut tx = tx(id);
+				tx.priority = priority;
+				tx.provides = vec![vec![sender, nonce]];
+				tx.requires = if nonce == 0 { vec![] } else { vec![vec![sender, nonce - 1]] };
+				import(&mut ready, tx).unwrap();
+			}
+		}
+
+		// when
+		let by_priority = ready.get().map(|tx| tx.data[0]).collect::<Vec<_>>();
+		let round_robin = ready
+			.get_with_ordering(Arc::new(RoundRobinOrdering::new(1)))
+			.map(|tx| tx.data[0])
+			.collect::<Vec<_>>();
+
+		// then
+		assert_eq!(by_priority, vec![1, 2, 3, 4, 5]);
+		assert_eq!(round_robin, vec![1, 4, 2, 5, 3]);
+	}
+
+	#[test]
+	fn should_alternate_senders_of_independent_transactions_with_round_robin_ordering() {
+		// given
+		let mut ready = ReadyTransactions::default();
+		// sender 100 sends two transactions that don't depend on each other.
+		for (id, sender, priority) in [(1, 100u8, 10), (2, 100, 9), (3, 200, 1)] {
+			let mut tx = tx(id);
+			tx.priority = priority;
+			tx.provides = vec![vec![sender, id]];
+			tx.requires.clear();
+			import(&mut ready, tx).unwrap();
+		}
+
+		// when
+		let round_robin = ready
+			.get_with_ordering(Arc::new(RoundRobinOrdering::new(1)))
+			.map(|tx| tx.data[0])
+			.collect::<Vec<_>>();
+
+		// then
+		assert_eq!(round_robin, vec![1, 3, 2]);
+	}
+
+	#[test]
+	fn should_return_transactions_by_fee_per_weight_with_fee_per_weight_ordering() {
+		// given
+		let mut ready = ReadyTransactions::default();
+		for (id, priority, cost) in [
+			(1, 10, None),
+			(2, 1, Some(TransactionCost { fee: 100, weight: 10 })),
+			(3, 1, Some(TransactionCost { fee: 100, weight: 50 })),
+			(4, 5, Some(TransactionCost { fee: 1_000, weight: 20 })),
+			(5, 20, None),
+		] {
+			let mut tx = tx(id);
+			tx.requires.clear();
+			tx.provides = vec![vec![id]];
+			tx.priority = priority;
+			tx.cost = cost;
+			import(&mut ready, tx).unwrap();
+		}
+
+		// when
+		let fee_per_weight = ready
+			.get_with_ordering(Arc::new(FeePerWeightOrdering))
+			.map(|tx| tx.data[0])
+			.collect::<Vec<_>>();
+
+		// then
+		assert_eq!(fee_per_weight, vec![4, 2, 3, 5, 1]);
+	}
 }
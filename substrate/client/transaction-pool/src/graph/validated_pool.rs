@@ -25,7 +25,11 @@ use std::{
 use crate::LOG_TARGET;
 use futures::channel::mpsc::{channel, Sender};
 use parking_lot::{Mutex, RwLock};
-use sc_transaction_pool_api::{error, PoolStatus, ReadyTransactions};
+use sc_transaction_pool_api::{
+	error,
+	ordering::{TransactionCost, TransactionOrdering},
+	PoolStatus, ReadyTransactions,
+};
 use serde::Serialize;
 use sp_runtime::{
 	generic::BlockId,
@@ -66,12 +70,14 @@ impl<Hash, Ex, Error> ValidatedTransaction<Hash, Ex, Error> {
 		data: Ex,
 		bytes: usize,
 		validity: ValidTransaction,
+		cost: Option<TransactionCost>,
 	) -> Self {
 		Self::Valid(base::Transaction {
 			data,
 			bytes,
 			hash,
 			source,
+			cost,
 			priority: validity.priority,
 			requires: validity.requires,
 			provides: validity.provides,
@@ -100,6 +106,13 @@ impl From<Box<dyn Fn() -> bool + Send + Sync>> for IsValidator {
 	}
 }
 
+impl IsValidator {
+	/// Returns true if the local node is currently a validator.
+	pub(crate) fn get(&self) -> bool {
+		(self.0)()
+	}
+}
+
 /// Pool that deals with validated transactions.
 pub struct ValidatedPool<B: ChainApi> {
 	api: Arc<B>,
@@ -615,9 +628,17 @@ impl<B: ChainApi> ValidatedPool<B> {
 		invalid
 	}
 
-	/// Get an iterator for ready transactions ordered by priority
+	/// Get an iterator for ready transactions ordered by the configured policy.
 	pub fn ready(&self) -> impl ReadyTransactions<Item = TransactionFor<B>> + Send {
-		self.pool.read().ready()
+		self.ready_with_ordering(self.options.ordering.clone())
+	}
+
+	/// Get an iterator for ready transactions ordered by the given policy.
+	pub fn ready_with_ordering(
+		&self,
+		ordering: Arc<dyn TransactionOrdering>,
+	) -> impl ReadyTransactions<Item = TransactionFor<B>> + Send {
+		self.pool.read().ready_with_ordering(ordering)
 	}
 
 	/// Returns a Vec of hashes and extrinsics in the future pool.
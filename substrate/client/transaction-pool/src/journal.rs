@@ -0,0 +1,380 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! On-disk journal of the transactions in the pool.
+//!
+//! The journal is a snapshot of the ready and future transactions, written by a blocking task
+//! whenever a block is finalized and right away when the pool is dropped. On startup the journaled
+//! transactions are submitted again, so they are revalidated against the current best block like
+//! any other transaction.
+
+use crate::{
+	graph::{ChainApi, ExtrinsicFor, ExtrinsicHash, ValidatedPool},
+	LOG_TARGET,
+};
+use codec::{Decode, Encode};
+use futures::{FutureExt, StreamExt};
+use parking_lot::Mutex;
+use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedSender};
+use sp_core::traits::SpawnEssentialNamed;
+use sp_runtime::transaction_validity::TransactionSource;
+use std::{
+	collections::HashMap,
+	fs, io,
+	path::PathBuf,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Version of the journal file format.
+const JOURNAL_VERSION: u8 = 1;
+
+/// Journal configuration.
+#[derive(Debug, Clone)]
+pub struct JournalOptions {
+	/// File the journal is written to.
+	pub path: PathBuf,
+	/// Maximum encoded size of the journaled transactions.
+	///
+	/// Ready transactions are journaled first, in the order they would be included in a block.
+	pub max_bytes: usize,
+	/// Journaled transactions older than this are not replayed.
+	pub max_age: Duration,
+}
+
+impl JournalOptions {
+	/// Journal at `path` with the default limits.
+	pub fn new(path: PathBuf) -> Self {
+		Self { path, max_bytes: 10 * 1024 * 1024, max_age: Duration::from_secs(3 * 60 * 60) }
+	}
+}
+
+/// A journaled transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub(crate) struct JournalEntry<Extrinsic> {
+	/// Source the transaction was originally submitted from.
+	pub source: TransactionSource,
+	/// Seconds since the unix epoch at which the transaction entered the pool.
+	pub inserted_at: u64,
+	/// The transaction.
+	pub extrinsic: Extrinsic,
+}
+
+/// Content of the journal file.
+#[derive(Encode, Decode)]
+struct JournalFile<Extrinsic> {
+	version: u8,
+	entries: Vec<JournalEntry<Extrinsic>>,
+}
+
+/// Encoded journal content, numbered in the order it was taken.
+struct Snapshot {
+	number: u64,
+	data: Vec<u8>,
+}
+
+/// Writes snapshots to the journal file.
+struct Writer {
+	path: PathBuf,
+	/// Number of the last snapshot written, older snapshots are not written anymore.
+	last_written: Mutex<u64>,
+}
+
+impl Writer {
+	fn write(&self, snapshot: Snapshot) {
+		let mut last_written = self.last_written.lock();
+		if snapshot.number <= *last_written {
+			return
+		}
+		*last_written = snapshot.number;
+		if let Err(e) = write(&self.path, &snapshot.data) {
+			log::warn!(
+				target: LOG_TARGET,
+				"Failed to write the transaction pool journal to {:?}: {e}",
+				self.path,
+			);
+		}
+	}
+}
+
+/// Keeps the journal file up to date with the pool.
+pub(crate) struct Journal<B: ChainApi> {
+	max_bytes: usize,
+	/// When the transactions currently in the pool were first seen.
+	inserted_at: Mutex<HashMap<ExtrinsicHash<B>, u64>>,
+	/// Number of the last snapshot taken.
+	snapshots: AtomicU64,
+	writer: Arc<Writer>,
+	/// Snapshots to be written by the background task, see [`Self::spawn_writer`].
+	to_writer: Option<TracingUnboundedSender<Snapshot>>,
+}
+
+impl<B: ChainApi> Journal<B> {
+	/// Opens the journal, returning the transactions that should be submitted again.
+	///
+	/// A missing or unreadable journal is treated as empty.
+	pub fn open(options: JournalOptions, api: &B) -> (Self, Vec<JournalEntry<ExtrinsicFor<B>>>) {
+		let entries = match read(&options.path) {
+			Ok(entries) => entries,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+			Err(e) => {
+				log::warn!(
+					target: LOG_TARGET,
+					"Ignoring transaction pool journal at {:?}: {e}",
+					options.path,
+				);
+				Vec::new()
+			},
+		};
+
+		let now = unix_now();
+		let max_age = options.max_age.as_secs();
+		let entries = entries
+			.into_iter()
+			.filter(|entry| now.saturating_sub(entry.inserted_at) <= max_age)
+			.collect::<Vec<JournalEntry<ExtrinsicFor<B>>>>();
+		let inserted_at = entries
+			.iter()
+			.map(|entry| (api.hash_and_length(&entry.extrinsic).0, entry.inserted_at))
+			.collect();
+
+		log::debug!(
+			target: LOG_TARGET,
+			"Loaded {} transactions from the journal at {:?}",
+			entries.len(),
+			options.path,
+		);
+
+		let journal = Self {
+			max_bytes: options.max_bytes,
+			inserted_at: Mutex::new(inserted_at),
+			snapshots: AtomicU64::new(0),
+			writer: Arc::new(Writer { path: options.path, last_written: Mutex::new(0) }),
+			to_writer: None,
+		};
+		(journal, entries)
+	}
+
+	/// Spawns the blocking task that [`Self::write_in_background`] hands the snapshots to.
+	pub fn spawn_writer(&mut self, spawner: &impl SpawnEssentialNamed) {
+		let (to_writer, mut from_journal) = tracing_unbounded("mpsc_txpool_journal", 100);
+		let writer = self.writer.clone();
+		spawner.spawn_essential_blocking(
+			"txpool-journal",
+			Some("transaction-pool"),
+			async move {
+				while let Some(mut snapshot) = from_journal.next().await {
+					// Only the latest of the pending snapshots is worth writing.
+					while let Ok(next) = from_journal.try_recv() {
+						snapshot = next;
+					}
+					writer.write(snapshot);
+				}
+			}
+			.boxed(),
+		);
+		self.to_writer = Some(to_writer);
+	}
+
+	/// Records that the given transactions entered the pool now, unless they are known already.
+	pub fn inserted(&self, hashes: impl IntoIterator<Item = ExtrinsicHash<B>>) {
+		let now = unix_now();
+		let mut inserted_at = self.inserted_at.lock();
+		for hash in hashes {
+			inserted_at.entry(hash).or_insert(now);
+		}
+	}
+
+	/// Writes the transactions currently in `pool` to the journal.
+	pub fn write(&self, pool: &ValidatedPool<B>) {
+		self.writer.write(self.snapshot(pool));
+	}
+
+	/// Writes the transactions currently in `pool` to the journal from the background task.
+	///
+	/// Without a background task the journal is written right away.
+	pub fn write_in_background(&self, pool: &ValidatedPool<B>) {
+		let snapshot = self.snapshot(pool);
+		match &self.to_writer {
+			Some(to_writer) =>
+				if let Err(e) = to_writer.unbounded_send(snapshot) {
+					self.writer.write(e.into_inner());
+				},
+			None => self.writer.write(snapshot),
+		}
+	}
+
+	/// Takes a snapshot of the ready and future transactions of `pool`.
+	fn snapshot(&self, pool: &ValidatedPool<B>) -> Snapshot {
+		let now = unix_now();
+		let mut inserted_at = self.inserted_at.lock();
+		let mut in_pool = HashMap::new();
+		let mut total_bytes = 0;
+		let mut entries = Vec::new();
+
+		let ready = pool.ready().map(|tx| (tx.hash, tx.source, tx.bytes, tx.data.clone()));
+		let futures = pool
+			.pool
+			.read()
+			.futures()
+			.map(|tx| (tx.hash, tx.source, tx.bytes, tx.data.clone()))
+			.collect::<Vec<_>>();
+		for (hash, source, bytes, extrinsic) in ready.chain(futures) {
+			let time = *inserted_at.get(&hash).unwrap_or(&now);
+			in_pool.insert(hash, time);
+			if total_bytes + bytes > self.max_bytes {
+				continue
+			}
+			total_bytes += bytes;
+			entries.push(JournalEntry { source, inserted_at: time, extrinsic });
+		}
+		*inserted_at = in_pool;
+
+		Snapshot {
+			number: self.snapshots.fetch_add(1, Ordering::Relaxed) + 1,
+			data: JournalFile { version: JOURNAL_VERSION, entries }.encode(),
+		}
+	}
+}
+
+fn unix_now() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or_default()
+}
+
+fn read<Extrinsic: Decode>(path: &PathBuf) -> io::Result<Vec<JournalEntry<Extrinsic>>> {
+	let data = fs::read(path)?;
+	let file = JournalFile::<Extrinsic>::decode(&mut &data[..])
+		.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+	if file.version != JOURNAL_VERSION {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidData,
+			format!("unsupported journal version {}", file.version),
+		))
+	}
+	Ok(file.entries)
+}
+
+/// Replaces the journal at `path`, so a crash while writing doesn't leave a partial journal.
+fn write(path: &PathBuf, data: &[u8]) -> io::Result<()> {
+	if let Some(parent) = path.parent() {
+		fs::create_dir_all(parent)?;
+	}
+	let tmp = path.with_extension("tmp");
+	fs::write(&tmp, data)?;
+	fs::rename(tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{pool, uxt};
+	use futures::executor::block_on;
+	use substrate_test_runtime::{AccountId, Transfer, H256};
+	use substrate_test_runtime_client::AccountKeyring::Alice;
+
+	fn transfer(nonce: u64) -> substrate_test_runtime::Extrinsic {
+		uxt(Transfer {
+			from: Alice.into(),
+			to: AccountId::from_h256(H256::from_low_u64_be(2)),
+			amount: 5,
+			nonce,
+		})
+	}
+
+	#[test]
+	fn journal_roundtrip_keeps_source_and_order() {
+		let dir = tempfile::tempdir().unwrap();
+		let options = JournalOptions::new(dir.path().join("journal"));
+		let (pool, api) = pool();
+		let at = api.expect_hash_from_number(0);
+		block_on(pool.submit_one(at, TransactionSource::Local, transfer(0))).unwrap();
+		block_on(pool.submit_one(at, TransactionSource::External, transfer(1))).unwrap();
+		block_on(pool.submit_one(at, TransactionSource::External, transfer(3))).unwrap();
+
+		let (journal, entries) = Journal::open(options.clone(), &*api);
+		assert!(entries.is_empty());
+		journal.write(pool.validated_pool());
+
+		let (_, entries) = Journal::open(options, &*api);
+		assert_eq!(
+			entries.iter().map(|e| (e.source, e.extrinsic.clone())).collect::<Vec<_>>(),
+			vec![
+				(TransactionSource::Local, transfer(0)),
+				(TransactionSource::External, transfer(1)),
+				(TransactionSource::External, transfer(3)),
+			],
+		);
+	}
+
+	#[test]
+	fn journal_keeps_the_time_transactions_were_inserted_at() {
+		let dir = tempfile::tempdir().unwrap();
+		let mut options = JournalOptions::new(dir.path().join("journal"));
+		options.max_age = Duration::from_secs(u64::MAX);
+		let (pool, api) = pool();
+		let at = api.expect_hash_from_number(0);
+		let hash = block_on(pool.submit_one(at, TransactionSource::External, transfer(0))).unwrap();
+
+		let (journal, _) = Journal::open(options.clone(), &*api);
+		journal.inserted_at.lock().insert(hash, 1);
+		journal.inserted([hash]);
+		journal.write(pool.validated_pool());
+
+		let (_, entries) = Journal::open(options, &*api);
+		assert_eq!(entries.iter().map(|e| e.inserted_at).collect::<Vec<_>>(), vec![1]);
+	}
+
+	#[test]
+	fn journal_respects_limits() {
+		let dir = tempfile::tempdir().unwrap();
+		let bytes = transfer(0).encode().len();
+		let mut options = JournalOptions::new(dir.path().join("journal"));
+		options.max_bytes = 2 * bytes;
+		let (pool, api) = pool();
+		let at = api.expect_hash_from_number(0);
+		for nonce in 0..3 {
+			block_on(pool.submit_one(at, TransactionSource::External, transfer(nonce))).unwrap();
+		}
+
+		let (journal, _) = Journal::open(options.clone(), &*api);
+		journal.write(pool.validated_pool());
+		let (_, entries) = Journal::open(options.clone(), &*api);
+		assert_eq!(
+			entries.into_iter().map(|e| e.extrinsic).collect::<Vec<_>>(),
+			vec![transfer(0), transfer(1)],
+		);
+
+		let old = JournalEntry {
+			source: TransactionSource::External,
+			inserted_at: unix_now() - options.max_age.as_secs() - 1,
+			extrinsic: transfer(0),
+		};
+		write(
+			&options.path,
+			&JournalFile { version: JOURNAL_VERSION, entries: vec![old] }.encode(),
+		)
+		.unwrap();
+		let (_, entries) = Journal::open(options, &*api);
+		assert!(entries.is_empty());
+	}
+}
@@ -25,7 +25,10 @@
 mod api;
 mod enactment_state;
 pub mod error;
+mod fork_aware_txpool;
+mod full_pool;
 mod graph;
+mod journal;
 mod metrics;
 mod revalidation;
 #[cfg(test)]
@@ -34,6 +37,8 @@ mod tests;
 pub use crate::api::FullChainApi;
 use async_trait::async_trait;
 use enactment_state::{EnactmentAction, EnactmentState};
+pub use fork_aware_txpool::{ForkAwareTxPool, FullForkAwarePool};
+pub use full_pool::{FullTransactionPool, TransactionPoolType};
 use futures::{
 	channel::oneshot,
 	future::{self, ready},
@@ -42,6 +47,8 @@ use futures::{
 pub use graph::{
 	base_pool::Limit as PoolLimit, ChainApi, Options, Pool, Transaction, ValidatedTransaction,
 };
+pub use journal::JournalOptions;
+use journal::{Journal, JournalEntry};
 use parking_lot::Mutex;
 use std::{
 	collections::{HashMap, HashSet},
@@ -51,8 +58,10 @@ use std::{
 
 use graph::{ExtrinsicHash, IsValidator};
 use sc_transaction_pool_api::{
-	error::Error as TxPoolError, ChainEvent, ImportNotificationStream, MaintainedTransactionPool,
-	PoolFuture, PoolStatus, ReadyTransactions, TransactionFor, TransactionPool, TransactionSource,
+	error::Error as TxPoolError,
+	ordering::{TransactionCostProvider, TransactionOrdering},
+	ChainEvent, ImportNotificationStream, MaintainedTransactionPool, PoolFuture, PoolStatus,
+	ReadyTransactions, TransactionFor, TransactionPool, TransactionSource,
 	TransactionStatusStreamFor, TxHash,
 };
 use sp_core::traits::SpawnEssentialNamed;
@@ -93,11 +102,14 @@ where
 	ready_poll: Arc<Mutex<ReadyPoll<ReadyIteratorFor<PoolApi>, Block>>>,
 	metrics: PrometheusMetrics,
 	enactment_state: Arc<Mutex<EnactmentState<Block>>>,
+	journal: Option<Arc<Journal<PoolApi>>>,
+	/// Journaled transactions that are submitted again on the next maintenance.
+	journal_replay: Mutex<Vec<JournalEntry<graph::ExtrinsicFor<PoolApi>>>>,
 }
 
 struct ReadyPoll<T, Block: BlockT> {
 	updated_at: NumberFor<Block>,
-	pollers: Vec<(NumberFor<Block>, oneshot::Sender<T>)>,
+	pollers: Vec<(NumberFor<Block>, Option<Arc<dyn TransactionOrdering>>, oneshot::Sender<T>)>,
 }
 
 impl<T, Block: BlockT> Default for ReadyPoll<T, Block> {
@@ -111,24 +123,32 @@ impl<T, Block: BlockT> ReadyPoll<T, Block> {
 		Self { updated_at: best_block_number, pollers: Default::default() }
 	}
 
-	fn trigger(&mut self, number: NumberFor<Block>, iterator_factory: impl Fn() -> T) {
+	fn trigger(
+		&mut self,
+		number: NumberFor<Block>,
+		iterator_factory: impl Fn(Option<Arc<dyn TransactionOrdering>>) -> T,
+	) {
 		self.updated_at = number;
 
 		let mut idx = 0;
 		while idx < self.pollers.len() {
 			if self.pollers[idx].0 <= number {
-				let poller_sender = self.pollers.swap_remove(idx);
+				let (_, ordering, poller_sender) = self.pollers.swap_remove(idx);
 				log::debug!(target: LOG_TARGET, "Sending ready signal at block {}", number);
-				let _ = poller_sender.1.send(iterator_factory());
+				let _ = poller_sender.send(iterator_factory(ordering));
 			} else {
 				idx += 1;
 			}
 		}
 	}
 
-	fn add(&mut self, number: NumberFor<Block>) -> oneshot::Receiver<T> {
+	fn add(
+		&mut self,
+		number: NumberFor<Block>,
+		ordering: Option<Arc<dyn TransactionOrdering>>,
+	) -> oneshot::Receiver<T> {
 		let (sender, receiver) = oneshot::channel();
-		self.pollers.push((number, sender));
+		self.pollers.push((number, ordering, sender));
 		receiver
 	}
 
@@ -183,6 +203,8 @@ where
 					best_block_hash,
 					finalized_hash,
 				))),
+				journal: None,
+				journal_replay: Default::default(),
 			},
 			background_task,
 		)
@@ -201,6 +223,14 @@ where
 		best_block_hash: Block::Hash,
 		finalized_hash: Block::Hash,
 	) -> Self {
+		let (journal, journal_replay) = match options.journal.clone() {
+			Some(journal_options) => {
+				let (mut journal, replay) = Journal::open(journal_options, &pool_api);
+				journal.spawn_writer(&spawner);
+				(Some(Arc::new(journal)), replay)
+			},
+			None => (None, Vec::new()),
+		};
 		let pool = Arc::new(graph::Pool::new(options, is_validator, pool_api.clone()));
 		let (revalidation_queue, background_task) = match revalidation_type {
 			RevalidationType::Light =>
@@ -234,6 +264,8 @@ where
 				best_block_hash,
 				finalized_hash,
 			))),
+			journal,
+			journal_replay: Mutex::new(journal_replay),
 		}
 	}
 
@@ -265,11 +297,19 @@ where
 		xts: Vec<TransactionFor<Self>>,
 	) -> PoolFuture<Vec<Result<TxHash<Self>, Self::Error>>, Self::Error> {
 		let pool = self.pool.clone();
+		let journal = self.journal.clone();
 
 		self.metrics
 			.report(|metrics| metrics.submitted_transactions.inc_by(xts.len() as u64));
 
-		async move { pool.submit_at(at, source, xts).await }.boxed()
+		async move {
+			let results = pool.submit_at(at, source, xts).await?;
+			if let Some(journal) = journal {
+				journal.inserted(results.iter().filter_map(|result| result.as_ref().ok()).cloned());
+			}
+			Ok(results)
+		}
+		.boxed()
 	}
 
 	fn submit_one(
@@ -279,10 +319,18 @@ where
 		xt: TransactionFor<Self>,
 	) -> PoolFuture<TxHash<Self>, Self::Error> {
 		let pool = self.pool.clone();
+		let journal = self.journal.clone();
 
 		self.metrics.report(|metrics| metrics.submitted_transactions.inc());
 
-		async move { pool.submit_one(at, source, xt).await }.boxed()
+		async move {
+			let hash = pool.submit_one(at, source, xt).await?;
+			if let Some(journal) = journal {
+				journal.inserted([hash]);
+			}
+			Ok(hash)
+		}
+		.boxed()
 	}
 
 	fn submit_and_watch(
@@ -292,11 +340,15 @@ where
 		xt: TransactionFor<Self>,
 	) -> PoolFuture<Pin<Box<TransactionStatusStreamFor<Self>>>, Self::Error> {
 		let pool = self.pool.clone();
+		let journal = self.journal.clone();
 
 		self.metrics.report(|metrics| metrics.submitted_transactions.inc());
 
 		async move {
 			let watcher = pool.submit_and_watch(at, source, xt).await?;
+			if let Some(journal) = journal {
+				journal.inserted([*watcher.hash()]);
+			}
 
 			Ok(watcher.into_stream().boxed())
 		}
@@ -331,6 +383,39 @@ where
 	}
 
 	fn ready_at(&self, at: NumberFor<Self::Block>) -> PolledIterator<PoolApi> {
+		self.ready_at_impl(at, None)
+	}
+
+	fn ready_at_with_ordering(
+		&self,
+		at: NumberFor<Self::Block>,
+		ordering: Arc<dyn TransactionOrdering>,
+	) -> PolledIterator<PoolApi> {
+		self.ready_at_impl(at, Some(ordering))
+	}
+
+	fn ready(&self) -> ReadyIteratorFor<PoolApi> {
+		Box::new(self.pool.validated_pool().ready())
+	}
+
+	fn futures(&self) -> Vec<Self::InPoolTransaction> {
+		let pool = self.pool.validated_pool().pool.read();
+
+		pool.futures().cloned().collect::<Vec<_>>()
+	}
+}
+
+impl<PoolApi, Block> BasicPool<PoolApi, Block>
+where
+	Block: BlockT,
+	PoolApi: 'static + graph::ChainApi<Block = Block>,
+{
+	/// Ready transactions at `at`, ordered by `ordering` or by the configured policy.
+	fn ready_at_impl(
+		&self,
+		at: NumberFor<Block>,
+		ordering: Option<Arc<dyn TransactionOrdering>>,
+	) -> PolledIterator<PoolApi> {
 		let status = self.status();
 		// If there are no transactions in the pool, it is fine to return early.
 		//
@@ -342,13 +427,13 @@ where
 
 		if self.ready_poll.lock().updated_at() >= at {
 			log::trace!(target: LOG_TARGET, "Transaction pool already processed block  #{}", at);
-			let iterator: ReadyIteratorFor<PoolApi> = Box::new(self.pool.validated_pool().ready());
+			let iterator = ready_iterator(&self.pool, ordering);
 			return async move { iterator }.boxed()
 		}
 
 		self.ready_poll
 			.lock()
-			.add(at)
+			.add(at, ordering)
 			.map(|received| {
 				received.unwrap_or_else(|e| {
 					log::warn!("Error receiving pending set: {:?}", e);
@@ -357,15 +442,16 @@ where
 			})
 			.boxed()
 	}
+}
 
-	fn ready(&self) -> ReadyIteratorFor<PoolApi> {
-		Box::new(self.pool.validated_pool().ready())
-	}
-
-	fn futures(&self) -> Vec<Self::InPoolTransaction> {
-		let pool = self.pool.validated_pool().pool.read();
-
-		pool.futures().cloned().collect::<Vec<_>>()
+/// Ready transactions of `pool`, ordered by `ordering` or by the configured policy.
+fn ready_iterator<PoolApi: 'static + graph::ChainApi>(
+	pool: &graph::Pool<PoolApi>,
+	ordering: Option<Arc<dyn TransactionOrdering>>,
+) -> ReadyIteratorFor<PoolApi> {
+	match ordering {
+		Some(ordering) => Box::new(pool.validated_pool().ready_with_ordering(ordering)),
+		None => Box::new(pool.validated_pool().ready()),
 	}
 }
 
@@ -392,7 +478,30 @@ where
 		spawner: impl SpawnEssentialNamed,
 		client: Arc<Client>,
 	) -> Arc<Self> {
-		let pool_api = Arc::new(FullChainApi::new(client.clone(), prometheus, &spawner));
+		Self::new_full_with_transaction_cost(
+			options,
+			is_validator,
+			prometheus,
+			spawner,
+			client,
+			None,
+		)
+	}
+
+	/// Create new basic transaction pool for a full node, reporting the fee and weight of
+	/// transactions from `transaction_cost` to the ordering policy.
+	pub fn new_full_with_transaction_cost(
+		options: graph::Options,
+		is_validator: IsValidator,
+		prometheus: Option<&PrometheusRegistry>,
+		spawner: impl SpawnEssentialNamed,
+		client: Arc<Client>,
+		transaction_cost: Option<Arc<dyn TransactionCostProvider<Block>>>,
+	) -> Arc<Self> {
+		let pool_api = Arc::new(
+			FullChainApi::new(client.clone(), prometheus, &spawner)
+				.with_transaction_cost(transaction_cost),
+		);
 		let pool = Arc::new(Self::with_revalidation_type(
 			options,
 			is_validator,
@@ -450,6 +559,7 @@ where
 			.block_id_to_number(&BlockId::hash(at))?
 			.ok_or_else(|| error::Error::BlockIdConversion(format!("{:?}", at)))?;
 
+		let cost = self.api.transaction_cost(at, &xt);
 		let validated = ValidatedTransaction::valid_at(
 			block_number.saturated_into::<u64>(),
 			hash,
@@ -457,9 +567,14 @@ where
 			xt,
 			bytes,
 			validity,
+			cost,
 		);
 
-		self.pool.validated_pool().submit(vec![validated]).remove(0)
+		let result = self.pool.validated_pool().submit(vec![validated]).remove(0);
+		if let (Ok(hash), Some(journal)) = (&result, &self.journal) {
+			journal.inserted([*hash]);
+		}
+		result
 	}
 }
 
@@ -709,7 +824,7 @@ where
 		// handler of "all blocks notification".
 		self.ready_poll
 			.lock()
-			.trigger(*block_number, move || Box::new(extra_pool.validated_pool().ready()));
+			.trigger(*block_number, move |ordering| ready_iterator(&extra_pool, ordering));
 
 		if next_action.revalidate {
 			let hashes = pool.validated_pool().ready().map(|tx| tx.hash).collect();
@@ -757,6 +872,8 @@ where
 			},
 		};
 
+		self.replay_journal(event.hash()).await;
+
 		if let ChainEvent::Finalized { hash, tree_route } = event {
 			log::trace!(
 				target: LOG_TARGET,
@@ -773,6 +890,57 @@ where
 					)
 				}
 			}
+
+			if let Some(journal) = &self.journal {
+				journal.write_in_background(self.pool.validated_pool());
+			}
+		}
+	}
+}
+
+impl<PoolApi, Block> BasicPool<PoolApi, Block>
+where
+	Block: BlockT,
+	PoolApi: 'static + graph::ChainApi<Block = Block>,
+{
+	/// Submits the transactions loaded from the journal at `at`, if not done yet.
+	async fn replay_journal(&self, at: Block::Hash) {
+		let entries = std::mem::take(&mut *self.journal_replay.lock());
+		if entries.is_empty() {
+			return
+		}
+
+		log::debug!(target: LOG_TARGET, "Resubmitting {} journaled transactions", entries.len());
+
+		// Submit consecutive transactions of the same source together, keeping the journal order.
+		let mut entries = entries.into_iter().peekable();
+		while let Some(first) = entries.next() {
+			let source = first.source;
+			let mut xts = vec![first.extrinsic];
+			while let Some(entry) = entries.next_if(|entry| entry.source == source) {
+				xts.push(entry.extrinsic);
+			}
+
+			if let Err(e) = self.pool.submit_at(at, source, xts).await {
+				log::debug!(target: LOG_TARGET, "Failed to resubmit journaled transactions: {e}");
+			}
+		}
+	}
+}
+
+impl<PoolApi, Block> Drop for BasicPool<PoolApi, Block>
+where
+	Block: BlockT,
+	PoolApi: graph::ChainApi<Block = Block>,
+{
+	fn drop(&mut self) {
+		// Until the journal is replayed its transactions are not in the pool, keep the journal.
+		if !self.journal_replay.get_mut().is_empty() {
+			return
+		}
+
+		if let Some(journal) = &self.journal {
+			journal.write(self.pool.validated_pool());
 		}
 	}
 }
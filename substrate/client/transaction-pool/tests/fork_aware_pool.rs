@@ -0,0 +1,272 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tests for the fork aware transaction pool.
+
+use futures::executor::{block_on, block_on_stream};
+use sc_transaction_pool::{ForkAwareTxPool, Options, PoolLimit};
+use sc_transaction_pool_api::{
+	error::{Error as TxPoolError, IntoPoolError},
+	ChainEvent, MaintainedTransactionPool, TransactionPool, TransactionStatus,
+};
+use sp_blockchain::HashAndNumber;
+use sp_runtime::transaction_validity::TransactionSource;
+use std::{collections::HashSet, sync::Arc};
+use substrate_test_runtime_client::{
+	runtime::{Block, Header},
+	AccountKeyring::*,
+};
+use substrate_test_runtime_transaction_pool::{uxt, TestApi};
+
+const SOURCE: TransactionSource = TransactionSource::External;
+
+fn create_pool(api: TestApi, best: &Header) -> ForkAwareTxPool<TestApi, Block> {
+	create_pool_with_options(Default::default(), api, best)
+}
+
+fn create_pool_with_options(
+	options: Options,
+	api: TestApi,
+	best: &Header,
+) -> ForkAwareTxPool<TestApi, Block> {
+	ForkAwareTxPool::new(
+		options,
+		true.into(),
+		Arc::new(api),
+		None,
+		HashAndNumber { hash: best.hash(), number: best.number },
+	)
+}
+
+fn genesis(api: &TestApi) -> Header {
+	api.chain()
+		.read()
+		.block_by_number
+		.get(&0)
+		.map(|blocks| blocks[0].0.header.clone())
+		.expect("there is block 0. qed")
+}
+
+fn new_best(header: &Header) -> ChainEvent<Block> {
+	ChainEvent::NewBestBlock { hash: header.hash(), tree_route: None }
+}
+
+fn finalized(header: &Header) -> ChainEvent<Block> {
+	ChainEvent::Finalized { hash: header.hash(), tree_route: Arc::from(vec![]) }
+}
+
+#[test]
+fn transactions_stay_ready_on_forks_that_did_not_include_them() {
+	let api = TestApi::with_alice_nonce(209);
+	let genesis = genesis(&api);
+	let pool = create_pool(api, &genesis);
+	let api = pool.api();
+
+	let xt = uxt(Alice, 209);
+	block_on(pool.submit_one(genesis.hash(), SOURCE, xt.clone())).expect("Imported");
+	assert_eq!(pool.status().ready, 1);
+
+	// A1 includes the transaction.
+	let a1 = api.push_block_with_parent(genesis.hash(), vec![xt.clone()], true);
+	block_on(pool.maintain(new_best(&a1)));
+	assert_eq!(pool.status().ready, 0);
+
+	// B1 is a sibling of A1 and does not include the transaction.
+	let b1 = api.push_block_with_parent(genesis.hash(), vec![], true);
+	block_on(pool.maintain(new_best(&b1)));
+	assert_eq!(pool.status().ready, 1);
+	assert!(pool.ready_transaction(&pool.hash_of(&xt)).is_some());
+
+	// switching back to A1 reuses its view.
+	block_on(pool.maintain(new_best(&a1)));
+	assert_eq!(pool.status().ready, 0);
+	// the view of genesis was dropped once A1 was built on top of it.
+	assert_eq!(pool.views().len(), 2);
+
+	// finalizing B1 drops the view of A1.
+	block_on(pool.maintain(finalized(&b1)));
+	assert_eq!(
+		pool.views().into_iter().map(|view| view.hash).collect::<HashSet<_>>(),
+		HashSet::from([b1.hash()])
+	);
+	assert_eq!(pool.status().ready, 1);
+}
+
+#[test]
+fn new_views_revalidate_the_transactions_of_their_ancestor() {
+	let api = TestApi::with_alice_nonce(209);
+	let genesis = genesis(&api);
+	let pool = create_pool(api, &genesis);
+	let api = pool.api();
+
+	let xt = uxt(Alice, 209);
+	block_on(pool.submit_one(genesis.hash(), SOURCE, xt.clone())).expect("Imported");
+	assert_eq!(pool.status().ready, 1);
+
+	// the transaction is not included in A1, but it is not valid there anymore.
+	api.add_invalid(&xt);
+	let a1 = api.push_block_with_parent(genesis.hash(), vec![], true);
+	block_on(pool.maintain(new_best(&a1)));
+	assert_eq!(pool.status().ready, 0);
+	assert_eq!(
+		pool.views().into_iter().map(|view| view.hash).collect::<HashSet<_>>(),
+		HashSet::from([a1.hash()])
+	);
+}
+
+#[test]
+fn watchers_follow_the_best_fork() {
+	let api = TestApi::empty();
+	let a1 = api.push_block(1, vec![], true);
+	let pool = create_pool(api, &a1);
+	let api = pool.api();
+
+	let from_alice = uxt(Alice, 1);
+	let from_bob = uxt(Bob, 2);
+	api.increment_nonce(Alice.into());
+	api.increment_nonce(Bob.into());
+
+	let alice_watcher =
+		block_on(pool.submit_and_watch(a1.hash(), SOURCE, from_alice.clone())).expect("Imported");
+	let bob_watcher =
+		block_on(pool.submit_and_watch(a1.hash(), SOURCE, from_bob.clone())).expect("Imported");
+
+	let b1 = api.push_block_with_parent(a1.hash(), vec![from_alice.clone()], true);
+	let b2 =
+		api.push_block_with_parent(a1.hash(), vec![from_bob.clone(), from_alice.clone()], true);
+
+	block_on(pool.maintain(new_best(&b1)));
+	assert_eq!(pool.status().ready, 1);
+	block_on(pool.maintain(new_best(&b2)));
+	assert_eq!(pool.status().ready, 0);
+	block_on(pool.maintain(finalized(&b2)));
+
+	let mut stream = block_on_stream(alice_watcher);
+	assert_eq!(stream.next(), Some(TransactionStatus::Ready));
+	assert_eq!(stream.next(), Some(TransactionStatus::InBlock((b1.hash(), 0))));
+	assert_eq!(stream.next(), Some(TransactionStatus::Retracted(b1.hash())));
+	assert_eq!(stream.next(), Some(TransactionStatus::InBlock((b2.hash(), 1))));
+	assert_eq!(stream.next(), Some(TransactionStatus::Finalized((b2.hash(), 1))));
+	assert_eq!(stream.next(), None);
+
+	let mut stream = block_on_stream(bob_watcher);
+	assert_eq!(stream.next(), Some(TransactionStatus::Ready));
+	assert_eq!(stream.next(), Some(TransactionStatus::InBlock((b2.hash(), 0))));
+	assert_eq!(stream.next(), Some(TransactionStatus::Finalized((b2.hash(), 0))));
+	assert_eq!(stream.next(), None);
+}
+
+#[test]
+fn finalizing_a_fork_that_was_never_the_best_moves_the_best_view() {
+	let api = TestApi::with_alice_nonce(209);
+	let genesis = genesis(&api);
+	let pool = create_pool(api, &genesis);
+	let api = pool.api();
+
+	let from_alice = uxt(Alice, 209);
+	let from_bob = uxt(Bob, 0);
+	block_on(pool.submit_one(genesis.hash(), SOURCE, from_alice.clone())).expect("Imported");
+	let bob_watcher = block_on(pool.submit_and_watch(genesis.hash(), SOURCE, from_bob.clone()))
+		.expect("Imported");
+
+	// A1 includes Alice's transaction and becomes the best block.
+	let a1 = api.push_block_with_parent(genesis.hash(), vec![from_alice.clone()], true);
+	block_on(pool.maintain(new_best(&a1)));
+	assert_eq!(pool.status().ready, 1);
+
+	// B1 includes Bob's transaction, it is finalized without ever being the best block.
+	let b1 = api.push_block_with_parent(genesis.hash(), vec![from_bob.clone()], false);
+	block_on(pool.maintain(finalized(&b1)));
+
+	assert_eq!(
+		pool.views().into_iter().map(|view| view.hash).collect::<HashSet<_>>(),
+		HashSet::from([b1.hash()])
+	);
+	assert_eq!(pool.status().ready, 1);
+	assert!(pool.ready_transaction(&pool.hash_of(&from_alice)).is_some());
+
+	let mut stream = block_on_stream(bob_watcher);
+	assert_eq!(stream.next(), Some(TransactionStatus::Ready));
+	assert_eq!(stream.next(), Some(TransactionStatus::InBlock((b1.hash(), 0))));
+	assert_eq!(stream.next(), Some(TransactionStatus::Finalized((b1.hash(), 0))));
+	assert_eq!(stream.next(), None);
+}
+
+#[test]
+fn transactions_invalid_at_the_finalized_block_are_removed() {
+	let api = TestApi::with_alice_nonce(209);
+	let genesis = genesis(&api);
+	let pool = create_pool(api, &genesis);
+	let api = pool.api();
+
+	let xt = uxt(Alice, 209);
+	let watcher =
+		block_on(pool.submit_and_watch(genesis.hash(), SOURCE, xt.clone())).expect("Imported");
+
+	// The transaction is dropped from the view of A1, but stays known to the pool.
+	api.add_invalid(&xt);
+	let a1 = api.push_block_with_parent(genesis.hash(), vec![], true);
+	block_on(pool.maintain(new_best(&a1)));
+	assert_eq!(pool.status().ready, 0);
+	assert!(matches!(
+		block_on(pool.submit_one(a1.hash(), SOURCE, xt.clone())).map_err(|e| e.into_pool_error()),
+		Err(Ok(TxPoolError::AlreadyImported(_)))
+	));
+
+	// It is invalid at the finalized block, so it is removed.
+	block_on(pool.maintain(finalized(&a1)));
+
+	let mut stream = block_on_stream(watcher);
+	assert_eq!(stream.next(), Some(TransactionStatus::Ready));
+	assert_eq!(stream.next(), Some(TransactionStatus::Invalid));
+	assert_eq!(stream.next(), None);
+}
+
+#[test]
+fn mempool_limits_bound_the_known_transactions() {
+	let api = TestApi::with_alice_nonce(209);
+	let genesis = genesis(&api);
+	let options = Options {
+		ready: PoolLimit { count: 2, total_bytes: 1024 * 1024 },
+		future: PoolLimit { count: 0, total_bytes: 0 },
+		..Default::default()
+	};
+	let pool = create_pool_with_options(options, api, &genesis);
+	let api = pool.api();
+
+	let from_alice = uxt(Alice, 209);
+	let from_bob = uxt(Bob, 0);
+	let from_charlie = uxt(Charlie, 1);
+	block_on(pool.submit_one(genesis.hash(), SOURCE, from_alice.clone())).expect("Imported");
+	block_on(pool.submit_one(genesis.hash(), SOURCE, from_bob.clone())).expect("Imported");
+
+	// The pool is full.
+	assert!(matches!(
+		block_on(pool.submit_one(genesis.hash(), SOURCE, from_charlie.clone()))
+			.map_err(|e| e.into_pool_error()),
+		Err(Ok(TxPoolError::ImmediatelyDropped))
+	));
+	assert_eq!(pool.status().ready, 2);
+
+	// Finalizing the inclusion of Alice's transaction makes room for another one.
+	let a1 = api.push_block_with_parent(genesis.hash(), vec![from_alice.clone()], true);
+	block_on(pool.maintain(new_best(&a1)));
+	block_on(pool.maintain(finalized(&a1)));
+	block_on(pool.submit_one(a1.hash(), SOURCE, from_charlie.clone())).expect("Imported");
+	assert_eq!(pool.status().ready, 2);
+}
@@ -125,3 +125,72 @@ fn task_execution_fails_for_invalid_task() {
 		);
 	});
 }
+
+#[test]
+fn task_execution_works_unsigned() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		Numbers::<Runtime>::insert(0, 1);
+
+		let task =
+			<Runtime as frame_system::Config>::RuntimeTask::TasksExample(crate::pallet::Task::<
+				Runtime,
+			>::AddNumberIntoTotal {
+				i: 0u32,
+			});
+		assert_ok!(System::do_task(RuntimeOrigin::none(), task.clone()));
+		assert_eq!(Numbers::<Runtime>::get(0), None);
+		assert_eq!(Total::<Runtime>::get(), (0, 1));
+		System::assert_last_event(frame_system::Event::<Runtime>::TaskCompleted { task }.into());
+	});
+}
+
+#[test]
+fn valid_tasks_are_returned() {
+	new_test_ext().execute_with(|| {
+		assert!(System::valid_tasks(10).is_empty());
+
+		Numbers::<Runtime>::insert(0, 1);
+		Numbers::<Runtime>::insert(1, 4);
+		assert_eq!(System::valid_tasks(1).len(), 1);
+		let valid_tasks = System::valid_tasks(10);
+		assert_eq!(valid_tasks.len(), 2);
+		for i in [0u32, 1u32] {
+			assert!(valid_tasks.contains(&RuntimeTask::TasksExample(crate::pallet::Task::<
+				Runtime,
+			>::AddNumberIntoTotal {
+				i
+			})));
+		}
+	});
+}
+
+#[test]
+fn unsigned_task_is_validated() {
+	use frame_support::pallet_prelude::{InvalidTransaction, TransactionSource, ValidateUnsigned};
+
+	new_test_ext().execute_with(|| {
+		Numbers::<Runtime>::insert(0, 1);
+
+		let call = frame_system::Call::<Runtime>::do_task {
+			task: RuntimeTask::TasksExample(crate::pallet::Task::<Runtime>::AddNumberIntoTotal {
+				i: 0u32,
+			}),
+		};
+		assert_ok!(System::validate_unsigned(TransactionSource::Local, &call));
+		assert_eq!(
+			System::validate_unsigned(TransactionSource::External, &call),
+			Err(InvalidTransaction::Call.into())
+		);
+
+		let call = frame_system::Call::<Runtime>::do_task {
+			task: RuntimeTask::TasksExample(crate::pallet::Task::<Runtime>::AddNumberIntoTotal {
+				i: 1u32,
+			}),
+		};
+		assert_eq!(
+			System::validate_unsigned(TransactionSource::Local, &call),
+			Err(InvalidTransaction::Stale.into())
+		);
+	});
+}
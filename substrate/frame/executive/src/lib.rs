@@ -167,6 +167,9 @@ pub type OriginOf<E, C> = <CallOf<E, C> as Dispatchable>::RuntimeOrigin;
 ///   used to call hooks e.g. `on_initialize`.
 /// - `OnRuntimeUpgrade`: Custom logic that should be called after a runtime upgrade. Modules are
 ///   already called by `AllPalletsWithSystem`. It will be called before all modules will be called.
+/// - `MultiStepMigrator`: Drives the multi-block migrations of the runtime, usually
+///   `pallet-migrations`. It is stepped once per block in `finalize_block`, before `on_idle`, and
+///   suspends all non-mandatory extrinsics while migrations are ongoing.
 pub struct Executive<
 	System,
 	Block,
@@ -174,6 +177,7 @@ pub struct Executive<
 	UnsignedValidator,
 	AllPalletsWithSystem,
 	OnRuntimeUpgrade = (),
+	MultiStepMigrator = (),
 >(
 	PhantomData<(
 		System,
@@ -182,6 +186,7 @@ pub struct Executive<
 		UnsignedValidator,
 		AllPalletsWithSystem,
 		OnRuntimeUpgrade,
+		MultiStepMigrator,
 	)>,
 );
 
@@ -200,8 +205,17 @@ impl<
 			+ OnFinalize<BlockNumberFor<System>>
 			+ OffchainWorker<BlockNumberFor<System>>,
 		COnRuntimeUpgrade: OnRuntimeUpgrade,
+		MultiStepMigrator: frame_support::migrations::MultiStepMigrator,
 	> ExecuteBlock<Block>
-	for Executive<System, Block, Context, UnsignedValidator, AllPalletsWithSystem, COnRuntimeUpgrade>
+	for Executive<
+		System,
+		Block,
+		Context,
+		UnsignedValidator,
+		AllPalletsWithSystem,
+		COnRuntimeUpgrade,
+		MultiStepMigrator,
+	>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
 	CheckedOf<Block::Extrinsic, Context>: Applyable + GetDispatchInfo,
@@ -218,6 +232,7 @@ where
 			UnsignedValidator,
 			AllPalletsWithSystem,
 			COnRuntimeUpgrade,
+			MultiStepMigrator,
 		>::execute_block(block);
 	}
 }
@@ -240,7 +255,17 @@ impl<
 			+ TryState<BlockNumberFor<System>>
 			+ TryDecodeEntireStorage,
 		COnRuntimeUpgrade: OnRuntimeUpgrade,
-	> Executive<System, Block, Context, UnsignedValidator, AllPalletsWithSystem, COnRuntimeUpgrade>
+		MultiStepMigrator: frame_support::migrations::MultiStepMigrator,
+	>
+	Executive<
+		System,
+		Block,
+		Context,
+		UnsignedValidator,
+		AllPalletsWithSystem,
+		COnRuntimeUpgrade,
+		MultiStepMigrator,
+	>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
 	CheckedOf<Block::Extrinsic, Context>: Applyable + GetDispatchInfo,
@@ -288,9 +313,14 @@ where
 			} else {
 				uxt.unchecked_into_checked_i_know_what_i_am_doing(&Default::default())
 			}?;
-			<frame_system::Pallet<System>>::note_extrinsic(encoded);
 
 			let dispatch_info = xt.get_dispatch_info();
+			if dispatch_info.class != DispatchClass::Mandatory && MultiStepMigrator::ongoing() {
+				return Err(InvalidTransaction::ExhaustsResources.into())
+			}
+
+			<frame_system::Pallet<System>>::note_extrinsic(encoded);
+
 			let r = Applyable::apply::<UnsignedValidator>(xt, &dispatch_info, encoded_len)?;
 
 			<frame_system::Pallet<System>>::note_applied_extrinsic(&r, dispatch_info);
@@ -383,6 +413,27 @@ where
 			),
 		);
 
+		// Drive the multi-block migrations to completion, one step per simulated block.
+		let mut mbm_steps = 0u32;
+		while MultiStepMigrator::ongoing() {
+			if MultiStepMigrator::stuck() {
+				log::error!(
+					target: LOG_TARGET,
+					"try-runtime: multi-block migrations got stuck after {mbm_steps} steps",
+				);
+				return Err("Multi-block migrations got stuck".into())
+			}
+
+			MultiStepMigrator::step();
+			mbm_steps += 1;
+		}
+		if mbm_steps > 0 {
+			log::info!(
+				target: LOG_TARGET,
+				"try-runtime: multi-block migrations completed in {mbm_steps} steps",
+			);
+		}
+
 		// Nothing should modify the state after the migrations ran:
 		let _guard = StorageNoopGuard::default();
 
@@ -450,7 +501,17 @@ impl<
 			+ OnFinalize<BlockNumberFor<System>>
 			+ OffchainWorker<BlockNumberFor<System>>,
 		COnRuntimeUpgrade: OnRuntimeUpgrade,
-	> Executive<System, Block, Context, UnsignedValidator, AllPalletsWithSystem, COnRuntimeUpgrade>
+		MultiStepMigrator: frame_support::migrations::MultiStepMigrator,
+	>
+	Executive<
+		System,
+		Block,
+		Context,
+		UnsignedValidator,
+		AllPalletsWithSystem,
+		COnRuntimeUpgrade,
+		MultiStepMigrator,
+	>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
 	CheckedOf<Block::Extrinsic, Context>: Applyable + GetDispatchInfo,
@@ -598,6 +659,16 @@ where
 	}
 
 	fn idle_and_finalize_hook(block_number: NumberFor<Block>) {
+		// Migrations are stepped before `on_idle`, so that idle work only gets the weight left
+		// over by the migration step.
+		if MultiStepMigrator::ongoing() {
+			let used_weight = MultiStepMigrator::step();
+			<frame_system::Pallet<System>>::register_extra_weight_unchecked(
+				used_weight,
+				DispatchClass::Mandatory,
+			);
+		}
+
 		let weight = <frame_system::Pallet<System>>::block_weight();
 		let max_weight = <System::BlockWeights as frame_support::traits::Get<_>>::get().max_block;
 		let remaining_weight = max_weight.saturating_sub(weight.total());
@@ -628,6 +699,13 @@ where
 				ext=?sp_core::hexdisplay::HexDisplay::from(&encoded)));
 		// Verify that the signature is good.
 		let xt = uxt.check(&Default::default())?;
+		let dispatch_info = xt.get_dispatch_info();
+
+		// Only inherents may be applied while multi-block migrations are ongoing. Transactions are
+		// rejected without being noted, so that block authors keep them for later blocks.
+		if dispatch_info.class != DispatchClass::Mandatory && MultiStepMigrator::ongoing() {
+			return Err(InvalidTransaction::ExhaustsResources.into())
+		}
 
 		// We don't need to make sure to `note_extrinsic` only after we know it's going to be
 		// executed to prevent it from leaking in storage since at this point, it will either
@@ -637,7 +715,6 @@ where
 		// AUDIT: Under no circumstances may this function panic from here onwards.
 
 		// Decode parameters and dispatch
-		let dispatch_info = xt.get_dispatch_info();
 		let r = Applyable::apply::<UnsignedValidator>(xt, &dispatch_info, encoded_len)?;
 
 		// Mandatory(inherents) are not allowed to fail.
@@ -985,6 +1062,21 @@ mod tests {
 	parameter_types! {
 		pub static RuntimeVersionTestValues: sp_version::RuntimeVersion =
 			Default::default();
+		pub static MbmActive: bool = false;
+		pub static MbmStepsTaken: u32 = 0;
+	}
+
+	/// Multi-block migrator that is ongoing as long as `MbmActive` is set.
+	pub struct MockedMultiStepMigrator;
+	impl frame_support::migrations::MultiStepMigrator for MockedMultiStepMigrator {
+		fn ongoing() -> bool {
+			MbmActive::get()
+		}
+
+		fn step() -> Weight {
+			MbmStepsTaken::mutate(|n| *n += 1);
+			Weight::from_parts(10, 0)
+		}
 	}
 
 	type SignedExtra = (
@@ -1019,6 +1111,7 @@ mod tests {
 		Runtime,
 		AllPalletsWithSystem,
 		CustomOnRuntimeUpgrade,
+		MockedMultiStepMigrator,
 	>;
 
 	fn extra(nonce: u64, fee: Balance) -> SignedExtra {
@@ -1698,4 +1791,66 @@ mod tests {
 			);
 		})
 	}
+
+	#[test]
+	fn mbms_suspend_transactions() {
+		let xt1 = TestXt::new(RuntimeCall::Custom(custom::Call::inherent_call {}), None);
+		let xt2 = TestXt::new(call_transfer(33, 0), sign_extra(1, 0, 0));
+
+		new_test_ext(1).execute_with(|| {
+			MbmActive::set(true);
+			MbmStepsTaken::set(0);
+
+			Executive::initialize_block(&Header::new(
+				1,
+				H256::default(),
+				H256::default(),
+				[69u8; 32].into(),
+				Digest::default(),
+			));
+
+			// Inherents are still applied.
+			Executive::apply_extrinsic(xt1).unwrap().unwrap();
+			// Transactions are rejected and not noted.
+			assert_eq!(
+				Executive::apply_extrinsic(xt2),
+				Err(TransactionValidityError::Invalid(InvalidTransaction::ExhaustsResources))
+			);
+			assert_eq!(<frame_system::Pallet<Runtime>>::extrinsic_index(), Some(1));
+
+			let weight_before = <frame_system::Pallet<Runtime>>::block_weight();
+			Executive::finalize_block();
+			assert_eq!(MbmStepsTaken::get(), 1);
+			assert!(
+				weight_before.get(DispatchClass::Mandatory).ref_time() + 10 <=
+					<frame_system::Pallet<Runtime>>::block_weight()
+						.get(DispatchClass::Mandatory)
+						.ref_time()
+			);
+
+			MbmActive::set(false);
+		});
+	}
+
+	#[test]
+	fn mbms_not_stepped_when_not_ongoing() {
+		let xt = TestXt::new(call_transfer(33, 0), sign_extra(1, 0, 0));
+
+		new_test_ext(1).execute_with(|| {
+			MbmActive::set(false);
+			MbmStepsTaken::set(0);
+
+			Executive::initialize_block(&Header::new(
+				1,
+				H256::default(),
+				H256::default(),
+				[69u8; 32].into(),
+				Digest::default(),
+			));
+
+			Executive::apply_extrinsic(xt).unwrap().unwrap();
+			Executive::finalize_block();
+			assert_eq!(MbmStepsTaken::get(), 0);
+		});
+	}
 }
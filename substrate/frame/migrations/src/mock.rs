@@ -0,0 +1,160 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Mocked runtime for testing the migrations pallet.
+
+#![cfg(test)]
+
+use crate as pallet_migrations;
+use crate::{mock_helpers::*, Pallet};
+
+use frame_support::{
+	derive_impl,
+	migrations::*,
+	traits::{ConstU32, OnFinalize, OnInitialize},
+	weights::Weight,
+};
+use frame_system::EventRecord;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system,
+		Migrations: pallet_migrations,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig as frame_system::DefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+	type PalletInfo = PalletInfo;
+}
+
+frame_support::parameter_types! {
+	pub const MaxServiceWeight: Weight = Weight::from_parts(500_000_000, 1_000_000);
+}
+
+impl pallet_migrations::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Migrations = MockedMigrations;
+	type CursorMaxLen = ConstU32<65_536>;
+	type IdentifierMaxLen = ConstU32<256>;
+	type MigrationStatusHandler = MockedMigrationStatusHandler;
+	type FailedMigrationHandler = MockedFailedMigrationHandler;
+	type MaxServiceWeight = MaxServiceWeight;
+	type WeightInfo = ();
+}
+
+frame_support::parameter_types! {
+	/// The number of started upgrades.
+	pub static UpgradesStarted: u32 = 0;
+	/// The number of completed upgrades.
+	pub static UpgradesCompleted: u32 = 0;
+	/// The migrations that failed.
+	pub static UpgradesFailed: Vec<Option<u32>> = vec![];
+	/// Return value of [`MockedFailedMigrationHandler::failed`].
+	pub static FailedUpgradeResponse: FailedMigrationHandling = FailedMigrationHandling::KeepStuck;
+}
+
+/// Records all started and completed upgrades in `UpgradesStarted` and `UpgradesCompleted`.
+pub struct MockedMigrationStatusHandler;
+impl MigrationStatusHandler for MockedMigrationStatusHandler {
+	fn started() {
+		log::info!("MigrationStatusHandler started");
+		UpgradesStarted::mutate(|v| *v += 1);
+	}
+
+	fn completed() {
+		log::info!("MigrationStatusHandler completed");
+		UpgradesCompleted::mutate(|v| *v += 1);
+	}
+}
+
+/// Records all failed upgrades in `UpgradesFailed`.
+pub struct MockedFailedMigrationHandler;
+impl FailedMigrationHandler for MockedFailedMigrationHandler {
+	fn failed(migration: Option<u32>) -> FailedMigrationHandling {
+		UpgradesFailed::mutate(|v| v.push(migration));
+		let res = FailedUpgradeResponse::get();
+		log::error!("FailedMigrationHandler failed at: {migration:?}, handling as {res:?}");
+		res
+	}
+}
+
+/// Returns the number of `(started, completed, failed)` upgrades and resets their numbers.
+pub fn upgrades_started_completed_failed() -> (u32, u32, u32) {
+	(UpgradesStarted::take(), UpgradesCompleted::take(), UpgradesFailed::take().len() as u32)
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	sp_io::TestExternalities::new(Default::default())
+}
+
+/// Run this closure in test externalities.
+pub fn test_closure<R>(f: impl FnOnce() -> R) -> R {
+	let mut ext = new_test_ext();
+	ext.execute_with(f)
+}
+
+/// Run the migration hooks of the pallet the way `frame-executive` does.
+pub fn run_to_block(n: u32) {
+	while System::block_number() < n as u64 {
+		if System::block_number() > 1 {
+			Migrations::on_finalize(System::block_number());
+			System::on_finalize(System::block_number());
+		}
+		log::debug!("Block {}", System::block_number() + 1);
+
+		System::set_block_number(System::block_number() + 1);
+		System::on_initialize(System::block_number());
+		Migrations::on_initialize(System::block_number());
+		// Executive calls this:
+		<Migrations as MultiStepMigrator>::step();
+	}
+}
+
+/// Returns the historic migrations, sorted by their identifier.
+pub fn historic() -> Vec<MockedIdentifier> {
+	let mut historic = crate::Historic::<Test>::iter_keys().collect::<Vec<_>>();
+	historic.sort();
+	historic
+}
+
+/// Checks the emitted events and resets them.
+pub fn assert_events<E: Into<RuntimeEvent>>(expected: Vec<E>) {
+	let mut events = System::events().into_iter().map(|EventRecord { event, .. }| event);
+
+	for (i, expected) in expected.into_iter().enumerate() {
+		assert_eq!(events.next(), Some(expected.into()), "event #{i} mismatches");
+	}
+	assert!(events.next().is_none(), "more events than expected");
+
+	System::reset_events();
+}
+
+/// Set the migrations that will run at the next upgrade.
+pub fn set_migrations(migrations: Vec<(MockedMigrationKind, u32)>) {
+	MockedMigrations::set(migrations);
+}
+
+/// Trigger a runtime upgrade and run the first block of the migrations.
+pub fn upgrade() {
+	<Pallet<Test> as frame_support::traits::OnRuntimeUpgrade>::on_runtime_upgrade();
+}
@@ -0,0 +1,362 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(test)]
+
+use crate::{
+	mock::{Test as T, *},
+	mock_helpers::{MockedMigrationKind::*, *},
+	Cursor, FailedMigrationHandling, HistoricCleanupSelector, MigrationCursor,
+};
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok, migrations::MultiStepMigrator, traits::Hooks, weights::Weight,
+};
+use frame_system::RawOrigin;
+use sp_runtime::DispatchError;
+
+type Event = crate::Event<T>;
+
+#[test]
+fn integrity_test_works() {
+	test_closure(|| {
+		<crate::Pallet<T> as Hooks<u64>>::integrity_test();
+	});
+}
+
+#[test]
+fn basic_works() {
+	test_closure(|| {
+		System::set_block_number(1);
+		set_migrations(vec![(SucceedAfter, 0), (SucceedAfter, 1)]);
+
+		upgrade();
+		assert!(<Migrations as MultiStepMigrator>::ongoing());
+		run_to_block(10);
+
+		assert_eq!(historic(), vec![mocked_id(SucceedAfter, 0), mocked_id(SucceedAfter, 1)]);
+		assert_events(vec![
+			Event::UpgradeStarted { migrations: 2 },
+			Event::MigrationCompleted { index: 0, took: 1 },
+			Event::MigrationAdvanced { index: 1, took: 0 },
+			Event::MigrationCompleted { index: 1, took: 1 },
+			Event::UpgradeCompleted,
+		]);
+		assert_eq!(upgrades_started_completed_failed(), (1, 1, 0));
+		assert!(!<Migrations as MultiStepMigrator>::ongoing());
+	});
+}
+
+#[test]
+fn historic_skipping_works() {
+	test_closure(|| {
+		System::set_block_number(1);
+		set_migrations(vec![(SucceedAfter, 0), (SucceedAfter, 1)]);
+		upgrade();
+		run_to_block(10);
+		assert_eq!(upgrades_started_completed_failed(), (1, 1, 0));
+		System::reset_events();
+
+		// Schedule the same migrations again, plus a new one.
+		set_migrations(vec![(SucceedAfter, 0), (SucceedAfter, 1), (SucceedAfter, 2)]);
+		upgrade();
+		run_to_block(20);
+
+		assert_events(vec![
+			Event::UpgradeStarted { migrations: 3 },
+			Event::MigrationSkipped { index: 0 },
+			Event::MigrationSkipped { index: 1 },
+			Event::MigrationAdvanced { index: 2, took: 0 },
+			Event::MigrationAdvanced { index: 2, took: 1 },
+			Event::MigrationCompleted { index: 2, took: 2 },
+			Event::UpgradeCompleted,
+		]);
+		assert_eq!(upgrades_started_completed_failed(), (1, 1, 0));
+
+		let mut expected = vec![
+			mocked_id(SucceedAfter, 0),
+			mocked_id(SucceedAfter, 1),
+			mocked_id(SucceedAfter, 2),
+		];
+		expected.sort();
+		assert_eq!(historic(), expected);
+	});
+}
+
+#[test]
+fn failing_migration_sets_cursor_to_stuck() {
+	test_closure(|| {
+		FailedUpgradeResponse::set(FailedMigrationHandling::KeepStuck);
+		System::set_block_number(1);
+		set_migrations(vec![(FailAfter, 2)]);
+
+		upgrade();
+		run_to_block(10);
+
+		assert_events(vec![
+			Event::UpgradeStarted { migrations: 1 },
+			Event::MigrationAdvanced { index: 0, took: 1 },
+			Event::MigrationAdvanced { index: 0, took: 2 },
+			Event::MigrationFailed { index: 0, took: 3 },
+			Event::UpgradeFailed,
+		]);
+		assert_eq!(upgrades_started_completed_failed(), (1, 0, 1));
+
+		// Failed migrations are not added to the historic set.
+		assert!(historic().is_empty());
+		assert_eq!(Cursor::<T>::get(), Some(MigrationCursor::Stuck));
+		assert!(<Migrations as MultiStepMigrator>::ongoing());
+		assert!(<Migrations as MultiStepMigrator>::stuck());
+
+		// A stuck cursor does not make progress anymore.
+		run_to_block(20);
+		assert_events::<Event>(vec![]);
+	});
+}
+
+#[test]
+fn failing_migration_force_unstuck_works() {
+	test_closure(|| {
+		FailedUpgradeResponse::set(FailedMigrationHandling::ForceUnstuck);
+		System::set_block_number(1);
+		set_migrations(vec![(FailAfter, 2)]);
+
+		upgrade();
+		run_to_block(10);
+
+		assert_events(vec![
+			Event::UpgradeStarted { migrations: 1 },
+			Event::MigrationAdvanced { index: 0, took: 1 },
+			Event::MigrationAdvanced { index: 0, took: 2 },
+			Event::MigrationFailed { index: 0, took: 3 },
+			Event::UpgradeFailed,
+		]);
+		assert_eq!(upgrades_started_completed_failed(), (1, 0, 1));
+
+		assert!(historic().is_empty());
+		assert!(Cursor::<T>::get().is_none());
+		assert!(!<Migrations as MultiStepMigrator>::ongoing());
+	});
+}
+
+/// A migration that reports not being able to make progress with the full service weight fails.
+#[test]
+fn high_weight_migration_singular_fails() {
+	test_closure(|| {
+		System::set_block_number(1);
+		set_migrations(vec![(HighWeightAfter(Weight::zero()), 2)]);
+
+		upgrade();
+		run_to_block(10);
+
+		assert_events(vec![
+			Event::UpgradeStarted { migrations: 1 },
+			Event::MigrationAdvanced { index: 0, took: 1 },
+			Event::MigrationAdvanced { index: 0, took: 2 },
+			Event::MigrationFailed { index: 0, took: 3 },
+			Event::UpgradeFailed,
+		]);
+		assert_eq!(upgrades_started_completed_failed(), (1, 0, 1));
+		assert_eq!(Cursor::<T>::get(), Some(MigrationCursor::Stuck));
+	});
+}
+
+/// A migration that lacks weight after another one completed is retried in the next block.
+#[test]
+fn high_weight_migration_retries_once() {
+	test_closure(|| {
+		System::set_block_number(1);
+		set_migrations(vec![(SucceedAfter, 0), (HighWeightAfter(Weight::zero()), 0)]);
+
+		upgrade();
+		run_to_block(10);
+
+		assert_events(vec![
+			Event::UpgradeStarted { migrations: 2 },
+			Event::MigrationCompleted { index: 0, took: 1 },
+			// `took` is one since it was retried in the next block.
+			Event::MigrationFailed { index: 1, took: 1 },
+			Event::UpgradeFailed,
+		]);
+		assert_eq!(upgrades_started_completed_failed(), (1, 0, 1));
+		assert_eq!(historic(), vec![mocked_id(SucceedAfter, 0)]);
+	});
+}
+
+/// A migration that requires more than the maximal service weight fails immediately.
+#[test]
+fn high_weight_migration_permanently_overweight_fails() {
+	test_closure(|| {
+		System::set_block_number(1);
+		set_migrations(vec![(SucceedAfter, 0), (HighWeightAfter(Weight::MAX), 0)]);
+
+		upgrade();
+		run_to_block(10);
+
+		assert_events(vec![
+			Event::UpgradeStarted { migrations: 2 },
+			Event::MigrationCompleted { index: 0, took: 1 },
+			Event::MigrationFailed { index: 1, took: 0 },
+			Event::UpgradeFailed,
+		]);
+		assert_eq!(upgrades_started_completed_failed(), (1, 0, 1));
+	});
+}
+
+#[test]
+fn timeout_migration_fails() {
+	test_closure(|| {
+		System::set_block_number(1);
+		set_migrations(vec![(TimeoutAfter, 3)]);
+
+		upgrade();
+		run_to_block(10);
+
+		assert_events(vec![
+			Event::UpgradeStarted { migrations: 1 },
+			Event::MigrationAdvanced { index: 0, took: 1 },
+			Event::MigrationAdvanced { index: 0, took: 2 },
+			Event::MigrationAdvanced { index: 0, took: 3 },
+			Event::MigrationAdvanced { index: 0, took: 4 },
+			Event::MigrationFailed { index: 0, took: 4 },
+			Event::UpgradeFailed,
+		]);
+		assert_eq!(upgrades_started_completed_failed(), (1, 0, 1));
+	});
+}
+
+/// Starting a new upgrade while migrations are still ongoing fails the upgrade.
+#[test]
+fn upgrade_while_ongoing_fails() {
+	test_closure(|| {
+		FailedUpgradeResponse::set(FailedMigrationHandling::KeepStuck);
+		System::set_block_number(1);
+		set_migrations(vec![(SucceedAfter, 5)]);
+
+		upgrade();
+		run_to_block(3);
+		upgrade();
+
+		assert_events(vec![
+			Event::UpgradeStarted { migrations: 1 },
+			Event::MigrationAdvanced { index: 0, took: 1 },
+			Event::MigrationAdvanced { index: 0, took: 2 },
+			Event::UpgradeFailed,
+		]);
+		assert_eq!(upgrades_started_completed_failed(), (1, 0, 1));
+		assert_eq!(Cursor::<T>::get(), Some(MigrationCursor::Stuck));
+	});
+}
+
+#[test]
+fn no_migrations_does_not_start_upgrade() {
+	test_closure(|| {
+		System::set_block_number(1);
+		set_migrations(vec![]);
+
+		upgrade();
+		run_to_block(10);
+
+		assert_events::<Event>(vec![]);
+		assert_eq!(upgrades_started_completed_failed(), (0, 0, 0));
+		assert!(!<Migrations as MultiStepMigrator>::ongoing());
+	});
+}
+
+#[test]
+fn force_set_cursor_works() {
+	test_closure(|| {
+		System::set_block_number(1);
+		set_migrations(vec![(SucceedAfter, 5)]);
+
+		assert_noop!(
+			Migrations::force_set_cursor(RuntimeOrigin::signed(1), None),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(Migrations::force_set_cursor(
+			RawOrigin::Root.into(),
+			Some(MigrationCursor::Stuck)
+		));
+		assert!(<Migrations as MultiStepMigrator>::stuck());
+
+		assert_ok!(Migrations::force_set_cursor(RawOrigin::Root.into(), None));
+		assert!(!<Migrations as MultiStepMigrator>::ongoing());
+
+		// Forward the migration to its last step.
+		assert_ok!(Migrations::force_set_active_cursor(
+			RawOrigin::Root.into(),
+			0,
+			Some(5u32.encode().try_into().unwrap()),
+			None
+		));
+		run_to_block(10);
+
+		assert_events(vec![
+			Event::MigrationCompleted { index: 0, took: 0 },
+			Event::UpgradeCompleted,
+		]);
+		assert_eq!(historic(), vec![mocked_id(SucceedAfter, 5)]);
+	});
+}
+
+#[test]
+fn force_onboard_mbms_works() {
+	test_closure(|| {
+		System::set_block_number(1);
+		set_migrations(vec![(SucceedAfter, 1)]);
+
+		assert_ok!(Migrations::force_onboard_mbms(RawOrigin::Root.into()));
+		assert_noop!(
+			Migrations::force_onboard_mbms(RawOrigin::Root.into()),
+			crate::Error::<T>::Ongoing
+		);
+
+		run_to_block(10);
+		assert_eq!(upgrades_started_completed_failed(), (1, 1, 0));
+		assert_ok!(Migrations::force_onboard_mbms(RawOrigin::Root.into()));
+	});
+}
+
+#[test]
+fn clear_historic_works() {
+	test_closure(|| {
+		System::set_block_number(1);
+		set_migrations(vec![(SucceedAfter, 0), (SucceedAfter, 1), (SucceedAfter, 2)]);
+		upgrade();
+		run_to_block(10);
+		assert_eq!(historic().len(), 3);
+		System::reset_events();
+
+		assert_ok!(Migrations::clear_historic(
+			RawOrigin::Root.into(),
+			HistoricCleanupSelector::Specific(vec![mocked_id(SucceedAfter, 0)])
+		));
+		assert_eq!(historic().len(), 2);
+
+		assert_ok!(Migrations::clear_historic(
+			RawOrigin::Root.into(),
+			HistoricCleanupSelector::Wildcard { limit: None, previous_cursor: None }
+		));
+		assert!(historic().is_empty());
+
+		assert_events(vec![
+			Event::HistoricCleared { next_cursor: None },
+			Event::HistoricCleared { next_cursor: None },
+		]);
+	});
+}
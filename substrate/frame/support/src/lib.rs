@@ -2722,7 +2722,9 @@ pub mod pallet_macros {
 	/// [`construct_runtime`](frame_support::construct_runtime).
 	///
 	/// Finally, the `RuntimeTask` can then used by a script or off-chain worker to create and
-	/// submit such tasks via an extrinsic defined in `frame_system` called `do_task`.
+	/// submit such tasks via an extrinsic defined in `frame_system` called `do_task`. The
+	/// extrinsic can also be submitted unsigned, which allows nodes to automatically submit
+	/// all valid tasks returned by the `TasksApi` runtime API.
 	///
 	/// ## Example
 	#[doc = docify::embed!("src/tests/tasks.rs", tasks_example)]
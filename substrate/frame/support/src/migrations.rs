@@ -16,12 +16,15 @@
 // limitations under the License.
 
 use crate::{
+	defensive,
 	traits::{GetStorageVersion, NoStorageVersionSet, PalletInfoAccess, StorageVersion},
-	weights::{RuntimeDbWeight, Weight},
+	weights::{RuntimeDbWeight, Weight, WeightMeter},
 };
+use codec::{Decode, Encode, MaxEncodedLen};
 use impl_trait_for_tuples::impl_for_tuples;
 use sp_core::Get;
 use sp_io::{hashing::twox_128, storage::clear_prefix, KillStorageResult};
+use sp_runtime::{DispatchError, TransactionOutcome};
 use sp_std::marker::PhantomData;
 
 /// Handles storage migration pallet versioning.
@@ -344,3 +347,560 @@ impl<P: Get<&'static str>, DbWeight: Get<RuntimeDbWeight>> frame_support::traits
 		Ok(())
 	}
 }
+
+/// A migration that can proceed in multiple steps.
+///
+/// Each step is executed in its own block and is bounded by the weight that is available in that
+/// block. The progress of the migration is tracked by a [`SteppedMigration::Cursor`] that is
+/// persisted in storage by the migrator between the steps. The migration is completed once
+/// [`SteppedMigration::step`] returns `Ok(None)`.
+///
+/// Multi-block migrations are driven by an implementation of the [`MultiStepMigrator`] trait,
+/// usually `pallet-migrations`. User transactions are suspended while a migration is ongoing.
+pub trait SteppedMigration {
+	/// The cursor type that stores the progress (aka. state) of this migration.
+	type Cursor: codec::FullCodec + codec::MaxEncodedLen;
+
+	/// The unique identifier type of this migration.
+	type Identifier: codec::FullCodec + codec::MaxEncodedLen;
+
+	/// The unique identifier of this migration.
+	///
+	/// If two migrations have the same identifier, then they are assumed to be identical.
+	fn id() -> Self::Identifier;
+
+	/// The maximum number of steps that this migration can take.
+	///
+	/// This can be used to enforce progress and prevent migrations from being stuck forever. A
+	/// migration that exceeds its max steps is treated as failed. `None` means that there is no
+	/// limit.
+	fn max_steps() -> Option<u32> {
+		None
+	}
+
+	/// Try to migrate as much as possible with the given weight.
+	///
+	/// **ANY STORAGE CHANGES MUST BE ROLLED-BACK BY THE CALLER UPON ERROR.** This is necessary
+	/// since the caller cannot return a cursor in the error case. [`Self::transactional_step`] is
+	/// provided as convenience for a caller. A cursor of `None` implies that the migration is at
+	/// its end.
+	fn step(
+		cursor: Option<Self::Cursor>,
+		meter: &mut WeightMeter,
+	) -> Result<Option<Self::Cursor>, SteppedMigrationError>;
+
+	/// Same as [`Self::step`], but rolls back pending changes in the error case.
+	fn transactional_step(
+		mut cursor: Option<Self::Cursor>,
+		meter: &mut WeightMeter,
+	) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+		with_transaction_opaque_err(move || match Self::step(cursor, meter) {
+			Ok(new_cursor) => {
+				cursor = new_cursor;
+				TransactionOutcome::Commit(Ok(cursor))
+			},
+			Err(err) => TransactionOutcome::Rollback(Err(err)),
+		})
+		.map_err(|()| SteppedMigrationError::Failed)?
+	}
+
+	/// Hook for testing that is run before the migration is started.
+	///
+	/// Returns some bytes which are passed into `post_upgrade` after the migration is completed.
+	/// This is not run for the real migration, so panicking is not an issue here.
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<sp_std::vec::Vec<u8>, sp_runtime::TryRuntimeError> {
+		Ok(sp_std::vec::Vec::new())
+	}
+
+	/// Hook for testing that is run after the migration is completed.
+	///
+	/// Should be used to verify the state of the chain after the migration. The `state` parameter
+	/// is the return value from `pre_upgrade`. This is not run for the real migration, so
+	/// panicking is not an issue here.
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(_state: sp_std::vec::Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+		Ok(())
+	}
+}
+
+/// Error that can occur in a [`SteppedMigration`].
+#[derive(Debug, Encode, Decode, MaxEncodedLen, scale_info::TypeInfo, PartialEq, Eq, Clone)]
+pub enum SteppedMigrationError {
+	// Transient errors:
+	/// The remaining weight is not enough to do anything.
+	///
+	/// Can be resolved by calling with at least `required` weight. Note that calling it with
+	/// exactly `required` weight could cause it to not make any progress.
+	InsufficientWeight {
+		/// Amount of weight required to make progress.
+		required: Weight,
+	},
+	// Permanent errors:
+	/// The migration cannot decode its cursor and therefore not proceed.
+	///
+	/// This should not happen unless (1) the migration itself returned an invalid cursor in a
+	/// previous iteration, (2) the storage got corrupted or (3) there is a bug in the caller's
+	/// code.
+	InvalidCursor,
+	/// The migration encountered a permanent error and cannot continue.
+	Failed,
+}
+
+/// Executes the supplied closure in a transaction and rolls back upon error.
+///
+/// The error of the closure is opaque to the caller and mapped into `()`.
+pub fn with_transaction_opaque_err<R, E, F>(f: F) -> Result<Result<R, E>, ()>
+where
+	F: FnOnce() -> TransactionOutcome<Result<R, E>>,
+{
+	crate::storage::transactional::with_transaction(
+		move || -> TransactionOutcome<Result<Result<R, E>, DispatchError>> {
+			match f() {
+				TransactionOutcome::Commit(res) => TransactionOutcome::Commit(Ok(res)),
+				TransactionOutcome::Rollback(res) => TransactionOutcome::Rollback(Ok(res)),
+			}
+		},
+	)
+	.map_err(|_| ())
+}
+
+/// Something that can do multi step migrations.
+///
+/// This is implemented by `pallet-migrations` and consumed by the `frame-executive`.
+pub trait MultiStepMigrator {
+	/// Hint for whether [`Self::step`] should be called.
+	///
+	/// User transactions are suspended while this returns `true`.
+	fn ongoing() -> bool;
+
+	/// Do the next step in the multi step migration process.
+	///
+	/// Must gracefully handle the case that it is currently not upgrading.
+	fn step() -> Weight;
+
+	/// Whether the ongoing migrations got stuck and cannot make any further progress.
+	///
+	/// Tools that drive the migrations offline, like try-runtime, use this to stop stepping.
+	fn stuck() -> bool {
+		false
+	}
+}
+
+impl MultiStepMigrator for () {
+	fn ongoing() -> bool {
+		false
+	}
+
+	fn step() -> Weight {
+		Weight::zero()
+	}
+}
+
+/// Multiple [`SteppedMigration`]s.
+///
+/// The migrations are type erased, which allows a tuple of heterogeneous [`SteppedMigration`]s
+/// to be treated as a single, indexable list. Cursors and identifiers are passed around in their
+/// SCALE encoded form.
+pub trait SteppedMigrations {
+	/// The number of migrations that `Self` aggregates.
+	fn len() -> u32;
+
+	/// The `n`th [`SteppedMigration::id`].
+	///
+	/// Is guaranteed to return `Some` if `n < Self::len()`.
+	fn nth_id(n: u32) -> Option<sp_std::vec::Vec<u8>>;
+
+	/// The [`SteppedMigration::max_steps`] of the `n`th migration.
+	///
+	/// Is guaranteed to return `Some` if `n < Self::len()`.
+	fn nth_max_steps(n: u32) -> Option<Option<u32>>;
+
+	/// Do a [`SteppedMigration::step`] on the `n`th migration.
+	///
+	/// Is guaranteed to return `Some` if `n < Self::len()`.
+	fn nth_step(
+		n: u32,
+		cursor: Option<sp_std::vec::Vec<u8>>,
+		meter: &mut WeightMeter,
+	) -> Option<Result<Option<sp_std::vec::Vec<u8>>, SteppedMigrationError>>;
+
+	/// Do a [`SteppedMigration::transactional_step`] on the `n`th migration.
+	///
+	/// Is guaranteed to return `Some` if `n < Self::len()`.
+	fn nth_transactional_step(
+		n: u32,
+		cursor: Option<sp_std::vec::Vec<u8>>,
+		meter: &mut WeightMeter,
+	) -> Option<Result<Option<sp_std::vec::Vec<u8>>, SteppedMigrationError>>;
+
+	/// Call the pre-upgrade hooks of the `n`th migration.
+	///
+	/// Returns `None` if the index is out of bounds.
+	#[cfg(feature = "try-runtime")]
+	fn nth_pre_upgrade(n: u32)
+		-> Option<Result<sp_std::vec::Vec<u8>, sp_runtime::TryRuntimeError>>;
+
+	/// Call the post-upgrade hooks of the `n`th migration.
+	///
+	/// Returns `None` if the index is out of bounds.
+	#[cfg(feature = "try-runtime")]
+	fn nth_post_upgrade(
+		n: u32,
+		_state: sp_std::vec::Vec<u8>,
+	) -> Option<Result<(), sp_runtime::TryRuntimeError>>;
+
+	/// The maximal encoded length across all cursors.
+	fn cursor_max_encoded_len() -> usize;
+
+	/// The maximal encoded length across all identifiers.
+	fn identifier_max_encoded_len() -> usize;
+
+	/// Assert the integrity of the migrations.
+	///
+	/// Should be executed as part of a test prior to runtime usage. May or may not need
+	/// externalities.
+	#[cfg(feature = "std")]
+	fn integrity_test() -> Result<(), &'static str> {
+		use crate::ensure;
+		let l = Self::len();
+
+		for n in 0..l {
+			ensure!(Self::nth_id(n).is_some(), "id is None");
+			ensure!(Self::nth_max_steps(n).is_some(), "steps is None");
+
+			// The cursor that we use does not matter. Hence use empty.
+			ensure!(
+				Self::nth_step(n, Some(vec![]), &mut WeightMeter::new()).is_some(),
+				"steps is None"
+			);
+			ensure!(
+				Self::nth_transactional_step(n, Some(vec![]), &mut WeightMeter::new()).is_some(),
+				"steps is None"
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl SteppedMigrations for () {
+	fn len() -> u32 {
+		0
+	}
+
+	fn nth_id(_n: u32) -> Option<sp_std::vec::Vec<u8>> {
+		None
+	}
+
+	fn nth_max_steps(_n: u32) -> Option<Option<u32>> {
+		None
+	}
+
+	fn nth_step(
+		_n: u32,
+		_cursor: Option<sp_std::vec::Vec<u8>>,
+		_meter: &mut WeightMeter,
+	) -> Option<Result<Option<sp_std::vec::Vec<u8>>, SteppedMigrationError>> {
+		None
+	}
+
+	fn nth_transactional_step(
+		_n: u32,
+		_cursor: Option<sp_std::vec::Vec<u8>>,
+		_meter: &mut WeightMeter,
+	) -> Option<Result<Option<sp_std::vec::Vec<u8>>, SteppedMigrationError>> {
+		None
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn nth_pre_upgrade(
+		_n: u32,
+	) -> Option<Result<sp_std::vec::Vec<u8>, sp_runtime::TryRuntimeError>> {
+		None
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn nth_post_upgrade(
+		_n: u32,
+		_state: sp_std::vec::Vec<u8>,
+	) -> Option<Result<(), sp_runtime::TryRuntimeError>> {
+		None
+	}
+
+	fn cursor_max_encoded_len() -> usize {
+		0
+	}
+
+	fn identifier_max_encoded_len() -> usize {
+		0
+	}
+}
+
+// A collection consisting of only a single migration.
+impl<T: SteppedMigration> SteppedMigrations for T {
+	fn len() -> u32 {
+		1
+	}
+
+	fn nth_id(n: u32) -> Option<sp_std::vec::Vec<u8>> {
+		(n == 0).then(|| T::id().encode())
+	}
+
+	fn nth_max_steps(n: u32) -> Option<Option<u32>> {
+		(n == 0).then(|| T::max_steps())
+	}
+
+	fn nth_step(
+		n: u32,
+		cursor: Option<sp_std::vec::Vec<u8>>,
+		meter: &mut WeightMeter,
+	) -> Option<Result<Option<sp_std::vec::Vec<u8>>, SteppedMigrationError>> {
+		if n != 0 {
+			defensive!("nth_step should only be called with n==0");
+			return None
+		}
+
+		let cursor = match cursor {
+			Some(cursor) => match T::Cursor::decode(&mut &cursor[..]) {
+				Ok(cursor) => Some(cursor),
+				Err(_) => return Some(Err(SteppedMigrationError::InvalidCursor)),
+			},
+			None => None,
+		};
+
+		Some(T::step(cursor, meter).map(|cursor| cursor.map(|cursor| cursor.encode())))
+	}
+
+	fn nth_transactional_step(
+		n: u32,
+		cursor: Option<sp_std::vec::Vec<u8>>,
+		meter: &mut WeightMeter,
+	) -> Option<Result<Option<sp_std::vec::Vec<u8>>, SteppedMigrationError>> {
+		if n != 0 {
+			defensive!("nth_transactional_step should only be called with n==0");
+			return None
+		}
+
+		let cursor = match cursor {
+			Some(cursor) => match T::Cursor::decode(&mut &cursor[..]) {
+				Ok(cursor) => Some(cursor),
+				Err(_) => return Some(Err(SteppedMigrationError::InvalidCursor)),
+			},
+			None => None,
+		};
+
+		Some(
+			T::transactional_step(cursor, meter).map(|cursor| cursor.map(|cursor| cursor.encode())),
+		)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn nth_pre_upgrade(
+		n: u32,
+	) -> Option<Result<sp_std::vec::Vec<u8>, sp_runtime::TryRuntimeError>> {
+		if n != 0 {
+			defensive!("nth_pre_upgrade should only be called with n==0");
+		}
+
+		Some(T::pre_upgrade())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn nth_post_upgrade(
+		n: u32,
+		state: sp_std::vec::Vec<u8>,
+	) -> Option<Result<(), sp_runtime::TryRuntimeError>> {
+		if n != 0 {
+			defensive!("nth_post_upgrade should only be called with n==0");
+		}
+		Some(T::post_upgrade(state))
+	}
+
+	fn cursor_max_encoded_len() -> usize {
+		T::Cursor::max_encoded_len()
+	}
+
+	fn identifier_max_encoded_len() -> usize {
+		T::Identifier::max_encoded_len()
+	}
+}
+
+#[impl_for_tuples(1, 30)]
+impl SteppedMigrations for Tuple {
+	fn len() -> u32 {
+		for_tuples!( #( Tuple::len() )+* )
+	}
+
+	fn nth_id(n: u32) -> Option<sp_std::vec::Vec<u8>> {
+		let mut i = 0;
+
+		for_tuples!( #(
+			if (i + Tuple::len()) > n {
+				return Tuple::nth_id(n - i)
+			}
+
+			i += Tuple::len();
+		)* );
+
+		None
+	}
+
+	fn nth_max_steps(n: u32) -> Option<Option<u32>> {
+		let mut i = 0;
+
+		for_tuples!( #(
+			if (i + Tuple::len()) > n {
+				return Tuple::nth_max_steps(n - i)
+			}
+
+			i += Tuple::len();
+		)* );
+
+		None
+	}
+
+	fn nth_step(
+		n: u32,
+		cursor: Option<sp_std::vec::Vec<u8>>,
+		meter: &mut WeightMeter,
+	) -> Option<Result<Option<sp_std::vec::Vec<u8>>, SteppedMigrationError>> {
+		let mut i = 0;
+
+		for_tuples!( #(
+			if (i + Tuple::len()) > n {
+				return Tuple::nth_step(n - i, cursor, meter)
+			}
+
+			i += Tuple::len();
+		)* );
+
+		None
+	}
+
+	fn nth_transactional_step(
+		n: u32,
+		cursor: Option<sp_std::vec::Vec<u8>>,
+		meter: &mut WeightMeter,
+	) -> Option<Result<Option<sp_std::vec::Vec<u8>>, SteppedMigrationError>> {
+		let mut i = 0;
+
+		for_tuples! ( #(
+			if (i + Tuple::len()) > n {
+				return Tuple::nth_transactional_step(n - i, cursor, meter)
+			}
+
+			i += Tuple::len();
+		)* );
+
+		None
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn nth_pre_upgrade(
+		n: u32,
+	) -> Option<Result<sp_std::vec::Vec<u8>, sp_runtime::TryRuntimeError>> {
+		let mut i = 0;
+
+		for_tuples! ( #(
+			if (i + Tuple::len()) > n {
+				return Tuple::nth_pre_upgrade(n - i)
+			}
+
+			i += Tuple::len();
+		)* );
+
+		None
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn nth_post_upgrade(
+		n: u32,
+		state: sp_std::vec::Vec<u8>,
+	) -> Option<Result<(), sp_runtime::TryRuntimeError>> {
+		let mut i = 0;
+
+		for_tuples! ( #(
+			if (i + Tuple::len()) > n {
+				return Tuple::nth_post_upgrade(n - i, state)
+			}
+
+			i += Tuple::len();
+		)* );
+
+		None
+	}
+
+	fn cursor_max_encoded_len() -> usize {
+		let mut max_len = 0;
+
+		for_tuples!( #(
+			max_len = max_len.max(Tuple::cursor_max_encoded_len());
+		)* );
+
+		max_len
+	}
+
+	fn identifier_max_encoded_len() -> usize {
+		let mut max_len = 0;
+
+		for_tuples!( #(
+			max_len = max_len.max(Tuple::identifier_max_encoded_len());
+		)* );
+
+		max_len
+	}
+}
+
+/// Notification handler for status updates regarding multi-block migrations.
+#[impl_for_tuples(8)]
+pub trait MigrationStatusHandler {
+	/// Notifies of the start of a runtime migration.
+	fn started() {}
+
+	/// Notifies of the completion of a runtime migration.
+	fn completed() {}
+}
+
+/// Handles a failed runtime upgrade.
+///
+/// Note that this is only called on a hard failure of a migration, not on a transient error like
+/// insufficient weight.
+pub trait FailedMigrationHandler {
+	/// Infallibly handle a failed runtime upgrade.
+	///
+	/// Gets passed in the optional index of the migration in the batch that caused the failure.
+	/// Returning `None` means that no automatic handling should take place and the callee decides
+	/// in the implementation what to do.
+	fn failed(migration: Option<u32>) -> FailedMigrationHandling;
+}
+
+/// Do not allow any transactions to be processed after a runtime upgrade failed.
+///
+/// This is **not a sane default**, since it prevents governance intervention.
+pub struct FreezeChainOnFailedMigration;
+
+impl FailedMigrationHandler for FreezeChainOnFailedMigration {
+	fn failed(_migration: Option<u32>) -> FailedMigrationHandling {
+		FailedMigrationHandling::KeepStuck
+	}
+}
+
+/// How to proceed after a runtime upgrade failed.
+///
+/// There is NO SANE DEFAULT HERE. All options are very dangerous and should be used with care.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailedMigrationHandling {
+	/// Resume extrinsic processing of the chain. This will not resume the upgrade.
+	///
+	/// This should be supplemented with additional measures to ensure that the broken chain state
+	/// does not get further messed up by user extrinsics.
+	ForceUnstuck,
+	/// Set the cursor to `Stuck` and keep blocking extrinsics.
+	KeepStuck,
+	/// Don't do anything with the cursor and let the handler decide.
+	///
+	/// This can be useful in cases where the other two options would overwrite any changes that
+	/// were done by the handler to the cursor.
+	Ignore,
+}
@@ -40,7 +40,7 @@ pub mod __private {
 /// tasks of this type.
 pub trait Task: Sized + FullCodec + TypeInfo + Clone + Debug + PartialEq + Eq {
 	/// An [`Iterator`] over tasks of this type used as the return type for `enumerate`.
-	type Enumeration: Iterator;
+	type Enumeration: Iterator<Item = Self>;
 
 	/// Inspects the pallet's state and enumerates tasks of this type.
 	fn iter() -> Self::Enumeration;
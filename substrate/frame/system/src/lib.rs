@@ -96,7 +96,8 @@ use frame_support::{
 	storage::{self, StorageStreamIter},
 	traits::{
 		ConstU32, Contains, EnsureOrigin, EnsureOriginWithArg, Get, HandleLifetime,
-		OnKilledAccount, OnNewAccount, OriginTrait, PalletInfo, SortedMembers, StoredMap, TypedGet,
+		OnKilledAccount, OnNewAccount, OriginTrait, PalletInfo, SortedMembers, StoredMap, Task,
+		TypedGet,
 	},
 	Parameter,
 };
@@ -134,6 +135,16 @@ pub use weights::WeightInfo;
 
 const LOG_TARGET: &str = "runtime::system";
 
+/// Priority of the unsigned `do_task` transactions submitted by the node.
+///
+/// High enough to be included before regular transactions, but leaves room above it for other
+/// service transactions.
+const TASK_TRANSACTION_PRIORITY: sp_runtime::transaction_validity::TransactionPriority =
+	sp_runtime::transaction_validity::TransactionPriority::max_value() / 2;
+
+/// Longevity, in blocks, of the unsigned `do_task` transactions submitted by the node.
+const TASK_TRANSACTION_LONGEVITY: sp_runtime::transaction_validity::TransactionLongevity = 64;
+
 /// Compute the trie root of a list of extrinsics.
 ///
 /// The merkle proof is using the same trie as runtime state with
@@ -637,10 +648,15 @@ pub mod pallet {
 			Ok(().into())
 		}
 
+		/// Execute a valid [`Task`](frame_support::traits::Task) of the runtime.
+		///
+		/// Can be submitted as unsigned transaction, which is validated by checking the validity of
+		/// the task. This allows nodes to automatically submit all valid tasks, see
+		/// [`Pallet::valid_tasks`].
 		#[pallet::call_index(8)]
 		#[pallet::weight(task.weight())]
 		pub fn do_task(origin: OriginFor<T>, task: T::RuntimeTask) -> DispatchResultWithPostInfo {
-			ensure_signed(origin)?;
+			ensure_signed_or_none(origin)?;
 
 			if !task.is_valid() {
 				return Err(Error::<T>::InvalidTask.into())
@@ -660,6 +676,36 @@ pub mod pallet {
 		}
 	}
 
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let Call::do_task { task } = call else { return Err(InvalidTransaction::Call.into()) };
+
+			// Tasks are submitted by the local node, see `Pallet::valid_tasks`. Anyone else has to
+			// pay for them by submitting a signed `do_task`.
+			match source {
+				TransactionSource::Local | TransactionSource::InBlock => { /* allowed */ },
+				_ => return Err(InvalidTransaction::Call.into()),
+			}
+
+			if !task.is_valid() {
+				return Err(InvalidTransaction::Stale.into())
+			}
+
+			// The node resubmits all valid tasks on every new best block, so a short longevity is
+			// enough. Once executed, a task is invalidated by the revalidation of the pool.
+			ValidTransaction::with_tag_prefix("SystemTask")
+				.priority(TASK_TRANSACTION_PRIORITY)
+				.and_provides(task.encode())
+				.longevity(TASK_TRANSACTION_LONGEVITY)
+				// Every node submits the tasks it sees itself, there is no need to gossip them.
+				.propagate(false)
+				.build()
+		}
+	}
+
 	/// Event for the System pallet.
 	#[pallet::event]
 	pub enum Event<T: Config> {
@@ -1147,6 +1193,22 @@ where
 	}
 }
 
+/// Ensure that the origin `o` represents either a signed or an unsigned extrinsic.
+/// Returns `Ok` with the account that signed the extrinsic, `None` if it was unsigned, or an `Err`
+/// otherwise.
+pub fn ensure_signed_or_none<OuterOrigin, AccountId>(
+	o: OuterOrigin,
+) -> Result<Option<AccountId>, BadOrigin>
+where
+	OuterOrigin: Into<Result<RawOrigin<AccountId>, OuterOrigin>>,
+{
+	match o.into() {
+		Ok(RawOrigin::None) => Ok(None),
+		Ok(RawOrigin::Signed(t)) => Ok(Some(t)),
+		_ => Err(BadOrigin),
+	}
+}
+
 /// Ensure that the origin `o` represents the root. Returns `Ok` or an `Err` otherwise.
 pub fn ensure_root<OuterOrigin, AccountId>(o: OuterOrigin) -> Result<(), BadOrigin>
 where
@@ -1195,6 +1257,14 @@ pub enum DecRefStatus {
 }
 
 impl<T: Config> Pallet<T> {
+	/// Returns up to `max` tasks of the runtime that are currently valid.
+	///
+	/// Used by the `TasksApi` runtime API, which allows nodes to submit the tasks as unsigned
+	/// `do_task` transactions.
+	pub fn valid_tasks(max: u32) -> Vec<T::RuntimeTask> {
+		T::RuntimeTask::iter().filter(|task| task.is_valid()).take(max as usize).collect()
+	}
+
 	/// Returns the `spec_version` of the last runtime upgrade.
 	///
 	/// This function is useful for writing guarded runtime migrations in the runtime. A runtime
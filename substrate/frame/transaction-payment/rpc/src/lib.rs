@@ -19,13 +19,14 @@
 
 use std::{convert::TryInto, sync::Arc};
 
-use codec::{Codec, Decode};
+use codec::{Codec, Decode, Encode};
 use jsonrpsee::{
 	core::{Error as JsonRpseeError, RpcResult},
 	proc_macros::rpc,
 	types::error::{CallError, ErrorCode, ErrorObject},
 };
 use pallet_transaction_payment_rpc_runtime_api::{FeeDetails, InclusionFee, RuntimeDispatchInfo};
+use sc_transaction_pool_api::ordering::{TransactionCost, TransactionCostProvider};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_core::Bytes;
@@ -175,3 +176,38 @@ where
 		})
 	}
 }
+
+/// Reports the fee and weight of transactions to the transaction pool, as returned by the
+/// `query_info` runtime API.
+///
+/// Used with the `FeePerWeightOrdering` of the pool.
+pub struct TransactionPaymentCost<C, Balance> {
+	/// Shared reference to the client.
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<fn() -> Balance>,
+}
+
+impl<C, Balance> TransactionPaymentCost<C, Balance> {
+	/// Creates a new instance of the transaction cost provider.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block, Balance> TransactionCostProvider<Block> for TransactionPaymentCost<C, Balance>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + Send + Sync + 'static,
+	C::Api: TransactionPaymentRuntimeApi<Block, Balance>,
+	Balance: Codec + TryInto<u128>,
+{
+	fn transaction_cost(&self, at: Block::Hash, uxt: &Block::Extrinsic) -> Option<TransactionCost> {
+		let encoded_len = uxt.encoded_size() as u32;
+		let info = self.client.runtime_api().query_info(at, uxt.clone(), encoded_len).ok()?;
+
+		Some(TransactionCost {
+			fee: info.partial_fee.try_into().ok()?,
+			weight: info.weight.ref_time(),
+		})
+	}
+}
@@ -34,4 +34,12 @@ sp_api::decl_runtime_apis! {
 		/// Starts the off-chain task for given block header.
 		fn offchain_worker(header: &Block::Header);
 	}
+
+	/// The api to discover `#[pallet::task]` work of the runtime.
+	///
+	/// Nodes use it to automatically submit the tasks that are ready to be executed.
+	pub trait TasksApi {
+		/// Returns unsigned extrinsics that execute up to `max` tasks that are currently valid.
+		fn valid_task_extrinsics(max: u32) -> sp_runtime::sp_std::vec::Vec<Block::Extrinsic>;
+	}
 }
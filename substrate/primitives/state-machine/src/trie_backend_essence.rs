@@ -403,6 +403,17 @@ where
 
 	/// Access the root of the child storage in its parent trie
 	fn child_root(&self, child_info: &ChildInfo) -> Result<Option<H::Out>> {
+		let result = self.fetch_child_root(child_info)?;
+
+		if let (Some(recorder), Some(root)) = (self.recorder.as_ref(), result) {
+			recorder.note_child_trie(root, child_info.prefixed_storage_key().as_slice());
+		}
+
+		Ok(result)
+	}
+
+	/// Read the root of the child storage from the cache or the parent trie.
+	fn fetch_child_root(&self, child_info: &ChildInfo) -> Result<Option<H::Out>> {
 		#[cfg(feature = "std")]
 		{
 			if let Some(result) = self.cache.read().child_root.get(child_info.storage_key()) {
@@ -727,6 +727,42 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn export_and_import_cache() {
+		let (db, root) = create_trie();
+
+		let shared_cache = Cache::new(CACHE_SIZE);
+		{
+			let local_cache = shared_cache.local_cache();
+			let mut cache = local_cache.as_trie_db_cache(root);
+			let trie = TrieDBBuilder::<Layout>::new(&db, &root).with_cache(&mut cache).build();
+			for (key, value) in TEST_DATA {
+				assert_eq!(value.to_vec(), trie.get(key).unwrap().unwrap());
+			}
+		}
+		let snapshot = shared_cache.export(&root);
+
+		let imported = Cache::new(CACHE_SIZE);
+		let (nodes, values) = imported.import(&snapshot, &root).unwrap();
+		assert_eq!(nodes, shared_cache.read_lock_inner().node_cache().lru.len());
+		assert_eq!(values, TEST_DATA.len());
+
+		// Everything is served from the imported cache, without touching the database.
+		let empty_db = MemoryDB::default();
+		let local_cache = imported.local_cache();
+		let mut cache = local_cache.as_trie_db_cache(root);
+		let trie = TrieDBBuilder::<Layout>::new(&empty_db, &root).with_cache(&mut cache).build();
+		for (key, value) in TEST_DATA {
+			assert_eq!(value.to_vec(), trie.get(key).unwrap().unwrap());
+		}
+
+		// Values of another state are not imported.
+		let other = Cache::new(CACHE_SIZE);
+		let (_, values) = other.import(&snapshot, &Default::default()).unwrap();
+		assert_eq!(values, 0);
+		assert!(other.import(&snapshot[1..], &root).is_err());
+	}
+
 	#[test]
 	fn trie_db_mut_cache_works() {
 		let (mut db, root) = create_trie();
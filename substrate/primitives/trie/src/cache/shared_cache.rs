@@ -18,6 +18,7 @@
 ///! Provides the [`SharedNodeCache`], the [`SharedValueCache`] and the [`SharedTrieCache`]
 ///! that combines both caches and is exported to the outside.
 use super::{CacheSize, NodeCached};
+use codec::{Decode, Encode};
 use hash_db::Hasher;
 use nohash_hasher::BuildNoHashHasher;
 use parking_lot::{Mutex, RwLock, RwLockWriteGuard};
@@ -27,7 +28,7 @@ use std::{
 	hash::{BuildHasher, Hasher as _},
 	sync::Arc,
 };
-use trie_db::{node::NodeOwned, CachedValue};
+use trie_db::{node::NodeOwned, CachedValue, NodeCodec as NodeCodecT};
 
 lazy_static::lazy_static! {
 	static ref RANDOM_STATE: ahash::RandomState = {
@@ -567,6 +568,31 @@ impl<H: Eq + std::hash::Hash + Clone + Copy + AsRef<[u8]>> SharedValueCache<H> {
 	}
 }
 
+/// Version of the [`CacheSnapshot`] format.
+const CACHE_SNAPSHOT_VERSION: u32 = 1;
+
+/// A cached node in a [`CacheSnapshot`].
+#[derive(Encode, Decode)]
+enum SnapshotNode {
+	/// An encoded trie node.
+	Node(Vec<u8>),
+	/// A value stored outside of its trie node.
+	Value(Vec<u8>),
+}
+
+/// Content of a [`SharedTrieCache`], as written by [`SharedTrieCache::export`].
+#[derive(Encode, Decode)]
+struct CacheSnapshot {
+	version: u32,
+	/// The state the values belong to.
+	storage_root: Vec<u8>,
+	/// Most recently used first.
+	nodes: Vec<SnapshotNode>,
+	/// Storage keys and the hashes of their values, `None` if they don't exist. Most recently
+	/// used first.
+	values: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+}
+
 /// The inner of [`SharedTrieCache`].
 pub(super) struct SharedTrieCacheInner<H: Hasher> {
 	node_cache: SharedNodeCache<H::Out>,
@@ -735,6 +761,122 @@ impl<H: Hasher> SharedTrieCache<H> {
 		self.reset_value_cache();
 	}
 
+	/// Encode a snapshot of the cached nodes and of the cached values of the state at
+	/// `storage_root`.
+	///
+	/// The snapshot can be loaded with [`Self::import`], e.g. to not start with an empty cache
+	/// after a restart.
+	pub fn export(&self, storage_root: &H::Out) -> Vec<u8> {
+		let inner = self.inner.read();
+		let nodes = inner
+			.node_cache
+			.lru
+			.iter()
+			.map(|(_, node)| match node {
+				NodeOwned::Value(data, _) => SnapshotNode::Value(data.to_vec()),
+				node => SnapshotNode::Node(node.to_encoded::<crate::NodeCodec<H>>()),
+			})
+			.collect();
+		let values = inner
+			.value_cache
+			.lru
+			.iter()
+			.filter(|(key, _)| key.storage_root == *storage_root)
+			.filter_map(|(key, value)| match value {
+				CachedValue::NonExisting => Some((key.storage_key.to_vec(), None)),
+				CachedValue::Existing { hash, .. } =>
+					Some((key.storage_key.to_vec(), Some(hash.as_ref().to_vec()))),
+				// Can't be restored without knowing the value.
+				CachedValue::ExistingHash(_) => None,
+			})
+			.collect();
+
+		CacheSnapshot {
+			version: CACHE_SNAPSHOT_VERSION,
+			storage_root: storage_root.as_ref().to_vec(),
+			nodes,
+			values,
+		}
+		.encode()
+	}
+
+	/// Load a snapshot created by [`Self::export`] into the cache.
+	///
+	/// Nodes are addressed by their hash and always loaded. Values are only loaded if the snapshot
+	/// was taken at `storage_root`, and if the node holding them was loaded as well.
+	///
+	/// Returns the number of nodes and values that were loaded.
+	pub fn import(
+		&self,
+		snapshot: &[u8],
+		storage_root: &H::Out,
+	) -> Result<(usize, usize), codec::Error> {
+		let snapshot = CacheSnapshot::decode(&mut &snapshot[..])?;
+		if snapshot.version != CACHE_SNAPSHOT_VERSION {
+			return Err("Unsupported trie cache snapshot version".into())
+		}
+
+		let mut inner = self.inner.write();
+		let mut values_by_hash = HashMap::new();
+		let mut nodes_loaded = 0;
+		// Insert the least recently used entries first, to keep the order of the LRU.
+		for node in snapshot.nodes.into_iter().rev() {
+			let (hash, node) = match node {
+				SnapshotNode::Node(encoded) => {
+					let node = crate::NodeCodec::<H>::decode(&encoded)
+						.ok()
+						.and_then(|node| node.to_owned_node::<crate::LayoutV1<H>>().ok())
+						.ok_or("Invalid trie node in trie cache snapshot")?;
+					(H::hash(&encoded), node)
+				},
+				SnapshotNode::Value(data) => {
+					let hash = H::hash(&data);
+					(hash, NodeOwned::Value(data.into(), hash))
+				},
+			};
+			if let Some(data) = node.data() {
+				values_by_hash.insert(H::hash(data), data.clone());
+			}
+			if inner.node_cache.lru.insert(hash, node) {
+				nodes_loaded += 1;
+			}
+		}
+
+		let mut values_loaded = 0;
+		if snapshot.storage_root.as_slice() == storage_root.as_ref() {
+			for (storage_key, value_hash) in snapshot.values.into_iter().rev() {
+				let value = match value_hash {
+					None => CachedValue::NonExisting,
+					Some(value_hash) => {
+						let mut hash = H::Out::default();
+						if hash.as_ref().len() != value_hash.len() {
+							return Err("Invalid value hash in trie cache snapshot".into())
+						}
+						hash.as_mut().copy_from_slice(&value_hash);
+						// The cache only holds weak references to the values owned by the nodes.
+						match values_by_hash.get(&hash) {
+							Some(data) => CachedValue::from((data.clone(), hash)),
+							None => continue,
+						}
+					},
+				};
+				let key = ValueCacheRef::new(&storage_key, *storage_root).into();
+				if inner.value_cache.lru.insert(key, value) {
+					values_loaded += 1;
+				}
+			}
+		}
+
+		tracing::debug!(
+			target: super::LOG_TARGET,
+			"Imported {} nodes and {} values into the shared trie cache",
+			nodes_loaded,
+			values_loaded,
+		);
+
+		Ok((nodes_loaded, values_loaded))
+	}
+
 	/// Returns the read locked inner.
 	#[cfg(test)]
 	pub(super) fn read_lock_inner(
@@ -164,6 +164,11 @@ pub trait TrieRecorderProvider<H: Hasher> {
 
 	/// Provide a recorder implementing [`trie_db::TrieRecorder`].
 	fn as_trie_recorder(&self, storage_root: H::Out) -> Self::Recorder<'_>;
+
+	/// Note that `storage_root` is the root of the child trie stored under `prefixed_storage_key`.
+	///
+	/// Does nothing by default.
+	fn note_child_trie(&self, _storage_root: H::Out, _prefixed_storage_key: &[u8]) {}
 }
 
 /// Type that is able to provide a proof size estimation.
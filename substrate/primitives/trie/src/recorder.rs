@@ -66,6 +66,16 @@ struct RecorderInner<H> {
 	///
 	/// Mapping: `Hash(Node) -> Node`.
 	accessed_nodes: HashMap<H, Vec<u8>>,
+
+	/// The storage key whose access recorded a node, if attribution is enabled.
+	///
+	/// Mapping: `Hash(Node) -> Key`.
+	node_keys: Option<HashMap<H, Arc<[u8]>>>,
+
+	/// The prefixed storage keys of the child tries, if attribution is enabled.
+	///
+	/// Mapping: `Child trie root -> Prefixed storage key`.
+	child_tries: HashMap<H, Arc<[u8]>>,
 }
 
 impl<H> Default for RecorderInner<H> {
@@ -74,10 +84,63 @@ impl<H> Default for RecorderInner<H> {
 			recorded_keys: Default::default(),
 			accessed_nodes: Default::default(),
 			transactions: Vec::new(),
+			node_keys: None,
+			child_tries: Default::default(),
 		}
 	}
 }
 
+impl<H> RecorderInner<H> {
+	/// Take all the recorded data, leaving behind an empty recording with the same settings.
+	fn take(&mut self) -> Self {
+		let node_keys = self.node_keys.as_ref().map(|_| Default::default());
+		mem::replace(self, Self { node_keys, ..Default::default() })
+	}
+}
+
+/// The proof size attributed to the storage keys whose accesses recorded the trie nodes.
+///
+/// A node is attributed to the first key that was accessed after recording it. Nodes that are
+/// shared between keys, like the root node, thus only count for one of them. The sizes are based
+/// on the plain encoding of the nodes, so they sum up to
+/// [`Recorder::estimate_encoded_size`] and not to the compact proof size.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ProofSizeAttribution {
+	/// The recorded bytes per storage key.
+	///
+	/// Keys of child tries are prefixed with the prefixed storage key of their child trie, e.g.
+	/// `:child_storage:default:` followed by the name of the child trie.
+	pub keys: HashMap<Vec<u8>, usize>,
+	/// The recorded bytes that could not be attributed to any key, e.g. nodes recorded while
+	/// iterating the trie.
+	pub unattributed: usize,
+}
+
+impl ProofSizeAttribution {
+	/// The total number of recorded bytes.
+	pub fn total(&self) -> usize {
+		self.keys.values().sum::<usize>() + self.unattributed
+	}
+
+	/// Sum up the recorded bytes of all keys sharing the first `len` bytes.
+	///
+	/// With `len = 32` this groups FRAME storage by storage item, with `len = 16` by pallet. Keys
+	/// shorter than `len` are kept as they are. The result is sorted by size, largest first.
+	pub fn by_prefix(&self, len: usize) -> Vec<(Vec<u8>, usize)> {
+		let mut prefixes = HashMap::<&[u8], usize>::new();
+		for (key, size) in &self.keys {
+			*prefixes.entry(&key[..len.min(key.len())]).or_default() += size;
+		}
+
+		let mut prefixes = prefixes
+			.into_iter()
+			.map(|(prefix, size)| (prefix.to_vec(), size))
+			.collect::<Vec<_>>();
+		prefixes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+		prefixes
+	}
+}
+
 /// The trie recorder.
 ///
 /// Owns the recorded data. Is used to transform data into a storage
@@ -107,6 +170,41 @@ impl<H: Hasher> Clone for Recorder<H> {
 }
 
 impl<H: Hasher> Recorder<H> {
+	/// Create a new recorder that attributes the recorded nodes to the storage keys.
+	///
+	/// See [`Self::proof_size_attribution`].
+	pub fn with_proof_size_attribution() -> Self {
+		let recorder = Self::default();
+		recorder.enable_proof_size_attribution();
+		recorder
+	}
+
+	/// Start attributing the recorded nodes to the storage keys.
+	///
+	/// Only nodes recorded from now on are attributed. This affects all clones of this recorder.
+	pub fn enable_proof_size_attribution(&self) {
+		self.inner.lock().node_keys.get_or_insert_with(Default::default);
+	}
+
+	/// Returns how much of the recorded proof each storage key is responsible for.
+	///
+	/// Returns `None` if attribution isn't enabled for this recorder.
+	pub fn proof_size_attribution(&self) -> Option<ProofSizeAttribution> {
+		let inner = self.inner.lock();
+		let node_keys = inner.node_keys.as_ref()?;
+
+		let mut attribution = ProofSizeAttribution::default();
+		for (hash, node) in &inner.accessed_nodes {
+			match node_keys.get(hash) {
+				Some(key) =>
+					*attribution.keys.entry(key.to_vec()).or_default() += node.encoded_size(),
+				None => attribution.unattributed += node.encoded_size(),
+			}
+		}
+
+		Some(attribution)
+	}
+
 	/// Returns [`RecordedForKey`] per recorded key per trie.
 	///
 	/// There are multiple tries when working with e.g. child tries.
@@ -126,6 +224,7 @@ impl<H: Hasher> Recorder<H> {
 			inner: self.inner.lock(),
 			storage_root,
 			encoded_size_estimation: self.encoded_size_estimation.clone(),
+			unattributed_nodes: Vec::new(),
 			_phantom: PhantomData,
 		}
 	}
@@ -139,7 +238,7 @@ impl<H: Hasher> Recorder<H> {
 	///
 	/// Returns the [`StorageProof`].
 	pub fn drain_storage_proof(self) -> StorageProof {
-		let mut recorder = mem::take(&mut *self.inner.lock());
+		let mut recorder = self.inner.lock().take();
 		StorageProof::new(recorder.accessed_nodes.drain().map(|(_, v)| v))
 	}
 
@@ -166,7 +265,7 @@ impl<H: Hasher> Recorder<H> {
 	///
 	/// This discards all recorded data.
 	pub fn reset(&self) {
-		mem::take(&mut *self.inner.lock());
+		self.inner.lock().take();
 		self.encoded_size_estimation.store(0, Ordering::Relaxed);
 	}
 
@@ -192,6 +291,10 @@ impl<H: Hasher> Recorder<H> {
 				new_encoded_size_estimation =
 					new_encoded_size_estimation.saturating_sub(old.encoded_size());
 			}
+
+			if let Some(node_keys) = inner.node_keys.as_mut() {
+				node_keys.remove(&n);
+			}
 		});
 
 		transaction.recorded_keys.into_iter().for_each(|(storage_root, keys)| {
@@ -248,6 +351,10 @@ pub struct TrieRecorder<'a, H: Hasher> {
 	inner: MutexGuard<'a, RecorderInner<H::Out>>,
 	storage_root: H::Out,
 	encoded_size_estimation: Arc<AtomicUsize>,
+	/// Nodes recorded since the last key access, if attribution is enabled.
+	///
+	/// `trie-db` records all the nodes on the path to a key before reporting the key itself.
+	unattributed_nodes: Vec<H::Out>,
 	_phantom: PhantomData<H>,
 }
 
@@ -261,11 +368,37 @@ impl<H: Hasher> crate::TrieRecorderProvider<H> for Recorder<H> {
 	fn as_trie_recorder(&self, storage_root: H::Out) -> Self::Recorder<'_> {
 		Recorder::as_trie_recorder(&self, storage_root)
 	}
+
+	fn note_child_trie(&self, storage_root: H::Out, prefixed_storage_key: &[u8]) {
+		let mut inner = self.inner.lock();
+		if inner.node_keys.is_some() && !inner.child_tries.contains_key(&storage_root) {
+			inner.child_tries.insert(storage_root, prefixed_storage_key.into());
+		}
+	}
 }
 
 impl<'a, H: Hasher> TrieRecorder<'a, H> {
+	/// Attribute the nodes recorded since the last key access to `full_key`.
+	fn attribute_nodes(&mut self, full_key: &[u8]) {
+		if self.unattributed_nodes.is_empty() || self.inner.node_keys.is_none() {
+			return
+		}
+
+		let key: Arc<[u8]> = match self.inner.child_tries.get(&self.storage_root) {
+			Some(prefixed_storage_key) => [&prefixed_storage_key[..], full_key].concat().into(),
+			None => full_key.into(),
+		};
+		if let Some(node_keys) = self.inner.node_keys.as_mut() {
+			for hash in self.unattributed_nodes.drain(..) {
+				node_keys.entry(hash).or_insert_with(|| key.clone());
+			}
+		}
+	}
+
 	/// Update the recorded keys entry for the given `full_key`.
 	fn update_recorded_keys(&mut self, full_key: &[u8], access: RecordedForKey) {
+		self.attribute_nodes(full_key);
+
 		let inner = self.inner.deref_mut();
 
 		let entry =
@@ -320,6 +453,7 @@ impl<'a, H: Hasher> trie_db::TrieRecorder<H::Out> for TrieRecorder<'a, H> {
 				);
 
 				let inner = self.inner.deref_mut();
+				let unattributed_nodes = &mut self.unattributed_nodes;
 
 				inner.accessed_nodes.entry(hash).or_insert_with(|| {
 					let node = node_owned.to_encoded::<NodeCodec<H>>();
@@ -330,6 +464,10 @@ impl<'a, H: Hasher> trie_db::TrieRecorder<H::Out> for TrieRecorder<'a, H> {
 						tx.accessed_nodes.insert(hash);
 					}
 
+					if inner.node_keys.is_some() {
+						unattributed_nodes.push(hash);
+					}
+
 					node
 				});
 			},
@@ -341,6 +479,7 @@ impl<'a, H: Hasher> trie_db::TrieRecorder<H::Out> for TrieRecorder<'a, H> {
 				);
 
 				let inner = self.inner.deref_mut();
+				let unattributed_nodes = &mut self.unattributed_nodes;
 
 				inner.accessed_nodes.entry(hash).or_insert_with(|| {
 					let node = encoded_node.into_owned();
@@ -351,6 +490,10 @@ impl<'a, H: Hasher> trie_db::TrieRecorder<H::Out> for TrieRecorder<'a, H> {
 						tx.accessed_nodes.insert(hash);
 					}
 
+					if inner.node_keys.is_some() {
+						unattributed_nodes.push(hash);
+					}
+
 					node
 				});
 			},
@@ -363,6 +506,7 @@ impl<'a, H: Hasher> trie_db::TrieRecorder<H::Out> for TrieRecorder<'a, H> {
 				);
 
 				let inner = self.inner.deref_mut();
+				let unattributed_nodes = &mut self.unattributed_nodes;
 
 				inner.accessed_nodes.entry(hash).or_insert_with(|| {
 					let value = value.into_owned();
@@ -373,6 +517,10 @@ impl<'a, H: Hasher> trie_db::TrieRecorder<H::Out> for TrieRecorder<'a, H> {
 						tx.accessed_nodes.insert(hash);
 					}
 
+					if inner.node_keys.is_some() {
+						unattributed_nodes.push(hash);
+					}
+
 					value
 				});
 
@@ -642,6 +790,93 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn proof_size_attribution_works() {
+		let (db, root) = create_trie();
+
+		let recorder = Recorder::default();
+		assert!(recorder.proof_size_attribution().is_none());
+
+		let recorder = Recorder::with_proof_size_attribution();
+		{
+			let mut trie_recorder = recorder.as_trie_recorder(root);
+			let trie = TrieDBBuilder::<Layout>::new(&db, &root)
+				.with_recorder(&mut trie_recorder)
+				.build();
+
+			assert_eq!(TEST_DATA[0].1.to_vec(), trie.get(TEST_DATA[0].0).unwrap().unwrap());
+		}
+
+		recorder.start_transaction();
+		{
+			let mut trie_recorder = recorder.as_trie_recorder(root);
+			let trie = TrieDBBuilder::<Layout>::new(&db, &root)
+				.with_recorder(&mut trie_recorder)
+				.build();
+
+			assert_eq!(TEST_DATA[1].1.to_vec(), trie.get(TEST_DATA[1].0).unwrap().unwrap());
+		}
+
+		let attribution = recorder.proof_size_attribution().unwrap();
+		assert_eq!(attribution.unattributed, 0);
+		assert_eq!(attribution.total(), recorder.estimate_encoded_size());
+		assert_eq!(attribution.keys.len(), 2);
+		// The value alone is already bigger than 64 bytes.
+		assert!(attribution.keys[TEST_DATA[0].0] > 64);
+		assert!(attribution.keys[TEST_DATA[1].0] > 64);
+		assert_eq!(
+			attribution.by_prefix(3),
+			vec![(b"key".to_vec(), attribution.keys.values().sum::<usize>())]
+		);
+
+		// Rolling back removes the nodes of the second key.
+		recorder.rollback_transaction().unwrap();
+		let attribution = recorder.proof_size_attribution().unwrap();
+		assert_eq!(attribution.keys.len(), 1);
+		assert_eq!(attribution.total(), recorder.estimate_encoded_size());
+
+		// Iterating doesn't access any specific key.
+		recorder.reset();
+		{
+			let mut trie_recorder = recorder.as_trie_recorder(root);
+			let trie = TrieDBBuilder::<Layout>::new(&db, &root)
+				.with_recorder(&mut trie_recorder)
+				.build();
+
+			assert_eq!(trie.key_iter().unwrap().count(), TEST_DATA.len());
+		}
+
+		let attribution = recorder.proof_size_attribution().unwrap();
+		assert!(attribution.keys.is_empty());
+		assert_eq!(attribution.unattributed, recorder.estimate_encoded_size());
+	}
+
+	#[test]
+	fn proof_size_attribution_prefixes_child_trie_keys() {
+		let (db, root) = create_trie();
+		let prefixed_storage_key = b":child_storage:default:child";
+
+		let recorder = Recorder::with_proof_size_attribution();
+		crate::TrieRecorderProvider::<sp_core::Blake2Hasher>::note_child_trie(
+			&recorder,
+			root,
+			prefixed_storage_key,
+		);
+		{
+			let mut trie_recorder = recorder.as_trie_recorder(root);
+			let trie = TrieDBBuilder::<Layout>::new(&db, &root)
+				.with_recorder(&mut trie_recorder)
+				.build();
+
+			assert_eq!(TEST_DATA[0].1.to_vec(), trie.get(TEST_DATA[0].0).unwrap().unwrap());
+		}
+
+		let attribution = recorder.proof_size_attribution().unwrap();
+		let key = [&prefixed_storage_key[..], TEST_DATA[0].0].concat();
+		assert_eq!(attribution.keys.len(), 1);
+		assert_eq!(attribution.keys[&key], recorder.estimate_encoded_size());
+	}
+
 	#[test]
 	fn recorder_transaction_accessed_keys_works() {
 		let key = TEST_DATA[0].0;
@@ -39,6 +39,7 @@ use sp_externalities::Extensions;
 use sp_keystore::{testing::MemoryKeystore, KeystoreExt};
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 use sp_state_machine::StateMachine;
+use sp_trie::recorder::ProofSizeAttribution;
 use std::{collections::HashMap, fmt::Debug, fs, str::FromStr, time};
 
 /// Logging target
@@ -215,6 +216,9 @@ impl PalletCmd {
 			// Do not enable storage tracking
 			false,
 		)?;
+		if self.pov_attribution {
+			state_with_tracking.enable_proof_size_attribution();
+		}
 
 		let method =
 			execution_method_from_cli(self.wasm_method, self.wasmtime_instantiation_strategy);
@@ -323,6 +327,7 @@ impl PalletCmd {
 		// Maps (pallet, extrinsic) to its component ranges.
 		let mut component_ranges = HashMap::<(Vec<u8>, Vec<u8>), Vec<ComponentRange>>::new();
 		let pov_modes = Self::parse_pov_modes(&benchmarks_to_run)?;
+		let mut pov_attributions = Vec::new();
 
 		for (pallet, extrinsic, components, _) in benchmarks_to_run.clone() {
 			log::info!(
@@ -449,6 +454,14 @@ impl PalletCmd {
 						.map_err(|e| format!("Failed to decode benchmark results: {:?}", e))??;
 
 					batches_db.extend(batch);
+
+					// Only the attribution of the last run is kept, which has the highest
+					// component values.
+					if let Some(attribution) = state.take_proof_size_attribution() {
+						if s + 1 == all_components.len() {
+							pov_attributions.push((pallet.clone(), extrinsic.clone(), attribution));
+						}
+					}
 				}
 				// Finally run a bunch of loops to get extrinsic timing information.
 				for r in 0..self.external_repeat {
@@ -509,7 +522,13 @@ impl PalletCmd {
 		// Combine all of the benchmark results, so that benchmarks of the same pallet/function
 		// are together.
 		let batches = combine_batches(batches, batches_db);
-		self.output(&batches, &storage_info, &component_ranges, pov_modes)
+		self.output(&batches, &storage_info, &component_ranges, pov_modes)?;
+
+		if !self.json_output {
+			print_pov_attributions(&pov_attributions, &storage_info);
+		}
+
+		Ok(())
 	}
 
 	fn output(
@@ -772,3 +791,68 @@ fn list_benchmark(
 		println!("{}, {}", String::from_utf8_lossy(&pallet), String::from_utf8_lossy(&extrinsic));
 	}
 }
+
+/// Print how much each storage item and pallet contributed to the proof of each benchmark.
+fn print_pov_attributions(
+	attributions: &[(Vec<u8>, Vec<u8>, ProofSizeAttribution)],
+	storage_info: &[StorageInfo],
+) {
+	let item_names = storage_info
+		.iter()
+		.map(|info| {
+			let name = format!(
+				"{}::{}",
+				String::from_utf8_lossy(&info.pallet_name),
+				String::from_utf8_lossy(&info.storage_name)
+			);
+			(info.prefix.clone(), name)
+		})
+		.collect::<HashMap<_, _>>();
+	let pallet_names = storage_info
+		.iter()
+		.filter(|info| info.prefix.len() >= 16)
+		.map(|info| (info.prefix[..16].to_vec(), String::from_utf8_lossy(&info.pallet_name)))
+		.collect::<HashMap<_, _>>();
+
+	let name_of = |prefix: &[u8]| -> String {
+		if let Some(name) = item_names.get(prefix) {
+			return name.clone()
+		}
+		// Well known keys like `:code` are not hashed.
+		if prefix.starts_with(b":") {
+			return String::from_utf8_lossy(prefix).into_owned()
+		}
+		match prefix.get(..16).and_then(|pallet| pallet_names.get(pallet)) {
+			Some(pallet) if prefix.len() > 16 =>
+				format!("{}::{}", pallet, array_bytes::bytes2hex("0x", &prefix[16..])),
+			Some(pallet) => pallet.to_string(),
+			None => array_bytes::bytes2hex("0x", prefix),
+		}
+	};
+
+	for (pallet, extrinsic, attribution) in attributions {
+		let total = attribution.total().max(1);
+		let print_line = |name: &str, bytes: usize| {
+			println!("{:<64} {:>10} {:>6.2}%", name, bytes, bytes as f64 * 100.0 / total as f64);
+		};
+
+		println!(
+			"Proof size attribution of {}::{} ({} bytes)\n========",
+			String::from_utf8_lossy(pallet),
+			String::from_utf8_lossy(extrinsic),
+			attribution.total(),
+		);
+		println!("-- Storage items --");
+		for (prefix, bytes) in attribution.by_prefix(32) {
+			print_line(&name_of(&prefix), bytes);
+		}
+		if attribution.unattributed > 0 {
+			print_line("Unattributed", attribution.unattributed);
+		}
+		println!("-- Pallets --");
+		for (prefix, bytes) in attribution.by_prefix(16) {
+			print_line(&name_of(&prefix), bytes);
+		}
+		println!();
+	}
+}
@@ -171,6 +171,14 @@ pub struct PalletCmd {
 	#[arg(long)]
 	pub no_storage_info: bool,
 
+	/// Print how much each storage item contributed to the proof size of the benchmarked call.
+	///
+	/// The proof is attributed to the storage keys whose access caused the trie nodes to be
+	/// recorded. It is reported for the last measured run of every benchmark, which uses the
+	/// highest component values.
+	#[arg(long)]
+	pub pov_attribution: bool,
+
 	/// The assumed default maximum size of any `StorageMap`.
 	///
 	/// When the maximum size of a map is not defined by the runtime developer,
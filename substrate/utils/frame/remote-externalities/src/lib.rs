@@ -18,29 +18,38 @@
 //! # Remote Externalities
 //!
 //! An equivalent of `sp_io::TestExternalities` that can load its state from a remote substrate
-//! based chain, or a local state snapshot file.
+//! based chain, a local node database, or a local state snapshot file.
 
 use codec::{Compact, Decode, Encode};
 use indicatif::{ProgressBar, ProgressStyle};
 use jsonrpsee::{
-	core::params::ArrayParams,
+	core::{client::SubscriptionKind, params::ArrayParams},
 	http_client::{HttpClient, HttpClientBuilder},
+	types::SubscriptionId,
 };
 use log::*;
-use serde::de::DeserializeOwned;
+#[cfg(feature = "database")]
+use sc_client_api::Backend as _;
+#[cfg(feature = "database")]
+use sc_client_db::{BlocksPruning, DatabaseSettings, DatabaseSource};
+#[cfg(feature = "database")]
+use sc_executor_common::runtime_blob::RuntimeBlob;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+#[cfg(feature = "database")]
+use sp_blockchain::HeaderBackend;
 use sp_core::{
 	hashing::twox_128,
 	hexdisplay::HexDisplay,
 	storage::{
-		well_known_keys::{is_default_child_storage_key, DEFAULT_CHILD_STORAGE_KEY_PREFIX},
+		well_known_keys::{self, is_default_child_storage_key, DEFAULT_CHILD_STORAGE_KEY_PREFIX},
 		ChildInfo, ChildType, PrefixedStorageKey, StorageData, StorageKey,
 	},
 };
 use sp_runtime::{
-	traits::{Block as BlockT, HashingFor},
+	traits::{Block as BlockT, HashingFor, Header as _},
 	StateVersion,
 };
-use sp_state_machine::TestExternalities;
+use sp_state_machine::{Backend as _, IterArgs, TestExternalities};
 use spinners::{Spinner, Spinners};
 use std::{
 	cmp::{max, min},
@@ -50,7 +59,9 @@ use std::{
 	sync::Arc,
 	time::{Duration, Instant},
 };
-use substrate_rpc_client::{rpc_params, BatchRequestBuilder, ChainApi, ClientT, StateApi};
+use substrate_rpc_client::{
+	rpc_params, ws_client, BatchRequestBuilder, ChainApi, ClientT, StateApi, WsClient,
+};
 use tokio_retry::{strategy::FixedInterval, Retry};
 
 type KeyValue = (StorageKey, StorageData);
@@ -61,6 +72,8 @@ type SnapshotVersion = Compact<u16>;
 const LOG_TARGET: &str = "remote-ext";
 const DEFAULT_HTTP_ENDPOINT: &str = "https://rpc.polkadot.io:443";
 const SNAPSHOT_VERSION: SnapshotVersion = Compact(3);
+/// Number of key-value pairs read from a database before they are inserted into the externalities.
+const DATABASE_INSERT_BATCH: usize = 100_000;
 
 /// The snapshot that we store on disk.
 #[derive(Decode, Encode)]
@@ -104,6 +117,138 @@ impl<B: BlockT> Snapshot<B> {
 	}
 }
 
+/// A query item of `archive_unstable_storageDiff`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StorageDiffItem {
+	/// All descendants of this key are compared.
+	key: StorageKey,
+	/// Either `value` or `hash`, we always need the former.
+	return_type: &'static str,
+	/// The child trie to compare, if any.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	child_trie_key: Option<StorageKey>,
+}
+
+impl StorageDiffItem {
+	fn values(key: Vec<u8>, child_trie_key: Option<Vec<u8>>) -> Self {
+		Self {
+			key: StorageKey(key),
+			return_type: "value",
+			child_trie_key: child_trie_key.map(StorageKey),
+		}
+	}
+}
+
+/// The kind of change reported by `archive_unstable_storageDiff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum StorageDiffOperation {
+	Added,
+	Modified,
+	Deleted,
+}
+
+/// A single difference reported by `archive_unstable_storageDiff`.
+#[derive(Debug, Clone, Deserialize)]
+struct StorageDiffEvent {
+	key: StorageKey,
+	/// The new value, or the old one for deleted keys.
+	value: Option<StorageData>,
+	#[serde(rename = "type")]
+	operation: StorageDiffOperation,
+}
+
+/// The result of `archive_unstable_storageDiff`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum StorageDiffResult {
+	Ok { result: Vec<StorageDiffEvent> },
+	Err { error: String },
+}
+
+/// A `value` query item of `archive_unstable_storage`.
+#[derive(Debug, Clone, Serialize)]
+struct StorageValueItem {
+	key: StorageKey,
+	#[serde(rename = "type")]
+	query_type: &'static str,
+}
+
+/// A single value reported by `archive_unstable_storage`.
+#[derive(Debug, Clone, Deserialize)]
+struct StorageValueEvent {
+	value: StorageData,
+}
+
+/// The result of `archive_unstable_storage`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum StorageValueResult {
+	Ok { result: Vec<StorageValueEvent> },
+	Err { error: String },
+}
+
+/// The error the remote node reports for a storage diff that compares more keys than it allows,
+/// see `archive_unstable_storageDiff`.
+const STORAGE_DIFF_TOO_MANY_KEYS: &str = "narrow down the queried keys";
+
+/// The methods exposed by a node, as returned by `rpc_methods`.
+#[derive(Debug, Clone, Deserialize)]
+struct RpcMethods {
+	methods: Vec<String>,
+}
+
+/// The `archive_unstable_storageDiff` queries that cover the given `prefixes` and `keys`.
+///
+/// Prefixes that are covered by another prefix, and keys that are covered by a prefix, are
+/// dropped, so that no difference is queried twice. The returned flag is `true` for exact keys,
+/// whose descendants are not of interest.
+fn storage_diff_queries(prefixes: &[Vec<u8>], keys: &[Vec<u8>]) -> Vec<(Vec<u8>, bool)> {
+	let mut prefixes = prefixes.to_vec();
+	// A prefix is sorted before all the keys it covers.
+	prefixes.sort();
+	prefixes.dedup();
+
+	let mut queries: Vec<(Vec<u8>, bool)> = Vec::new();
+	for prefix in prefixes {
+		if !queries.iter().any(|(covering, _)| prefix.starts_with(covering)) {
+			queries.push((prefix, false));
+		}
+	}
+
+	let covered = queries.len();
+	for key in keys {
+		if !queries[..covered].iter().any(|(prefix, _)| key.starts_with(prefix)) &&
+			!queries[covered..].iter().any(|(other, _)| other == key)
+		{
+			queries.push((key.clone(), true));
+		}
+	}
+
+	queries
+}
+
+/// Apply `events` to the storage of the current externalities, either the top trie or the child
+/// trie with the given `child_storage_key`.
+fn apply_storage_diff(
+	child_storage_key: Option<&[u8]>,
+	events: Vec<StorageDiffEvent>,
+) -> Result<(), &'static str> {
+	for StorageDiffEvent { key, value, operation } in events {
+		match (operation, value, child_storage_key) {
+			(StorageDiffOperation::Deleted, _, None) => sp_io::storage::clear(&key.0),
+			(StorageDiffOperation::Deleted, _, Some(child)) =>
+				sp_io::default_child_storage::clear(child, &key.0),
+			(_, Some(value), None) => sp_io::storage::set(&key.0, &value.0),
+			(_, Some(value), Some(child)) =>
+				sp_io::default_child_storage::set(child, &key.0, &value.0),
+			(_, None, _) => return Err("storage diff is missing the value of a changed key"),
+		}
+	}
+	Ok(())
+}
+
 /// An externalities that acts exactly the same as [`sp_io::TestExternalities`] but has a few extra
 /// bits and pieces to it, and can be loaded remotely.
 pub struct RemoteExternalities<B: BlockT> {
@@ -135,6 +280,15 @@ pub enum Mode<B: BlockT> {
 	Offline(OfflineConfig),
 	/// Prefer using a snapshot file if it exists, else use a remote server.
 	OfflineOrElseOnline(OfflineConfig, OnlineConfig<B>),
+	/// Read the state from the database of a local node. Potentially writes to a snapshot file.
+	#[cfg(feature = "database")]
+	Database(DatabaseConfig<B>),
+	/// Bring the state snapshot of the offline config to the block of the online config, only
+	/// downloading the keys that changed in between. Potentially writes to a snapshot file.
+	///
+	/// The remote node must expose `archive_unstable_storageDiff`, which archive nodes do, and
+	/// still have the state of the snapshot's block.
+	Update(OfflineConfig, OnlineConfig<B>),
 }
 
 impl<B: BlockT> Default for Mode<B> {
@@ -152,6 +306,45 @@ pub struct OfflineConfig {
 	pub state_snapshot: SnapshotConfig,
 }
 
+/// The kind of database that is read in [`Mode::Database`].
+#[cfg(feature = "database")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatabaseBackend {
+	/// A RocksDB database.
+	RocksDb,
+	/// A ParityDB database.
+	ParityDb,
+}
+
+/// Configuration of the execution from a local node database.
+///
+/// The database is opened read-only, so the node owning it may keep running. A state snapshot
+/// config may be present and will be written to in that case.
+#[cfg(feature = "database")]
+#[derive(Clone)]
+pub struct DatabaseConfig<B: BlockT> {
+	/// The path to the database, e.g. `<base-path>/chains/<chain-id>/db/full`.
+	pub path: PathBuf,
+	/// The kind of database found at `path`.
+	pub backend: DatabaseBackend,
+	/// The block hash at which to read the state. Will be the latest finalized block if not
+	/// provided. The state of this block must not have been pruned.
+	pub at: Option<B::Hash>,
+	/// An optional state snapshot file to WRITE to, not for reading. Not written if set to `None`.
+	pub state_snapshot: Option<SnapshotConfig>,
+	/// Load the child tries as well if set to true.
+	pub child_trie: bool,
+}
+
+#[cfg(feature = "database")]
+impl<B: BlockT> DatabaseConfig<B> {
+	/// Read the whole state, including child tries, at the latest finalized block of the database
+	/// found at `path`.
+	pub fn new<P: Into<PathBuf>>(path: P, backend: DatabaseBackend) -> Self {
+		Self { path: path.into(), backend, at: None, state_snapshot: None, child_trie: true }
+	}
+}
+
 /// Description of the transport protocol (for online execution).
 #[derive(Debug, Clone)]
 pub enum Transport {
@@ -313,6 +506,8 @@ pub struct Builder<B: BlockT> {
 	///
 	/// Overwrite only with care.
 	overwrite_state_version: Option<StateVersion>,
+	/// WebSocket connection to stream the state with, if the transport is a WebSocket URI.
+	stream_client: Option<Arc<WsClient>>,
 }
 
 impl<B: BlockT> Default for Builder<B> {
@@ -322,6 +517,7 @@ impl<B: BlockT> Default for Builder<B> {
 			hashed_key_values: Default::default(),
 			hashed_blacklist: Default::default(),
 			overwrite_state_version: None,
+			stream_client: None,
 		}
 	}
 }
@@ -332,6 +528,7 @@ impl<B: BlockT> Builder<B> {
 		match &self.mode {
 			Mode::Online(config) => config,
 			Mode::OfflineOrElseOnline(_, config) => config,
+			Mode::Update(_, config) => config,
 			_ => panic!("Unexpected mode: Online"),
 		}
 	}
@@ -340,6 +537,7 @@ impl<B: BlockT> Builder<B> {
 		match &mut self.mode {
 			Mode::Online(config) => config,
 			Mode::OfflineOrElseOnline(_, config) => config,
+			Mode::Update(_, config) => config,
 			_ => panic!("Unexpected mode: Online"),
 		}
 	}
@@ -360,6 +558,7 @@ where
 	const DEFAULT_KEY_DOWNLOAD_PAGE: u32 = 1000;
 	const MAX_RETRIES: usize = 12;
 	const KEYS_PAGE_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+	const STORAGE_ITER_PAGE_SIZE: u32 = 1000;
 
 	async fn rpc_get_storage(
 		&self,
@@ -373,6 +572,154 @@ where
 		})
 	}
 
+	/// Get the differences between the storage at `previous` and `at` for the descendants of
+	/// `item`.
+	async fn rpc_get_storage_diff(
+		&self,
+		item: StorageDiffItem,
+		at: B::Hash,
+		previous: B::Hash,
+	) -> Result<StorageDiffResult, &'static str> {
+		trace!(target: LOG_TARGET, "rpc: archive_unstable_storageDiff");
+		self.as_online()
+			.rpc_client()
+			.request::<StorageDiffResult, _>(
+				"archive_unstable_storageDiff",
+				rpc_params![at, vec![item], previous],
+			)
+			.await
+			.map_err(|e| {
+				error!(target: LOG_TARGET, "Error = {:?}", e);
+				"rpc archive_unstable_storageDiff failed."
+			})
+	}
+
+	/// Get the differences between the storage at `previous` and `at` for the descendants of
+	/// `key`, in the child trie `child_trie_key` if given.
+	///
+	/// The remote node bounds the number of keys a single diff compares. Queries that exceed the
+	/// bound are split by the next byte of the key, until every query is small enough.
+	async fn rpc_get_storage_diff_split(
+		&self,
+		key: Vec<u8>,
+		child_trie_key: Option<Vec<u8>>,
+		at: B::Hash,
+		previous: B::Hash,
+	) -> Result<Vec<StorageDiffEvent>, &'static str> {
+		let mut events = Vec::new();
+		let mut pending = vec![key];
+		while let Some(prefix) = pending.pop() {
+			let item = StorageDiffItem::values(prefix.clone(), child_trie_key.clone());
+			match self.rpc_get_storage_diff(item, at, previous).await? {
+				StorageDiffResult::Ok { result } => events.extend(result),
+				StorageDiffResult::Err { error } if error.contains(STORAGE_DIFF_TOO_MANY_KEYS) => {
+					debug!(
+						target: LOG_TARGET,
+						"splitting the storage diff of {}",
+						HexDisplay::from(&prefix)
+					);
+
+					// The narrower queries don't cover the key that equals the prefix.
+					let key = StorageKey(prefix.clone());
+					let value = self.rpc_get_archive_value(&key, &child_trie_key, at).await?;
+					let previous_value =
+						self.rpc_get_archive_value(&key, &child_trie_key, previous).await?;
+					let operation = match (&previous_value, &value) {
+						(None, Some(_)) => Some(StorageDiffOperation::Added),
+						(Some(_), None) => Some(StorageDiffOperation::Deleted),
+						(Some(old), Some(new)) if old != new =>
+							Some(StorageDiffOperation::Modified),
+						_ => None,
+					};
+					if let Some(operation) = operation {
+						events.push(StorageDiffEvent {
+							key,
+							value: value.or(previous_value),
+							operation,
+						});
+					}
+
+					pending.extend((0..=u8::MAX).rev().map(|byte| [&prefix[..], &[byte]].concat()));
+				},
+				StorageDiffResult::Err { error } => {
+					error!(target: LOG_TARGET, "Error = {:?}", error);
+					return Err("rpc archive_unstable_storageDiff returned an error.")
+				},
+			}
+		}
+
+		Ok(events)
+	}
+
+	/// Get the value of `key` at `at` through `archive_unstable_storage`, in the child trie
+	/// `child_trie_key` if given.
+	async fn rpc_get_archive_value(
+		&self,
+		key: &StorageKey,
+		child_trie_key: &Option<Vec<u8>>,
+		at: B::Hash,
+	) -> Result<Option<StorageData>, &'static str> {
+		trace!(target: LOG_TARGET, "rpc: archive_unstable_storage");
+		let item = StorageValueItem { key: key.clone(), query_type: "value" };
+		let result = self
+			.as_online()
+			.rpc_client()
+			.request::<StorageValueResult, _>(
+				"archive_unstable_storage",
+				rpc_params![at, vec![item], child_trie_key.clone().map(StorageKey)],
+			)
+			.await
+			.map_err(|e| {
+				error!(target: LOG_TARGET, "Error = {:?}", e);
+				"rpc archive_unstable_storage failed."
+			})?;
+
+		match result {
+			StorageValueResult::Ok { result } => Ok(result.into_iter().next().map(|v| v.value)),
+			StorageValueResult::Err { error } => {
+				error!(target: LOG_TARGET, "Error = {:?}", error);
+				Err("rpc archive_unstable_storage returned an error.")
+			},
+		}
+	}
+
+	/// Ensure that the remote node serves `archive_unstable_storageDiff`.
+	async fn rpc_ensure_storage_diff(&self) -> Result<(), &'static str> {
+		trace!(target: LOG_TARGET, "rpc: rpc_methods");
+		let RpcMethods { methods } = self
+			.as_online()
+			.rpc_client()
+			.request::<RpcMethods, _>("rpc_methods", rpc_params![])
+			.await
+			.map_err(|e| {
+				error!(target: LOG_TARGET, "Error = {:?}", e);
+				"rpc rpc_methods failed."
+			})?;
+
+		if !methods.iter().any(|method| method == "archive_unstable_storageDiff") {
+			error!(
+				target: LOG_TARGET,
+				"the remote node doesn't serve `archive_unstable_storageDiff`, only archive nodes \
+				do. Use an archive node or download the state again."
+			);
+			return Err("remote node doesn't serve archive_unstable_storageDiff")
+		}
+
+		Ok(())
+	}
+
+	/// Get the header of the given block.
+	async fn rpc_get_header(&self, at: B::Hash) -> Result<B::Header, &'static str> {
+		trace!(target: LOG_TARGET, "rpc: header");
+		ChainApi::<(), _, B::Header, ()>::header(self.as_online().rpc_client(), Some(at))
+			.await
+			.map_err(|e| {
+				error!(target: LOG_TARGET, "Error = {:?}", e);
+				"rpc header failed."
+			})?
+			.ok_or("header not found")
+	}
+
 	/// Get the latest finalized head.
 	async fn rpc_get_head(&self) -> Result<B::Hash, &'static str> {
 		trace!(target: LOG_TARGET, "rpc: finalized_head");
@@ -681,16 +1028,157 @@ where
 		Ok(all_data)
 	}
 
-	/// Synonym of `getPairs` that uses paged queries to first get the keys, and then
-	/// map them to values one by one.
+	/// Stream the key/value pairs under `prefix` and of the child tries stored under them with
+	/// `state_subscribeStorageIter`.
 	///
+	/// An interrupted iteration is resumed from the last received page.
+	async fn rpc_stream_pairs(
+		client: &WsClient,
+		prefix: &StorageKey,
+		at: B::Hash,
+	) -> Result<(TopKeyValues, ChildKeyValues), &'static str> {
+		let mut top_kv = Vec::new();
+		let mut child_kv: ChildKeyValues = Vec::new();
+		let mut cursor = None;
+		let mut retries = 0;
+
+		let bar = ProgressBar::new_spinner();
+		bar.enable_steady_tick(Duration::from_secs(1));
+		bar.set_message("Streaming key values".to_string());
+		bar.set_style(
+			ProgressStyle::with_template("[{elapsed_precise}] {msg} {per_sec} {pos}").unwrap(),
+		);
+
+		loop {
+			let subscription = StateApi::<B::Hash>::subscribe_storage_iter(
+				client,
+				Some(prefix.clone()),
+				true,
+				Some(Self::STORAGE_ITER_PAGE_SIZE),
+				cursor.clone(),
+				Some(at),
+			)
+			.await;
+			let mut subscription = match subscription {
+				Ok(subscription) => subscription,
+				// The node most likely does not support streaming the state.
+				Err(e) if cursor.is_none() => {
+					debug!(target: LOG_TARGET, "Error = {:?}", e);
+					return Err("rpc state_subscribeStorageIter failed.")
+				},
+				Err(e) => {
+					warn!(target: LOG_TARGET, "failed to resume storage iteration: {:?}", e);
+					retries += 1;
+					if retries > Self::MAX_RETRIES {
+						return Err("storage iteration failed too often")
+					}
+					tokio::time::sleep(Self::KEYS_PAGE_RETRY_INTERVAL).await;
+					continue
+				},
+			};
+			let subscription_id = match subscription.kind() {
+				SubscriptionKind::Subscription(SubscriptionId::Num(num)) => num.to_string(),
+				SubscriptionKind::Subscription(SubscriptionId::Str(id)) => id.to_string(),
+				SubscriptionKind::Method(_) => return Err("unexpected subscription kind"),
+			};
+
+			while let Some(page) = subscription.next().await {
+				let page = match page {
+					Ok(page) => page,
+					Err(e) => {
+						warn!(target: LOG_TARGET, "storage iteration interrupted: {:?}", e);
+						break
+					},
+				};
+
+				// Request the next page right away, so that the node keeps a few pages in flight.
+				if page.cursor.is_some() {
+					let _ = StateApi::<B::Hash>::storage_iter_continue(
+						client,
+						subscription_id.clone(),
+						1,
+					)
+					.await;
+				}
+
+				bar.inc(page.pairs.len() as u64);
+				match page.child_storage_key {
+					None => top_kv.extend(page.pairs),
+					Some(child_root) => {
+						let info = match ChildType::from_prefixed_key(&child_root) {
+							Some((ChildType::ParentKeyId, storage_key)) =>
+								ChildInfo::new_default(storage_key),
+							None => {
+								log::error!(target: LOG_TARGET, "invalid key: {:?}", child_root);
+								return Err("Invalid child key")
+							},
+						};
+						match child_kv.last_mut() {
+							Some((last_info, pairs))
+								if last_info.storage_key() == info.storage_key() =>
+								pairs.extend(page.pairs),
+							_ => child_kv.push((info, page.pairs)),
+						}
+					},
+				}
+
+				cursor = page.cursor;
+				if cursor.is_none() {
+					bar.finish_with_message("✅ Streamed key values");
+					let _ = subscription.unsubscribe().await;
+					return Ok((top_kv, child_kv))
+				}
+			}
+
+			retries += 1;
+			if retries > Self::MAX_RETRIES {
+				return Err("storage iteration failed too often")
+			}
+			tokio::time::sleep(Self::KEYS_PAGE_RETRY_INTERVAL).await;
+		}
+	}
+
+	/// Get the key/value pairs under `prefix` and insert them into `pending_ext`.
+	///
+	/// The pairs are streamed if the node supports it. Otherwise, this is a synonym of `getPairs`
+	/// that uses paged queries to first get the keys, and then map them to values one by one.
 	/// This can work with public nodes. But, expect it to be darn slow.
+	///
+	/// Child trie roots are only returned if their child tries were not streamed.
 	pub(crate) async fn rpc_get_pairs(
 		&self,
 		prefix: StorageKey,
 		at: B::Hash,
 		pending_ext: &mut TestExternalities<HashingFor<B>>,
 	) -> Result<Vec<KeyValue>, &'static str> {
+		if let Some(client) = self.stream_client.as_deref() {
+			match Self::rpc_stream_pairs(client, &prefix, at).await {
+				Ok((top_kv, child_kv)) => {
+					let (child_roots, top_kv): (Vec<_>, Vec<_>) =
+						top_kv.into_iter().partition(|(k, _)| is_default_child_storage_key(&k.0));
+					debug!(
+						target: LOG_TARGET,
+						"streamed {} top keys and {} child tries",
+						top_kv.len(),
+						child_roots.len(),
+					);
+
+					pending_ext.batch_insert(top_kv.iter().cloned().map(|(k, v)| (k.0, v.0)));
+					for (info, key_values) in child_kv {
+						for (k, v) in key_values {
+							pending_ext.insert_child(info.clone(), k.0, v.0);
+						}
+					}
+					return Ok(top_kv)
+				},
+				Err(e) => info!(
+					target: LOG_TARGET,
+					"streaming the state failed ({}), falling back to paged queries",
+					e
+				),
+			}
+		}
+
 		let start = Instant::now();
 		let mut sp = Spinner::with_timer(Spinners::Dots, "Scraping keys...".into());
 		// TODO We could start downloading when having collected the first batch of keys
@@ -983,9 +1471,24 @@ where
 	///
 	/// initializes the remote client in `transport`, and sets the `at` field, if not specified.
 	async fn init_remote_client(&mut self) -> Result<(), &'static str> {
+		// The state is streamed in small pages, which the WebSocket connection can handle.
+		let ws_uri = match &self.as_online().transport {
+			Transport::Uri(uri) if uri.starts_with("ws://") || uri.starts_with("wss://") =>
+				Some(uri.clone()),
+			_ => None,
+		};
+
 		// First, initialize the http client.
 		self.as_online_mut().transport.init().await?;
 
+		if let Some(ws_uri) = ws_uri {
+			self.stream_client = ws_client(ws_uri)
+				.await
+				.map_err(|e| warn!(target: LOG_TARGET, "failed to build ws client: {:?}", e))
+				.ok()
+				.map(Arc::new);
+		}
+
 		// Then, if `at` is not set, set it.
 		if self.as_online().at.is_none() {
 			let at = self.rpc_get_head().await?;
@@ -1053,35 +1556,40 @@ where
 
 		// If we need to save a snapshot, save the raw storage and root hash to the snapshot.
 		if let Some(path) = self.as_online().state_snapshot.clone().map(|c| c.path) {
-			let (raw_storage, storage_root) = pending_ext.into_raw_snapshot();
-			let snapshot = Snapshot::<B>::new(
-				state_version,
-				self.as_online()
-					.at
-					.expect("set to `Some` in `init_remote_client`; must be called before; qed"),
-				raw_storage.clone(),
-				storage_root,
-			);
-			let encoded = snapshot.encode();
-			log::info!(
-				target: LOG_TARGET,
-				"writing snapshot of {} bytes to {:?}",
-				encoded.len(),
-				path
-			);
-			std::fs::write(path, encoded).map_err(|_| "fs::write failed")?;
-
-			// pending_ext was consumed when creating the snapshot, need to reinitailize it
-			return Ok(TestExternalities::from_raw_snapshot(
-				raw_storage,
-				storage_root,
-				self.overwrite_state_version.unwrap_or(state_version),
-			))
+			let at = self
+				.as_online()
+				.at
+				.expect("set to `Some` in `init_remote_client`; must be called before; qed");
+			return self.save_snapshot(pending_ext, state_version, at, path)
 		}
 
 		Ok(pending_ext)
 	}
 
+	/// Write the state of `ext` at `block_hash` to a snapshot file at `path`.
+	///
+	/// `ext` is consumed when creating the snapshot, an equivalent one is returned.
+	fn save_snapshot(
+		&self,
+		ext: TestExternalities<HashingFor<B>>,
+		state_version: StateVersion,
+		block_hash: B::Hash,
+		path: PathBuf,
+	) -> Result<TestExternalities<HashingFor<B>>, &'static str> {
+		let (raw_storage, storage_root) = ext.into_raw_snapshot();
+		let snapshot =
+			Snapshot::<B>::new(state_version, block_hash, raw_storage.clone(), storage_root);
+		let encoded = snapshot.encode();
+		log::info!(target: LOG_TARGET, "writing snapshot of {} bytes to {:?}", encoded.len(), path);
+		std::fs::write(path, encoded).map_err(|_| "fs::write failed")?;
+
+		Ok(TestExternalities::from_raw_snapshot(
+			raw_storage,
+			storage_root,
+			self.overwrite_state_version.unwrap_or(state_version),
+		))
+	}
+
 	async fn do_load_remote(&mut self) -> Result<RemoteExternalities<B>, &'static str> {
 		self.init_remote_client().await?;
 		let block_hash = self.as_online().at_expected();
@@ -1109,9 +1617,275 @@ where
 		Ok(RemoteExternalities { inner_ext, block_hash })
 	}
 
+	/// Open the database described by `config` read-only and copy its state at `config.at` into a
+	/// fresh externalities.
+	#[cfg(feature = "database")]
+	fn do_load_database(
+		&mut self,
+		config: DatabaseConfig<B>,
+	) -> Result<RemoteExternalities<B>, &'static str> {
+		info!(target: LOG_TARGET, "Opening {:?} database at {:?}", config.backend, &config.path);
+		let source = match config.backend {
+			DatabaseBackend::RocksDb =>
+				DatabaseSource::RocksDb { path: config.path.clone(), cache_size: 128 },
+			DatabaseBackend::ParityDb => DatabaseSource::ParityDb { path: config.path.clone() },
+		};
+		let backend = sc_client_db::Backend::<B>::new_read_only(DatabaseSettings {
+			trie_cache_maximum_size: None,
+			trie_cache_snapshot: None,
+			// Use whatever pruning mode the node stored in the database.
+			state_pruning: None,
+			source,
+			blocks_pruning: BlocksPruning::KeepAll,
+		})
+		.map_err(|e| {
+			error!(target: LOG_TARGET, "Error = {:?}", e);
+			"failed to open database"
+		})?;
+
+		let block_hash = match config.at {
+			Some(at) => at,
+			None => {
+				let at = backend.blockchain().info().finalized_hash;
+				log::info!(
+					target: LOG_TARGET,
+					"since no at is provided, setting it to latest finalized head, {:?}",
+					at
+				);
+				at
+			},
+		};
+		let state = backend.state_at(block_hash).map_err(|e| {
+			error!(target: LOG_TARGET, "Error = {:?}", e);
+			"state of the requested block is not available in the database, it may be pruned"
+		})?;
+
+		let state_version =
+			match self.overwrite_state_version {
+				Some(state_version) => state_version,
+				None => {
+					let code = state
+						.storage(well_known_keys::CODE)
+						.map_err(|e| {
+							error!(target: LOG_TARGET, "Error = {:?}", e);
+							"failed to read `:code` from database"
+						})?
+						.ok_or("`:code` is missing from the state")?;
+					let blob = RuntimeBlob::uncompress_if_needed(&code)
+						.map_err(|_| "failed to decompress `:code`")?;
+					sc_executor::read_embedded_version(&blob)
+					.map_err(|_| "failed to read runtime version from `:code`")?
+					.ok_or("runtime has no embedded version, the state version must be overwritten")?
+					.state_version()
+				},
+			};
+
+		let start = Instant::now();
+		let mut sp = Spinner::with_timer(Spinners::Dots, "Reading state from database...".into());
+		let mut pending_ext = TestExternalities::new_with_code_and_state(
+			Default::default(),
+			Default::default(),
+			state_version,
+		);
+
+		let read_err = |e: String| {
+			error!(target: LOG_TARGET, "Error = {:?}", e);
+			"failed to read state from database"
+		};
+
+		let mut top_count = 0;
+		let mut child_roots = Vec::new();
+		let mut batch = Vec::with_capacity(DATABASE_INSERT_BATCH);
+		for pair in state.pairs(Default::default()).map_err(read_err)? {
+			let (key, value) = pair.map_err(read_err)?;
+			// Child tries are inserted separately below, which also sets their root in the top
+			// trie.
+			if is_default_child_storage_key(&key) {
+				if config.child_trie {
+					child_roots.push(key);
+				}
+				continue
+			}
+			batch.push((key, value));
+			if batch.len() == DATABASE_INSERT_BATCH {
+				top_count += batch.len();
+				pending_ext.batch_insert(std::mem::take(&mut batch));
+			}
+		}
+		top_count += batch.len();
+		pending_ext.batch_insert(batch);
+
+		let mut child_count = 0;
+		for prefixed_top_key in &child_roots {
+			let un_prefixed =
+				match ChildType::from_prefixed_key(PrefixedStorageKey::new_ref(prefixed_top_key)) {
+					Some((ChildType::ParentKeyId, storage_key)) => storage_key,
+					None => {
+						log::error!(target: LOG_TARGET, "invalid key: {:?}", prefixed_top_key);
+						return Err("Invalid child key")
+					},
+				};
+			let info = ChildInfo::new_default(un_prefixed);
+			let mut args = IterArgs::default();
+			args.child_info = Some(info.clone());
+			for pair in state.pairs(args).map_err(read_err)? {
+				let (key, value) = pair.map_err(read_err)?;
+				pending_ext.insert_child(info.clone(), key, value);
+				child_count += 1;
+			}
+		}
+
+		sp.stop_with_message(format!(
+			"✅ Read {} top keys and {} keys from {} child tries ({:.2}s)",
+			top_count,
+			child_count,
+			child_roots.len(),
+			start.elapsed().as_secs_f32()
+		));
+
+		let inner_ext = match config.state_snapshot {
+			Some(SnapshotConfig { path }) =>
+				self.save_snapshot(pending_ext, state_version, block_hash, path)?,
+			None => pending_ext,
+		};
+
+		Ok(RemoteExternalities { inner_ext, block_hash })
+	}
+
+	/// Load the state snapshot of `config` and apply the storage changes between its block and the
+	/// block of the online config, as reported by the remote node.
+	async fn do_update_offline(
+		&mut self,
+		config: OfflineConfig,
+	) -> Result<RemoteExternalities<B>, &'static str> {
+		info!(target: LOG_TARGET, "Loading snapshot from {:?}", &config.state_snapshot.path);
+		let Snapshot {
+			snapshot_version: _,
+			block_hash: previous,
+			state_version,
+			raw_storage,
+			storage_root,
+		} = Snapshot::<B>::load(&config.state_snapshot.path)?;
+		let mut ext = TestExternalities::from_raw_snapshot(
+			raw_storage,
+			storage_root,
+			self.overwrite_state_version.unwrap_or(state_version),
+		);
+
+		self.init_remote_client().await?;
+		self.rpc_ensure_storage_diff().await?;
+		let at = self.as_online().at_expected();
+		if at == previous {
+			info!(target: LOG_TARGET, "snapshot is already at block {:?}", at);
+			return Ok(RemoteExternalities { inner_ext: ext, block_hash: at })
+		}
+		info!(target: LOG_TARGET, "updating snapshot from block {:?} to {:?}", previous, at);
+
+		let start = Instant::now();
+		let online_config = self.as_online();
+		let queries =
+			storage_diff_queries(&online_config.hashed_prefixes, &online_config.hashed_keys);
+
+		// Child tries are only known by their root in the top trie, so changed roots are collected
+		// first and the child tries are compared afterwards.
+		let mut top_changes = 0;
+		let mut child_roots = std::collections::BTreeMap::new();
+		for (key, exact) in queries {
+			let mut events =
+				self.rpc_get_storage_diff_split(key.clone(), None, at, previous).await?;
+			events.retain(|event| {
+				// The node reports all the descendants of the queried key.
+				if exact && event.key.0 != key {
+					return false
+				}
+				if !is_default_child_storage_key(&event.key.0) {
+					return true
+				}
+				if online_config.child_trie {
+					child_roots.insert(event.key.0.clone(), event.operation);
+				}
+				false
+			});
+			top_changes += events.len();
+			ext.execute_with(|| apply_storage_diff(None, events))?;
+		}
+
+		let mut child_changes = 0;
+		for (prefixed_top_key, operation) in &child_roots {
+			let storage_key =
+				match ChildType::from_prefixed_key(PrefixedStorageKey::new_ref(prefixed_top_key)) {
+					Some((ChildType::ParentKeyId, storage_key)) => storage_key,
+					None => {
+						log::error!(target: LOG_TARGET, "invalid key: {:?}", prefixed_top_key);
+						return Err("Invalid child key")
+					},
+				};
+
+			if *operation == StorageDiffOperation::Deleted {
+				ext.execute_with(|| {
+					let _ = sp_io::default_child_storage::storage_kill(storage_key, None);
+				});
+				continue
+			}
+
+			let events = self
+				.rpc_get_storage_diff_split(vec![], Some(storage_key.to_vec()), at, previous)
+				.await?;
+			child_changes += events.len();
+			ext.execute_with(|| apply_storage_diff(Some(storage_key), events))?;
+		}
+		ext.commit_all().map_err(|e| {
+			error!(target: LOG_TARGET, "Error = {:?}", e);
+			"failed to commit the storage changes"
+		})?;
+
+		info!(
+			target: LOG_TARGET,
+			"✅ applied {} top changes and {} changes in {} child tries ({:.2}s)",
+			top_changes,
+			child_changes,
+			child_roots.len(),
+			start.elapsed().as_secs_f32()
+		);
+
+		// The root can only be checked if the snapshot holds the entire state.
+		let online_config = self.as_online();
+		if online_config.child_trie && online_config.hashed_prefixes.iter().any(|p| p.is_empty()) {
+			let expected = *self.rpc_get_header(at).await?.state_root();
+			let root = *ext.as_backend().root();
+			if root != expected {
+				error!(
+					target: LOG_TARGET,
+					"state root after the update is {:?}, while block {:?} has {:?}",
+					root,
+					at,
+					expected
+				);
+				return Err("state root mismatch after updating the snapshot")
+			}
+			info!(target: LOG_TARGET, "✅ state root of the updated snapshot matches {:?}", at);
+		} else {
+			warn!(
+				target: LOG_TARGET,
+				"not checking the state root of the updated snapshot, since it only holds a part \
+				of the state"
+			);
+		}
+
+		let inner_ext = match self.as_online().state_snapshot.clone() {
+			Some(SnapshotConfig { path }) => self.save_snapshot(ext, state_version, at, path)?,
+			None => ext,
+		};
+
+		Ok(RemoteExternalities { inner_ext, block_hash: at })
+	}
+
 	pub(crate) async fn pre_build(mut self) -> Result<RemoteExternalities<B>, &'static str> {
 		let mut ext = match self.mode.clone() {
 			Mode::Offline(config) => self.do_load_offline(config)?,
+			#[cfg(feature = "database")]
+			Mode::Database(config) => self.do_load_database(config)?,
+			Mode::Update(offline_config, _) => self.do_update_offline(offline_config).await?,
 			Mode::Online(_) => self.do_load_remote().await?,
 			Mode::OfflineOrElseOnline(offline_config, _) => {
 				match self.do_load_offline(offline_config) {
@@ -1257,6 +2031,232 @@ mod tests {
 			.expect("Can't read state snapshot file")
 			.execute_with(|| assert!(sp_io::storage::get(&some_key).is_none()));
 	}
+
+	#[test]
+	fn storage_diff_queries_are_not_redundant() {
+		let prefixes =
+			vec![vec![1, 2], vec![1], vec![2, 1], DEFAULT_CHILD_STORAGE_KEY_PREFIX.to_vec()];
+		let keys = vec![vec![1, 3], vec![3, 1], vec![3, 1], vec![2]];
+
+		assert_eq!(
+			storage_diff_queries(&prefixes, &keys),
+			vec![
+				(vec![1], false),
+				(vec![2, 1], false),
+				(DEFAULT_CHILD_STORAGE_KEY_PREFIX.to_vec(), false),
+				(vec![3, 1], true),
+				(vec![2], true),
+			]
+		);
+
+		// The empty prefix covers everything, including the child tries.
+		let prefixes = vec![DEFAULT_CHILD_STORAGE_KEY_PREFIX.to_vec(), vec![]];
+		assert_eq!(storage_diff_queries(&prefixes, &keys), vec![(vec![], false)]);
+	}
+
+	#[test]
+	fn storage_diff_is_applied() {
+		let child_info = ChildInfo::new_default(b"child");
+		let mut ext = TestExternalities::<HashingFor<Block>>::new_with_code_and_state(
+			Default::default(),
+			Default::default(),
+			StateVersion::V1,
+		);
+		ext.insert(b"\x01".to_vec(), b"old".to_vec());
+		ext.insert(b"\x02".to_vec(), b"gone".to_vec());
+
+		let response = r#"{"result":[
+			{"key":"0x01","value":"0xaa","type":"modified"},
+			{"key":"0x02","value":"0xbb","type":"deleted"},
+			{"key":"0x03","value":"0xcc","type":"added"}
+		]}"#;
+		let StorageDiffResult::Ok { result } =
+			serde_json::from_str::<StorageDiffResult>(response).unwrap()
+		else {
+			panic!("response is not an error")
+		};
+		let error = r#"{"error":"block pruned"}"#;
+		assert!(matches!(
+			serde_json::from_str::<StorageDiffResult>(error).unwrap(),
+			StorageDiffResult::Err { .. }
+		));
+
+		ext.execute_with(|| {
+			apply_storage_diff(None, result.clone()).unwrap();
+			apply_storage_diff(Some(child_info.storage_key()), result).unwrap();
+
+			assert_eq!(sp_io::storage::get(b"\x01"), Some(vec![0xaa].into()));
+			assert_eq!(sp_io::storage::get(b"\x02"), None);
+			assert_eq!(sp_io::storage::get(b"\x03"), Some(vec![0xcc].into()));
+			assert_eq!(
+				sp_io::default_child_storage::get(child_info.storage_key(), b"\x03"),
+				Some(vec![0xcc])
+			);
+		});
+	}
+
+	#[cfg(feature = "database")]
+	#[tokio::test]
+	async fn can_load_from_database() {
+		use sc_client_api::{backend::NewBlockState, BlockImportOperation};
+		use sc_client_db::PruningMode;
+		use sp_core::storage::{Storage, StorageChild};
+		use sp_runtime::{testing::Header, traits::Header as _};
+
+		init_logger();
+		let dir = tempfile::tempdir().unwrap();
+		let child_info = ChildInfo::new_default(b"child");
+
+		// create a database with a single, finalized genesis block.
+		let genesis_hash = {
+			let backend = sc_client_db::Backend::<Block>::new(
+				DatabaseSettings {
+					trie_cache_maximum_size: None,
+					trie_cache_snapshot: None,
+					state_pruning: Some(PruningMode::ArchiveAll),
+					source: DatabaseSource::ParityDb { path: dir.path().into() },
+					blocks_pruning: BlocksPruning::KeepAll,
+				},
+				0,
+			)
+			.unwrap();
+			let mut op = backend.begin_operation().unwrap();
+			let storage = Storage {
+				top: [(b"foo".to_vec(), b"bar".to_vec())].into_iter().collect(),
+				children_default: [(
+					child_info.storage_key().to_vec(),
+					StorageChild {
+						data: [(b"baz".to_vec(), b"qux".to_vec())].into_iter().collect(),
+						child_info: child_info.clone(),
+					},
+				)]
+				.into_iter()
+				.collect(),
+			};
+			let state_root = op.reset_storage(storage, StateVersion::V1).unwrap();
+			let header = Header {
+				number: 0,
+				parent_hash: Default::default(),
+				state_root,
+				digest: Default::default(),
+				extrinsics_root: Default::default(),
+			};
+			let hash = header.hash();
+			op.set_block_data(header, Some(vec![]), None, None, NewBlockState::Final)
+				.unwrap();
+			backend.commit_operation(op).unwrap();
+			hash
+		};
+
+		let mut ext = Builder::<Block>::new()
+			.mode(Mode::Database(DatabaseConfig::new(dir.path(), DatabaseBackend::ParityDb)))
+			// the test state has no runtime to read the state version from.
+			.overwrite_state_version(StateVersion::V1)
+			.build()
+			.await
+			.unwrap();
+
+		assert_eq!(ext.block_hash, genesis_hash);
+		ext.execute_with(|| {
+			assert_eq!(sp_io::storage::get(b"foo"), Some(b"bar".to_vec().into()));
+			assert_eq!(
+				sp_io::default_child_storage::get(child_info.storage_key(), b"baz"),
+				Some(b"qux".to_vec())
+			);
+		});
+	}
+
+	#[tokio::test]
+	async fn snapshot_is_updated_through_the_archive_rpc() {
+		use sc_block_builder::BlockBuilderBuilder;
+		use sc_rpc_spec_v2::archive::{Archive, ArchiveApiServer, ArchiveConfig};
+		use sp_blockchain::HeaderBackend;
+		use sp_consensus::BlockOrigin;
+		use substrate_test_runtime_client::{
+			prelude::*, runtime::Block, BlockBuilderExt, ClientBlockImportExt, GenesisInit,
+			GenesisParameters,
+		};
+
+		init_logger();
+		let builder = TestClientBuilder::new();
+		let backend = builder.backend();
+		let mut client = Arc::new(builder.build());
+		let genesis_hash = client.chain_info().genesis_hash;
+
+		// The snapshot holds the genesis state.
+		let genesis = TestExternalities::<HashingFor<Block>>::new_with_state_version(
+			GenesisParameters::default().genesis_storage(),
+			StateVersion::V1,
+		);
+		let (raw_storage, storage_root) = genesis.into_raw_snapshot();
+		assert_eq!(storage_root, *client.header(genesis_hash).unwrap().unwrap().state_root());
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("snapshot");
+		let snapshot =
+			Snapshot::<Block>::new(StateVersion::V1, genesis_hash, raw_storage, storage_root);
+		std::fs::write(&path, snapshot.encode()).unwrap();
+
+		// Many keys below a single prefix, which is a key itself.
+		let mut block_builder = BlockBuilderBuilder::new(&*client)
+			.on_parent_block(genesis_hash)
+			.with_parent_block_number(0)
+			.build()
+			.unwrap();
+		block_builder.push_storage_change(vec![5], Some(vec![0])).unwrap();
+		for i in 0..40u8 {
+			block_builder.push_storage_change(vec![5, i, i], Some(vec![i])).unwrap();
+		}
+		let block = block_builder.build().unwrap().block;
+		let at = block.header.hash();
+		client.import(BlockOrigin::Own, block).await.unwrap();
+
+		// The storage diffs of the whole state and of the new keys exceed the bound and have to
+		// be split.
+		let mut rpc = jsonrpsee::RpcModule::new(());
+		rpc.merge(
+			Archive::new(
+				client.clone(),
+				backend,
+				genesis_hash,
+				ArchiveConfig { max_diff_keys: 32, ..Default::default() },
+			)
+			.into_rpc(),
+		)
+		.unwrap();
+		let header_client = client.clone();
+		rpc.register_method("chain_getHeader", move |params, _| {
+			let hash: <Block as BlockT>::Hash = params.one()?;
+			Ok(header_client.header(hash).unwrap())
+		})
+		.unwrap();
+		let methods = rpc.method_names().map(String::from).collect::<Vec<_>>();
+		rpc.register_method("rpc_methods", move |_, _| {
+			Ok(serde_json::json!({ "methods": methods }))
+		})
+		.unwrap();
+		let server =
+			jsonrpsee::server::ServerBuilder::default().build("127.0.0.1:0").await.unwrap();
+		let uri = format!("http://{}", server.local_addr().unwrap());
+		let _handle = server.start(rpc).unwrap();
+
+		// Fails if the updated state doesn't match the state root of the block.
+		let mut ext = Builder::<Block>::new()
+			.mode(Mode::Update(
+				OfflineConfig { state_snapshot: SnapshotConfig::new(path) },
+				OnlineConfig { at: Some(at), transport: uri.into(), ..Default::default() },
+			))
+			.build()
+			.await
+			.unwrap();
+
+		assert_eq!(ext.block_hash, at);
+		ext.execute_with(|| {
+			assert_eq!(sp_io::storage::get(&[5]), Some(vec![0].into()));
+			for i in 0..40u8 {
+				assert_eq!(sp_io::storage::get(&[5, i, i]), Some(vec![i].into()));
+			}
+		});
+	}
 }
 
 #[cfg(all(test, feature = "remote-test"))]
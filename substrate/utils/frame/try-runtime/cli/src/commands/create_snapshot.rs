@@ -18,7 +18,7 @@
 use crate::{build_executor, LiveState, SharedParams, State, LOG_TARGET};
 use sc_executor::sp_wasm_interface::HostFunctions;
 use sp_runtime::traits::{Block as BlockT, NumberFor};
-use std::{fmt::Debug, str::FromStr};
+use std::{fmt::Debug, path::PathBuf, str::FromStr};
 use substrate_rpc_client::{ws_client, StateApi};
 
 /// Configurations of the [`crate::Command::CreateSnapshot`].
@@ -32,6 +32,15 @@ pub struct CreateSnapshotCmd {
 	///
 	/// If not provided `<spec-name>-<spec-version>@<block-hash>.snap` will be used.
 	pub snapshot_path: Option<String>,
+
+	/// An existing snapshot to update to the block of the remote node, instead of downloading
+	/// the whole state again.
+	///
+	/// The snapshot must have been taken from the same chain, and the node must expose the
+	/// `archive` RPC methods and still have the state of the snapshot's block. May be the same as
+	/// the snapshot path to write to.
+	#[arg(long)]
+	pub update: Option<PathBuf>,
 }
 
 /// inner command for `Command::CreateSnapshot`.
@@ -70,7 +79,11 @@ where
 	};
 
 	let executor = build_executor::<HostFns>(&shared);
-	let _ = State::Live(command.from)
+	let state = match command.update {
+		Some(snapshot_path) => State::Update { snapshot_path, live_state: command.from },
+		None => State::Live(command.from),
+	};
+	let _ = state
 		.into_ext::<Block, HostFns>(&shared, &executor, Some(path.into()), false)
 		.await?;
 
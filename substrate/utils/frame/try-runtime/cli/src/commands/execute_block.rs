@@ -71,13 +71,14 @@ impl ExecuteBlockCmd {
 		<Block::Hash as FromStr>::Err: Debug,
 	{
 		match (&self.block_ws_uri, &self.state) {
-			(Some(block_ws_uri), State::Snap { .. }) => block_ws_uri.to_owned(),
-			(Some(block_ws_uri), State::Live { .. }) => {
+			(Some(block_ws_uri), State::Snap { .. } | State::Db(_)) => block_ws_uri.to_owned(),
+			(Some(block_ws_uri), State::Live { .. } | State::Update { .. }) => {
 				log::error!(target: LOG_TARGET, "--block-uri is provided while state type is live, Are you sure you know what you are doing?");
 				block_ws_uri.to_owned()
 			},
-			(None, State::Live(LiveState { uri, .. })) => uri.clone(),
-			(None, State::Snap { .. }) => {
+			(None, State::Live(LiveState { uri, .. })) |
+			(None, State::Update { live_state: LiveState { uri, .. }, .. }) => uri.clone(),
+			(None, State::Snap { .. } | State::Db(_)) => {
 				panic!("either `--block-uri` must be provided, or state must be `live`");
 			},
 		}
@@ -133,6 +134,7 @@ where
 		&payload,
 		full_extensions(executor.clone()),
 		shared.export_proof,
+		shared.proof_size_report,
 	)?;
 
 	Ok(())
@@ -67,7 +67,8 @@ pub struct FastForwardCmd {
 impl FastForwardCmd {
 	fn block_ws_uri(&self) -> &str {
 		match self.state {
-			State::Live(LiveState { ref uri, .. }) => &uri,
+			State::Live(LiveState { ref uri, .. }) |
+			State::Update { live_state: LiveState { ref uri, .. }, .. } => &uri,
 			_ => self
 				.block_ws_uri
 				.as_ref()
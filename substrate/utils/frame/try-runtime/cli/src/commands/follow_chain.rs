@@ -157,6 +157,7 @@ where
 				.export_proof
 				.as_ref()
 				.map(|path| path.as_path().join(&format!("{}.json", number))),
+			shared.proof_size_report,
 		);
 
 		if let Err(why) = result {
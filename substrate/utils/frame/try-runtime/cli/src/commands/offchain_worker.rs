@@ -49,13 +49,14 @@ impl OffchainWorkerCmd {
 		<Block::Hash as FromStr>::Err: Debug,
 	{
 		match (&self.header_ws_uri, &self.state) {
-			(Some(header_ws_uri), State::Snap { .. }) => header_ws_uri.to_owned(),
-			(Some(header_ws_uri), State::Live { .. }) => {
+			(Some(header_ws_uri), State::Snap { .. } | State::Db(_)) => header_ws_uri.to_owned(),
+			(Some(header_ws_uri), State::Live { .. } | State::Update { .. }) => {
 				log::error!(target: LOG_TARGET, "--header-uri is provided while state type is live, this will most likely lead to a nonsensical result.");
 				header_ws_uri.to_owned()
 			},
-			(None, State::Live(LiveState { uri, .. })) => uri.clone(),
-			(None, State::Snap { .. }) => {
+			(None, State::Live(LiveState { uri, .. })) |
+			(None, State::Update { live_state: LiveState { uri, .. }, .. }) => uri.clone(),
+			(None, State::Snap { .. } | State::Db(_)) => {
 				panic!("either `--header-uri` must be provided, or state must be `live`");
 			},
 		}
@@ -71,6 +71,7 @@ where
 		command.checks.encode().as_ref(),
 		Default::default(), // we don't really need any extensions here.
 		shared.export_proof,
+		shared.proof_size_report,
 	)?;
 
 	let (weight, total_weight) = <(Weight, Weight) as Decode>::decode(&mut &*encoded_result)
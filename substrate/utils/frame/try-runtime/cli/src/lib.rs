@@ -27,7 +27,8 @@
 use crate::block_building_info::BlockBuildingInfoProvider;
 use parity_scale_codec::Decode;
 use remote_externalities::{
-	Builder, Mode, OfflineConfig, OnlineConfig, RemoteExternalities, SnapshotConfig,
+	Builder, DatabaseBackend, DatabaseConfig, Mode, OfflineConfig, OnlineConfig,
+	RemoteExternalities, SnapshotConfig,
 };
 use sc_cli::{
 	execution_method_from_cli, CliConfiguration, RuntimeVersion, WasmExecutionMethod,
@@ -57,8 +58,9 @@ use sp_runtime::{
 use sp_state_machine::{
 	CompactProof, OverlayedChanges, StateMachine, TestExternalities, TrieBackendBuilder,
 };
+use sp_trie::recorder::{ProofSizeAttribution, Recorder};
 use sp_version::StateVersion;
-use std::{fmt::Debug, path::PathBuf, str::FromStr};
+use std::{collections::HashMap, fmt::Debug, path::PathBuf, str::FromStr};
 
 pub mod block_building_info;
 pub mod commands;
@@ -209,6 +211,14 @@ pub struct SharedParams {
 	#[clap(long)]
 	pub export_proof: Option<PathBuf>,
 
+	/// Report how much each storage item contributed to the storage proof.
+	///
+	/// The proof is attributed to the storage keys whose access caused the trie nodes to be
+	/// recorded. Keys are grouped by their first 32 bytes, which for FRAME storage identifies the
+	/// pallet and storage item.
+	#[clap(long)]
+	pub proof_size_report: bool,
+
 	/// Overwrite the `state_version`.
 	///
 	/// Otherwise `remote-externalities` will automatically set the correct state version.
@@ -263,6 +273,64 @@ pub struct LiveState {
 	child_tree: bool,
 }
 
+impl LiveState {
+	/// The [`OnlineConfig`] to fetch the state described by `self`, writing it to
+	/// `state_snapshot` if given.
+	fn to_online_config<Block: BlockT>(
+		&self,
+		state_snapshot: Option<SnapshotConfig>,
+	) -> sc_cli::Result<OnlineConfig<Block>>
+	where
+		<Block::Hash as FromStr>::Err: Debug,
+	{
+		let at = match &self.at {
+			Some(at_str) => Some(hash_of::<Block>(at_str)?),
+			None => None,
+		};
+		Ok(OnlineConfig {
+			at,
+			transport: self.uri.to_owned().into(),
+			state_snapshot,
+			pallets: self.pallet.clone(),
+			child_trie: self.child_tree,
+			hashed_keys: vec![
+				// we always download the code, but we almost always won't use it, based on
+				// `Runtime`.
+				well_known_keys::CODE.to_vec(),
+				// we will always download this key, since it helps detect if we should do
+				// runtime migration or not.
+				[twox_128(b"System"), twox_128(b"LastRuntimeUpgrade")].concat(),
+				[twox_128(b"System"), twox_128(b"Number")].concat(),
+			],
+			hashed_prefixes: vec![],
+		})
+	}
+}
+
+/// A `Db` variant [`State`]
+#[derive(Debug, Clone, clap::Args)]
+pub struct DbState {
+	/// Path to the database of a node, e.g. `<base-path>/chains/<chain-id>/db/full`.
+	///
+	/// The database is opened read-only, so the node may keep running.
+	#[arg(short, long)]
+	path: PathBuf,
+
+	/// The kind of database found at `path`.
+	#[arg(long, value_enum, ignore_case = true)]
+	database: sc_cli::Database,
+
+	/// The block hash at which to read the state.
+	///
+	/// If non provided, then the latest finalized head is used.
+	#[arg(
+		short,
+		long,
+		value_parser = parse::hash,
+	)]
+	at: Option<String>,
+}
+
 /// The source of runtime *state* to use.
 #[derive(Debug, Clone, clap::Subcommand)]
 pub enum State {
@@ -274,6 +342,23 @@ pub enum State {
 
 	/// Use a live chain as the source of runtime state.
 	Live(LiveState),
+
+	/// Use the database of a local node as the source of runtime state.
+	Db(DbState),
+
+	/// Use a state snapshot, updated to a newer block of a live chain, as the source of runtime
+	/// state.
+	///
+	/// Only the keys that changed since the block of the snapshot are downloaded, which requires
+	/// the node to expose the `archive` RPC methods.
+	Update {
+		/// The snapshot to update.
+		#[arg(short, long)]
+		snapshot_path: PathBuf,
+
+		#[command(flatten)]
+		live_state: LiveState,
+	},
 }
 
 impl State {
@@ -298,27 +383,31 @@ impl State {
 				Builder::<Block>::new().mode(Mode::Offline(OfflineConfig {
 					state_snapshot: SnapshotConfig::new(snapshot_path),
 				})),
-			State::Live(LiveState { pallet, uri, at, child_tree }) => {
+			State::Live(live_state) => Builder::<Block>::new()
+				.mode(Mode::Online(live_state.to_online_config(state_snapshot)?)),
+			State::Update { snapshot_path, live_state } =>
+				Builder::<Block>::new().mode(Mode::Update(
+					OfflineConfig { state_snapshot: SnapshotConfig::new(snapshot_path) },
+					live_state.to_online_config(state_snapshot)?,
+				)),
+			State::Db(DbState { path, database, at }) => {
+				let backend = match database {
+					sc_cli::Database::RocksDb => DatabaseBackend::RocksDb,
+					sc_cli::Database::ParityDb | sc_cli::Database::ParityDbDeprecated =>
+						DatabaseBackend::ParityDb,
+					sc_cli::Database::Auto =>
+						return Err("`--database auto` is not supported, the kind of database \
+							must be given explicitly"
+							.into()),
+				};
 				let at = match at {
 					Some(at_str) => Some(hash_of::<Block>(at_str)?),
 					None => None,
 				};
-				Builder::<Block>::new().mode(Mode::Online(OnlineConfig {
+				Builder::<Block>::new().mode(Mode::Database(DatabaseConfig {
 					at,
-					transport: uri.to_owned().into(),
 					state_snapshot,
-					pallets: pallet.clone(),
-					child_trie: *child_tree,
-					hashed_keys: vec![
-						// we always download the code, but we almost always won't use it, based on
-						// `Runtime`.
-						well_known_keys::CODE.to_vec(),
-						// we will always download this key, since it helps detect if we should do
-						// runtime migration or not.
-						[twox_128(b"System"), twox_128(b"LastRuntimeUpgrade")].concat(),
-						[twox_128(b"System"), twox_128(b"Number")].concat(),
-					],
-					hashed_prefixes: vec![],
+					..DatabaseConfig::new(path, backend)
 				}))
 			},
 		};
@@ -567,14 +656,20 @@ pub(crate) fn state_machine_call_with_proof<Block: BlockT, HostFns: HostFunction
 	data: &[u8],
 	mut extensions: Extensions,
 	maybe_export_proof: Option<PathBuf>,
+	proof_size_report: bool,
 ) -> sc_cli::Result<(OverlayedChanges<HashingFor<Block>>, Vec<u8>)> {
 	use parity_scale_codec::Encode;
 
 	let mut changes = Default::default();
 	let backend = ext.backend.clone();
 	let runtime_code_backend = sp_state_machine::backend::BackendRuntimeCode::new(&backend);
+	let recorder = if proof_size_report {
+		Recorder::with_proof_size_attribution()
+	} else {
+		Recorder::default()
+	};
 	let proving_backend =
-		TrieBackendBuilder::wrap(&backend).with_recorder(Default::default()).build();
+		TrieBackendBuilder::wrap(&backend).with_recorder(recorder.clone()).build();
 	let runtime_code = runtime_code_backend.runtime_code()?;
 
 	let pre_root = *backend.root();
@@ -592,6 +687,8 @@ pub(crate) fn state_machine_call_with_proof<Block: BlockT, HostFns: HostFunction
 	.map_err(|e| format!("failed to execute {}: {}", method, e))
 	.map_err::<sc_cli::Error, _>(Into::into)?;
 
+	// Extracting the proof drains the recorder, so the attribution has to be taken before.
+	let proof_size_attribution = recorder.proof_size_attribution();
 	let proof = proving_backend
 		.extract_proof()
 		.expect("A recorder was set and thus, a storage proof can be extracted; qed");
@@ -672,11 +769,129 @@ pub(crate) fn state_machine_call_with_proof<Block: BlockT, HostFns: HostFunction
 		humanize(compressed_proof.len()),
 	);
 
+	if let Some(attribution) = proof_size_attribution {
+		let storage_names = storage_names::<Block, HostFns>(ext, executor).unwrap_or_else(|e| {
+			log::warn!(target: LOG_TARGET, "failed to read the storage names: {:?}", e);
+			Default::default()
+		});
+		log_proof_size_report(&attribution, &storage_names);
+	}
+
 	log::debug!(target: LOG_TARGET, "{} executed without errors.", method);
 
 	Ok((changes, encoded_results))
 }
 
+/// The names of the pallets and storage items of the runtime, by their storage prefix.
+#[derive(Default)]
+struct StorageNames {
+	/// `twox_128(pallet) ++ twox_128(item) -> "Pallet::Item"`.
+	items: HashMap<Vec<u8>, String>,
+	/// `twox_128(pallet) -> "Pallet"`.
+	pallets: HashMap<Vec<u8>, String>,
+}
+
+impl StorageNames {
+	fn add_pallet<'a>(
+		&mut self,
+		pallet: &str,
+		storage: Option<(&str, impl Iterator<Item = &'a String>)>,
+	) {
+		let Some((prefix, items)) = storage else { return };
+		let pallet_prefix = twox_128(prefix.as_bytes());
+		self.pallets.insert(pallet_prefix.to_vec(), pallet.to_string());
+		for item in items {
+			let item_prefix = [pallet_prefix, twox_128(item.as_bytes())].concat();
+			self.items.insert(item_prefix, format!("{}::{}", pallet, item));
+		}
+	}
+
+	/// The name of a storage prefix, like the benchmarking CLI reports it.
+	fn name_of(&self, prefix: &[u8]) -> String {
+		if let Some(name) = self.items.get(prefix) {
+			return name.clone()
+		}
+		// Well known keys like `:code` are not hashed.
+		if prefix.starts_with(b":") {
+			return String::from_utf8_lossy(prefix).into_owned()
+		}
+		match prefix.get(..16).and_then(|pallet| self.pallets.get(pallet)) {
+			Some(pallet) if prefix.len() > 16 =>
+				format!("{}::0x{}", pallet, hex::encode(&prefix[16..])),
+			Some(pallet) => pallet.clone(),
+			None => format!("0x{}", hex::encode(prefix)),
+		}
+	}
+}
+
+/// Read the storage names from the metadata of the runtime in `ext`.
+fn storage_names<Block: BlockT, HostFns: HostFunctions>(
+	ext: &TestExternalities<HashingFor<Block>>,
+	executor: &WasmExecutor<HostFns>,
+) -> sc_cli::Result<StorageNames> {
+	use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+	use parity_scale_codec::Decode;
+
+	let (_, encoded) = state_machine_call::<Block, HostFns>(
+		ext,
+		executor,
+		"Metadata_metadata",
+		&[],
+		Default::default(),
+	)?;
+	let metadata = <Vec<u8>>::decode(&mut &*encoded)
+		.and_then(|metadata| RuntimeMetadataPrefixed::decode(&mut &*metadata))
+		.map_err(|e| format!("failed to decode the metadata: {:?}", e))?;
+
+	let mut names = StorageNames::default();
+	match metadata.1 {
+		RuntimeMetadata::V14(metadata) =>
+			for pallet in metadata.pallets {
+				let storage = pallet.storage.as_ref().map(|storage| {
+					(storage.prefix.as_str(), storage.entries.iter().map(|entry| &entry.name))
+				});
+				names.add_pallet(&pallet.name, storage);
+			},
+		RuntimeMetadata::V15(metadata) =>
+			for pallet in metadata.pallets {
+				let storage = pallet.storage.as_ref().map(|storage| {
+					(storage.prefix.as_str(), storage.entries.iter().map(|entry| &entry.name))
+				});
+				names.add_pallet(&pallet.name, storage);
+			},
+		_ => return Err("unsupported metadata version".into()),
+	}
+
+	Ok(names)
+}
+
+/// Log the storage items and pallets that contributed the most to the storage proof.
+fn log_proof_size_report(attribution: &ProofSizeAttribution, names: &StorageNames) {
+	let total = attribution.total().max(1);
+	let log_line = |name: &str, bytes: usize| {
+		log::info!(
+			target: LOG_TARGET,
+			"{:<68} {:>10} bytes {:>6.2}%",
+			name,
+			bytes,
+			bytes as f64 * 100.0 / total as f64,
+		);
+	};
+
+	log::info!(target: LOG_TARGET, "proof size per storage item (of {} bytes):", total);
+	for (prefix, bytes) in attribution.by_prefix(32) {
+		log_line(&names.name_of(&prefix), bytes);
+	}
+	if attribution.unattributed > 0 {
+		log_line("unattributed", attribution.unattributed);
+	}
+
+	log::info!(target: LOG_TARGET, "proof size per pallet:");
+	for (prefix, bytes) in attribution.by_prefix(16) {
+		log_line(&names.name_of(&prefix), bytes);
+	}
+}
+
 pub(crate) fn rpc_err_handler(error: impl Debug) -> &'static str {
 	log::error!(target: LOG_TARGET, "rpc error: {:?}", error);
 	"rpc error."